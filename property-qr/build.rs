@@ -0,0 +1,13 @@
+// build.rs
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the vendored protoc unless the environment provides one
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/property_qr.proto"], &["proto"])?;
+    Ok(())
+}
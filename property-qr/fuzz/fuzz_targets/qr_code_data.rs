@@ -0,0 +1,18 @@
+// QR payloads are decoded from whatever a phone camera hands back
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use property_qr::models::QrCodeData;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else { return };
+    let Ok(qr_data) = QrCodeData::from_json_string(json) else { return };
+
+    let _ = qr_data.is_valid();
+
+    // Anything we accept must survive a round trip unchanged
+    let encoded = qr_data.to_json_string().expect("decoded QR data re-encodes");
+    let decoded = QrCodeData::from_json_string(&encoded).expect("re-encoded QR data decodes");
+    assert_eq!(decoded.property_id, qr_data.property_id);
+    assert_eq!(decoded.scan_url, qr_data.scan_url);
+});
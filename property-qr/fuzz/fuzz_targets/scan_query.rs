@@ -0,0 +1,38 @@
+// Scan URLs carry UTM, redirect and consent parameters plus visitor cookies
+#![no_main]
+
+use axum::extract::Query;
+use axum::http::{header, HeaderMap, HeaderValue, Uri};
+use libfuzzer_sys::fuzz_target;
+use property_qr::handlers::{extract_tracking_consent, extract_visitor_cookie, ScanQuery};
+use property_qr::models::{CreateShortLinkRequest, ShortLink};
+
+fuzz_target!(|input: (&str, &str)| {
+    let (query_string, cookie) = input;
+
+    let Ok(uri) = format!("/scan/507f1f77bcf86cd799439011?{}", query_string).parse::<Uri>() else { return };
+    let Ok(Query(query)) = Query::<ScanQuery>::try_from_uri(&uri) else { return };
+
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(cookie) {
+        headers.insert(header::COOKIE, value);
+    }
+
+    // Accepted visitor IDs are echoed back in Set-Cookie
+    if let Some(visitor_id) = extract_visitor_cookie(&headers) {
+        assert!(HeaderValue::from_str(&visitor_id).is_ok());
+    }
+    let _ = extract_tracking_consent(&headers, &query);
+
+    // UTM values flow into redirect URLs and must not break out of the query string
+    let link = ShortLink::new("fuzz123".to_string(), CreateShortLinkRequest {
+        property_id: "507f1f77bcf86cd799439011".to_string(),
+        code: None,
+        campaign: query.utm_campaign.clone(),
+        channel: query.utm_source.clone(),
+        target_url: None,
+    });
+    let destination = link.destination_url("https://www.daobitat.xyz");
+    assert!(destination.parse::<Uri>().is_ok());
+    assert!(!destination.contains('#'));
+});
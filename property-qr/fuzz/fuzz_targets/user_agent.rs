@@ -0,0 +1,15 @@
+// User-Agent headers on scan requests are fully attacker-controlled
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use property_qr::models::{DeviceInfo, ScanEvent};
+
+fuzz_target!(|user_agent: &str| {
+    let _ = DeviceInfo::from_user_agent(user_agent);
+
+    let _ = ScanEvent::is_bot_user_agent(user_agent);
+
+    // Visitor IDs end up in cookies, so they must stay header-safe
+    let visitor_id = ScanEvent::visitor_hash(Some("203.0.113.7"), Some(user_agent));
+    assert!(visitor_id.chars().all(|c| c.is_ascii_alphanumeric()));
+});
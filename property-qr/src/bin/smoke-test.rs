@@ -0,0 +1,201 @@
+// src/bin/smoke-test.rs
+
+// Post-deploy gate: `smoke-test <base-url> <property-id>` generates a QR code for a seeded
+// test property on the target environment, fetches its image, scans it as a marked bot,
+// checks the scan was recorded, then cleans up. Reads MONGODB_URI and DATABASE_NAME for
+// the target's database, like the server. Exits non-zero if any step fails.
+
+use std::error::Error;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use mongodb::{bson::doc, Database};
+use property_qr::config::Settings;
+use property_qr::models::{QrCodeResponse, ScanEvent, SMOKE_TEST_USER_AGENT_MARKER};
+use serde::Deserialize;
+
+// How long to wait for the scan event; busy servers write it from the analytics worker
+const SCAN_RECORD_TIMEOUT: Duration = Duration::from_secs(15);
+const SCAN_RECORD_POLL: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+const PNG_SIGNATURE: [u8; 4] = [0x89, 0x50, 0x4E, 0x47];
+
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+struct SmokeTest {
+    http: reqwest::Client,
+    database: Database,
+    base_url: String,
+    property_id: String,
+    user_agent: String,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let (Some(base_url), Some(property_id)) = (args.next(), args.next()) else {
+        eprintln!("usage: smoke-test <base-url> <property-id>");
+        return ExitCode::from(2);
+    };
+
+    let smoke = match SmokeTest::connect(base_url.trim_end_matches('/').to_string(), property_id).await {
+        Ok(smoke) => smoke,
+        Err(e) => {
+            eprintln!("FAIL setup: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let outcome = smoke.run().await;
+    // Clean up whether or not the run got through
+    let cleaned = step("cleanup", smoke.cleanup()).await;
+
+    match (outcome, cleaned) {
+        (Ok(()), Ok(())) => {
+            println!("Smoke test passed against {}", smoke.base_url);
+            ExitCode::SUCCESS
+        }
+        _ => ExitCode::FAILURE,
+    }
+}
+
+/// Run one step, printing its outcome and how long it took
+async fn step<T>(name: &str, fut: impl std::future::Future<Output = Result<T, Box<dyn Error>>>) -> Result<T, ()> {
+    let started = Instant::now();
+    let result = fut.await;
+    let elapsed = started.elapsed().as_millis();
+    match result {
+        Ok(value) => {
+            println!("ok   {} ({} ms)", name, elapsed);
+            Ok(value)
+        }
+        Err(e) => {
+            eprintln!("FAIL {} ({} ms): {}", name, elapsed, e);
+            Err(())
+        }
+    }
+}
+
+/// User agent the scan is made with; unique per run so checks and cleanup only touch this run's scan
+fn smoke_user_agent() -> String {
+    format!("Mozilla/5.0 (compatible; {}/{})", SMOKE_TEST_USER_AGENT_MARKER, Utc::now().timestamp_millis())
+}
+
+impl SmokeTest {
+    async fn connect(base_url: String, property_id: String) -> Result<Self, Box<dyn Error>> {
+        let settings = Settings::from_env()?;
+        let client = mongodb::Client::with_uri_str(&settings.database.mongodb_uri).await?;
+        let database = client.database(&settings.database.database_name);
+        database.run_command(doc! { "ping": 1 }).await?;
+
+        let user_agent = smoke_user_agent();
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(user_agent.clone())
+            // The scan answers with a redirect or the dual page; either proves it was served
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self { http, database, base_url, property_id, user_agent })
+    }
+
+    async fn run(&self) -> Result<(), ()> {
+        let qr_code = step("generate", self.generate()).await?;
+        step("fetch_image", self.fetch_image(&qr_code.qr_code_url)).await?;
+        let scanned_at = Utc::now();
+        step("scan", self.scan(&qr_code.scan_url)).await?;
+        step("analytics", self.scan_recorded(scanned_at)).await
+    }
+
+    async fn generate(&self) -> Result<QrCodeResponse, Box<dyn Error>> {
+        let response = self.http
+            .post(format!("{}/api/v1/qr/generate/{}", self.base_url, self.property_id))
+            .json(&serde_json::json!({
+                "propertyId": self.property_id,
+                "forceRegenerate": true,
+                "reason": "manual_regeneration",
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("{}: {}", response.status(), response.text().await.unwrap_or_default()).into());
+        }
+        Ok(response.json::<Envelope<QrCodeResponse>>().await?.data)
+    }
+
+    async fn fetch_image(&self, qr_code_url: &str) -> Result<(), Box<dyn Error>> {
+        let response = self.http.get(qr_code_url).send().await?.error_for_status()?;
+        let image = response.bytes().await?;
+
+        if !image.starts_with(&PNG_SIGNATURE) {
+            return Err(format!("{} is not a PNG ({} bytes)", qr_code_url, image.len()).into());
+        }
+        Ok(())
+    }
+
+    async fn scan(&self, scan_url: &str) -> Result<(), Box<dyn Error>> {
+        let response = self.http.get(scan_url).send().await?;
+        let status = response.status();
+
+        if !(status.is_success() || status.is_redirection()) {
+            return Err(format!("scan of {} answered {}", scan_url, status).into());
+        }
+        Ok(())
+    }
+
+    async fn scan_recorded(&self, since: chrono::DateTime<Utc>) -> Result<(), Box<dyn Error>> {
+        let scan_events = self.database.collection::<ScanEvent>("scan_events");
+        let filter = doc! {
+            "propertyId": &self.property_id,
+            "userAgent": &self.user_agent,
+            "scannedAt": { "$gte": mongodb::bson::DateTime::from_millis(since.timestamp_millis() - 1_000) },
+        };
+
+        let deadline = Instant::now() + SCAN_RECORD_TIMEOUT;
+        loop {
+            if let Some(event) = scan_events.find_one(filter.clone()).await? {
+                if !event.is_bot {
+                    return Err("smoke scan was not flagged as a bot and would count in analytics".into());
+                }
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("no scan event within {:?}", SCAN_RECORD_TIMEOUT).into());
+            }
+            tokio::time::sleep(SCAN_RECORD_POLL).await;
+        }
+    }
+
+    async fn cleanup(&self) -> Result<(), Box<dyn Error>> {
+        self.database
+            .collection::<ScanEvent>("scan_events")
+            .delete_many(doc! { "propertyId": &self.property_id, "userAgent": &self.user_agent })
+            .await?;
+
+        let response = self.http
+            .delete(format!("{}/api/v1/qr/{}", self.base_url, self.property_id))
+            .send()
+            .await?;
+        // Nothing to delete when generation never got that far
+        if !(response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND) {
+            return Err(format!("deleting the QR code answered {}", response.status()).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoke_user_agent_is_kept_out_of_analytics() {
+        assert!(ScanEvent::is_bot_user_agent(&smoke_user_agent()));
+    }
+}
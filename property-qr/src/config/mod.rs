@@ -1,8 +1,10 @@
  // src/config/mod.rs
 
 pub mod aws;
+pub mod loader;
 pub mod settings;
 
 // Re-export the main types for easier imports
 pub use aws::AwsConfig;
-pub use settings::Settings;
+pub use loader::{ConfigArgs, ConfigError};
+pub use settings::{AlertWebhookKind, AnomalyConfig, AppLinkConfig, BlockchainConfig, DigestConfig, EmailConfig, EmailProviderKind, Environment, EventStreamConfig, EventStreamKind, GeoProviderKind, GeolocationConfig, LoadSheddingConfig, PrivacyConfig, QrPayloadMode, RedirectTarget, RetentionConfig, SecurityHeadersConfig, Settings, SmsConfig, SmsProviderKind, StorageBackendKind, StorageConfig};
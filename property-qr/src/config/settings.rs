@@ -1,26 +1,49 @@
  // src/config/settings.rs
 
 use serde::{Deserialize, Serialize};
-use std::env;
+use std::collections::BTreeMap;
+
+use crate::config::ConfigArgs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
     pub aws: AwsConfig,
+    pub storage: StorageConfig,
     pub urls: UrlConfig,
     pub qr: QrConfig,
     pub logging: LoggingConfig,
+    pub sms: SmsConfig,
+    pub email: EmailConfig,
+    pub digest: DigestConfig,
+    pub retention: RetentionConfig,
+    pub load_shedding: LoadSheddingConfig,
+    pub geolocation: GeolocationConfig,
+    pub app_links: AppLinkConfig,
+    pub privacy: PrivacyConfig,
+    pub event_stream: EventStreamConfig,
+    pub anomaly: AnomalyConfig,
+    pub blockchain: BlockchainConfig,
+    pub security_headers: SecurityHeadersConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    pub grpc_port: Option<u16>, // Serves the internal gRPC API when set; keep it off the public network
     pub environment: Environment,
     pub cors_origins: Vec<String>,
     pub request_timeout_seconds: u64,
+    pub max_body_bytes: usize,          // Largest request body; image upload routes set their own
     pub max_connections: Option<u32>,
+    pub admin_api_key: Option<String>, // Enables the /admin dashboard when set
+    pub startup_self_test: bool,       // Generate and store a probe QR before reporting ready
+    pub session_secret: Option<String>, // Signs scan session cookies; random per process when unset
+    pub jwt_secret: Option<String>,     // HS256 key shared with the auth service; enables role checks on /qr
+    pub jwt_issuer: Option<String>,     // Expected `iss` claim, when set
+    pub jwks_url: Option<String>,       // Auth service key set for RS256 tokens; enables role checks on /qr
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,19 +61,69 @@ pub struct AwsConfig {
     pub s3_bucket: String,
     pub s3_bucket_region: String,
     pub cloudfront_domain: Option<String>,
+    pub secondary_s3_bucket: Option<String>, // Replica in another region, for S3 outages
+    pub secondary_s3_bucket_region: Option<String>,
     pub access_key_id: Option<String>,
     pub secret_access_key: Option<String>,
     pub session_token: Option<String>,
 }
 
+// Where QR images are stored; S3 uses the bucket in AwsConfig
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    pub backend: StorageBackendKind,
+    pub gcs_bucket: Option<String>,
+    pub gcs_access_token: Option<String>, // Without one, tokens come from the GCE metadata server
+    pub local_root: String,               // Directory the local backend writes to
+    pub public_url: Option<String>,       // Base URL GCS or local objects are served from
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackendKind {
+    S3,
+    Gcs,
+    Local, // Files on disk, served by this service; for development without LocalStack
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UrlConfig {
     pub base_url: String,
+    pub alias_hosts: Vec<String>, // Old hostnames that still serve printed codes
+    pub image_domains: Vec<String>, // Hosts property images may be shown from on scan pages
+    pub link_domains: Vec<String>, // Hosts short links may send visitors to
+    pub hook_domains: Vec<String>, // Hosts REST hook subscriptions may deliver to
     pub daobitat_base_url: String,
-    pub blockchain_explorer_base_url: String,
+    pub blockchain_explorer_base_url: String, // For listings on a chain without an entry below
+    #[serde(with = "chain_id_keys")]
+    pub blockchain_explorers: BTreeMap<u64, String>, // Explorer base URL per chain ID
     pub api_version: String,
 }
 
+// Per-chain URL maps are keyed by chain ID; config files can only have string keys
+mod chain_id_keys {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(urls: &BTreeMap<u64, String>, serializer: S) -> Result<S::Ok, S::Error> {
+        urls.iter()
+            .map(|(chain_id, url)| (chain_id.to_string(), url))
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<u64, String>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(chain_id, url)| {
+                let chain_id = chain_id.parse()
+                    .map_err(|_| D::Error::custom(format!("chain ID `{}` is not a number", chain_id)))?;
+                Ok((chain_id, url))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrConfig {
     pub default_size: u32,
@@ -61,6 +134,11 @@ pub struct QrConfig {
     pub foreground_color: String,
     pub format: String,
     pub expiry_days: i64,
+    pub auto_redirect_seconds: Option<u64>, // Dual page countdown; 0 redirects instantly, None never does
+    pub default_target: RedirectTarget,     // Where the dual page sends visitors on its own
+    pub payload_mode: QrPayloadMode,        // What new codes encode: the scan URL, or the legacy JSON
+    pub watch_properties: bool,             // Follow listing edits through a change stream; needs a replica set
+    pub verify_assets: bool,                // Check every code's image in S3 daily, e.g. after a bucket migration
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +148,184 @@ pub struct LoggingConfig {
     pub enable_json: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmsConfig {
+    pub provider: SmsProviderKind,
+    pub account_id: Option<String>, // Twilio account SID / Africa's Talking username
+    pub api_key: Option<String>,    // Twilio auth token / Africa's Talking API key
+    pub sender_id: Option<String>,  // Twilio "from" number / Africa's Talking sender ID
+    pub max_per_ip_per_hour: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub provider: EmailProviderKind,
+    pub api_key: Option<String>,      // SendGrid; SES uses the AWS credentials
+    pub from_address: Option<String>, // Verified sender, e.g. listings@daobitat.xyz
+    pub from_name: String,
+    pub smtp_host: Option<String>,    // Relay for the SMTP provider
+    pub smtp_port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestConfig {
+    pub enabled: bool,           // Email owners a weekly summary of their scans
+    pub weekday: chrono::Weekday, // Sent on this day, covering the seven days before it
+    pub hour_utc: u32,           // ... at or after this hour
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    pub enabled: bool,
+    pub dry_run: bool,          // Only report what would be removed
+    pub raw_event_days: i64,    // Scan and conversion events
+    pub aggregate_days: i64,    // Daily counters and per-property analytics
+    pub interval_hours: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadSheddingConfig {
+    pub enabled: bool,
+    pub probe_interval_ms: u64,
+    pub skip_enrichment_ms: u64,   // Runtime lag at which geo/device parsing is skipped
+    pub defer_persistence_ms: u64, // ... scan events are written by the analytics worker
+    pub reject_non_scan_ms: u64,   // ... everything but scans, short links and health gets 503
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeolocationConfig {
+    pub provider: GeoProviderKind,
+    pub maxmind_db_path: Option<String>, // GeoLite2-City / GeoIP2-City .mmdb file
+    pub ipapi_base_url: String,
+    pub ipapi_key: Option<String>,       // Optional; the free tier works without one
+    pub cache_ttl_secs: u64,
+    pub cache_capacity: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLinkConfig {
+    pub enabled: bool,                  // Send phones and tablets to the mobile app
+    pub scheme: String,                 // Custom URL scheme, e.g. daobitat://property/{id}
+    pub android_package: Option<String>, // Lets Android fall back to the web itself via an intent URL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    pub public_stats_noise: bool, // Noise the public stats endpoint; partners can always ask for it
+    pub epsilon: f64,             // Privacy budget per count; lower adds more noise
+    pub min_bucket_size: i64,     // Buckets with fewer (noisy) unique visitors are suppressed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventStreamConfig {
+    pub kind: EventStreamKind,
+    pub url: String,          // Kafka bootstrap brokers (comma-separated) or NATS server URL
+    pub topic: String,        // Kafka topic or NATS subject; Kafka topics must already exist
+    pub queue_capacity: usize, // Events buffered while the broker is slow; more are dropped
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    pub webhook_url: Option<String>, // Slack or Discord incoming webhook alerts are posted to
+    pub webhook_kind: AlertWebhookKind,
+    pub check_interval_minutes: u64,
+    pub spike_window_minutes: i64,   // Recent scans compared against the property's usual rate
+    pub spike_multiplier: f64,       // ... a spike is this many times the usual rate
+    pub spike_min_scans: i64,        // ... and at least this many scans, so quiet codes don't page
+    pub silence_hours: i64,          // A property with no scans for this long ...
+    pub active_min_scans: i64,       // ... after this many in the week before has gone quiet
+    pub cooldown_hours: i64,         // One alert per property and kind within this window
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockchainConfig {
+    pub registry_contract: Option<String>, // Ownership token/SBT contract; on-chain verification is off without it
+    pub default_rpc_url: Option<String>,   // For listings on a chain without an entry below
+    #[serde(with = "chain_id_keys")]
+    pub rpc_urls: BTreeMap<u64, String>,   // JSON-RPC endpoint per chain ID
+    pub cache_ttl_secs: u64,               // How long a verification answer is shown before it's re-checked
+}
+
+// Browser hardening headers; the CSP only goes on scan pages, which run inline scripts
+// and show property images from the hosts in UrlConfig::image_domains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityHeadersConfig {
+    pub enabled: bool,
+    pub scan_page_csp: Option<String>, // Replaces the policy built from the image domains
+    pub frame_options: String,         // X-Frame-Options; empty leaves it off
+    pub referrer_policy: String,       // Empty leaves it off
+    pub hsts_max_age_seconds: u64,     // Strict-Transport-Security, sent in production only; 0 turns it off
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertWebhookKind {
+    Slack,
+    Discord,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventStreamKind {
+    Kafka,
+    Nats,
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeoProviderKind {
+    MaxMind, // Local database: free, fast, accuracy depends on update cadence
+    IpApi,   // HTTP lookups: paid per request, no database to ship
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmsProviderKind {
+    Twilio,
+    AfricasTalking,
+    Disabled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailProviderKind {
+    SendGrid,
+    Ses,
+    Smtp, // Plain relay, e.g. a local MTA; no TLS or authentication
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedirectTarget {
+    Property,
+    Blockchain, // Falls back to the property page for listings without an on-chain ID
+}
+
+// What printed QR codes encode. Generic camera apps only open plain URLs; the JSON payload
+// is the original format, kept for codes already in print.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrPayloadMode {
+    #[default]
+    Url,
+    Json,
+}
+
+impl QrPayloadMode {
+    /// Mode a stored payload was encoded in
+    pub fn of(payload: &str) -> Self {
+        if payload.starts_with("http://") || payload.starts_with("https://") {
+            QrPayloadMode::Url
+        } else {
+            QrPayloadMode::Json
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Environment {
@@ -79,114 +335,195 @@ pub enum Environment {
 }
 
 impl Settings {
-    /// Load settings from environment variables
+    /// Load settings from the environment, over the file in CONFIG_FILE when it's set.
+    /// Malformed values are errors rather than falling back to defaults.
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(Settings {
+        Ok(Self::load(&ConfigArgs::default())?)
+    }
+
+    /// What each setting is when no config file, environment variable or flag sets it
+    pub fn defaults() -> Self {
+        Settings {
             server: ServerConfig {
-                host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-                port: env::var("PORT")
-                    .unwrap_or_else(|_| "3000".to_string())
-                    .parse()
-                    .unwrap_or(3000),
-                environment: match env::var("ENVIRONMENT")
-                    .unwrap_or_else(|_| "development".to_string())
-                    .to_lowercase()
-                    .as_str()
-                {
-                    "production" => Environment::Production,
-                    "staging" => Environment::Staging,
-                    _ => Environment::Development,
-                },
-                cors_origins: env::var("CORS_ORIGINS")
-                    .unwrap_or_else(|_| "http://localhost:3000,https://daobitat.xyz".to_string())
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .collect(),
-                request_timeout_seconds: env::var("REQUEST_TIMEOUT_SECONDS")
-                    .unwrap_or_else(|_| "30".to_string())
-                    .parse()
-                    .unwrap_or(30),
-                max_connections: env::var("MAX_CONNECTIONS")
-                    .ok()
-                    .and_then(|s| s.parse().ok()),
+                host: "0.0.0.0".to_string(),
+                port: 3000,
+                grpc_port: None,
+                environment: Environment::Development,
+                cors_origins: vec![
+                    "http://localhost:3000".to_string(),
+                    "https://daobitat.xyz".to_string(),
+                ],
+                request_timeout_seconds: 30,
+                max_body_bytes: 1024 * 1024,
+                max_connections: None,
+                admin_api_key: None,
+                startup_self_test: true,
+                session_secret: None,
+                jwt_secret: None,
+                jwt_issuer: None,
+                jwks_url: None,
             },
             
             database: DatabaseConfig {
-                mongodb_uri: env::var("MONGODB_URI")
-                    .unwrap_or_else(|_| "mongodb://localhost:27017".to_string()),
-                database_name: env::var("DATABASE_NAME")
-                    .unwrap_or_else(|_| "daobitat_qr".to_string()),
-                connection_timeout_seconds: env::var("DB_CONNECTION_TIMEOUT_SECONDS")
-                    .unwrap_or_else(|_| "10".to_string())
-                    .parse()
-                    .unwrap_or(10),
-                max_pool_size: env::var("DB_MAX_POOL_SIZE")
-                    .ok()
-                    .and_then(|s| s.parse().ok()),
-                min_pool_size: env::var("DB_MIN_POOL_SIZE")
-                    .ok()
-                    .and_then(|s| s.parse().ok()),
+                mongodb_uri: "mongodb://localhost:27017".to_string(),
+                database_name: "daobitat_qr".to_string(),
+                connection_timeout_seconds: 10,
+                max_pool_size: None,
+                min_pool_size: None,
             },
             
             aws: AwsConfig {
-                region: env::var("AWS_REGION")
-                    .unwrap_or_else(|_| "us-east-1".to_string()),
-                s3_bucket: env::var("S3_BUCKET")
-                    .unwrap_or_else(|_| "daobitat-qr-codes".to_string()),
-                s3_bucket_region: env::var("S3_BUCKET_REGION")
-                    .unwrap_or_else(|_| "us-east-1".to_string()),
-                cloudfront_domain: env::var("CLOUDFRONT_DOMAIN").ok(),
-                access_key_id: env::var("AWS_ACCESS_KEY_ID").ok(),
-                secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").ok(),
-                session_token: env::var("AWS_SESSION_TOKEN").ok(),
+                region: "us-east-1".to_string(),
+                s3_bucket: "daobitat-qr-codes".to_string(),
+                s3_bucket_region: "us-east-1".to_string(),
+                cloudfront_domain: None,
+                secondary_s3_bucket: None,
+                secondary_s3_bucket_region: None,
+                access_key_id: None,
+                secret_access_key: None,
+                session_token: None,
+            },
+            
+            storage: StorageConfig {
+                backend: StorageBackendKind::S3,
+                gcs_bucket: None,
+                gcs_access_token: None,
+                local_root: "./storage".to_string(),
+                public_url: None,
             },
             
             urls: UrlConfig {
-                base_url: env::var("BASE_URL")
-                    .unwrap_or_else(|_| "https://qr-service.daobitat.xyz".to_string()),
-                daobitat_base_url: env::var("DAOBITAT_BASE_URL")
-                    .unwrap_or_else(|_| "https://www.daobitat.xyz".to_string()),
-                blockchain_explorer_base_url: env::var("BLOCKCHAIN_EXPLORER_BASE_URL")
-                    .unwrap_or_else(|_| "https://basescan.org".to_string()),
-                api_version: env::var("API_VERSION")
-                    .unwrap_or_else(|_| "v1".to_string()),
+                base_url: "https://qr-service.daobitat.xyz".to_string(),
+                alias_hosts: Vec::new(),
+                image_domains: vec!["daobitat.xyz".to_string()],
+                link_domains: vec!["daobitat.xyz".to_string()],
+                hook_domains: vec!["hooks.zapier.com".to_string(), "make.com".to_string()],
+                daobitat_base_url: "https://www.daobitat.xyz".to_string(),
+                blockchain_explorer_base_url: "https://basescan.org".to_string(),
+                blockchain_explorers: BTreeMap::from([
+                    (8453, "https://basescan.org".to_string()),
+                    (84532, "https://sepolia.basescan.org".to_string()),
+                ]),
+                api_version: "v1".to_string(),
             },
             
             qr: QrConfig {
-                default_size: env::var("QR_DEFAULT_SIZE")
-                    .unwrap_or_else(|_| "256".to_string())
-                    .parse()
-                    .unwrap_or(256),
-                default_error_correction: env::var("QR_ERROR_CORRECTION")
-                    .unwrap_or_else(|_| "medium".to_string()),
-                include_logo: env::var("QR_INCLUDE_LOGO")
-                    .unwrap_or_else(|_| "true".to_string())
-                    .parse()
-                    .unwrap_or(true),
-                logo_url: env::var("QR_LOGO_URL").ok(),
-                background_color: env::var("QR_BACKGROUND_COLOR")
-                    .unwrap_or_else(|_| "#FFFFFF".to_string()),
-                foreground_color: env::var("QR_FOREGROUND_COLOR")
-                    .unwrap_or_else(|_| "#000000".to_string()),
-                format: env::var("QR_FORMAT")
-                    .unwrap_or_else(|_| "png".to_string()),
-                expiry_days: env::var("QR_EXPIRY_DAYS")
-                    .unwrap_or_else(|_| "365".to_string())
-                    .parse()
-                    .unwrap_or(365),
+                default_size: 256,
+                default_error_correction: "medium".to_string(),
+                include_logo: true,
+                logo_url: None,
+                background_color: "#FFFFFF".to_string(),
+                foreground_color: "#000000".to_string(),
+                format: "png".to_string(),
+                expiry_days: 365,
+                auto_redirect_seconds: Some(10),
+                default_target: RedirectTarget::Property,
+                payload_mode: QrPayloadMode::Url,
+                watch_properties: false,
+                verify_assets: false,
             },
             
             logging: LoggingConfig {
-                level: env::var("LOG_LEVEL")
-                    .unwrap_or_else(|_| "info".to_string()),
-                format: env::var("LOG_FORMAT")
-                    .unwrap_or_else(|_| "pretty".to_string()),
-                enable_json: env::var("LOG_JSON")
-                    .unwrap_or_else(|_| "false".to_string())
-                    .parse()
-                    .unwrap_or(false),
-            },
-        })
+                level: "info".to_string(),
+                format: "pretty".to_string(),
+                enable_json: false,
+            },
+            
+            sms: SmsConfig {
+                provider: SmsProviderKind::Disabled,
+                account_id: None,
+                api_key: None,
+                sender_id: None,
+                max_per_ip_per_hour: 3,
+            },
+            
+            email: EmailConfig {
+                provider: EmailProviderKind::Disabled,
+                api_key: None,
+                from_address: None,
+                from_name: "DAO-Bitat".to_string(),
+                smtp_host: None,
+                smtp_port: 25,
+            },
+            
+            digest: DigestConfig {
+                enabled: false,
+                weekday: chrono::Weekday::Mon,
+                hour_utc: 6,
+            },
+            
+            retention: RetentionConfig {
+                enabled: false,
+                dry_run: true,
+                raw_event_days: 365,
+                aggregate_days: 730,
+                interval_hours: 24,
+            },
+            
+            load_shedding: LoadSheddingConfig {
+                enabled: true,
+                probe_interval_ms: 100,
+                skip_enrichment_ms: 25,
+                defer_persistence_ms: 75,
+                reject_non_scan_ms: 200,
+            },
+            
+            geolocation: GeolocationConfig {
+                provider: GeoProviderKind::Disabled,
+                maxmind_db_path: None,
+                ipapi_base_url: "https://ipapi.co".to_string(),
+                ipapi_key: None,
+                cache_ttl_secs: 86400,
+                cache_capacity: 10_000,
+            },
+            
+            app_links: AppLinkConfig {
+                enabled: true,
+                scheme: "daobitat".to_string(),
+                android_package: None,
+            },
+            
+            privacy: PrivacyConfig {
+                public_stats_noise: true,
+                epsilon: 1.0,
+                min_bucket_size: 10,
+            },
+            
+            event_stream: EventStreamConfig {
+                kind: EventStreamKind::Disabled,
+                url: "localhost:9092".to_string(),
+                topic: "qr.scan-events".to_string(),
+                queue_capacity: 10_000,
+            },
+            
+            anomaly: AnomalyConfig {
+                enabled: false,
+                webhook_url: None,
+                webhook_kind: AlertWebhookKind::Slack,
+                check_interval_minutes: 15,
+                spike_window_minutes: 60,
+                spike_multiplier: 10.0,
+                spike_min_scans: 50,
+                silence_hours: 48,
+                active_min_scans: 20,
+                cooldown_hours: 24,
+            },
+            
+            blockchain: BlockchainConfig {
+                registry_contract: None,
+                default_rpc_url: None,
+                rpc_urls: BTreeMap::new(),
+                cache_ttl_secs: 600,
+            },
+            
+            security_headers: SecurityHeadersConfig {
+                enabled: true,
+                scan_page_csp: None,
+                frame_options: "DENY".to_string(),
+                referrer_policy: "strict-origin-when-cross-origin".to_string(),
+                hsts_max_age_seconds: 31_536_000,
+            },
+        }
     }
 
     /// Create default settings for development
@@ -195,13 +532,21 @@ impl Settings {
             server: ServerConfig {
                 host: "127.0.0.1".to_string(),
                 port: 3000,
+                grpc_port: Some(50051),
                 environment: Environment::Development,
                 cors_origins: vec![
                     "http://localhost:3000".to_string(),
                     "http://localhost:3001".to_string(),
                 ],
                 request_timeout_seconds: 30,
+                max_body_bytes: 1024 * 1024,
                 max_connections: Some(100),
+                admin_api_key: None,
+                startup_self_test: true,
+                session_secret: None,
+                jwt_secret: None,
+                jwt_issuer: None,
+                jwks_url: None,
             },
             
             database: DatabaseConfig {
@@ -217,15 +562,30 @@ impl Settings {
                 s3_bucket: "daobitat-qr-codes-dev".to_string(),
                 s3_bucket_region: "us-east-1".to_string(),
                 cloudfront_domain: None,
+                secondary_s3_bucket: None,
+                secondary_s3_bucket_region: None,
                 access_key_id: None,
                 secret_access_key: None,
                 session_token: None,
             },
             
+            storage: StorageConfig {
+                backend: StorageBackendKind::Local,
+                gcs_bucket: None,
+                gcs_access_token: None,
+                local_root: "./storage".to_string(),
+                public_url: None,
+            },
+            
             urls: UrlConfig {
                 base_url: "http://localhost:3000".to_string(),
+                alias_hosts: Vec::new(),
+                image_domains: vec!["daobitat.xyz".to_string()],
+                link_domains: vec!["daobitat.xyz".to_string()],
+                hook_domains: vec!["hooks.zapier.com".to_string(), "make.com".to_string()],
                 daobitat_base_url: "http://localhost:3001".to_string(),
                 blockchain_explorer_base_url: "https://sepolia.basescan.org".to_string(),
+                blockchain_explorers: BTreeMap::from([(84532, "https://sepolia.basescan.org".to_string())]),
                 api_version: "v1".to_string(),
             },
             
@@ -238,6 +598,11 @@ impl Settings {
                 foreground_color: "#000000".to_string(),
                 format: "png".to_string(),
                 expiry_days: 30, // Shorter expiry for dev
+                auto_redirect_seconds: Some(10),
+                default_target: RedirectTarget::Property,
+                payload_mode: QrPayloadMode::Url,
+                watch_properties: false,
+                verify_assets: false,
             },
             
             logging: LoggingConfig {
@@ -245,6 +610,101 @@ impl Settings {
                 format: "pretty".to_string(),
                 enable_json: false,
             },
+            
+            sms: SmsConfig {
+                provider: SmsProviderKind::Disabled,
+                account_id: None,
+                api_key: None,
+                sender_id: None,
+                max_per_ip_per_hour: 10,
+            },
+            
+            email: EmailConfig {
+                provider: EmailProviderKind::Disabled,
+                api_key: None,
+                from_address: None,
+                from_name: "DAO-Bitat (dev)".to_string(),
+                smtp_host: None,
+                smtp_port: 25,
+            },
+            
+            digest: DigestConfig {
+                enabled: false,
+                weekday: chrono::Weekday::Mon,
+                hour_utc: 6,
+            },
+            
+            retention: RetentionConfig {
+                enabled: true,
+                dry_run: true,
+                raw_event_days: 30,
+                aggregate_days: 90,
+                interval_hours: 24,
+            },
+            
+            load_shedding: LoadSheddingConfig {
+                enabled: false, // Debug builds and breakpoints would trip it constantly
+                probe_interval_ms: 100,
+                skip_enrichment_ms: 25,
+                defer_persistence_ms: 75,
+                reject_non_scan_ms: 200,
+            },
+            
+            geolocation: GeolocationConfig {
+                provider: GeoProviderKind::Disabled,
+                maxmind_db_path: None,
+                ipapi_base_url: "https://ipapi.co".to_string(),
+                ipapi_key: None,
+                cache_ttl_secs: 3600,
+                cache_capacity: 1000,
+            },
+            
+            app_links: AppLinkConfig {
+                enabled: true,
+                scheme: "daobitat".to_string(),
+                android_package: None,
+            },
+            
+            privacy: PrivacyConfig {
+                public_stats_noise: false, // Exact counts are easier to check against test scans
+                epsilon: 1.0,
+                min_bucket_size: 10,
+            },
+            
+            event_stream: EventStreamConfig {
+                kind: EventStreamKind::Disabled,
+                url: "localhost:9092".to_string(),
+                topic: "qr.scan-events".to_string(),
+                queue_capacity: 1000,
+            },
+            
+            anomaly: AnomalyConfig {
+                enabled: false,
+                webhook_url: None,
+                webhook_kind: AlertWebhookKind::Slack,
+                check_interval_minutes: 15,
+                spike_window_minutes: 60,
+                spike_multiplier: 10.0,
+                spike_min_scans: 10,
+                silence_hours: 48,
+                active_min_scans: 5,
+                cooldown_hours: 24,
+            },
+            
+            blockchain: BlockchainConfig {
+                registry_contract: None,
+                default_rpc_url: None,
+                rpc_urls: BTreeMap::new(),
+                cache_ttl_secs: 600,
+            },
+            
+            security_headers: SecurityHeadersConfig {
+                enabled: true,
+                scan_page_csp: None,
+                frame_options: "DENY".to_string(),
+                referrer_policy: "strict-origin-when-cross-origin".to_string(),
+                hsts_max_age_seconds: 31_536_000,
+            },
         }
     }
 
@@ -254,13 +714,21 @@ impl Settings {
             server: ServerConfig {
                 host: "0.0.0.0".to_string(),
                 port: 8080,
+                grpc_port: None, // Should come from env vars
                 environment: Environment::Production,
                 cors_origins: vec![
                     "https://www.daobitat.xyz".to_string(),
                     "https://app.daobitat.xyz".to_string(),
                 ],
                 request_timeout_seconds: 30,
+                max_body_bytes: 1024 * 1024,
                 max_connections: Some(1000),
+                admin_api_key: None, // Should come from env vars
+                startup_self_test: true,
+                session_secret: None, // Should come from env vars
+                jwt_secret: None,     // Should come from env vars
+                jwt_issuer: None,
+                jwks_url: None,
             },
             
             database: DatabaseConfig {
@@ -276,15 +744,30 @@ impl Settings {
                 s3_bucket: "daobitat-qr-codes".to_string(),
                 s3_bucket_region: "us-east-1".to_string(),
                 cloudfront_domain: Some("cdn.daobitat.xyz".to_string()),
+                secondary_s3_bucket: None,
+                secondary_s3_bucket_region: None,
                 access_key_id: None, // Should come from IAM role or env vars
                 secret_access_key: None,
                 session_token: None,
             },
             
+            storage: StorageConfig {
+                backend: StorageBackendKind::S3,
+                gcs_bucket: None,
+                gcs_access_token: None,
+                local_root: "./storage".to_string(),
+                public_url: None,
+            },
+            
             urls: UrlConfig {
                 base_url: "https://qr-service.daobitat.xyz".to_string(),
+                alias_hosts: Vec::new(),
+                image_domains: vec!["daobitat.xyz".to_string()],
+                link_domains: vec!["daobitat.xyz".to_string()],
+                hook_domains: vec!["hooks.zapier.com".to_string(), "make.com".to_string()],
                 daobitat_base_url: "https://www.daobitat.xyz".to_string(),
                 blockchain_explorer_base_url: "https://basescan.org".to_string(),
+                blockchain_explorers: BTreeMap::from([(8453, "https://basescan.org".to_string())]),
                 api_version: "v1".to_string(),
             },
             
@@ -297,6 +780,11 @@ impl Settings {
                 foreground_color: "#000000".to_string(),
                 format: "png".to_string(),
                 expiry_days: 365,
+                auto_redirect_seconds: Some(10),
+                default_target: RedirectTarget::Property,
+                payload_mode: QrPayloadMode::Url,
+                watch_properties: false,
+                verify_assets: false,
             },
             
             logging: LoggingConfig {
@@ -304,6 +792,101 @@ impl Settings {
                 format: "json".to_string(),
                 enable_json: true,
             },
+            
+            sms: SmsConfig {
+                provider: SmsProviderKind::AfricasTalking,
+                account_id: None, // Should come from env vars
+                api_key: None,
+                sender_id: Some("DAOBITAT".to_string()),
+                max_per_ip_per_hour: 3,
+            },
+            
+            email: EmailConfig {
+                provider: EmailProviderKind::SendGrid,
+                api_key: None, // Should come from env vars
+                from_address: Some("listings@daobitat.xyz".to_string()),
+                from_name: "DAO-Bitat".to_string(),
+                smtp_host: None,
+                smtp_port: 25,
+            },
+            
+            digest: DigestConfig {
+                enabled: true,
+                weekday: chrono::Weekday::Mon,
+                hour_utc: 6,
+            },
+            
+            retention: RetentionConfig {
+                enabled: true,
+                dry_run: false,
+                raw_event_days: 365,
+                aggregate_days: 730,
+                interval_hours: 24,
+            },
+            
+            load_shedding: LoadSheddingConfig {
+                enabled: true,
+                probe_interval_ms: 100,
+                skip_enrichment_ms: 25,
+                defer_persistence_ms: 75,
+                reject_non_scan_ms: 200,
+            },
+            
+            geolocation: GeolocationConfig {
+                provider: GeoProviderKind::MaxMind,
+                maxmind_db_path: Some("/usr/share/GeoIP/GeoLite2-City.mmdb".to_string()),
+                ipapi_base_url: "https://ipapi.co".to_string(),
+                ipapi_key: None,
+                cache_ttl_secs: 86400,
+                cache_capacity: 10_000,
+            },
+            
+            app_links: AppLinkConfig {
+                enabled: true,
+                scheme: "daobitat".to_string(),
+                android_package: None, // Should come from env vars
+            },
+            
+            privacy: PrivacyConfig {
+                public_stats_noise: true,
+                epsilon: 1.0,
+                min_bucket_size: 10,
+            },
+            
+            event_stream: EventStreamConfig {
+                kind: EventStreamKind::Disabled, // Should come from env vars
+                url: String::new(),
+                topic: "qr.scan-events".to_string(),
+                queue_capacity: 10_000,
+            },
+            
+            anomaly: AnomalyConfig {
+                enabled: false, // Needs ANOMALY_WEBHOOK_URL from env vars
+                webhook_url: None,
+                webhook_kind: AlertWebhookKind::Slack,
+                check_interval_minutes: 15,
+                spike_window_minutes: 60,
+                spike_multiplier: 10.0,
+                spike_min_scans: 50,
+                silence_hours: 48,
+                active_min_scans: 20,
+                cooldown_hours: 24,
+            },
+            
+            blockchain: BlockchainConfig {
+                registry_contract: None,
+                default_rpc_url: None,
+                rpc_urls: BTreeMap::new(),
+                cache_ttl_secs: 600,
+            },
+            
+            security_headers: SecurityHeadersConfig {
+                enabled: true,
+                scan_page_csp: None,
+                frame_options: "DENY".to_string(),
+                referrer_policy: "strict-origin-when-cross-origin".to_string(),
+                hsts_max_age_seconds: 31_536_000,
+            },
         }
     }
 
@@ -314,6 +897,62 @@ impl Settings {
             return Err("Server port cannot be 0".to_string());
         }
 
+        if let Some(grpc_port) = self.server.grpc_port {
+            if grpc_port == 0 || grpc_port == self.server.port {
+                return Err("gRPC port must be non-zero and differ from the HTTP port".to_string());
+            }
+        }
+
+        if self.server.max_body_bytes == 0 {
+            return Err("Maximum request body size must be greater than 0".to_string());
+        }
+
+        if self.server.admin_api_key.as_ref().is_some_and(|key| key.len() < 16) {
+            return Err("Admin API key must be at least 16 characters".to_string());
+        }
+
+        if self.server.session_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err("Session secret must be at least 32 characters".to_string());
+        }
+
+        if self.server.jwt_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err("JWT secret must be at least 32 characters".to_string());
+        }
+
+        if self.server.jwks_url.as_ref().is_some_and(|url| !url.starts_with("http")) {
+            return Err("JWKS URL must start with http or https".to_string());
+        }
+
+        if self.urls.blockchain_explorers.values().any(|url| !url.starts_with("http")) {
+            return Err("Blockchain explorer URLs must start with http or https".to_string());
+        }
+
+        if self.blockchain.default_rpc_url.iter().chain(self.blockchain.rpc_urls.values()).any(|url| !url.starts_with("http")) {
+            return Err("Blockchain RPC URLs must start with http or https".to_string());
+        }
+
+        if self.event_stream.kind != EventStreamKind::Disabled
+            && (self.event_stream.url.is_empty() || self.event_stream.topic.is_empty())
+        {
+            return Err("Event streaming needs a URL and a topic".to_string());
+        }
+
+        // Validate anomaly alerting config
+        if self.anomaly.enabled && !self.anomaly.webhook_url.as_ref().is_some_and(|url| url.starts_with("http")) {
+            return Err("Anomaly alerts need an ANOMALY_WEBHOOK_URL starting with http or https".to_string());
+        }
+
+        if self.anomaly.check_interval_minutes == 0
+            || self.anomaly.spike_window_minutes < 1
+            || self.anomaly.silence_hours < 1
+        {
+            return Err("Anomaly check interval and windows must be greater than 0".to_string());
+        }
+
+        if self.anomaly.spike_multiplier <= 1.0 {
+            return Err("Anomaly spike multiplier must be greater than 1".to_string());
+        }
+
         // Validate database config
         if self.database.mongodb_uri.is_empty() {
             return Err("MongoDB URI cannot be empty".to_string());
@@ -328,6 +967,35 @@ impl Settings {
             return Err("S3 bucket name cannot be empty".to_string());
         }
 
+        if self.storage.backend == StorageBackendKind::Gcs && self.storage.gcs_bucket.is_none() {
+            return Err("GCS storage requires GCS_BUCKET".to_string());
+        }
+
+        if self.storage.backend == StorageBackendKind::Local && self.storage.local_root.is_empty() {
+            return Err("Local storage requires STORAGE_LOCAL_ROOT".to_string());
+        }
+
+        if self.storage.public_url.as_ref().is_some_and(|url| !url.starts_with("http")) {
+            return Err("Storage public URL must start with http or https".to_string());
+        }
+
+        if self.aws.secondary_s3_bucket.is_some() && self.storage.backend != StorageBackendKind::S3 {
+            return Err("A secondary S3 bucket needs the S3 storage backend".to_string());
+        }
+
+        if let Some(secondary_bucket) = &self.aws.secondary_s3_bucket {
+            if *secondary_bucket == self.aws.s3_bucket {
+                return Err("Secondary S3 bucket must differ from the primary".to_string());
+            }
+            match &self.aws.secondary_s3_bucket_region {
+                None => return Err("Secondary S3 bucket requires S3_SECONDARY_BUCKET_REGION".to_string()),
+                Some(region) if *region == self.aws.region => {
+                    return Err("Secondary S3 bucket must be in a different region from the primary".to_string());
+                }
+                Some(_) => {}
+            }
+        }
+
         // Validate URLs
         if !self.urls.base_url.starts_with("http") {
             return Err("Base URL must start with http or https".to_string());
@@ -337,11 +1005,113 @@ impl Settings {
             return Err("DAO-Bitat base URL must start with http or https".to_string());
         }
 
+        if self.urls.alias_hosts.iter().any(|host| host.contains('/')) {
+            return Err("Base URL aliases must be hostnames, not URLs".to_string());
+        }
+
+        // Validate SMS config
+        if self.sms.provider != SmsProviderKind::Disabled
+            && (self.sms.account_id.is_none() || self.sms.api_key.is_none())
+        {
+            return Err("SMS provider requires SMS_ACCOUNT_ID and SMS_API_KEY".to_string());
+        }
+
+        // Validate email config
+        if self.email.provider != EmailProviderKind::Disabled && self.email.from_address.is_none() {
+            return Err("Email provider requires EMAIL_FROM_ADDRESS".to_string());
+        }
+
+        if self.email.provider == EmailProviderKind::SendGrid && self.email.api_key.is_none() {
+            return Err("SendGrid email requires EMAIL_API_KEY".to_string());
+        }
+
+        if self.email.provider == EmailProviderKind::Ses
+            && (self.aws.access_key_id.is_none() || self.aws.secret_access_key.is_none())
+        {
+            return Err("SES email requires AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY".to_string());
+        }
+
+        if self.email.provider == EmailProviderKind::Smtp && self.email.smtp_host.is_none() {
+            return Err("SMTP email requires EMAIL_SMTP_HOST".to_string());
+        }
+
+        if self.digest.hour_utc > 23 {
+            return Err("Digest hour must be between 0 and 23".to_string());
+        }
+
+        // Validate retention config
+        if self.retention.raw_event_days < 1 || self.retention.aggregate_days < 1 {
+            return Err("Retention periods must be at least 1 day".to_string());
+        }
+
+        if self.retention.aggregate_days < self.retention.raw_event_days {
+            return Err("Aggregate retention cannot be shorter than raw event retention".to_string());
+        }
+
+        // Validate load shedding config
+        let shedding = &self.load_shedding;
+        if shedding.probe_interval_ms == 0 {
+            return Err("Load shedding probe interval must be greater than 0".to_string());
+        }
+
+        if !(shedding.skip_enrichment_ms <= shedding.defer_persistence_ms
+            && shedding.defer_persistence_ms <= shedding.reject_non_scan_ms)
+        {
+            return Err("Load shedding thresholds must increase: skip enrichment <= defer persistence <= reject".to_string());
+        }
+
+        // Validate geolocation config
+        if self.geolocation.provider == GeoProviderKind::MaxMind && self.geolocation.maxmind_db_path.is_none() {
+            return Err("MaxMind geolocation requires GEO_MAXMIND_DB_PATH".to_string());
+        }
+
+        if self.geolocation.provider == GeoProviderKind::IpApi && !self.geolocation.ipapi_base_url.starts_with("http") {
+            return Err("ipapi base URL must start with http or https".to_string());
+        }
+
+        // Validate app link config
+        let scheme = &self.app_links.scheme;
+        if self.app_links.enabled
+            && !(scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')))
+        {
+            return Err("App link scheme must be a URL scheme like 'daobitat'".to_string());
+        }
+
+        // Validate privacy config
+        if !(self.privacy.epsilon.is_finite() && self.privacy.epsilon > 0.0) {
+            return Err("Privacy epsilon must be a positive number".to_string());
+        }
+
+        if self.privacy.min_bucket_size < 0 {
+            return Err("Privacy minimum bucket size cannot be negative".to_string());
+        }
+
         // Validate QR config
         if self.qr.default_size < 64 || self.qr.default_size > 2048 {
             return Err("QR size must be between 64 and 2048 pixels".to_string());
         }
 
+        if self.qr.auto_redirect_seconds.is_some_and(|seconds| seconds > 300) {
+            return Err("Auto-redirect delay cannot exceed 300 seconds".to_string());
+        }
+
+        // Validate security headers config
+        let frame_options = self.security_headers.frame_options.to_ascii_uppercase();
+        if !matches!(frame_options.as_str(), "" | "DENY" | "SAMEORIGIN") {
+            return Err("X-Frame-Options must be DENY or SAMEORIGIN".to_string());
+        }
+
+        if self.urls.image_domains.iter().any(|domain| domain.contains(['/', ';', ' '])) {
+            return Err("Image domains must be hostnames, not URLs".to_string());
+        }
+        if self.urls.link_domains.iter().any(|domain| domain.contains(['/', ';', ' '])) {
+            return Err("Short link domains must be hostnames, not URLs".to_string());
+        }
+        if self.urls.hook_domains.iter().any(|domain| domain.contains(['/', ';', ' '])) {
+            return Err("REST hook domains must be hostnames, not URLs".to_string());
+        }
+
         Ok(())
     }
 
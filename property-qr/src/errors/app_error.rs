@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use tracing::error;
 
+use crate::utils::ValidationError;
+
 /// Result type alias for the application
 pub type AppResult<T> = Result<T, AppError>;
 
@@ -19,6 +21,7 @@ pub struct AppError {
     pub message: String,
     pub context: Option<ErrorContext>,
     pub source: Option<String>,
+    pub validation_errors: Vec<ValidationError>, // Every invalid field, for ValidationFailed
 }
 
 /// Error codes for different types of errors
@@ -27,6 +30,7 @@ pub struct AppError {
 pub enum ErrorCode {
     // Validation errors
     InvalidInput,
+    ValidationFailed, // One or more fields failed validation; all of them are in the response
     InvalidPropertyId,
     InvalidQrCode,
     InvalidFileFormat,
@@ -101,7 +105,7 @@ pub struct ErrorResponse {
     pub request_id: Option<String>,
     pub context: Option<ErrorContext>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub details: Option<serde_json::Value>, // Field errors for ValidationFailed, as [{field, message, code}]
 }
 
 impl AppError {
@@ -112,6 +116,7 @@ impl AppError {
             message: message.into(),
             context: None,
             source: None,
+            validation_errors: Vec::new(),
         }
     }
 
@@ -132,6 +137,18 @@ impl AppError {
         Self::new(ErrorCode::InvalidInput, message)
     }
 
+    /// Create an error carrying every failed check, e.g. from ValidationBuilder::build_all_errors
+    pub fn validation_failed(errors: Vec<ValidationError>) -> Self {
+        let message = match errors.as_slice() {
+            [error] => error.to_string(),
+            _ => format!("{} fields are invalid", errors.len()),
+        };
+        Self {
+            validation_errors: errors,
+            ..Self::new(ErrorCode::ValidationFailed, message)
+        }
+    }
+
     /// Create a not found error
     pub fn not_found(resource: impl Into<String>) -> Self {
         Self::new(ErrorCode::DocumentNotFound, format!("{} not found", resource.into()))
@@ -212,6 +229,7 @@ impl AppError {
     pub fn status_code(&self) -> StatusCode {
         match self.code {
             ErrorCode::InvalidInput
+            | ErrorCode::ValidationFailed
             | ErrorCode::InvalidPropertyId
             | ErrorCode::InvalidQrCode
             | ErrorCode::InvalidFileFormat
@@ -264,7 +282,9 @@ impl AppError {
             timestamp: chrono::Utc::now().to_rfc3339(),
             request_id,
             context: self.context.clone(),
-            details: None,
+            details: (!self.validation_errors.is_empty())
+                .then(|| serde_json::to_value(&self.validation_errors).ok())
+                .flatten(),
         }
     }
 }
@@ -336,6 +356,18 @@ impl From<std::io::Error> for AppError {
     }
 }
 
+impl From<ValidationError> for AppError {
+    fn from(err: ValidationError) -> Self {
+        AppError::validation_failed(vec![err])
+    }
+}
+
+impl From<Vec<ValidationError>> for AppError {
+    fn from(errors: Vec<ValidationError>) -> Self {
+        AppError::validation_failed(errors)
+    }
+}
+
 impl From<mongodb::bson::oid::Error> for AppError {
     fn from(err: mongodb::bson::oid::Error) -> Self {
         error!("ObjectId error: {}", err);
@@ -480,6 +512,28 @@ mod tests {
         assert!(!response.timestamp.is_empty());
     }
 
+    #[test]
+    fn test_validation_failed_lists_every_field() {
+        let mut builder = crate::utils::ValidationBuilder::new();
+        builder
+            .validate(|| crate::utils::validate_email("invalid"))
+            .validate(|| crate::utils::validate_price(-100, "price"));
+        let error = AppError::from(builder.build_all_errors().unwrap_err());
+        assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(error.message, "2 fields are invalid");
+
+        let response = serde_json::to_value(error.to_response(None)).unwrap();
+        assert_eq!(response["error"], "validation_failed");
+        assert_eq!(response["details"], serde_json::json!([
+            { "field": "email", "message": "Invalid email format", "code": "INVALID_EMAIL_FORMAT" },
+            { "field": "price", "message": "Price cannot be negative", "code": "NEGATIVE_PRICE" },
+        ]));
+
+        // Other errors leave details out
+        let response = serde_json::to_value(AppError::internal_error("boom").to_response(None)).unwrap();
+        assert!(response.get("details").is_none());
+    }
+
     #[test]
     fn test_app_error_macro() {
         let error = app_error!(ErrorCode::InvalidInput, "Invalid data");
@@ -0,0 +1,128 @@
+// src/graphql/mod.rs
+
+// GraphQL schema for dashboards: QR codes, listings and analytics in one query, with
+// nested fields (property -> qr -> recentScans) resolved only when asked for
+
+pub mod types;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema};
+
+use crate::models::{QrSortField, SortOrder, SystemAnalyticsResponse};
+use crate::services::{AnalyticsService, PropertyService, QrGeneratorService};
+use types::{load_property, load_qr_code, Property, QrCode};
+
+// Nesting and cost caps, so one request can't fan out into thousands of lookups
+const MAX_QUERY_DEPTH: usize = 8;
+const MAX_QUERY_COMPLEXITY: usize = 500;
+
+const DEFAULT_QR_CODE_LIMIT: i32 = 20;
+const MAX_QR_CODE_LIMIT: i32 = 100;
+
+pub type QrSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub struct QueryRoot;
+
+/// Build the read-only schema over the QR, listing and analytics services
+pub fn build_schema(
+    qr_generator: QrGeneratorService,
+    property_service: PropertyService,
+    analytics_service: AnalyticsService,
+) -> QrSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(qr_generator)
+        .data(property_service)
+        .data(analytics_service)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+#[Object]
+impl QueryRoot {
+    /// A listing by property ID
+    async fn property(&self, ctx: &Context<'_>, id: String) -> Result<Option<Property>> {
+        load_property(ctx, &id).await
+    }
+
+    /// A property's QR code
+    async fn qr_code(&self, ctx: &Context<'_>, property_id: String) -> Result<Option<QrCode>> {
+        load_qr_code(ctx, &property_id).await
+    }
+
+    /// QR codes, most recently generated first
+    async fn qr_codes(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default_with = "DEFAULT_QR_CODE_LIMIT")] limit: i32,
+        #[graphql(default = 0)] skip: i32,
+        #[graphql(default = false)] active_only: bool,
+    ) -> Result<Vec<QrCode>> {
+        let page = ctx.data::<QrGeneratorService>()?
+            .list_qr_codes(
+                None,
+                active_only,
+                QrSortField::GeneratedAt,
+                SortOrder::Desc,
+                limit.clamp(1, MAX_QR_CODE_LIMIT) as i64,
+                skip.max(0) as u64,
+            )
+            .await?;
+        Ok(page.items.into_iter().map(QrCode).collect())
+    }
+
+    /// System-wide scan and generation totals
+    async fn system_analytics(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = false)] include_comparison: bool,
+    ) -> Result<SystemAnalyticsResponse> {
+        Ok(ctx.data::<AnalyticsService>()?.get_system_analytics(include_comparison).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::Client;
+    use crate::services::{S3Storage, StorageService};
+    use std::sync::Arc;
+
+    async fn get_test_schema() -> QrSchema {
+        let client = Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .expect("Failed to connect to MongoDB");
+        let db = client.database("test_qr_graphql");
+        let property_service = PropertyService::new(&db);
+        let storage = StorageService::new(Arc::new(
+            S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).expect("Failed to create S3 storage"),
+        ));
+        let qr_generator = QrGeneratorService::new(
+            &db,
+            property_service.clone(),
+            storage,
+            "https://qr-service.daobitat.xyz".to_string(),
+        );
+        build_schema(qr_generator, property_service, AnalyticsService::new(&db))
+    }
+
+    #[tokio::test]
+    async fn test_schema_exposes_nested_fields() {
+        let sdl = get_test_schema().await.sdl();
+
+        assert!(sdl.contains("qr: QrCode"));
+        assert!(sdl.contains("recentScans(limit: Int! = 10): [Scan!]!"));
+        assert!(sdl.contains("systemAnalytics(includeComparison: Boolean! = false): SystemAnalyticsResponse!"));
+        // Scans never expose who scanned
+        assert!(!sdl.contains("ipAddress"));
+        assert!(!sdl.contains("visitorId"));
+    }
+
+    #[tokio::test]
+    async fn test_query_depth_is_capped() {
+        let schema = get_test_schema().await;
+        let query = "{ qrCode(propertyId: \"x\") { property { qr { property { qr { property { qr { property { qr { scanCount } } } } } } } } } }";
+
+        let response = schema.execute(query).await;
+        assert!(!response.errors.is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+// src/graphql/types.rs
+
+use async_graphql::{Context, Enum, Object, Result};
+use chrono::{DateTime, Utc};
+
+use crate::models::{self, PropertyListItem, PropertyScanAnalytics, QrCodeMetadata, ScanEvent};
+use crate::services::{AnalyticsService, PropertyService, QrGeneratorService};
+use crate::services::{property_service::PropertyError, qr_generator::QrGeneratorError};
+
+// Scans a nested recentScans field returns unless asked for fewer
+pub const DEFAULT_RECENT_SCANS: i32 = 10;
+pub const MAX_RECENT_SCANS: i32 = 100;
+
+// Listing, with its QR code and analytics resolved on demand
+pub struct Property(pub PropertyListItem);
+
+// QR code, with its listing, analytics and latest scans resolved on demand
+pub struct QrCode(pub QrCodeMetadata);
+
+// One human scan; visitor identifiers, IPs and user agents are left out
+pub struct Scan(pub ScanEvent);
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "models::ScanSource")]
+pub enum ScanSource {
+    QrCode,
+    DirectLink,
+    ShareLink,
+    SearchEngine,
+    SocialMedia,
+    EmailSignature,
+    Unknown,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "models::RedirectType")]
+pub enum RedirectType {
+    DualRedirect,
+    DaobitarOnly,
+    BlockchainOnly,
+    CustomRedirect,
+    DocumentVerification,
+    ShareOffer,
+    Failed,
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "models::DeviceType")]
+pub enum DeviceType {
+    Mobile,
+    Tablet,
+    Desktop,
+    Unknown,
+}
+
+/// Listing for a property ID, or None if it doesn't exist; ineligible listings are still returned
+pub async fn load_property(ctx: &Context<'_>, property_id: &str) -> Result<Option<Property>> {
+    match ctx.data::<PropertyService>()?.get_property_by_id(property_id).await {
+        Ok(property) => Ok(Some(Property(property.to_list_item()))),
+        Err(PropertyError::NotFound | PropertyError::InvalidId) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// QR code for a property ID, or None if it has none
+pub async fn load_qr_code(ctx: &Context<'_>, property_id: &str) -> Result<Option<QrCode>> {
+    match ctx.data::<QrGeneratorService>()?.get_qr_code(property_id).await {
+        Ok(qr_code) => Ok(Some(QrCode(qr_code))),
+        Err(QrGeneratorError::PropertyNotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn load_analytics(ctx: &Context<'_>, property_id: &str) -> Result<PropertyScanAnalytics> {
+    let response = ctx.data::<AnalyticsService>()?
+        .get_property_analytics(property_id, false)
+        .await?;
+    Ok(response.analytics)
+}
+
+#[Object]
+impl Property {
+    async fn id(&self) -> &str {
+        &self.0.property_id
+    }
+
+    async fn owner_id(&self) -> &str {
+        &self.0.owner_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.property_name
+    }
+
+    async fn location(&self) -> &str {
+        &self.0.location
+    }
+
+    /// "for sale" or "for rent"
+    async fn action(&self) -> &str {
+        &self.0.action
+    }
+
+    async fn price(&self) -> i64 {
+        self.0.price
+    }
+
+    async fn onchain_id(&self) -> Option<&str> {
+        self.0.onchain_id.as_deref()
+    }
+
+    async fn crypto_accepted(&self) -> bool {
+        self.0.crypto_accepted
+    }
+
+    async fn primary_image(&self) -> Option<&str> {
+        self.0.primary_image.as_deref()
+    }
+
+    async fn is_verified(&self) -> bool {
+        self.0.is_verified
+    }
+
+    async fn sold(&self) -> bool {
+        self.0.sold
+    }
+
+    /// Whether the listing meets the rules for getting a QR code
+    async fn qr_eligible(&self) -> bool {
+        self.0.qr_eligible
+    }
+
+    async fn qr(&self, ctx: &Context<'_>) -> Result<Option<QrCode>> {
+        load_qr_code(ctx, &self.0.property_id).await
+    }
+
+    async fn analytics(&self, ctx: &Context<'_>) -> Result<PropertyScanAnalytics> {
+        load_analytics(ctx, &self.0.property_id).await
+    }
+}
+
+#[Object]
+impl QrCode {
+    async fn property_id(&self) -> &str {
+        &self.0.property_id
+    }
+
+    /// Stored image
+    async fn qr_code_url(&self) -> &str {
+        &self.0.qr_code_url
+    }
+
+    /// Scan URL the code encodes; None for geo and WiFi codes
+    async fn scan_url(&self) -> Option<String> {
+        self.0.encoded_scan_url()
+    }
+
+    async fn generated_at(&self) -> DateTime<Utc> {
+        self.0.generated_at
+    }
+
+    async fn last_updated(&self) -> DateTime<Utc> {
+        self.0.last_updated
+    }
+
+    async fn scan_count(&self) -> i64 {
+        self.0.scan_count
+    }
+
+    async fn last_scanned(&self) -> Option<DateTime<Utc>> {
+        self.0.last_scanned
+    }
+
+    async fn is_active(&self) -> bool {
+        self.0.is_active
+    }
+
+    async fn qr_version(&self) -> i32 {
+        self.0.qr_version
+    }
+
+    async fn style(&self) -> Option<&str> {
+        self.0.style.as_deref()
+    }
+
+    /// Where scans go instead of the property page right now, if anywhere
+    async fn custom_redirect_url(&self) -> Option<&str> {
+        self.0.active_redirect_url(Utc::now())
+    }
+
+    async fn property(&self, ctx: &Context<'_>) -> Result<Option<Property>> {
+        load_property(ctx, &self.0.property_id).await
+    }
+
+    async fn analytics(&self, ctx: &Context<'_>) -> Result<PropertyScanAnalytics> {
+        load_analytics(ctx, &self.0.property_id).await
+    }
+
+    /// Latest human scans, newest first
+    async fn recent_scans(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default_with = "DEFAULT_RECENT_SCANS")] limit: i32,
+    ) -> Result<Vec<Scan>> {
+        let limit = limit.clamp(1, MAX_RECENT_SCANS) as i64;
+        let scans = ctx.data::<AnalyticsService>()?
+            .recent_scans(&self.0.property_id, limit)
+            .await?;
+        Ok(scans.into_iter().map(Scan).collect())
+    }
+}
+
+#[Object]
+impl Scan {
+    async fn id(&self) -> String {
+        self.0.id.to_hex()
+    }
+
+    async fn scanned_at(&self) -> DateTime<Utc> {
+        self.0.scanned_at
+    }
+
+    /// Version printed in the scanned code
+    async fn qr_version(&self) -> i32 {
+        self.0.qr_version
+    }
+
+    async fn outdated_qr(&self) -> bool {
+        self.0.outdated_qr
+    }
+
+    async fn scan_source(&self) -> ScanSource {
+        self.0.scan_source.clone().into()
+    }
+
+    async fn redirect_type(&self) -> RedirectType {
+        self.0.redirect_type.clone().into()
+    }
+
+    async fn redirect_success(&self) -> bool {
+        self.0.redirect_success
+    }
+
+    /// ISO 3166-1 alpha-2 code
+    async fn country(&self) -> Option<&str> {
+        self.0.geolocation.as_ref().and_then(|geo| geo.country.as_deref())
+    }
+
+    async fn city(&self) -> Option<&str> {
+        self.0.geolocation.as_ref().and_then(|geo| geo.city.as_deref())
+    }
+
+    async fn device_type(&self) -> Option<DeviceType> {
+        self.0.device_info.as_ref().map(|device| device.device_type.clone().into())
+    }
+
+    async fn platform(&self) -> Option<&str> {
+        self.0.device_info.as_ref().and_then(|device| device.platform.as_deref())
+    }
+
+    async fn referrer(&self) -> Option<&str> {
+        self.0.referrer.as_deref()
+    }
+
+    async fn utm_source(&self) -> Option<&str> {
+        self.0.utm.as_ref().and_then(|utm| utm.source.as_deref())
+    }
+
+    async fn utm_medium(&self) -> Option<&str> {
+        self.0.utm.as_ref().and_then(|utm| utm.medium.as_deref())
+    }
+
+    async fn utm_campaign(&self) -> Option<&str> {
+        self.0.utm.as_ref().and_then(|utm| utm.campaign.as_deref())
+    }
+
+    /// Milliseconds to answer the scan
+    async fn response_time(&self) -> Option<u64> {
+        self.0.response_time
+    }
+}
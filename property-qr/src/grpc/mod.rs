@@ -0,0 +1,16 @@
+// src/grpc/mod.rs
+
+// Internal gRPC API for the property backend: GenerateQr, GetQr and RecordScan, served
+// on settings.server.grpc_port alongside the HTTP server. Callers authenticate like on the
+// HTTP management API. Messages come from proto/property_qr.proto, compiled by build.rs.
+
+pub mod auth;
+pub mod service;
+
+pub mod proto {
+    tonic::include_proto!("propertyqr.v1");
+}
+
+pub use proto::property_qr_server::PropertyQrServer;
+pub use auth::GrpcAuth;
+pub use service::PropertyQrGrpc;
@@ -0,0 +1,284 @@
+// src/grpc/service.rs
+
+use axum::http::StatusCode;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{info, error, warn};
+
+use crate::grpc::proto::{self, property_qr_server::PropertyQr};
+use crate::handlers::{check_property_access, AuthAppState};
+use crate::models::{AuditActor, Principal, QrCodeMetadata, QrCodeResponse, QrGenerationReason, QrStatus, RedirectType, Role, ScanEvent, ScanSource, UtmParameters};
+use crate::services::{AnalyticsService, PropertyService, QrGeneratorService, QrGenerationOptions};
+use crate::services::{property_service::PropertyError, qr_generator::QrGeneratorError};
+
+// Implements the PropertyQr gRPC service over the same services the HTTP handlers use
+#[derive(Clone)]
+pub struct PropertyQrGrpc {
+    pub qr_generator: QrGeneratorService,
+    pub property_service: PropertyService,
+    pub analytics_service: AnalyticsService,
+    pub auth: Arc<AuthAppState>, // Property access checks for the caller GrpcAuth identified
+}
+
+fn qr_status(e: QrGeneratorError) -> Status {
+    match e {
+        QrGeneratorError::PropertyNotFound => Status::not_found(e.to_string()),
+        QrGeneratorError::InvalidPropertyId
+        | QrGeneratorError::StyleNotFound(_)
+        | QrGeneratorError::InvalidPayload(_) => Status::invalid_argument(e.to_string()),
+        QrGeneratorError::PropertyNotEligible(_) => Status::failed_precondition(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+fn generation_reason(reason: proto::GenerationReason) -> QrGenerationReason {
+    match reason {
+        proto::GenerationReason::Unspecified | proto::GenerationReason::NewProperty => QrGenerationReason::NewProperty,
+        proto::GenerationReason::PropertyUpdated => QrGenerationReason::PropertyUpdated,
+        proto::GenerationReason::ManualRegeneration => QrGenerationReason::ManualRegeneration,
+        proto::GenerationReason::BatchGeneration => QrGenerationReason::BatchGeneration,
+        proto::GenerationReason::ExpiredQr => QrGenerationReason::ExpiredQr,
+        proto::GenerationReason::BaseUrlChanged => QrGenerationReason::BaseUrlChanged,
+    }
+}
+
+fn scan_source(source: proto::ScanSource) -> ScanSource {
+    match source {
+        proto::ScanSource::Unspecified | proto::ScanSource::QrCode => ScanSource::QrCode,
+        proto::ScanSource::DirectLink => ScanSource::DirectLink,
+        proto::ScanSource::ShareLink => ScanSource::ShareLink,
+        proto::ScanSource::SearchEngine => ScanSource::SearchEngine,
+        proto::ScanSource::SocialMedia => ScanSource::SocialMedia,
+        proto::ScanSource::EmailSignature => ScanSource::EmailSignature,
+    }
+}
+
+fn redirect_type(redirect: proto::RedirectType) -> RedirectType {
+    match redirect {
+        proto::RedirectType::Unspecified | proto::RedirectType::DaobitatOnly => RedirectType::DaobitarOnly,
+        proto::RedirectType::DualRedirect => RedirectType::DualRedirect,
+        proto::RedirectType::BlockchainOnly => RedirectType::BlockchainOnly,
+        proto::RedirectType::CustomRedirect => RedirectType::CustomRedirect,
+    }
+}
+
+fn generate_response(qr: QrCodeResponse) -> proto::GenerateQrResponse {
+    let status = match qr.status {
+        QrStatus::Generated => proto::QrStatus::Generated,
+        QrStatus::Regenerated => proto::QrStatus::Regenerated,
+        QrStatus::Failed => proto::QrStatus::Failed,
+        QrStatus::Exists => proto::QrStatus::Exists,
+    };
+
+    proto::GenerateQrResponse {
+        property_id: qr.property_id,
+        qr_code_url: qr.qr_code_url,
+        scan_url: qr.scan_url,
+        generated_at_unix_ms: qr.generated_at.timestamp_millis(),
+        status: status.into(),
+    }
+}
+
+fn qr_code_message(qr: QrCodeMetadata) -> proto::QrCode {
+    proto::QrCode {
+        scan_url: qr.encoded_scan_url(),
+        property_id: qr.property_id,
+        qr_code_url: qr.qr_code_url,
+        generated_at_unix_ms: qr.generated_at.timestamp_millis(),
+        last_updated_unix_ms: qr.last_updated.timestamp_millis(),
+        scan_count: qr.scan_count,
+        last_scanned_unix_ms: qr.last_scanned.map(|at| at.timestamp_millis()),
+        is_active: qr.is_active,
+        qr_version: qr.qr_version,
+        style: qr.style,
+    }
+}
+
+/// The caller GrpcAuth identified; a service mounted without it serves nobody
+fn caller<T>(request: &Request<T>) -> Option<Principal> {
+    request.extensions().get::<Principal>().cloned()
+}
+
+/// Who is calling, for the audit log, as authenticated
+fn request_actor(principal: &Principal) -> AuditActor {
+    match (principal.role, principal.user_id.clone()) {
+        (Role::Admin, user_id) => AuditActor::admin(user_id),
+        (_, Some(user_id)) => AuditActor::user(user_id),
+        (_, None) => AuditActor::system("grpc"),
+    }
+}
+
+/// UTM tags from the request, or None when it carries none
+fn utm_parameters(request: &proto::RecordScanRequest) -> Option<UtmParameters> {
+    let utm = UtmParameters {
+        source: request.utm_source.clone(),
+        medium: request.utm_medium.clone(),
+        campaign: request.utm_campaign.clone(),
+    };
+    (utm.source.is_some() || utm.medium.is_some() || utm.campaign.is_some()).then_some(utm)
+}
+
+impl PropertyQrGrpc {
+    /// Agents may only reach their own properties, and read-only staff may only read
+    async fn authorize(&self, principal: Option<&Principal>, property_id: &str, is_read: bool) -> Result<(), Status> {
+        let Some(principal) = principal else {
+            return Err(Status::unauthenticated("Caller is not authenticated"));
+        };
+        match check_property_access(&self.auth, principal, Some(property_id), is_read).await {
+            None => Ok(()),
+            Some(denied) if denied.status() == StatusCode::FORBIDDEN => {
+                Err(Status::permission_denied("Caller may not access this property"))
+            }
+            Some(_) => Err(Status::internal("Failed to check property access")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl PropertyQr for PropertyQrGrpc {
+    async fn generate_qr(
+        &self,
+        request: Request<proto::GenerateQrRequest>,
+    ) -> Result<Response<proto::GenerateQrResponse>, Status> {
+        let principal = caller(&request);
+        let request = request.into_inner();
+        self.authorize(principal.as_ref(), &request.property_id, false).await?;
+        let actor = principal.as_ref().map_or_else(|| AuditActor::system("grpc"), request_actor);
+        info!("gRPC: generating QR code for property: {}", request.property_id);
+
+        let reason = generation_reason(request.reason());
+        let options = QrGenerationOptions {
+            style: request.style,
+            actor: Some(actor),
+            ..QrGenerationOptions::default()
+        };
+
+        match self.qr_generator
+            .generate_qr_code_with(request.property_id.clone(), request.force_regenerate, reason, options)
+            .await
+        {
+            Ok(qr) => Ok(Response::new(generate_response(qr))),
+            Err(e) => {
+                error!("gRPC: failed to generate QR code for property {}: {}", request.property_id, e);
+                Err(qr_status(e))
+            }
+        }
+    }
+
+    async fn get_qr(
+        &self,
+        request: Request<proto::GetQrRequest>,
+    ) -> Result<Response<proto::QrCode>, Status> {
+        let principal = caller(&request);
+        let property_id = request.into_inner().property_id;
+        self.authorize(principal.as_ref(), &property_id, true).await?;
+
+        match self.qr_generator.get_qr_code(&property_id).await {
+            Ok(qr) => Ok(Response::new(qr_code_message(qr))),
+            Err(e) => {
+                warn!("gRPC: QR code not found for property {}: {}", property_id, e);
+                Err(qr_status(e))
+            }
+        }
+    }
+
+    async fn record_scan(
+        &self,
+        request: Request<proto::RecordScanRequest>,
+    ) -> Result<Response<proto::RecordScanResponse>, Status> {
+        // Scans are reported by the property backend, never on an agent's behalf
+        match caller(&request) {
+            Some(principal) if principal.is_admin() => {}
+            Some(_) => return Err(Status::permission_denied("Only the admin API key may record scans")),
+            None => return Err(Status::unauthenticated("Caller is not authenticated")),
+        }
+        let request = request.into_inner();
+
+        match self.property_service.get_property_by_id(&request.property_id).await {
+            Ok(_) => {}
+            Err(PropertyError::NotFound) => return Err(Status::not_found("Property not found")),
+            Err(PropertyError::InvalidId) => return Err(Status::invalid_argument("Invalid property ID")),
+            Err(e) => return Err(Status::internal(e.to_string())),
+        }
+
+        let current_qr_version = self.qr_generator.get_qr_code(&request.property_id).await
+            .ok()
+            .map(|qr| qr.qr_version);
+        let visitor_id = request.visitor_id.clone().unwrap_or_else(|| {
+            ScanEvent::visitor_hash(request.ip_address.as_deref(), request.user_agent.as_deref())
+        });
+
+        // Crawlers are recorded but don't count towards the code's scan count, as on /scan
+        if !request.user_agent.as_deref().is_some_and(ScanEvent::is_bot_user_agent) {
+            if let Err(e) = self.qr_generator.record_scan(&request.property_id).await {
+                warn!("gRPC: failed to update QR scan count for property {}: {}", request.property_id, e);
+            }
+        }
+
+        let scan_id = self.analytics_service
+            .record_scan(
+                request.property_id.clone(),
+                request.qr_version.max(1),
+                current_qr_version,
+                scan_source(request.scan_source()),
+                redirect_type(request.redirect_type()),
+                request.user_agent.clone(),
+                request.ip_address.clone(),
+                request.session_id.clone(),
+                Some(visitor_id),
+                request.referrer.clone(),
+                utm_parameters(&request),
+                None,
+            )
+            .await
+            .map_err(|e| {
+                error!("gRPC: failed to record scan for property {}: {}", request.property_id, e);
+                Status::internal("Failed to record scan")
+            })?;
+
+        Ok(Response::new(proto::RecordScanResponse { scan_id: scan_id.to_hex() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unspecified_enums_fall_back_to_defaults() {
+        assert!(matches!(generation_reason(proto::GenerationReason::Unspecified), QrGenerationReason::NewProperty));
+        assert!(matches!(scan_source(proto::ScanSource::Unspecified), ScanSource::QrCode));
+        assert!(matches!(redirect_type(proto::RedirectType::Unspecified), RedirectType::DaobitarOnly));
+    }
+
+    #[test]
+    fn test_qr_errors_map_to_grpc_codes() {
+        assert_eq!(qr_status(QrGeneratorError::PropertyNotFound).code(), tonic::Code::NotFound);
+        assert_eq!(qr_status(QrGeneratorError::InvalidPropertyId).code(), tonic::Code::InvalidArgument);
+        assert_eq!(qr_status(QrGeneratorError::PropertyNotEligible("sold".into())).code(), tonic::Code::FailedPrecondition);
+        assert_eq!(qr_status(QrGeneratorError::JobNotFound).code(), tonic::Code::Internal);
+    }
+
+    #[test]
+    fn test_request_actor_from_principal() {
+        let admin = Principal { role: Role::Admin, user_id: None };
+        assert_eq!(request_actor(&admin), AuditActor::admin(None));
+
+        let agent = Principal { role: Role::Agent, user_id: Some("agent-42".into()) };
+        assert_eq!(request_actor(&agent), AuditActor::user("agent-42".into()));
+
+        // Metadata alone never names an actor
+        let mut request = Request::new(());
+        request.metadata_mut().insert("x-user-id", "agent-42".parse().unwrap());
+        assert!(caller(&request).is_none());
+    }
+
+    #[test]
+    fn test_utm_parameters_only_when_tagged() {
+        let mut request = proto::RecordScanRequest { property_id: "p1".into(), ..Default::default() };
+        assert!(utm_parameters(&request).is_none());
+
+        request.utm_campaign = Some("open-house".into());
+        assert_eq!(utm_parameters(&request).and_then(|utm| utm.campaign).as_deref(), Some("open-house"));
+    }
+}
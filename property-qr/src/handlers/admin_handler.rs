@@ -0,0 +1,571 @@
+// src/handlers/admin_handler.rs
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Redirect, Response},
+    Form, Json,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn, error};
+
+use crate::handlers::{cookie_value, audit_handler::request_actor, ErrorResponse};
+use crate::models::{QrCodeMetadata, QrGenerationReason, QrRegenerationJob};
+use crate::services::{AutoRedirectService, ImpersonationService, OrganizationService, PropertyService, QrGenerationOptions, QrGeneratorService};
+use crate::utils::{escape_html, render_template};
+
+// Browser session for the dashboard; holds a hash of the key, never the key itself
+const ADMIN_COOKIE_NAME: &str = "dbqr_admin";
+const ADMIN_COOKIE_MAX_AGE: u64 = 60 * 60 * 8; // 8 hours
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const RECENT_JOB_LIMIT: i64 = 10;
+const MAX_LISTED_FAILURES: usize = 25;
+
+// Application state for the admin dashboard
+#[derive(Clone)]
+pub struct AdminAppState {
+    pub qr_generator: QrGeneratorService,
+    pub property_service: PropertyService, // Live listing names for the dashboard
+    pub impersonation_service: ImpersonationService,
+    pub organization_service: OrganizationService, // Membership is managed by admins only
+    pub auto_redirect_service: AutoRedirectService, // The global config; owners manage their properties'
+    pub api_key: String,
+    pub secure_cookies: bool, // Only send the session cookie over HTTPS
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminDashboardQuery {
+    pub limit: Option<i64>,
+    pub skip: Option<u64>,
+    pub notice: Option<String>,   // Outcome of the last action, set by our own redirects
+    pub property: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminLoginForm {
+    pub api_key: String,
+}
+
+/// Middleware for admin routes: accept the API key as `X-API-Key`, a bearer token,
+/// or the session cookie issued by the login form
+pub async fn require_admin_key(
+    State(state): State<Arc<AdminAppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_authorized(&state.api_key, request.headers()) {
+        return next.run(request).await;
+    }
+
+    // Send browsers to the login form; API clients get a plain 401
+    if request.method() == Method::GET {
+        return Redirect::to("/admin/login").into_response();
+    }
+
+    warn!("Rejected unauthenticated admin request: {} {}", request.method(), request.uri().path());
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse::new("unauthorized", "A valid admin API key is required")),
+    ).into_response()
+}
+
+/// Show the admin login form
+/// GET /admin/login
+pub async fn admin_login_page() -> Html<String> {
+    Html(render_login_page(None))
+}
+
+/// Exchange the API key for a dashboard session cookie
+/// POST /admin/login
+pub async fn admin_login(
+    State(state): State<Arc<AdminAppState>>,
+    Form(form): Form<AdminLoginForm>,
+) -> Response {
+    if !constant_time_eq(form.api_key.as_bytes(), state.api_key.as_bytes()) {
+        warn!("Failed admin login attempt");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Html(render_login_page(Some("That API key is not valid."))),
+        ).into_response();
+    }
+
+    info!("Admin signed in to the dashboard");
+    let cookie = format!(
+        "{}={}; Path=/admin; Max-Age={}; HttpOnly; SameSite=Strict{}",
+        ADMIN_COOKIE_NAME,
+        admin_session_token(&state.api_key),
+        ADMIN_COOKIE_MAX_AGE,
+        if state.secure_cookies { "; Secure" } else { "" },
+    );
+
+    ([(header::SET_COOKIE, cookie)], Redirect::to("/admin")).into_response()
+}
+
+/// End the dashboard session
+/// POST /admin/logout
+pub async fn admin_logout() -> Response {
+    let cookie = format!("{}=; Path=/admin; Max-Age=0; HttpOnly; SameSite=Strict", ADMIN_COOKIE_NAME);
+    ([(header::SET_COOKIE, cookie)], Redirect::to("/admin/login")).into_response()
+}
+
+/// Admin dashboard listing QR codes, scan counts and recent regeneration failures
+/// GET /admin?limit=50&skip=0
+pub async fn admin_dashboard(
+    State(state): State<Arc<AdminAppState>>,
+    Query(query): Query<AdminDashboardQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, 500);
+    let skip = query.skip.unwrap_or(0);
+
+    let qr_codes = match state.qr_generator.get_all_qr_codes(Some(limit), Some(skip)).await {
+        Ok(qr_codes) => qr_codes,
+        Err(e) => {
+            error!("Failed to load QR codes for admin dashboard: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Html(render_layout("Error", &format!(
+                    r#"<p class="notice error">Failed to load QR codes: {}</p>"#,
+                    escape_html(&e.to_string())
+                ))),
+            ).into_response();
+        }
+    };
+
+    // Failures are secondary; still show the QR list if jobs can't be read
+    let jobs = state.qr_generator.recent_regeneration_jobs(RECENT_JOB_LIMIT).await
+        .unwrap_or_else(|e| {
+            warn!("Failed to load regeneration jobs for admin dashboard: {}", e);
+            Vec::new()
+        });
+
+    let listing_ids: Vec<ObjectId> = qr_codes.iter().filter_map(QrCodeMetadata::listing_id).collect();
+    let names = state.property_service.listing_names(&listing_ids).await;
+
+    let notice = notice_message(query.notice.as_deref(), query.property.as_deref());
+    Html(render_dashboard(&qr_codes, &names, &jobs, notice.as_deref(), limit, skip)).into_response()
+}
+
+/// Regenerate a QR code from the dashboard
+/// POST /admin/qr/{property_id}/regenerate
+pub async fn admin_regenerate_qr(
+    State(state): State<Arc<AdminAppState>>,
+    Path(property_id): Path<String>,
+    headers: HeaderMap,
+) -> Redirect {
+    info!("Admin regenerating QR code for property: {}", property_id);
+
+    let notice = match state.qr_generator
+        .generate_qr_code_with(property_id.clone(), true, QrGenerationReason::ManualRegeneration, QrGenerationOptions {
+            actor: Some(request_actor(Some(&state.api_key), None, &headers)),
+            ..QrGenerationOptions::default()
+        })
+        .await
+    {
+        Ok(_) => "regenerated",
+        Err(e) => {
+            error!("Admin regeneration failed for property {}: {}", property_id, e);
+            "regenerate_failed"
+        }
+    };
+
+    Redirect::to(&notice_redirect(notice, &property_id))
+}
+
+/// Deactivate a QR code from the dashboard
+/// POST /admin/qr/{property_id}/deactivate
+pub async fn admin_deactivate_qr(
+    State(state): State<Arc<AdminAppState>>,
+    Path(property_id): Path<String>,
+    headers: HeaderMap,
+) -> Redirect {
+    info!("Admin deactivating QR code for property: {}", property_id);
+
+    let notice = match state.qr_generator.deactivate_qr_code(&property_id, Some(request_actor(Some(&state.api_key), None, &headers))).await {
+        Ok(true) => "deactivated",
+        Ok(false) => "not_found",
+        Err(e) => {
+            error!("Admin deactivation failed for property {}: {}", property_id, e);
+            "deactivate_failed"
+        }
+    };
+
+    Redirect::to(&notice_redirect(notice, &property_id))
+}
+
+/// Check the request for the admin key in a header or a valid session cookie
+pub(crate) fn is_authorized(api_key: &str, headers: &HeaderMap) -> bool {
+    let header_key = headers.get("x-api-key")
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| {
+            headers.get(header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        });
+    if let Some(key) = header_key {
+        return constant_time_eq(key.trim().as_bytes(), api_key.as_bytes());
+    }
+
+    cookie_value(headers, ADMIN_COOKIE_NAME)
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), admin_session_token(api_key).as_bytes()))
+}
+
+/// Session cookie value derived from the API key, so rotating the key signs everyone out
+fn admin_session_token(api_key: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    format!("{:x}", Sha256::digest(format!("property-qr-admin:{}", api_key)))
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn notice_redirect(notice: &str, property_id: &str) -> String {
+    format!("/admin?notice={}&property={}", notice, urlencoding::encode(property_id))
+}
+
+/// Map a notice code to fixed text so the query string can't inject arbitrary messages
+fn notice_message(notice: Option<&str>, property_id: Option<&str>) -> Option<String> {
+    let property_id = property_id.unwrap_or("unknown");
+    let message = match notice? {
+        "regenerated" => format!("Regenerated QR code for {}", property_id),
+        "deactivated" => format!("Deactivated QR code for {}", property_id),
+        "not_found" => format!("No active QR code found for {}", property_id),
+        "regenerate_failed" => format!("Failed to regenerate QR code for {}; check the logs", property_id),
+        "deactivate_failed" => format!("Failed to deactivate QR code for {}; check the logs", property_id),
+        _ => return None,
+    };
+    Some(message)
+}
+
+const ADMIN_LAYOUT: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex">
+    <title>{{title}} - DAO-Bitat QR Admin</title>
+    <style>
+        body {
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            margin: 0;
+            padding: 24px;
+            background: #f3f4f6;
+            color: #111827;
+        }
+        header {
+            display: flex;
+            align-items: center;
+            justify-content: space-between;
+            margin-bottom: 24px;
+        }
+        h1 { margin: 0; font-size: 22px; }
+        h2 { font-size: 18px; margin: 32px 0 12px 0; }
+        section {
+            background: white;
+            border-radius: 12px;
+            padding: 20px;
+            box-shadow: 0 1px 3px rgba(0, 0, 0, 0.08);
+        }
+        table { width: 100%; border-collapse: collapse; font-size: 14px; }
+        th, td { text-align: left; padding: 8px; border-bottom: 1px solid #e5e7eb; vertical-align: top; }
+        th { color: #6b7280; font-weight: 600; }
+        code { font-size: 12px; }
+        form { display: inline; }
+        button {
+            padding: 6px 12px;
+            border: none;
+            border-radius: 6px;
+            background: #667eea;
+            color: white;
+            cursor: pointer;
+        }
+        button.danger { background: #dc2626; }
+        .stats { display: flex; gap: 16px; margin-bottom: 16px; }
+        .stat { background: #eef2ff; border-radius: 8px; padding: 12px 16px; }
+        .stat strong { display: block; font-size: 20px; }
+        .inactive { color: #9ca3af; }
+        .notice { padding: 12px 16px; border-radius: 8px; background: #ecfdf5; color: #065f46; }
+        .notice.error { background: #fef2f2; color: #991b1b; }
+        .pager { margin-top: 12px; display: flex; gap: 12px; }
+    </style>
+</head>
+<body>
+    {{content}}
+</body>
+</html>
+"#;
+
+const DASHBOARD_TEMPLATE: &str = r#"<header>
+        <h1>QR Admin</h1>
+        <form method="post" action="/admin/logout"><button type="submit">Sign out</button></form>
+    </header>
+    {{notice}}
+    <section>
+        <div class="stats">
+            <div class="stat"><strong>{{qr_count}}</strong>QR codes on this page</div>
+            <div class="stat"><strong>{{active_count}}</strong>Active</div>
+            <div class="stat"><strong>{{scan_total}}</strong>Scans</div>
+        </div>
+        <table>
+            <thead>
+                <tr><th>Property</th><th>Version</th><th>Status</th><th>Scans</th><th>Last scanned</th><th>Generated</th><th></th></tr>
+            </thead>
+            <tbody>
+                {{rows}}
+            </tbody>
+        </table>
+        <div class="pager">{{pager}}</div>
+    </section>
+    <h2>Recent failures</h2>
+    <section>
+        <table>
+            <thead>
+                <tr><th>Property</th><th>Error</th><th>Job</th><th>Started</th></tr>
+            </thead>
+            <tbody>
+                {{failures}}
+            </tbody>
+        </table>
+    </section>"#;
+
+const QR_ROW_TEMPLATE: &str = r#"<tr class="{{row_class}}">
+                    <td>{{property_name}}<br><code>{{property_id}}</code></td>
+                    <td>v{{version}}</td>
+                    <td>{{status}}</td>
+                    <td>{{scan_count}}</td>
+                    <td>{{last_scanned}}</td>
+                    <td>{{generated_at}}</td>
+                    <td>
+                        <form method="post" action="/admin/qr/{{property_id}}/regenerate"><button type="submit">Regenerate</button></form>
+                        {{deactivate}}
+                    </td>
+                </tr>"#;
+
+const FAILURE_ROW_TEMPLATE: &str = r#"<tr>
+                    <td><code>{{property_id}}</code></td>
+                    <td>{{error_code}}: {{error}}</td>
+                    <td><code>{{job_id}}</code></td>
+                    <td>{{started_at}}</td>
+                </tr>"#;
+
+const LOGIN_TEMPLATE: &str = r#"<section style="max-width: 360px; margin: 80px auto;">
+        <h1>QR Admin</h1>
+        {{notice}}
+        <form method="post" action="/admin/login" style="display: block; margin-top: 16px;">
+            <p><label for="api_key">API key</label></p>
+            <p><input id="api_key" name="api_key" type="password" autocomplete="current-password" required style="width: 100%; padding: 8px;"></p>
+            <button type="submit">Sign in</button>
+        </form>
+    </section>"#;
+
+fn render_layout(title: &str, content: &str) -> String {
+    render_template(ADMIN_LAYOUT, &[("title", &escape_html(title)), ("content", content)])
+}
+
+fn render_login_page(error_message: Option<&str>) -> String {
+    let notice = error_message
+        .map(|message| format!(r#"<p class="notice error">{}</p>"#, escape_html(message)))
+        .unwrap_or_default();
+
+    render_layout("Sign in", &render_template(LOGIN_TEMPLATE, &[("notice", &notice)]))
+}
+
+/// Render the dashboard page for one page of QR codes
+fn render_dashboard(
+    qr_codes: &[QrCodeMetadata],
+    names: &HashMap<ObjectId, String>,
+    jobs: &[QrRegenerationJob],
+    notice: Option<&str>,
+    limit: i64,
+    skip: u64,
+) -> String {
+    let rows = if qr_codes.is_empty() {
+        r#"<tr><td colspan="7">No QR codes yet.</td></tr>"#.to_string()
+    } else {
+        qr_codes.iter()
+            .map(|qr_code| render_qr_row(qr_code, Some(&qr_code.listing_name(names))))
+            .collect::<Vec<_>>()
+            .join("\n                ")
+    };
+
+    let failures: Vec<String> = jobs
+        .iter()
+        .flat_map(|job| job.failed.iter().map(move |failure| (job, failure)))
+        .take(MAX_LISTED_FAILURES)
+        .map(|(job, failure)| render_template(FAILURE_ROW_TEMPLATE, &[
+            ("property_id", &escape_html(&failure.property_id)),
+            ("error_code", &escape_html(&failure.error_code)),
+            ("error", &escape_html(&failure.error)),
+            ("job_id", &job.id.to_hex()),
+            ("started_at", &job.created_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+        ]))
+        .collect();
+    let failures = if failures.is_empty() {
+        r#"<tr><td colspan="4">No failures in recent regeneration jobs.</td></tr>"#.to_string()
+    } else {
+        failures.join("\n                ")
+    };
+
+    let mut pager = Vec::new();
+    if skip > 0 {
+        let previous = skip.saturating_sub(limit as u64);
+        pager.push(format!(r#"<a href="/admin?limit={}&amp;skip={}">&larr; Previous</a>"#, limit, previous));
+    }
+    if qr_codes.len() as i64 == limit {
+        pager.push(format!(r#"<a href="/admin?limit={}&amp;skip={}">Next &rarr;</a>"#, limit, skip + limit as u64));
+    }
+
+    let notice = notice
+        .map(|message| format!(r#"<p class="notice">{}</p>"#, escape_html(message)))
+        .unwrap_or_default();
+
+    let content = render_template(DASHBOARD_TEMPLATE, &[
+        ("notice", &notice),
+        ("qr_count", &qr_codes.len().to_string()),
+        ("active_count", &qr_codes.iter().filter(|qr| qr.is_active).count().to_string()),
+        ("scan_total", &qr_codes.iter().map(|qr| qr.scan_count).sum::<i64>().to_string()),
+        ("rows", &rows),
+        ("pager", &pager.join("")),
+        ("failures", &failures),
+    ]);
+
+    render_layout("Dashboard", &content)
+}
+
+fn render_qr_row(qr_code: &QrCodeMetadata, live_name: Option<&String>) -> String {
+    let property_id = escape_html(&qr_code.property_id);
+    let deactivate = if qr_code.is_active {
+        format!(
+            r#"<form method="post" action="/admin/qr/{}/deactivate"><button type="submit" class="danger">Deactivate</button></form>"#,
+            property_id
+        )
+    } else {
+        String::new()
+    };
+
+    render_template(QR_ROW_TEMPLATE, &[
+        ("row_class", if qr_code.is_active { "" } else { "inactive" }),
+        ("property_name", &escape_html(live_name.unwrap_or(&qr_code.metadata.property_name))),
+        ("property_id", &property_id),
+        ("version", &qr_code.qr_version.to_string()),
+        ("status", if qr_code.is_active { "Active" } else { "Inactive" }),
+        ("scan_count", &qr_code.scan_count.to_string()),
+        ("last_scanned", &qr_code.last_scanned
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "Never".to_string())),
+        ("generated_at", &qr_code.generated_at.format("%Y-%m-%d").to_string()),
+        ("deactivate", &deactivate),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QrGenerationError, QrMetadata};
+
+    fn qr_code(name: &str, active: bool) -> QrCodeMetadata {
+        let mut qr_code = QrCodeMetadata::new(
+            "507f1f77bcf86cd799439011".to_string(),
+            "{}".to_string(),
+            "https://cdn.daobitat.xyz/qr.png".to_string(),
+            QrMetadata {
+                property_name: name.to_string(),
+                location: "Nairobi".to_string(),
+                action: "for sale".to_string(),
+                price: 1,
+                onchain_id: None,
+                crypto_accepted: false,
+                primary_image: None,
+                is_verified: false,
+                generated_by: None,
+                generation_reason: QrGenerationReason::NewProperty,
+            },
+        );
+        qr_code.is_active = active;
+        qr_code.scan_count = 7;
+        qr_code
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let api_key = "0123456789abcdef0123";
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(api_key, &headers));
+
+        headers.insert("x-api-key", api_key.parse().unwrap());
+        assert!(is_authorized(api_key, &headers));
+
+        headers.insert("x-api-key", "wrong".parse().unwrap());
+        assert!(!is_authorized(api_key, &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, format!("Bearer {}", api_key).parse().unwrap());
+        assert!(is_authorized(api_key, &headers));
+
+        // The session cookie carries the derived token, not the raw key
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, format!("{}={}", ADMIN_COOKIE_NAME, api_key).parse().unwrap());
+        assert!(!is_authorized(api_key, &headers));
+        headers.insert(
+            header::COOKIE,
+            format!("{}={}", ADMIN_COOKIE_NAME, admin_session_token(api_key)).parse().unwrap(),
+        );
+        assert!(is_authorized(api_key, &headers));
+    }
+
+    #[test]
+    fn test_render_dashboard() {
+        let mut job = QrRegenerationJob::new("https://qr-service.daobitat.xyz".to_string(), 10, 1);
+        job.failed.push(QrGenerationError {
+            property_id: "66a000000000000000000001".to_string(),
+            error: "S3 upload failed: timeout".to_string(),
+            error_code: "S3_UPLOAD_FAILED".to_string(),
+        });
+
+        let html = render_dashboard(
+            &[qr_code("<b>Villa</b>", true), qr_code("Old Flat", false)],
+            &HashMap::new(),
+            &[job],
+            Some("Regenerated QR code for 507f1f77bcf86cd799439011"),
+            50,
+            0,
+        );
+
+        assert!(html.contains("&lt;b&gt;Villa&lt;/b&gt;"));
+        assert!(!html.contains("<b>Villa</b>"));
+        assert_eq!(html.matches("/deactivate\"").count(), 1);
+        assert!(html.contains("<strong>14</strong>Scans"));
+        assert!(html.contains("S3_UPLOAD_FAILED: S3 upload failed: timeout"));
+        assert!(html.contains("Regenerated QR code for"));
+        assert!(!html.contains("{{"));
+    }
+
+    #[test]
+    fn test_render_qr_row_prefers_live_name() {
+        let qr_code = qr_code("Old Flat", true);
+
+        assert!(render_qr_row(&qr_code, Some(&"Garden Villa".to_string())).contains("Garden Villa"));
+        assert!(render_qr_row(&qr_code, None).contains("Old Flat"));
+
+        let names = HashMap::from([(qr_code.listing_id().unwrap(), "Garden Villa".to_string())]);
+        assert!(render_dashboard(std::slice::from_ref(&qr_code), &names, &[], None, 50, 0).contains("Garden Villa"));
+        assert!(render_dashboard(&[qr_code], &HashMap::new(), &[], None, 50, 0).contains("Old Flat"));
+    }
+
+    #[test]
+    fn test_notice_message_ignores_unknown_codes() {
+        assert_eq!(
+            notice_message(Some("deactivated"), Some("abc")).as_deref(),
+            Some("Deactivated QR code for abc")
+        );
+        assert_eq!(notice_message(Some("<script>"), Some("abc")), None);
+        assert_eq!(notice_message(None, None), None);
+    }
+}
@@ -0,0 +1,757 @@
+// src/handlers/analytics_handler.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use tracing::error;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{
+    AnalyticsComparison, CampaignStats, FunnelStats, PropertyAnalyticsSnapshot, PublicAreaStats, QrVersionStats,
+    DailyScanCount, ExperimentReport, HourlyScanDistribution, MonthlyScanCount, PropertyPerformance, ScanHeatmap,
+};
+use crate::services::{AnalyticsService, ExperimentService, PrivacyPolicy};
+
+// Application state for analytics handlers
+#[derive(Clone)]
+pub struct AnalyticsAppState {
+    pub analytics_service: AnalyticsService,
+    pub experiment_service: ExperimentService,
+    pub public_privacy: Option<PrivacyPolicy>, // Noise for the public stats; None publishes exact counts
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalyticsHistoryQuery {
+    /// Day to look up, YYYY-MM-DD (UTC)
+    pub date: String,
+}
+
+// Look-back window for campaign breakdowns
+const DEFAULT_CAMPAIGN_DAYS: i64 = 30;
+const MAX_CAMPAIGN_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CampaignBreakdownQuery {
+    /// Days to look back, 1-365 (default 30)
+    pub days: Option<i64>,
+}
+
+// Heatmap grid: decimal places kept from scan coordinates. 1 is roughly 11 km cells; more
+// than 2 (about 1 km) would start to pick out individual scanners
+const DEFAULT_HEATMAP_PRECISION: u32 = 1;
+const MAX_HEATMAP_PRECISION: u32 = 2;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScanHeatmapQuery {
+    /// Days to look back, 1-365 (default 30)
+    pub days: Option<i64>,
+    /// Decimal places to round coordinates to, 0-2 (default 1)
+    pub precision: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HourlyDistributionQuery {
+    /// Days to look back, 1-365 (default 30)
+    pub days: Option<i64>,
+    /// IANA timezone or UTC offset the hours are counted in, e.g. Africa/Nairobi or +03:00 (default UTC)
+    pub tz: Option<String>,
+}
+
+// Only the shape is checked here; the database rejects names it doesn't know
+fn is_plausible_timezone(tz: &str) -> bool {
+    !tz.is_empty()
+        && tz.len() <= 64
+        && tz.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+' | ':'))
+}
+
+// Monthly trends: months back, including the current one
+const DEFAULT_TREND_MONTHS: u32 = 12;
+const MAX_TREND_MONTHS: u32 = 60;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MonthlyTrendsQuery {
+    /// Months to cover, including this one, 1-60 (default 12)
+    pub months: Option<u32>,
+}
+
+// Top performers per request
+const DEFAULT_TOP_PROPERTIES: i64 = 10;
+const MAX_TOP_PROPERTIES: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TopPropertiesQuery {
+    /// Days to look back, 1-365 (default 30)
+    pub days: Option<i64>,
+    /// Properties to return, 1-100 (default 10)
+    pub limit: Option<i64>,
+}
+
+// Comparison limits: tags per request and days per window
+const MAX_COMPARE_TAGS: usize = 10;
+const DEFAULT_COMPARE_DAYS: i64 = 30;
+const MAX_COMPARE_DAYS: i64 = 366;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnalyticsCompareQuery {
+    /// Comma-separated tags, matched against UTM campaign, source or medium (up to 10)
+    pub tags: Option<String>,
+    /// First of two properties to compare, instead of tags
+    pub property_a: Option<String>,
+    /// Second property to compare against `property_a`
+    pub property_b: Option<String>,
+    /// First day, YYYY-MM-DD (UTC); defaults to `days` before `to`
+    pub from: Option<String>,
+    /// Last day, inclusive, YYYY-MM-DD (UTC); defaults to today
+    pub to: Option<String>,
+    /// Days in the window when `from` isn't given (default 30)
+    pub days: Option<i64>,
+}
+
+// What a comparison is between
+#[derive(Debug, PartialEq)]
+enum CompareSubjects {
+    Tags(Vec<String>),
+    Properties(Vec<String>),
+}
+
+/// Work out whether tags or a pair of properties are being compared
+fn parse_compare_subjects(query: &AnalyticsCompareQuery) -> Result<CompareSubjects, String> {
+    match (&query.tags, &query.property_a, &query.property_b) {
+        (Some(tags), None, None) => parse_compare_tags(tags).map(CompareSubjects::Tags),
+        (None, Some(a), Some(b)) => {
+            let (a, b) = (a.trim(), b.trim());
+            if a.is_empty() || b.is_empty() {
+                Err("Both property_a and property_b are required".to_string())
+            } else if a == b {
+                Err("property_a and property_b must be different properties".to_string())
+            } else {
+                Ok(CompareSubjects::Properties(vec![a.to_string(), b.to_string()]))
+            }
+        }
+        (None, None, None) => Err("Give either tags or property_a and property_b".to_string()),
+        (Some(_), _, _) => Err("Compare either tags or properties, not both".to_string()),
+        _ => Err("Both property_a and property_b are required".to_string()),
+    }
+}
+
+/// Split and de-duplicate the tag list, keeping the order given
+fn parse_compare_tags(tags: &str) -> Result<Vec<String>, String> {
+    let mut parsed: Vec<String> = Vec::new();
+    for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !parsed.iter().any(|existing| existing == tag) {
+            parsed.push(tag.to_string());
+        }
+    }
+
+    match parsed.len() {
+        0 => Err("At least one tag is required".to_string()),
+        n if n > MAX_COMPARE_TAGS => Err(format!("At most {} tags can be compared at once", MAX_COMPARE_TAGS)),
+        _ => Ok(parsed),
+    }
+}
+
+/// Resolve the comparison window, defaulting to the `days` (or 30) days ending today
+fn parse_compare_window(
+    from: Option<&str>,
+    to: Option<&str>,
+    days: Option<i64>,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let parse = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", date));
+
+    let days = days.unwrap_or(DEFAULT_COMPARE_DAYS);
+    if days < 1 {
+        return Err("'days' must be at least 1".to_string());
+    }
+
+    let to = to.map(parse).transpose()?.unwrap_or(today);
+    let from = from.map(parse).transpose()?.unwrap_or(to - chrono::Duration::days(days - 1));
+
+    if from > to {
+        return Err(format!("'from' ({}) is after 'to' ({})", from, to));
+    }
+    if (to - from).num_days() >= MAX_COMPARE_DAYS {
+        return Err(format!("The window can span at most {} days", MAX_COMPARE_DAYS));
+    }
+    Ok((from, to))
+}
+
+/// Parse a history date, rejecting days that haven't happened yet
+fn parse_history_date(date: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", date))?;
+
+    if date > today {
+        return Err(format!("Date {} is in the future", date));
+    }
+    Ok(date)
+}
+
+/// Get a property's cumulative analytics as they stood at the end of a past day
+/// GET /analytics/properties/{property_id}/history?date=2025-07-01
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/history",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        AnalyticsHistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Latest snapshot on or before the date", body = SuccessResponse<PropertyAnalyticsSnapshot>),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 404, description = "No snapshot on or before the date", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_analytics_history(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<AnalyticsHistoryQuery>,
+) -> Result<ResponseJson<SuccessResponse<PropertyAnalyticsSnapshot>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let date = parse_history_date(&query.date, Utc::now().date_naive())
+        .map_err(|message| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("invalid_date", &message))))?;
+
+    match state.analytics_service.get_property_analytics_history(&property_id, date).await {
+        Ok(Some(snapshot)) => Ok(Json(SuccessResponse::new(snapshot))),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                "snapshot_not_found",
+                &format!("No analytics snapshot for property {} on or before {}", property_id, date),
+            )),
+        )),
+        Err(e) => {
+            error!("Failed to get analytics history for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("analytics_history_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Scans by UTM campaign, source and medium across all properties
+/// GET /analytics/campaigns?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/campaigns",
+    tag = "analytics",
+    params(CampaignBreakdownQuery),
+    responses(
+        (status = 200, description = "Campaign breakdown, most scans first", body = SuccessResponse<Vec<CampaignStats>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_campaign_breakdown(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<CampaignStats>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    campaign_breakdown(&state, None, query).await
+}
+
+/// Scans of one property by UTM campaign, source and medium
+/// GET /analytics/properties/{property_id}/campaigns?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/campaigns",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        CampaignBreakdownQuery,
+    ),
+    responses(
+        (status = 200, description = "Campaign breakdown, most scans first", body = SuccessResponse<Vec<CampaignStats>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_campaign_breakdown(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<CampaignStats>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    campaign_breakdown(&state, Some(&property_id), query).await
+}
+
+async fn campaign_breakdown(
+    state: &AnalyticsAppState,
+    property_id: Option<&str>,
+    query: CampaignBreakdownQuery,
+) -> Result<ResponseJson<SuccessResponse<Vec<CampaignStats>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+
+    match state.analytics_service.get_campaign_breakdown(property_id, days).await {
+        Ok(campaigns) => Ok(Json(SuccessResponse::new(campaigns))),
+        Err(e) => {
+            error!("Failed to get campaign breakdown: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("campaign_breakdown_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// When scans happen across all properties, by hour of day and day of week
+/// GET /analytics/hourly?days=30&tz=Africa/Nairobi
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/hourly",
+    tag = "analytics",
+    params(HourlyDistributionQuery),
+    responses(
+        (status = 200, description = "Scan counts by hour and weekday", body = SuccessResponse<HourlyScanDistribution>),
+        (status = 400, description = "Malformed timezone", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_hourly_distribution(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Query(query): Query<HourlyDistributionQuery>,
+) -> Result<ResponseJson<SuccessResponse<HourlyScanDistribution>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    hourly_distribution(&state, None, query).await
+}
+
+/// When one property's signs get scanned, by hour of day and day of week
+/// GET /analytics/properties/{property_id}/hourly?days=30&tz=Africa/Nairobi
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/hourly",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        HourlyDistributionQuery,
+    ),
+    responses(
+        (status = 200, description = "Scan counts by hour and weekday", body = SuccessResponse<HourlyScanDistribution>),
+        (status = 400, description = "Malformed timezone", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_hourly_distribution(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<HourlyDistributionQuery>,
+) -> Result<ResponseJson<SuccessResponse<HourlyScanDistribution>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    hourly_distribution(&state, Some(&property_id), query).await
+}
+
+async fn hourly_distribution(
+    state: &AnalyticsAppState,
+    property_id: Option<&str>,
+    query: HourlyDistributionQuery,
+) -> Result<ResponseJson<SuccessResponse<HourlyScanDistribution>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+    let timezone = query.tz.unwrap_or_else(|| "UTC".to_string());
+    if !is_plausible_timezone(&timezone) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("invalid_timezone", &format!("Not a timezone: {}", timezone))),
+        ));
+    }
+
+    match state.analytics_service.get_hourly_distribution(property_id, days, &timezone).await {
+        Ok(distribution) => Ok(Json(SuccessResponse::new(distribution))),
+        Err(e) => {
+            error!("Failed to get hourly scan distribution: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("hourly_distribution_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Human scans of one property per day; ranges over 30 days are served from daily rollups
+/// GET /analytics/properties/{property_id}/trends?days=90
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/trends",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        CampaignBreakdownQuery,
+    ),
+    responses(
+        (status = 200, description = "Scans per day with any, oldest first", body = SuccessResponse<Vec<DailyScanCount>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_scan_trends(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<DailyScanCount>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+
+    match state.analytics_service.get_property_scan_trends(&property_id, days).await {
+        Ok(trends) => Ok(Json(SuccessResponse::new(trends))),
+        Err(e) => {
+            error!("Failed to get scan trends for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("scan_trends_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Human scans and unique visitors of one property per calendar month, from monthly rollups
+/// GET /analytics/properties/{property_id}/trends/monthly?months=12
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/trends/monthly",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        MonthlyTrendsQuery,
+    ),
+    responses(
+        (status = 200, description = "Scans per month with any, oldest first", body = SuccessResponse<Vec<MonthlyScanCount>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_monthly_trends(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<MonthlyTrendsQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<MonthlyScanCount>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let months = query.months.unwrap_or(DEFAULT_TREND_MONTHS).clamp(1, MAX_TREND_MONTHS);
+
+    match state.analytics_service.get_property_monthly_trends(&property_id, months).await {
+        Ok(trends) => Ok(Json(SuccessResponse::new(trends))),
+        Err(e) => {
+            error!("Failed to get monthly scan trends for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("scan_trends_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Most scanned properties; ranges over 30 days are served from daily rollups
+/// GET /analytics/top-properties?days=90&limit=10
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/top-properties",
+    tag = "analytics",
+    params(TopPropertiesQuery),
+    responses(
+        (status = 200, description = "Properties by successful scans, most first", body = SuccessResponse<Vec<PropertyPerformance>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_top_properties(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Query(query): Query<TopPropertiesQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<PropertyPerformance>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+    let limit = query.limit.unwrap_or(DEFAULT_TOP_PROPERTIES).clamp(1, MAX_TOP_PROPERTIES);
+
+    match state.analytics_service.get_top_performing_properties(limit, days).await {
+        Ok(properties) => Ok(Json(SuccessResponse::new(properties))),
+        Err(e) => {
+            error!("Failed to get top properties: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("top_properties_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Scans of one property by the QR version printed in the scanned code, so owners can tell when signage needs reprinting
+/// GET /analytics/properties/{property_id}/qr-versions?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/qr-versions",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        CampaignBreakdownQuery,
+    ),
+    responses(
+        (status = 200, description = "Scans per QR version, newest version first", body = SuccessResponse<Vec<QrVersionStats>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_qr_version_breakdown(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<QrVersionStats>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+
+    match state.analytics_service.get_qr_version_breakdown(&property_id, days).await {
+        Ok(versions) => Ok(Json(SuccessResponse::new(versions))),
+        Err(e) => {
+            error!("Failed to get QR version breakdown for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("qr_version_breakdown_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Where one property's scans came from, as lat/lng cells for a heatmap
+/// GET /analytics/properties/{property_id}/heatmap?days=30&precision=1
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/heatmap",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ScanHeatmapQuery,
+    ),
+    responses(
+        (status = 200, description = "Scan counts per grid cell, busiest first; scans without coordinates are left out", body = SuccessResponse<ScanHeatmap>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_heatmap(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<ScanHeatmapQuery>,
+) -> Result<ResponseJson<SuccessResponse<ScanHeatmap>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+    let precision = query.precision.unwrap_or(DEFAULT_HEATMAP_PRECISION).min(MAX_HEATMAP_PRECISION);
+
+    match state.analytics_service.get_scan_heatmap(&property_id, days, precision).await {
+        Ok(heatmap) => Ok(Json(SuccessResponse::new(heatmap))),
+        Err(e) => {
+            error!("Failed to get scan heatmap for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("scan_heatmap_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Landing page funnel for one property: page views, and whether visitors left by the countdown or by a button
+/// GET /analytics/properties/{property_id}/funnel?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/funnel",
+    tag = "analytics",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        CampaignBreakdownQuery,
+    ),
+    responses(
+        (status = 200, description = "Scans that reached each funnel stage", body = SuccessResponse<FunnelStats>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_funnel_stats(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<FunnelStats>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+
+    match state.analytics_service.get_funnel_stats(&property_id, days).await {
+        Ok(stats) => Ok(Json(SuccessResponse::new(stats))),
+        Err(e) => {
+            error!("Failed to get funnel stats for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("funnel_stats_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Landing page experiments for one property, with scans and conversions per variant
+/// GET /analytics/properties/{property_id}/experiments
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/properties/{property_id}/experiments",
+    tag = "analytics",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "The property's experiments, newest first", body = SuccessResponse<Vec<ExperimentReport>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_experiment_reports(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<Vec<ExperimentReport>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.experiment_service.reports(&property_id).await {
+        Ok(reports) => Ok(Json(SuccessResponse::new(reports))),
+        Err(e) => {
+            error!("Failed to get experiment reports for property {}: {}", property_id, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("experiment_reports_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Compare scan totals, unique visitors and conversion rates for several tags side by side, or
+/// totals, devices and daily trends for two properties
+/// GET /analytics/compare?tags=billboard,flyer&from=2025-07-01&to=2025-07-31
+/// GET /analytics/compare?property_a=..&property_b=..&days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/compare",
+    tag = "analytics",
+    params(AnalyticsCompareQuery),
+    responses(
+        (status = 200, description = "Totals per tag or property, in the order requested", body = SuccessResponse<AnalyticsComparison>),
+        (status = 400, description = "Invalid tags, properties or dates", body = ErrorResponse),
+        (status = 403, description = "Agents can only compare their own properties", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn compare_analytics(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Query(query): Query<AnalyticsCompareQuery>,
+) -> Result<ResponseJson<SuccessResponse<AnalyticsComparison>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let invalid = |message: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("invalid_comparison", &message)));
+    let subjects = parse_compare_subjects(&query).map_err(invalid)?;
+    let (from, to) = parse_compare_window(query.from.as_deref(), query.to.as_deref(), query.days, Utc::now().date_naive())
+        .map_err(invalid)?;
+
+    let comparison = match &subjects {
+        CompareSubjects::Tags(tags) => state.analytics_service.compare_tags(tags, from, to).await
+            .map(|tags| AnalyticsComparison { from, to, tags, properties: Vec::new() }),
+        CompareSubjects::Properties(property_ids) => state.analytics_service.compare_properties(property_ids, from, to).await
+            .map(|properties| AnalyticsComparison { from, to, tags: Vec::new(), properties }),
+    };
+
+    match comparison {
+        Ok(comparison) => Ok(Json(SuccessResponse::new(comparison))),
+        Err(e) => {
+            error!("Failed to compare analytics for {:?}: {}", subjects, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("analytics_comparison_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+/// Scans per neighborhood across all properties, noised for publication when the deployment enables it
+/// GET /stats/areas?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/stats/areas",
+    tag = "analytics",
+    params(CampaignBreakdownQuery),
+    responses(
+        (status = 200, description = "Scans and unique visitors per neighborhood, most scanned first", body = SuccessResponse<PublicAreaStats>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_public_area_stats(
+    State(state): State<Arc<AnalyticsAppState>>,
+    Query(query): Query<CampaignBreakdownQuery>,
+) -> Result<ResponseJson<SuccessResponse<PublicAreaStats>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_CAMPAIGN_DAYS).clamp(1, MAX_CAMPAIGN_DAYS);
+
+    let mut areas = state.analytics_service.get_area_distribution(days).await
+        .map_err(|e| {
+            error!("Failed to get public area stats: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new("area_stats_failed", &e.to_string())))
+        })?;
+
+    if let Some(policy) = state.public_privacy {
+        areas = policy.apply(areas, &mut rand::rng());
+        // Re-rank by the noisy counts, so the order doesn't give the true ones away
+        areas.sort_by_key(|area| std::cmp::Reverse(area.scans));
+    }
+
+    Ok(Json(SuccessResponse::new(PublicAreaStats {
+        days,
+        areas,
+        privacy: state.public_privacy.map(|policy| policy.notice()),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_history_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 7, 15).unwrap();
+
+        assert_eq!(parse_history_date("2025-07-01", today), Ok(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()));
+        assert_eq!(parse_history_date("2025-07-15", today), Ok(today));
+        assert!(parse_history_date("2025-07-16", today).is_err());
+        assert!(parse_history_date("July 1", today).is_err());
+        assert!(parse_history_date("2025-02-30", today).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare_tags() {
+        assert_eq!(
+            parse_compare_tags(" billboard,flyer,,billboard "),
+            Ok(vec!["billboard".to_string(), "flyer".to_string()])
+        );
+        assert!(parse_compare_tags(" , ").is_err());
+
+        let too_many: Vec<String> = (0..11).map(|i| format!("tag{}", i)).collect();
+        assert!(parse_compare_tags(&too_many.join(",")).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare_window() {
+        let today = NaiveDate::from_ymd_opt(2025, 7, 31).unwrap();
+        let date = |d: u32| NaiveDate::from_ymd_opt(2025, 7, d).unwrap();
+
+        assert_eq!(parse_compare_window(None, None, None, today), Ok((date(2), today)));
+        assert_eq!(parse_compare_window(None, None, Some(7), today), Ok((date(25), today)));
+        assert_eq!(parse_compare_window(Some("2025-07-01"), Some("2025-07-15"), None, today), Ok((date(1), date(15))));
+        assert!(parse_compare_window(Some("2025-07-16"), Some("2025-07-15"), None, today).is_err());
+        assert!(parse_compare_window(Some("2024-01-01"), None, None, today).is_err());
+        assert!(parse_compare_window(Some("yesterday"), None, None, today).is_err());
+        assert!(parse_compare_window(None, None, Some(0), today).is_err());
+        assert!(parse_compare_window(None, None, Some(400), today).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare_subjects() {
+        let query = |tags: Option<&str>, a: Option<&str>, b: Option<&str>| AnalyticsCompareQuery {
+            tags: tags.map(str::to_string),
+            property_a: a.map(str::to_string),
+            property_b: b.map(str::to_string),
+            from: None,
+            to: None,
+            days: None,
+        };
+
+        assert_eq!(
+            parse_compare_subjects(&query(Some("billboard"), None, None)),
+            Ok(CompareSubjects::Tags(vec!["billboard".to_string()]))
+        );
+        assert_eq!(
+            parse_compare_subjects(&query(None, Some("p1"), Some("p2"))),
+            Ok(CompareSubjects::Properties(vec!["p1".to_string(), "p2".to_string()]))
+        );
+        assert!(parse_compare_subjects(&query(None, Some("p1"), None)).is_err());
+        assert!(parse_compare_subjects(&query(None, Some("p1"), Some("p1"))).is_err());
+        assert!(parse_compare_subjects(&query(Some("billboard"), Some("p1"), Some("p2"))).is_err());
+        assert!(parse_compare_subjects(&query(None, None, None)).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+// src/handlers/audit_handler.rs
+
+use axum::{
+    extract::{Extension, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+    response::Json as ResponseJson,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use tracing::error;
+
+use crate::handlers::{is_authorized, ErrorResponse, SuccessResponse};
+use crate::models::{ActorKind, AuditAction, AuditActor, AuditFilter, AuditLogPage, Principal, Role};
+use crate::services::AuditService;
+
+// Caller-supplied user ID recorded as the actor of QR changes
+pub const ACTOR_USER_HEADER: &str = "x-user-id";
+
+const DEFAULT_AUDIT_LIMIT: i64 = 50;
+const MAX_AUDIT_LIMIT: i64 = 200;
+
+// Application state for audit log handlers
+#[derive(Clone)]
+pub struct AuditAppState {
+    pub audit_service: AuditService,
+    pub admin_api_key: Option<String>, // Required for the audit log while role checks are off; unset disables it
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub property_id: Option<String>,
+    pub action: Option<AuditAction>,
+    pub actor_kind: Option<ActorKind>,
+    pub actor_id: Option<String>,
+    pub from: Option<DateTime<Utc>>, // Inclusive
+    pub to: Option<DateTime<Utc>>,   // Exclusive
+    pub limit: Option<i64>,          // Default 50, max 200
+    pub skip: Option<u64>,
+}
+
+/// Who is making a request to the public QR API, for the audit log. A caller authenticated
+/// by role is recorded as such; otherwise the admin API key makes an admin, and the user ID
+/// header, if any, is taken at the caller's word.
+pub(crate) fn request_actor(admin_api_key: Option<&str>, principal: Option<&Principal>, headers: &HeaderMap) -> AuditActor {
+    if let Some(principal) = principal {
+        return match (principal.role, principal.user_id.clone()) {
+            (Role::Admin, user_id) => AuditActor::admin(user_id),
+            (_, Some(user_id)) => AuditActor::user(user_id),
+            (_, None) => AuditActor::anonymous(),
+        };
+    }
+
+    let user_id = headers.get(ACTOR_USER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+
+    if admin_api_key.is_some_and(|api_key| is_authorized(api_key, headers)) {
+        return AuditActor::admin(user_id);
+    }
+    match user_id {
+        Some(user_id) => AuditActor::user(user_id),
+        None => AuditActor::anonymous(),
+    }
+}
+
+/// List audit log entries, newest first
+/// GET /audit
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    tag = "audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit log entries", body = SuccessResponse<AuditLogPage>),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_audit_log(
+    State(state): State<Arc<AuditAppState>>,
+    principal: Option<Extension<Principal>>,
+    Query(query): Query<AuditQuery>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<SuccessResponse<AuditLogPage>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    // With role checks off there's no caller to go by, so the admin API key is still required
+    let is_admin = match principal {
+        Some(Extension(principal)) => principal.is_admin(),
+        None => state.admin_api_key.as_deref().is_some_and(|api_key| is_authorized(api_key, &headers)),
+    };
+    if !is_admin {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new("admin_required", "The audit log requires the admin API key")),
+        ));
+    }
+
+    let filter = AuditFilter {
+        property_id: query.property_id,
+        action: query.action,
+        actor_kind: query.actor_kind,
+        actor_id: query.actor_id,
+        from: query.from,
+        to: query.to,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, MAX_AUDIT_LIMIT);
+    let skip = query.skip.unwrap_or(0);
+
+    match state.audit_service.list(&filter, limit, skip).await {
+        Ok(page) => Ok(Json(SuccessResponse::new(page))),
+        Err(e) => {
+            error!("Failed to list audit log: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("audit_query_failed", &e.to_string())),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_request_actor() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_actor(Some("secret"), None, &headers), AuditActor::anonymous());
+
+        headers.insert(ACTOR_USER_HEADER, HeaderValue::from_static("agent-42"));
+        assert_eq!(request_actor(Some("secret"), None, &headers), AuditActor::user("agent-42".into()));
+
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        assert_eq!(request_actor(Some("secret"), None, &headers), AuditActor::admin(Some("agent-42".into())));
+        assert_eq!(request_actor(None, None, &headers), AuditActor::user("agent-42".into()));
+
+        // A role-authenticated caller is recorded by their token's subject, not the header
+        let agent = Principal { role: Role::Agent, user_id: Some("owner-1".into()) };
+        assert_eq!(request_actor(Some("secret"), Some(&agent), &headers), AuditActor::user("owner-1".into()));
+    }
+}
@@ -0,0 +1,347 @@
+// src/handlers/auth_handler.rs
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Query, RawPathParams, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::handlers::{is_authorized, ErrorResponse, ACTOR_USER_HEADER};
+use crate::models::{Principal, Role};
+use crate::services::{PropertyService, property_service::PropertyError};
+use crate::utils::JwtVerifier;
+
+// Largest JSON body read to find the property a new code is for
+const MAX_PROPERTY_BODY_BYTES: usize = 64 * 1024;
+
+// Application state for role checks on the QR management API
+#[derive(Clone)]
+pub struct AuthAppState {
+    pub verifier: Option<JwtVerifier>, // Role checks are off, as before, until a JWT secret is set
+    pub admin_api_key: Option<String>, // Still accepted, as an admin
+    pub property_service: PropertyService,
+}
+
+fn auth_error(status_code: StatusCode, error_type: &str, message: &str) -> Response {
+    (status_code, Json(ErrorResponse::new(error_type, message))).into_response()
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Who is calling: the admin API key makes an admin, otherwise a bearer JWT's role claim does.
+/// Without a verifier only the admin API key is accepted. Failures carry the error type and
+/// message for the 401.
+pub(crate) fn authenticate_caller(
+    state: &AuthAppState,
+    verifier: Option<&JwtVerifier>,
+    headers: &HeaderMap,
+) -> Result<Principal, (&'static str, String)> {
+    if state.admin_api_key.as_deref().is_some_and(|api_key| is_authorized(api_key, headers)) {
+        let user_id = headers.get(ACTOR_USER_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|user_id| user_id.trim().to_string())
+            .filter(|user_id| !user_id.is_empty());
+        return Ok(Principal { role: Role::Admin, user_id });
+    }
+
+    let Some(token) = bearer_token(headers) else {
+        return Err(("authentication_required", "A bearer token is required".to_string()));
+    };
+    let Some(verifier) = verifier else {
+        return Err(("invalid_token", "Bearer tokens aren't accepted until a JWT secret is configured".to_string()));
+    };
+    match verifier.verify(token, chrono::Utc::now().timestamp()) {
+        Ok(claims) => Ok(Principal::from_claims(claims)),
+        Err(e) => Err(("invalid_token", e.to_string())),
+    }
+}
+
+/// Middleware for the QR management API: identifies the caller and stores their
+/// Principal for the role checks below; passes everything through when roles are off.
+/// A caller already identified, by a support impersonation token, is kept as is.
+pub async fn authenticate(
+    State(state): State<Arc<AuthAppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(verifier) = &state.verifier else {
+        return next.run(request).await;
+    };
+    if request.extensions().get::<Principal>().is_some() {
+        return next.run(request).await;
+    }
+
+    match authenticate_caller(&state, Some(verifier), request.headers()) {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err((error_type, message)) => {
+            warn!("Rejected unauthenticated request on {} {}: {}", request.method(), request.uri().path(), message);
+            auth_error(StatusCode::UNAUTHORIZED, error_type, &message)
+        }
+    }
+}
+
+/// Route layer for batch, bulk and delete operations
+pub async fn require_admin(request: Request, next: Next) -> Response {
+    match request.extensions().get::<Principal>() {
+        Some(principal) if !principal.is_admin() => {
+            auth_error(StatusCode::FORBIDDEN, "admin_required", "This operation requires the admin role")
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Route layer for listings across all properties: admins and read-only staff
+pub async fn require_staff(request: Request, next: Next) -> Response {
+    match request.extensions().get::<Principal>() {
+        Some(principal) if !principal.can_view_all() => {
+            auth_error(StatusCode::FORBIDDEN, "forbidden", "Agents can only access their own properties")
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Route layer for routes on one property: agents must own it, and read-only staff may only read
+pub async fn require_property_access(
+    State(state): State<Arc<AuthAppState>>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return next.run(request).await;
+    };
+
+    let property_id = path_param(&params, "property_id");
+    if let Some(denied) = check_property_access(&state, principal, property_id, is_read(&request)).await {
+        return denied;
+    }
+    next.run(request).await
+}
+
+/// Route layer for codes created for a property named in the JSON body's `propertyId`, such
+/// as document and share offer codes: agents must own it, and read-only staff are turned away
+pub async fn require_body_property_access(
+    State(state): State<Arc<AuthAppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let is_read = is_read(&request);
+    let (parts, body) = request.into_parts();
+    let Ok(body) = to_bytes(body, MAX_PROPERTY_BODY_BYTES).await else {
+        return auth_error(StatusCode::PAYLOAD_TOO_LARGE, "body_too_large", "Request body too large");
+    };
+    // An unreadable body names no property, which only agents are refused for; the handler rejects it
+    let property_id = serde_json::from_slice::<serde_json::Value>(&body).ok()
+        .and_then(|body| body.get("propertyId")?.as_str().map(str::to_string));
+
+    if let Some(denied) = check_property_access(&state, &principal, property_id.as_deref(), is_read).await {
+        return denied;
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+/// Route layer for routes addressed as /{booking_id}: checked against the booked property
+pub async fn require_booking_access(
+    State(state): State<Arc<AuthAppState>>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return next.run(request).await;
+    };
+
+    // Only an agent's access depends on which property it is
+    let property_id = match (principal.role, path_param(&params, "booking_id")) {
+        (Role::Agent, Some(booking_id)) => match booking_property_id(&state.property_service, booking_id).await {
+            Ok(property_id) => property_id,
+            Err(e) => return auth_error(StatusCode::INTERNAL_SERVER_ERROR, "authorization_failed", &e.to_string()),
+        },
+        _ => None,
+    };
+    if let Some(denied) = check_property_access(&state, principal, property_id.as_deref(), is_read(&request)).await {
+        return denied;
+    }
+    next.run(request).await
+}
+
+/// Route layer for /analytics/compare: comparing tags spans every property and is for staff,
+/// while agents may compare properties they own
+pub async fn require_compare_access(
+    State(state): State<Arc<AuthAppState>>,
+    Query(query): Query<HashMap<String, String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return next.run(request).await;
+    };
+
+    if query.contains_key("tags") && !principal.can_view_all() {
+        return auth_error(StatusCode::FORBIDDEN, "forbidden", "Agents can only compare their own properties");
+    }
+    for key in ["property_a", "property_b"] {
+        if let Some(property_id) = query.get(key) {
+            if let Some(denied) = check_property_access(&state, principal, Some(property_id.trim()), true).await {
+                return denied;
+            }
+        }
+    }
+    next.run(request).await
+}
+
+/// Route layer for routes on one owner's listings: agents may only reach their own,
+/// and read-only staff may only read
+pub async fn require_owner_access(params: RawPathParams, request: Request, next: Next) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return next.run(request).await;
+    };
+
+    if let Some(denied) = check_owner_access(principal, path_param(&params, "owner_id"), is_read(&request)) {
+        return denied;
+    }
+    next.run(request).await
+}
+
+/// Route layer for routes addressed as /{scope}/{scope_id}, on either an owner or a property
+pub async fn require_scope_access(
+    State(state): State<Arc<AuthAppState>>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(principal) = request.extensions().get::<Principal>() else {
+        return next.run(request).await;
+    };
+
+    let scope_id = path_param(&params, "scope_id");
+    let denied = match path_param(&params, "scope") {
+        Some("owner") => check_owner_access(principal, scope_id, is_read(&request)),
+        _ => check_property_access(&state, principal, scope_id, is_read(&request)).await,
+    };
+    if let Some(denied) = denied {
+        return denied;
+    }
+    next.run(request).await
+}
+
+fn path_param<'a>(params: &'a RawPathParams, name: &str) -> Option<&'a str> {
+    params.iter().find(|(key, _)| *key == name).map(|(_, value)| value)
+}
+
+fn is_read(request: &Request) -> bool {
+    matches!(*request.method(), Method::GET | Method::HEAD)
+}
+
+/// The response turning the caller away from a property, if they may not reach it
+pub(crate) async fn check_property_access(
+    state: &AuthAppState,
+    principal: &Principal,
+    property_id: Option<&str>,
+    is_read: bool,
+) -> Option<Response> {
+    match principal.role {
+        Role::Admin => None,
+        Role::Readonly if is_read => None,
+        Role::Readonly => {
+            Some(auth_error(StatusCode::FORBIDDEN, "read_only", "The read-only role can't change QR codes"))
+        }
+        Role::Agent => {
+            let (Some(property_id), Some(user_id)) = (property_id, principal.user_id.as_deref()) else {
+                return Some(auth_error(StatusCode::FORBIDDEN, "forbidden", "Agents can only access their own properties"));
+            };
+
+            match owns_property(&state.property_service, user_id, property_id).await {
+                Ok(true) => None,
+                Ok(false) => {
+                    warn!("Agent {} denied access to property {}", user_id, property_id);
+                    Some(auth_error(StatusCode::FORBIDDEN, "not_property_owner", "Agents can only access their own properties"))
+                }
+                Err(e) => {
+                    Some(auth_error(StatusCode::INTERNAL_SERVER_ERROR, "authorization_failed", &e.to_string()))
+                }
+            }
+        }
+    }
+}
+
+/// The response turning the caller away from an owner's settings, if they may not reach them
+fn check_owner_access(principal: &Principal, owner_id: Option<&str>, is_read: bool) -> Option<Response> {
+    if principal.role == Role::Readonly && !is_read {
+        return Some(auth_error(StatusCode::FORBIDDEN, "read_only", "The read-only role can't change owner settings"));
+    }
+    if !owner_id.is_some_and(|owner_id| principal.can_view_owner(owner_id)) {
+        return Some(auth_error(StatusCode::FORBIDDEN, "not_owner", "Agents can only access their own listings"));
+    }
+    None
+}
+
+async fn booking_property_id(property_service: &PropertyService, booking_id: &str) -> Result<Option<String>, PropertyError> {
+    let Ok(booking_id) = ObjectId::parse_str(booking_id) else {
+        return Ok(None);
+    };
+    match property_service.get_property_by_booking(&booking_id).await {
+        Ok(property) => Ok(Some(property.id.to_hex())),
+        Err(PropertyError::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn owns_property(property_service: &PropertyService, owner_id: &str, property_id: &str) -> Result<bool, PropertyError> {
+    match property_service.get_properties_by_owner(owner_id).await {
+        Ok(properties) => Ok(properties.iter().any(|property| property.id.to_hex() == property_id)),
+        // A subject that isn't an owner ID owns nothing
+        Err(PropertyError::InvalidId) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer abc.def.ghi"));
+        assert_eq!(bearer_token(&headers), Some("abc.def.ghi"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic dXNlcjpwYXNz"));
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[test]
+    fn test_principal_roles() {
+        let agent = Principal { role: Role::Agent, user_id: Some("u1".into()) };
+        let readonly = Principal { role: Role::Readonly, user_id: Some("u2".into()) };
+        assert!(!agent.is_admin() && !agent.can_view_all());
+        assert!(!readonly.is_admin() && readonly.can_view_all());
+        assert!(Principal { role: Role::Admin, user_id: None }.is_admin());
+
+        assert!(agent.can_view_owner("u1") && !agent.can_view_owner("u2"));
+        assert!(readonly.can_view_owner("u1"));
+    }
+}
@@ -0,0 +1,193 @@
+// src/handlers/auto_redirect_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{AdminAppState, ErrorResponse, SuccessResponse};
+use crate::models::{AutoRedirectConfigResponse, UpsertAutoRedirectConfigRequest, GLOBAL_AUTO_REDIRECT_SCOPE};
+use crate::services::{AutoRedirectService, auto_redirect_service::AutoRedirectError};
+
+// Application state for auto-redirect config handlers
+#[derive(Clone)]
+pub struct AutoRedirectAppState {
+    pub auto_redirect_service: AutoRedirectService,
+}
+
+fn auto_redirect_error_response(e: AutoRedirectError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        AutoRedirectError::NotFound => (StatusCode::NOT_FOUND, "auto_redirect_config_not_found"),
+        AutoRedirectError::InvalidConfig(_) => (StatusCode::BAD_REQUEST, "invalid_auto_redirect_config"),
+        AutoRedirectError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "auto_redirect_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Property routes only reach property configs; the global one is admin-only
+fn require_property_id(property_id: &str) -> Result<(), (StatusCode, ResponseJson<ErrorResponse>)> {
+    match ObjectId::parse_str(property_id) {
+        Ok(_) => Ok(()),
+        Err(_) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("invalid_property_id", "Property ID must be a 24-character hex ObjectId")),
+        )),
+    }
+}
+
+/// Get where a property's dual page sends visitors on its own
+/// GET /auto-redirects/{property_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/auto-redirects/{property_id}",
+    tag = "auto-redirect",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Auto-redirect config", body = SuccessResponse<AutoRedirectConfigResponse>),
+        (status = 400, description = "Invalid property ID", body = ErrorResponse),
+        (status = 404, description = "Not found; the global config or deployment default applies", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_auto_redirect_config(
+    State(state): State<Arc<AutoRedirectAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<AutoRedirectConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    require_property_id(&property_id)?;
+
+    match state.auto_redirect_service.get_config(&property_id).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to get auto-redirect config for {}: {}", property_id, e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+/// Set a property's auto-redirect destination, or split visitors between destinations
+/// PUT /auto-redirects/{property_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/auto-redirects/{property_id}",
+    tag = "auto-redirect",
+    params(("property_id" = String, Path, description = "Property ID")),
+    request_body = UpsertAutoRedirectConfigRequest,
+    responses(
+        (status = 200, description = "Auto-redirect config saved", body = SuccessResponse<AutoRedirectConfigResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_auto_redirect_config(
+    State(state): State<Arc<AutoRedirectAppState>>,
+    Path(property_id): Path<String>,
+    Json(request): Json<UpsertAutoRedirectConfigRequest>,
+) -> Result<ResponseJson<SuccessResponse<AutoRedirectConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    require_property_id(&property_id)?;
+    info!("Updating auto-redirect config for property {}", property_id);
+
+    match state.auto_redirect_service.upsert_config(&property_id, request).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to update auto-redirect config for {}: {}", property_id, e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+/// Remove a property's auto-redirect config, so the global one applies again
+/// DELETE /auto-redirects/{property_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auto-redirects/{property_id}",
+    tag = "auto-redirect",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Auto-redirect config removed", body = SuccessResponse<serde_json::Value>),
+        (status = 400, description = "Invalid property ID", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_auto_redirect_config(
+    State(state): State<Arc<AutoRedirectAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    require_property_id(&property_id)?;
+    info!("Removing auto-redirect config for property {}", property_id);
+
+    match state.auto_redirect_service.delete_config(&property_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "propertyId": property_id
+        })))),
+        Err(e) => {
+            error!("Failed to remove auto-redirect config for {}: {}", property_id, e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+/// Get the auto-redirect config every property without its own follows
+/// GET /admin/auto-redirect
+pub async fn get_global_auto_redirect_config(
+    State(state): State<Arc<AdminAppState>>,
+) -> Result<ResponseJson<SuccessResponse<AutoRedirectConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.auto_redirect_service.get_config(GLOBAL_AUTO_REDIRECT_SCOPE).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to get global auto-redirect config: {}", e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+/// Set the global auto-redirect config; applies from the next scan, no restart needed
+/// PUT /admin/auto-redirect
+pub async fn upsert_global_auto_redirect_config(
+    State(state): State<Arc<AdminAppState>>,
+    Json(request): Json<UpsertAutoRedirectConfigRequest>,
+) -> Result<ResponseJson<SuccessResponse<AutoRedirectConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating global auto-redirect config");
+
+    match state.auto_redirect_service.upsert_config(GLOBAL_AUTO_REDIRECT_SCOPE, request).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to update global auto-redirect config: {}", e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+/// Remove the global auto-redirect config, going back to the deployment's default target
+/// DELETE /admin/auto-redirect
+pub async fn delete_global_auto_redirect_config(
+    State(state): State<Arc<AdminAppState>>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Removing global auto-redirect config");
+
+    match state.auto_redirect_service.delete_config(GLOBAL_AUTO_REDIRECT_SCOPE).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({ "deleted": true })))),
+        Err(e) => {
+            error!("Failed to remove global auto-redirect config: {}", e);
+            Err(auto_redirect_error_response(e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_property_routes_cannot_reach_global_config() {
+        assert!(require_property_id("507f1f77bcf86cd799439011").is_ok());
+        assert!(require_property_id(GLOBAL_AUTO_REDIRECT_SCOPE).is_err());
+    }
+}
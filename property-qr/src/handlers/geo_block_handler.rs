@@ -0,0 +1,127 @@
+// src/handlers/geo_block_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{GeoBlockPolicyResponse, GeoBlockScope, UpsertGeoBlockPolicyRequest};
+use crate::services::{GeoBlockService, geo_block_service::GeoBlockError};
+
+// Application state for geo-blocking policy handlers
+#[derive(Clone)]
+pub struct GeoBlockAppState {
+    pub geo_block_service: GeoBlockService,
+}
+
+fn geo_block_error_response(e: GeoBlockError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        GeoBlockError::NotFound => (StatusCode::NOT_FOUND, "geo_block_policy_not_found"),
+        GeoBlockError::InvalidPolicy(_) => (StatusCode::BAD_REQUEST, "invalid_geo_block_policy"),
+        GeoBlockError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "geo_block_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Get the geo-blocking policy for an owner or property
+/// GET /geo-blocks/{scope}/{scope_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/geo-blocks/{scope}/{scope_id}",
+    tag = "geo-blocking",
+    params(
+        ("scope" = GeoBlockScope, Path, description = "Whether the policy covers an owner or a single property"),
+        ("scope_id" = String, Path, description = "Owner ID or property ID"),
+    ),
+    responses(
+        (status = 200, description = "Geo-blocking policy", body = SuccessResponse<GeoBlockPolicyResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_geo_block_policy(
+    State(state): State<Arc<GeoBlockAppState>>,
+    Path((scope, scope_id)): Path<(GeoBlockScope, String)>,
+) -> Result<ResponseJson<SuccessResponse<GeoBlockPolicyResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.geo_block_service.get_policy(scope, &scope_id).await {
+        Ok(policy) => Ok(Json(SuccessResponse::new(policy.to_response()))),
+        Err(e) => {
+            error!("Failed to get geo-blocking policy for {:?} {}: {}", scope, scope_id, e);
+            Err(geo_block_error_response(e))
+        }
+    }
+}
+
+/// Create or replace the geo-blocking policy for an owner or property
+/// PUT /geo-blocks/{scope}/{scope_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/geo-blocks/{scope}/{scope_id}",
+    tag = "geo-blocking",
+    params(
+        ("scope" = GeoBlockScope, Path, description = "Whether the policy covers an owner or a single property"),
+        ("scope_id" = String, Path, description = "Owner ID or property ID"),
+    ),
+    request_body = UpsertGeoBlockPolicyRequest,
+    responses(
+        (status = 200, description = "Geo-blocking policy saved", body = SuccessResponse<GeoBlockPolicyResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_geo_block_policy(
+    State(state): State<Arc<GeoBlockAppState>>,
+    Path((scope, scope_id)): Path<(GeoBlockScope, String)>,
+    Json(request): Json<UpsertGeoBlockPolicyRequest>,
+) -> Result<ResponseJson<SuccessResponse<GeoBlockPolicyResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating geo-blocking policy for {:?} {}", scope, scope_id);
+
+    match state.geo_block_service.upsert_policy(scope, &scope_id, request).await {
+        Ok(policy) => Ok(Json(SuccessResponse::new(policy.to_response()))),
+        Err(e) => {
+            error!("Failed to update geo-blocking policy for {:?} {}: {}", scope, scope_id, e);
+            Err(geo_block_error_response(e))
+        }
+    }
+}
+
+/// Remove the geo-blocking policy for an owner or property
+/// DELETE /geo-blocks/{scope}/{scope_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/geo-blocks/{scope}/{scope_id}",
+    tag = "geo-blocking",
+    params(
+        ("scope" = GeoBlockScope, Path, description = "Whether the policy covers an owner or a single property"),
+        ("scope_id" = String, Path, description = "Owner ID or property ID"),
+    ),
+    responses(
+        (status = 200, description = "Geo-blocking policy removed", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_geo_block_policy(
+    State(state): State<Arc<GeoBlockAppState>>,
+    Path((scope, scope_id)): Path<(GeoBlockScope, String)>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Removing geo-blocking policy for {:?} {}", scope, scope_id);
+
+    match state.geo_block_service.delete_policy(scope, &scope_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "scope": scope,
+            "scopeId": scope_id
+        })))),
+        Err(e) => {
+            error!("Failed to remove geo-blocking policy for {:?} {}: {}", scope, scope_id, e);
+            Err(geo_block_error_response(e))
+        }
+    }
+}
@@ -0,0 +1,30 @@
+// src/handlers/graphql_handler.rs
+
+use axum::{
+    extract::State,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::graphql::QrSchema;
+
+// Application state for the GraphQL endpoint
+#[derive(Clone)]
+pub struct GraphQlAppState {
+    pub schema: QrSchema,
+}
+
+/// Run a GraphQL query over QR codes, listings and analytics
+/// POST /api/graphql
+pub async fn graphql_query(
+    State(state): State<Arc<GraphQlAppState>>,
+    Json(request): Json<async_graphql::Request>,
+) -> ResponseJson<async_graphql::Response> {
+    let response = state.schema.execute(request).await;
+    if response.is_err() {
+        warn!("GraphQL query failed: {:?}", response.errors);
+    }
+    Json(response)
+}
@@ -1,14 +1,31 @@
  // src/handlers/health.rs
 
 use axum::{
+    extract::State,
     http::StatusCode,
     Json,
     response::Json as ResponseJson,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::models::SelfTestReport;
+use crate::services::{DependencyRegistry, dependency_registry::overall_status};
+
+// Longest a single dependency probe may hold up /health/detailed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+// Application state for health probes
+#[derive(Clone, Default)]
+pub struct HealthAppState {
+    pub self_test: Option<SelfTestReport>, // None when the startup self-test is disabled
+    pub dependencies: DependencyRegistry, // Probed by /health/detailed
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub timestamp: String,
@@ -18,7 +35,7 @@ pub struct HealthResponse {
     pub environment: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ServiceHealth {
     pub status: String, // "healthy", "degraded", "unhealthy"
     pub message: Option<String>,
@@ -26,7 +43,7 @@ pub struct ServiceHealth {
     pub response_time_ms: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DetailedHealthResponse {
     pub status: String,
     pub timestamp: String,
@@ -38,7 +55,7 @@ pub struct DetailedHealthResponse {
     pub metrics: HealthMetrics,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SystemInfo {
     pub hostname: String,
     pub platform: String,
@@ -46,14 +63,14 @@ pub struct SystemInfo {
     pub memory_usage: MemoryUsage,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MemoryUsage {
     pub used_mb: u64,
     pub total_mb: u64,
     pub percentage: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HealthMetrics {
     pub total_requests: u64,
     pub successful_requests: u64,
@@ -64,6 +81,14 @@ pub struct HealthMetrics {
 }
 
 // Simple health check endpoint
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Service health", body = HealthResponse),
+    )
+)]
 pub async fn health() -> Result<ResponseJson<HealthResponse>, StatusCode> {
     let start_time = std::time::SystemTime::now();
     
@@ -107,23 +132,28 @@ pub async fn health() -> Result<ResponseJson<HealthResponse>, StatusCode> {
 }
 
 // Detailed health check with system metrics
-pub async fn health_detailed() -> Result<ResponseJson<DetailedHealthResponse>, StatusCode> {
-    let start_time = std::time::SystemTime::now();
-    
-    // Basic service checks (same as above but more detailed)
-    let mut services = HashMap::new();
-    
-    // MongoDB check with actual connection test
-    let mongodb_health = check_mongodb_health().await;
-    services.insert("mongodb".to_string(), mongodb_health);
-    
-    // S3 check with actual connectivity test
-    let s3_health = check_s3_health().await;
-    services.insert("s3".to_string(), s3_health);
-    
-    // QR generator service check
-    let qr_health = check_qr_generator_health().await;
-    services.insert("qr_generator".to_string(), qr_health);
+#[utoipa::path(
+    get,
+    path = "/health/detailed",
+    tag = "health",
+    responses(
+        (status = 200, description = "Detailed service health", body = DetailedHealthResponse),
+    )
+)]
+pub async fn health_detailed(
+    State(state): State<Arc<HealthAppState>>,
+) -> Result<ResponseJson<DetailedHealthResponse>, StatusCode> {
+    // Every registered dependency reports here, so new subsystems show up without edits
+    let checks = state.dependencies.check_all(PROBE_TIMEOUT).await;
+    let last_check = chrono::Utc::now().to_rfc3339();
+    let services: HashMap<String, ServiceHealth> = checks.iter()
+        .map(|check| (check.name.to_string(), ServiceHealth {
+            status: check.status.as_str().to_string(),
+            message: check.message.clone(),
+            last_check: last_check.clone(),
+            response_time_ms: Some(check.response_time_ms),
+        }))
+        .collect();
 
     // System information
     let system_info = SystemInfo {
@@ -143,17 +173,8 @@ pub async fn health_detailed() -> Result<ResponseJson<DetailedHealthResponse>, S
         qr_codes_scanned: 890, // Placeholder
     };
 
-    // Determine overall status based on service health
-    let overall_status = if services.values().all(|s| s.status == "healthy") {
-        "healthy"
-    } else if services.values().any(|s| s.status == "unhealthy") {
-        "unhealthy"
-    } else {
-        "degraded"
-    };
-
     let response = DetailedHealthResponse {
-        status: overall_status.to_string(),
+        status: overall_status(&checks).as_str().to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime: get_uptime_seconds(),
@@ -167,6 +188,14 @@ pub async fn health_detailed() -> Result<ResponseJson<DetailedHealthResponse>, S
 }
 
 // Liveness probe - simple "I'm alive" check
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses(
+        (status = 200, description = "Process is alive"),
+    )
+)]
 pub async fn liveness() -> Result<ResponseJson<serde_json::Value>, StatusCode> {
     Ok(Json(serde_json::json!({
         "status": "alive",
@@ -175,19 +204,38 @@ pub async fn liveness() -> Result<ResponseJson<serde_json::Value>, StatusCode> {
 }
 
 // Readiness probe - check if service is ready to handle requests
-pub async fn readiness() -> Result<ResponseJson<serde_json::Value>, StatusCode> {
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic"),
+        (status = 503, description = "Not ready"),
+    )
+)]
+pub async fn readiness(
+    State(state): State<Arc<HealthAppState>>,
+) -> Result<ResponseJson<serde_json::Value>, StatusCode> {
     // Check critical dependencies
     let mongodb_ready = check_mongodb_readiness().await;
     let s3_ready = check_s3_readiness().await;
     
-    if mongodb_ready && s3_ready {
+    // A failed startup self-test keeps the pod out of rotation until it's fixed and restarted
+    let self_test = match &state.self_test {
+        None => "disabled",
+        Some(report) if report.passed => "passed",
+        Some(_) => "failed",
+    };
+    
+    if mongodb_ready && s3_ready && self_test != "failed" {
         Ok(Json(serde_json::json!({
             "status": "ready",
             "timestamp": chrono::Utc::now().to_rfc3339(),
             "services": {
                 "mongodb": "ready",
                 "s3": "ready"
-            }
+            },
+            "selfTest": self_test
         })))
     } else {
         Err(StatusCode::SERVICE_UNAVAILABLE)
@@ -196,54 +244,6 @@ pub async fn readiness() -> Result<ResponseJson<serde_json::Value>, StatusCode>
 
 // Helper functions (these would contain actual health check logic)
 
-async fn check_mongodb_health() -> ServiceHealth {
-    let start = std::time::Instant::now();
-    
-    // TODO: Implement actual MongoDB health check
-    // Example: try to ping the database
-    
-    let elapsed = start.elapsed().as_millis() as u64;
-    
-    ServiceHealth {
-        status: "healthy".to_string(),
-        message: Some("Database connection successful".to_string()),
-        last_check: chrono::Utc::now().to_rfc3339(),
-        response_time_ms: Some(elapsed),
-    }
-}
-
-async fn check_s3_health() -> ServiceHealth {
-    let start = std::time::Instant::now();
-    
-    // TODO: Implement actual S3 health check
-    // Example: try to list bucket or get bucket location
-    
-    let elapsed = start.elapsed().as_millis() as u64;
-    
-    ServiceHealth {
-        status: "healthy".to_string(),
-        message: Some("S3 service accessible".to_string()),
-        last_check: chrono::Utc::now().to_rfc3339(),
-        response_time_ms: Some(elapsed),
-    }
-}
-
-async fn check_qr_generator_health() -> ServiceHealth {
-    let start = std::time::Instant::now();
-    
-    // TODO: Implement QR generator health check
-    // Example: try to generate a test QR code
-    
-    let elapsed = start.elapsed().as_millis() as u64;
-    
-    ServiceHealth {
-        status: "healthy".to_string(),
-        message: Some("QR generation service operational".to_string()),
-        last_check: chrono::Utc::now().to_rfc3339(),
-        response_time_ms: Some(elapsed),
-    }
-}
-
 async fn check_mongodb_readiness() -> bool {
     // TODO: Implement actual MongoDB readiness check
     // Return true if MongoDB is ready to accept connections
@@ -292,6 +292,21 @@ mod tests {
         assert!(!health_data.services.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_detailed_health_reports_registered_dependencies() {
+        use crate::services::dependency_registry::ProbeResult;
+
+        let mut dependencies = DependencyRegistry::new();
+        dependencies.register("mongodb", true, || async { ProbeResult::healthy("Ping succeeded") });
+        dependencies.register("webhooks", false, || async { ProbeResult::degraded("1 subscribers, 1 failing") });
+        let state = HealthAppState { self_test: None, dependencies };
+
+        let health_data = health_detailed(State(Arc::new(state))).await.unwrap().0;
+        assert_eq!(health_data.status, "degraded");
+        assert_eq!(health_data.services.len(), 2);
+        assert_eq!(health_data.services["webhooks"].status, "degraded");
+    }
+
     #[tokio::test]
     async fn test_liveness_endpoint() {
         let response = liveness().await;
@@ -300,7 +315,22 @@ mod tests {
 
     #[tokio::test]
     async fn test_readiness_endpoint() {
-        let response = readiness().await;
+        let response = readiness(State(Arc::new(HealthAppState::default()))).await;
         assert!(response.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_readiness_fails_after_failed_self_test() {
+        let state = HealthAppState {
+            self_test: Some(SelfTestReport {
+                passed: false,
+                steps: Vec::new(),
+                completed_at: chrono::Utc::now(),
+            }),
+            dependencies: DependencyRegistry::new(),
+        };
+
+        let response = readiness(State(Arc::new(state))).await;
+        assert_eq!(response.unwrap_err(), StatusCode::SERVICE_UNAVAILABLE);
+    }
 }
@@ -0,0 +1,160 @@
+// src/handlers/hook_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{HookEvent, HookSubscriptionResponse, SubscribeHookRequest};
+use crate::services::{HookService, hook_service::HookError};
+
+// Application state for REST hook handlers
+#[derive(Clone)]
+pub struct HookAppState {
+    pub hook_service: HookService,
+}
+
+fn hook_error_response(e: HookError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        HookError::NotFound => (StatusCode::NOT_FOUND, "hook_not_found"),
+        HookError::InvalidId => (StatusCode::BAD_REQUEST, "invalid_hook_id"),
+        HookError::InvalidTargetUrl(_) => (StatusCode::BAD_REQUEST, "invalid_target_url"),
+        HookError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "hook_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Subscribe a REST hook
+/// POST /hooks
+#[utoipa::path(
+    post,
+    path = "/api/v1/hooks",
+    tag = "hooks",
+    request_body = SubscribeHookRequest,
+    responses(
+        (status = 201, description = "Hook subscribed", body = SuccessResponse<HookSubscriptionResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn subscribe_hook(
+    State(state): State<Arc<HookAppState>>,
+    Json(request): Json<SubscribeHookRequest>,
+) -> Result<(StatusCode, ResponseJson<SuccessResponse<HookSubscriptionResponse>>), (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Subscribing {:?} hook for {}", request.event, request.target_url);
+
+    match state.hook_service.subscribe(request.target_url, request.event, request.property_id).await {
+        Ok(subscription) => Ok((StatusCode::CREATED, Json(SuccessResponse::new(subscription.to_response())))),
+        Err(e) => {
+            error!("Failed to subscribe hook: {}", e);
+            Err(hook_error_response(e))
+        }
+    }
+}
+
+/// Unsubscribe a REST hook
+/// DELETE /hooks/{hook_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/hooks/{hook_id}",
+    tag = "hooks",
+    params(
+        ("hook_id" = String, Path, description = "Hook subscription ID"),
+    ),
+    responses(
+        (status = 200, description = "Hook removed", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn unsubscribe_hook(
+    State(state): State<Arc<HookAppState>>,
+    Path(hook_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Unsubscribing hook: {}", hook_id);
+
+    match state.hook_service.unsubscribe(&hook_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "id": hook_id
+        })))),
+        Err(e) => {
+            error!("Failed to unsubscribe hook {}: {}", hook_id, e);
+            Err(hook_error_response(e))
+        }
+    }
+}
+
+/// List registered REST hooks
+/// GET /hooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/hooks",
+    tag = "hooks",
+    responses(
+        (status = 200, description = "Registered hooks", body = SuccessResponse<Vec<HookSubscriptionResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_hooks(
+    State(state): State<Arc<HookAppState>>,
+) -> Result<ResponseJson<SuccessResponse<Vec<HookSubscriptionResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.hook_service.list_subscriptions().await {
+        Ok(subscriptions) => Ok(Json(SuccessResponse::new(
+            subscriptions.iter().map(|s| s.to_response()).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to list hooks: {}", e);
+            Err(hook_error_response(e))
+        }
+    }
+}
+
+/// Recent sample payloads for an event (polling fallback / trigger setup)
+/// GET /hooks/samples/{event}
+#[utoipa::path(
+    get,
+    path = "/api/v1/hooks/samples/{event}",
+    tag = "hooks",
+    params(
+        ("event" = HookEvent, Path, description = "Hook event"),
+    ),
+    responses(
+        (status = 200, description = "Recent sample payloads", body = Vec<serde_json::Value>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_hook_samples(
+    State(state): State<Arc<HookAppState>>,
+    Path(event): Path<HookEvent>,
+) -> Result<ResponseJson<Vec<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    // Zapier and Make expect a bare JSON array here
+    match state.hook_service.sample_events(event).await {
+        Ok(samples) => Ok(Json(samples)),
+        Err(e) => {
+            error!("Failed to load hook samples: {}", e);
+            Err(hook_error_response(e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_error_status_codes() {
+        assert_eq!(hook_error_response(HookError::NotFound).0, StatusCode::NOT_FOUND);
+        assert_eq!(hook_error_response(HookError::InvalidId).0, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            hook_error_response(HookError::InvalidTargetUrl("bad".to_string())).0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
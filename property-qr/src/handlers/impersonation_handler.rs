@@ -0,0 +1,248 @@
+// src/handlers/impersonation_handler.rs
+
+use axum::{
+    extract::{OriginalUri, Path, Query, RawPathParams, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{info, warn, error};
+
+use crate::handlers::{AdminAppState, ErrorResponse, SuccessResponse};
+use crate::models::{
+    CreateImpersonationTokenRequest, ImpersonationAuditEntryResponse, ImpersonationToken,
+    ImpersonationTokenResponse, Principal, Role,
+};
+use crate::services::{ImpersonationService, PropertyService, impersonation_service::ImpersonationError};
+
+// Header support staff send the impersonation token in
+pub const IMPERSONATION_HEADER: &str = "x-impersonation-token";
+
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+const MAX_AUDIT_LIMIT: i64 = 1000;
+
+// State for the middleware guarding owner-facing routes
+#[derive(Clone)]
+pub struct ImpersonationAppState {
+    pub impersonation_service: ImpersonationService,
+    pub property_service: PropertyService, // Resolves property-scoped routes to their owner
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonationAuditQuery {
+    #[serde(rename = "ownerId")]
+    pub owner_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Whose data an owner-facing route touches, read from its path parameters
+#[derive(Debug, PartialEq)]
+enum ImpersonationTarget {
+    Owner(String),
+    Property(String),
+    Unknown,
+}
+
+fn impersonation_error_response(e: ImpersonationError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        ImpersonationError::NotFound => (StatusCode::NOT_FOUND, "impersonation_token_not_found"),
+        ImpersonationError::InvalidToken => (StatusCode::UNAUTHORIZED, "invalid_impersonation_token"),
+        ImpersonationError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_impersonation_request"),
+        ImpersonationError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "impersonation_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Issue a support token to act as an owner
+/// POST /admin/impersonation
+pub async fn issue_impersonation_token(
+    State(state): State<Arc<AdminAppState>>,
+    Json(request): Json<CreateImpersonationTokenRequest>,
+) -> Result<(StatusCode, ResponseJson<SuccessResponse<ImpersonationTokenResponse>>), (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.impersonation_service.issue(request).await {
+        Ok((token, secret)) => Ok((
+            StatusCode::CREATED,
+            Json(SuccessResponse::new(token.to_response(Some(secret)))),
+        )),
+        Err(e) => {
+            error!("Failed to issue impersonation token: {}", e);
+            Err(impersonation_error_response(e))
+        }
+    }
+}
+
+/// Revoke a support token
+/// DELETE /admin/impersonation/{token_id}
+pub async fn revoke_impersonation_token(
+    State(state): State<Arc<AdminAppState>>,
+    Path(token_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<ImpersonationTokenResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.impersonation_service.revoke(&token_id).await {
+        Ok(token) => Ok(Json(SuccessResponse::new(token.to_response(None)))),
+        Err(e) => {
+            error!("Failed to revoke impersonation token {}: {}", token_id, e);
+            Err(impersonation_error_response(e))
+        }
+    }
+}
+
+/// Requests made with support tokens, newest first
+/// GET /admin/impersonation/audit?ownerId=...&limit=100
+pub async fn get_impersonation_audit(
+    State(state): State<Arc<AdminAppState>>,
+    Query(query): Query<ImpersonationAuditQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<ImpersonationAuditEntryResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, MAX_AUDIT_LIMIT);
+
+    match state.impersonation_service.audit_log(query.owner_id.as_deref(), limit).await {
+        Ok(entries) => Ok(Json(SuccessResponse::new(
+            entries.iter().map(|entry| entry.to_response()).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to read impersonation audit log: {}", e);
+            Err(impersonation_error_response(e.into()))
+        }
+    }
+}
+
+/// Middleware for owner-facing routes: requests carrying an impersonation token must
+/// stay within the token's owner (and be reads unless writes were granted), and every
+/// one of them is audit-logged as "admin A acting as owner X". Goes outside `authenticate`,
+/// which takes the token's owner as the caller.
+pub async fn audit_impersonation(
+    State(state): State<Arc<ImpersonationAppState>>,
+    OriginalUri(uri): OriginalUri,
+    params: RawPathParams,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(secret) = request.headers()
+        .get(IMPERSONATION_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string())
+    else {
+        return next.run(request).await;
+    };
+
+    let token = match state.impersonation_service.authenticate(&secret).await {
+        Ok(token) => token,
+        Err(e) => {
+            warn!("Rejected impersonation token on {} {}: {}", request.method(), uri.path(), e);
+            return impersonation_error_response(e).into_response();
+        }
+    };
+
+    let method = request.method().clone();
+    let target = impersonation_target(params.iter().collect::<Vec<_>>().as_slice());
+    let target_owner = match target {
+        ImpersonationTarget::Owner(owner_id) => Some(owner_id),
+        ImpersonationTarget::Property(property_id) => state.property_service
+            .get_property_qr_info(&property_id)
+            .await
+            .ok()
+            .map(|property| property.owner.to_hex()),
+        ImpersonationTarget::Unknown => None,
+    };
+
+    let response = if target_owner.as_deref() != Some(token.owner_id.as_str()) {
+        forbidden("impersonation_out_of_scope", "This token only grants access to its owner's data")
+    } else if !permits(&token, &method) {
+        forbidden("impersonation_read_only", "This token is read-only")
+    } else {
+        info!("Admin {} acting as owner {}: {} {}", token.admin_id, token.owner_id, method, uri.path());
+        request.extensions_mut().insert(Principal { role: Role::Agent, user_id: Some(token.owner_id.clone()) });
+        next.run(request).await
+    };
+
+    audit(&state, &token, &method, uri.path(), response.status()).await;
+    response
+}
+
+/// Read-only tokens may only look
+fn permits(token: &ImpersonationToken, method: &Method) -> bool {
+    token.allow_writes || matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn impersonation_target(params: &[(&str, &str)]) -> ImpersonationTarget {
+    let param = |name: &str| params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string());
+
+    if let Some(owner_id) = param("owner_id") {
+        return ImpersonationTarget::Owner(owner_id);
+    }
+    if let Some(property_id) = param("property_id") {
+        return ImpersonationTarget::Property(property_id);
+    }
+
+    // Geo-blocking policies are addressed as /{scope}/{scope_id}
+    match (param("scope").as_deref(), param("scope_id")) {
+        (Some("owner"), Some(owner_id)) => ImpersonationTarget::Owner(owner_id),
+        (Some("property"), Some(property_id)) => ImpersonationTarget::Property(property_id),
+        _ => ImpersonationTarget::Unknown,
+    }
+}
+
+fn forbidden(error_type: &str, message: &str) -> Response {
+    (StatusCode::FORBIDDEN, Json(ErrorResponse::new(error_type, message))).into_response()
+}
+
+async fn audit(state: &ImpersonationAppState, token: &ImpersonationToken, method: &Method, path: &str, status: StatusCode) {
+    if let Err(e) = state.impersonation_service
+        .record(token, method.to_string(), path.to_string(), status.as_u16())
+        .await
+    {
+        error!(
+            "Failed to audit admin {} acting as owner {} ({} {}): {}",
+            token.admin_id, token.owner_id, method, path, e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_impersonation_target() {
+        assert_eq!(
+            impersonation_target(&[("owner_id", "507f1f77bcf86cd799439011")]),
+            ImpersonationTarget::Owner("507f1f77bcf86cd799439011".to_string())
+        );
+        assert_eq!(
+            impersonation_target(&[("property_id", "abc")]),
+            ImpersonationTarget::Property("abc".to_string())
+        );
+        assert_eq!(
+            impersonation_target(&[("scope", "owner"), ("scope_id", "o1")]),
+            ImpersonationTarget::Owner("o1".to_string())
+        );
+        assert_eq!(
+            impersonation_target(&[("scope", "property"), ("scope_id", "p1")]),
+            ImpersonationTarget::Property("p1".to_string())
+        );
+        assert_eq!(impersonation_target(&[("link_id", "l1")]), ImpersonationTarget::Unknown);
+    }
+
+    #[test]
+    fn test_read_only_tokens_only_permit_reads() {
+        let request = CreateImpersonationTokenRequest {
+            admin_id: "support@daobitat.xyz".to_string(),
+            owner_id: "507f1f77bcf86cd799439011".to_string(),
+            reason: "Ticket #4821".to_string(),
+            ttl_minutes: None,
+            allow_writes: None,
+        };
+        let mut token = ImpersonationToken::new(request, "hash".to_string(), Duration::minutes(5));
+
+        assert!(permits(&token, &Method::GET));
+        assert!(!permits(&token, &Method::PUT));
+        assert!(!permits(&token, &Method::DELETE));
+
+        token.allow_writes = true;
+        assert!(permits(&token, &Method::PUT));
+    }
+}
@@ -0,0 +1,285 @@
+// src/handlers/link_handler.rs
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json as ResponseJson, Redirect, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::{net::SocketAddr, sync::Arc};
+use utoipa::IntoParams;
+use tracing::{info, warn, error};
+
+use crate::handlers::{extract_visitor_cookie, ErrorResponse, SuccessResponse};
+use crate::models::{
+    CreateShortLinkRequest, RedirectType, ScanEvent, ScanSource, ShortLinkResponse,
+    UpdateShortLinkRequest, UtmParameters,
+};
+use crate::services::{AnalyticsService, LinkService, link_service::LinkError};
+
+// Application state for short link handlers
+#[derive(Clone)]
+pub struct LinkAppState {
+    pub link_service: LinkService,
+    pub analytics_service: AnalyticsService,
+    pub daobitar_base_url: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LinkListQuery {
+    pub property_id: Option<String>,
+    pub limit: Option<i64>,
+    pub skip: Option<u64>,
+}
+
+fn link_error_response(e: LinkError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        LinkError::NotFound => (StatusCode::NOT_FOUND, "link_not_found"),
+        LinkError::InvalidId => (StatusCode::BAD_REQUEST, "invalid_id"),
+        LinkError::InvalidCode(_) => (StatusCode::BAD_REQUEST, "invalid_code"),
+        LinkError::CodeTaken => (StatusCode::CONFLICT, "code_taken"),
+        LinkError::InvalidTargetUrl(_) => (StatusCode::BAD_REQUEST, "invalid_target_url"),
+        LinkError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "link_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Create a short marketing link
+/// POST /links
+#[utoipa::path(
+    post,
+    path = "/api/v1/links",
+    tag = "links",
+    request_body = CreateShortLinkRequest,
+    responses(
+        (status = 201, description = "Short link created", body = SuccessResponse<ShortLinkResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 409, description = "Conflict", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn create_link(
+    State(state): State<Arc<LinkAppState>>,
+    Json(request): Json<CreateShortLinkRequest>,
+) -> Result<(StatusCode, ResponseJson<SuccessResponse<ShortLinkResponse>>), (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Creating short link for property: {}", request.property_id);
+
+    match state.link_service.create_link(request).await {
+        Ok(link) => Ok((
+            StatusCode::CREATED,
+            Json(SuccessResponse::new(link.to_response(state.link_service.base_url()))),
+        )),
+        Err(e) => {
+            error!("Failed to create short link: {}", e);
+            Err(link_error_response(e))
+        }
+    }
+}
+
+/// Get a short link
+/// GET /links/{link_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/links/{link_id}",
+    tag = "links",
+    params(
+        ("link_id" = String, Path, description = "Short link ID"),
+    ),
+    responses(
+        (status = 200, description = "Short link", body = SuccessResponse<ShortLinkResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_link(
+    State(state): State<Arc<LinkAppState>>,
+    Path(link_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<ShortLinkResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.link_service.get_link(&link_id).await {
+        Ok(link) => Ok(Json(SuccessResponse::new(link.to_response(state.link_service.base_url())))),
+        Err(e) => Err(link_error_response(e)),
+    }
+}
+
+/// List short links
+/// GET /links?property_id=...&limit=50&skip=0
+#[utoipa::path(
+    get,
+    path = "/api/v1/links",
+    tag = "links",
+    params(
+        LinkListQuery,
+    ),
+    responses(
+        (status = 200, description = "Short links", body = SuccessResponse<Vec<ShortLinkResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_links(
+    State(state): State<Arc<LinkAppState>>,
+    Query(query): Query<LinkListQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<ShortLinkResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.link_service.list_links(query.property_id, query.limit, query.skip).await {
+        Ok(links) => Ok(Json(SuccessResponse::new(
+            links.iter().map(|l| l.to_response(state.link_service.base_url())).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to list short links: {}", e);
+            Err(link_error_response(e))
+        }
+    }
+}
+
+/// Update a short link
+/// PATCH /links/{link_id}
+#[utoipa::path(
+    patch,
+    path = "/api/v1/links/{link_id}",
+    tag = "links",
+    params(
+        ("link_id" = String, Path, description = "Short link ID"),
+    ),
+    request_body = UpdateShortLinkRequest,
+    responses(
+        (status = 200, description = "Short link updated", body = SuccessResponse<ShortLinkResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn update_link(
+    State(state): State<Arc<LinkAppState>>,
+    Path(link_id): Path<String>,
+    Json(request): Json<UpdateShortLinkRequest>,
+) -> Result<ResponseJson<SuccessResponse<ShortLinkResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating short link: {}", link_id);
+
+    match state.link_service.update_link(&link_id, request).await {
+        Ok(link) => Ok(Json(SuccessResponse::new(link.to_response(state.link_service.base_url())))),
+        Err(e) => {
+            error!("Failed to update short link {}: {}", link_id, e);
+            Err(link_error_response(e))
+        }
+    }
+}
+
+/// Delete a short link
+/// DELETE /links/{link_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/links/{link_id}",
+    tag = "links",
+    params(
+        ("link_id" = String, Path, description = "Short link ID"),
+    ),
+    responses(
+        (status = 200, description = "Short link deleted", body = SuccessResponse<serde_json::Value>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_link(
+    State(state): State<Arc<LinkAppState>>,
+    Path(link_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Deleting short link: {}", link_id);
+
+    match state.link_service.delete_link(&link_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "id": link_id
+        })))),
+        Err(e) => {
+            error!("Failed to delete short link {}: {}", link_id, e);
+            Err(link_error_response(e))
+        }
+    }
+}
+
+/// Follow a short link, recording it as a share-link scan
+/// GET /l/{code}
+#[utoipa::path(
+    get,
+    path = "/l/{code}",
+    tag = "links",
+    params(
+        ("code" = String, Path, description = "Short code"),
+    ),
+    responses(
+        (status = 307, description = "Redirect to the link destination"),
+        (status = 404, description = "Unknown short code"),
+    )
+)]
+pub async fn follow_link(
+    State(state): State<Arc<LinkAppState>>,
+    Path(code): Path<String>,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> Result<Response, StatusCode> {
+    let link = match state.link_service.resolve_code(&code).await {
+        Ok(link) => link,
+        Err(LinkError::NotFound) => {
+            warn!("Unknown short link code: {}", code);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            error!("Failed to resolve short link {}: {}", code, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let user_agent = headers.get("user-agent")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let ip_address = addr.ip().to_string();
+    let referrer = headers.get("referer")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let visitor_id = extract_visitor_cookie(&headers).unwrap_or_else(|| {
+        ScanEvent::visitor_hash(Some(&ip_address), user_agent.as_deref())
+    });
+
+    // Same analytics pipeline as QR scans, tagged as a share link
+    if let Err(e) = state.analytics_service.record_scan(
+        link.property_id.clone(),
+        1,
+        None, // Short links aren't printed codes, so they can't be outdated
+        ScanSource::ShareLink,
+        RedirectType::DaobitarOnly,
+        user_agent,
+        Some(ip_address),
+        None,
+        Some(visitor_id),
+        referrer,
+        UtmParameters::from_query(link.channel.as_deref(), Some("short_link"), link.campaign.as_deref()),
+        None,
+    ).await {
+        error!("Failed to record short link scan: {}", e);
+    }
+
+    if let Err(e) = state.link_service.record_click(&link).await {
+        warn!("Failed to count click for short link {}: {}", link.code, e);
+    }
+
+    // Temporary redirect so the destination can be changed after links go out
+    Ok(Redirect::temporary(&link.destination_url(&state.daobitar_base_url)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_error_status_codes() {
+        assert_eq!(link_error_response(LinkError::NotFound).0, StatusCode::NOT_FOUND);
+        assert_eq!(link_error_response(LinkError::CodeTaken).0, StatusCode::CONFLICT);
+        assert_eq!(
+            link_error_response(LinkError::InvalidCode("short".to_string())).0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
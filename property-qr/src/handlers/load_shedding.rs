@@ -0,0 +1,58 @@
+// src/handlers/load_shedding.rs
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::warn;
+
+use crate::handlers::ErrorResponse;
+use crate::services::LoadShedder;
+
+// Paths that keep working at every shed level: printed QR codes and short links
+// must always redirect, and failing probes would only restart a busy pod
+const ALWAYS_SERVED_PREFIXES: [&str; 3] = ["/scan/", "/l/", "/health"];
+
+// Seconds clients are asked to wait before retrying a shed request
+const SHED_RETRY_AFTER_SECS: &str = "5";
+
+/// Middleware for the whole app: once the load shedder reaches its last level,
+/// answer everything except scan redirects, short links and health checks with 503
+pub async fn shed_load(
+    State(shedder): State<LoadShedder>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_always_served(request.uri().path()) || !shedder.shed_request() {
+        return next.run(request).await;
+    }
+
+    warn!("Shedding {} {} under load", request.method(), request.uri().path());
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, SHED_RETRY_AFTER_SECS)],
+        Json(ErrorResponse::new("overloaded", "Service is under heavy load, please retry shortly")),
+    ).into_response()
+}
+
+fn is_always_served(path: &str) -> bool {
+    ALWAYS_SERVED_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_always_served() {
+        assert!(is_always_served("/scan/507f1f77bcf86cd799439011"));
+        assert!(is_always_served("/l/spring-open-house"));
+        assert!(is_always_served("/health/ready"));
+        assert!(!is_always_served("/api/v1/qr"));
+        assert!(!is_always_served("/api/scan/507f1f77bcf86cd799439011/sms"));
+        assert!(!is_always_served("/admin"));
+    }
+}
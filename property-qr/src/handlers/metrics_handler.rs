@@ -0,0 +1,92 @@
+// src/handlers/metrics_handler.rs
+
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+use std::fmt::Write;
+use std::sync::Arc;
+use tracing::error;
+
+use crate::services::{AnalyticsService, analytics_service::DeadLetterMetrics};
+use crate::services::analytics_worker::AnalyticsWorkerMetrics;
+
+// Prometheus text exposition format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+// Application state for the metrics endpoint
+#[derive(Clone)]
+pub struct MetricsAppState {
+    pub analytics_service: AnalyticsService,
+}
+
+/// Service counters in Prometheus text format, for scraping
+/// GET /metrics
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Prometheus metrics", content_type = "text/plain"),
+    )
+)]
+pub async fn metrics(State(state): State<Arc<MetricsAppState>>) -> impl IntoResponse {
+    // A failed count leaves the gauge out rather than failing the scrape
+    let pending = match state.analytics_service.count_failed_updates().await {
+        Ok(pending) => Some(pending),
+        Err(e) => {
+            error!("Failed to count queued analytics updates: {}", e);
+            None
+        }
+    };
+
+    let body = render_metrics(
+        &state.analytics_service.dead_letter_metrics(),
+        pending,
+        state.analytics_service.worker_metrics().as_ref(),
+    );
+    ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], body)
+}
+
+fn render_metrics(dead_letters: &DeadLetterMetrics, pending: Option<u64>, worker: Option<&AnalyticsWorkerMetrics>) -> String {
+    let mut body = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: u64| {
+        let _ = writeln!(body, "# HELP {} {}\n# TYPE {} {}\n{} {}", name, help, name, kind, name, value);
+    };
+
+    metric("qr_analytics_dead_letters_total", "counter", "Scans whose analytics update failed and were queued for retry", dead_letters.queued);
+    metric("qr_analytics_dead_letters_recovered_total", "counter", "Queued scans applied by a later retry", dead_letters.recovered);
+    metric("qr_analytics_dead_letter_retry_failures_total", "counter", "Retries of queued analytics updates that failed again", dead_letters.retry_failures);
+    metric("qr_analytics_lost_scans_total", "counter", "Scans whose analytics update failed and couldn't be queued", dead_letters.lost);
+    if let Some(pending) = pending {
+        metric("qr_analytics_dead_letters_pending", "gauge", "Queued analytics updates not yet applied", pending);
+    }
+
+    if let Some(worker) = worker {
+        metric("qr_analytics_queue_depth", "gauge", "Scans waiting in the analytics worker queue", worker.queue_depth as u64);
+        metric("qr_analytics_processed_total", "counter", "Scans aggregated by the analytics worker", worker.processed);
+        metric("qr_analytics_failed_total", "counter", "Scans the analytics worker failed to write or aggregate", worker.failed);
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let dead_letters = DeadLetterMetrics { queued: 12, recovered: 9, retry_failures: 2, lost: 1 };
+
+        let body = render_metrics(&dead_letters, Some(3), None);
+        assert!(body.contains("# TYPE qr_analytics_dead_letters_total counter\nqr_analytics_dead_letters_total 12\n"));
+        assert!(body.contains("\nqr_analytics_dead_letters_recovered_total 9\n"));
+        assert!(body.contains("\nqr_analytics_dead_letters_pending 3\n"));
+        assert!(!body.contains("qr_analytics_queue_depth"));
+
+        // Without a count the gauge is left out rather than reported as zero
+        assert!(!render_metrics(&dead_letters, None, None).contains("pending"));
+    }
+}
@@ -1,10 +1,54 @@
  // src/handlers/mod.rs
 
+pub mod admin_handler;
+pub mod analytics_handler;
+pub mod audit_handler;
+pub mod auth_handler;
+pub mod auto_redirect_handler;
+pub mod geo_block_handler;
+pub mod graphql_handler;
 pub mod health;
+pub mod hook_handler;
+pub mod impersonation_handler;
+pub mod link_handler;
+pub mod load_shedding;
+pub mod metrics_handler;
+pub mod organization_handler;
+pub mod owner_handler;
+pub mod property_handler;
 pub mod qr_handler;
+pub mod qr_style_handler;
+pub mod scan_cap_handler;
 pub mod scan_handler;
+pub mod security_headers;
+pub mod storage_handler;
+pub mod tracking_handler;
+pub mod validated_json;
+pub mod waitlist_handler;
 
 // Re-export handler functions for convenience
+pub use admin_handler::*;
+pub use analytics_handler::*;
+pub use audit_handler::*;
+pub use auth_handler::*;
+pub use auto_redirect_handler::*;
+pub use geo_block_handler::*;
+pub use graphql_handler::*;
 pub use health::*;
+pub use hook_handler::*;
+pub use impersonation_handler::*;
+pub use link_handler::*;
+pub use load_shedding::*;
+pub use metrics_handler::*;
+pub use organization_handler::*;
+pub use owner_handler::*;
+pub use property_handler::*;
 pub use qr_handler::*;
+pub use qr_style_handler::*;
+pub use scan_cap_handler::*;
 pub use scan_handler::*;
+pub use security_headers::*;
+pub use storage_handler::*;
+pub use tracking_handler::*;
+pub use validated_json::*;
+pub use waitlist_handler::*;
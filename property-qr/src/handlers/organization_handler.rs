@@ -0,0 +1,487 @@
+// src/handlers/organization_handler.rs
+
+use axum::{
+    extract::{Extension, Path, Query, RawPathParams, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    Json,
+};
+use serde::Deserialize;
+use mongodb::bson::oid::ObjectId;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use tracing::{info, warn, error};
+
+use crate::handlers::{AdminAppState, ErrorResponse, SuccessResponse};
+use crate::models::{
+    BrandingProfile, CreateOrganizationRequest, OrgAnalyticsSummary, Organization, OrganizationResponse, QrCodeMetadata,
+    QrCodeResponse, QrGenerationReason, AuditActor,
+};
+use crate::services::{
+    AnalyticsService, OrganizationService, PrivacyPolicy, PropertyService, QrGenerationOptions, QrGeneratorService,
+    branding_kit::build_branding_kit, organization_service::OrganizationError, qr_generator::QrGeneratorError,
+};
+
+// Header organization admins send their organization's API key in
+pub const ORG_API_KEY_HEADER: &str = "x-org-api-key";
+
+// Look-back window for organization rollups
+const DEFAULT_ORG_ANALYTICS_DAYS: i64 = 30;
+const MAX_ORG_ANALYTICS_DAYS: i64 = 365;
+
+// Application state for organization routes
+#[derive(Clone)]
+pub struct OrgAppState {
+    pub organization_service: OrganizationService,
+    pub property_service: PropertyService, // Resolves member owners to their properties
+    pub qr_generator: QrGeneratorService,
+    pub analytics_service: AnalyticsService,
+    pub privacy: PrivacyPolicy, // Applied to rollups requested for sharing with partners
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OrgAnalyticsQuery {
+    /// Days to look back, 1-365 (default 30)
+    pub days: Option<i64>,
+    /// Add differential-privacy noise and leave out properties with few visitors, for sharing with partners
+    #[serde(default)]
+    pub privacy: bool,
+}
+
+fn organization_error_response(e: OrganizationError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        OrganizationError::NotFound => (StatusCode::NOT_FOUND, "organization_not_found"),
+        OrganizationError::InvalidKey => (StatusCode::UNAUTHORIZED, "invalid_organization_key"),
+        OrganizationError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, "invalid_organization_request"),
+        OrganizationError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "organization_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+fn internal_error(error_type: &str, message: String) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new(error_type, &message)))
+}
+
+/// Create an organization; the response carries its API key, shown only this once
+/// POST /admin/orgs
+pub async fn create_organization(
+    State(state): State<Arc<AdminAppState>>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, ResponseJson<SuccessResponse<OrganizationResponse>>), (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.organization_service.create(request).await {
+        Ok((organization, api_key)) => Ok((
+            StatusCode::CREATED,
+            Json(SuccessResponse::new(organization.to_response(Some(api_key)))),
+        )),
+        Err(e) => {
+            error!("Failed to create organization: {}", e);
+            Err(organization_error_response(e))
+        }
+    }
+}
+
+/// Add an owner account to an organization
+/// PUT /admin/orgs/{org_id}/members/{owner_id}
+pub async fn add_organization_member(
+    State(state): State<Arc<AdminAppState>>,
+    Path((org_id, owner_id)): Path<(String, String)>,
+) -> Result<ResponseJson<SuccessResponse<OrganizationResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.organization_service.add_member(&org_id, &owner_id).await {
+        Ok(organization) => Ok(Json(SuccessResponse::new(organization.to_response(None)))),
+        Err(e) => {
+            error!("Failed to add owner {} to organization {}: {}", owner_id, org_id, e);
+            Err(organization_error_response(e))
+        }
+    }
+}
+
+/// Remove an owner account from an organization
+/// DELETE /admin/orgs/{org_id}/members/{owner_id}
+pub async fn remove_organization_member(
+    State(state): State<Arc<AdminAppState>>,
+    Path((org_id, owner_id)): Path<(String, String)>,
+) -> Result<ResponseJson<SuccessResponse<OrganizationResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.organization_service.remove_member(&org_id, &owner_id).await {
+        Ok(organization) => Ok(Json(SuccessResponse::new(organization.to_response(None)))),
+        Err(e) => {
+            error!("Failed to remove owner {} from organization {}: {}", owner_id, org_id, e);
+            Err(organization_error_response(e))
+        }
+    }
+}
+
+/// Middleware for organization routes: the organization's API key, as `X-Org-Api-Key`
+/// or a bearer token, must match the organization in the path
+pub async fn require_org_key(
+    State(state): State<Arc<OrgAppState>>,
+    params: RawPathParams,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let org_id = params.iter().find(|(key, _)| *key == "org_id").map(|(_, value)| value.to_string());
+    let (Some(org_id), Some(api_key)) = (org_id, presented_key(request.headers())) else {
+        return organization_error_response(OrganizationError::InvalidKey).into_response();
+    };
+
+    match state.organization_service.authenticate(&org_id, &api_key).await {
+        Ok(organization) => {
+            request.extensions_mut().insert(organization);
+            next.run(request).await
+        }
+        Err(e) => {
+            warn!("Rejected organization request on {} {}: {}", request.method(), request.uri().path(), e);
+            organization_error_response(e).into_response()
+        }
+    }
+}
+
+fn presented_key(headers: &HeaderMap) -> Option<String> {
+    headers.get(ORG_API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| {
+            headers.get(header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+        })
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+/// Property IDs of every member owner's listings
+async fn member_property_ids(
+    state: &OrgAppState,
+    organization: &Organization,
+) -> Result<Vec<String>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    state.property_service
+        .get_property_ids_by_owners(&organization.owner_ids)
+        .await
+        .map_err(|e| {
+            error!("Failed to load properties of organization {}: {}", organization.id, e);
+            internal_error("organization_properties_failed", e.to_string())
+        })
+}
+
+/// Only properties owned by a member may be managed through the organization
+async fn require_member_property(
+    state: &OrgAppState,
+    organization: &Organization,
+    property_id: &str,
+) -> Result<(), (StatusCode, ResponseJson<ErrorResponse>)> {
+    let owner = state.property_service
+        .get_property_qr_info(property_id)
+        .await
+        .ok()
+        .map(|property| property.owner.to_hex());
+
+    match owner {
+        Some(owner_id) if organization.has_member(&owner_id) => Ok(()),
+        Some(_) => Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new("property_not_in_organization", "Property is not owned by a member of this organization")),
+        )),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("property_not_found", "Property not found")),
+        )),
+    }
+}
+
+/// The organization the API key belongs to, with its member owners
+/// GET /orgs/{org_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{org_id}",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "Organization", body = SuccessResponse<OrganizationResponse>),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+    )
+)]
+pub async fn get_organization(
+    Extension(organization): Extension<Organization>,
+) -> ResponseJson<SuccessResponse<OrganizationResponse>> {
+    Json(SuccessResponse::new(organization.to_response(None)))
+}
+
+/// QR codes of every member owner's properties
+/// GET /orgs/{org_id}/qr
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{org_id}/qr",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "Member QR codes, newest first", body = SuccessResponse<Vec<QrCodeMetadata>>),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_org_qr_codes(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+) -> Result<ResponseJson<SuccessResponse<Vec<QrCodeMetadata>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let property_ids = member_property_ids(&state, &organization).await?;
+
+    match state.qr_generator.get_qr_codes_for_properties(&property_ids).await {
+        Ok(qr_codes) => Ok(Json(SuccessResponse::new(qr_codes))),
+        Err(e) => {
+            error!("Failed to list QR codes of organization {}: {}", organization.id, e);
+            Err(internal_error("list_failed", e.to_string()))
+        }
+    }
+}
+
+/// Regenerate the QR code of a member's property
+/// POST /orgs/{org_id}/qr/{property_id}/regenerate
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs/{org_id}/qr/{property_id}/regenerate",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR code regenerated", body = SuccessResponse<QrCodeResponse>),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+        (status = 403, description = "Property is not owned by a member", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn regenerate_org_qr(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+    Path((org_id, property_id)): Path<(String, String)>,
+) -> Result<ResponseJson<SuccessResponse<QrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    require_member_property(&state, &organization, &property_id).await?;
+    info!("Organization {} regenerating QR code for property: {}", org_id, property_id);
+
+    match state.qr_generator
+        .generate_qr_code_with(property_id.clone(), true, QrGenerationReason::ManualRegeneration, QrGenerationOptions {
+            actor: Some(AuditActor::organization(&organization.id)),
+            ..QrGenerationOptions::default()
+        })
+        .await
+    {
+        Ok(qr_response) => Ok(Json(SuccessResponse::new(qr_response))),
+        Err(e) => {
+            error!("Failed to regenerate QR code for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                QrGeneratorError::PropertyNotEligible(_) => (StatusCode::BAD_REQUEST, "property_not_eligible"),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "regeneration_failed"),
+            };
+            Err((status_code, Json(ErrorResponse::new(error_type, &e.to_string()))))
+        }
+    }
+}
+
+/// Deactivate the QR code of a member's property
+/// POST /orgs/{org_id}/qr/{property_id}/deactivate
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs/{org_id}/qr/{property_id}/deactivate",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR code deactivated"),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+        (status = 403, description = "Property is not owned by a member", body = ErrorResponse),
+        (status = 404, description = "Property or QR code not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn deactivate_org_qr(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+    Path((org_id, property_id)): Path<(String, String)>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    require_member_property(&state, &organization, &property_id).await?;
+    info!("Organization {} deactivating QR code for property: {}", org_id, property_id);
+
+    match state.qr_generator
+        .deactivate_qr_code(&property_id, Some(AuditActor::organization(&organization.id)))
+        .await {
+        Ok(true) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "propertyId": property_id,
+            "deactivated": true
+        })))),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("qr_not_found", "No QR code found for this property")),
+        )),
+        Err(e) => {
+            error!("Failed to deactivate QR code for property {}: {}", property_id, e);
+            Err(internal_error("deactivation_failed", e.to_string()))
+        }
+    }
+}
+
+/// Scans rolled up across every member owner's properties
+/// GET /orgs/{org_id}/analytics?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{org_id}/analytics",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+        OrgAnalyticsQuery,
+    ),
+    responses(
+        (status = 200, description = "Scan totals per member property", body = SuccessResponse<OrgAnalyticsSummary>),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_org_analytics(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+    Query(query): Query<OrgAnalyticsQuery>,
+) -> Result<ResponseJson<SuccessResponse<OrgAnalyticsSummary>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_ORG_ANALYTICS_DAYS).clamp(1, MAX_ORG_ANALYTICS_DAYS);
+    let property_ids = member_property_ids(&state, &organization).await?;
+
+    match state.analytics_service.get_property_scan_totals(&property_ids, days).await {
+        Ok(properties) if query.privacy => {
+            let mut properties = state.privacy.apply(properties, &mut rand::rng());
+            properties.sort_by_key(|property| std::cmp::Reverse(property.scans));
+
+            let mut summary = OrgAnalyticsSummary::new(organization.id.to_hex(), days, property_ids.len(), properties);
+            summary.privacy = Some(state.privacy.notice());
+            Ok(Json(SuccessResponse::new(summary)))
+        }
+        Ok(properties) => Ok(Json(SuccessResponse::new(OrgAnalyticsSummary::new(
+            organization.id.to_hex(),
+            days,
+            property_ids.len(),
+            properties,
+        )))),
+        Err(e) => {
+            error!("Failed to roll up analytics for organization {}: {}", organization.id, e);
+            Err(internal_error("organization_analytics_failed", e.to_string()))
+        }
+    }
+}
+
+/// Set the logo, colors and tagline used for the organization's branding kit
+/// PUT /orgs/{org_id}/branding
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs/{org_id}/branding",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+    ),
+    request_body = BrandingProfile,
+    responses(
+        (status = 200, description = "Branding saved", body = SuccessResponse<OrganizationResponse>),
+        (status = 400, description = "Invalid color, logo URL or tagline", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+    )
+)]
+pub async fn update_org_branding(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+    Json(branding): Json<BrandingProfile>,
+) -> Result<ResponseJson<SuccessResponse<OrganizationResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.organization_service.set_branding(&organization.id.to_hex(), branding).await {
+        Ok(organization) => {
+            info!("Updated branding of organization {}", organization.id);
+            Ok(Json(SuccessResponse::new(organization.to_response(None))))
+        }
+        Err(e) => {
+            warn!("Failed to update branding of organization {}: {}", organization.id, e);
+            Err(organization_error_response(e))
+        }
+    }
+}
+
+/// Download a ZIP of branded print frames for every active member QR code, with a manifest
+/// GET /orgs/{org_id}/kit
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{org_id}/kit",
+    tag = "organizations",
+    params(
+        ("org_id" = String, Path, description = "Organization ID"),
+    ),
+    responses(
+        (status = 200, description = "Branding kit as a ZIP attachment", content_type = "application/zip"),
+        (status = 401, description = "Missing or invalid organization API key", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn export_org_branding_kit(
+    State(state): State<Arc<OrgAppState>>,
+    Extension(organization): Extension<Organization>,
+) -> Result<Response, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let property_ids = member_property_ids(&state, &organization).await?;
+    let qr_codes: Vec<QrCodeMetadata> = state.qr_generator
+        .get_qr_codes_for_properties(&property_ids)
+        .await
+        .map_err(|e| {
+            error!("Failed to load QR codes for branding kit of organization {}: {}", organization.id, e);
+            internal_error("branding_kit_failed", e.to_string())
+        })?
+        .into_iter()
+        .filter(|qr_code| qr_code.is_active)
+        .collect();
+
+    let listing_ids: Vec<ObjectId> = qr_codes.iter().filter_map(QrCodeMetadata::listing_id).collect();
+    let names = state.property_service.listing_names(&listing_ids).await;
+    let entries: Vec<(QrCodeMetadata, String)> = qr_codes.into_iter()
+        .map(|qr_code| {
+            let name = qr_code.listing_name(&names);
+            (qr_code, name)
+        })
+        .collect();
+
+    let branding = organization.branding.clone()
+        .unwrap_or_else(|| BrandingProfile::from_settings(state.qr_generator.settings()));
+
+    match build_branding_kit(&branding, &entries) {
+        Ok(bytes) => {
+            info!("Built branding kit of {} frames for organization {}", entries.len(), organization.id);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"branding-kit-{}.zip\"", organization.id.to_hex())),
+                ],
+                bytes,
+            ).into_response())
+        }
+        Err(e) => {
+            error!("Failed to build branding kit for organization {}: {}", organization.id, e);
+            Err(internal_error("branding_kit_failed", e.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_presented_key() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(presented_key(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer org-key"));
+        assert_eq!(presented_key(&headers).as_deref(), Some("org-key"));
+
+        headers.insert(ORG_API_KEY_HEADER, HeaderValue::from_static(" header-key "));
+        assert_eq!(presented_key(&headers).as_deref(), Some("header-key"));
+    }
+}
@@ -0,0 +1,168 @@
+// src/handlers/owner_handler.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use tracing::error;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{DigestPreferenceResponse, OwnerQrListing, UpdateDigestPreferenceRequest};
+use crate::services::{
+    AnalyticsService, DigestService, PropertyService, QrGeneratorService,
+    digest_service::DigestError, property_service::PropertyError,
+};
+
+// Look-back window for the recent scans on an owner's listing
+const DEFAULT_OWNER_SCAN_DAYS: i64 = 30;
+const MAX_OWNER_SCAN_DAYS: i64 = 365;
+
+// Application state for owner routes
+#[derive(Clone)]
+pub struct OwnerAppState {
+    pub qr_generator: QrGeneratorService,
+    pub property_service: PropertyService,
+    pub analytics_service: AnalyticsService,
+    pub digest_service: DigestService,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OwnerQrQuery {
+    /// Days of recent scans to count, 1-365 (default 30)
+    pub days: Option<i64>,
+}
+
+fn internal_error(error_type: &str, message: String) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new(error_type, &message)))
+}
+
+fn digest_error_response(e: DigestError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    match e {
+        DigestError::InvalidOwnerId => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("invalid_owner_id", "Owner ID must be a valid ObjectId")),
+        ),
+        e => internal_error("digest_preference_failed", e.to_string()),
+    }
+}
+
+/// Every QR code on an owner's properties, with scan totals, for the app's "My listings" page
+/// GET /owners/{owner_id}/qr?days=30
+#[utoipa::path(
+    get,
+    path = "/api/v1/owners/{owner_id}/qr",
+    tag = "owners",
+    params(
+        ("owner_id" = String, Path, description = "Owner (DAO-Bitat user) ID"),
+        OwnerQrQuery,
+    ),
+    responses(
+        (status = 200, description = "The owner's QR codes with scan totals", body = SuccessResponse<OwnerQrListing>),
+        (status = 400, description = "Invalid owner ID", body = ErrorResponse),
+        (status = 403, description = "Agents can only list their own codes", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_owner_qr_codes(
+    State(state): State<Arc<OwnerAppState>>,
+    Path(owner_id): Path<String>,
+    Query(query): Query<OwnerQrQuery>,
+) -> Result<ResponseJson<SuccessResponse<OwnerQrListing>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let days = query.days.unwrap_or(DEFAULT_OWNER_SCAN_DAYS).clamp(1, MAX_OWNER_SCAN_DAYS);
+
+    let property_ids: Vec<String> = match state.property_service.get_properties_by_owner(&owner_id).await {
+        Ok(properties) => properties.iter().map(|property| property.id.to_hex()).collect(),
+        Err(PropertyError::InvalidId) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new("invalid_owner_id", "Owner ID must be a valid ObjectId")),
+            ));
+        }
+        Err(e) => {
+            error!("Failed to load properties of owner {}: {}", owner_id, e);
+            return Err(internal_error("owner_properties_failed", e.to_string()));
+        }
+    };
+
+    let qr_codes = state.qr_generator
+        .get_qr_codes_for_properties(&property_ids)
+        .await
+        .map_err(|e| {
+            error!("Failed to load QR codes of owner {}: {}", owner_id, e);
+            internal_error("owner_qr_codes_failed", e.to_string())
+        })?;
+
+    let scans = state.analytics_service
+        .get_property_scan_totals(&property_ids, days)
+        .await
+        .map_err(|e| {
+            error!("Failed to load scan totals of owner {}: {}", owner_id, e);
+            internal_error("owner_scans_failed", e.to_string())
+        })?;
+
+    Ok(Json(SuccessResponse::new(OwnerQrListing::new(owner_id, days, &property_ids, qr_codes, scans))))
+}
+
+/// Whether an owner gets the weekly scan digest email
+/// GET /owners/{owner_id}/digest
+#[utoipa::path(
+    get,
+    path = "/api/v1/owners/{owner_id}/digest",
+    tag = "owners",
+    params(
+        ("owner_id" = String, Path, description = "Owner (DAO-Bitat user) ID"),
+    ),
+    responses(
+        (status = 200, description = "The owner's digest settings", body = SuccessResponse<DigestPreferenceResponse>),
+        (status = 400, description = "Invalid owner ID", body = ErrorResponse),
+        (status = 403, description = "Agents can only see their own settings", body = ErrorResponse),
+    )
+)]
+pub async fn get_digest_preference(
+    State(state): State<Arc<OwnerAppState>>,
+    Path(owner_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<DigestPreferenceResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.digest_service.get_preference(&owner_id).await {
+        Ok(preference) => Ok(Json(SuccessResponse::new(preference.into()))),
+        Err(e) => {
+            error!("Failed to load digest settings of owner {}: {}", owner_id, e);
+            Err(digest_error_response(e))
+        }
+    }
+}
+
+/// Opt an owner out of, or back into, the weekly scan digest email
+/// PUT /owners/{owner_id}/digest
+#[utoipa::path(
+    put,
+    path = "/api/v1/owners/{owner_id}/digest",
+    tag = "owners",
+    params(
+        ("owner_id" = String, Path, description = "Owner (DAO-Bitat user) ID"),
+    ),
+    request_body = UpdateDigestPreferenceRequest,
+    responses(
+        (status = 200, description = "Digest settings saved", body = SuccessResponse<DigestPreferenceResponse>),
+        (status = 400, description = "Invalid owner ID", body = ErrorResponse),
+        (status = 403, description = "Agents can only change their own settings", body = ErrorResponse),
+    )
+)]
+pub async fn update_digest_preference(
+    State(state): State<Arc<OwnerAppState>>,
+    Path(owner_id): Path<String>,
+    Json(request): Json<UpdateDigestPreferenceRequest>,
+) -> Result<ResponseJson<SuccessResponse<DigestPreferenceResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.digest_service.set_opt_out(&owner_id, request.opted_out).await {
+        Ok(preference) => Ok(Json(SuccessResponse::new(preference.into()))),
+        Err(e) => {
+            error!("Failed to save digest settings of owner {}: {}", owner_id, e);
+            Err(digest_error_response(e))
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// src/handlers/property_handler.rs
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{info, error};
+use utoipa::IntoParams;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{EligibilityReport, IneligibilityReason, PropertyListItem};
+use crate::services::{PropertyService, property_service::{PropertyError, PropertySearchCriteria, PropertyStats}};
+
+const DEFAULT_REPORT_PAGE_SIZE: i64 = 50;
+const MAX_REPORT_PAGE_SIZE: i64 = 500;
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 200;
+const DEFAULT_RECENT_LIMIT: i64 = 20;
+
+// Application state for listing-level handlers
+#[derive(Clone)]
+pub struct PropertyAppState {
+    pub property_service: PropertyService,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PropertySearchQuery {
+    pub location: Option<String>, // Case-insensitive substring
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub property_type: Option<String>, // Residential | Commercial | Land ...
+    pub verified_only: Option<bool>,
+    pub blockchain_only: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecentPropertiesQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EligibilityReportQuery {
+    pub reason: Option<IneligibilityReason>, // Only this group; every reason by default
+    pub limit: Option<i64>,                  // Properties per group
+    pub skip: Option<u64>,
+}
+
+impl PropertySearchQuery {
+    fn into_criteria(self) -> PropertySearchCriteria {
+        PropertySearchCriteria {
+            location: self.location.filter(|location| !location.trim().is_empty()),
+            min_price: self.min_price,
+            max_price: self.max_price,
+            property_type: self.property_type,
+            verified_only: self.verified_only.unwrap_or(false),
+            blockchain_only: self.blockchain_only.unwrap_or(false),
+            limit: Some(self.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT)),
+        }
+    }
+}
+
+fn property_error_response(e: PropertyError, error_type: &str) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Search live listings, newest first
+/// GET /properties?location=kilimani&min_price=1000000&verified_only=true&limit=50
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties",
+    tag = "qr",
+    params(PropertySearchQuery),
+    responses(
+        (status = 200, description = "Matching listings, newest first", body = SuccessResponse<Vec<PropertyListItem>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn search_properties(
+    State(state): State<Arc<PropertyAppState>>,
+    Query(query): Query<PropertySearchQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<PropertyListItem>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.property_service.search_properties(query.into_criteria()).await {
+        Ok(properties) => Ok(Json(SuccessResponse::new(
+            properties.iter().map(|property| property.to_list_item()).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to search properties: {}", e);
+            Err(property_error_response(e, "search_failed"))
+        }
+    }
+}
+
+/// Listing counts behind QR coverage: live, verified, with images and QR-eligible
+/// GET /properties/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/stats",
+    tag = "qr",
+    responses(
+        (status = 200, description = "Listing counts", body = SuccessResponse<PropertyStats>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_property_stats(
+    State(state): State<Arc<PropertyAppState>>,
+) -> Result<ResponseJson<SuccessResponse<PropertyStats>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.property_service.get_property_stats().await {
+        Ok(stats) => Ok(Json(SuccessResponse::new(stats))),
+        Err(e) => {
+            error!("Failed to get property stats: {}", e);
+            Err(property_error_response(e, "stats_failed"))
+        }
+    }
+}
+
+/// Newest QR-eligible listings
+/// GET /properties/recent?limit=20
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/recent",
+    tag = "qr",
+    params(RecentPropertiesQuery),
+    responses(
+        (status = 200, description = "Newest eligible listings", body = SuccessResponse<Vec<PropertyListItem>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_recent_properties(
+    State(state): State<Arc<PropertyAppState>>,
+    Query(query): Query<RecentPropertiesQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<PropertyListItem>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(DEFAULT_RECENT_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+
+    match state.property_service.get_recent_properties(limit).await {
+        // Only eligible listings are returned
+        Ok(listings) => Ok(Json(SuccessResponse::new(
+            listings.iter().map(|listing| PropertyListItem::new(listing, true)).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to get recent properties: {}", e);
+            Err(property_error_response(e, "recent_failed"))
+        }
+    }
+}
+
+/// Listings that can't get a QR code, grouped by what needs fixing
+/// GET /properties/eligibility?reason=no_images&limit=50&skip=0
+#[utoipa::path(
+    get,
+    path = "/api/v1/properties/eligibility",
+    tag = "qr",
+    params(EligibilityReportQuery),
+    responses(
+        (status = 200, description = "Counts and one page of listings per ineligibility reason", body = SuccessResponse<EligibilityReport>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_eligibility_report(
+    State(state): State<Arc<PropertyAppState>>,
+    Query(query): Query<EligibilityReportQuery>,
+) -> Result<ResponseJson<SuccessResponse<EligibilityReport>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let limit = query.limit.unwrap_or(DEFAULT_REPORT_PAGE_SIZE).clamp(1, MAX_REPORT_PAGE_SIZE);
+    let skip = query.skip.unwrap_or(0);
+    let reasons = match query.reason {
+        Some(reason) => vec![reason],
+        None => IneligibilityReason::ALL.to_vec(),
+    };
+
+    match state.property_service.eligibility_report(&reasons, limit, skip).await {
+        Ok(report) => {
+            info!("{} of {} properties are not eligible for QR codes", report.ineligible_properties, report.total_properties);
+            Ok(Json(SuccessResponse::new(report)))
+        }
+        Err(e) => {
+            error!("Failed to build eligibility report: {}", e);
+            Err(property_error_response(e, "eligibility_report_failed"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_query_into_criteria() {
+        let query = PropertySearchQuery {
+            location: Some(" ".to_string()),
+            min_price: Some(1_000_000),
+            max_price: None,
+            property_type: Some("Residential".to_string()),
+            verified_only: Some(true),
+            blockchain_only: None,
+            limit: Some(10_000),
+        };
+        let criteria = query.into_criteria();
+
+        assert_eq!(criteria.location, None);
+        assert_eq!(criteria.min_price, Some(1_000_000));
+        assert!(criteria.verified_only);
+        assert!(!criteria.blockchain_only);
+        assert_eq!(criteria.limit, Some(MAX_SEARCH_LIMIT));
+    }
+}
@@ -2,48 +2,140 @@
 // src/handlers/qr_handler.rs
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Extension, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     Json,
-    response::Json as ResponseJson,
+    response::{Html, IntoResponse, Json as ResponseJson, Response},
 };
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
+use mongodb::bson::oid::ObjectId;
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 use tracing::{info, warn, error};
 
 use crate::models::{
-    GenerateQrRequest, BatchGenerateQrRequest, QrCodeResponse, BatchQrCodeResponse,
-    QrGenerationReason, QrStatus, QrCodeMetadata
+    AssetVerification, QrDecodeReport, GenerateQrRequest, BatchGenerateQrRequest, QrCodeResponse, BatchQrCodeResponse,
+    QrGenerationReason, QrCodeMetadata, QrCodeDetails, QrRegenerationJobResponse, StaleQrReport,
+    UpdateQrRedirectRequest, PropertyQrInfo, CreateDocumentQrRequest, DocumentQrResponse,
+    CreateShareOfferQrRequest, ShareOfferQrResponse, BookingQrResponse, CreateExperimentRequest, ExperimentResponse,
+    LandingExperiment, Principal, QrCodePage, QrSortField, SortOrder, QrExportRequest, PosterSize, StickerSheetRequest,
 };
-use crate::services::QrGeneratorService;
+use crate::handlers::{is_authorized, audit_handler::request_actor, ValidatedJson};
+use crate::services::{
+    DocumentQrService, PosterService, PropertyService, QrGenerationOptions, QrGeneratorService, qr_generator::UploadedImageFormat,
+    document_qr_service::DocumentQrError, ShareOfferService, share_offer_service::ShareOfferError,
+    BookingCheckinService, booking_checkin_service::BookingCheckinError,
+    ExperimentService, experiment_service::ExperimentError,
+    poster_service::{compose_poster, compose_sticker_sheet, PosterContent, Sticker},
+    qr_export::{build_qr_archive, ExportedQrImage},
+};
+use crate::utils::{escape_html, ValidationError};
+
+// Rendered size of the QR code in email signatures, in CSS pixels
+const SIGNATURE_QR_SIZE: u32 = 96;
+
+// Codes per print export, and stored images fetched at once while building it
+const MAX_EXPORT_CODES: usize = 200;
+const EXPORT_FETCH_CONCURRENCY: usize = 8;
+
+// Sticker sheet grid; the default matches common 21-up (3 x 7) label sheets
+const DEFAULT_STICKER_ROWS: u32 = 7;
+const DEFAULT_STICKER_COLUMNS: u32 = 3;
+const MAX_STICKER_ROWS: u32 = 12;
+const MAX_STICKER_COLUMNS: u32 = 8;
+
+// Phone photos of signs run to a few megabytes
+pub const MAX_DECODE_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+// Designed QR images, even print-resolution PNGs, are far smaller than photos
+pub const MAX_UPLOADED_QR_IMAGE_BYTES: usize = 5 * 1024 * 1024;
 
 // Application state that will be passed to handlers
 #[derive(Clone)]
 pub struct AppState {
     pub qr_generator: QrGeneratorService,
+    pub property_service: PropertyService, // Live listing names for printed codes
+    pub poster_service: PosterService,
+    pub document_qr: DocumentQrService,
+    pub share_offers: ShareOfferService,
+    pub booking_checkins: BookingCheckinService,
+    pub experiments: ExperimentService,
+    pub admin_api_key: Option<String>, // Required to generate codes for ineligible listings
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GenerateQrQuery {
+    pub allow_ineligible: Option<bool>, // Admin only; needs an overrideNote for the audit record
 }
 
 // Query parameters for pagination and filtering
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct QrListQuery {
     pub limit: Option<i64>,
     pub skip: Option<u64>,
     pub property_id: Option<String>,
     pub active_only: Option<bool>,
+    pub sort: Option<QrSortField>, // generated_at (default) | scan_count
+    pub order: Option<SortOrder>,  // desc (default) | asc
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QrSearchQuery {
+    pub q: String, // Words from the listing name or location, e.g. "Kilimani 2BR"
+    pub limit: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BookingQrQuery {
+    pub force_regenerate: Option<bool>, // Replace an unused code, or issue another after check-in
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PosterQuery {
+    pub size: Option<PosterSize>, // A4 (default) | A5
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RegenerateQuery {
     pub reason: Option<QrGenerationReason>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StaleRegenerationQuery {
+    pub batch_size: Option<usize>,
+}
+
 // Error response structure
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
     pub timestamp: String,
     pub path: Option<String>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub details: Box<[FieldError]>, // Every invalid field of a rejected request body; boxed to keep error results small
+}
+
+// One invalid field of a request body, e.g. "propertyIds[3]"
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+    pub code: String,
+}
+
+impl From<ValidationError> for FieldError {
+    fn from(error: ValidationError) -> Self {
+        Self { field: error.field, message: error.message, code: error.error_code }
+    }
 }
 
 impl ErrorResponse {
@@ -53,6 +145,7 @@ impl ErrorResponse {
             message: message.to_string(),
             timestamp: chrono::Utc::now().to_rfc3339(),
             path: None,
+            details: Box::default(),
         }
     }
 
@@ -60,10 +153,15 @@ impl ErrorResponse {
         self.path = Some(path.to_string());
         self
     }
+
+    pub fn with_details(mut self, errors: Vec<ValidationError>) -> Self {
+        self.details = errors.into_iter().map(FieldError::from).collect();
+        self
+    }
 }
 
 // Success response wrapper
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SuccessResponse<T> {
     pub success: bool,
     pub data: T,
@@ -81,11 +179,31 @@ impl<T> SuccessResponse<T> {
 }
 
 /// Generate QR code for a single property
-/// POST /generate/{property_id}
+/// POST /generate/{property_id}?allow_ineligible=true
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/generate/{property_id}",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        GenerateQrQuery,
+    ),
+    request_body = GenerateQrRequest,
+    responses(
+        (status = 200, description = "QR code generated", body = SuccessResponse<QrCodeResponse>),
+        (status = 400, description = "Invalid request, with every invalid field in details", body = ErrorResponse),
+        (status = 403, description = "allow_ineligible without the admin API key", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn generate_qr_code(
     State(state): State<Arc<AppState>>,
     Path(property_id): Path<String>,
-    Json(request): Json<GenerateQrRequest>,
+    Query(query): Query<GenerateQrQuery>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
+    ValidatedJson(request): ValidatedJson<GenerateQrRequest>,
 ) -> Result<ResponseJson<SuccessResponse<QrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Generating QR code for property: {}", property_id);
 
@@ -100,10 +218,25 @@ pub async fn generate_qr_code(
         ));
     }
 
+    let eligibility_override = match query.allow_ineligible.unwrap_or(false) {
+        true => Some(eligibility_override(state.admin_api_key.as_deref(), &headers, request.override_note)?),
+        false => None,
+    };
+
     let force_regenerate = request.force_regenerate.unwrap_or(false);
     let reason = request.reason.unwrap_or(QrGenerationReason::NewProperty);
+    let options = QrGenerationOptions {
+        style: request.style,
+        payload: request.payload,
+        max_scans: request.max_scans,
+        active_from: request.active_from,
+        active_until: request.active_until,
+        eligibility_override,
+        actor: Some(request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers)),
+        generated_by: principal.as_deref().and_then(Principal::generated_by),
+    };
 
-    match state.qr_generator.generate_qr_code(property_id.clone(), force_regenerate, reason).await {
+    match state.qr_generator.generate_qr_code_with(property_id.clone(), force_regenerate, reason, options).await {
         Ok(qr_response) => {
             info!("Successfully generated QR code for property: {}", property_id);
             Ok(Json(SuccessResponse::new(qr_response)))
@@ -120,6 +253,12 @@ pub async fn generate_qr_code(
                 crate::services::qr_generator::QrGeneratorError::InvalidPropertyId => {
                     (StatusCode::BAD_REQUEST, "invalid_property_id")
                 }
+                crate::services::qr_generator::QrGeneratorError::StyleNotFound(_) => {
+                    (StatusCode::BAD_REQUEST, "style_not_found")
+                }
+                crate::services::qr_generator::QrGeneratorError::InvalidPayload(_) => {
+                    (StatusCode::BAD_REQUEST, "invalid_payload")
+                }
                 _ => (StatusCode::INTERNAL_SERVER_ERROR, "generation_failed")
             };
 
@@ -131,38 +270,61 @@ pub async fn generate_qr_code(
     }
 }
 
-/// Generate QR codes for multiple properties
-/// POST /generate/batch
-pub async fn batch_generate_qr_codes(
-    State(state): State<Arc<AppState>>,
-    Json(request): Json<BatchGenerateQrRequest>,
-) -> Result<ResponseJson<SuccessResponse<BatchQrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
-    info!("Batch generating QR codes for {} properties", request.property_ids.len());
-
-    if request.property_ids.is_empty() {
+/// Admin note for generating despite the eligibility rules, once the caller proves they're an admin
+fn eligibility_override(
+    admin_api_key: Option<&str>,
+    headers: &HeaderMap,
+    note: Option<String>,
+) -> Result<String, (StatusCode, ResponseJson<ErrorResponse>)> {
+    if !admin_api_key.is_some_and(|api_key| is_authorized(api_key, headers)) {
         return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "Property IDs list cannot be empty"
-            ))
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new("admin_required", "allow_ineligible requires the admin API key")),
         ));
     }
 
-    if request.property_ids.len() > 100 {
-        return Err((
+    match note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()) {
+        Some(note) => Ok(note),
+        None => Err((
             StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::new(
-                "validation_error",
-                "Cannot process more than 100 properties at once"
-            ))
-        ));
+            Json(ErrorResponse::new("validation_error", "overrideNote is required with allow_ineligible for the audit record")),
+        )),
     }
+}
+
+/// Generate QR codes for multiple properties
+/// POST /generate/batch
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/generate/batch",
+    tag = "qr",
+    request_body = BatchGenerateQrRequest,
+    responses(
+        (status = 200, description = "Batch result", body = SuccessResponse<BatchQrCodeResponse>),
+        (status = 400, description = "Invalid request, with every invalid property ID in details", body = ErrorResponse),
+        (status = 413, description = "Request body too large", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn batch_generate_qr_codes(
+    State(state): State<Arc<AppState>>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
+    ValidatedJson(request): ValidatedJson<BatchGenerateQrRequest>,
+) -> Result<ResponseJson<SuccessResponse<BatchQrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Batch generating QR codes for {} properties", request.property_ids.len());
 
     let force_regenerate = request.force_regenerate.unwrap_or(false);
     let reason = request.reason.unwrap_or(QrGenerationReason::BatchGeneration);
+    let options = QrGenerationOptions {
+        actor: Some(request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers)),
+        generated_by: principal.as_deref().and_then(Principal::generated_by),
+        ..QrGenerationOptions::default()
+    };
 
-    match state.qr_generator.batch_generate_qr_codes(request.property_ids, force_regenerate, reason).await {
+    match state.qr_generator
+        .batch_generate_qr_codes(request.property_ids, force_regenerate, reason, options)
+        .await {
         Ok(batch_response) => {
             info!(
                 "Batch QR generation completed: {} successful, {} failed",
@@ -181,17 +343,286 @@ pub async fn batch_generate_qr_codes(
     }
 }
 
+/// Generate a QR code for one of a property's documents, resolving to its verification page
+/// POST /qr/document
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/document",
+    tag = "qr",
+    request_body = CreateDocumentQrRequest,
+    responses(
+        (status = 200, description = "Document QR code generated", body = SuccessResponse<DocumentQrResponse>),
+        (status = 400, description = "Invalid property ID", body = ErrorResponse),
+        (status = 403, description = "Agents can only create codes for their own properties", body = ErrorResponse),
+        (status = 404, description = "Property or document not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn generate_document_qr_code(
+    State(state): State<Arc<AppState>>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<CreateDocumentQrRequest>,
+) -> Result<ResponseJson<SuccessResponse<DocumentQrResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Generating QR code for document {} of property {}", request.document_id, request.property_id);
+
+    let generated_by = principal.as_deref().and_then(Principal::generated_by);
+    match state.document_qr.generate(request, generated_by).await {
+        Ok((code, status)) => Ok(Json(SuccessResponse::new(code.to_response(status)))),
+        Err(e) => {
+            let (status_code, error_type) = match e {
+                DocumentQrError::PropertyNotFound => (StatusCode::NOT_FOUND, "property_not_found"),
+                DocumentQrError::DocumentNotFound => (StatusCode::NOT_FOUND, "document_not_found"),
+                DocumentQrError::InvalidPropertyId => (StatusCode::BAD_REQUEST, "invalid_property_id"),
+                _ => {
+                    error!("Failed to generate document QR code: {}", e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, "generation_failed")
+                }
+            };
+            Err((status_code, Json(ErrorResponse::new(error_type, &e.to_string()))))
+        }
+    }
+}
+
+/// Generate a QR code for an offer of a co-owned property's shares, opening DAO-Bitat's share-purchase flow
+/// POST /qr/share-offer
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/share-offer",
+    tag = "qr",
+    request_body = CreateShareOfferQrRequest,
+    responses(
+        (status = 200, description = "Share offer QR code generated", body = SuccessResponse<ShareOfferQrResponse>),
+        (status = 400, description = "Property not co-owned, no shares available or invalid offer", body = ErrorResponse),
+        (status = 403, description = "Agents can only create codes for their own properties", body = ErrorResponse),
+        (status = 404, description = "Property not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn generate_share_offer_qr_code(
+    State(state): State<Arc<AppState>>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<CreateShareOfferQrRequest>,
+) -> Result<ResponseJson<SuccessResponse<ShareOfferQrResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Generating share offer QR code for property {}", request.property_id);
+
+    let generated_by = principal.as_deref().and_then(Principal::generated_by);
+    match state.share_offers.create(request, generated_by).await {
+        Ok(offer) => Ok(Json(SuccessResponse::new(offer.to_response(state.share_offers.daobitar_base_url())))),
+        Err(e) => Err(share_offer_error(e)),
+    }
+}
+
+/// List a property's share offers with their scan counts, newest first
+/// GET /qr/{property_id}/share-offers
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}/share-offers",
+    tag = "qr",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "The property's share offers", body = SuccessResponse<Vec<ShareOfferQrResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_share_offer_qr_codes(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<Vec<ShareOfferQrResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.share_offers.list_for_property(&property_id).await {
+        Ok(offers) => {
+            let daobitar_base_url = state.share_offers.daobitar_base_url();
+            Ok(Json(SuccessResponse::new(
+                offers.iter().map(|offer| offer.to_response(daobitar_base_url)).collect(),
+            )))
+        }
+        Err(e) => Err(share_offer_error(e)),
+    }
+}
+
+fn share_offer_error(e: ShareOfferError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        ShareOfferError::PropertyNotFound => (StatusCode::NOT_FOUND, "property_not_found"),
+        ShareOfferError::NotFound => (StatusCode::NOT_FOUND, "share_offer_not_found"),
+        ShareOfferError::InvalidPropertyId => (StatusCode::BAD_REQUEST, "invalid_property_id"),
+        ShareOfferError::NotCoOwned => (StatusCode::BAD_REQUEST, "not_co_owned"),
+        ShareOfferError::SoldOut => (StatusCode::BAD_REQUEST, "no_shares_available"),
+        ShareOfferError::Validation(_) => (StatusCode::BAD_REQUEST, "validation_error"),
+        _ => {
+            error!("Share offer request failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "share_offer_failed")
+        }
+    };
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Start an A/B test of two versions of a property's dual landing page
+/// POST /qr/{property_id}/experiments
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/{property_id}/experiments",
+    tag = "qr",
+    params(("property_id" = String, Path, description = "Property ID")),
+    request_body = CreateExperimentRequest,
+    responses(
+        (status = 200, description = "Experiment started", body = SuccessResponse<ExperimentResponse>),
+        (status = 400, description = "Invalid experiment", body = ErrorResponse),
+        (status = 409, description = "The property already has a running experiment", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn create_experiment(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<CreateExperimentRequest>,
+) -> Result<ResponseJson<SuccessResponse<ExperimentResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Starting landing page experiment for property {}", property_id);
+
+    let created_by = principal.as_deref().and_then(Principal::generated_by);
+    match state.experiments.create(&property_id, request, created_by).await {
+        Ok(experiment) => Ok(Json(SuccessResponse::new(experiment.to_response()))),
+        Err(e) => Err(experiment_error(e)),
+    }
+}
+
+/// List a property's landing page experiments, newest first
+/// GET /qr/{property_id}/experiments
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}/experiments",
+    tag = "qr",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "The property's experiments", body = SuccessResponse<Vec<ExperimentResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_experiments(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<Vec<ExperimentResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.experiments.list_for_property(&property_id).await {
+        Ok(experiments) => Ok(Json(SuccessResponse::new(
+            experiments.iter().map(LandingExperiment::to_response).collect(),
+        ))),
+        Err(e) => Err(experiment_error(e)),
+    }
+}
+
+/// End a property's running experiment; every scan sees the page's defaults again
+/// POST /qr/{property_id}/experiments/{experiment_id}/end
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/{property_id}/experiments/{experiment_id}/end",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ("experiment_id" = String, Path, description = "Experiment ID"),
+    ),
+    responses(
+        (status = 200, description = "Experiment ended", body = SuccessResponse<ExperimentResponse>),
+        (status = 404, description = "No running experiment with that ID", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn end_experiment(
+    State(state): State<Arc<AppState>>,
+    Path((property_id, experiment_id)): Path<(String, String)>,
+) -> Result<ResponseJson<SuccessResponse<ExperimentResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Ending experiment {} for property {}", experiment_id, property_id);
+
+    match state.experiments.end(&property_id, &experiment_id).await {
+        Ok(experiment) => Ok(Json(SuccessResponse::new(experiment.to_response()))),
+        Err(e) => Err(experiment_error(e)),
+    }
+}
+
+fn experiment_error(e: ExperimentError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        ExperimentError::NotFound => (StatusCode::NOT_FOUND, "experiment_not_found"),
+        ExperimentError::AlreadyRunning => (StatusCode::CONFLICT, "experiment_already_running"),
+        ExperimentError::InvalidExperiment(_) => (StatusCode::BAD_REQUEST, "invalid_experiment"),
+        ExperimentError::DatabaseError(_) => {
+            error!("Experiment request failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "experiment_failed")
+        }
+    };
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Generate a one-time check-in code for a short-term rental booking
+/// POST /qr/booking/{booking_id}?force_regenerate=true
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/booking/{booking_id}",
+    tag = "qr",
+    params(
+        ("booking_id" = String, Path, description = "Booking ID"),
+        BookingQrQuery,
+    ),
+    responses(
+        (status = 200, description = "Check-in QR code; the scan URL only comes with a new code", body = SuccessResponse<BookingQrResponse>),
+        (status = 400, description = "Invalid booking ID, or the booking isn't confirmed or has ended", body = ErrorResponse),
+        (status = 403, description = "Agents can only create codes for their own properties", body = ErrorResponse),
+        (status = 404, description = "Booking not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn generate_booking_qr_code(
+    State(state): State<Arc<AppState>>,
+    Path(booking_id): Path<String>,
+    Query(query): Query<BookingQrQuery>,
+    principal: Option<Extension<Principal>>,
+) -> Result<ResponseJson<SuccessResponse<BookingQrResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Generating check-in QR code for booking: {}", booking_id);
+
+    let generated_by = principal.as_deref().and_then(Principal::generated_by);
+    let force_regenerate = query.force_regenerate.unwrap_or(false);
+    match state.booking_checkins.generate(&booking_id, force_regenerate, generated_by).await {
+        Ok((code, scan_url, status)) => Ok(Json(SuccessResponse::new(code.to_response(scan_url, status)))),
+        Err(e) => {
+            let (status_code, error_type) = match e {
+                BookingCheckinError::BookingNotFound => (StatusCode::NOT_FOUND, "booking_not_found"),
+                BookingCheckinError::InvalidBookingId => (StatusCode::BAD_REQUEST, "invalid_booking_id"),
+                BookingCheckinError::NotConfirmed => (StatusCode::BAD_REQUEST, "booking_not_confirmed"),
+                BookingCheckinError::BookingEnded => (StatusCode::BAD_REQUEST, "booking_ended"),
+                _ => {
+                    error!("Failed to generate check-in QR code for booking {}: {}", booking_id, e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, "generation_failed")
+                }
+            };
+            Err((status_code, Json(ErrorResponse::new(error_type, &e.to_string()))))
+        }
+    }
+}
+
 /// Get existing QR code for a property
 /// GET /qr/{property_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR code metadata, with the scans a limited-use code has left", body = SuccessResponse<QrCodeDetails>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn get_qr_code(
     State(state): State<Arc<AppState>>,
     Path(property_id): Path<String>,
-) -> Result<ResponseJson<SuccessResponse<QrCodeMetadata>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+) -> Result<ResponseJson<SuccessResponse<QrCodeDetails>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Getting QR code for property: {}", property_id);
 
     match state.qr_generator.get_qr_code(&property_id).await {
         Ok(qr_metadata) => {
-            Ok(Json(SuccessResponse::new(qr_metadata)))
+            Ok(Json(SuccessResponse::new(QrCodeDetails {
+                remaining_uses: qr_metadata.remaining_uses(),
+                qr_code: qr_metadata,
+            })))
         }
         Err(e) => {
             warn!("QR code not found for property {}: {}", property_id, e);
@@ -213,18 +644,170 @@ pub async fn get_qr_code(
     }
 }
 
+/// HTML fragment with a property's QR code and link, for pasting into an email signature
+/// GET /qr/{property_id}/signature.html
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}/signature.html",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "Email signature fragment (text/html)", content_type = "text/html"),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_qr_signature(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> Result<Html<String>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_generator.get_qr_code(&property_id).await {
+        Ok(qr_metadata) if qr_metadata.is_active => {
+            // Clicks from signatures are told apart from printed codes by their source
+            let link = format!("{}?source=signature", state.qr_generator.scan_url(&property_id));
+            let listing = state.qr_generator.live_listing(&property_id).await;
+            Ok(Html(create_signature_html(&qr_metadata, listing.as_ref(), &link)))
+        }
+        Ok(_) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("qr_not_found", "QR code is deactivated"))
+        )),
+        Err(e) => {
+            warn!("Failed to build email signature for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "retrieval_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Print-ready PDF poster with the QR code, listing photo, name and price
+/// GET /qr/{property_id}/poster?size=A4
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}/poster",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        PosterQuery,
+    ),
+    responses(
+        (status = 200, description = "One-page poster", content_type = "application/pdf"),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_qr_poster(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+    Query(query): Query<PosterQuery>,
+) -> Result<Response, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let qr_code = match state.qr_generator.get_qr_code(&property_id).await {
+        Ok(qr_code) if qr_code.is_active => qr_code,
+        Ok(_) => return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("qr_not_found", "QR code is deactivated"))
+        )),
+        Err(e) => {
+            warn!("Failed to build poster for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "retrieval_failed")
+            };
+            return Err((status_code, Json(ErrorResponse::new(error_type, &e.to_string()))));
+        }
+    };
+
+    // Current listing details, or the generation-time snapshot if the property can't be loaded
+    let listing = state.qr_generator.live_listing(&property_id).await;
+    let metadata = &qr_code.metadata;
+    let (name, location, action, price, photo_url) = match &listing {
+        Some(listing) => (&listing.property_name, &listing.location, &listing.action, listing.price, listing.images.first()),
+        None => (&metadata.property_name, &metadata.location, &metadata.action, metadata.price, metadata.primary_image.as_ref()),
+    };
+
+    let qr_png = match state.qr_generator.qr_image(&qr_code).await {
+        Ok(image) if !image.is_empty() => Some(image),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Failed to fetch QR image of property {} for poster: {}", property_id, e);
+            None
+        }
+    };
+    let photo_jpeg = match photo_url {
+        Some(url) => state.poster_service.fetch_photo(url).await,
+        None => None,
+    };
+
+    let size = query.size.unwrap_or_default();
+    let pdf = compose_poster(&PosterContent {
+        property_name: name.clone(),
+        location: location.clone(),
+        action: action.clone(),
+        price,
+        scan_url: state.qr_generator.scan_url(&property_id),
+        qr_png,
+        photo_jpeg,
+    }, size);
+
+    info!("Built {:?} poster for property {}", size, property_id);
+    // Property IDs are ObjectId hex, but keep the header well-formed whatever the path holds
+    let filename: String = property_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, format!("inline; filename=\"poster-{}-{}.pdf\"", filename, size.as_str())),
+        ],
+        pdf,
+    ).into_response())
+}
+
 /// Regenerate QR code for a property
 /// PUT /regenerate/{property_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/qr/regenerate/{property_id}",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        RegenerateQuery,
+    ),
+    responses(
+        (status = 200, description = "QR code regenerated", body = SuccessResponse<QrCodeResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn regenerate_qr_code(
     State(state): State<Arc<AppState>>,
     Path(property_id): Path<String>,
     Query(query): Query<RegenerateQuery>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<SuccessResponse<QrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Regenerating QR code for property: {}", property_id);
 
     let reason = query.reason.unwrap_or(QrGenerationReason::ManualRegeneration);
 
-    match state.qr_generator.generate_qr_code(property_id.clone(), true, reason).await {
+    let options = QrGenerationOptions {
+        actor: Some(request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers)),
+        generated_by: principal.as_deref().and_then(Principal::generated_by),
+        ..QrGenerationOptions::default()
+    };
+
+    match state.qr_generator.generate_qr_code_with(property_id.clone(), true, reason, options).await {
         Ok(qr_response) => {
             info!("Successfully regenerated QR code for property: {}", property_id);
             Ok(Json(SuccessResponse::new(qr_response)))
@@ -254,13 +837,30 @@ pub async fn regenerate_qr_code(
 
 /// Delete QR code for a property
 /// DELETE /qr/{property_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/qr/{property_id}",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR code deleted", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn delete_qr_code(
     State(state): State<Arc<AppState>>,
     Path(property_id): Path<String>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Deleting QR code for property: {}", property_id);
 
-    match state.qr_generator.delete_qr_code(&property_id).await {
+    match state.qr_generator
+        .delete_qr_code(&property_id, Some(request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers)))
+        .await {
         Ok(deleted) => {
             if deleted {
                 info!("Successfully deleted QR code for property: {}", property_id);
@@ -286,15 +886,251 @@ pub async fn delete_qr_code(
     }
 }
 
+/// Re-read a listing's name, price and images into its QR metadata without redrawing the image
+/// POST /qr/{property_id}/refresh-metadata
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/{property_id}/refresh-metadata",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR metadata refreshed; image, scan URL and version unchanged", body = SuccessResponse<QrCodeMetadata>),
+        (status = 400, description = "Invalid property ID", body = ErrorResponse),
+        (status = 404, description = "No QR code or property", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn refresh_qr_metadata(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<QrCodeMetadata>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Refreshing QR metadata for property: {}", property_id);
+
+    match state.qr_generator.refresh_metadata(&property_id).await {
+        Ok(qr_metadata) => Ok(Json(SuccessResponse::new(qr_metadata))),
+        Err(e) => {
+            warn!("Failed to refresh QR metadata for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                crate::services::qr_generator::QrGeneratorError::InvalidPropertyId => {
+                    (StatusCode::BAD_REQUEST, "invalid_property_id")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "refresh_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Check a QR code's image in S3 against the upload, redrawing it if it's missing or corrupted
+/// GET /qr/{property_id}/verify-asset
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/{property_id}/verify-asset",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "What was found, and whether the image was regenerated", body = SuccessResponse<AssetVerification>),
+        (status = 404, description = "No QR code for the property", body = ErrorResponse),
+        (status = 500, description = "Storage or internal error", body = ErrorResponse),
+    )
+)]
+pub async fn verify_qr_asset(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<AssetVerification>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_generator.verify_asset(&property_id).await {
+        Ok(verification) => Ok(Json(SuccessResponse::new(verification))),
+        Err(e) => {
+            warn!("Failed to verify QR image for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "verification_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Decode a photo of a QR code and report the property, version and stored image it maps to
+/// POST /qr/decode
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/decode",
+    tag = "qr",
+    request_body(content_type = "multipart/form-data", description = "The photo, as an `image` field"),
+    responses(
+        (status = 200, description = "What the code decodes to, checked against the code on record", body = SuccessResponse<QrDecodeReport>),
+        (status = 400, description = "No image in the upload", body = ErrorResponse),
+        (status = 422, description = "No readable QR code in the image", body = ErrorResponse),
+        (status = 500, description = "Storage or internal error", body = ErrorResponse),
+    )
+)]
+pub async fn decode_qr_code(
+    State(state): State<Arc<AppState>>,
+    multipart: Multipart,
+) -> Result<ResponseJson<SuccessResponse<QrDecodeReport>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let image = read_image_field(multipart).await?;
+
+    match state.qr_generator.inspect_qr_image(&image).await {
+        Ok(report) => {
+            info!("Decoded QR photo: property {:?}, asset {:?}", report.property_id, report.asset_status);
+            Ok(Json(SuccessResponse::new(report)))
+        }
+        Err(e) => {
+            warn!("Failed to inspect QR photo: {}", e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::DecodeFailed(_) => {
+                    (StatusCode::UNPROCESSABLE_ENTITY, "decode_failed")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "inspection_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Replace a QR code's image with a design made elsewhere; it must decode to the property's scan URL
+/// PUT /qr/{property_id}/image
+#[utoipa::path(
+    put,
+    path = "/api/v1/qr/{property_id}/image",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    request_body(content_type = "multipart/form-data", description = "The PNG or SVG design, as an `image` field"),
+    responses(
+        (status = 200, description = "Image replaced and the QR version bumped", body = SuccessResponse<QrCodeMetadata>),
+        (status = 400, description = "No image, or not a PNG or SVG", body = ErrorResponse),
+        (status = 404, description = "No QR code for the property", body = ErrorResponse),
+        (status = 422, description = "The image isn't a readable code for the property's scan URL", body = ErrorResponse),
+        (status = 500, description = "Storage or internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upload_qr_image(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Result<ResponseJson<SuccessResponse<QrCodeMetadata>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let image = read_image_field(multipart).await?;
+    let format = UploadedImageFormat::detect(&image).ok_or_else(|| (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::new("unsupported_image", "Upload the design as a PNG or SVG")),
+    ))?;
+    let actor = request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers);
+
+    match state.qr_generator.replace_qr_image(&property_id, image.to_vec(), format, Some(actor)).await {
+        Ok(qr_code) => Ok(Json(SuccessResponse::new(qr_code))),
+        Err(e) => {
+            warn!("Failed to replace QR image for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                crate::services::qr_generator::QrGeneratorError::DecodeFailed(_) => {
+                    (StatusCode::UNPROCESSABLE_ENTITY, "decode_failed")
+                }
+                crate::services::qr_generator::QrGeneratorError::InvalidPayload(_) => {
+                    (StatusCode::UNPROCESSABLE_ENTITY, "payload_mismatch")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "upload_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+// The `image` field of a multipart upload
+async fn read_image_field(mut multipart: Multipart) -> Result<axum::body::Bytes, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let bad_request = |message: &str| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("invalid_upload", message)));
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| bad_request(&e.body_text()))? {
+        if field.name() == Some("image") {
+            return field.bytes().await.map_err(|e| bad_request(&e.body_text()));
+        }
+    }
+    Err(bad_request("Upload the image as an image field"))
+}
+
+/// Property webhook: the listing platform calls this after editing a property so scans stop serving cached pages
+/// POST /properties/{property_id}/changed
+#[utoipa::path(
+    post,
+    path = "/api/v1/properties/{property_id}/changed",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "Cached listing and landing pages dropped", body = SuccessResponse<serde_json::Value>),
+    )
+)]
+pub async fn property_changed(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+) -> ResponseJson<SuccessResponse<serde_json::Value>> {
+    info!("Property {} changed, dropping its cached pages", property_id);
+    state.qr_generator.invalidate_property(&property_id);
+
+    Json(SuccessResponse::new(serde_json::json!({
+        "invalidated": true,
+        "property_id": property_id
+    })))
+}
+
 /// Deactivate QR code for a property (soft delete)
 /// PATCH /deactivate/{property_id}
+#[utoipa::path(
+    patch,
+    path = "/api/v1/qr/deactivate/{property_id}",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    responses(
+        (status = 200, description = "QR code deactivated", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn deactivate_qr_code(
     State(state): State<Arc<AppState>>,
     Path(property_id): Path<String>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
 ) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Deactivating QR code for property: {}", property_id);
 
-    match state.qr_generator.deactivate_qr_code(&property_id).await {
+    match state.qr_generator
+        .deactivate_qr_code(&property_id, Some(request_actor(state.admin_api_key.as_deref(), principal.as_deref(), &headers)))
+        .await {
         Ok(deactivated) => {
             if deactivated {
                 info!("Successfully deactivated QR code for property: {}", property_id);
@@ -320,33 +1156,86 @@ pub async fn deactivate_qr_code(
     }
 }
 
-/// List all QR codes with pagination
+/// Temporarily point a property's printed QR code at another page, or clear that redirect
+/// PATCH /qr/{property_id}/redirect
+#[utoipa::path(
+    patch,
+    path = "/api/v1/qr/{property_id}/redirect",
+    tag = "qr",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    request_body = UpdateQrRedirectRequest,
+    responses(
+        (status = 200, description = "Redirect updated", body = SuccessResponse<QrCodeMetadata>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn update_qr_redirect(
+    State(state): State<Arc<AppState>>,
+    Path(property_id): Path<String>,
+    Json(request): Json<UpdateQrRedirectRequest>,
+) -> Result<ResponseJson<SuccessResponse<QrCodeMetadata>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating custom redirect for property: {}", property_id);
+
+    match state.qr_generator.set_custom_redirect(&property_id, request).await {
+        Ok(qr_metadata) => Ok(Json(SuccessResponse::new(qr_metadata))),
+        Err(e) => {
+            warn!("Failed to update custom redirect for property {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::PropertyNotFound => {
+                    (StatusCode::NOT_FOUND, "qr_not_found")
+                }
+                crate::services::qr_generator::QrGeneratorError::InvalidRedirect(_) => {
+                    (StatusCode::BAD_REQUEST, "invalid_redirect")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "update_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// List QR codes, filtered and sorted in the database, with total count and page cursors
 /// GET /qr
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr",
+    tag = "qr",
+    params(
+        QrListQuery,
+    ),
+    responses(
+        (status = 200, description = "One page of QR codes", body = SuccessResponse<QrCodePage>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn list_qr_codes(
     State(state): State<Arc<AppState>>,
     Query(query): Query<QrListQuery>,
-) -> Result<ResponseJson<SuccessResponse<Vec<QrCodeMetadata>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+) -> Result<ResponseJson<SuccessResponse<QrCodePage>>, (StatusCode, ResponseJson<ErrorResponse>)> {
     info!("Listing QR codes with query: {:?}", query);
 
-    let limit = query.limit.unwrap_or(50).min(100); // Cap at 100
+    let limit = query.limit.unwrap_or(50).clamp(1, 100); // Cap at 100
     let skip = query.skip.unwrap_or(0);
 
-    match state.qr_generator.get_all_qr_codes(Some(limit), Some(skip)).await {
-        Ok(qr_codes) => {
-            let filtered_codes = if let Some(property_id) = query.property_id {
-                qr_codes.into_iter()
-                    .filter(|qr| qr.property_id == property_id)
-                    .collect()
-            } else if query.active_only.unwrap_or(false) {
-                qr_codes.into_iter()
-                    .filter(|qr| qr.is_active)
-                    .collect()
-            } else {
-                qr_codes
-            };
-
-            info!("Retrieved {} QR codes", filtered_codes.len());
-            Ok(Json(SuccessResponse::new(filtered_codes)))
+    match state.qr_generator.list_qr_codes(
+        query.property_id.as_deref(),
+        query.active_only.unwrap_or(false),
+        query.sort.unwrap_or_default(),
+        query.order.unwrap_or_default(),
+        limit,
+        skip,
+    ).await {
+        Ok(page) => {
+            info!("Retrieved {} of {} QR codes", page.items.len(), page.total_count);
+            Ok(Json(SuccessResponse::new(page)))
         }
         Err(e) => {
             error!("Failed to list QR codes: {}", e);
@@ -358,8 +1247,204 @@ pub async fn list_qr_codes(
     }
 }
 
+/// Find QR codes by listing name or location
+/// GET /qr/search
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/search",
+    tag = "qr",
+    params(
+        QrSearchQuery,
+    ),
+    responses(
+        (status = 200, description = "Matching QR codes, best match first", body = SuccessResponse<Vec<QrCodeMetadata>>),
+        (status = 400, description = "Empty search", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn search_qr_codes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<QrSearchQuery>,
+) -> Result<ResponseJson<SuccessResponse<Vec<QrCodeMetadata>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let search = query.q.trim();
+    if search.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new("validation_error", "Search query cannot be empty"))
+        ));
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    match state.qr_generator.search_qr_codes(search, limit).await {
+        Ok(qr_codes) => {
+            info!("QR search {:?} matched {} codes", search, qr_codes.len());
+            Ok(Json(SuccessResponse::new(qr_codes)))
+        }
+        Err(e) => {
+            error!("Failed to search QR codes: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("search_failed", &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Download the QR images of many properties at once as a ZIP, for print
+/// POST /qr/export
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/export",
+    tag = "qr",
+    request_body = QrExportRequest,
+    responses(
+        (status = 200, description = "PNG files named by property, with a manifest", content_type = "application/zip"),
+        (status = 400, description = "Neither or both of propertyIds and query, or too many codes", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn export_qr_codes(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<QrExportRequest>,
+) -> Result<Response, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let search = request.query.as_deref().map(str::trim).filter(|query| !query.is_empty());
+    let validation_error = |message: &str| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("validation_error", message)));
+
+    let qr_codes = match (request.property_ids.is_empty(), search) {
+        (false, None) if request.property_ids.len() > MAX_EXPORT_CODES => {
+            return Err(validation_error(&format!("At most {} properties can be exported at once", MAX_EXPORT_CODES)));
+        }
+        (false, None) => state.qr_generator.get_qr_codes_for_properties(&request.property_ids).await,
+        (true, Some(search)) => state.qr_generator.search_qr_codes(search, MAX_EXPORT_CODES as i64).await,
+        _ => return Err(validation_error("Provide either propertyIds or query")),
+    };
+    let qr_codes = qr_codes.map_err(|e| {
+        error!("Failed to load QR codes for export: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new("export_failed", &e.to_string())))
+    })?;
+
+    let images = print_images(&state, qr_codes).await;
+    let missing = images.iter().filter(|exported| exported.image.is_none()).count();
+    match build_qr_archive(&images) {
+        Ok(bytes) => {
+            info!("Exported {} QR codes ({} without an image)", images.len(), missing);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"qr-codes-{}.zip\"", chrono::Utc::now().format("%Y%m%d"))),
+                ],
+                bytes,
+            ).into_response())
+        }
+        Err(e) => {
+            error!("Failed to build QR export archive: {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new("export_failed", &e.to_string()))))
+        }
+    }
+}
+
+/// Multi-up PDF of QR codes with their listing names, for Avery-style sticker sheets
+/// POST /qr/stickers
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/stickers",
+    tag = "qr",
+    request_body = StickerSheetRequest,
+    responses(
+        (status = 200, description = "Sticker sheets, one grid per page", content_type = "application/pdf"),
+        (status = 400, description = "No properties, too many, or a grid out of range", body = ErrorResponse),
+        (status = 404, description = "None of the properties has an active QR code", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn create_sticker_sheet(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<StickerSheetRequest>,
+) -> Result<Response, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let rows = request.rows.unwrap_or(DEFAULT_STICKER_ROWS);
+    let columns = request.columns.unwrap_or(DEFAULT_STICKER_COLUMNS);
+    let validation_error = |message: String| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new("validation_error", &message)));
+
+    if request.property_ids.is_empty() || request.property_ids.len() > MAX_EXPORT_CODES {
+        return Err(validation_error(format!("Provide between 1 and {} property IDs", MAX_EXPORT_CODES)));
+    }
+    if !(1..=MAX_STICKER_ROWS).contains(&rows) || !(1..=MAX_STICKER_COLUMNS).contains(&columns) {
+        return Err(validation_error(format!(
+            "rows must be 1-{} and columns 1-{}",
+            MAX_STICKER_ROWS, MAX_STICKER_COLUMNS
+        )));
+    }
+
+    let mut qr_codes = state.qr_generator.get_qr_codes_for_properties(&request.property_ids).await
+        .map_err(|e| {
+            error!("Failed to load QR codes for sticker sheet: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new("sticker_sheet_failed", &e.to_string())))
+        })?;
+    // Stickers follow the order the properties were asked for; deactivated codes are left off
+    qr_codes.retain(|qr_code| qr_code.is_active);
+    qr_codes.sort_by_key(|qr_code| request.property_ids.iter().position(|id| *id == qr_code.property_id));
+    if qr_codes.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new("qr_not_found", "None of these properties has an active QR code"))
+        ));
+    }
+
+    let stickers: Vec<Sticker> = print_images(&state, qr_codes).await
+        .into_iter()
+        .map(|exported| Sticker { label: exported.property_name, qr_png: exported.image })
+        .collect();
+    let size = request.size.unwrap_or_default();
+    let pdf = compose_sticker_sheet(&stickers, size, rows, columns);
+
+    info!("Built sticker sheet of {} codes ({}x{} on {:?})", stickers.len(), columns, rows, size);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, format!("inline; filename=\"qr-stickers-{}.pdf\"", chrono::Utc::now().format("%Y%m%d"))),
+        ],
+        pdf,
+    ).into_response())
+}
+
+/// Stored QR images for print, each with its listing's current name
+async fn print_images(state: &AppState, qr_codes: Vec<QrCodeMetadata>) -> Vec<ExportedQrImage> {
+    let listing_ids: Vec<ObjectId> = qr_codes.iter().filter_map(QrCodeMetadata::listing_id).collect();
+    let names = state.property_service.listing_names(&listing_ids).await;
+
+    stream::iter(qr_codes)
+        .map(|qr_code| {
+            let qr_generator = &state.qr_generator;
+            let property_name = qr_code.listing_name(&names);
+            async move {
+                let image = match qr_generator.qr_image(&qr_code).await {
+                    Ok(image) if !image.is_empty() => Some(image),
+                    Ok(_) => None,
+                    Err(e) => {
+                        warn!("Failed to fetch QR image of property {} for printing: {}", qr_code.property_id, e);
+                        None
+                    }
+                };
+                ExportedQrImage { property_id: qr_code.property_id, property_name, image }
+            }
+        })
+        .buffered(EXPORT_FETCH_CONCURRENCY)
+        .collect()
+        .await
+}
+
 /// Generate QR codes for all properties that don't have them
 /// POST /generate/missing
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/generate/missing",
+    tag = "qr",
+    responses(
+        (status = 200, description = "Batch result", body = SuccessResponse<BatchQrCodeResponse>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
 pub async fn generate_missing_qr_codes(
     State(state): State<Arc<AppState>>,
 ) -> Result<ResponseJson<SuccessResponse<BatchQrCodeResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
@@ -384,9 +1469,217 @@ pub async fn generate_missing_qr_codes(
     }
 }
 
+/// Report QR codes whose encoded scan URL doesn't match the current base URL (dry run)
+/// GET /qr/regenerate/stale
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/regenerate/stale",
+    tag = "qr",
+    responses(
+        (status = 200, description = "Stale QR codes", body = SuccessResponse<StaleQrReport>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_stale_qr_codes(
+    State(state): State<Arc<AppState>>,
+) -> Result<ResponseJson<SuccessResponse<StaleQrReport>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_generator.find_stale_qr_codes().await {
+        Ok(report) => {
+            info!("Found {} stale QR codes for base URL {}", report.stale_count, report.base_url);
+            Ok(Json(SuccessResponse::new(report)))
+        }
+        Err(e) => {
+            error!("Failed to find stale QR codes: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("stale_check_failed", &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Regenerate stale QR codes in the background, in batches
+/// POST /qr/regenerate/stale?batch_size=50
+#[utoipa::path(
+    post,
+    path = "/api/v1/qr/regenerate/stale",
+    tag = "qr",
+    params(
+        StaleRegenerationQuery,
+    ),
+    responses(
+        (status = 202, description = "Regeneration job started", body = SuccessResponse<QrRegenerationJobResponse>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn regenerate_stale_qr_codes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StaleRegenerationQuery>,
+) -> Result<(StatusCode, ResponseJson<SuccessResponse<QrRegenerationJobResponse>>), (StatusCode, ResponseJson<ErrorResponse>)> {
+    let batch_size = query.batch_size.unwrap_or(50).clamp(1, 100);
+    info!("Starting stale QR regeneration with batch size {}", batch_size);
+
+    match state.qr_generator.start_stale_regeneration(batch_size).await {
+        Ok(job) => Ok((
+            StatusCode::ACCEPTED,
+            Json(SuccessResponse::new(QrRegenerationJobResponse {
+                progress: job.progress(),
+                job,
+            })),
+        )),
+        Err(e) => {
+            error!("Failed to start stale QR regeneration: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new("regeneration_failed", &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Get progress of a regeneration job
+/// GET /qr/regenerate/jobs/{job_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/regenerate/jobs/{job_id}",
+    tag = "qr",
+    params(
+        ("job_id" = String, Path, description = "Regeneration job ID"),
+    ),
+    responses(
+        (status = 200, description = "Job progress", body = SuccessResponse<QrRegenerationJobResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_regeneration_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<QrRegenerationJobResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_generator.get_regeneration_job(&job_id).await {
+        Ok(job) => Ok(Json(SuccessResponse::new(QrRegenerationJobResponse {
+            progress: job.progress(),
+            job,
+        }))),
+        Err(e) => {
+            let (status_code, error_type) = match e {
+                crate::services::qr_generator::QrGeneratorError::JobNotFound => {
+                    (StatusCode::NOT_FOUND, "job_not_found")
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "retrieval_failed")
+            };
+
+            Err((
+                status_code,
+                Json(ErrorResponse::new(error_type, &e.to_string()))
+            ))
+        }
+    }
+}
+
+/// Table layout with inline styles, the only markup Gmail and Outlook keep when pasting
+fn create_signature_html(qr_code: &QrCodeMetadata, listing: Option<&PropertyQrInfo>, link: &str) -> String {
+    // Current listing details, or the generation-time snapshot if the property can't be loaded
+    let metadata = &qr_code.metadata;
+    let (name, location, action) = match listing {
+        Some(listing) => (&listing.property_name, &listing.location, &listing.action),
+        None => (&metadata.property_name, &metadata.location, &metadata.action),
+    };
+
+    format!(
+        r#"<table cellpadding="0" cellspacing="0" border="0" style="border-collapse: collapse; font-family: Arial, Helvetica, sans-serif;">
+  <tr>
+    <td style="padding: 0 12px 0 0; vertical-align: middle;">
+      <a href="{link}" target="_blank"><img src="{}" width="{size}" height="{size}" alt="{}" style="display: block; border: 0; width: {size}px; height: {size}px;"></a>
+    </td>
+    <td style="vertical-align: middle; font-size: 13px; line-height: 18px; color: #333333;">
+      <strong>{}</strong><br>
+      {} &middot; {}<br>
+      <a href="{link}" target="_blank" style="color: #764ba2; text-decoration: none;">View on DAO-Bitat</a>
+    </td>
+  </tr>
+</table>
+"#,
+        escape_html(&qr_code.qr_code_url),
+        escape_html(&format!("QR code for {}", name)),
+        escape_html(name),
+        escape_html(location),
+        escape_html(action),
+        link = escape_html(link),
+        size = SIGNATURE_QR_SIZE,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::QrMetadata;
+
+    #[test]
+    fn test_eligibility_override_needs_admin_key_and_note() {
+        let api_key = "0123456789abcdef0123";
+        let mut headers = HeaderMap::new();
+        let note = || Some("Launch event display unit".to_string());
+
+        assert_eq!(eligibility_override(Some(api_key), &headers, note()).unwrap_err().0, StatusCode::FORBIDDEN);
+
+        headers.insert("x-api-key", api_key.parse().unwrap());
+        assert_eq!(eligibility_override(None, &headers, note()).unwrap_err().0, StatusCode::FORBIDDEN);
+        assert_eq!(eligibility_override(Some(api_key), &headers, Some("  ".to_string())).unwrap_err().0, StatusCode::BAD_REQUEST);
+        assert_eq!(eligibility_override(Some(api_key), &headers, note()).unwrap(), "Launch event display unit");
+    }
+
+    #[test]
+    fn test_signature_html() {
+        let metadata = QrMetadata {
+            property_name: "Garden <Villa> & Co".to_string(),
+            location: "Kilimani".to_string(),
+            action: "for sale".to_string(),
+            price: 10_000_000,
+            onchain_id: None,
+            crypto_accepted: false,
+            primary_image: None,
+            is_verified: true,
+            generated_by: None,
+            generation_reason: QrGenerationReason::NewProperty,
+        };
+        let qr_code = QrCodeMetadata::new(
+            "507f1f77bcf86cd799439011".to_string(),
+            "{}".to_string(),
+            "https://cdn.daobitat.xyz/qr-images/507f1f77bcf86cd799439011.png".to_string(),
+            metadata,
+        );
+
+        insta::assert_snapshot!(create_signature_html(
+            &qr_code,
+            None,
+            "https://qr.daobitat.xyz/scan/507f1f77bcf86cd799439011?source=signature",
+        ));
+
+        // The live listing wins over the snapshot once the owner renames it
+        let listing = PropertyQrInfo {
+            id: mongodb::bson::oid::ObjectId::new(),
+            owner: mongodb::bson::oid::ObjectId::new(),
+            property_name: "Garden Villa".to_string(),
+            location: "Lavington".to_string(),
+            action: "for rent".to_string(),
+            price: 250_000,
+            onchain_id: None,
+            chain_id: None,
+            owner_wallet_address: None,
+            base_name: None,
+            crypto_accepted: false,
+            images: Vec::new(),
+            is_verified: Some(true),
+            removed: None,
+            sold: false,
+            coordinates: None,
+        };
+        let html = create_signature_html(&qr_code, Some(&listing), "https://qr.daobitat.xyz/scan/x");
+        assert!(html.contains("<strong>Garden Villa</strong>"));
+        assert!(html.contains("Lavington &middot; for rent"));
+    }
     
     #[test]
     fn test_error_response_creation() {
@@ -0,0 +1,140 @@
+// src/handlers/qr_style_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{QrStyleResponse, UpsertQrStyleRequest};
+use crate::services::{QrStyleService, qr_style_service::QrStyleError};
+
+// Application state for QR style preset handlers
+#[derive(Clone)]
+pub struct QrStyleAppState {
+    pub qr_style_service: QrStyleService,
+}
+
+fn qr_style_error_response(e: QrStyleError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        QrStyleError::NotFound => (StatusCode::NOT_FOUND, "style_not_found"),
+        QrStyleError::InvalidStyle(_) => (StatusCode::BAD_REQUEST, "invalid_style"),
+        QrStyleError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "style_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// List the named QR styles codes can be generated with
+/// GET /qr/styles
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/styles",
+    tag = "qr",
+    responses(
+        (status = 200, description = "QR styles, by name", body = SuccessResponse<Vec<QrStyleResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn list_qr_styles(
+    State(state): State<Arc<QrStyleAppState>>,
+) -> Result<ResponseJson<SuccessResponse<Vec<QrStyleResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_style_service.list_styles().await {
+        Ok(styles) => Ok(Json(SuccessResponse::new(styles.iter().map(|style| style.to_response()).collect()))),
+        Err(e) => {
+            error!("Failed to list QR styles: {}", e);
+            Err(qr_style_error_response(e))
+        }
+    }
+}
+
+/// Get one named QR style
+/// GET /qr/styles/{name}
+#[utoipa::path(
+    get,
+    path = "/api/v1/qr/styles/{name}",
+    tag = "qr",
+    params(("name" = String, Path, description = "Style name")),
+    responses(
+        (status = 200, description = "QR style", body = SuccessResponse<QrStyleResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_qr_style(
+    State(state): State<Arc<QrStyleAppState>>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<QrStyleResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.qr_style_service.get_style(&name).await {
+        Ok(style) => Ok(Json(SuccessResponse::new(style.to_response()))),
+        Err(e) => {
+            error!("Failed to get QR style '{}': {}", name, e);
+            Err(qr_style_error_response(e))
+        }
+    }
+}
+
+/// Create or replace a named QR style, e.g. a partner brand's colors
+/// PUT /qr/styles/{name}
+#[utoipa::path(
+    put,
+    path = "/api/v1/qr/styles/{name}",
+    tag = "qr",
+    params(("name" = String, Path, description = "Style name: lowercase letters, digits and dashes")),
+    request_body = UpsertQrStyleRequest,
+    responses(
+        (status = 200, description = "QR style saved", body = SuccessResponse<QrStyleResponse>),
+        (status = 400, description = "Invalid name, colors, quiet zone or logo URL", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_qr_style(
+    State(state): State<Arc<QrStyleAppState>>,
+    Path(name): Path<String>,
+    Json(request): Json<UpsertQrStyleRequest>,
+) -> Result<ResponseJson<SuccessResponse<QrStyleResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating QR style '{}'", name);
+
+    match state.qr_style_service.upsert_style(&name, request).await {
+        Ok(style) => Ok(Json(SuccessResponse::new(style.to_response()))),
+        Err(e) => {
+            error!("Failed to update QR style '{}': {}", name, e);
+            Err(qr_style_error_response(e))
+        }
+    }
+}
+
+/// Remove a named QR style; its codes go back to the defaults when next regenerated
+/// DELETE /qr/styles/{name}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/qr/styles/{name}",
+    tag = "qr",
+    params(("name" = String, Path, description = "Style name")),
+    responses(
+        (status = 200, description = "QR style removed", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_qr_style(
+    State(state): State<Arc<QrStyleAppState>>,
+    Path(name): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Removing QR style '{}'", name);
+
+    match state.qr_style_service.delete_style(&name).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "name": name
+        })))),
+        Err(e) => {
+            error!("Failed to remove QR style '{}': {}", name, e);
+            Err(qr_style_error_response(e))
+        }
+    }
+}
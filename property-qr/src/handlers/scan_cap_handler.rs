@@ -0,0 +1,164 @@
+// src/handlers/scan_cap_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{ScanCap, ScanCapResponse, UpsertScanCapRequest};
+use crate::services::{ScanCapService, scan_cap_service::ScanCapError};
+
+// Application state for scan cap handlers
+#[derive(Clone)]
+pub struct ScanCapAppState {
+    pub scan_cap_service: ScanCapService,
+}
+
+fn scan_cap_error_response(e: ScanCapError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        ScanCapError::NotFound => (StatusCode::NOT_FOUND, "scan_cap_not_found"),
+        ScanCapError::InvalidCap(_) => (StatusCode::BAD_REQUEST, "invalid_scan_cap"),
+        ScanCapError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "scan_cap_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// A cap with today's scan count
+async fn cap_response(state: &ScanCapAppState, cap: ScanCap) -> Result<ScanCapResponse, ScanCapError> {
+    let scans_today = state.scan_cap_service.scans_today(&cap.property_id).await?;
+    Ok(cap.to_response(scans_today))
+}
+
+/// Get a property's daily scan cap and whether it is paused
+/// GET /scan-caps/{property_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/scan-caps/{property_id}",
+    tag = "scan-caps",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Scan cap", body = SuccessResponse<ScanCapResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_scan_cap(
+    State(state): State<Arc<ScanCapAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<ScanCapResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let result = match state.scan_cap_service.get_cap(&property_id).await {
+        Ok(cap) => cap_response(&state, cap).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(response) => Ok(Json(SuccessResponse::new(response))),
+        Err(e) => {
+            error!("Failed to get scan cap for property {}: {}", property_id, e);
+            Err(scan_cap_error_response(e))
+        }
+    }
+}
+
+/// Create or replace a property's daily scan cap
+/// PUT /scan-caps/{property_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/scan-caps/{property_id}",
+    tag = "scan-caps",
+    params(("property_id" = String, Path, description = "Property ID")),
+    request_body = UpsertScanCapRequest,
+    responses(
+        (status = 200, description = "Scan cap saved", body = SuccessResponse<ScanCapResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_scan_cap(
+    State(state): State<Arc<ScanCapAppState>>,
+    Path(property_id): Path<String>,
+    Json(request): Json<UpsertScanCapRequest>,
+) -> Result<ResponseJson<SuccessResponse<ScanCapResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating scan cap for property {}", property_id);
+
+    let result = match state.scan_cap_service.upsert_cap(&property_id, request).await {
+        Ok(cap) => cap_response(&state, cap).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(response) => Ok(Json(SuccessResponse::new(response))),
+        Err(e) => {
+            error!("Failed to update scan cap for property {}: {}", property_id, e);
+            Err(scan_cap_error_response(e))
+        }
+    }
+}
+
+/// Remove a property's daily scan cap
+/// DELETE /scan-caps/{property_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/scan-caps/{property_id}",
+    tag = "scan-caps",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Scan cap removed", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_scan_cap(
+    State(state): State<Arc<ScanCapAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Removing scan cap for property {}", property_id);
+
+    match state.scan_cap_service.delete_cap(&property_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "propertyId": property_id
+        })))),
+        Err(e) => {
+            error!("Failed to remove scan cap for property {}: {}", property_id, e);
+            Err(scan_cap_error_response(e))
+        }
+    }
+}
+
+/// Lift today's pause so scans reach the listing again until midnight UTC
+/// POST /scan-caps/{property_id}/resume
+#[utoipa::path(
+    post,
+    path = "/api/v1/scan-caps/{property_id}/resume",
+    tag = "scan-caps",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Scans resumed", body = SuccessResponse<ScanCapResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn resume_scan_cap(
+    State(state): State<Arc<ScanCapAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<ScanCapResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    let result = match state.scan_cap_service.resume(&property_id).await {
+        Ok(cap) => cap_response(&state, cap).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(response) => Ok(Json(SuccessResponse::new(response))),
+        Err(e) => {
+            error!("Failed to resume scans for property {}: {}", property_id, e);
+            Err(scan_cap_error_response(e))
+        }
+    }
+}
@@ -2,20 +2,45 @@
 // src/handlers/scan_handler.rs
 
 use axum::{
-    extract::{Path, Query, State, ConnectInfo},
-    http::{StatusCode, HeaderMap},
+    extract::{Path, Query, Request, State, ConnectInfo},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
     response::{Html, Redirect, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::{sync::Arc, net::SocketAddr};
+use std::{collections::BTreeMap, sync::Arc, net::SocketAddr};
 use tracing::{info, warn, error};
 use axum::response::IntoResponse;
+use utoipa::{IntoParams, ToSchema};
 
+use crate::config::{AppLinkConfig, RedirectTarget};
 use crate::models::{
-    ScanSource, RedirectType, ScanRedirectData, PropertyQrInfo
+    ScanEvent, ScanSource, RedirectType, ScanRedirectData, PropertyQrInfo,
+    ForwardedScan, TrackingConsent, ConversionType, DeviceInfo, UtmParameters, JoinWaitlistRequest,
+    WaitlistReason, QrCodeMetadata, QrCodeWindow, FunnelStage, AutoRedirectDestination, ScanOutcome,
+    DocumentVerification, DocumentVerificationStatus, LandingVariant, ScanExperiment,
 };
-use crate::services::{QrGeneratorService, PropertyService, AnalyticsService};
+use crate::services::{
+    QrGeneratorService, PropertyService, AnalyticsService, TrackingService, LinkService, SmsService,
+    GeoBlockService, ScanCapService, WaitlistService, PageCache, AutoRedirectService, BlockchainService,
+    DocumentQrService, ShareOfferService, share_offer_service::ShareOfferError,
+    BookingCheckinService, booking_checkin_service::BookingCheckinError, ExperimentService,
+    auto_redirect_service::resolve_destination, geo_block_service::blocking_policy,
+    sms_service::SmsError, waitlist_service::WaitlistError,
+};
+use crate::utils::{escape_html, js_string_literal, translate, HostPolicy, Locale, SessionSigner, UrlBuilder, UrlValidator};
+
+// Cookie used to recognise returning visitors across scans
+const VISITOR_COOKIE_NAME: &str = "dbqr_visitor";
+const VISITOR_COOKIE_MAX_AGE: u64 = 60 * 60 * 24 * 365; // 1 year
+
+// Signed cookie grouping a visitor's repeated scans into one visit
+const SESSION_COOKIE_NAME: &str = "dbqr_session";
+const SESSION_COOKIE_MAX_AGE: u64 = 60 * 30; // 30 minutes, renewed on every scan
+
+// Cookie set by the landing page's consent banner
+const CONSENT_COOKIE_NAME: &str = "dbqr_consent";
 
 // Application state for scan handlers
 #[derive(Clone)]
@@ -23,39 +48,116 @@ pub struct ScanAppState {
     pub qr_generator: QrGeneratorService,
     pub property_service: PropertyService,
     pub analytics_service: AnalyticsService,
+    pub tracking_service: TrackingService,
+    pub link_service: LinkService,
+    pub sms_service: SmsService,
+    pub geo_block_service: GeoBlockService,
+    pub scan_cap_service: ScanCapService,
+    pub waitlist_service: WaitlistService,
+    pub document_qr_service: DocumentQrService,
+    pub share_offer_service: ShareOfferService,
+    pub booking_checkin_service: BookingCheckinService,
+    pub experiment_service: ExperimentService,
+    pub app_links: AppLinkConfig,
+    pub session_signer: SessionSigner,
+    pub auto_redirect_seconds: Option<u64>, // Dual page countdown; 0 redirects instantly, None never does
+    pub default_target: RedirectTarget, // Used until an auto-redirect config is stored
+    pub auto_redirect_service: AutoRedirectService,
+    pub host_policy: HostPolicy,
+    pub image_domains: Vec<String>, // Hosts primary images may be loaded from
     pub daobitar_base_url: String,
     pub blockchain_explorer_base_url: String,
+    pub blockchain_explorers: BTreeMap<u64, String>, // Per chain ID, for listings that say which chain they're on
+    pub blockchain_service: Option<BlockchainService>, // Confirms on-chain ownership for the dual page badge
+    pub page_cache: PageCache, // Rendered dual pages, shared with the QR generator for invalidation
 }
 
 // Query parameters for scan redirects
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ScanQuery {
-    pub source: Option<String>,        // "qr", "direct", "share", etc.
+    pub source: Option<String>,        // "qr", "direct", "share", "signature", etc.
     pub redirect: Option<String>,      // "dual", "property", "blockchain"
     pub ref_: Option<String>,          // Referrer information
     pub utm_source: Option<String>,    // UTM tracking
     pub utm_medium: Option<String>,
     pub utm_campaign: Option<String>,
+    pub consent: Option<String>,       // "granted" / "denied" from the consent banner
+    pub lang: Option<String>,          // "en" / "sw", overrides Accept-Language
+    pub no_redirect: Option<String>,   // "1" keeps the dual page from navigating on its own
+    pub v: Option<i32>,                // QR version printed in the code; absent on codes printed before versioning
+}
+
+/// Where and after how long the dual page navigates on its own
+#[derive(Debug, Clone, PartialEq)]
+struct AutoRedirect {
+    url: String,
+    seconds: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SendListingSmsRequest {
+    pub phone: String,
 }
 
-#[derive(Debug, Serialize)]
+// Sent by the dual page with navigator.sendBeacon as the visitor moves through it
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FunnelBeaconRequest {
+    #[serde(rename = "scanId")]
+    pub scan_id: String,
+    pub stage: FunnelStage,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ScanResponse {
     pub success: bool,
     pub property_id: String,
     pub redirect_type: String,
     pub urls: RedirectUrls,
     pub scan_id: String,
+    pub property: PropertySummary, // Enough for the app to render the scan without a second request
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PropertySummary {
+    pub name: String,
+    pub price: i64,
+    pub action: String, // "for sale" | "for rent"
+    pub location: String,
+    pub primary_image: Option<String>, // Only from allowed image hosts, as on the landing page
+    pub is_verified: bool,
+    pub crypto_accepted: bool,
+    pub base_name: Option<String>, // Confirmed Base name, e.g. kilimani-flat.base.eth
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RedirectUrls {
     pub property_url: String,
     pub blockchain_url: Option<String>,
+    pub payment_url: Option<String>, // DAO-Bitat crypto checkout, for listings that accept crypto
     pub redirect_page_url: String,
+    pub app_url: Option<String>, // Set for phones and tablets when app links are enabled
+    pub custom_redirect_url: Option<String>, // Owner's temporary redirect, which scans follow instead
 }
 
 /// Handle QR code scan with property ID
 /// GET /scan/{property_id}
+#[utoipa::path(
+    get,
+    path = "/scan/{property_id}",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ScanQuery,
+    ),
+    responses(
+        (status = 200, description = "Dual-redirect landing page, or an app hand-off page on phones and tablets (text/html)"),
+        (status = 308, description = "Redirect to the property page or blockchain explorer"),
+        (status = 403, description = "Scheduled code scanned before its window opens (text/html)"),
+        (status = 410, description = "Scheduled code's event has ended, or a limited-use code has no scans left (text/html)"),
+        (status = 451, description = "Property not available in the visitor's region (text/html)"),
+    )
+)]
 pub async fn scan_qr_code(
     State(state): State<Arc<ScanAppState>>,
     Path(property_id): Path<String>,
@@ -75,6 +177,23 @@ pub async fn scan_qr_code(
         .and_then(|h| h.to_str().ok())
         .map(|s| s.to_string());
 
+    // Reuse the visitor cookie if present, otherwise issue one from the IP+UA hash
+    let existing_visitor_id = extract_visitor_cookie(&headers);
+    let is_new_visitor = existing_visitor_id.is_none();
+    let visitor_id = existing_visitor_id.unwrap_or_else(|| {
+        ScanEvent::visitor_hash(Some(&ip_address), user_agent.as_deref())
+    });
+
+    // Continue the visit if the session cookie checks out, otherwise start a new one
+    let session_id = extract_session_cookie(&headers, &state.session_signer)
+        .unwrap_or_else(SessionSigner::new_session_id);
+
+    // Landing and error pages follow ?lang=, then the browser's languages
+    let locale = Locale::negotiate(
+        query.lang.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|h| h.to_str().ok()),
+    );
+
     // Determine scan source
     let scan_source = match query.source.as_deref() {
         Some("qr") => ScanSource::QrCode,
@@ -82,11 +201,12 @@ pub async fn scan_qr_code(
         Some("share") => ScanSource::ShareLink,
         Some("search") => ScanSource::SearchEngine,
         Some("social") => ScanSource::SocialMedia,
+        Some("signature") => ScanSource::EmailSignature,
         _ => ScanSource::QrCode, // Default assumption
     };
 
     // Get property information
-    let property_info = match state.property_service.get_property_qr_info(&property_id).await {
+    let property_info = match state.property_service.get_live_listing(&property_id).await {
         Ok(info) => info,
         Err(_) => {
             warn!("Property not found for scan: {}", property_id);
@@ -99,16 +219,38 @@ pub async fn scan_qr_code(
                 Some(ip_address),
             ).await;
             
-            return Ok(Html(create_error_page("Property not found", &property_id)).into_response());
+            let title = translate(locale, "error.property_not_found");
+            return Ok(localized(Html(create_error_page(title, &property_id, locale)), locale));
         }
     };
 
+    if is_region_blocked(&state, &property_info, user_agent.as_deref(), &ip_address).await {
+        let mut response = localized(Html(create_blocked_page(locale)), locale);
+        *response.status_mut() = StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS;
+        return Ok(response);
+    }
+
+    if let Some(reason) = waitlist_reason(&state, &property_info).await {
+        let html_page = create_waitlist_page(&property_info.property_name, &property_id, reason, locale);
+        return Ok(localized(Html(html_page), locale));
+    }
+
+    let qr_code = state.qr_generator.get_qr_code(&property_id).await.ok();
+
+    // An owner's temporary redirect takes over unless the scan asked for a specific destination
+    let custom_redirect_url = match query.redirect {
+        Some(_) => None,
+        None => custom_redirect_url(qr_code.as_ref()),
+    };
+
     // Determine redirect type
     let redirect_type = match query.redirect.as_deref() {
         Some("property") => RedirectType::DaobitarOnly,
         Some("blockchain") => RedirectType::BlockchainOnly,
         Some("dual") | _ => {
-            if property_info.onchain_id.is_some() {
+            if custom_redirect_url.is_some() {
+                RedirectType::CustomRedirect
+            } else if property_info.onchain_id.is_some() {
                 RedirectType::DualRedirect
             } else {
                 RedirectType::DaobitarOnly
@@ -116,18 +258,84 @@ pub async fn scan_qr_code(
         }
     };
 
+    // Phones and tablets open the app unless the scan asked for a specific destination
+    let app_device = user_agent.as_deref()
+        .filter(|_| query.redirect.is_none())
+        .map(DeviceInfo::from_user_agent);
+
+    let consent = extract_tracking_consent(&headers, &query);
+    let forwarded_scan = ForwardedScan {
+        scan_id: String::new(),
+        property_id: property_id.clone(),
+        visitor_id: visitor_id.clone(),
+        scan_source: scan_source_name(&scan_source).to_string(),
+        user_agent: user_agent.clone(),
+        ip_address: Some(ip_address.clone()),
+        scanned_at: chrono::Utc::now(),
+    };
+
+    if let Some(response) = schedule_page(qr_code.as_ref(), &property_info.property_name, locale) {
+        info!("Scheduled QR code for property {} scanned outside its window", property_id);
+        return Ok(response);
+    }
+
+    if !count_qr_scan(&state, &property_id, qr_code.as_ref(), user_agent.as_deref()).await {
+        info!("Limited-use QR code for property {} has no scans left", property_id);
+        let title = translate(locale, "error.scan_limit_reached");
+        let mut response = localized(Html(create_error_page(title, &property_id, locale)), locale);
+        *response.status_mut() = StatusCode::GONE;
+        return Ok(response);
+    }
+
+    // Generate URLs
+    let property_url = format!("{}/property/{}", state.daobitar_base_url, property_id);
+    let blockchain_url = blockchain_url(&state, &property_info);
+    let app_url = app_device.and_then(|device| {
+        app_link_url(&state.app_links, &device, &property_id, &property_url)
+    });
+
+    // Decided before recording so only scans that are shown the dual page count towards an
+    // experiment; ones sent on instantly never saw a variant
+    let dual_page = match (&redirect_type, &app_url) {
+        (RedirectType::DualRedirect, None) => Some(auto_redirect(
+            state.auto_redirect_seconds,
+            auto_redirect_destination(&state, &property_id, &visitor_id).await,
+            query.no_redirect.as_deref(),
+            &property_url,
+            blockchain_url.as_deref(),
+        )),
+        _ => None,
+    };
+    let landing_variant = if shows_dual_page(&dual_page) {
+        landing_variant(&state, &property_id, &session_id).await
+    } else {
+        None
+    };
+
     // Record scan analytics
     let scan_id = match state.analytics_service.record_scan(
         property_id.clone(),
-        1, // QR version - would get from QR metadata
+        scanned_qr_version(&query),
+        qr_code.as_ref().map(|qr_code| qr_code.qr_version),
         scan_source,
         redirect_type.clone(),
         user_agent,
         Some(ip_address),
-        None, // session_id
+        Some(session_id.clone()),
+        Some(visitor_id.clone()),
         referrer,
+        utm_from_query(&query),
+        landing_variant.as_ref().map(|(experiment, _)| experiment.clone()),
     ).await {
-        Ok(id) => id,
+        Ok(id) => {
+            // Forward to the owner's GA4 / Meta destinations if consent allows
+            state.tracking_service.forward_scan(
+                &property_info.owner.to_hex(),
+                ForwardedScan { scan_id: id.to_hex(), ..forwarded_scan },
+                consent,
+            );
+            id
+        }
         Err(e) => {
             error!("Failed to record scan analytics: {}", e);
             mongodb::bson::oid::ObjectId::new() // Fallback
@@ -137,55 +345,119 @@ pub async fn scan_qr_code(
     // Update property click count
     let _ = state.property_service.increment_property_clicks(&property_id).await;
 
-    // Generate URLs
-    let property_url = format!("{}/property/{}", state.daobitar_base_url, property_id);
-    let blockchain_url = property_info.onchain_id.as_ref().map(|onchain_id| {
-        format!("{}/token/{}", state.blockchain_explorer_base_url, onchain_id)
-    });
-
     // Handle different redirect types
-    match redirect_type {
-        RedirectType::DaobitarOnly => {
+    let response = match (redirect_type, app_url) {
+        (RedirectType::DaobitarOnly | RedirectType::DualRedirect, Some(app_url)) => {
+            info!("Opening property in the mobile app: {}", property_id);
+            let html_page = create_app_redirect_page(&app_url, &property_url, locale);
+            localized(Html(html_page), locale)
+        }
+        (RedirectType::DaobitarOnly, None) => {
             info!("Redirecting to DAO-Bitat property page: {}", property_id);
-            Ok(Redirect::permanent(&property_url).into_response())
+            Redirect::permanent(&property_url).into_response()
         }
-        RedirectType::BlockchainOnly => {
+        (RedirectType::CustomRedirect, _) => {
+            let custom_redirect_url = custom_redirect_url.unwrap_or_else(|| property_url.clone());
+            info!("Redirecting to custom URL for property {}: {}", property_id, custom_redirect_url);
+            // Temporary, so browsers come back here once the owner clears it
+            Redirect::temporary(&custom_redirect_url).into_response()
+        }
+        (RedirectType::BlockchainOnly, _) => {
             if let Some(blockchain_url) = blockchain_url {
                 info!("Redirecting to blockchain explorer: {}", property_id);
-                Ok(Redirect::permanent(&blockchain_url).into_response())
+                Redirect::permanent(&blockchain_url).into_response()
             } else {
                 warn!("Blockchain redirect requested but no onchain_id: {}", property_id);
-                Ok(Redirect::permanent(&property_url).into_response())
+                Redirect::permanent(&property_url).into_response()
             }
         }
-        RedirectType::DualRedirect => {
-            info!("Showing dual redirect page for property: {}", property_id);
-            let redirect_data = ScanRedirectData {
-                property_id: property_id.clone(),
-                property_name: property_info.property_name.clone(),
-                location: Some(property_info.location.clone()),
-                daobitar_url: property_url,
-                blockchain_url,
-                action: property_info.action,
-                price: property_info.price,
-                primary_image: property_info.images.first().cloned(),
-                is_verified: property_info.is_verified.unwrap_or(false),
-                crypto_accepted: property_info.crypto_accepted,
-                scan_id,
-            };
-            
-            let html_page = create_redirect_page(&redirect_data);
-            Ok(Html(html_page).into_response())
-        }
-        RedirectType::Failed => {
+        (RedirectType::DualRedirect, None) => match dual_page.flatten() {
+            Some(AutoRedirect { url, seconds: 0 }) => {
+                info!("Redirecting instantly instead of showing the dual page: {}", property_id);
+                // The destination follows the owner's settings, so browsers mustn't cache it
+                Redirect::temporary(&url).into_response()
+            }
+            auto_redirect => {
+                info!("Showing dual redirect page for property: {}", property_id);
+                let onchain_verified_at = state.blockchain_service.as_ref()
+                    .and_then(|service| service.verification(&property_info))
+                    .filter(|verification| verification.verified)
+                    .map(|verification| verification.checked_at);
+                let variant = format!(
+                    "dual:{}:{}:{}:{}",
+                    locale.code(),
+                    auto_redirect.as_ref().map_or("none".to_string(), |r| format!("{}:{}", r.seconds, r.url)),
+                    onchain_verified_at.map_or(0, |checked_at| checked_at.timestamp()),
+                    landing_variant.as_ref().map_or("none".to_string(), |(experiment, _)| {
+                        format!("{}:{}", experiment.experiment_id, experiment.variant)
+                    }),
+                );
+                if let Some(cached) = state.page_cache.get(&property_id, &variant) {
+                    return Ok(finish_scan_response(
+                        localized(Html(fill_scan_id(&cached, &scan_id)), locale),
+                        &state,
+                        &visitor_id,
+                        is_new_visitor,
+                        &session_id,
+                    ));
+                }
+
+                // Rendered without this scan's ID so the page can be cached and reused
+                let redirect_data = ScanRedirectData {
+                    property_id: property_id.clone(),
+                    property_name: property_info.property_name.clone(),
+                    location: Some(property_info.location.clone()),
+                    daobitar_url: property_url,
+                    blockchain_url,
+                    action: property_info.action,
+                    price: property_info.price,
+                    primary_image: property_info.images.first()
+                        .filter(|url| is_allowed_image_url(url, &state.image_domains))
+                        .cloned(),
+                    is_verified: property_info.is_verified.unwrap_or(false),
+                    crypto_accepted: property_info.crypto_accepted,
+                    base_name: property_info.base_name.as_deref().and_then(UrlBuilder::resolve_base_name),
+                    onchain_verified_at,
+                    variant: landing_variant.map(|(_, variant)| variant),
+                    scan_id: scan_id_slot(),
+                };
+
+                let canonical_url = state.host_policy.canonical_url(&format!("scan/{}", property_id));
+                let html_page = create_redirect_page(&redirect_data, &canonical_url, auto_redirect.as_ref(), locale);
+                let response = localized(Html(fill_scan_id(&html_page, &scan_id)), locale);
+                state.page_cache.store(&property_id, &variant, html_page);
+                response
+            }
+        },
+        // Document and share offer codes have their own routes, never a listing's scan
+        (RedirectType::Failed | RedirectType::DocumentVerification | RedirectType::ShareOffer, _) => {
             error!("Scan failed for property: {}", property_id);
-            Ok(Html(create_error_page("Scan failed", &property_id)).into_response())
+            let title = translate(locale, "error.scan_failed");
+            localized(Html(create_error_page(title, &property_id, locale)), locale)
         }
-    }
+    };
+
+    Ok(finish_scan_response(response, &state, &visitor_id, is_new_visitor, &session_id))
 }
 
 /// API endpoint to get scan redirect data as JSON
 /// GET /api/scan/{property_id}
+#[utoipa::path(
+    get,
+    path = "/api/scan/{property_id}",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ScanQuery,
+    ),
+    responses(
+        (status = 200, description = "Scan redirect data", body = ScanResponse),
+        (status = 403, description = "Scheduled code scanned before its window opens"),
+        (status = 404, description = "Property not found"),
+        (status = 410, description = "Scheduled code's event has ended, or a limited-use code has no scans left"),
+        (status = 451, description = "Property not available in the visitor's region"),
+    )
+)]
 pub async fn get_scan_data(
     State(state): State<Arc<ScanAppState>>,
     Path(property_id): Path<String>,
@@ -201,9 +473,11 @@ pub async fn get_scan_data(
         .map(|s| s.to_string());
     
     let ip_address = addr.ip().to_string();
+    let visitor_id = extract_visitor_cookie(&headers);
+    let session_id = extract_session_cookie(&headers, &state.session_signer);
 
     // Get property information
-    let property_info = match state.property_service.get_property_qr_info(&property_id).await {
+    let property_info = match state.property_service.get_live_listing(&property_id).await {
         Ok(info) => info,
         Err(_) => {
             return Err((
@@ -216,37 +490,125 @@ pub async fn get_scan_data(
         }
     };
 
+    if is_region_blocked(&state, &property_info, user_agent.as_deref(), &ip_address).await {
+        return Err((
+            StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+            Json(serde_json::json!({
+                "error": "region_blocked",
+                "message": "This property is not available in your region"
+            }))
+        ));
+    }
+
+    if is_scan_capped(&state, &property_id).await {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "error": "scan_cap_reached",
+                "message": "This property has reached its scan limit for today; visitors can join the waitlist"
+            }))
+        ));
+    }
+
     // Determine scan source and redirect type
     let scan_source = match query.source.as_deref() {
         Some("qr") => ScanSource::QrCode,
         Some("direct") => ScanSource::DirectLink,
+        Some("signature") => ScanSource::EmailSignature,
         _ => ScanSource::QrCode,
     };
 
-    let redirect_type = if property_info.onchain_id.is_some() {
+    let qr_code = state.qr_generator.get_qr_code(&property_id).await.ok();
+    let custom_redirect_url = custom_redirect_url(qr_code.as_ref());
+    let redirect_type = if custom_redirect_url.is_some() {
+        RedirectType::CustomRedirect
+    } else if property_info.onchain_id.is_some() {
         RedirectType::DualRedirect
     } else {
         RedirectType::DaobitarOnly
     };
+    let app_device = user_agent.as_deref().map(DeviceInfo::from_user_agent);
+
+    let consent = extract_tracking_consent(&headers, &query);
+    let forwarded_scan = ForwardedScan {
+        scan_id: String::new(),
+        property_id: property_id.clone(),
+        visitor_id: visitor_id.clone().unwrap_or_else(|| {
+            ScanEvent::visitor_hash(Some(&ip_address), user_agent.as_deref())
+        }),
+        scan_source: scan_source_name(&scan_source).to_string(),
+        user_agent: user_agent.clone(),
+        ip_address: Some(ip_address.clone()),
+        scanned_at: chrono::Utc::now(),
+    };
+
+    match qr_code.as_ref().map(|qr_code| qr_code.window(chrono::Utc::now())) {
+        Some(QrCodeWindow::NotStarted(opens)) => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": "not_yet_active",
+                    "message": "This QR code isn't active yet",
+                    "activeFrom": opens
+                }))
+            ));
+        }
+        Some(QrCodeWindow::Ended) => {
+            return Err((
+                StatusCode::GONE,
+                Json(serde_json::json!({
+                    "error": "event_ended",
+                    "message": "This event has ended"
+                }))
+            ));
+        }
+        _ => {}
+    }
+
+    if !count_qr_scan(&state, &property_id, qr_code.as_ref(), user_agent.as_deref()).await {
+        return Err((
+            StatusCode::GONE,
+            Json(serde_json::json!({
+                "error": "scan_limit_reached",
+                "message": "This QR code has been used the maximum number of times"
+            }))
+        ));
+    }
 
     // Record scan
-    let scan_id = state.analytics_service.record_scan(
+    let scan_id = match state.analytics_service.record_scan(
         property_id.clone(),
-        1,
+        scanned_qr_version(&query),
+        qr_code.as_ref().map(|qr_code| qr_code.qr_version),
         scan_source,
         redirect_type.clone(),
         user_agent,
         Some(ip_address),
+        session_id,
+        visitor_id,
         None,
+        utm_from_query(&query),
         None,
-    ).await.unwrap_or_else(|_| mongodb::bson::oid::ObjectId::new());
+    ).await {
+        Ok(id) => {
+            state.tracking_service.forward_scan(
+                &property_info.owner.to_hex(),
+                ForwardedScan { scan_id: id.to_hex(), ..forwarded_scan },
+                consent,
+            );
+            id
+        }
+        Err(_) => mongodb::bson::oid::ObjectId::new(),
+    };
 
     // Generate URLs
     let property_url = format!("{}/property/{}", state.daobitar_base_url, property_id);
-    let blockchain_url = property_info.onchain_id.as_ref().map(|onchain_id| {
-        format!("{}/token/{}", state.blockchain_explorer_base_url, onchain_id)
+    let blockchain_url = blockchain_url(&state, &property_info);
+    let payment_url = payment_url(&state.daobitar_base_url, &property_info);
+    let redirect_page_url = state.host_policy.canonical_url(&format!("scan/{}", property_id));
+    let app_url = app_device.and_then(|device| {
+        app_link_url(&state.app_links, &device, &property_id, &property_url)
     });
-    let redirect_page_url = format!("{}/scan/{}", state.daobitar_base_url, property_id);
 
     let response = ScanResponse {
         success: true,
@@ -255,83 +617,1034 @@ pub async fn get_scan_data(
             RedirectType::DualRedirect => "dual".to_string(),
             RedirectType::DaobitarOnly => "property".to_string(),
             RedirectType::BlockchainOnly => "blockchain".to_string(),
+            RedirectType::CustomRedirect => "custom".to_string(),
+            RedirectType::DocumentVerification => "document".to_string(),
+            RedirectType::ShareOffer => "share_offer".to_string(),
             RedirectType::Failed => "failed".to_string(),
         },
         urls: RedirectUrls {
             property_url,
             blockchain_url,
+            payment_url,
             redirect_page_url,
+            app_url,
+            custom_redirect_url,
         },
         scan_id: scan_id.to_hex(),
+        property: property_summary(&property_info, &state.image_domains),
     };
 
     Ok(Json(response))
 }
 
+/// Text the property's short link to a visitor ("Text me this listing")
+/// POST /api/scan/{property_id}/sms
+#[utoipa::path(
+    post,
+    path = "/api/scan/{property_id}/sms",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    request_body = SendListingSmsRequest,
+    responses(
+        (status = 200, description = "Text message sent"),
+        (status = 400, description = "Invalid phone number"),
+        (status = 404, description = "Property not found"),
+        (status = 429, description = "Too many requests from this client"),
+        (status = 502, description = "SMS provider rejected the message"),
+        (status = 503, description = "SMS is not configured"),
+    )
+)]
+pub async fn send_listing_sms(
+    State(state): State<Arc<ScanAppState>>,
+    Path(property_id): Path<String>,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(request): Json<SendListingSmsRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    info!("SMS listing request for property: {}", property_id);
+
+    let sms_error = |e: SmsError| {
+        let (status_code, error_type) = match e {
+            SmsError::Disabled => (StatusCode::SERVICE_UNAVAILABLE, "sms_disabled"),
+            SmsError::RateLimited => (StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+            SmsError::InvalidPhone(_) => (StatusCode::BAD_REQUEST, "invalid_phone"),
+            SmsError::ProviderError(_) => (StatusCode::BAD_GATEWAY, "sms_send_failed"),
+        };
+        (status_code, Json(serde_json::json!({
+            "error": error_type,
+            "message": e.to_string()
+        })))
+    };
+
+    if !state.sms_service.is_enabled() {
+        return Err(sms_error(SmsError::Disabled));
+    }
+    state.sms_service.check_rate_limit(&addr.ip().to_string()).map_err(sms_error)?;
+
+    let property_info = state.property_service.get_live_listing(&property_id).await
+        .map_err(|_| (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "property_not_found",
+                "message": "Property not found"
+            }))
+        ))?;
+
+    let link = state.link_service.get_or_create_channel_link(&property_id, "sms").await
+        .map_err(|e| {
+            error!("Failed to create SMS short link for {}: {}", property_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "link_creation_failed",
+                    "message": "Could not create a link for this property"
+                }))
+            )
+        })?;
+    let short_url = link.to_response(state.link_service.base_url()).short_url;
+
+    let message = format!(
+        "{} in {} on DAO-Bitat: {}",
+        property_info.property_name, property_info.location, short_url
+    );
+    state.sms_service.send(&request.phone, &message).await.map_err(|e| {
+        warn!("Failed to send listing SMS for {}: {}", property_id, e);
+        sms_error(e)
+    })?;
+
+    let visitor_id = extract_visitor_cookie(&headers);
+    if let Err(e) = state.analytics_service.record_conversion(
+        property_id.clone(),
+        ConversionType::SmsShare,
+        visitor_id,
+    ).await {
+        error!("Failed to record SMS conversion for {}: {}", property_id, e);
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "propertyId": property_id,
+        "shortUrl": short_url
+    })))
+}
+
+/// Funnel beacon from the dual landing page: page view, auto-redirect or which button was clicked
+/// POST /api/scan/{property_id}/funnel
+#[utoipa::path(
+    post,
+    path = "/api/scan/{property_id}/funnel",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    request_body = FunnelBeaconRequest,
+    responses(
+        (status = 204, description = "Stage recorded, or already recorded for this scan"),
+        (status = 400, description = "Invalid scan ID"),
+    )
+)]
+pub async fn record_funnel_event(
+    State(state): State<Arc<ScanAppState>>,
+    Path(property_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<FunnelBeaconRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let scan_id = mongodb::bson::oid::ObjectId::parse_str(&request.scan_id).map_err(|_| (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": "invalid_scan_id",
+            "message": "Scan ID is not valid"
+        }))
+    ))?;
+
+    // Beacons are fire-and-forget, so a failed write is only logged
+    if let Err(e) = state.analytics_service.record_funnel_event(
+        &property_id,
+        scan_id,
+        request.stage,
+        extract_visitor_cookie(&headers),
+    ).await {
+        error!("Failed to record {} funnel event for {}: {}", request.stage.as_str(), property_id, e);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScanOutcomeQuery {
+    /// Scan the dual page was rendered for
+    pub scan: Option<String>,
+}
+
+/// Tracked dual page button: records which destination the visitor picked, then sends them there
+/// GET /scan/{property_id}/go/{outcome}
+#[utoipa::path(
+    get,
+    path = "/scan/{property_id}/go/{outcome}",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ("outcome" = ScanOutcome, Path, description = "property, blockchain or crypto_payment"),
+        ScanOutcomeQuery,
+    ),
+    responses(
+        (status = 303, description = "Redirect to the property page, blockchain explorer or crypto checkout"),
+        (status = 400, description = "Unknown outcome"),
+    )
+)]
+pub async fn follow_scan_outcome(
+    State(state): State<Arc<ScanAppState>>,
+    Path((property_id, outcome)): Path<(String, ScanOutcome)>,
+    Query(query): Query<ScanOutcomeQuery>,
+    headers: HeaderMap,
+) -> Redirect {
+    // Destinations are rebuilt here rather than taken from the link, so this can't be used as an open redirect
+    let property_url = format!("{}/property/{}", state.daobitar_base_url, property_id);
+    let destination = match outcome {
+        ScanOutcome::Property => property_url,
+        ScanOutcome::Blockchain => match state.property_service.get_live_listing(&property_id).await {
+            Ok(property_info) => blockchain_url(&state, &property_info).unwrap_or(property_url),
+            Err(_) => property_url,
+        },
+        ScanOutcome::CryptoPayment => match state.property_service.get_live_listing(&property_id).await {
+            Ok(property_info) => payment_url(&state.daobitar_base_url, &property_info).unwrap_or(property_url),
+            Err(_) => property_url,
+        },
+    };
+
+    // The visitor is on their way regardless of whether the click could be recorded
+    match query.scan.as_deref().map(mongodb::bson::oid::ObjectId::parse_str) {
+        Some(Ok(scan_id)) => {
+            let stage = outcome.funnel_stage();
+            if let Err(e) = state.analytics_service.record_funnel_event(
+                &property_id,
+                scan_id,
+                stage,
+                extract_visitor_cookie(&headers),
+            ).await {
+                error!("Failed to record {} funnel event for {}: {}", stage.as_str(), property_id, e);
+            }
+        }
+        Some(Err(_)) => warn!("Ignoring click with an invalid scan ID for {}", property_id),
+        None => {}
+    }
+
+    Redirect::to(&destination)
+}
+
+/// Explorer page of an on-chain listing's token, on the explorer for the chain it lives on
+fn blockchain_url(state: &ScanAppState, property_info: &PropertyQrInfo) -> Option<String> {
+    explorer_token_url(&state.blockchain_explorers, &state.blockchain_explorer_base_url, property_info)
+}
+
+fn explorer_token_url(
+    explorers: &BTreeMap<u64, String>,
+    default_explorer: &str,
+    property_info: &PropertyQrInfo,
+) -> Option<String> {
+    let onchain_id = property_info.onchain_id.as_ref()?;
+    let explorer = property_info.chain_id
+        .and_then(|chain_id| explorers.get(&chain_id))
+        .map(String::as_str)
+        .unwrap_or(default_explorer);
+    Some(format!("{}/token/{}", explorer, onchain_id))
+}
+
+/// DAO-Bitat's crypto checkout with the listing and its price filled in; it asks the visitor to connect a wallet
+fn payment_url(daobitar_base_url: &str, property_info: &PropertyQrInfo) -> Option<String> {
+    if !property_info.crypto_accepted {
+        return None;
+    }
+    Some(format!(
+        "{}/pay/{}?amount={}&currency=KES&connect=wallet",
+        daobitar_base_url,
+        property_info.id.to_hex(),
+        property_info.price
+    ))
+}
+
+/// Tracked link for one of the dual page's buttons
+fn outcome_url(data: &ScanRedirectData, outcome: ScanOutcome) -> String {
+    let outcome = match outcome {
+        ScanOutcome::Property => "property",
+        ScanOutcome::Blockchain => "blockchain",
+        ScanOutcome::CryptoPayment => "crypto_payment",
+    };
+    format!(
+        "/scan/{}/go/{}?scan={}",
+        urlencoding::encode(&data.property_id),
+        outcome,
+        data.scan_id.to_hex()
+    )
+}
+
+/// Join the waitlist of a property that is sold or has reached its daily scan cap
+/// POST /api/scan/{property_id}/waitlist
+#[utoipa::path(
+    post,
+    path = "/api/scan/{property_id}/waitlist",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+    ),
+    request_body = JoinWaitlistRequest,
+    responses(
+        (status = 200, description = "Visitor is on the waitlist"),
+        (status = 400, description = "Missing or invalid email and phone number"),
+        (status = 404, description = "Property not found"),
+        (status = 409, description = "Property can be viewed, so there is no waitlist to join"),
+    )
+)]
+pub async fn join_waitlist(
+    State(state): State<Arc<ScanAppState>>,
+    Path(property_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<JoinWaitlistRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let property_info = state.property_service.get_property_qr_info(&property_id).await
+        .map_err(|_| (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "property_not_found",
+                "message": "Property not found"
+            }))
+        ))?;
+
+    let Some(reason) = waitlist_reason(&state, &property_info).await else {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "waitlist_closed",
+                "message": "This property can be viewed now, so there is no waitlist to join"
+            }))
+        ));
+    };
+
+    let visitor_id = extract_visitor_cookie(&headers);
+    match state.waitlist_service.join(&property_info, reason, request, visitor_id).await {
+        Ok(entry) => Ok(Json(serde_json::json!({
+            "success": true,
+            "propertyId": property_id,
+            "reason": entry.reason,
+            "joinedAt": entry.created_at
+        }))),
+        Err(e) => {
+            warn!("Failed to add visitor to the waitlist for {}: {}", property_id, e);
+            let (status_code, error_type) = match e {
+                WaitlistError::InvalidContact(_) => (StatusCode::BAD_REQUEST, "invalid_contact"),
+                WaitlistError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "waitlist_failed"),
+            };
+            Err((status_code, Json(serde_json::json!({
+                "error": error_type,
+                "message": e.to_string()
+            }))))
+        }
+    }
+}
+
+/// Handle a scan of a property document's QR code: the document's verification page
+/// GET /scan/{property_id}/documents/{document_id}
+#[utoipa::path(
+    get,
+    path = "/scan/{property_id}/documents/{document_id}",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ("document_id" = String, Path, description = "Document ID"),
+        ScanQuery,
+    ),
+    responses(
+        (status = 200, description = "Document verification page (text/html)"),
+        (status = 404, description = "No active QR code for the document (text/html)"),
+    )
+)]
+pub async fn scan_document_qr(
+    State(state): State<Arc<ScanAppState>>,
+    Path((property_id, document_id)): Path<(String, String)>,
+    Query(query): Query<ScanQuery>,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> Response {
+    info!("QR code scan for document {} of property {}", document_id, property_id);
+
+    let locale = Locale::negotiate(
+        query.lang.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|h| h.to_str().ok()),
+    );
+
+    let verification = match state.document_qr_service.verification(&property_id, &document_id).await {
+        Ok(verification) => verification,
+        Err(e) => {
+            warn!("Document QR scan for {}/{} not served: {}", property_id, document_id, e);
+            let title = translate(locale, "document.not_found");
+            let mut response = localized(Html(create_error_page(title, &property_id, locale)), locale);
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            return response;
+        }
+    };
+
+    let user_agent = headers.get("user-agent")
+        .and_then(|h| h.to_str().ok());
+    let ip_address = addr.ip().to_string();
+
+    let existing_visitor_id = extract_visitor_cookie(&headers);
+    let is_new_visitor = existing_visitor_id.is_none();
+    let visitor_id = existing_visitor_id.unwrap_or_else(|| {
+        ScanEvent::visitor_hash(Some(&ip_address), user_agent)
+    });
+    let session_id = extract_session_cookie(&headers, &state.session_signer)
+        .unwrap_or_else(SessionSigner::new_session_id);
+
+    // Only the document's own count; checking a deed isn't interest in the listing, so it stays
+    // out of the listing's scan analytics
+    if let Err(e) = state.document_qr_service.record_scan(&property_id, &document_id, user_agent).await {
+        warn!("Failed to update document QR scan count for {}/{}: {}", property_id, document_id, e);
+    }
+
+    let response = localized(Html(create_document_page(&verification, locale)), locale);
+    finish_scan_response(response, &state, &visitor_id, is_new_visitor, &session_id)
+}
+
+/// Handle a scan of a share offer's QR code: on to DAO-Bitat's share-purchase flow
+/// GET /scan/{property_id}/shares/{offer_id}
+#[utoipa::path(
+    get,
+    path = "/scan/{property_id}/shares/{offer_id}",
+    tag = "scan",
+    params(
+        ("property_id" = String, Path, description = "Property ID"),
+        ("offer_id" = String, Path, description = "Share offer ID"),
+        ScanQuery,
+    ),
+    responses(
+        (status = 307, description = "Redirect to the share-purchase flow for the shares still on offer"),
+        (status = 404, description = "No active share offer with that ID (text/html)"),
+        (status = 410, description = "The property's shares have all been sold (text/html)"),
+    )
+)]
+pub async fn scan_share_offer(
+    State(state): State<Arc<ScanAppState>>,
+    Path((property_id, offer_id)): Path<(String, String)>,
+    Query(query): Query<ScanQuery>,
+    headers: HeaderMap,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> Response {
+    info!("QR code scan for share offer {} of property {}", offer_id, property_id);
+
+    let locale = Locale::negotiate(
+        query.lang.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|h| h.to_str().ok()),
+    );
+
+    let purchase_url = match state.share_offer_service.purchase_url(&property_id, &offer_id).await {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("Share offer scan for {}/{} not served: {}", property_id, offer_id, e);
+            let (status, title) = match e {
+                ShareOfferError::SoldOut => (StatusCode::GONE, "error.share_offer_sold_out"),
+                _ => (StatusCode::NOT_FOUND, "error.share_offer_not_found"),
+            };
+            let title = translate(locale, title);
+            let mut response = localized(Html(create_error_page(title, &property_id, locale)), locale);
+            *response.status_mut() = status;
+            return response;
+        }
+    };
+
+    let user_agent = headers.get("user-agent")
+        .and_then(|h| h.to_str().ok());
+    let ip_address = addr.ip().to_string();
+
+    let existing_visitor_id = extract_visitor_cookie(&headers);
+    let is_new_visitor = existing_visitor_id.is_none();
+    let visitor_id = existing_visitor_id.unwrap_or_else(|| {
+        ScanEvent::visitor_hash(Some(&ip_address), user_agent)
+    });
+    let session_id = extract_session_cookie(&headers, &state.session_signer)
+        .unwrap_or_else(SessionSigner::new_session_id);
+
+    // Only the offer's own counts; they'd inflate the listing's scan analytics
+    if let Err(e) = state.share_offer_service.record_scan(&offer_id, user_agent).await {
+        warn!("Failed to update share offer scan count for {}: {}", offer_id, e);
+    }
+
+    // Temporary, as the shares on offer change with every sale
+    let response = Redirect::temporary(&purchase_url).into_response();
+    finish_scan_response(response, &state, &visitor_id, is_new_visitor, &session_id)
+}
+
+/// Handle a scan of a booking's check-in code: check the renter in, once, within the booking window
+/// GET /checkin/{token}
+#[utoipa::path(
+    get,
+    path = "/checkin/{token}",
+    tag = "scan",
+    params(
+        ("token" = String, Path, description = "One-time check-in token"),
+        ScanQuery,
+    ),
+    responses(
+        (status = 200, description = "Checked in (text/html)"),
+        (status = 403, description = "Before the check-in window, or the booking is no longer confirmed (text/html)"),
+        (status = 404, description = "Unknown or replaced code (text/html)"),
+        (status = 409, description = "Code already used (text/html)"),
+        (status = 410, description = "Booking has ended (text/html)"),
+    )
+)]
+pub async fn check_in_booking(
+    State(state): State<Arc<ScanAppState>>,
+    Path(token): Path<String>,
+    Query(query): Query<ScanQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let locale = Locale::negotiate(
+        query.lang.as_deref(),
+        headers.get(header::ACCEPT_LANGUAGE).and_then(|h| h.to_str().ok()),
+    );
+    let text = |key: &str| escape_html(translate(locale, key));
+    let time = |at: chrono::DateTime<chrono::Utc>| at.format("%-d %b %Y, %H:%M UTC").to_string();
+
+    // Link previews in chat apps fetch the URL before the renter ever scans it
+    let is_bot = headers.get("user-agent")
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(ScanEvent::is_bot_user_agent);
+    if is_bot {
+        return localized(Html(create_checkin_page("🔑", &text("checkin.preview"), &[], locale)), locale);
+    }
+
+    let (status, page) = match state.booking_checkin_service.check_in(&token, chrono::Utc::now()).await {
+        Ok((code, property_name)) => (StatusCode::OK, create_checkin_page("✅", &text("checkin.checked_in"), &[
+            escape_html(&property_name),
+            format!("{}: {} – {}", text("checkin.stay"), time(code.check_in), time(code.check_out)),
+        ], locale)),
+        Err(BookingCheckinError::AlreadyUsed(at)) => (StatusCode::CONFLICT, create_checkin_page("⚠️", &text("checkin.already_used"), &[
+            format!("{}: {}", text("checkin.used_at"), time(at)),
+        ], locale)),
+        Err(BookingCheckinError::TooEarly(opens)) => (StatusCode::FORBIDDEN, create_checkin_page("⏳", &text("checkin.too_early"), &[
+            format!("{}: {}", text("checkin.opens_at"), time(opens)),
+        ], locale)),
+        Err(BookingCheckinError::Expired | BookingCheckinError::BookingEnded) => {
+            (StatusCode::GONE, create_checkin_page("⌛", &text("checkin.expired"), &[], locale))
+        }
+        Err(BookingCheckinError::NotConfirmed) => {
+            (StatusCode::FORBIDDEN, create_checkin_page("🚫", &text("checkin.not_confirmed"), &[], locale))
+        }
+        Err(e @ BookingCheckinError::DatabaseError(_)) => {
+            error!("Check-in failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, create_checkin_page("⚠️", &text("error.scan_failed"), &[], locale))
+        }
+        Err(_) => (StatusCode::NOT_FOUND, create_checkin_page("🔍", &text("checkin.not_found"), &[], locale)),
+    };
+
+    let mut response = localized(Html(page), locale);
+    *response.status_mut() = status;
+    response
+}
+
 /// Health check endpoint for scan service
 /// GET /scan/health
-pub async fn scan_health() -> Json<serde_json::Value> {
+#[utoipa::path(
+    get,
+    path = "/scan/health",
+    tag = "scan",
+    responses(
+        (status = 200, description = "Scan service status, analytics worker and load shedding metrics"),
+    )
+)]
+pub async fn scan_health(State(state): State<Arc<ScanAppState>>) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "status": "healthy",
         "service": "scan_handler",
+        "analyticsWorker": state.analytics_service.worker_metrics(),
+        "loadShedding": state.analytics_service.load_shedding_metrics(),
+        "geolocation": state.analytics_service.geolocation_metrics(),
+        "pageCache": state.page_cache.metrics(),
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
 
-/// Create HTML page for dual redirect
-fn create_redirect_page(data: &ScanRedirectData) -> String {
-    let blockchain_section = if let Some(blockchain_url) = &data.blockchain_url {
-        format!(
-            r#"
-            <div class="redirect-option blockchain">
-                <h3>🔗 View on Blockchain</h3>
-                <p>See this property's on-chain verification and ownership details</p>
-                <a href="{}" class="redirect-btn blockchain-btn" target="_blank">
-                    View on Base Explorer
-                </a>
-            </div>
-            "#,
-            blockchain_url
-        )
-    } else {
-        String::new()
-    };
+/// Read a named cookie from the request's Cookie header
+pub(crate) fn cookie_value(headers: &HeaderMap, cookie_name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == cookie_name)
+        .map(|(_, value)| value.trim().to_string())
+}
 
-    let verified_badge = if data.is_verified {
-        r#"<span class="verified-badge">✓ Verified</span>"#
-    } else {
-        ""
-    };
+/// Read the visitor ID from the request's Cookie header
+pub fn extract_visitor_cookie(headers: &HeaderMap) -> Option<String> {
+    cookie_value(headers, VISITOR_COOKIE_NAME)
+        .filter(|value| !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric()))
+}
 
-    let crypto_badge = if data.crypto_accepted {
-        r#"<span class="crypto-badge">₿ Crypto Accepted</span>"#
-    } else {
-        ""
-    };
+/// Read the session ID from the request's Cookie header, if its signature is valid
+pub fn extract_session_cookie(headers: &HeaderMap, signer: &SessionSigner) -> Option<String> {
+    cookie_value(headers, SESSION_COOKIE_NAME).and_then(|value| signer.verify(&value))
+}
 
-    let image_section = if let Some(image_url) = &data.primary_image {
-        format!(r#"<img src="{}" alt="Property Image" class="property-image">"#, image_url)
-    } else {
-        r#"<div class="property-image-placeholder">🏠</div>"#.to_string()
-    };
+/// Work out the visitor's tracking consent; browser opt-out signals always win
+pub fn extract_tracking_consent(headers: &HeaderMap, query: &ScanQuery) -> TrackingConsent {
+    let opted_out = ["sec-gpc", "dnt"].iter().any(|name| {
+        headers.get(*name).and_then(|h| h.to_str().ok()) == Some("1")
+    });
+    if opted_out {
+        return TrackingConsent::Denied;
+    }
 
-    format!(
-        r#"
-        <!DOCTYPE html>
-        <html lang="en">
-        <head>
-            <meta charset="UTF-8">
-            <meta name="viewport" content="width=device-width, initial-scale=1.0">
-            <title>{} - DAO-Bitat Property</title>
-            <style>
-                body {{
-                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
-                    margin: 0;
-                    padding: 20px;
-                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
-                    min-height: 100vh;
-                    display: flex;
-                    align-items: center;
+    let signal = query.consent.clone().or_else(|| cookie_value(headers, CONSENT_COOKIE_NAME));
+    match signal.as_deref() {
+        Some("granted") | Some("1") | Some("true") => TrackingConsent::Granted,
+        Some("denied") | Some("0") | Some("false") => TrackingConsent::Denied,
+        _ => TrackingConsent::Unknown,
+    }
+}
+
+/// UTM tags from the scanned URL, if it carried any
+fn utm_from_query(query: &ScanQuery) -> Option<UtmParameters> {
+    UtmParameters::from_query(
+        query.utm_source.as_deref(),
+        query.utm_medium.as_deref(),
+        query.utm_campaign.as_deref(),
+    )
+}
+
+/// Stable name for a scan source, matching its serialized form
+fn scan_source_name(scan_source: &ScanSource) -> &'static str {
+    match scan_source {
+        ScanSource::QrCode => "qr_code",
+        ScanSource::DirectLink => "direct_link",
+        ScanSource::ShareLink => "share_link",
+        ScanSource::SearchEngine => "search_engine",
+        ScanSource::SocialMedia => "social_media",
+        ScanSource::EmailSignature => "email_signature",
+        ScanSource::Unknown => "unknown",
+    }
+}
+
+/// Middleware for public routes: answer only on the canonical host and its aliases,
+/// and point every response at the canonical URL so old hostnames aren't indexed
+pub async fn enforce_canonical_host(
+    State(policy): State<Arc<HostPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // HTTP/2 carries the host in the URI authority rather than a Host header
+    let host = request.headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .or_else(|| request.uri().host())
+        .map(|s| s.to_string());
+
+    if let Some(host) = host {
+        if !policy.accepts(&host) {
+            warn!("Rejected request for unknown host: {}", host);
+            return (StatusCode::MISDIRECTED_REQUEST, "Unknown host").into_response();
+        }
+    }
+
+    let canonical_url = policy.canonical_url(request.uri().path());
+    let mut response = next.run(request).await;
+
+    if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"canonical\"", canonical_url)) {
+        response.headers_mut().insert(header::LINK, link);
+    }
+
+    response
+}
+
+/// Only show images served over HTTPS from an allow-listed host; anything else is dropped
+/// in favour of the placeholder rather than loaded from an arbitrary origin
+fn is_allowed_image_url(url: &str, image_domains: &[String]) -> bool {
+    let domains: Vec<&str> = image_domains.iter().map(String::as_str).collect();
+    let allowed = UrlValidator::is_secure_url(url) && UrlValidator::is_allowed_domain(url, &domains);
+    if !allowed {
+        warn!("Skipping primary image from disallowed URL: {}", url);
+    }
+    allowed
+}
+
+/// The listing details the scan API returns alongside its URLs
+fn property_summary(property_info: &PropertyQrInfo, image_domains: &[String]) -> PropertySummary {
+    PropertySummary {
+        name: property_info.property_name.clone(),
+        price: property_info.price,
+        action: property_info.action.clone(),
+        location: property_info.location.clone(),
+        primary_image: property_info.images.first()
+            .filter(|url| is_allowed_image_url(url, image_domains))
+            .cloned(),
+        is_verified: property_info.is_verified.unwrap_or(false),
+        crypto_accepted: property_info.crypto_accepted,
+        base_name: property_info.base_name.as_deref().and_then(UrlBuilder::resolve_base_name),
+    }
+}
+
+/// App link for a phone or tablet: the custom scheme, or on Android an intent URL
+/// that falls back to the web page itself when the app isn't installed
+fn app_link_url(config: &AppLinkConfig, device: &DeviceInfo, property_id: &str, web_url: &str) -> Option<String> {
+    if !config.enabled || !device.is_mobile {
+        return None;
+    }
+
+    let path = format!("property/{}", urlencoding::encode(property_id));
+    match (&config.android_package, device.platform.as_deref()) {
+        (Some(package), Some("Android")) => Some(format!(
+            "intent://{}#Intent;scheme={};package={};S.browser_fallback_url={};end",
+            path, config.scheme, package, urlencoding::encode(web_url)
+        )),
+        _ => Some(format!("{}://{}", config.scheme, path)),
+    }
+}
+
+/// Build the Set-Cookie value that issues a visitor ID
+fn visitor_cookie_header(visitor_id: &str) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax",
+        VISITOR_COOKIE_NAME, visitor_id, VISITOR_COOKIE_MAX_AGE
+    )
+}
+
+/// Build the Set-Cookie value that starts or renews a session
+fn session_cookie_header(signer: &SessionSigner, session_id: &str) -> String {
+    format!(
+        "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax",
+        SESSION_COOKIE_NAME, signer.sign(session_id), SESSION_COOKIE_MAX_AGE
+    )
+}
+
+/// Check the owner's and property's geo-blocking policies, recording the scan if one applies.
+/// Lookup failures let the scan through rather than turning visitors away.
+async fn is_region_blocked(
+    state: &ScanAppState,
+    property_info: &PropertyQrInfo,
+    user_agent: Option<&str>,
+    ip_address: &str,
+) -> bool {
+    let property_id = property_info.id.to_hex();
+    let policies = match state.geo_block_service.policies_for(&property_info.owner.to_hex(), &property_id).await {
+        Ok(policies) => policies,
+        Err(e) => {
+            error!("Failed to load geo-blocking policies for {}: {}", property_id, e);
+            return false;
+        }
+    };
+
+    // Most properties have no policy, so only geolocate when one exists
+    if policies.is_empty() {
+        return false;
+    }
+
+    let geolocation = state.analytics_service.locate(ip_address).await;
+    let Some(policy) = blocking_policy(&policies, geolocation.as_ref()) else {
+        return false;
+    };
+
+    warn!("Scan of property {} blocked by {:?} geo-blocking policy", property_id, policy.scope);
+    if let Err(e) = state.analytics_service.record_blocked_scan(
+        property_id,
+        policy,
+        geolocation,
+        user_agent.map(|ua| ua.to_string()),
+        Some(ip_address.to_string()),
+    ).await {
+        error!("Failed to record geo-blocked scan: {}", e);
+    }
+
+    true
+}
+
+/// Where this visitor's dual page goes on its own, from the live config. Lookup failures
+/// fall back to the deployment's default target rather than failing the scan.
+async fn auto_redirect_destination(state: &ScanAppState, property_id: &str, visitor_id: &str) -> AutoRedirectDestination {
+    let default = AutoRedirectDestination::from(state.default_target);
+    match state.auto_redirect_service.configs_for(property_id).await {
+        Ok(configs) => resolve_destination(&configs, property_id, visitor_id, default),
+        Err(e) => {
+            error!("Failed to load auto-redirect config for {}: {}", property_id, e);
+            default
+        }
+    }
+}
+
+/// Set the visitor and session cookies on a scan's response
+fn finish_scan_response(
+    mut response: Response,
+    state: &ScanAppState,
+    visitor_id: &str,
+    is_new_visitor: bool,
+    session_id: &str,
+) -> Response {
+    if is_new_visitor {
+        if let Ok(cookie) = HeaderValue::from_str(&visitor_cookie_header(visitor_id)) {
+            response.headers_mut().append(header::SET_COOKIE, cookie);
+        }
+    }
+    if let Ok(cookie) = HeaderValue::from_str(&session_cookie_header(&state.session_signer, session_id)) {
+        response.headers_mut().append(header::SET_COOKIE, cookie);
+    }
+    response
+}
+
+/// Stand-in scan ID that cached landing pages are rendered with; never a real ObjectId
+fn scan_id_slot() -> mongodb::bson::oid::ObjectId {
+    mongodb::bson::oid::ObjectId::from_bytes([0; 12])
+}
+
+/// Put this scan's ID into a page rendered with the stand-in
+fn fill_scan_id(html: &str, scan_id: &mongodb::bson::oid::ObjectId) -> String {
+    html.replace(&scan_id_slot().to_hex(), &scan_id.to_hex())
+}
+
+/// The owner's custom redirect for this property's QR code, if one is set and hasn't lapsed
+fn custom_redirect_url(qr_code: Option<&QrCodeMetadata>) -> Option<String> {
+    qr_code?.active_redirect_url(chrono::Utc::now()).map(|url| url.to_string())
+}
+
+/// Whether a scan headed for the dual page (Some) is actually shown it, rather than being sent
+/// on by an instant auto-redirect
+fn shows_dual_page(dual_page: &Option<Option<AutoRedirect>>) -> bool {
+    !matches!(dual_page, None | Some(Some(AutoRedirect { seconds: 0, .. })))
+}
+
+/// Variant of the property's running experiment a session is shown, with how the scan records
+/// it; None when nothing is being tested
+async fn landing_variant(
+    state: &ScanAppState,
+    property_id: &str,
+    session_id: &str,
+) -> Option<(ScanExperiment, LandingVariant)> {
+    let experiment = match state.experiment_service.active_for(property_id).await {
+        Ok(experiment) => experiment?,
+        Err(e) => {
+            // The page's defaults are shown, and the scan is left out of the experiment
+            warn!("Failed to look up experiment for property {}: {}", property_id, e);
+            return None;
+        }
+    };
+
+    let variant = experiment.assign(session_id).clone();
+    let scan_experiment = ScanExperiment { experiment_id: experiment.id.to_hex(), variant: variant.key.clone() };
+    Some((scan_experiment, variant))
+}
+
+/// Page for a scheduled code scanned outside its window; None while the window is open
+fn schedule_page(qr_code: Option<&QrCodeMetadata>, property_name: &str, locale: Locale) -> Option<Response> {
+    let text = |key: &str| escape_html(translate(locale, key));
+    let (status, page) = match qr_code?.window(chrono::Utc::now()) {
+        QrCodeWindow::Open => return None,
+        QrCodeWindow::NotStarted(opens) => (StatusCode::FORBIDDEN, create_schedule_page("⏳", &text("schedule.not_started"), &[
+            escape_html(property_name),
+            format!("{}: {}", text("schedule.opens_at"), opens.format("%-d %b %Y, %H:%M UTC")),
+        ], locale)),
+        QrCodeWindow::Ended => (StatusCode::GONE, create_schedule_page("🏁", &text("schedule.ended"), &[
+            escape_html(property_name),
+            text("schedule.ended_body"),
+        ], locale)),
+    };
+
+    let mut response = localized(Html(page), locale);
+    *response.status_mut() = status;
+    Some(response)
+}
+
+/// Keep the QR code's own scan count current; crawlers and link previews don't count.
+/// A limited-use code gives up one of its scans, and false means it had none left.
+async fn count_qr_scan(
+    state: &ScanAppState,
+    property_id: &str,
+    qr_code: Option<&QrCodeMetadata>,
+    user_agent: Option<&str>,
+) -> bool {
+    if qr_code.is_some_and(QrCodeMetadata::is_used_up) {
+        return false;
+    }
+    if user_agent.is_some_and(ScanEvent::is_bot_user_agent) {
+        return true;
+    }
+
+    if qr_code.is_some_and(|qr_code| qr_code.max_scans.is_some()) {
+        return match state.qr_generator.use_limited_scan(property_id).await {
+            Ok(allowed) => allowed,
+            Err(e) => {
+                // Like the scan cap, a failed lookup lets the scan through
+                error!("Failed to take a limited-use scan for property {}: {}", property_id, e);
+                true
+            }
+        };
+    }
+    if let Err(e) = state.qr_generator.record_scan(property_id).await {
+        warn!("Failed to update QR scan count for property {}: {}", property_id, e);
+    }
+    true
+}
+
+/// QR version printed in the scanned code; codes from before versioned scan URLs were the first version
+fn scanned_qr_version(query: &ScanQuery) -> i32 {
+    query.v.filter(|version| *version >= 1).unwrap_or(1)
+}
+
+/// Why a scan gets the waitlist page instead of the listing, if it does
+async fn waitlist_reason(state: &ScanAppState, property_info: &PropertyQrInfo) -> Option<WaitlistReason> {
+    if property_info.sold {
+        Some(WaitlistReason::Sold)
+    } else if is_scan_capped(state, &property_info.id.to_hex()).await {
+        Some(WaitlistReason::Capped)
+    } else {
+        None
+    }
+}
+
+/// Check the property's daily scan cap. Lookup failures let the scan through.
+async fn is_scan_capped(state: &ScanAppState, property_id: &str) -> bool {
+    match state.scan_cap_service.check(property_id).await {
+        Ok(capped) => capped,
+        Err(e) => {
+            error!("Failed to check scan cap for {}: {}", property_id, e);
+            false
+        }
+    }
+}
+
+/// Mark a rendered page with its language; caches must key it on Accept-Language
+fn localized(page: Html<String>, locale: Locale) -> Response {
+    let mut response = page.into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Language"));
+    response
+}
+
+/// Create HTML page for dual redirect
+fn create_redirect_page(
+    data: &ScanRedirectData,
+    canonical_url: &str,
+    auto_redirect: Option<&AutoRedirect>,
+    locale: Locale,
+) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+
+    let blockchain_section = if data.blockchain_url.is_some() {
+        format!(
+            r#"
+            <div class="redirect-option blockchain">
+                <h3>{}</h3>
+                <p>{}</p>
+                <a href="{}" id="blockchain-btn" class="redirect-btn blockchain-btn" target="_blank" rel="noopener noreferrer">
+                    {}
+                </a>
+            </div>
+            "#,
+            text("redirect.blockchain_heading"),
+            text("redirect.blockchain_body"),
+            escape_html(&outcome_url(data, ScanOutcome::Blockchain)),
+            text("redirect.blockchain_button")
+        )
+    } else {
+        String::new()
+    };
+
+    let payment_section = if data.crypto_accepted {
+        format!(
+            r#"
+            <div class="redirect-option payment">
+                <h3>{}</h3>
+                <p>{}</p>
+                <a href="{}" id="payment-btn" class="redirect-btn payment-btn">
+                    {}
+                </a>
+            </div>
+            "#,
+            text("redirect.payment_heading"),
+            text("redirect.payment_body"),
+            escape_html(&outcome_url(data, ScanOutcome::CryptoPayment)),
+            text("redirect.payment_button")
+        )
+    } else {
+        String::new()
+    };
+
+    let verified_badge = if data.is_verified {
+        format!(r#"<span class="verified-badge">{}</span>"#, text("redirect.verified"))
+    } else {
+        String::new()
+    };
+
+    let crypto_badge = if data.crypto_accepted {
+        format!(r#"<span class="crypto-badge">{}</span>"#, text("redirect.crypto_accepted"))
+    } else {
+        String::new()
+    };
+
+    let onchain_badge = if let Some(verified_at) = data.onchain_verified_at {
+        format!(
+            r#"<span class="onchain-badge">{} {}</span>"#,
+            text("redirect.onchain_verified"),
+            verified_at.format("%-d %b %Y, %H:%M UTC"),
+        )
+    } else {
+        String::new()
+    };
+
+    let base_name_link = if let Some(name) = &data.base_name {
+        format!(
+            r#"<div class="base-name"><a href="{}" target="_blank" rel="noopener noreferrer">🔵 {}</a></div>"#,
+            escape_html(&UrlBuilder::build_base_name_url(name)),
+            escape_html(name),
+        )
+    } else {
+        String::new()
+    };
+
+    let location = data.location.as_deref().unwrap_or(translate(locale, "redirect.location_unknown"));
+
+    // An experiment's variant swaps its own copy in for the property option's
+    let variant_text = |field: fn(&LandingVariant) -> Option<&String>, key: &str| {
+        data.variant.as_ref()
+            .and_then(field)
+            .map_or_else(|| text(key), |variant_text| escape_html(variant_text))
+    };
+    let property_heading = variant_text(|variant| variant.property_heading.as_ref(), "redirect.property_heading");
+    let property_body = variant_text(|variant| variant.property_body.as_ref(), "redirect.property_body");
+    let property_button = variant_text(|variant| variant.property_button.as_ref(), "redirect.property_button");
+
+    let share_meta = share_meta_tags(data, canonical_url, location);
+
+    let image_section = if let Some(image_url) = &data.primary_image {
+        format!(
+            r#"<img src="{}" alt="{}" class="property-image">"#,
+            escape_html(image_url),
+            text("redirect.image_alt")
+        )
+    } else {
+        r#"<div class="property-image-placeholder">🏠</div>"#.to_string()
+    };
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <title>{} - {}</title>
+            <link rel="canonical" href="{}">
+            {}
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
                     justify-content: center;
                 }}
                 .container {{
@@ -369,6 +1682,11 @@ fn create_redirect_page(data: &ScanRedirectData) -> String {
                     color: #666;
                     margin-bottom: 20px;
                 }}
+                .base-name a {{
+                    color: #0052ff;
+                    font-weight: 600;
+                    text-decoration: none;
+                }}
                 .property-price {{
                     font-size: 20px;
                     font-weight: bold;
@@ -378,7 +1696,7 @@ fn create_redirect_page(data: &ScanRedirectData) -> String {
                 .badges {{
                     margin: 15px 0;
                 }}
-                .verified-badge, .crypto-badge {{
+                .verified-badge, .crypto-badge, .onchain-badge {{
                     display: inline-block;
                     background: #10b981;
                     color: white;
@@ -391,6 +1709,9 @@ fn create_redirect_page(data: &ScanRedirectData) -> String {
                 .crypto-badge {{
                     background: #f59e0b;
                 }}
+                .onchain-badge {{
+                    background: #2563eb;
+                }}
                 .redirect-options {{
                     display: grid;
                     gap: 20px;
@@ -436,6 +1757,33 @@ fn create_redirect_page(data: &ScanRedirectData) -> String {
                 .blockchain-btn:hover {{
                     background: #7c3aed;
                 }}
+                .payment-btn {{
+                    background: #f59e0b;
+                }}
+                .payment-btn:hover {{
+                    background: #d97706;
+                }}
+                .sms-form {{
+                    display: flex;
+                    gap: 10px;
+                    justify-content: center;
+                }}
+                .sms-form input {{
+                    padding: 12px;
+                    border: 2px solid #e5e7eb;
+                    border-radius: 8px;
+                    font-size: 14px;
+                    flex: 1;
+                    max-width: 220px;
+                }}
+                .sms-form button {{
+                    border: none;
+                    cursor: pointer;
+                }}
+                .sms-status {{
+                    margin: 10px 0 0 0;
+                    min-height: 1em;
+                }}
                 .footer {{
                     margin-top: 30px;
                     padding-top: 20px;
@@ -453,65 +1801,198 @@ fn create_redirect_page(data: &ScanRedirectData) -> String {
                    <div class="property-details">
     📍 {} • {}
 </div>
+                    {}
                     <div class="property-price">KES {}</div>
                     <div class="badges">
                         {}
                         {}
+                        {}
                     </div>
                 </div>
 
                 <div class="redirect-options">
                     <div class="redirect-option property">
-                        <h3>🏠 View Property Details</h3>
-                        <p>See full property information, photos, and contact the owner</p>
-                        <a href="{}" class="redirect-btn">
-                            View on DAO-Bitat
+                        <h3>{}</h3>
+                        <p>{}</p>
+                        <a href="{}" id="property-btn" class="redirect-btn">
+                            {}
                         </a>
                     </div>
 
                     {}
+
+                    {}
+
+                    <div class="redirect-option sms">
+                        <h3>{}</h3>
+                        <p>{}</p>
+                        <form id="sms-form" class="sms-form">
+                            <input type="tel" id="sms-phone" placeholder="+254 712 345 678" required>
+                            <button type="submit" class="redirect-btn">{}</button>
+                        </form>
+                        <p id="sms-status" class="sms-status"></p>
+                    </div>
                 </div>
 
                 <div class="footer">
-                    <p>Powered by DAO-Bitat • Secure Property Transactions</p>
-                    <p>Scan ID: {}</p>
+                    <p>{}</p>
+                    <p>{}: {}</p>
                 </div>
             </div>
 
             <script>
-                // Auto-redirect after 10 seconds to property page
-                setTimeout(() => {{
-                    window.location.href = '{}';
-                }}, 10000);
+                // Funnel beacons for the page view and the countdown; button clicks are
+                // recorded by the tracked links themselves
+                const sendFunnelEvent = (stage) => navigator.sendBeacon && navigator.sendBeacon(
+                    {},
+                    new Blob([JSON.stringify({{ scanId: {}, stage }})], {{ type: 'application/json' }})
+                );
+                sendFunnelEvent('page_view');
+
+                {}
+
+                // Don't navigate away while the visitor is typing their number
+                const smsPhone = document.getElementById('sms-phone');
+                const smsStatus = document.getElementById('sms-status');
+                smsPhone.addEventListener('focus', () => clearTimeout(autoRedirect));
+
+                document.getElementById('sms-form').addEventListener('submit', async (event) => {{
+                    event.preventDefault();
+                    smsStatus.textContent = {};
+                    try {{
+                        const response = await fetch({}, {{
+                            method: 'POST',
+                            headers: {{ 'Content-Type': 'application/json' }},
+                            body: JSON.stringify({{ phone: smsPhone.value }})
+                        }});
+                        const result = await response.json();
+                        smsStatus.textContent = response.ok ? {} : result.message;
+                    }} catch (e) {{
+                        smsStatus.textContent = {};
+                    }}
+                }});
             </script>
         </body>
         </html>
         "#,
-        data.property_name,
+        locale.code(),
+        escape_html(&data.property_name),
+        text("redirect.title_suffix"),
+        escape_html(canonical_url),
+        share_meta,
         image_section,
-        data.property_name,
-        data.location.as_deref().unwrap_or("Location not specified"), 
-        data.action,
+        escape_html(&data.property_name),
+        escape_html(location),
+        escape_html(&data.action),
+        base_name_link,
         data.price,
         verified_badge,
         crypto_badge,
-        data.daobitar_url,
+        onchain_badge,
+        property_heading,
+        property_body,
+        escape_html(&outcome_url(data, ScanOutcome::Property)),
+        property_button,
         blockchain_section,
+        payment_section,
+        text("redirect.sms_heading"),
+        text("redirect.sms_body"),
+        text("redirect.sms_button"),
+        text("redirect.footer"),
+        text("redirect.scan_id"),
         data.scan_id.to_hex(),
-        data.daobitar_url
+        js_string_literal(&format!("/api/scan/{}/funnel", urlencoding::encode(&data.property_id))),
+        js_string_literal(&data.scan_id.to_hex()),
+        auto_redirect_script(auto_redirect),
+        js_string_literal(translate(locale, "redirect.sms_sending")),
+        js_string_literal(&format!("/api/scan/{}/sms", urlencoding::encode(&data.property_id))),
+        js_string_literal(translate(locale, "redirect.sms_sent")),
+        js_string_literal(translate(locale, "redirect.sms_failed"))
     )
 }
 
+/// Script that runs the dual page's countdown, or a no-op timer for a static page
+fn auto_redirect_script(auto_redirect: Option<&AutoRedirect>) -> String {
+    match auto_redirect {
+        Some(redirect) => format!(
+            r#"// Auto-redirect after {} seconds
+                const autoRedirect = setTimeout(() => {{
+                    sendFunnelEvent('auto_redirect');
+                    window.location.href = {};
+                }}, {});"#,
+            redirect.seconds,
+            js_string_literal(&redirect.url),
+            redirect.seconds * 1000
+        ),
+        None => "// Visitors pick a destination themselves\n                const autoRedirect = null;".to_string(),
+    }
+}
+
+/// The dual page's auto-redirect: the configured delay and destination, unless the scan
+/// asked for a static page with ?no_redirect=1
+fn auto_redirect(
+    seconds: Option<u64>,
+    destination: AutoRedirectDestination,
+    no_redirect: Option<&str>,
+    property_url: &str,
+    blockchain_url: Option<&str>,
+) -> Option<AutoRedirect> {
+    if matches!(no_redirect, Some("1") | Some("true")) {
+        return None;
+    }
+
+    let url = match (destination, blockchain_url) {
+        (AutoRedirectDestination::None, _) => return None,
+        (AutoRedirectDestination::Blockchain, Some(blockchain_url)) => blockchain_url,
+        _ => property_url,
+    };
+    seconds.map(|seconds| AutoRedirect { url: url.to_string(), seconds })
+}
+
+/// Open Graph and Twitter card tags, so links shared on WhatsApp or X preview
+/// the property's photo and price instead of a blank card
+fn share_meta_tags(data: &ScanRedirectData, canonical_url: &str, location: &str) -> String {
+    let title = escape_html(&data.property_name);
+    let description = escape_html(&format!("KES {} • {} • {}", data.price, data.action, location));
+
+    let mut tags = vec![
+        r#"<meta property="og:type" content="website">"#.to_string(),
+        r#"<meta property="og:site_name" content="DAO-Bitat">"#.to_string(),
+        format!(r#"<meta property="og:title" content="{}">"#, title),
+        format!(r#"<meta property="og:description" content="{}">"#, description),
+        format!(r#"<meta property="og:url" content="{}">"#, escape_html(canonical_url)),
+        format!(r#"<meta name="description" content="{}">"#, description),
+        format!(r#"<meta name="twitter:title" content="{}">"#, title),
+        format!(r#"<meta name="twitter:description" content="{}">"#, description),
+    ];
+
+    // Large cards need an image; without one, fall back to the compact card
+    match &data.primary_image {
+        Some(image_url) => {
+            let image_url = escape_html(image_url);
+            tags.push(format!(r#"<meta property="og:image" content="{}">"#, image_url));
+            tags.push(format!(r#"<meta property="og:image:alt" content="{}">"#, title));
+            tags.push(r#"<meta name="twitter:card" content="summary_large_image">"#.to_string());
+            tags.push(format!(r#"<meta name="twitter:image" content="{}">"#, image_url));
+        }
+        None => tags.push(r#"<meta name="twitter:card" content="summary">"#.to_string()),
+    }
+
+    tags.join("\n            ")
+}
+
 /// Create error page HTML
-fn create_error_page(error_message: &str, property_id: &str) -> String {
+fn create_error_page(error_message: &str, property_id: &str, locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+
     format!(
         r#"
         <!DOCTYPE html>
-        <html lang="en">
+        <html lang="{}">
         <head>
             <meta charset="UTF-8">
             <meta name="viewport" content="width=device-width, initial-scale=1.0">
-            <title>Error - DAO-Bitat</title>
+            <title>{}</title>
             <style>
                 body {{
                     font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
@@ -560,46 +2041,1191 @@ fn create_error_page(error_message: &str, property_id: &str) -> String {
             <div class="container">
                 <div class="error-icon">⚠️</div>
                 <h1>{}</h1>
-                <p>Property ID: {}</p>
-                <p>The property you're looking for could not be found or is no longer available.</p>
+                <p>{}: {}</p>
+                <p>{}</p>
                 <a href="https://daobitat.xyz" class="home-btn">
-                    Go to DAO-Bitat
+                    {}
                 </a>
             </div>
         </body>
         </html>
         "#,
-        error_message, property_id
+        locale.code(),
+        text("error.page_title"),
+        escape_html(error_message),
+        text("error.property_id"),
+        escape_html(property_id),
+        text("error.body"),
+        text("error.home_button")
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_create_error_page() {
-        let html = create_error_page("Test Error", "test123");
-        assert!(html.contains("Test Error"));
-        assert!(html.contains("test123"));
-        assert!(html.contains("<!DOCTYPE html>"));
-    }
-
-    #[test]
-    fn test_scan_response_creation() {
-        let response = ScanResponse {
-            success: true,
-            property_id: "test123".to_string(),
-            redirect_type: "dual".to_string(),
-            urls: RedirectUrls {
-                property_url: "https://daobitat.xyz/property/test123".to_string(),
-                blockchain_url: Some("https://explorer.base.org/token/test123".to_string()),
-                redirect_page_url: "https://qr.daobitat.xyz/scan/test123".to_string(),
-            },
-            scan_id: "scan123".to_string(),
-        };
+/// Create the page that hands a mobile scan to the app. The web URL is the fallback:
+/// it's also a universal/app link, so it opens the app when the OS has claimed it.
+fn create_app_redirect_page(app_url: &str, web_url: &str, locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
 
-        assert_eq!(response.success, true);
-        assert_eq!(response.property_id, "test123");
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    text-align: center;
+                    backdrop-filter: blur(10px);
+                }}
+                h1 {{
+                    margin: 0 0 30px 0;
+                    font-size: 22px;
+                }}
+                .btn {{
+                    display: block;
+                    padding: 14px 24px;
+                    margin-bottom: 12px;
+                    border-radius: 8px;
+                    font-weight: bold;
+                    text-decoration: none;
+                }}
+                .app-btn {{
+                    background: white;
+                    color: #764ba2;
+                }}
+                .web-btn {{
+                    border: 1px solid rgba(255, 255, 255, 0.6);
+                    color: white;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <h1>{}</h1>
+                <a href="{}" class="btn app-btn">{}</a>
+                <a href="{}" class="btn web-btn">{}</a>
+            </div>
+            <script>
+                window.location.href = {};
+                // Still here once the app had its chance: it isn't installed
+                setTimeout(function() {{
+                    if (!document.hidden) {{
+                        window.location.replace({});
+                    }}
+                }}, 1500);
+            </script>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text("app.page_title"),
+        text("app.heading"),
+        escape_html(app_url),
+        text("app.open_button"),
+        escape_html(web_url),
+        text("app.web_button"),
+        js_string_literal(app_url),
+        js_string_literal(web_url)
+    )
+}
+
+/// Create the page shown when a property isn't offered in the visitor's region
+fn create_blocked_page(locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    text-align: center;
+                    backdrop-filter: blur(10px);
+                }}
+                .region-icon {{
+                    font-size: 64px;
+                    margin-bottom: 20px;
+                }}
+                h1 {{
+                    margin: 0 0 10px 0;
+                    font-size: 24px;
+                }}
+                p {{
+                    margin: 0 0 30px 0;
+                    opacity: 0.9;
+                }}
+                .home-btn {{
+                    display: inline-block;
+                    padding: 12px 24px;
+                    background: white;
+                    color: #764ba2;
+                    text-decoration: none;
+                    border-radius: 8px;
+                    font-weight: bold;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="region-icon">🌍</div>
+                <h1>{}</h1>
+                <p>{}</p>
+                <a href="https://daobitat.xyz" class="home-btn">
+                    {}
+                </a>
+            </div>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text("blocked.page_title"),
+        text("blocked.heading"),
+        text("blocked.body"),
+        text("error.home_button")
+    )
+}
+
+/// Create the waitlist page shown for sold properties and once a property reaches its daily scan cap
+fn create_waitlist_page(property_name: &str, property_id: &str, reason: WaitlistReason, locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+    let (icon, page_title, heading, body) = match reason {
+        WaitlistReason::Capped => ("🔥", "waitlist.page_title", "waitlist.heading", "waitlist.body"),
+        WaitlistReason::Sold => ("🏷️", "waitlist.sold_page_title", "waitlist.sold_heading", "waitlist.sold_body"),
+    };
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    text-align: center;
+                    backdrop-filter: blur(10px);
+                }}
+                .demand-icon {{
+                    font-size: 64px;
+                    margin-bottom: 20px;
+                }}
+                h1 {{
+                    margin: 0 0 10px 0;
+                    font-size: 24px;
+                }}
+                p {{
+                    margin: 0 0 30px 0;
+                    opacity: 0.9;
+                }}
+                .waitlist-form {{
+                    display: flex;
+                    flex-wrap: wrap;
+                    gap: 10px;
+                    justify-content: center;
+                }}
+                .waitlist-form input {{
+                    padding: 12px;
+                    border: none;
+                    border-radius: 8px;
+                    font-size: 14px;
+                    flex: 1;
+                    max-width: 220px;
+                }}
+                .waitlist-form button {{
+                    padding: 12px 24px;
+                    background: white;
+                    color: #764ba2;
+                    border: none;
+                    border-radius: 8px;
+                    font-weight: bold;
+                    cursor: pointer;
+                }}
+                .waitlist-status {{
+                    margin: 15px 0 0 0;
+                    min-height: 1em;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="demand-icon">{}</div>
+                <h1>{}</h1>
+                <p><strong>{}</strong></p>
+                <p>{}</p>
+                <form id="waitlist-form" class="waitlist-form">
+                    <input type="email" id="waitlist-email" placeholder="you@example.com">
+                    <input type="tel" id="waitlist-phone" placeholder="+254 712 345 678">
+                    <button type="submit">{}</button>
+                </form>
+                <p id="waitlist-status" class="waitlist-status"></p>
+            </div>
+
+            <script>
+                const waitlistEmail = document.getElementById('waitlist-email');
+                const waitlistPhone = document.getElementById('waitlist-phone');
+                const waitlistStatus = document.getElementById('waitlist-status');
+
+                document.getElementById('waitlist-form').addEventListener('submit', async (event) => {{
+                    event.preventDefault();
+                    waitlistStatus.textContent = {};
+                    try {{
+                        const response = await fetch({}, {{
+                            method: 'POST',
+                            headers: {{ 'Content-Type': 'application/json' }},
+                            body: JSON.stringify({{ email: waitlistEmail.value, phone: waitlistPhone.value }})
+                        }});
+                        const result = await response.json();
+                        waitlistStatus.textContent = response.ok ? {} : result.message;
+                    }} catch (e) {{
+                        waitlistStatus.textContent = {};
+                    }}
+                }});
+            </script>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text(page_title),
+        icon,
+        text(heading),
+        escape_html(property_name),
+        text(body),
+        text("waitlist.button"),
+        js_string_literal(translate(locale, "waitlist.sending")),
+        js_string_literal(&format!("/api/scan/{}/waitlist", urlencoding::encode(property_id))),
+        js_string_literal(translate(locale, "waitlist.joined")),
+        js_string_literal(translate(locale, "waitlist.failed"))
+    )
+}
+
+/// Create the verification page a property document's QR code resolves to
+fn create_document_page(verification: &DocumentVerification, locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+    let date = |date: chrono::DateTime<chrono::Utc>| date.format("%-d %b %Y").to_string();
+
+    let (status_class, status_key) = match verification.status {
+        DocumentVerificationStatus::Verified => ("verified", "document.status.verified"),
+        DocumentVerificationStatus::Unverified => ("unverified", "document.status.unverified"),
+        DocumentVerificationStatus::Expired => ("expired", "document.status.expired"),
+    };
+
+    let hash = match &verification.document_hash {
+        Some(hash) => format!(
+            r#"<code class="hash">{}</code><p class="hint">{}</p>"#,
+            escape_html(hash),
+            text("document.hash_hint"),
+        ),
+        None => format!(r#"<p class="hint">{}</p>"#, text("document.hash_missing")),
+    };
+
+    let mut details = Vec::new();
+    if let Some(verifier) = &verification.verifier {
+        details.push(format!(
+            "<dt>{}</dt><dd>{} #{}</dd>",
+            text("document.verifier"),
+            text("document.verifier_name"),
+            escape_html(verifier),
+        ));
+    }
+    if let Some(verified_at) = verification.verified_at {
+        details.push(format!("<dt>{}</dt><dd>{}</dd>", text("document.verified_at"), date(verified_at)));
+    }
+    if let Some(expiry_date) = verification.expiry_date {
+        details.push(format!("<dt>{}</dt><dd>{}</dd>", text("document.expires"), date(expiry_date)));
+    }
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    backdrop-filter: blur(10px);
+                }}
+                h1 {{
+                    margin: 0 0 10px 0;
+                    font-size: 24px;
+                }}
+                .status {{
+                    display: inline-block;
+                    padding: 6px 12px;
+                    border-radius: 8px;
+                    font-weight: bold;
+                    margin-bottom: 20px;
+                }}
+                .status.verified {{ background: #10b981; }}
+                .status.unverified {{ background: #6b7280; }}
+                .status.expired {{ background: #f59e0b; }}
+                dl {{
+                    margin: 0 0 20px 0;
+                }}
+                dt {{
+                    font-size: 12px;
+                    text-transform: uppercase;
+                    opacity: 0.7;
+                    margin-top: 12px;
+                }}
+                dd {{
+                    margin: 4px 0 0 0;
+                }}
+                .hash {{
+                    display: block;
+                    word-break: break-all;
+                    background: rgba(0, 0, 0, 0.2);
+                    padding: 10px;
+                    border-radius: 8px;
+                }}
+                .hint {{
+                    font-size: 13px;
+                    opacity: 0.8;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <h1>{}</h1>
+                <div class="status {}">{}</div>
+                <dl>
+                    <dt>{}</dt><dd>{}</dd>
+                    <dt>{}</dt><dd>{}</dd>
+                    <dt>{}</dt><dd>{}</dd>
+                    {}
+                </dl>
+                <h2>{}</h2>
+                {}
+            </div>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text("document.page_title"),
+        text("document.heading"),
+        status_class,
+        text(status_key),
+        text("document.property"),
+        escape_html(&verification.property_name),
+        text("document.type"),
+        text(verification.document_type.i18n_key()),
+        text("document.heading"),
+        escape_html(&verification.document_name),
+        details.join("\n                    "),
+        text("document.hash"),
+        hash
+    )
+}
+
+/// Create the page a check-in scan lands on; `heading` and `lines` must already be escaped
+fn create_checkin_page(icon: &str, heading: &str, lines: &[String], locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+    let lines: String = lines.iter().map(|line| format!("<p>{}</p>", line)).collect();
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    text-align: center;
+                    backdrop-filter: blur(10px);
+                }}
+                .checkin-icon {{
+                    font-size: 64px;
+                    margin-bottom: 20px;
+                }}
+                h1 {{
+                    margin: 0 0 10px 0;
+                    font-size: 24px;
+                }}
+                p {{
+                    margin: 0 0 10px 0;
+                    opacity: 0.9;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="checkin-icon">{}</div>
+                <h1>{}</h1>
+                {}
+            </div>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text("checkin.page_title"),
+        icon,
+        heading,
+        lines
+    )
+}
+
+/// Notice shown for a scheduled code, e.g. an open house's, outside its window
+fn create_schedule_page(icon: &str, heading: &str, lines: &[String], locale: Locale) -> String {
+    let text = |key: &str| escape_html(translate(locale, key));
+    let lines: String = lines.iter().map(|line| format!("<p>{}</p>", line)).collect();
+
+    format!(
+        r#"
+        <!DOCTYPE html>
+        <html lang="{}">
+        <head>
+            <meta charset="UTF-8">
+            <meta name="viewport" content="width=device-width, initial-scale=1.0">
+            <meta name="robots" content="noindex">
+            <title>{}</title>
+            <style>
+                body {{
+                    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+                    margin: 0;
+                    padding: 20px;
+                    background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+                    min-height: 100vh;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    color: white;
+                }}
+                .container {{
+                    background: rgba(255, 255, 255, 0.1);
+                    border-radius: 20px;
+                    padding: 40px;
+                    max-width: 500px;
+                    width: 100%;
+                    text-align: center;
+                    backdrop-filter: blur(10px);
+                }}
+                .schedule-icon {{
+                    font-size: 64px;
+                    margin-bottom: 20px;
+                }}
+                h1 {{
+                    margin: 0 0 10px 0;
+                    font-size: 24px;
+                }}
+                p {{
+                    margin: 0 0 10px 0;
+                    opacity: 0.9;
+                }}
+            </style>
+        </head>
+        <body>
+            <div class="container">
+                <div class="schedule-icon">{}</div>
+                <h1>{}</h1>
+                {}
+            </div>
+        </body>
+        </html>
+        "#,
+        locale.code(),
+        text("schedule.page_title"),
+        icon,
+        heading,
+        lines
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_error_page() {
+        let html = create_error_page("Test Error", "test123", Locale::En);
+        assert!(html.contains("Test Error"));
+        assert!(html.contains("test123"));
+        assert!(html.contains("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn test_extract_visitor_cookie() {
+        let mut headers = HeaderMap::new();
+        assert!(extract_visitor_cookie(&headers).is_none());
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("theme=dark; dbqr_visitor=abc123"));
+        assert_eq!(extract_visitor_cookie(&headers), Some("abc123".to_string()));
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("dbqr_visitor=<script>"));
+        assert!(extract_visitor_cookie(&headers).is_none());
+    }
+
+    #[test]
+    fn test_session_cookie_round_trip() {
+        let signer = SessionSigner::random();
+        let cookie = session_cookie_header(&signer, "abc123");
+        assert!(cookie.contains("Max-Age=1800"));
+
+        let value = cookie.split(';').next().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_str(value).unwrap());
+        assert_eq!(extract_session_cookie(&headers, &signer), Some("abc123".to_string()));
+        assert!(extract_session_cookie(&headers, &SessionSigner::random()).is_none());
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("dbqr_session=abc123"));
+        assert!(extract_session_cookie(&headers, &signer).is_none());
+    }
+
+    #[test]
+    fn test_visitor_cookie_header() {
+        let cookie = visitor_cookie_header("abc123");
+        assert!(cookie.starts_with("dbqr_visitor=abc123;"));
+        assert!(cookie.contains("HttpOnly"));
+    }
+
+    #[test]
+    fn test_extract_tracking_consent() {
+        let query = |consent: Option<&str>| ScanQuery {
+            source: None,
+            redirect: None,
+            ref_: None,
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+            consent: consent.map(|c| c.to_string()),
+            lang: None,
+            no_redirect: None,
+            v: None,
+        };
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(extract_tracking_consent(&headers, &query(None)), TrackingConsent::Unknown);
+        assert_eq!(extract_tracking_consent(&headers, &query(Some("granted"))), TrackingConsent::Granted);
+
+        headers.insert(header::COOKIE, HeaderValue::from_static("dbqr_consent=granted"));
+        assert_eq!(extract_tracking_consent(&headers, &query(None)), TrackingConsent::Granted);
+
+        // Global Privacy Control overrides an earlier opt-in
+        headers.insert("sec-gpc", HeaderValue::from_static("1"));
+        assert_eq!(extract_tracking_consent(&headers, &query(Some("granted"))), TrackingConsent::Denied);
+    }
+
+    #[test]
+    fn test_utm_from_query() {
+        let mut query = ScanQuery {
+            source: None,
+            redirect: None,
+            ref_: None,
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+            consent: None,
+            lang: None,
+            no_redirect: None,
+            v: None,
+        };
+        assert!(utm_from_query(&query).is_none());
+
+        query.utm_source = Some(" flyer ".to_string());
+        query.utm_medium = Some("".to_string());
+        query.utm_campaign = Some("x".repeat(150));
+        let utm = utm_from_query(&query).unwrap();
+        assert_eq!(utm.source.as_deref(), Some("flyer"));
+        assert!(utm.medium.is_none());
+        assert_eq!(utm.campaign.map(|c| c.len()), Some(UtmParameters::MAX_VALUE_LENGTH));
+    }
+
+    #[test]
+    fn test_scanned_qr_version() {
+        let query = |v: Option<i32>| ScanQuery {
+            source: None,
+            redirect: None,
+            ref_: None,
+            utm_source: None,
+            utm_medium: None,
+            utm_campaign: None,
+            consent: None,
+            lang: None,
+            no_redirect: None,
+            v,
+        };
+
+        assert_eq!(scanned_qr_version(&query(Some(3))), 3);
+        // Codes printed before versioned scan URLs, and nonsense values, count as the first print
+        assert_eq!(scanned_qr_version(&query(None)), 1);
+        assert_eq!(scanned_qr_version(&query(Some(0))), 1);
+    }
+
+    fn redirect_data(name: &str, is_verified: bool, onchain: bool) -> ScanRedirectData {
+        ScanRedirectData {
+            property_id: "507f1f77bcf86cd799439011".to_string(),
+            property_name: name.to_string(),
+            location: Some("Westlands, Nairobi".to_string()),
+            daobitar_url: "https://www.daobitat.xyz/property/507f1f77bcf86cd799439011".to_string(),
+            blockchain_url: onchain.then(|| "https://basescan.org/token/0xabc123".to_string()),
+            action: "rent".to_string(),
+            price: 85000,
+            primary_image: is_verified.then(|| "https://cdn.daobitat.xyz/img/1.jpg".to_string()),
+            is_verified,
+            crypto_accepted: onchain,
+            base_name: None,
+            onchain_verified_at: None,
+            variant: None,
+            scan_id: mongodb::bson::oid::ObjectId::parse_str("65f0c0ffee0000000000abcd").unwrap(),
+        }
+    }
+
+    const CANONICAL_URL: &str = "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011";
+
+    fn countdown() -> AutoRedirect {
+        AutoRedirect {
+            url: "https://www.daobitat.xyz/property/507f1f77bcf86cd799439011".to_string(),
+            seconds: 10,
+        }
+    }
+
+    #[test]
+    fn snapshot_redirect_page_verified_onchain() {
+        let html = create_redirect_page(&redirect_data("Garden Villa", true, true), CANONICAL_URL, Some(&countdown()), Locale::En);
+        insta::assert_snapshot!(html);
+    }
+
+    #[test]
+    fn snapshot_redirect_page_unverified_offchain() {
+        let html = create_redirect_page(&redirect_data("Studio Apartment", false, false), CANONICAL_URL, Some(&countdown()), Locale::En);
+        insta::assert_snapshot!(html);
+    }
+
+    #[test]
+    fn snapshot_redirect_page_long_unicode_name() {
+        let name = "Nyumba ya Kifahari — 4 Chumba cha Kulala, Bustani & Bwawa 🏡 Résidence Éléphant près du Lac Naivasha";
+        let html = create_redirect_page(&redirect_data(name, true, false), CANONICAL_URL, Some(&countdown()), Locale::En);
+        insta::assert_snapshot!(html);
+    }
+
+    #[test]
+    fn test_redirect_page_variant() {
+        let data = ScanRedirectData {
+            variant: Some(LandingVariant {
+                key: "b".to_string(),
+                property_heading: None,
+                property_body: None,
+                property_button: Some("Book a viewing <today>".to_string()),
+            }),
+            ..redirect_data("Garden Villa", true, false)
+        };
+        let html = create_redirect_page(&data, CANONICAL_URL, None, Locale::En);
+        assert!(html.contains("Book a viewing &lt;today&gt;"));
+        assert!(!html.contains("View on DAO-Bitat"));
+        // Text the variant doesn't set keeps the page's default
+        assert!(html.contains("View Property Details"));
+    }
+
+    #[test]
+    fn snapshot_redirect_page_swahili() {
+        let html = create_redirect_page(&redirect_data("Nyumba ya Bustani", true, true), CANONICAL_URL, Some(&countdown()), Locale::Sw);
+        insta::assert_snapshot!(html);
+    }
+
+    #[test]
+    fn test_error_page_is_localized() {
+        let html = create_error_page(translate(Locale::Sw, "error.property_not_found"), "test123", Locale::Sw);
+        assert!(html.contains(r#"<html lang="sw">"#));
+        assert!(html.contains("Mali haikupatikana"));
+        assert!(html.contains("Nenda DAO-Bitat"));
+    }
+
+    #[test]
+    fn test_app_link_url() {
+        let mut config = AppLinkConfig {
+            enabled: true,
+            scheme: "daobitat".to_string(),
+            android_package: None,
+        };
+        let web_url = "https://www.daobitat.xyz/property/507f1f77bcf86cd799439011";
+        let iphone = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1",
+        );
+        let android = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        );
+        let desktop = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        );
+
+        assert_eq!(
+            app_link_url(&config, &iphone, "507f1f77bcf86cd799439011", web_url).as_deref(),
+            Some("daobitat://property/507f1f77bcf86cd799439011")
+        );
+        assert_eq!(
+            app_link_url(&config, &android, "507f1f77bcf86cd799439011", web_url).as_deref(),
+            Some("daobitat://property/507f1f77bcf86cd799439011")
+        );
+        assert!(app_link_url(&config, &desktop, "507f1f77bcf86cd799439011", web_url).is_none());
+
+        // With a package, Android gets an intent URL that carries its own web fallback
+        config.android_package = Some("xyz.daobitat.app".to_string());
+        assert_eq!(
+            app_link_url(&config, &android, "507f1f77bcf86cd799439011", web_url).as_deref(),
+            Some("intent://property/507f1f77bcf86cd799439011#Intent;scheme=daobitat;package=xyz.daobitat.app;S.browser_fallback_url=https%3A%2F%2Fwww.daobitat.xyz%2Fproperty%2F507f1f77bcf86cd799439011;end")
+        );
+        assert!(app_link_url(&config, &iphone, "507f1f77bcf86cd799439011", web_url).unwrap().starts_with("daobitat://"));
+
+        config.enabled = false;
+        assert!(app_link_url(&config, &iphone, "507f1f77bcf86cd799439011", web_url).is_none());
+    }
+
+    #[test]
+    fn test_app_redirect_page_falls_back_to_web() {
+        let html = create_app_redirect_page(
+            "daobitat://property/1",
+            "https://www.daobitat.xyz/property/1\"</script>",
+            Locale::Sw,
+        );
+        assert!(html.contains(r#"window.location.href = "daobitat://property/1";"#));
+        assert!(html.contains(r#"href="https://www.daobitat.xyz/property/1&quot;&lt;/script&gt;""#));
+        assert!(!html.contains("1\"</script>"));
+        assert!(html.contains("Endelea kwenye kivinjari"));
+    }
+
+    #[test]
+    fn test_blocked_page_is_localized() {
+        let html = create_blocked_page(Locale::En);
+        assert!(html.contains("Not available in your region"));
+        assert!(!html.contains("Property ID"));
+
+        let html = create_blocked_page(Locale::Sw);
+        assert!(html.contains(r#"<html lang="sw">"#));
+        assert!(html.contains("Haipatikani katika eneo lako"));
+    }
+
+    #[test]
+    fn test_cached_page_gets_each_scans_id() {
+        let mut data = redirect_data("Garden Villa", true, false);
+        data.scan_id = scan_id_slot();
+        let cached = create_redirect_page(&data, CANONICAL_URL, None, Locale::En);
+
+        let scan_id = mongodb::bson::oid::ObjectId::parse_str("65f0c0ffee0000000000abcd").unwrap();
+        let html = fill_scan_id(&cached, &scan_id);
+        assert!(html.contains(r#"scanId: "65f0c0ffee0000000000abcd""#));
+        assert!(!html.contains(&scan_id_slot().to_hex()));
+    }
+
+    #[test]
+    fn test_redirect_page_escapes_property_content() {
+        let mut data = redirect_data(r#"Villa</title><script>alert("x")</script>"#, false, false);
+        data.location = Some(r#"Nairobi" onmouseover="alert(1)"#.to_string());
+        data.daobitar_url = "https://www.daobitat.xyz/property/1';alert(1);//".to_string();
+        data.primary_image = Some(r#"https://cdn.daobitat.xyz/a.jpg" onerror="alert(1)"#.to_string());
+
+        let auto_redirect = AutoRedirect { url: data.daobitar_url.clone(), seconds: 10 };
+        let html = create_redirect_page(&data, CANONICAL_URL, Some(&auto_redirect), Locale::En);
+
+        assert!(!html.contains("<script>alert"));
+        assert!(html.contains("Villa&lt;/title&gt;&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt;"));
+        assert!(!html.contains(r#"" onmouseover=""#));
+        assert!(!html.contains(r#"" onerror=""#));
+        assert!(html.contains(r#"window.location.href = "https://www.daobitat.xyz/property/1';alert(1);//";"#));
+        assert!(create_error_page("Not found", "<img src=x onerror=alert(1)>", Locale::En).contains("&lt;img src=x"));
+    }
+
+    #[test]
+    fn test_redirect_page_buttons_use_tracked_links() {
+        let data = redirect_data("Garden Villa", true, true);
+        let html = create_redirect_page(&data, CANONICAL_URL, None, Locale::En);
+        let scan_id = data.scan_id.to_hex();
+
+        assert!(html.contains(&format!(r#"href="/scan/{}/go/property?scan={}" id="property-btn""#, data.property_id, scan_id)));
+        assert!(html.contains(&format!(r#"href="/scan/{}/go/blockchain?scan={}" id="blockchain-btn""#, data.property_id, scan_id)));
+        assert!(!html.contains("manual_click_property"));
+
+        assert!(html.contains(&format!(r#"href="/scan/{}/go/crypto_payment?scan={}" id="payment-btn""#, data.property_id, scan_id)));
+
+        let data = redirect_data("Garden Villa", true, false);
+        let html = create_redirect_page(&data, CANONICAL_URL, None, Locale::En);
+        assert!(!html.contains("/go/blockchain"));
+        assert!(!html.contains("/go/crypto_payment"));
+    }
+
+    #[test]
+    fn test_redirect_page_base_name() {
+        let mut data = redirect_data("Garden Villa", true, true);
+        assert!(!create_redirect_page(&data, CANONICAL_URL, None, Locale::En).contains("base-name\""));
+
+        data.base_name = Some("kilimani-flat.base.eth".to_string());
+        let html = create_redirect_page(&data, CANONICAL_URL, None, Locale::En);
+        assert!(html.contains(r#"<a href="https://www.base.org/name/kilimani-flat" target="_blank" rel="noopener noreferrer">🔵 kilimani-flat.base.eth</a>"#));
+    }
+
+    #[test]
+    fn test_redirect_page_onchain_badge() {
+        let mut data = redirect_data("Garden Villa", true, true);
+        assert!(!create_redirect_page(&data, CANONICAL_URL, None, Locale::En).contains(r#"class="onchain-badge""#));
+
+        data.onchain_verified_at = Some("2026-03-05T14:30:00Z".parse().unwrap());
+        assert!(create_redirect_page(&data, CANONICAL_URL, None, Locale::En)
+            .contains(r#"<span class="onchain-badge">⛓ Verified on-chain as of 5 Mar 2026, 14:30 UTC</span>"#));
+        assert!(create_redirect_page(&data, CANONICAL_URL, None, Locale::Sw)
+            .contains("⛓ Imethibitishwa kwenye blockchain kufikia 5 Mar 2026"));
+    }
+
+    #[test]
+    fn test_auto_redirect() {
+        const PROPERTY: &str = "https://www.daobitat.xyz/property/p1";
+        const EXPLORER: &str = "https://explorer.base.org/token/42";
+
+        assert_eq!(
+            auto_redirect(Some(10), AutoRedirectDestination::Property, None, PROPERTY, Some(EXPLORER)),
+            Some(AutoRedirect { url: PROPERTY.to_string(), seconds: 10 })
+        );
+        assert_eq!(
+            auto_redirect(Some(0), AutoRedirectDestination::Blockchain, None, PROPERTY, Some(EXPLORER)),
+            Some(AutoRedirect { url: EXPLORER.to_string(), seconds: 0 })
+        );
+        // Off-chain listings can only go to the property page
+        assert_eq!(
+            auto_redirect(Some(5), AutoRedirectDestination::Blockchain, None, PROPERTY, None).map(|r| r.url),
+            Some(PROPERTY.to_string())
+        );
+        assert!(auto_redirect(Some(10), AutoRedirectDestination::Property, Some("1"), PROPERTY, None).is_none());
+        assert!(auto_redirect(None, AutoRedirectDestination::Property, None, PROPERTY, None).is_none());
+        assert!(auto_redirect(Some(10), AutoRedirectDestination::None, None, PROPERTY, Some(EXPLORER)).is_none());
+    }
+
+    #[test]
+    fn test_only_scans_shown_the_dual_page_join_experiments() {
+        let redirect = |seconds| Some(Some(AutoRedirect { url: "https://www.daobitat.xyz/property/p1".to_string(), seconds }));
+
+        assert!(shows_dual_page(&Some(None)));
+        assert!(shows_dual_page(&redirect(5)));
+        assert!(!shows_dual_page(&redirect(0)));
+        assert!(!shows_dual_page(&None));
+    }
+
+    #[test]
+    fn test_static_redirect_page_has_no_timer() {
+        let html = create_redirect_page(&redirect_data("Garden Villa", true, true), CANONICAL_URL, None, Locale::En);
+        assert!(html.contains("const autoRedirect = null;"));
+        assert!(!html.contains("window.location.href"));
+        assert!(!html.contains("sendFunnelEvent('auto_redirect')"));
+
+        let html = create_redirect_page(
+            &redirect_data("Garden Villa", true, true),
+            CANONICAL_URL,
+            Some(&AutoRedirect { url: "https://explorer.base.org/token/42".to_string(), seconds: 3 }),
+            Locale::En,
+        );
+        assert!(html.contains(r#"window.location.href = "https://explorer.base.org/token/42";"#));
+        assert!(html.contains("}, 3000);"));
+        assert!(html.contains("sendFunnelEvent('auto_redirect');"));
+    }
+
+    #[test]
+    fn test_waitlist_page() {
+        let html = create_waitlist_page("Villa <b>", "507f1f77bcf86cd799439011", WaitlistReason::Capped, Locale::Sw);
+        assert!(html.contains(r#"<html lang="sw">"#));
+        assert!(html.contains("Villa &lt;b&gt;"));
+        assert!(html.contains(r#""/api/scan/507f1f77bcf86cd799439011/waitlist""#));
+        assert!(html.contains(&escape_html(translate(Locale::Sw, "waitlist.heading"))));
+
+        let html = create_waitlist_page("Villa", "507f1f77bcf86cd799439011", WaitlistReason::Sold, Locale::En);
+        assert!(html.contains(&escape_html(translate(Locale::En, "waitlist.sold_heading"))));
+        assert!(!html.contains(&escape_html(translate(Locale::En, "waitlist.heading"))));
+    }
+
+    #[test]
+    fn test_share_meta_tags() {
+        let data = redirect_data("Garden Villa", true, true);
+        let tags = share_meta_tags(&data, CANONICAL_URL, "Westlands, Nairobi");
+
+        assert!(tags.contains(r#"<meta property="og:title" content="Garden Villa">"#));
+        assert!(tags.contains(r#"<meta property="og:description" content="KES 85000 • rent • Westlands, Nairobi">"#));
+        assert!(tags.contains(r#"<meta property="og:image" content="https://cdn.daobitat.xyz/img/1.jpg">"#));
+        assert!(tags.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+
+        let mut data = redirect_data(r#"Villa" onload="alert(1)"#, false, false);
+        data.primary_image = None;
+        let tags = share_meta_tags(&data, CANONICAL_URL, "Nairobi");
+        assert!(tags.contains(r#"<meta name="twitter:card" content="summary">"#));
+        assert!(!tags.contains("og:image"));
+        assert!(!tags.contains(r#"" onload=""#));
+    }
+
+    #[test]
+    fn test_is_allowed_image_url() {
+        let domains = vec!["daobitat.xyz".to_string()];
+
+        assert!(is_allowed_image_url("https://cdn.daobitat.xyz/img/1.jpg", &domains));
+        assert!(!is_allowed_image_url("http://cdn.daobitat.xyz/img/1.jpg", &domains));
+        assert!(!is_allowed_image_url("https://tracker.example.com/pixel.gif", &domains));
+        assert!(!is_allowed_image_url("javascript:alert(1)", &domains));
+    }
+
+    #[test]
+    fn test_document_page() {
+        let verification = DocumentVerification {
+            property_id: "507f1f77bcf86cd799439011".to_string(),
+            property_name: "Garden <Villa>".to_string(),
+            document_id: "deed-1".to_string(),
+            document_name: "Title deed LR 209/1234".to_string(),
+            document_type: crate::models::DocumentType::TitleDeed,
+            document_hash: Some("0x9f86d081884c7d65".to_string()),
+            status: DocumentVerificationStatus::Verified,
+            verifier: Some("00ABCD".to_string()),
+            verified_at: Some("2026-01-12T10:00:00Z".parse().unwrap()),
+            expiry_date: None,
+        };
+
+        let html = create_document_page(&verification, Locale::En);
+        assert!(html.contains(r#"<div class="status verified">✓ Verified by DAO-Bitat</div>"#));
+        assert!(html.contains("<dd>Title deed</dd>"));
+        assert!(html.contains(r#"<code class="hash">0x9f86d081884c7d65</code>"#));
+        assert!(html.contains("<dd>DAO-Bitat verifier #00ABCD</dd>"));
+        assert!(html.contains("<dd>12 Jan 2026</dd>"));
+        assert!(html.contains("Garden &lt;Villa&gt;"));
+
+        let unhashed = DocumentVerification {
+            document_hash: None,
+            status: DocumentVerificationStatus::Unverified,
+            verifier: None,
+            verified_at: None,
+            ..verification
+        };
+        let html = create_document_page(&unhashed, Locale::Sw);
+        assert!(html.contains(r#"class="status unverified""#));
+        assert!(html.contains(translate(Locale::Sw, "document.hash_missing")));
+        assert!(!html.contains("#00ABCD"));
+    }
+
+    #[test]
+    fn test_checkin_page() {
+        let html = create_checkin_page("✅", "You&#x27;re checked in", &["Garden &lt;Villa&gt;".to_string()], Locale::En);
+        assert!(html.contains("<title>Check-in - DAO-Bitat</title>"));
+        assert!(html.contains("<h1>You&#x27;re checked in</h1>"));
+        assert!(html.contains("<p>Garden &lt;Villa&gt;</p>"));
+    }
+
+    #[test]
+    fn test_schedule_page() {
+        let html = create_schedule_page("🏁", "Tukio hili limekwisha", &["Garden &lt;Villa&gt;".to_string()], Locale::Sw);
+        assert!(html.contains("<title>Tukio - DAO-Bitat</title>"));
+        assert!(html.contains("<h1>Tukio hili limekwisha</h1>"));
+        assert!(html.contains("<p>Garden &lt;Villa&gt;</p>"));
+
+        // Codes without a schedule, or scans of properties without a code, aren't held back
+        assert!(schedule_page(None, "Garden Villa", Locale::En).is_none());
+    }
+
+    #[test]
+    fn snapshot_error_page() {
+        insta::assert_snapshot!(create_error_page("Property not found", "507f1f77bcf86cd799439011", Locale::En));
+    }
+
+    #[test]
+    fn test_scan_response_creation() {
+        let response = ScanResponse {
+            success: true,
+            property_id: "test123".to_string(),
+            redirect_type: "dual".to_string(),
+            urls: RedirectUrls {
+                property_url: "https://daobitat.xyz/property/test123".to_string(),
+                blockchain_url: Some("https://explorer.base.org/token/test123".to_string()),
+                payment_url: None,
+                redirect_page_url: "https://qr.daobitat.xyz/scan/test123".to_string(),
+                app_url: None,
+                custom_redirect_url: None,
+            },
+            scan_id: "scan123".to_string(),
+            property: PropertySummary {
+                name: "Garden Villa".to_string(),
+                price: 12_500_000,
+                action: "for sale".to_string(),
+                location: "Kilimani, Nairobi".to_string(),
+                primary_image: None,
+                is_verified: true,
+                crypto_accepted: false,
+                base_name: None,
+            },
+        };
+
+        assert_eq!(response.success, true);
+        assert_eq!(response.property_id, "test123");
+    }
+
+    #[test]
+    fn test_explorer_token_url() {
+        let explorers = BTreeMap::from([
+            (8453, "https://basescan.org".to_string()),
+            (84532, "https://sepolia.basescan.org".to_string()),
+        ]);
+        let mut property_info = PropertyQrInfo {
+            id: mongodb::bson::oid::ObjectId::new(),
+            owner: mongodb::bson::oid::ObjectId::new(),
+            property_name: "Garden Villa".to_string(),
+            location: "Kilimani, Nairobi".to_string(),
+            action: "for sale".to_string(),
+            price: 12_500_000,
+            onchain_id: None,
+            chain_id: Some(84532),
+            owner_wallet_address: None,
+            base_name: None,
+            crypto_accepted: true,
+            images: Vec::new(),
+            is_verified: None,
+            removed: None,
+            sold: false,
+            coordinates: None,
+        };
+        let default_explorer = "https://explorer.base.org";
+
+        assert_eq!(explorer_token_url(&explorers, default_explorer, &property_info), None);
+
+        property_info.onchain_id = Some("42".to_string());
+        assert_eq!(
+            explorer_token_url(&explorers, default_explorer, &property_info).as_deref(),
+            Some("https://sepolia.basescan.org/token/42")
+        );
+
+        // Unknown or unset chains use the default explorer
+        property_info.chain_id = Some(1);
+        assert_eq!(
+            explorer_token_url(&explorers, default_explorer, &property_info).as_deref(),
+            Some("https://explorer.base.org/token/42")
+        );
+        property_info.chain_id = None;
+        assert_eq!(
+            explorer_token_url(&explorers, default_explorer, &property_info).as_deref(),
+            Some("https://explorer.base.org/token/42")
+        );
+
+        assert_eq!(
+            payment_url("https://www.daobitat.xyz", &property_info),
+            Some(format!(
+                "https://www.daobitat.xyz/pay/{}?amount=12500000&currency=KES&connect=wallet",
+                property_info.id.to_hex()
+            ))
+        );
+        property_info.crypto_accepted = false;
+        assert_eq!(payment_url("https://www.daobitat.xyz", &property_info), None);
+    }
+
+    #[test]
+    fn test_property_summary() {
+        let mut property_info = PropertyQrInfo {
+            id: mongodb::bson::oid::ObjectId::new(),
+            owner: mongodb::bson::oid::ObjectId::new(),
+            property_name: "Garden Villa".to_string(),
+            location: "Kilimani, Nairobi".to_string(),
+            action: "for sale".to_string(),
+            price: 12_500_000,
+            onchain_id: None,
+            chain_id: None,
+            owner_wallet_address: None,
+            base_name: None,
+            crypto_accepted: true,
+            images: vec!["https://cdn.daobitat.xyz/img/1.jpg".to_string()],
+            is_verified: None,
+            removed: None,
+            sold: false,
+            coordinates: None,
+        };
+        let domains = vec!["daobitat.xyz".to_string()];
+
+        let summary = property_summary(&property_info, &domains);
+        assert_eq!(summary.name, "Garden Villa");
+        assert_eq!(summary.primary_image.as_deref(), Some("https://cdn.daobitat.xyz/img/1.jpg"));
+        assert!(!summary.is_verified);
+        assert!(summary.crypto_accepted);
+
+        property_info.images = vec!["https://tracker.example.com/pixel.gif".to_string()];
+        assert!(property_summary(&property_info, &domains).primary_image.is_none());
     }
 }
\ No newline at end of file
@@ -0,0 +1,174 @@
+// src/handlers/security_headers.rs
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::config::Settings;
+
+// The HTML pages printed codes land on; the CSP is written for what they load
+const SCAN_PAGE_PREFIXES: [&str; 2] = ["/scan/", "/checkin/"];
+
+/// Header values set on every response, built once from Settings::security_headers
+#[derive(Debug, Clone, Default)]
+pub struct SecurityHeaders {
+    scan_page_csp: Option<HeaderValue>,
+    frame_options: Option<HeaderValue>,
+    referrer_policy: Option<HeaderValue>,
+    hsts: Option<HeaderValue>,
+}
+
+impl SecurityHeaders {
+    /// Headers for the configured environment; none at all when they're turned off
+    pub fn from_settings(settings: &Settings) -> Result<Self, String> {
+        let config = &settings.security_headers;
+        if !config.enabled {
+            return Ok(Self::default());
+        }
+
+        let frame_options = config.frame_options.to_ascii_uppercase();
+        let scan_page_csp = config
+            .scan_page_csp
+            .clone()
+            .unwrap_or_else(|| scan_page_csp(&settings.urls.image_domains, &frame_options));
+        let hsts = (settings.is_production() && config.hsts_max_age_seconds > 0)
+            .then(|| format!("max-age={}; includeSubDomains", config.hsts_max_age_seconds));
+
+        Ok(Self {
+            scan_page_csp: Some(header_value("Content-Security-Policy", &scan_page_csp)?),
+            frame_options: optional_header_value("X-Frame-Options", &frame_options)?,
+            referrer_policy: optional_header_value("Referrer-Policy", &config.referrer_policy)?,
+            hsts: hsts.map(|hsts| header_value("Strict-Transport-Security", &hsts)).transpose()?,
+        })
+    }
+
+    fn apply(&self, headers: &mut HeaderMap, is_scan_page: bool) {
+        let mut set = |name: HeaderName, value: &Option<HeaderValue>| {
+            // Handlers that set their own value keep it
+            if let Some(value) = value {
+                headers.entry(name).or_insert_with(|| value.clone());
+            }
+        };
+        if is_scan_page {
+            set(header::CONTENT_SECURITY_POLICY, &self.scan_page_csp);
+        }
+        set(header::X_FRAME_OPTIONS, &self.frame_options);
+        set(header::REFERRER_POLICY, &self.referrer_policy);
+        set(header::STRICT_TRANSPORT_SECURITY, &self.hsts);
+        if self.scan_page_csp.is_some() {
+            headers.entry(header::X_CONTENT_TYPE_OPTIONS).or_insert(HeaderValue::from_static("nosniff"));
+        }
+    }
+}
+
+/// Middleware for the whole app: security headers on every response, plus the CSP on
+/// HTML served from scan paths
+pub async fn set_security_headers(
+    State(security_headers): State<Arc<SecurityHeaders>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let scan_path = is_scan_page_path(request.uri().path());
+    let mut response = next.run(request).await;
+
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    security_headers.apply(response.headers_mut(), scan_path && is_html);
+    response
+}
+
+/// Policy for the scan pages: their own inline scripts and styles, beacons and forms back
+/// to this service, and images only from the allow-listed hosts
+fn scan_page_csp(image_domains: &[String], frame_options: &str) -> String {
+    let image_sources: String = image_domains
+        .iter()
+        .map(|domain| format!(" https://{domain} https://*.{domain}"))
+        .collect();
+    let frame_ancestors = match frame_options {
+        "SAMEORIGIN" => "; frame-ancestors 'self'",
+        "DENY" => "; frame-ancestors 'none'",
+        _ => "",
+    };
+
+    format!(
+        "default-src 'self'; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'; \
+         img-src 'self' data:{image_sources}; connect-src 'self'; form-action 'self'; \
+         base-uri 'none'; object-src 'none'{frame_ancestors}"
+    )
+}
+
+fn is_scan_page_path(path: &str) -> bool {
+    SCAN_PAGE_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+fn header_value(name: &str, value: &str) -> Result<HeaderValue, String> {
+    HeaderValue::from_str(value).map_err(|_| format!("Invalid {} header value: {}", name, value))
+}
+
+fn optional_header_value(name: &str, value: &str) -> Result<Option<HeaderValue>, String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| header_value(name, value)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_for(settings: &Settings, is_scan_page: bool) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        SecurityHeaders::from_settings(settings).unwrap().apply(&mut headers, is_scan_page);
+        headers
+    }
+
+    #[test]
+    fn test_scan_page_csp() {
+        let csp = scan_page_csp(&["daobitat.xyz".to_string()], "DENY");
+        assert!(csp.contains("img-src 'self' data: https://daobitat.xyz https://*.daobitat.xyz;"));
+        assert!(csp.contains("script-src 'self' 'unsafe-inline'"));
+        assert!(csp.ends_with("frame-ancestors 'none'"));
+        assert!(!scan_page_csp(&[], "").contains("frame-ancestors"));
+    }
+
+    #[test]
+    fn test_security_headers() {
+        let dev = headers_for(&Settings::default_dev(), true);
+        assert!(dev.contains_key(header::CONTENT_SECURITY_POLICY));
+        assert_eq!(dev[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(dev[header::REFERRER_POLICY], "strict-origin-when-cross-origin");
+        assert!(!dev.contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+        let prod = headers_for(&Settings::default_prod(), false);
+        assert!(!prod.contains_key(header::CONTENT_SECURITY_POLICY));
+        assert_eq!(prod[header::STRICT_TRANSPORT_SECURITY], "max-age=31536000; includeSubDomains");
+
+        let mut settings = Settings::default_prod();
+        settings.security_headers.enabled = false;
+        assert!(headers_for(&settings, true).is_empty());
+
+        settings.security_headers.enabled = true;
+        settings.security_headers.frame_options = String::new();
+        settings.security_headers.scan_page_csp = Some("default-src 'self'".to_string());
+        let custom = headers_for(&settings, true);
+        assert_eq!(custom[header::CONTENT_SECURITY_POLICY], "default-src 'self'");
+        assert!(!custom.contains_key(header::X_FRAME_OPTIONS));
+
+        settings.security_headers.referrer_policy = "no-referrer\n".to_string();
+        settings.security_headers.scan_page_csp = Some("default-src\n'self'".to_string());
+        assert!(SecurityHeaders::from_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_is_scan_page_path() {
+        assert!(is_scan_page_path("/scan/507f1f77bcf86cd799439011"));
+        assert!(is_scan_page_path("/checkin/abc123"));
+        assert!(!is_scan_page_path("/api/scan/507f1f77bcf86cd799439011"));
+        assert!(!is_scan_page_path("/admin"));
+    }
+}
@@ -0,0 +1,53 @@
+// src/handlers/storage_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::services::{local_storage::content_type_for, StorageService};
+
+// Stored images are never rewritten in place; a regenerated code gets a new key
+const STORAGE_CACHE_CONTROL: &str = "public, max-age=31536000";
+
+// Application state for serving locally stored files
+#[derive(Clone)]
+pub struct StorageAppState {
+    pub storage: StorageService,
+}
+
+/// Serve a file from the local storage backend
+/// GET /storage/{*key}
+#[utoipa::path(
+    get,
+    path = "/storage/{key}",
+    tag = "qr",
+    params(
+        ("key" = String, Path, description = "Storage key, e.g. qr-images/{property_id}.png")
+    ),
+    responses(
+        (status = 200, description = "Stored file", content_type = "image/png"),
+        (status = 404, description = "No such file"),
+    )
+)]
+pub async fn get_stored_file(
+    State(state): State<Arc<StorageAppState>>,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    match state.storage.download_file(&key).await {
+        Ok(bytes) => Ok((
+            [
+                (header::CONTENT_TYPE, content_type_for(&key)),
+                (header::CACHE_CONTROL, STORAGE_CACHE_CONTROL),
+            ],
+            bytes,
+        )),
+        Err(e) => {
+            debug!("Stored file {} not served: {}", key, e);
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+}
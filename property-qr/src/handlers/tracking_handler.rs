@@ -0,0 +1,123 @@
+// src/handlers/tracking_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::Json as ResponseJson,
+};
+use std::sync::Arc;
+use tracing::{info, error};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::{TrackingConfigResponse, UpsertTrackingConfigRequest};
+use crate::services::{TrackingService, tracking_service::TrackingError};
+
+// Application state for tracking config handlers
+#[derive(Clone)]
+pub struct TrackingAppState {
+    pub tracking_service: TrackingService,
+}
+
+fn tracking_error_response(e: TrackingError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        TrackingError::NotFound => (StatusCode::NOT_FOUND, "tracking_config_not_found"),
+        TrackingError::InvalidConfig(_) => (StatusCode::BAD_REQUEST, "invalid_tracking_config"),
+        TrackingError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "tracking_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Get an owner's analytics forwarding config
+/// GET /tracking/{owner_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/tracking/{owner_id}",
+    tag = "tracking",
+    params(
+        ("owner_id" = String, Path, description = "Property owner ID"),
+    ),
+    responses(
+        (status = 200, description = "Tracking config", body = SuccessResponse<TrackingConfigResponse>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_tracking_config(
+    State(state): State<Arc<TrackingAppState>>,
+    Path(owner_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<TrackingConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.tracking_service.get_config(&owner_id).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to get tracking config for owner {}: {}", owner_id, e);
+            Err(tracking_error_response(e))
+        }
+    }
+}
+
+/// Create or replace an owner's analytics forwarding config
+/// PUT /tracking/{owner_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/tracking/{owner_id}",
+    tag = "tracking",
+    params(
+        ("owner_id" = String, Path, description = "Property owner ID"),
+    ),
+    request_body = UpsertTrackingConfigRequest,
+    responses(
+        (status = 200, description = "Tracking config saved", body = SuccessResponse<TrackingConfigResponse>),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn upsert_tracking_config(
+    State(state): State<Arc<TrackingAppState>>,
+    Path(owner_id): Path<String>,
+    Json(request): Json<UpsertTrackingConfigRequest>,
+) -> Result<ResponseJson<SuccessResponse<TrackingConfigResponse>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Updating tracking config for owner: {}", owner_id);
+
+    match state.tracking_service.upsert_config(&owner_id, request).await {
+        Ok(config) => Ok(Json(SuccessResponse::new(config.to_response()))),
+        Err(e) => {
+            error!("Failed to update tracking config for owner {}: {}", owner_id, e);
+            Err(tracking_error_response(e))
+        }
+    }
+}
+
+/// Remove an owner's analytics forwarding config
+/// DELETE /tracking/{owner_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tracking/{owner_id}",
+    tag = "tracking",
+    params(
+        ("owner_id" = String, Path, description = "Property owner ID"),
+    ),
+    responses(
+        (status = 200, description = "Tracking config removed", body = SuccessResponse<serde_json::Value>),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn delete_tracking_config(
+    State(state): State<Arc<TrackingAppState>>,
+    Path(owner_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<serde_json::Value>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    info!("Removing tracking config for owner: {}", owner_id);
+
+    match state.tracking_service.delete_config(&owner_id).await {
+        Ok(()) => Ok(Json(SuccessResponse::new(serde_json::json!({
+            "deleted": true,
+            "ownerId": owner_id
+        })))),
+        Err(e) => {
+            error!("Failed to remove tracking config for owner {}: {}", owner_id, e);
+            Err(tracking_error_response(e))
+        }
+    }
+}
@@ -0,0 +1,106 @@
+// src/handlers/validated_json.rs
+
+use axum::{
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::handlers::ErrorResponse;
+use crate::utils::Validate;
+
+/// JSON body that has passed its Validate checks. A body that fails them is answered with
+/// a 400 listing every invalid field; one that isn't JSON, or is over the body size limit,
+/// with the matching status in the usual error shape.
+#[derive(Debug, Clone)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<ErrorResponse>);
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|rejection| {
+                (rejection.status(), Json(ErrorResponse::new("invalid_body", &rejection.body_text())))
+            })?;
+
+        value.validate().map_err(|errors| {
+            let message = match errors.as_slice() {
+                [error] => error.message.clone(),
+                _ => format!("{} fields are invalid", errors.len()),
+            };
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new("validation_error", &message).with_details(errors)),
+            )
+        })?;
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::FieldError;
+    use crate::models::BatchGenerateQrRequest;
+    use axum::{body::Body, extract::DefaultBodyLimit, http::header, routing::post, Router};
+    use tower::ServiceExt;
+
+    async fn extract(body: &str) -> Result<ValidatedJson<BatchGenerateQrRequest>, (StatusCode, Json<ErrorResponse>)> {
+        let request = Request::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        ValidatedJson::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_validated_json() {
+        let ValidatedJson(request) = extract(r#"{"propertyIds": ["507f1f77bcf86cd799439011"]}"#).await.unwrap();
+        assert_eq!(request.property_ids.len(), 1);
+
+        // Every bad ID is reported, not just the first
+        let (status, Json(error)) = extract(r#"{"propertyIds": ["507f1f77bcf86cd799439011", "x", "y"]}"#).await.unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error.message, "2 fields are invalid");
+        let fields: Vec<&str> = error.details.iter().map(|detail| detail.field.as_str()).collect();
+        assert_eq!(fields, vec!["propertyIds[1]", "propertyIds[2]"]);
+
+        let (_, Json(error)) = extract(r#"{"propertyIds": []}"#).await.unwrap_err();
+        assert_eq!(*error.details, [FieldError {
+            field: "propertyIds".to_string(),
+            message: "Property IDs list cannot be empty".to_string(),
+            code: "EMPTY_LIST".to_string(),
+        }]);
+
+        let (status, Json(error)) = extract(r#"{"propertyIds": "507f1f77bcf86cd799439011"}"#).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.error, "invalid_body");
+        assert!(error.details.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_body_size_limit() {
+        async fn handler(ValidatedJson(request): ValidatedJson<BatchGenerateQrRequest>) -> String {
+            request.property_ids.len().to_string()
+        }
+        let app = Router::new().route("/", post(handler)).layer(DefaultBodyLimit::max(64));
+
+        let body = format!(r#"{{"propertyIds": {:?}}}"#, vec!["507f1f77bcf86cd799439011"; 5]);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,91 @@
+// src/handlers/waitlist_handler.rs
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    Json,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::models::WaitlistEntryResponse;
+use crate::services::{WaitlistService, waitlist_service::{export_csv, WaitlistError}};
+
+// Application state for waitlist handlers
+#[derive(Clone)]
+pub struct WaitlistAppState {
+    pub waitlist_service: WaitlistService,
+}
+
+fn waitlist_error_response(e: WaitlistError) -> (StatusCode, ResponseJson<ErrorResponse>) {
+    let (status_code, error_type) = match e {
+        WaitlistError::InvalidContact(_) => (StatusCode::BAD_REQUEST, "invalid_contact"),
+        WaitlistError::DatabaseError(_) => (StatusCode::INTERNAL_SERVER_ERROR, "waitlist_operation_failed"),
+    };
+
+    (status_code, Json(ErrorResponse::new(error_type, &e.to_string())))
+}
+
+/// Prospects waiting to hear back about a capped or sold property, oldest first
+/// GET /waitlist/{property_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/waitlist/{property_id}",
+    tag = "waitlist",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Waitlist, oldest first", body = SuccessResponse<Vec<WaitlistEntryResponse>>),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn get_waitlist(
+    State(state): State<Arc<WaitlistAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<ResponseJson<SuccessResponse<Vec<WaitlistEntryResponse>>>, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.waitlist_service.list(&property_id).await {
+        Ok(entries) => Ok(Json(SuccessResponse::new(
+            entries.iter().map(|entry| entry.to_response()).collect(),
+        ))),
+        Err(e) => {
+            error!("Failed to get waitlist for property {}: {}", property_id, e);
+            Err(waitlist_error_response(e.into()))
+        }
+    }
+}
+
+/// Download a property's waitlist as CSV
+/// GET /waitlist/{property_id}/export
+#[utoipa::path(
+    get,
+    path = "/api/v1/waitlist/{property_id}/export",
+    tag = "waitlist",
+    params(("property_id" = String, Path, description = "Property ID")),
+    responses(
+        (status = 200, description = "Waitlist as a CSV attachment", content_type = "text/csv"),
+        (status = 500, description = "Internal error", body = ErrorResponse),
+    )
+)]
+pub async fn export_waitlist(
+    State(state): State<Arc<WaitlistAppState>>,
+    Path(property_id): Path<String>,
+) -> Result<Response, (StatusCode, ResponseJson<ErrorResponse>)> {
+    match state.waitlist_service.list(&property_id).await {
+        Ok(entries) => {
+            // Property IDs are ObjectId hex, but keep the header well-formed whatever the path holds
+            let filename: String = property_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, format!("attachment; filename=\"waitlist-{}.csv\"", filename)),
+                ],
+                export_csv(&entries),
+            ).into_response())
+        }
+        Err(e) => {
+            error!("Failed to export waitlist for property {}: {}", property_id, e);
+            Err(waitlist_error_response(e.into()))
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// src/lib.rs
+
+// Library target so fuzz targets and integration tests can reach the
+// parsers and models; the server binary lives in main.rs
+pub mod config;
+pub mod models;
+pub mod services;
+pub mod utils;
+pub mod handlers;
+pub mod errors;
+pub mod graphql;
+pub mod grpc;
+pub mod routes;
+pub mod test_support;
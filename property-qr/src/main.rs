@@ -1,10 +1,12 @@
 // src/main.rs
 
 use axum::{
+    extract::DefaultBodyLimit,
     http::{
         header::{AUTHORIZATION, CONTENT_TYPE},
-        HeaderValue, Method,
+        HeaderName, HeaderValue, Method,
     },
+    middleware,
     Router,
 };
 use std::{net::SocketAddr, sync::Arc, time::Duration};
@@ -14,35 +16,89 @@ use tower_http::{
     trace::TraceLayer,
     timeout::TimeoutLayer,
 };
-use tracing::{info, error};
+use tracing::{info, warn, error};
 use std::error::Error;
 
-// Import your modules
-mod config;
-mod models;
-mod services;
-mod utils;
-mod handlers;
-mod errors;
-mod routes;
-
 // Import configuration and services
-use config::Settings;
-use services::{AnalyticsService, PropertyService, QrGeneratorService, S3Service};
-use handlers::{AppState, ScanAppState};
-use routes::{qr_routes, scan_routes, health_routes};
+use property_qr::config::{ConfigArgs, Settings, StorageBackendKind};
+use property_qr::graphql::build_schema;
+use property_qr::grpc::{GrpcAuth, PropertyQrGrpc, PropertyQrServer};
+use property_qr::models::SelfTestReport;
+use property_qr::services::{AnalyticsService, AnomalyDetector, AuditService, AutoRedirectService, BlockchainService, BookingCheckinService, DependencyRegistry, DigestService, DocumentQrService, EmailService, EventPublisher, ExperimentService, PageCache, GeoBlockService, GeolocationService, HookService, ImpersonationService, JwksService, LoadShedder, NotificationService, OrganizationService, PosterService, PrivacyPolicy, PropertyService, PropertyWatcher, QrGeneratorService, QrStyleService, ScanCapService, ShareOfferService, StorageService, SmsService, TrackingService, LinkService, WaitlistService};
+use property_qr::handlers::{AdminAppState, AnalyticsAppState, AppState, AuditAppState, AuthAppState, AutoRedirectAppState, GeoBlockAppState, GraphQlAppState, HealthAppState, HookAppState, ImpersonationAppState, OrgAppState, OwnerAppState, PropertyAppState, QrStyleAppState, ScanAppState, ScanCapAppState, SecurityHeaders, TrackingAppState, WaitlistAppState, LinkAppState, MetricsAppState, StorageAppState, ACTOR_USER_HEADER, IMPERSONATION_HEADER, ORG_API_KEY_HEADER, enforce_canonical_host, set_security_headers, shed_load};
+use property_qr::services::dependency_registry::ProbeResult;
+use property_qr::utils::{HostPolicy, JwtVerifier, SessionSigner};
+use property_qr::routes::{admin_routes, analytics_routes, audit_routes, auto_redirect_routes, public_stats_routes, geo_block_routes, graphql_routes, qr_routes, property_routes, qr_style_routes, scan_cap_routes, scan_routes, waitlist_routes, organization_routes, owner_routes, health_routes, hook_routes, metrics_routes, storage_routes, link_routes, short_link_routes, docs_routes};
+
+// How often daily counters and system analytics are rebuilt from raw events
+const ANALYTICS_RECONCILE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Bounded analytics worker queue and how many scans it applies per batch
+const ANALYTICS_QUEUE_CAPACITY: usize = 10_000;
+const ANALYTICS_BATCH_SIZE: usize = 100;
+
+// Stored scan events are brought up to the current schema in batches after startup
+const SCAN_EVENT_UPGRADE_BATCH_SIZE: i64 = 500;
+const SCAN_EVENT_UPGRADE_PAUSE: Duration = Duration::from_millis(200);
+
+// How often waitlisted prospects are checked against relisted and newly listed properties
+const WAITLIST_NOTIFY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// How often the auth service's signing keys are re-fetched, to pick up rotations
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Landing pages render live listing details, cached briefly so popular codes don't hit the database per scan
+const LISTING_CACHE_TTL: Duration = Duration::from_secs(60);
+const LISTING_CACHE_CAPACITY: usize = 5_000;
+
+// Rendered dual pages are reused for this long unless the property changes first
+const LANDING_PAGE_CACHE_TTL: Duration = Duration::from_secs(30);
+const LANDING_PAGE_CACHE_CAPACITY: usize = 2_000;
+
+// Analytics updates that failed are retried from the dead-letter queue in batches
+const DEAD_LETTER_RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEAD_LETTER_RETRY_BATCH_SIZE: i64 = 100;
+
+// How often QR records and S3 images are reconciled, and how long a write may be in flight first
+const QR_STORAGE_RECONCILE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+const QR_STORAGE_GRACE_MINUTES: i64 = 15;
+
+// How often every QR image is checked against its upload, when enabled
+const QR_ASSET_VERIFY_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// How often scheduled codes are activated and deactivated as their windows open and end
+const QR_SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+// How often the primary S3 bucket is probed when a secondary is configured
+const STORAGE_HEALTH_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+// Upper bound on waiting for in-flight analytics writes after the server stops
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     // Initialize logging
     tracing_subscriber::fmt::init();
     
-    info!("Starting DAO-Bitat QR Service...");
-    
-    // Load configuration
-    let settings = Settings::from_env()
+    // Load configuration: defaults, then the config file, environment variables and --set flags
+    let args = ConfigArgs::parse(std::env::args().skip(1))
+        .map_err(|e| format!("Invalid arguments: {}", e))?;
+    if let Some(command) = args.command.as_deref().filter(|command| *command != "seed") {
+        return Err(format!("Unknown command {}", command).into());
+    }
+    let settings = Settings::load(&args)
         .map_err(|e| format!("Failed to load configuration: {}", e))?;
     
+    // --print-config shows what a deployment would run with, then checks it
+    if args.print_config {
+        println!("{}", settings.to_redacted_toml()?);
+        settings.validate()
+            .map_err(|e| format!("Invalid configuration: {}", e))?;
+        return Ok(());
+    }
+    
+    info!("Starting DAO-Bitat QR Service...");
+    
     // Validate configuration
     settings.validate()
         .map_err(|e| format!("Invalid configuration: {}", e))?;
@@ -58,41 +114,401 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let database = client.database(&settings.database.database_name);
     
     // Test MongoDB connection
-    database.run_command(mongodb::bson::doc! {"ping": 1}, None).await
+    database.run_command(mongodb::bson::doc! {"ping": 1}).await
         .map_err(|e| format!("MongoDB connection test failed: {}", e))?;
     
     info!("MongoDB connection verified");
     
+    // `property-qr seed` fills a local database with sample data and exits
+    if args.command.as_deref() == Some("seed") {
+        if settings.is_production() {
+            return Err("Refusing to seed a production database".into());
+        }
+        let summary = property_qr::test_support::seed_database(&database).await
+            .map_err(|e| format!("Seeding failed: {}", e))?;
+        info!("Seed complete: {} properties, {} scan events", summary.properties, summary.scan_events);
+        return Ok(());
+    }
+    
     // Initialize services
-    let property_service = PropertyService::new(&database);
-    let s3_service = S3Service::new(
-        settings.aws.s3_bucket.clone(),
-        settings.aws.region.clone(),
-    ).map_err(|e| format!("Failed to create S3 service: {}", e))?;
+    let property_service = PropertyService::new(&database)
+        .with_listing_cache(LISTING_CACHE_TTL, LISTING_CACHE_CAPACITY);
+    // With a secondary S3 bucket, uploads go to both and images are served from the
+    // secondary while the primary is down
+    let storage = StorageService::from_config(&settings.storage, &settings.aws, &settings.urls.base_url)
+        .map_err(|e| format!("Failed to create storage service: {}", e))?;
+    storage.spawn_health_probe(STORAGE_HEALTH_PROBE_INTERVAL);
+    
+    // Sheds analytics work, then non-scan traffic, as runtime lag grows
+    let load_shedder = LoadShedder::new(settings.load_shedding.clone());
+    load_shedder.spawn_probe();
+    
+    let geolocation_service = GeolocationService::from_config(&settings.geolocation)
+        .map_err(|e| format!("Failed to set up geolocation: {}", e))?;
+    
+    // Scan event streaming is optional; a broker that's down shouldn't stop scans being served
+    let event_publisher = match EventPublisher::from_config(&settings.event_stream).await {
+        Ok(event_publisher) => event_publisher,
+        Err(e) => {
+            error!("Scan event streaming disabled: {}", e);
+            None
+        }
+    };
     
-    let analytics_service = AnalyticsService::new(&database);
+    let hook_service = HookService::new(&database, settings.urls.hook_domains.clone());
+    let mut analytics_service = AnalyticsService::new(&database)
+        .with_hooks(hook_service.clone())
+        .with_geolocation(geolocation_service.clone())
+        .with_property_service(property_service.clone())
+        .with_load_shedder(load_shedder.clone());
+    if let Some(event_publisher) = event_publisher.clone() {
+        analytics_service = analytics_service.with_event_publisher(event_publisher);
+    }
+    let analytics_service = analytics_service.with_worker(ANALYTICS_QUEUE_CAPACITY, ANALYTICS_BATCH_SIZE);
+    analytics_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create analytics indexes: {}", e))?;
+    analytics_service.spawn_reconciliation(ANALYTICS_RECONCILE_INTERVAL);
+    analytics_service.spawn_scan_event_upgrade(SCAN_EVENT_UPGRADE_BATCH_SIZE, SCAN_EVENT_UPGRADE_PAUSE);
+    analytics_service.spawn_dead_letter_retry(DEAD_LETTER_RETRY_INTERVAL, DEAD_LETTER_RETRY_BATCH_SIZE);
+    if settings.retention.enabled {
+        analytics_service.spawn_retention(settings.retention.clone());
+    }
+    // Page the team on scan spikes and on active codes going quiet
+    if settings.anomaly.enabled {
+        AnomalyDetector::new(analytics_service.clone(), settings.anomaly.clone()).spawn();
+    }
+    
+    let shutdown_analytics = analytics_service.clone();
+    
+    let tracking_service = TrackingService::new(&database);
+    let link_service = LinkService::new(
+        &database,
+        settings.urls.base_url.clone(),
+        settings.urls.link_domains.clone(),
+    );
+    let sms_service = SmsService::new(settings.sms.clone());
+    let impersonation_service = ImpersonationService::new(&database);
+    impersonation_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create impersonation indexes: {}", e))?;
+    let organization_service = OrganizationService::new(&database);
+    organization_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create organization indexes: {}", e))?;
+    let geo_block_service = GeoBlockService::new(&database);
+    geo_block_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create geo-blocking indexes: {}", e))?;
+    let scan_cap_service = ScanCapService::new(&database).with_hooks(hook_service.clone());
+    scan_cap_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create scan cap indexes: {}", e))?;
+    let auto_redirect_service = AutoRedirectService::new(&database);
+    auto_redirect_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create auto-redirect indexes: {}", e))?;
+    let email_service = EmailService::from_config(&settings.email, &settings.aws)
+        .map_err(|e| format!("Failed to set up email: {}", e))?;
+    let notification_service = NotificationService::new(email_service.clone())
+        .with_sms(sms_service.clone());
+    if !notification_service.email_enabled() {
+        info!("EMAIL_PROVIDER not set, waitlist notifications go out by SMS only");
+    }
+    let waitlist_service = WaitlistService::new(
+        &database,
+        property_service.clone(),
+        settings.urls.daobitat_base_url.clone(),
+    )
+    .with_hooks(hook_service.clone())
+    .with_notifications(notification_service.clone());
+    waitlist_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create waitlist indexes: {}", e))?;
+    waitlist_service.spawn_notifier(WAITLIST_NOTIFY_INTERVAL);
+    let qr_style_service = QrStyleService::new(&database);
+    qr_style_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create QR style indexes: {}", e))?;
+    let page_cache = PageCache::new(LANDING_PAGE_CACHE_TTL, LANDING_PAGE_CACHE_CAPACITY);
+    let audit_service = AuditService::new(&database);
+    audit_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create audit log indexes: {}", e))?;
     let qr_generator_service = QrGeneratorService::new(
         &database,
         property_service.clone(),
-        s3_service.clone(),
+        storage.clone(),
+        settings.urls.base_url.clone(),
+    )
+    .with_page_cache(page_cache.clone())
+    .with_payload_mode(settings.qr.payload_mode)
+    .with_styles(qr_style_service.clone())
+    .with_audit_log(audit_service.clone());
+    qr_generator_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create QR metadata indexes: {}", e))?;
+    let document_qr_service = DocumentQrService::new(
+        &database,
+        property_service.clone(),
+        qr_generator_service.clone(),
         settings.urls.base_url.clone(),
     );
+    document_qr_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create document QR indexes: {}", e))?;
+    let share_offer_service = ShareOfferService::new(
+        &database,
+        property_service.clone(),
+        qr_generator_service.clone(),
+        settings.urls.base_url.clone(),
+        settings.urls.daobitat_base_url.clone(),
+    );
+    share_offer_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create share offer indexes: {}", e))?;
+    let booking_checkin_service = BookingCheckinService::new(
+        &database,
+        property_service.clone(),
+        qr_generator_service.clone(),
+        settings.urls.base_url.clone(),
+    );
+    booking_checkin_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create booking check-in indexes: {}", e))?;
+    // Cached like the landing pages it picks variants of
+    let experiment_service = ExperimentService::new(&database)
+        .with_active_cache(LANDING_PAGE_CACHE_TTL, LANDING_PAGE_CACHE_CAPACITY);
+    experiment_service.ensure_indexes().await
+        .map_err(|e| format!("Failed to create experiment indexes: {}", e))?;
+    qr_generator_service.spawn_storage_reconciliation(QR_STORAGE_RECONCILE_INTERVAL, chrono::Duration::minutes(QR_STORAGE_GRACE_MINUTES));
+    if settings.qr.verify_assets {
+        qr_generator_service.spawn_asset_verification(QR_ASSET_VERIFY_INTERVAL);
+    }
+    qr_generator_service.spawn_schedule_job(QR_SCHEDULE_INTERVAL);
+    
+    // Regenerate and deactivate codes as listings are edited, alongside the property webhook
+    if settings.qr.watch_properties {
+        PropertyWatcher::new(&database, qr_generator_service.clone()).spawn();
+    }
     
     info!("Services initialized successfully");
     
+    // Catch broken storage credentials or QR libraries before taking traffic;
+    // a failure keeps /health/ready at 503 rather than stopping the process
+    let self_test = if settings.server.startup_self_test {
+        Some(qr_generator_service.run_self_test().await)
+    } else {
+        None
+    };
+    
+    // Old hostnames keep answering scans after a domain cutover
+    let host_policy = HostPolicy::new(&settings.urls.base_url, &settings.urls.alias_hosts);
+    if !settings.urls.alias_hosts.is_empty() {
+        info!("Serving scans on alias hosts: {}", settings.urls.alias_hosts.join(", "));
+    }
+    
+    // Scan session cookies stay valid across restarts only with a configured secret
+    let session_signer = match settings.server.session_secret.as_deref() {
+        Some(secret) => SessionSigner::new(secret),
+        None => {
+            warn!("SESSION_SECRET not set; scan sessions will reset on restart");
+            SessionSigner::random()
+        }
+    };
+    
     // Create application states
     let app_state = Arc::new(AppState {
         qr_generator: qr_generator_service,
+        property_service: property_service.clone(),
+        poster_service: PosterService::new(settings.urls.image_domains.clone()),
+        document_qr: document_qr_service.clone(),
+        share_offers: share_offer_service.clone(),
+        booking_checkins: booking_checkin_service.clone(),
+        experiments: experiment_service.clone(),
+        admin_api_key: settings.server.admin_api_key.clone(),
     });
     
+    let admin_state = settings.server.admin_api_key.clone().map(|api_key| Arc::new(AdminAppState {
+        qr_generator: app_state.qr_generator.clone(),
+        property_service: property_service.clone(),
+        impersonation_service: impersonation_service.clone(),
+        organization_service: organization_service.clone(),
+        auto_redirect_service: auto_redirect_service.clone(),
+        api_key,
+        secure_cookies: settings.urls.base_url.starts_with("https://"),
+    }));
+    
+    // Support staff acting as an owner on the owner-facing routes
+    let impersonation_state = Arc::new(ImpersonationAppState {
+        impersonation_service,
+        property_service: property_service.clone(),
+    });
+    
+    let org_state = Arc::new(OrgAppState {
+        organization_service,
+        property_service: property_service.clone(),
+        qr_generator: app_state.qr_generator.clone(),
+        analytics_service: analytics_service.clone(),
+        privacy: PrivacyPolicy::from(&settings.privacy),
+    });
+    
+    let blockchain_service = BlockchainService::from_config(&settings.blockchain);
     let scan_state = Arc::new(ScanAppState {
         qr_generator: app_state.qr_generator.clone(),
-        property_service,
+        property_service: property_service.clone(),
         analytics_service,
+        tracking_service: tracking_service.clone(),
+        link_service: link_service.clone(),
+        sms_service,
+        geo_block_service: geo_block_service.clone(),
+        scan_cap_service: scan_cap_service.clone(),
+        waitlist_service: waitlist_service.clone(),
+        document_qr_service,
+        share_offer_service,
+        booking_checkin_service,
+        experiment_service: experiment_service.clone(),
+        app_links: settings.app_links.clone(),
+        session_signer,
+        auto_redirect_seconds: settings.qr.auto_redirect_seconds,
+        default_target: settings.qr.default_target,
+        auto_redirect_service: auto_redirect_service.clone(),
+        host_policy: host_policy.clone(),
+        image_domains: settings.urls.image_domains.clone(),
         daobitar_base_url: settings.urls.daobitat_base_url.clone(),
         blockchain_explorer_base_url: settings.urls.blockchain_explorer_base_url.clone(),
+        blockchain_explorers: settings.urls.blockchain_explorers.clone(),
+        blockchain_service: blockchain_service.clone(),
+        page_cache,
     });
     
+    let mut dependencies = register_dependencies(
+        &database,
+        storage.clone(),
+        geolocation_service,
+        hook_service.clone(),
+        notification_service,
+        event_publisher,
+        self_test.clone(),
+    );
+    if let Some(blockchain_service) = blockchain_service {
+        register_blockchain_probe(&mut dependencies, blockchain_service);
+    }
+    
+    let health_state = Arc::new(HealthAppState {
+        self_test,
+        dependencies,
+    });
+    
+    let analytics_state = Arc::new(AnalyticsAppState {
+        analytics_service: scan_state.analytics_service.clone(),
+        experiment_service,
+        public_privacy: settings.privacy.public_stats_noise.then(|| PrivacyPolicy::from(&settings.privacy)),
+    });
+    
+    let geo_block_state = Arc::new(GeoBlockAppState {
+        geo_block_service,
+    });
+    
+    let scan_cap_state = Arc::new(ScanCapAppState {
+        scan_cap_service,
+    });
+    
+    // Role checks on the QR management API, from tokens issued by the main auth service
+    let verifier = match (&settings.server.jwks_url, &settings.server.jwt_secret) {
+        (None, None) => {
+            warn!("Neither JWKS_URL nor JWT_SECRET set; the QR management API is open to any caller");
+            None
+        }
+        (jwks_url, jwt_secret) => {
+            let mut verifier = JwtVerifier::new(settings.server.jwt_issuer.clone());
+            if let Some(secret) = jwt_secret {
+                verifier = verifier.with_hmac_secret(secret);
+            }
+            // Until the first fetch succeeds, RS256 tokens are rejected
+            if let Some(jwks_url) = jwks_url {
+                let jwks_service = JwksService::new(jwks_url.clone(), verifier.clone());
+                match jwks_service.refresh().await {
+                    Ok(count) => info!("Loaded {} JWKS signing keys from {}", count, jwks_url),
+                    Err(e) => error!("Failed to load JWKS from {}: {}", jwks_url, e),
+                }
+                jwks_service.spawn_refresher(JWKS_REFRESH_INTERVAL);
+            }
+            Some(verifier)
+        }
+    };
+    let auth_state = Arc::new(AuthAppState {
+        verifier,
+        admin_api_key: settings.server.admin_api_key.clone(),
+        property_service: property_service.clone(),
+    });
+    
+    let audit_state = Arc::new(AuditAppState {
+        audit_service,
+        admin_api_key: settings.server.admin_api_key.clone(),
+    });
+    
+    let graphql_state = Arc::new(GraphQlAppState {
+        schema: build_schema(
+            scan_state.qr_generator.clone(),
+            property_service.clone(),
+            scan_state.analytics_service.clone(),
+        ),
+    });
+    
+    let grpc_service = PropertyQrGrpc {
+        qr_generator: scan_state.qr_generator.clone(),
+        property_service: property_service.clone(),
+        analytics_service: scan_state.analytics_service.clone(),
+        auth: auth_state.clone(),
+    };
+    let grpc_auth = GrpcAuth::new(auth_state.clone());
+    
+    // Weekly scan summaries for owners, unless they opt out
+    let digest_service = DigestService::new(
+        &database,
+        property_service.clone(),
+        scan_state.analytics_service.clone(),
+        email_service,
+    );
+    if settings.digest.enabled {
+        if digest_service.email_enabled() {
+            digest_service.spawn_scheduler(settings.digest.clone());
+        } else {
+            warn!("DIGEST_ENABLED is set but no email provider is configured; weekly digests are off");
+        }
+    }
+    
+    let owner_state = Arc::new(OwnerAppState {
+        qr_generator: scan_state.qr_generator.clone(),
+        property_service: property_service.clone(),
+        analytics_service: scan_state.analytics_service.clone(),
+        digest_service,
+    });
+    
+    let property_state = Arc::new(PropertyAppState {
+        property_service,
+    });
+    
+    let qr_style_state = Arc::new(QrStyleAppState {
+        qr_style_service,
+    });
+    
+    let auto_redirect_state = Arc::new(AutoRedirectAppState {
+        auto_redirect_service,
+    });
+    
+    let waitlist_state = Arc::new(WaitlistAppState {
+        waitlist_service,
+    });
+    
+    let hook_state = Arc::new(HookAppState {
+        hook_service,
+    });
+    
+    let tracking_state = Arc::new(TrackingAppState {
+        tracking_service,
+    });
+    
+    let link_state = Arc::new(LinkAppState {
+        link_service,
+        analytics_service: scan_state.analytics_service.clone(),
+        daobitar_base_url: settings.urls.daobitat_base_url.clone(),
+    });
+    
+    let metrics_state = Arc::new(MetricsAppState {
+        analytics_service: scan_state.analytics_service.clone(),
+    });
+    
+    let storage_state = Arc::new(StorageAppState { storage });
+    
     // Configure CORS
     let cors = CorsLayer::new()
         .allow_origin(
@@ -102,26 +518,104 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(|e| format!("Invalid CORS origin: {}", e))?,
         )
-        .allow_headers([AUTHORIZATION, CONTENT_TYPE])
+        .allow_headers([AUTHORIZATION, CONTENT_TYPE, HeaderName::from_static(IMPERSONATION_HEADER), HeaderName::from_static(ORG_API_KEY_HEADER), HeaderName::from_static(ACTOR_USER_HEADER)])
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::PATCH]);
     
+    // Frame, referrer and HSTS headers everywhere; the CSP on scan pages
+    let security_headers = Arc::new(SecurityHeaders::from_settings(&settings)?);
+    
     // Build the application router
-    let app = Router::new()
+    let mut app = Router::new()
         // Health routes
-        .nest("/health", health_routes())
+        .nest("/health", health_routes(health_state))
+        
+        // Prometheus scrape endpoint
+        .merge(metrics_routes(metrics_state))
+        
+        // OpenAPI spec and Swagger UI
+        .merge(docs_routes())
         
         // QR management API routes
-        .nest("/api/v1", qr_routes(app_state))
+        .nest("/api/v1", qr_routes(app_state, auth_state.clone()))
+        
+        // An owner's codes for the app's "My listings" page, and their analytics forwarding config
+        .nest("/api/v1", owner_routes(owner_state, tracking_state, auth_state.clone(), impersonation_state.clone()))
+        
+        // What listings need before they can get codes
+        .nest("/api/v1", property_routes(property_state, auth_state.clone()))
+        
+        // Dashboard queries across QR codes, listings and analytics
+        .merge(graphql_routes(graphql_state, auth_state.clone()))
+        
+        // Who changed which QR code, for compliance disputes
+        .nest("/api/v1", audit_routes(audit_state, auth_state.clone()))
+        
+        // Property analytics routes
+        .nest("/api/v1", analytics_routes(analytics_state.clone(), auth_state.clone(), impersonation_state.clone()))
+        
+        // Public per-neighborhood scan stats
+        .nest("/api/v1", public_stats_routes(analytics_state))
+        
+        // Per-owner and per-property country blocking
+        .nest("/api/v1", geo_block_routes(geo_block_state, auth_state.clone(), impersonation_state.clone()))
         
-        // Scan routes (public-facing)
-        .merge(scan_routes(scan_state))
+        // Daily scan caps for limited-release listings
+        .nest("/api/v1", scan_cap_routes(scan_cap_state, auth_state.clone(), impersonation_state.clone()))
         
+        // Named QR style presets, e.g. per partner brand
+        .nest("/api/v1", qr_style_routes(qr_style_state, auth_state.clone()))
+        
+        // Where the dual page sends visitors on its own, per property
+        .nest("/api/v1", auto_redirect_routes(auto_redirect_state, auth_state.clone(), impersonation_state.clone()))
+        
+        // Waitlists for sold and capped listings
+        .nest("/api/v1", waitlist_routes(waitlist_state, auth_state.clone(), impersonation_state.clone()))
+        
+        // REST hook routes for no-code integrations
+        .nest("/api/v1", hook_routes(hook_state, auth_state.clone()))
+        
+        // Agency-wide management across member owners
+        .nest("/api/v1", organization_routes(org_state))
+        
+        // Short link management routes
+        .nest("/api/v1", link_routes(link_state.clone(), auth_state.clone()))
+        
+        // Scan routes (public-facing), served on the canonical host and its aliases
+        .merge(
+            Router::new()
+                .merge(scan_routes(scan_state))
+                .merge(short_link_routes(link_state))
+                .route_layer(middleware::from_fn_with_state(
+                    Arc::new(host_policy),
+                    enforce_canonical_host,
+                )),
+        );
+    
+    // Files on disk are only served when they're where QR images are kept
+    if settings.storage.backend == StorageBackendKind::Local {
+        app = app.merge(storage_routes(storage_state));
+        info!("Serving locally stored files at /storage");
+    }
+    
+    // Admin dashboard is only served when an API key is configured
+    match admin_state {
+        Some(admin_state) => {
+            app = app.merge(admin_routes(admin_state));
+            info!("Admin dashboard enabled at /admin");
+        }
+        None => info!("ADMIN_API_KEY not set, admin dashboard disabled"),
+    }
+    
+    let app = app
         // Add middleware
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
                 .layer(TimeoutLayer::new(Duration::from_secs(settings.server.request_timeout_seconds)))
+                .layer(DefaultBodyLimit::max(settings.server.max_body_bytes))
                 .layer(cors)
+                .layer(middleware::from_fn_with_state(security_headers, set_security_headers))
+                .layer(middleware::from_fn_with_state(load_shedder, shed_load))
         );
     
     // Create server address
@@ -142,9 +636,209 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     info!("📱 QR API: http://{}/api/v1/qr", addr);
     info!("🔗 Scan endpoint: http://{}/scan/{{property_id}}", addr);
     
-    // Start the server
-    axum::serve(listener, app).await
+    // Internal gRPC API on its own port, stopped by the same signal as the HTTP server
+    let grpc_server = match settings.server.grpc_port {
+        Some(_) if !grpc_auth.is_configured() => {
+            warn!("GRPC_PORT set but neither ADMIN_API_KEY nor a JWT secret is, gRPC API disabled");
+            None
+        }
+        Some(grpc_port) => {
+            let grpc_addr = SocketAddr::new(addr.ip(), grpc_port);
+            info!("🔌 gRPC API: {}", grpc_addr);
+            Some(tokio::spawn(
+                tonic::transport::Server::builder()
+                    .add_service(PropertyQrServer::with_interceptor(grpc_service, grpc_auth))
+                    .serve_with_shutdown(grpc_addr, shutdown_signal()),
+            ))
+        }
+        None => {
+            info!("GRPC_PORT not set, gRPC API disabled");
+            None
+        }
+    };
+    
+    // Start the server; on SIGINT/SIGTERM stop accepting and let open requests finish
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(shutdown_signal())
+        .await
         .map_err(|e| format!("Server error: {}", e))?;
     
+    if let Some(grpc_server) = grpc_server {
+        match grpc_server.await {
+            Ok(Err(e)) => error!("gRPC server error: {}", e),
+            Err(e) => error!("gRPC server task failed: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+    
+    // Flush background scan writes before closing the database connection
+    shutdown_analytics.drain(SHUTDOWN_DRAIN_TIMEOUT).await;
+    client.shutdown().await;
+    
+    info!("DAO-Bitat QR Service stopped");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Resolve once the process receives Ctrl+C or SIGTERM (sent by Kubernetes on rollout)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received, draining connections...");
+}
+
+/// Health probes for every external system; each shows up in /health/detailed
+fn register_dependencies(
+    database: &mongodb::Database,
+    storage: StorageService,
+    geolocation_service: GeolocationService,
+    hook_service: HookService,
+    notification_service: NotificationService,
+    event_publisher: Option<EventPublisher>,
+    self_test: Option<SelfTestReport>,
+) -> DependencyRegistry {
+    let mut dependencies = DependencyRegistry::new();
+    
+    let database = database.clone();
+    dependencies.register("mongodb", true, move || {
+        let database = database.clone();
+        async move {
+            match database.run_command(mongodb::bson::doc! {"ping": 1}).await {
+                Ok(_) => ProbeResult::healthy("Ping succeeded"),
+                Err(e) => ProbeResult::unhealthy(format!("Ping failed: {}", e)),
+            }
+        }
+    });
+    
+    dependencies.register("storage", true, move || {
+        let storage = storage.clone();
+        async move {
+            match storage.file_exists("health/probe").await {
+                Ok(_) => ProbeResult::healthy("Storage reachable"),
+                Err(e) => ProbeResult::unhealthy(e.to_string()),
+            }
+        }
+    });
+    
+    dependencies.register("qr_generator", false, move || {
+        let result = match &self_test {
+            None => ProbeResult::healthy("Startup self-test disabled"),
+            Some(report) if report.passed => ProbeResult::healthy("Startup self-test passed"),
+            Some(report) => {
+                let failed: Vec<&str> = report.steps.iter()
+                    .filter(|step| !step.passed)
+                    .map(|step| step.name.as_str())
+                    .collect();
+                ProbeResult::unhealthy(format!("Startup self-test failed: {}", failed.join(", ")))
+            }
+        };
+        async move { result }
+    });
+    
+    // Lookups fail open, so a struggling provider only degrades scans
+    dependencies.register("geolocation", false, move || {
+        let metrics = geolocation_service.metrics();
+        let summary = format!("{}: {} lookups, {} failures", metrics.provider, metrics.lookups, metrics.failures);
+        let result = if metrics.lookups > 0 && metrics.failures * 2 > metrics.lookups {
+            ProbeResult::degraded(summary)
+        } else {
+            ProbeResult::healthy(summary)
+        };
+        async move { result }
+    });
+    
+    if let Some(event_publisher) = event_publisher {
+        dependencies.register("event_stream", false, move || {
+            let metrics = event_publisher.metrics();
+            let summary = format!(
+                "{}: {} published, {} dropped, {} failures",
+                metrics.sink, metrics.published, metrics.dropped, metrics.failures,
+            );
+            let result = if metrics.dropped + metrics.failures > metrics.published {
+                ProbeResult::degraded(summary)
+            } else {
+                ProbeResult::healthy(summary)
+            };
+            async move { result }
+        });
+    }
+    
+    dependencies.register("webhooks", false, move || {
+        let hook_service = hook_service.clone();
+        async move {
+            match hook_service.list_subscriptions().await {
+                Ok(subscriptions) => {
+                    let failing = subscriptions.iter().filter(|s| s.failure_count > 0).count();
+                    let summary = format!("{} subscribers, {} failing", subscriptions.len(), failing);
+                    if failing > 0 {
+                        ProbeResult::degraded(summary)
+                    } else {
+                        ProbeResult::healthy(summary)
+                    }
+                }
+                Err(e) => ProbeResult::unhealthy(e.to_string()),
+            }
+        }
+    });
+    
+    dependencies.register("notifications", false, move || {
+        let result = if notification_service.email_enabled() {
+            ProbeResult::healthy("Email provider configured")
+        } else if notification_service.is_enabled() {
+            ProbeResult::healthy("SMS only; no email provider configured")
+        } else {
+            ProbeResult::degraded("No email or SMS provider configured")
+        };
+        async move { result }
+    });
+    
+    dependencies
+}
+
+/// Health probe for the blockchain RPC nodes, when on-chain verification is configured.
+/// Verification badges go stale while they're down, but scans carry on.
+fn register_blockchain_probe(dependencies: &mut DependencyRegistry, blockchain_service: BlockchainService) {
+    dependencies.register("blockchain_rpc", false, move || {
+        let blockchain_service = blockchain_service.clone();
+        async move {
+            let results = blockchain_service.block_numbers().await;
+            let failed: Vec<String> = results.iter()
+                .filter_map(|(chain_id, result)| {
+                    let endpoint = chain_id.map_or_else(|| "default".to_string(), |chain_id| format!("chain {}", chain_id));
+                    result.as_ref().err().map(|e| format!("{}: {}", endpoint, e))
+                })
+                .collect();
+            let summary = format!("{} of {} RPC endpoints answering", results.len() - failed.len(), results.len());
+            match failed.len() {
+                0 => ProbeResult::healthy(summary),
+                n if n == results.len() => ProbeResult::unhealthy(format!("{}; {}", summary, failed.join("; "))),
+                _ => ProbeResult::degraded(format!("{}; {}", summary, failed.join("; "))),
+            }
+        }
+    });
+}
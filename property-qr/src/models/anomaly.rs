@@ -0,0 +1,53 @@
+// src/models/anomaly.rs
+
+use serde::{Deserialize, Serialize};
+
+// Scans of one property over the anomaly detector's windows, bots included
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertyScanActivity {
+    #[serde(rename = "_id")]
+    pub property_id: String,
+    #[serde(rename = "spikeWindowScans")]
+    pub spike_window_scans: i64,
+    #[serde(rename = "silenceWindowScans")]
+    pub silence_window_scans: i64,
+    #[serde(rename = "baselineScans")]
+    pub baseline_scans: i64, // The week before the silence window
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyKind {
+    Spike,   // Far more scans than usual, e.g. a bot hammering a code
+    Silence, // An active code that stopped being scanned, e.g. a poster taken down or damaged
+}
+
+// A property whose scans look wrong, for alerting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScanAnomaly {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub kind: AnomalyKind,
+    #[serde(rename = "recentScans")]
+    pub recent_scans: i64,
+    #[serde(rename = "expectedScans")]
+    pub expected_scans: f64, // At the property's usual rate, over the same window
+    #[serde(rename = "windowMinutes")]
+    pub window_minutes: i64,
+}
+
+impl ScanAnomaly {
+    /// One-line alert text
+    pub fn message(&self) -> String {
+        match self.kind {
+            AnomalyKind::Spike => format!(
+                ":rotating_light: Scan spike on property {}: {} scans in the last {} minutes, against about {:.1} usually. Possible bot traffic.",
+                self.property_id, self.recent_scans, self.window_minutes, self.expected_scans,
+            ),
+            AnomalyKind::Silence => format!(
+                ":warning: Property {} has had no scans for {} hours, against about {:.0} usually. Check the printed code is still up.",
+                self.property_id, self.window_minutes / 60, self.expected_scans,
+            ),
+        }
+    }
+}
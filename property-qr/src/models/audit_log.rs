@@ -0,0 +1,139 @@
+// src/models/audit_log.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::QrCodeMetadata;
+
+// One management action on a QR code, kept so disputes over who changed a code can be settled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub action: AuditAction,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub actor: AuditActor,
+    pub before: Option<QrCodeMetadata>, // None when the code didn't exist yet
+    pub after: Option<QrCodeMetadata>,  // None once deleted
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Generate,
+    Regenerate,
+    Delete,
+    Deactivate,
+    Upload, // A designed image replaced the drawn one
+}
+
+// Who made a change. Admins and organizations are identified by their API key; user IDs
+// are as claimed by the calling platform in the X-User-Id header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditActor {
+    pub kind: ActorKind,
+    pub id: Option<String>, // User ID, organization ID or background job name
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ActorKind {
+    Admin,        // Holder of the admin API key
+    Organization, // Agency, through its organization API key
+    User,         // Listing platform user, through the public API or gRPC
+    System,       // Background jobs, or gRPC clients that didn't name a user
+    Anonymous,    // Public API caller that didn't say who they were
+}
+
+impl AuditActor {
+    pub fn admin(user_id: Option<String>) -> Self {
+        Self { kind: ActorKind::Admin, id: user_id }
+    }
+
+    pub fn organization(org_id: &ObjectId) -> Self {
+        Self { kind: ActorKind::Organization, id: Some(org_id.to_hex()) }
+    }
+
+    pub fn user(user_id: String) -> Self {
+        Self { kind: ActorKind::User, id: Some(user_id) }
+    }
+
+    pub fn system(job: &str) -> Self {
+        Self { kind: ActorKind::System, id: Some(job.to_string()) }
+    }
+
+    pub fn anonymous() -> Self {
+        Self { kind: ActorKind::Anonymous, id: None }
+    }
+}
+
+impl AuditEntry {
+    pub fn new(
+        action: AuditAction,
+        property_id: String,
+        actor: AuditActor,
+        before: Option<QrCodeMetadata>,
+        after: Option<QrCodeMetadata>,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            action,
+            property_id,
+            actor,
+            before,
+            after,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn to_response(&self) -> AuditEntryResponse {
+        AuditEntryResponse {
+            id: self.id.to_hex(),
+            action: self.action,
+            property_id: self.property_id.clone(),
+            actor: self.actor.clone(),
+            before: self.before.clone(),
+            after: self.after.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+// Filters for reading the audit log; all optional
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub property_id: Option<String>,
+    pub action: Option<AuditAction>,
+    pub actor_kind: Option<ActorKind>,
+    pub actor_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub id: String,
+    pub action: AuditAction,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub actor: AuditActor,
+    pub before: Option<QrCodeMetadata>,
+    pub after: Option<QrCodeMetadata>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditLogPage {
+    pub items: Vec<AuditEntryResponse>, // Newest first
+    #[serde(rename = "totalCount")]
+    pub total_count: u64,
+    pub limit: i64,
+    pub skip: u64,
+}
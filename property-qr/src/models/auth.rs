@@ -0,0 +1,66 @@
+// src/models/auth.rs
+
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+// What a caller of the QR management API may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,    // Everything, including batch and delete operations
+    Agent,    // Generate, view and manage codes for their own properties
+    Readonly, // View any code, change none
+}
+
+// Claims we read from tokens issued by the main DAO-Bitat auth service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthClaims {
+    pub sub: Option<String>,
+    #[serde(rename = "userId", alias = "user_id")]
+    pub user_id: Option<String>, // DAO-Bitat's own claim; preferred over sub
+    pub role: Option<Role>,      // Platform users without one are agents
+    pub exp: i64,                // Unix seconds
+    pub iss: Option<String>,
+}
+
+impl AuthClaims {
+    /// The DAO-Bitat user ID, which for agents is the owner ID on their listings
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref().or(self.sub.as_deref()).filter(|user_id| !user_id.is_empty())
+    }
+}
+
+// The authenticated caller of a request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principal {
+    pub role: Role,
+    pub user_id: Option<String>, // None for the admin API key without an X-User-Id
+}
+
+impl Principal {
+    pub fn from_claims(claims: AuthClaims) -> Self {
+        Self {
+            role: claims.role.unwrap_or(Role::Agent),
+            user_id: claims.user_id().map(str::to_string),
+        }
+    }
+
+    /// The user as recorded on QR codes they generate, if their ID is a DAO-Bitat user ID
+    pub fn generated_by(&self) -> Option<ObjectId> {
+        self.user_id.as_deref().and_then(|user_id| ObjectId::parse_str(user_id).ok())
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    /// Admins and read-only staff can see every code; agents only their own
+    pub fn can_view_all(&self) -> bool {
+        matches!(self.role, Role::Admin | Role::Readonly)
+    }
+
+    /// Whether the caller may see an owner's listings: staff any, agents only their own
+    pub fn can_view_owner(&self, owner_id: &str) -> bool {
+        self.can_view_all() || self.user_id.as_deref() == Some(owner_id)
+    }
+}
@@ -0,0 +1,171 @@
+// src/models/auto_redirect.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::config::RedirectTarget;
+
+// Scope ID of the config that applies to every property without one of its own
+pub const GLOBAL_AUTO_REDIRECT_SCOPE: &str = "global";
+
+// Where the dual page sends visitors once its countdown runs out, for one property or globally.
+// Weights split visitors between destinations for experiments; each visitor keeps their arm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRedirectConfig {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "scopeId")]
+    pub scope_id: String, // Property ID, or "global"
+    pub destination: AutoRedirectDestination, // Used when no weights are set
+    #[serde(default)]
+    pub weights: Vec<DestinationWeight>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoRedirectDestination {
+    Property,
+    Blockchain, // Falls back to the property page for listings without an on-chain ID
+    None,       // Visitors pick a destination themselves
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DestinationWeight {
+    pub destination: AutoRedirectDestination,
+    pub weight: u32,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertAutoRedirectConfigRequest {
+    pub destination: AutoRedirectDestination,
+    #[serde(default)]
+    pub weights: Vec<DestinationWeight>, // e.g. 80 property / 20 blockchain; empty turns the experiment off
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AutoRedirectConfigResponse {
+    #[serde(rename = "scopeId")]
+    pub scope_id: String,
+    pub destination: AutoRedirectDestination,
+    pub weights: Vec<DestinationWeight>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AutoRedirectDestination {
+    /// Stored value, matching the serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AutoRedirectDestination::Property => "property",
+            AutoRedirectDestination::Blockchain => "blockchain",
+            AutoRedirectDestination::None => "none",
+        }
+    }
+}
+
+impl From<RedirectTarget> for AutoRedirectDestination {
+    fn from(target: RedirectTarget) -> Self {
+        match target {
+            RedirectTarget::Property => AutoRedirectDestination::Property,
+            RedirectTarget::Blockchain => AutoRedirectDestination::Blockchain,
+        }
+    }
+}
+
+impl AutoRedirectConfig {
+    /// Create a config for a property, or the global one
+    pub fn new(scope_id: String, request: UpsertAutoRedirectConfigRequest) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            scope_id,
+            destination: request.destination,
+            weights: request.weights,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Replace the destination and weights, keeping the creation date
+    pub fn apply(mut self, request: UpsertAutoRedirectConfigRequest) -> Self {
+        self.destination = request.destination;
+        self.weights = request.weights;
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Destination for one visitor. With weights set, the visitor ID picks the arm, so
+    /// repeat scans from the same visitor always land on the same destination.
+    pub fn destination_for(&self, property_id: &str, visitor_id: &str) -> AutoRedirectDestination {
+        let total: u64 = self.weights.iter().map(|arm| u64::from(arm.weight)).sum();
+        if total == 0 {
+            return self.destination;
+        }
+
+        let digest = Sha256::digest(format!("{}:{}", property_id, visitor_id).as_bytes());
+        let mut bucket = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes")) % total;
+        for arm in &self.weights {
+            if bucket < u64::from(arm.weight) {
+                return arm.destination;
+            }
+            bucket -= u64::from(arm.weight);
+        }
+        self.destination
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> AutoRedirectConfigResponse {
+        AutoRedirectConfigResponse {
+            scope_id: self.scope_id.clone(),
+            destination: self.destination,
+            weights: self.weights.clone(),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(weights: &[(AutoRedirectDestination, u32)]) -> AutoRedirectConfig {
+        AutoRedirectConfig::new(
+            "507f1f77bcf86cd799439011".to_string(),
+            UpsertAutoRedirectConfigRequest {
+                destination: AutoRedirectDestination::Property,
+                weights: weights.iter().map(|&(destination, weight)| DestinationWeight { destination, weight }).collect(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_destination_without_weights() {
+        assert_eq!(config(&[]).destination_for("p1", "visitor"), AutoRedirectDestination::Property);
+        assert_eq!(
+            config(&[(AutoRedirectDestination::Blockchain, 0)]).destination_for("p1", "visitor"),
+            AutoRedirectDestination::Property
+        );
+    }
+
+    #[test]
+    fn test_weighted_destination_is_sticky_and_split() {
+        let split = config(&[(AutoRedirectDestination::Property, 50), (AutoRedirectDestination::None, 50)]);
+        assert_eq!(split.destination_for("p1", "visitor-1"), split.destination_for("p1", "visitor-1"));
+
+        let to_property = (0..1000)
+            .filter(|i| split.destination_for("p1", &format!("visitor-{}", i)) == AutoRedirectDestination::Property)
+            .count();
+        assert!((400..600).contains(&to_property), "{} of 1000 went to the property page", to_property);
+
+        let only_blockchain = config(&[(AutoRedirectDestination::Blockchain, 1)]);
+        assert_eq!(only_blockchain.destination_for("p1", "visitor-1"), AutoRedirectDestination::Blockchain);
+    }
+}
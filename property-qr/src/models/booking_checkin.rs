@@ -0,0 +1,139 @@
+// src/models/booking_checkin.rs
+
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::QrStatus;
+
+// Guests arriving early can check in this long before the booking starts
+pub const EARLY_CHECK_IN_HOURS: i64 = 6;
+
+// One-time code a short-term renter scans on arrival. Only the token's hash is stored;
+// the token itself is in the printed or sent code and nowhere else.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookingCheckinCode {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "bookingId")]
+    pub booking_id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "renterId")]
+    pub renter_id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    #[serde(rename = "checkIn")]
+    pub check_in: DateTime<Utc>,
+    #[serde(rename = "checkOut")]
+    pub check_out: DateTime<Utc>,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "imageKey")]
+    pub image_key: String,
+    #[serde(rename = "checkedInAt")]
+    pub checked_in_at: Option<DateTime<Utc>>, // Set by the one scan the code allows
+    #[serde(rename = "generatedBy")]
+    pub generated_by: Option<ObjectId>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BookingQrResponse {
+    #[serde(rename = "bookingId")]
+    pub booking_id: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "scanUrl")]
+    pub scan_url: Option<String>, // Only when the code is generated, as the token isn't kept
+    #[serde(rename = "validFrom")]
+    pub valid_from: DateTime<Utc>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "checkedInAt")]
+    pub checked_in_at: Option<DateTime<Utc>>,
+    pub status: QrStatus,
+}
+
+/// Where a moment falls against a code's check-in window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckinWindow {
+    Open,
+    TooEarly(DateTime<Utc>), // Opens at
+    Expired,
+}
+
+impl BookingCheckinCode {
+    /// Page a code with this token resolves to
+    pub fn scan_url_for(base_url: &str, token: &str) -> String {
+        format!("{}/checkin/{}", base_url.trim_end_matches('/'), token)
+    }
+
+    /// Key a code's image is stored at; a fresh one per code, as each holds a different token
+    pub fn image_key_for(property_id: &str, booking_id: &ObjectId, code_id: &ObjectId) -> String {
+        format!("booking-qr/{}/{}-{}.png", property_id, booking_id.to_hex(), code_id.to_hex())
+    }
+
+    /// When the code starts working
+    pub fn valid_from(&self) -> DateTime<Utc> {
+        self.check_in - Duration::hours(EARLY_CHECK_IN_HOURS)
+    }
+
+    /// Whether `now` falls in the booking's check-in window; the code expires at check-out
+    pub fn window(&self, now: DateTime<Utc>) -> CheckinWindow {
+        if now < self.valid_from() {
+            CheckinWindow::TooEarly(self.valid_from())
+        } else if now > self.check_out {
+            CheckinWindow::Expired
+        } else {
+            CheckinWindow::Open
+        }
+    }
+
+    /// Convert to API response; `scan_url` only for a newly generated code
+    pub fn to_response(&self, scan_url: Option<String>, status: QrStatus) -> BookingQrResponse {
+        BookingQrResponse {
+            booking_id: self.booking_id.to_hex(),
+            property_id: self.property_id.clone(),
+            qr_code_url: self.qr_code_url.clone(),
+            scan_url,
+            valid_from: self.valid_from(),
+            expires_at: self.check_out,
+            checked_in_at: self.checked_in_at,
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkin_window() {
+        let code = BookingCheckinCode {
+            id: ObjectId::new(),
+            booking_id: ObjectId::new(),
+            property_id: "507f1f77bcf86cd799439011".to_string(),
+            renter_id: ObjectId::new(),
+            token_hash: String::new(),
+            check_in: "2026-07-10T14:00:00Z".parse().unwrap(),
+            check_out: "2026-07-13T10:00:00Z".parse().unwrap(),
+            qr_code_url: String::new(),
+            image_key: String::new(),
+            checked_in_at: None,
+            generated_by: None,
+            created_at: Utc::now(),
+        };
+
+        let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(code.window(at("2026-07-10T07:59:00Z")), CheckinWindow::TooEarly(at("2026-07-10T08:00:00Z")));
+        assert_eq!(code.window(at("2026-07-10T08:00:00Z")), CheckinWindow::Open);
+        assert_eq!(code.window(at("2026-07-12T22:00:00Z")), CheckinWindow::Open);
+        assert_eq!(code.window(at("2026-07-13T10:00:01Z")), CheckinWindow::Expired);
+    }
+}
@@ -0,0 +1,149 @@
+// src/models/digest.rs
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// An owner's weekly digest settings. Owners without one get the digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestPreference {
+    #[serde(rename = "_id")]
+    pub owner_id: String,
+    #[serde(rename = "optedOut")]
+    pub opted_out: bool,
+    #[serde(rename = "lastSentWeek")]
+    pub last_sent_week: Option<String>, // ISO week, e.g. "2026-W42", so restarts don't resend
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDigestPreferenceRequest {
+    #[serde(rename = "optedOut")]
+    pub opted_out: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DigestPreferenceResponse {
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    #[serde(rename = "optedOut")]
+    pub opted_out: bool,
+    #[serde(rename = "lastSentWeek")]
+    pub last_sent_week: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DigestPreference> for DigestPreferenceResponse {
+    fn from(preference: DigestPreference) -> Self {
+        Self {
+            owner_id: preference.owner_id,
+            opted_out: preference.opted_out,
+            last_sent_week: preference.last_sent_week,
+            updated_at: preference.updated_at,
+        }
+    }
+}
+
+// Human scans of one property in the digest week and the week before it
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertyWeekScans {
+    #[serde(rename = "_id")]
+    pub property_id: String,
+    pub scans: i64,
+    #[serde(rename = "previousScans")]
+    pub previous_scans: i64,
+}
+
+// The most scanned of an owner's properties in the digest week
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestTopProperty {
+    pub property_id: String,
+    pub property_name: String,
+    pub scans: i64,
+}
+
+// One owner's weekly scan summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerDigest {
+    pub owner_id: String,
+    pub week_start: NaiveDate, // First day covered
+    pub total_scans: i64,
+    pub previous_scans: i64,   // The seven days before
+    pub top_property: Option<DigestTopProperty>,
+}
+
+impl OwnerDigest {
+    /// Change on the previous week in percent; None when there were no scans to compare with
+    pub fn trend_percent(&self) -> Option<f64> {
+        (self.previous_scans > 0)
+            .then(|| (self.total_scans - self.previous_scans) as f64 / self.previous_scans as f64 * 100.0)
+    }
+
+    /// The email's subject and plain-text body
+    pub fn render(&self) -> (String, String) {
+        let week_end = self.week_start + chrono::Duration::days(6);
+        let subject = format!("Your QR codes were scanned {} times last week", self.total_scans);
+
+        let trend = match self.trend_percent() {
+            Some(percent) if percent >= 0.5 => format!("up {:.0}% on the week before ({} scans)", percent, self.previous_scans),
+            Some(percent) if percent <= -0.5 => format!("down {:.0}% on the week before ({} scans)", -percent, self.previous_scans),
+            Some(_) => format!("about the same as the week before ({} scans)", self.previous_scans),
+            None => "your first scans in two weeks".to_string(),
+        };
+
+        let mut body = format!(
+            "Your DAO-Bitat listings' QR codes, {} to {}:\n\nTotal scans: {}, {}.\n",
+            self.week_start.format("%-d %b"),
+            week_end.format("%-d %b %Y"),
+            self.total_scans,
+            trend,
+        );
+        if let Some(top_property) = &self.top_property {
+            body.push_str(&format!("Most scanned: {} ({} scans).\n", top_property.property_name, top_property.scans));
+        }
+        body.push_str("\nYou can turn these weekly emails off from your DAO-Bitat account.\n");
+
+        (subject, body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(total_scans: i64, previous_scans: i64) -> OwnerDigest {
+        OwnerDigest {
+            owner_id: "507f1f77bcf86cd799439011".to_string(),
+            week_start: NaiveDate::from_ymd_opt(2026, 10, 5).unwrap(),
+            total_scans,
+            previous_scans,
+            top_property: Some(DigestTopProperty {
+                property_id: "507f1f77bcf86cd799439012".to_string(),
+                property_name: "Garden Villa".to_string(),
+                scans: 30,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_trend_percent() {
+        assert_eq!(digest(45, 30).trend_percent(), Some(50.0));
+        assert_eq!(digest(15, 30).trend_percent(), Some(-50.0));
+        assert_eq!(digest(12, 0).trend_percent(), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let (subject, body) = digest(45, 30).render();
+
+        assert_eq!(subject, "Your QR codes were scanned 45 times last week");
+        assert!(body.contains("5 Oct to 11 Oct 2026"));
+        assert!(body.contains("Total scans: 45, up 50% on the week before (30 scans)."));
+        assert!(body.contains("Most scanned: Garden Villa (30 scans)."));
+
+        let (_, body) = digest(15, 30).render();
+        assert!(body.contains("down 50% on the week before"));
+    }
+}
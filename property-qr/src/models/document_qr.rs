@@ -0,0 +1,222 @@
+// src/models/document_qr.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{Document, DocumentType, Property, QrStatus};
+
+// QR code printed on or alongside a property document (title deed, sale agreement) that
+// resolves to a page confirming the document's hash and verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentQrCode {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    #[serde(rename = "qrVersion")]
+    pub qr_version: i32, // Bumped on regeneration, along with the image key
+    #[serde(rename = "scanUrl")]
+    pub scan_url: String,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "imageKey")]
+    pub image_key: String,
+    pub scans: i64,
+    pub active: bool,
+    #[serde(rename = "generatedBy")]
+    pub generated_by: Option<ObjectId>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateDocumentQrRequest {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    #[serde(rename = "forceRegenerate")]
+    pub force_regenerate: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DocumentQrResponse {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "scanUrl")]
+    pub scan_url: String,
+    #[serde(rename = "generatedAt")]
+    pub generated_at: DateTime<Utc>,
+    pub scans: i64,
+    pub status: QrStatus,
+}
+
+impl DocumentQrCode {
+    /// Create the first version of a document's code
+    pub fn new(property_id: String, document_id: String, base_url: &str, qr_code_url: String, generated_by: Option<ObjectId>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            scan_url: Self::scan_url_for(base_url, &property_id, &document_id),
+            image_key: Self::image_key_for(&property_id, &document_id, 1),
+            property_id,
+            document_id,
+            qr_version: 1,
+            qr_code_url,
+            scans: 0,
+            active: true,
+            generated_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Page a document's code resolves to
+    pub fn scan_url_for(base_url: &str, property_id: &str, document_id: &str) -> String {
+        format!(
+            "{}/scan/{}/documents/{}",
+            base_url.trim_end_matches('/'),
+            urlencoding::encode(property_id),
+            urlencoding::encode(document_id)
+        )
+    }
+
+    /// Key a version of a document's image is stored at; kept apart from listing codes'
+    /// images, which storage reconciliation sweeps for orphans
+    pub fn image_key_for(property_id: &str, document_id: &str, qr_version: i32) -> String {
+        format!(
+            "document-qr/{}/{}-v{}.png",
+            property_id,
+            urlencoding::encode(document_id),
+            qr_version
+        )
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self, status: QrStatus) -> DocumentQrResponse {
+        DocumentQrResponse {
+            property_id: self.property_id.clone(),
+            document_id: self.document_id.clone(),
+            qr_code_url: self.qr_code_url.clone(),
+            scan_url: self.scan_url.clone(),
+            generated_at: self.updated_at,
+            scans: self.scans,
+            status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentVerificationStatus {
+    Verified,
+    Unverified,
+    Expired, // Was verified, but is past its expiry date
+}
+
+// What a document code's page shows; never the document itself
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentVerification {
+    pub property_id: String,
+    pub property_name: String,
+    pub document_id: String,
+    pub document_name: String,
+    pub document_type: DocumentType,
+    pub document_hash: Option<String>,
+    pub status: DocumentVerificationStatus,
+    pub verifier: Option<String>, // Short reference to the DAO-Bitat verifier, not who they are
+    pub verified_at: Option<DateTime<Utc>>,
+    pub expiry_date: Option<DateTime<Utc>>,
+}
+
+impl DocumentVerification {
+    /// Verification details of one of a property's documents as of `now`
+    pub fn new(property: &Property, document: &Document, now: DateTime<Utc>) -> Self {
+        let status = match (document.is_verified, document.expiry_date) {
+            (true, Some(expiry_date)) if expiry_date < now => DocumentVerificationStatus::Expired,
+            (true, _) => DocumentVerificationStatus::Verified,
+            (false, _) => DocumentVerificationStatus::Unverified,
+        };
+
+        Self {
+            property_id: property.id.to_hex(),
+            property_name: property.property_name.clone(),
+            document_id: document.document_id.clone(),
+            document_name: document.document_name.clone(),
+            document_type: document.document_type.clone(),
+            document_hash: document.document_hash.clone(),
+            status,
+            verifier: document.verified_by
+                .filter(|_| document.is_verified)
+                .map(|verifier| verifier.to_hex()[18..].to_uppercase()),
+            verified_at: document.verified_at.filter(|_| document.is_verified),
+            expiry_date: document.expiry_date,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::PropertyFixture;
+
+    fn title_deed(is_verified: bool, expiry_date: Option<DateTime<Utc>>) -> Document {
+        Document {
+            document_id: "deed-1".to_string(),
+            document_type: DocumentType::TitleDeed,
+            document_name: "Title deed LR 209/1234".to_string(),
+            document_url: "https://cdn.daobitat.xyz/docs/deed-1.pdf".to_string(),
+            file_type: "pdf".to_string(),
+            upload_date: "2026-01-10T09:00:00Z".parse().unwrap(),
+            expiry_date,
+            is_verified,
+            verified_by: Some(ObjectId::parse_str("65f0c0ffee0000000000abcd").unwrap()),
+            verified_at: Some("2026-01-12T10:00:00Z".parse().unwrap()),
+            verification_notes: None,
+            document_hash: Some("0x9f86d081884c7d65".to_string()),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_document_verification_status() {
+        let property = PropertyFixture::new().build();
+        let now: DateTime<Utc> = "2026-06-01T00:00:00Z".parse().unwrap();
+
+        let verified = DocumentVerification::new(&property, &title_deed(true, None), now);
+        assert_eq!(verified.status, DocumentVerificationStatus::Verified);
+        assert_eq!(verified.verifier.as_deref(), Some("00ABCD"));
+        assert_eq!(verified.document_hash.as_deref(), Some("0x9f86d081884c7d65"));
+
+        let expired = DocumentVerification::new(&property, &title_deed(true, Some("2026-05-01T00:00:00Z".parse().unwrap())), now);
+        assert_eq!(expired.status, DocumentVerificationStatus::Expired);
+
+        // A verifier recorded on a document that isn't verified vouches for nothing
+        let unverified = DocumentVerification::new(&property, &title_deed(false, None), now);
+        assert_eq!(unverified.status, DocumentVerificationStatus::Unverified);
+        assert_eq!((unverified.verifier, unverified.verified_at), (None, None));
+    }
+
+    #[test]
+    fn test_document_qr_urls() {
+        assert_eq!(
+            DocumentQrCode::scan_url_for("https://qr-service.daobitat.xyz/", "507f1f77bcf86cd799439011", "deed 1"),
+            "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011/documents/deed%201"
+        );
+        assert_eq!(
+            DocumentQrCode::image_key_for("507f1f77bcf86cd799439011", "deeds/1", 2),
+            "document-qr/507f1f77bcf86cd799439011/deeds%2F1-v2.png"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+// src/models/experiment.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Longest text a variant can put on the dual page
+pub const MAX_VARIANT_TEXT_CHARS: usize = 80;
+
+// Keys the two variants of an experiment are stored and reported under
+pub const VARIANT_KEYS: [&str; 2] = ["a", "b"];
+
+// A/B test of two versions of a property's dual landing page. Scanners are split between
+// them by session, and each scan records the variant it was shown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandingExperiment {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub name: String,
+    pub variants: Vec<LandingVariant>,
+    pub active: bool, // At most one per property
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<ObjectId>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "endedAt")]
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+// Text one version of the page shows in place of the defaults; a variant that sets none is
+// the page as it is, i.e. the control
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LandingVariant {
+    pub key: String,
+    #[serde(rename = "propertyHeading")]
+    pub property_heading: Option<String>,
+    #[serde(rename = "propertyBody")]
+    pub property_body: Option<String>,
+    #[serde(rename = "propertyButton")]
+    pub property_button: Option<String>, // The main call to action
+}
+
+// Variant a scan was shown, as stored on its scan event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScanExperiment {
+    #[serde(rename = "experimentId")]
+    pub experiment_id: String,
+    pub variant: String,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateExperimentRequest {
+    pub name: String,
+    pub variants: Vec<LandingVariantRequest>, // Exactly two, stored as "a" and "b"
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LandingVariantRequest {
+    #[serde(rename = "propertyHeading")]
+    pub property_heading: Option<String>,
+    #[serde(rename = "propertyBody")]
+    pub property_body: Option<String>,
+    #[serde(rename = "propertyButton")]
+    pub property_button: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExperimentResponse {
+    #[serde(rename = "experimentId")]
+    pub experiment_id: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub name: String,
+    pub variants: Vec<LandingVariant>,
+    pub active: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "endedAt")]
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+// Scans shown one variant, and how many of them went on to click through
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VariantStats {
+    pub variant: String,
+    pub scans: i64,
+    pub conversions: i64, // Scans followed by a click on one of the page's buttons
+    #[serde(rename = "conversionRate")]
+    pub conversion_rate: f64, // Percentage of scans
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExperimentReport {
+    #[serde(flatten)]
+    pub experiment: ExperimentResponse,
+    #[serde(rename = "variantStats")]
+    pub variant_stats: Vec<VariantStats>,
+}
+
+impl LandingVariantRequest {
+    /// Trimmed, with blank text left to the page's default
+    fn into_variant(self, key: &str) -> Result<LandingVariant, String> {
+        let clean = |text: Option<String>, field: &str| -> Result<Option<String>, String> {
+            let text = text.map(|text| text.trim().to_string()).filter(|text| !text.is_empty());
+            match text {
+                Some(text) if text.chars().count() > MAX_VARIANT_TEXT_CHARS => Err(format!(
+                    "{} of variant {} must be at most {} characters",
+                    field, key, MAX_VARIANT_TEXT_CHARS
+                )),
+                text => Ok(text),
+            }
+        };
+
+        Ok(LandingVariant {
+            key: key.to_string(),
+            property_heading: clean(self.property_heading, "propertyHeading")?,
+            property_body: clean(self.property_body, "propertyBody")?,
+            property_button: clean(self.property_button, "propertyButton")?,
+        })
+    }
+}
+
+impl LandingExperiment {
+    /// Create a running experiment from a request, checking it describes two different pages
+    pub fn new(property_id: String, request: CreateExperimentRequest, created_by: Option<ObjectId>) -> Result<Self, String> {
+        let name = request.name.trim().to_string();
+        if name.is_empty() {
+            return Err("name is required".to_string());
+        }
+        if request.variants.len() != VARIANT_KEYS.len() {
+            return Err(format!("an experiment needs exactly {} variants", VARIANT_KEYS.len()));
+        }
+
+        let variants = request.variants
+            .into_iter()
+            .zip(VARIANT_KEYS)
+            .map(|(variant, key)| variant.into_variant(key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let text = |variant: &LandingVariant| {
+            (variant.property_heading.clone(), variant.property_body.clone(), variant.property_button.clone())
+        };
+        if text(&variants[0]) == text(&variants[1]) {
+            return Err("the variants must differ".to_string());
+        }
+
+        Ok(Self {
+            id: ObjectId::new(),
+            property_id,
+            name,
+            variants,
+            active: true,
+            created_by,
+            created_at: Utc::now(),
+            ended_at: None,
+        })
+    }
+
+    /// Variant a session is shown; the same one on every scan, as it's derived from the
+    /// session ID, and evenly spread as that is random
+    pub fn assign(&self, session_id: &str) -> &LandingVariant {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.id.bytes());
+        hasher.update(session_id.as_bytes());
+        let index = hasher.finalize()[0] as usize % self.variants.len();
+        &self.variants[index]
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> ExperimentResponse {
+        ExperimentResponse {
+            experiment_id: self.id.to_hex(),
+            property_id: self.property_id.clone(),
+            name: self.name.clone(),
+            variants: self.variants.clone(),
+            active: self.active,
+            created_at: self.created_at,
+            ended_at: self.ended_at,
+        }
+    }
+}
+
+impl VariantStats {
+    /// Totals for a variant, with the conversion rate worked out from them
+    pub fn new(variant: String, scans: i64, conversions: i64) -> Self {
+        let conversion_rate = if scans == 0 {
+            0.0
+        } else {
+            conversions as f64 / scans as f64 * 100.0
+        };
+        Self { variant, scans, conversions, conversion_rate }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(buttons: [Option<&str>; 2]) -> CreateExperimentRequest {
+        CreateExperimentRequest {
+            name: " Button copy ".to_string(),
+            variants: buttons
+                .into_iter()
+                .map(|button| LandingVariantRequest { property_button: button.map(str::to_string), ..Default::default() })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_new_experiment() {
+        let experiment = LandingExperiment::new("p1".to_string(), request([None, Some(" Book a viewing ")]), None).unwrap();
+        assert_eq!(experiment.name, "Button copy");
+        assert_eq!(experiment.variants[0], LandingVariant {
+            key: "a".to_string(),
+            property_heading: None,
+            property_body: None,
+            property_button: None,
+        });
+        assert_eq!(experiment.variants[1].key, "b");
+        assert_eq!(experiment.variants[1].property_button.as_deref(), Some("Book a viewing"));
+
+        // Blank text is the default, so these are the same page
+        assert!(LandingExperiment::new("p1".to_string(), request([None, Some("  ")]), None).is_err());
+        assert!(LandingExperiment::new("p1".to_string(), request([None, Some(&"x".repeat(81))]), None).is_err());
+        let mut three = request([None, Some("Book a viewing")]);
+        three.variants.push(LandingVariantRequest::default());
+        assert!(LandingExperiment::new("p1".to_string(), three, None).is_err());
+    }
+
+    #[test]
+    fn test_assign_is_sticky_and_split() {
+        let experiment = LandingExperiment::new("p1".to_string(), request([None, Some("Book a viewing")]), None).unwrap();
+        assert_eq!(experiment.assign("session-1"), experiment.assign("session-1"));
+
+        let b_sessions = (0..1000)
+            .filter(|i| experiment.assign(&format!("session-{}", i)).key == "b")
+            .count();
+        assert!((400..=600).contains(&b_sessions), "{} of 1000 sessions got b", b_sessions);
+    }
+
+    #[test]
+    fn test_variant_stats() {
+        assert_eq!(VariantStats::new("a".to_string(), 200, 30).conversion_rate, 15.0);
+        assert_eq!(VariantStats::new("b".to_string(), 0, 0).conversion_rate, 0.0);
+    }
+}
@@ -0,0 +1,127 @@
+// src/models/geo_block.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::GeoLocation;
+
+// Countries a tenant (property owner) or single property refuses scans from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoBlockPolicy {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub scope: GeoBlockScope,
+    #[serde(rename = "scopeId")]
+    pub scope_id: String, // Owner ID or property ID, depending on scope
+    #[serde(rename = "blockedCountries")]
+    pub blocked_countries: Vec<String>, // ISO 3166-1 alpha-2, upper case
+    #[serde(rename = "blockUnknown")]
+    pub block_unknown: bool, // Also block scans whose country can't be determined
+    pub enabled: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoBlockScope {
+    Owner,    // Every property of a tenant
+    Property, // A single property
+}
+
+impl GeoBlockScope {
+    /// Stored value, matching the serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeoBlockScope::Owner => "owner",
+            GeoBlockScope::Property => "property",
+        }
+    }
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertGeoBlockPolicyRequest {
+    #[serde(rename = "blockedCountries")]
+    pub blocked_countries: Vec<String>,
+    #[serde(rename = "blockUnknown")]
+    pub block_unknown: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GeoBlockPolicyResponse {
+    pub scope: GeoBlockScope,
+    #[serde(rename = "scopeId")]
+    pub scope_id: String,
+    #[serde(rename = "blockedCountries")]
+    pub blocked_countries: Vec<String>,
+    #[serde(rename = "blockUnknown")]
+    pub block_unknown: bool,
+    pub enabled: bool,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GeoBlockPolicy {
+    /// Create an empty policy for an owner or property
+    pub fn new(scope: GeoBlockScope, scope_id: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            scope,
+            scope_id,
+            blocked_countries: Vec::new(),
+            block_unknown: false,
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Apply an upsert request on top of the current values
+    pub fn apply(mut self, request: UpsertGeoBlockPolicyRequest) -> Self {
+        self.blocked_countries = request.blocked_countries
+            .iter()
+            .map(|country| country.trim().to_ascii_uppercase())
+            .collect();
+        self.blocked_countries.sort();
+        self.blocked_countries.dedup();
+        if let Some(block_unknown) = request.block_unknown {
+            self.block_unknown = block_unknown;
+        }
+        if let Some(enabled) = request.enabled {
+            self.enabled = enabled;
+        }
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Whether a scan from this location is refused
+    pub fn blocks(&self, geolocation: Option<&GeoLocation>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        match geolocation.and_then(|geolocation| geolocation.country.as_deref()) {
+            Some(country) => self.blocked_countries.iter().any(|blocked| blocked.eq_ignore_ascii_case(country)),
+            None => self.block_unknown,
+        }
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> GeoBlockPolicyResponse {
+        GeoBlockPolicyResponse {
+            scope: self.scope,
+            scope_id: self.scope_id.clone(),
+            blocked_countries: self.blocked_countries.clone(),
+            block_unknown: self.block_unknown,
+            enabled: self.enabled,
+            updated_at: self.updated_at,
+        }
+    }
+}
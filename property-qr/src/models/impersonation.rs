@@ -0,0 +1,160 @@
+// src/models/impersonation.rs
+
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Support-mode token letting an admin act as one owner; only a hash of the secret is stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationToken {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    #[serde(rename = "adminId")]
+    pub admin_id: String, // Who asked for it; the admin API key is shared, so this is self-declared
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub reason: String, // Support ticket or note, kept for the audit trail
+    #[serde(rename = "allowWrites")]
+    pub allow_writes: bool, // Read-only unless explicitly granted
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+// One request made with an impersonation token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationAuditEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenId")]
+    pub token_id: ObjectId,
+    #[serde(rename = "adminId")]
+    pub admin_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    #[serde(rename = "requestedAt")]
+    pub requested_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateImpersonationTokenRequest {
+    #[serde(rename = "adminId")]
+    pub admin_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub reason: String,
+    #[serde(rename = "ttlMinutes")]
+    pub ttl_minutes: Option<i64>,
+    #[serde(rename = "allowWrites")]
+    pub allow_writes: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImpersonationTokenResponse {
+    pub id: String,
+    pub token: Option<String>, // Only returned when the token is issued
+    #[serde(rename = "adminId")]
+    pub admin_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub reason: String,
+    #[serde(rename = "allowWrites")]
+    pub allow_writes: bool,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImpersonationAuditEntryResponse {
+    pub id: String,
+    #[serde(rename = "tokenId")]
+    pub token_id: String,
+    #[serde(rename = "adminId")]
+    pub admin_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    #[serde(rename = "requestedAt")]
+    pub requested_at: DateTime<Utc>,
+}
+
+impl ImpersonationToken {
+    /// Create a token for an admin to act as an owner until `ttl` has passed
+    pub fn new(request: CreateImpersonationTokenRequest, token_hash: String, ttl: Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            token_hash,
+            admin_id: request.admin_id.trim().to_string(),
+            owner_id: request.owner_id,
+            reason: request.reason.trim().to_string(),
+            allow_writes: request.allow_writes.unwrap_or(false),
+            created_at: now,
+            expires_at: now + ttl,
+            revoked_at: None,
+        }
+    }
+
+    /// Whether the token can still be used
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && now < self.expires_at
+    }
+
+    /// Convert to API response; the secret is only included right after issuing
+    pub fn to_response(&self, token: Option<String>) -> ImpersonationTokenResponse {
+        ImpersonationTokenResponse {
+            id: self.id.to_hex(),
+            token,
+            admin_id: self.admin_id.clone(),
+            owner_id: self.owner_id.clone(),
+            reason: self.reason.clone(),
+            allow_writes: self.allow_writes,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+        }
+    }
+}
+
+impl ImpersonationAuditEntry {
+    /// Record a request made with a token
+    pub fn new(token: &ImpersonationToken, method: String, path: String, status: u16) -> Self {
+        Self {
+            id: ObjectId::new(),
+            token_id: token.id,
+            admin_id: token.admin_id.clone(),
+            owner_id: token.owner_id.clone(),
+            method,
+            path,
+            status,
+            requested_at: Utc::now(),
+        }
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> ImpersonationAuditEntryResponse {
+        ImpersonationAuditEntryResponse {
+            id: self.id.to_hex(),
+            token_id: self.token_id.to_hex(),
+            admin_id: self.admin_id.clone(),
+            owner_id: self.owner_id.clone(),
+            method: self.method.clone(),
+            path: self.path.clone(),
+            status: self.status,
+            requested_at: self.requested_at,
+        }
+    }
+}
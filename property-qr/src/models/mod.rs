@@ -1,10 +1,48 @@
  // src/models/mod.rs
 
+pub mod anomaly;
+pub mod audit_log;
+pub mod auth;
+pub mod auto_redirect;
+pub mod digest;
+pub mod booking_checkin;
+pub mod document_qr;
+pub mod experiment;
+pub mod share_offer;
+pub mod geo_block;
+pub mod impersonation;
+pub mod organization;
 pub mod property;
 pub mod qr_code;
+pub mod qr_payload;
+pub mod qr_style;
 pub mod scan_analytics;
+pub mod scan_cap;
+pub mod short_link;
+pub mod tracking;
+pub mod waitlist;
+pub mod webhook;
 
 // Re-export commonly used types for convenience
+pub use anomaly::*;
+pub use audit_log::*;
+pub use auth::*;
+pub use auto_redirect::*;
+pub use digest::*;
+pub use booking_checkin::*;
+pub use document_qr::*;
+pub use experiment::*;
+pub use share_offer::*;
+pub use geo_block::*;
+pub use impersonation::*;
+pub use organization::*;
 pub use property::*;
 pub use qr_code::*;
+pub use qr_payload::*;
+pub use qr_style::*;
 pub use scan_analytics::*;
+pub use scan_cap::*;
+pub use short_link::*;
+pub use tracking::*;
+pub use waitlist::*;
+pub use webhook::*;
@@ -0,0 +1,148 @@
+// src/models/organization.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{PrivacyNotice, QrGenerationSettings};
+
+// Agency grouping several owner accounts; whoever holds its API key manages every
+// member's properties. Only a hash of the key is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Organization {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    #[serde(rename = "ownerIds")]
+    pub owner_ids: Vec<String>,
+    #[serde(rename = "apiKeyHash")]
+    pub api_key_hash: String,
+    #[serde(default)]
+    pub branding: Option<BrandingProfile>, // Look of the agency's branding kit; QR defaults until set
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Agency look applied to the frame around every QR code in its branding kit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BrandingProfile {
+    #[serde(rename = "logoUrl")]
+    pub logo_url: Option<String>,
+    #[serde(rename = "primaryColor")]
+    pub primary_color: String, // "#RRGGBB", frame and heading
+    #[serde(rename = "accentColor")]
+    pub accent_color: String, // "#RRGGBB", call to action
+    pub tagline: Option<String>, // e.g. "Scan to view this home"
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    #[serde(rename = "ownerIds", default)]
+    pub owner_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationResponse {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "ownerIds")]
+    pub owner_ids: Vec<String>,
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>, // Only returned when the organization is created
+    pub branding: Option<BrandingProfile>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Scans of one member property within an organization's rollup
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrgPropertyScans {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub scans: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+}
+
+// Human scans across all of an organization's member properties
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrgAnalyticsSummary {
+    #[serde(rename = "organizationId")]
+    pub organization_id: String,
+    pub days: i64,
+    #[serde(rename = "propertyCount")]
+    pub property_count: usize,
+    #[serde(rename = "totalScans")]
+    pub total_scans: i64,
+    pub properties: Vec<OrgPropertyScans>, // Most scanned first; properties without scans are left out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyNotice>, // Set when noised for sharing; small properties are then left out too
+}
+
+impl Organization {
+    pub fn new(request: CreateOrganizationRequest, api_key_hash: String) -> Self {
+        let now = Utc::now();
+        let mut owner_ids = request.owner_ids;
+        owner_ids.sort();
+        owner_ids.dedup();
+
+        Self {
+            id: ObjectId::new(),
+            name: request.name.trim().to_string(),
+            owner_ids,
+            api_key_hash,
+            branding: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn has_member(&self, owner_id: &str) -> bool {
+        self.owner_ids.iter().any(|member| member == owner_id)
+    }
+
+    /// Convert to API response; the key is only included right after creation
+    pub fn to_response(&self, api_key: Option<String>) -> OrganizationResponse {
+        OrganizationResponse {
+            id: self.id.to_hex(),
+            name: self.name.clone(),
+            owner_ids: self.owner_ids.clone(),
+            api_key,
+            branding: self.branding.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl BrandingProfile {
+    /// The look kits get before an agency sets its own: the QR generation defaults
+    pub fn from_settings(settings: &QrGenerationSettings) -> Self {
+        Self {
+            logo_url: settings.logo_url.clone(),
+            primary_color: settings.foreground_color.clone(),
+            accent_color: settings.foreground_color.clone(),
+            tagline: None,
+        }
+    }
+}
+
+impl OrgAnalyticsSummary {
+    pub fn new(organization_id: String, days: i64, property_count: usize, properties: Vec<OrgPropertyScans>) -> Self {
+        Self {
+            organization_id,
+            days,
+            property_count,
+            total_scans: properties.iter().map(|property| property.scans).sum(),
+            properties,
+            privacy: None,
+        }
+    }
+}
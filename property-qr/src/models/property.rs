@@ -4,6 +4,7 @@ use chrono::{DateTime, Utc};
 use mongodb::bson::{oid::ObjectId, doc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Property {
@@ -52,6 +53,8 @@ pub struct Property {
     // Blockchain and ownership fields
     #[serde(rename = "onchainId")]
     pub onchain_id: Option<String>,
+    #[serde(rename = "chainId", default)]
+    pub chain_id: Option<u64>, // EVM chain the token lives on, e.g. 8453 for Base
     #[serde(rename = "coOwned")]
     pub co_owned: bool,
     #[serde(rename = "coOwners")]
@@ -216,6 +219,8 @@ pub struct Transaction {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Booking {
+    #[serde(rename = "_id", default)]
+    pub id: Option<ObjectId>, // Subdocument ID the listing platform gives each booking
     #[serde(rename = "renterId")]
     pub renter_id: ObjectId,
     #[serde(rename = "checkIn")]
@@ -351,11 +356,33 @@ pub enum DocumentType {
     Other,
 }
 
+impl DocumentType {
+    /// Translation key for the type's name on document verification pages
+    pub fn i18n_key(&self) -> &'static str {
+        match self {
+            DocumentType::TitleDeed => "document.type.title_deed",
+            DocumentType::CertificateOfLease => "document.type.certificate_of_lease",
+            DocumentType::RatesClearance => "document.type.rates_clearance",
+            DocumentType::LandRentClearance => "document.type.land_rent_clearance",
+            DocumentType::UtilityBill => "document.type.utility_bill",
+            DocumentType::CourtJudgment => "document.type.court_judgment",
+            DocumentType::Probate => "document.type.probate",
+            DocumentType::ConsentToTransfer => "document.type.consent_to_transfer",
+            DocumentType::SaleAgreement => "document.type.sale_agreement",
+            DocumentType::StampDutyReceipt => "document.type.stamp_duty_receipt",
+            DocumentType::AdversePossession => "document.type.adverse_possession",
+            DocumentType::Affidavit => "document.type.affidavit",
+            DocumentType::Other => "document.type.other",
+        }
+    }
+}
+
 // Simplified property for QR generation (only essential fields)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropertyQrInfo {
     #[serde(rename = "_id")]
     pub id: ObjectId,
+    pub owner: ObjectId,
     #[serde(rename = "propertyName")]
     pub property_name: String,
     pub location: String,
@@ -363,48 +390,184 @@ pub struct PropertyQrInfo {
     pub price: i64,
     #[serde(rename = "onchainId")]
     pub onchain_id: Option<String>,
+    #[serde(rename = "chainId", default)]
+    pub chain_id: Option<u64>, // Picks the explorer for onchain_id; None uses the default one
+    #[serde(rename = "ownerWalletAddress", default)]
+    pub owner_wallet_address: Option<String>, // Recorded holder of the onchain_id token
+    #[serde(rename = "baseName", default)]
+    pub base_name: Option<String>, // Only once the name's registration is confirmed
     #[serde(rename = "cryptoAccepted")]
     pub crypto_accepted: bool,
     pub images: Vec<String>,
     #[serde(rename = "isVerified")]
     pub is_verified: Option<bool>,
     pub removed: Option<bool>,
+    #[serde(default)]
+    pub sold: bool,
+    #[serde(default)]
+    pub coordinates: Option<Coordinates>, // For geo: payloads
+}
+
+// Why a listing can't get a QR code, most blocking first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IneligibilityReason {
+    Removed,
+    NoImages,
+    ZeroPrice,
+}
+
+// Listings blocked from QR generation, by reason. Removed listings are only listed as
+// removed; a live listing can show up under both no_images and zero_price.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EligibilityReport {
+    #[serde(rename = "totalProperties")]
+    pub total_properties: u64,
+    #[serde(rename = "eligibleProperties")]
+    pub eligible_properties: u64,
+    #[serde(rename = "ineligibleProperties")]
+    pub ineligible_properties: u64,
+    pub groups: Vec<IneligibilityGroup>,
+}
+
+// One page of the listings blocked for a reason; the cursor is the skip value for the next page
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IneligibilityGroup {
+    pub reason: IneligibilityReason,
+    pub count: u64,
+    pub properties: Vec<IneligibleProperty>,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<u64>, // None on the last page
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct IneligibleProperty {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+    pub location: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Listing as the QR admin UI shows it in search results and recent listings
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PropertyListItem {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+    pub location: String,
+    pub action: String, // "for sale" | "for rent"
+    pub price: i64,
+    #[serde(rename = "onchainId")]
+    pub onchain_id: Option<String>,
+    #[serde(rename = "cryptoAccepted")]
+    pub crypto_accepted: bool,
+    #[serde(rename = "primaryImage")]
+    pub primary_image: Option<String>,
+    #[serde(rename = "isVerified")]
+    pub is_verified: bool,
+    pub sold: bool,
+    #[serde(rename = "qrEligible")]
+    pub qr_eligible: bool,
+}
+
+impl PropertyListItem {
+    pub fn new(listing: &PropertyQrInfo, qr_eligible: bool) -> Self {
+        Self {
+            property_id: listing.id.to_hex(),
+            owner_id: listing.owner.to_hex(),
+            property_name: listing.property_name.clone(),
+            location: listing.location.clone(),
+            action: listing.action.clone(),
+            price: listing.price,
+            onchain_id: listing.onchain_id.clone(),
+            crypto_accepted: listing.crypto_accepted,
+            primary_image: listing.images.first().cloned(),
+            is_verified: listing.is_verified.unwrap_or(false),
+            sold: listing.sold,
+            qr_eligible,
+        }
+    }
+}
+
+impl IneligibilityReason {
+    pub const ALL: [IneligibilityReason; 3] = [
+        IneligibilityReason::Removed,
+        IneligibilityReason::NoImages,
+        IneligibilityReason::ZeroPrice,
+    ];
+
+    /// Message used when refusing to generate a code
+    pub fn description(&self) -> &'static str {
+        match self {
+            IneligibilityReason::Removed => "Property has been removed",
+            IneligibilityReason::NoImages => "Property has no images",
+            IneligibilityReason::ZeroPrice => "Property has invalid price",
+        }
+    }
 }
 
 impl Property {
     /// Check if property is available for QR generation
     pub fn is_qr_eligible(&self) -> bool {
-        // Property should not be removed
+        self.ineligibility_reason().is_none()
+    }
+
+    /// First rule the listing fails, if any: it must not be removed, and needs an image and a price
+    pub fn ineligibility_reason(&self) -> Option<IneligibilityReason> {
         if self.removed.unwrap_or(false) {
-            return false;
-        }
-        
-        // Property should have at least one image
-        if self.images.is_empty() {
-            return false;
+            Some(IneligibilityReason::Removed)
+        } else if self.images.is_empty() {
+            Some(IneligibilityReason::NoImages)
+        } else if self.price <= 0 {
+            Some(IneligibilityReason::ZeroPrice)
+        } else {
+            None
         }
-        
-        // Property should have a valid price
-        if self.price <= 0 {
-            return false;
+    }
+
+    /// Summary for search results
+    pub fn to_list_item(&self) -> PropertyListItem {
+        PropertyListItem::new(&self.to_qr_info(), self.is_qr_eligible())
+    }
+
+    /// Summary for the eligibility report
+    pub fn to_ineligible_property(&self) -> IneligibleProperty {
+        IneligibleProperty {
+            property_id: self.id.to_hex(),
+            property_name: self.property_name.clone(),
+            location: self.location.clone(),
+            owner_id: self.owner.to_hex(),
+            updated_at: self.updated_at,
         }
-        
-        true
     }
     
     /// Get essential info for QR code generation
     pub fn to_qr_info(&self) -> PropertyQrInfo {
         PropertyQrInfo {
             id: self.id,
+            owner: self.owner,
             property_name: self.property_name.clone(),
             location: self.location.clone(),
             action: self.action.clone(),
             price: self.price,
             onchain_id: self.onchain_id.clone(),
+            chain_id: self.chain_id,
+            owner_wallet_address: self.blockchain.as_ref().and_then(|b| b.owner_wallet_address.clone()),
+            base_name: self.base_name.clone().filter(|_| self.base_name_confirmed == Some(true)),
             crypto_accepted: self.crypto_accepted,
             images: self.images.clone(),
             is_verified: self.is_verified,
             removed: self.removed,
+            sold: self.status.sold,
+            coordinates: Some(self.coordinates.clone()),
         }
     }
     
@@ -413,6 +576,16 @@ impl Property {
         self.images.first()
     }
     
+    /// One of the property's documents by ID
+    pub fn document(&self, document_id: &str) -> Option<&Document> {
+        self.documents.as_ref()?.iter().find(|document| document.document_id == document_id)
+    }
+
+    /// One of the property's bookings by ID
+    pub fn booking(&self, booking_id: &ObjectId) -> Option<&Booking> {
+        self.bookings.iter().find(|booking| booking.id.as_ref() == Some(booking_id))
+    }
+
     /// Check if property has blockchain registration
     pub fn has_blockchain_info(&self) -> bool {
         self.onchain_id.is_some() || 
@@ -471,6 +644,7 @@ impl Default for Property {
             wishlist_history: Vec::new(),
             popularity_score: 0.0,
             onchain_id: None,
+            chain_id: None,
             co_owned: false,
             co_owners: Vec::new(),
             available_shares: 0,
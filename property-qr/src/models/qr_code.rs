@@ -3,11 +3,17 @@
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::config::QrPayloadMode;
+use crate::models::{OrgPropertyScans, QrPayload};
+use crate::utils::{validate_object_id, validate_property_ids, Validate, ValidationBuilder, ValidationError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QrCodeMetadata {
     #[serde(rename = "_id")]
+    #[schema(value_type = String)]
     pub id: ObjectId,
     #[serde(rename = "propertyId")]
     pub property_id: String, // MongoDB property ID as string
@@ -29,10 +35,126 @@ pub struct QrCodeMetadata {
     pub is_active: bool, // Whether QR code is active or disabled
     #[serde(rename = "qrVersion")]
     pub qr_version: i32, // Version number for QR regeneration tracking
-    pub metadata: QrMetadata,
+    pub metadata: QrMetadata, // Snapshot from generation time; render pages from the live listing
+
+    #[serde(rename = "customRedirectUrl", default)]
+    pub custom_redirect_url: Option<String>, // Temporarily replaces the property page, e.g. an open-house signup
+    #[serde(rename = "customRedirectUntil", default)]
+    pub custom_redirect_until: Option<DateTime<Utc>>, // Scans go back to the property page after this
+    #[serde(default)]
+    pub style: Option<String>, // Named QR style the image was drawn with; regenerations keep it
+    #[serde(default)]
+    pub payload: QrPayload, // What the code encodes; regenerations keep it
+    #[serde(rename = "storageState", default)]
+    pub storage_state: QrStorageState, // Records from before this was tracked are active
+    #[serde(rename = "imageSize", default)]
+    pub image_size: Option<i64>, // Of the uploaded image, to verify it later; None before this was recorded
+    #[serde(rename = "imageEtag", default)]
+    pub image_etag: Option<String>, // As S3 reported it on upload
+    #[serde(rename = "imageKey", default)]
+    pub image_key: Option<String>, // Where the image is stored; None for images at the original unversioned key
+    #[serde(rename = "renditionSizes", default)]
+    pub rendition_sizes: Vec<u32>, // Stored in every rendition format; empty for codes drawn before renditions
+    #[serde(rename = "customImage", default)]
+    pub custom_image: bool, // Designed elsewhere and uploaded, so never redrawn here
+    #[serde(rename = "maxScans", default)]
+    pub max_scans: Option<i64>, // Limited-use codes, e.g. exclusive-viewing invitations, deactivate after this many scans
+    #[serde(rename = "limitedScans", default)]
+    pub limited_scans: i64, // Scans counted against max_scans since it was set
+    #[serde(rename = "activeFrom", default)]
+    pub active_from: Option<DateTime<Utc>>, // Scheduled codes, e.g. for an open house, open at this time
+    #[serde(rename = "activeUntil", default)]
+    pub active_until: Option<DateTime<Utc>>, // ... and stop working after this one
+    #[serde(rename = "awaitingActivation", default)]
+    pub awaiting_activation: bool, // Inactive only until active_from; the schedule job activates it then
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a moment falls against a scheduled code's active window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QrCodeWindow {
+    Open,
+    NotStarted(DateTime<Utc>), // Opens at
+    Ended,
+}
+
+// A code as GET /qr/{property_id} returns it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrCodeDetails {
+    #[serde(flatten)]
+    pub qr_code: QrCodeMetadata,
+    #[serde(rename = "remainingUses")]
+    pub remaining_uses: Option<i64>, // None for codes without a scan limit
+}
+
+// Whether a code's image is known to be in S3. Records are written pending before the upload
+// and activated after it, so reconciliation can finish an upload that failed in between.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QrStorageState {
+    Pending,
+    #[default]
+    Active,
+}
+
+/// What a photographed QR code decodes to, checked against the code on record; for support
+/// looking into a broken sign
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct QrDecodeReport {
+    #[serde(rename = "decodedText")]
+    pub decoded_text: String,
+    #[serde(rename = "scanUrl")]
+    pub scan_url: Option<String>, // None if the code doesn't point at a property's scan page
+    #[serde(rename = "propertyId")]
+    pub property_id: Option<String>,
+    #[serde(rename = "knownProperty")]
+    pub known_property: bool, // The listing platform has the property
+    #[serde(rename = "hasQrCode")]
+    pub has_qr_code: bool,
+    #[serde(rename = "encodedVersion")]
+    pub encoded_version: Option<i32>, // From the scan URL; None for codes printed before versions
+    #[serde(rename = "currentVersion")]
+    pub current_version: Option<i32>,
+    #[serde(rename = "isCurrent")]
+    pub is_current: bool, // Decodes to exactly what the code on record encodes
+    #[serde(rename = "isActive")]
+    pub is_active: Option<bool>,
+    #[serde(rename = "assetStatus")]
+    pub asset_status: Option<AssetStatus>, // The stored image compared with its upload; never repaired here
+}
+
+/// Widths, in pixels, each code is also stored at for web and print
+pub const QR_RENDITION_SIZES: [u32; 3] = [256, 512, 1024];
+
+// Most properties one batch generation request may name
+pub const MAX_BATCH_PROPERTIES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrRenditionFormat {
+    Png,
+    Webp,
+}
+
+impl QrRenditionFormat {
+    pub const ALL: [QrRenditionFormat; 2] = [QrRenditionFormat::Png, QrRenditionFormat::Webp];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            QrRenditionFormat::Png => "png",
+            QrRenditionFormat::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            QrRenditionFormat::Png => "image/png",
+            QrRenditionFormat::Webp => "image/webp",
+        }
+    }
+}
+
+// Listing details as they were when the code was generated. Names, prices and images
+// change afterwards, so pages render from the live property and use this only as a fallback.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QrMetadata {
     #[serde(rename = "propertyName")]
     pub property_name: String,
@@ -48,12 +170,13 @@ pub struct QrMetadata {
     #[serde(rename = "isVerified")]
     pub is_verified: bool,
     #[serde(rename = "generatedBy")]
+    #[schema(value_type = Option<String>)]
     pub generated_by: Option<ObjectId>, // User who generated the QR
     #[serde(rename = "generationReason")]
     pub generation_reason: QrGenerationReason,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QrGenerationReason {
     NewProperty,
@@ -61,6 +184,7 @@ pub enum QrGenerationReason {
     ManualRegeneration,
     BatchGeneration,
     ExpiredQr,
+    BaseUrlChanged,
 }
 
 // QR Code data structure that gets encoded into the QR
@@ -79,16 +203,44 @@ pub struct QrCodeData {
 }
 
 // Request/Response DTOs for API
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GenerateQrRequest {
     #[serde(rename = "propertyId")]
     pub property_id: String,
     #[serde(rename = "forceRegenerate")]
     pub force_regenerate: Option<bool>, // Force regeneration even if QR exists
     pub reason: Option<QrGenerationReason>,
+    pub style: Option<String>, // Named QR style; regenerating without one keeps the code's current style
+    pub payload: Option<QrPayload>, // vCard, geo or WiFi instead of the scan URL; regenerating without one keeps the current payload
+    #[serde(rename = "overrideNote")]
+    pub override_note: Option<String>, // Required with ?allow_ineligible=true; kept in the audit record
+    #[serde(rename = "maxScans")]
+    pub max_scans: Option<i64>, // Deactivate after this many scans, counted from now; regenerating without one keeps the current limit
+    #[serde(rename = "activeFrom")]
+    pub active_from: Option<DateTime<Utc>>, // Scheduled window; setting either end replaces the whole window
+    #[serde(rename = "activeUntil")]
+    pub active_until: Option<DateTime<Utc>>, // Regenerating without either keeps the current window
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Codes to download for print: explicit property IDs, or everything matching a search
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrExportRequest {
+    #[serde(rename = "propertyIds", default)]
+    pub property_ids: Vec<String>,
+    pub query: Option<String>, // Same matching as GET /qr/search
+}
+
+// Properties to print on sticker sheets, in order, and the grid each sheet uses
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct StickerSheetRequest {
+    #[serde(rename = "propertyIds")]
+    pub property_ids: Vec<String>,
+    pub rows: Option<u32>,    // Default 7
+    pub columns: Option<u32>, // Default 3
+    pub size: Option<PosterSize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BatchGenerateQrRequest {
     #[serde(rename = "propertyIds")]
     pub property_ids: Vec<String>,
@@ -97,7 +249,14 @@ pub struct BatchGenerateQrRequest {
     pub reason: Option<QrGenerationReason>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateQrRedirectRequest {
+    #[serde(rename = "customRedirectUrl")]
+    pub custom_redirect_url: Option<String>, // null goes back to the property page
+    pub until: Option<DateTime<Utc>>,        // Leave unset to keep the redirect until it's cleared
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QrCodeResponse {
     #[serde(rename = "propertyId")]
     pub property_id: String,
@@ -109,9 +268,12 @@ pub struct QrCodeResponse {
     pub generated_at: DateTime<Utc>,
     pub metadata: QrMetadata,
     pub status: QrStatus,
+    /// Rendition URLs by format, then by srcset width descriptor, e.g. renditions.webp["512w"]
+    #[serde(default)]
+    pub renditions: BTreeMap<String, BTreeMap<String, String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BatchQrCodeResponse {
     pub successful: Vec<QrCodeResponse>,
     pub failed: Vec<QrGenerationError>,
@@ -123,7 +285,172 @@ pub struct BatchQrCodeResponse {
     pub total_failed: usize,
 }
 
+// Audit record of an admin generating a code for a listing that isn't eligible for one
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EligibilityOverride {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "ineligibleReason")]
+    pub ineligible_reason: String, // Why the listing would normally be refused
+    pub note: String, // Why ops generated it anyway
+    #[serde(rename = "generationReason")]
+    pub generation_reason: QrGenerationReason,
+    #[serde(rename = "qrVersion")]
+    pub qr_version: i32,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+// Background job regenerating QR codes whose encoded scan URL is stale
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrRegenerationJob {
+    #[serde(rename = "_id")]
+    #[schema(value_type = String)]
+    pub id: ObjectId,
+    pub status: RegenerationJobStatus,
+    #[serde(rename = "baseUrl")]
+    pub base_url: String, // Scan base URL the QR codes are being moved to
+    #[serde(rename = "batchSize")]
+    pub batch_size: usize,
+    pub total: usize,
+    pub processed: usize,
+    pub succeeded: usize,
+    pub failed: Vec<QrGenerationError>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrRegenerationJobResponse {
+    #[serde(flatten)]
+    pub job: QrRegenerationJob,
+    pub progress: f64, // Percentage processed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StaleQrReport {
+    #[serde(rename = "baseUrl")]
+    pub base_url: String,
+    #[serde(rename = "staleCount")]
+    pub stale_count: usize,
+    #[serde(rename = "propertyIds")]
+    pub property_ids: Vec<String>,
+}
+
+// Field the QR code list is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QrSortField {
+    #[default]
+    GeneratedAt,
+    ScanCount,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// Sheet a printable poster is laid out for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PosterSize {
+    #[default]
+    A4,
+    A5,
+}
+
+impl PosterSize {
+    /// Page width and height in points
+    pub fn dimensions(&self) -> (f32, f32) {
+        match self {
+            PosterSize::A4 => (595.28, 841.89),
+            PosterSize::A5 => (419.53, 595.28),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PosterSize::A4 => "a4",
+            PosterSize::A5 => "a5",
+        }
+    }
+}
+
+// One page of QR codes; the cursors are the skip values for the neighbouring pages
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QrCodePage {
+    pub items: Vec<QrCodeMetadata>,
+    #[serde(rename = "totalCount")]
+    pub total_count: u64,
+    pub limit: i64,
+    pub skip: u64,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<u64>, // None on the last page
+    #[serde(rename = "prevCursor")]
+    pub prev_cursor: Option<u64>, // None on the first page
+}
+
+// An owner's QR code with its scan totals
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnerQrCode {
+    #[serde(flatten)]
+    pub qr_code: QrCodeMetadata, // scanCount is the all-time total
+    #[serde(rename = "recentScans")]
+    pub recent_scans: i64, // Human scans over the listing's `days`
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+}
+
+// Every QR code of an owner's listed properties, for the app's "My listings" page
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OwnerQrListing {
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub days: i64,
+    pub items: Vec<OwnerQrCode>, // Newest first
+    #[serde(rename = "totalScans")]
+    pub total_scans: i64,
+    #[serde(rename = "recentScans")]
+    pub recent_scans: i64,
+    #[serde(rename = "propertiesWithoutQr")]
+    pub properties_without_qr: Vec<String>, // Listed properties that have no code yet
+}
+
+// Outcome of the startup check that QR generation and storage work end to end
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub steps: Vec<SelfTestStep>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestStep {
+    pub name: String, // "qr_encode", "qr_decode", "storage_upload", "storage_delete"
+    pub passed: bool,
+    pub error: Option<String>,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegenerationJobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QrGenerationError {
     #[serde(rename = "propertyId")]
     pub property_id: String,
@@ -132,7 +459,7 @@ pub struct QrGenerationError {
     pub error_code: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QrStatus {
     Generated,
@@ -141,6 +468,28 @@ pub enum QrStatus {
     Exists,
 }
 
+// What checking a code's image in S3 against the uploaded one found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetStatus {
+    Intact,
+    Unrecorded, // Present, but uploaded before sizes and ETags were kept; they're recorded now
+    Missing,
+    Corrupted,  // Size or ETag differs from the upload
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AssetVerification {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "s3Key")]
+    pub s3_key: String,
+    pub status: AssetStatus,
+    pub regenerated: bool, // Missing and corrupted images are redrawn and uploaded again
+    pub size: Option<i64>, // Of the image now stored
+    pub etag: Option<String>,
+}
+
 // S3 storage configuration for QR codes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrS3Config {
@@ -186,6 +535,16 @@ pub struct QrGenerationSettings {
     #[serde(rename = "foregroundColor")]
     pub foreground_color: String, // Hex color code
     pub format: QrImageFormat,
+    #[serde(rename = "roundedModules", default)]
+    pub rounded_modules: bool, // Draw modules as dots instead of squares
+    #[serde(rename = "quietZone", default = "default_quiet_zone")]
+    pub quiet_zone: u32, // Margin around the code, in modules
+    #[serde(rename = "payloadMode", default)]
+    pub payload_mode: QrPayloadMode, // Bare scan URL (default) or the legacy JSON
+}
+
+fn default_quiet_zone() -> u32 {
+    crate::models::DEFAULT_QUIET_ZONE
 }
 
 
@@ -217,14 +576,62 @@ impl QrCodeMetadata {
             is_active: true,
             qr_version: 1,
             metadata,
+            custom_redirect_url: None,
+            custom_redirect_until: None,
+            style: None,
+            payload: QrPayload::Scan,
+            storage_state: QrStorageState::Active,
+            image_size: None,
+            image_etag: None,
+            image_key: None,
+            rendition_sizes: Vec::new(),
+            custom_image: false,
+            max_scans: None,
+            limited_scans: 0,
+            active_from: None,
+            active_until: None,
+            awaiting_activation: false,
         }
     }
 
-    /// Increment scan count and update last scanned timestamp
-    pub fn record_scan(&mut self) {
-        self.scan_count += 1;
-        self.last_scanned = Some(Utc::now());
-        self.last_updated = Utc::now();
+    /// The listing behind the code; None for a malformed property ID
+    pub fn listing_id(&self) -> Option<ObjectId> {
+        ObjectId::parse_str(&self.property_id).ok()
+    }
+
+    /// The listing's live name when it was loaded, else the one the code was generated with
+    pub fn listing_name(&self, live_names: &HashMap<ObjectId, String>) -> String {
+        self.listing_id()
+            .and_then(|id| live_names.get(&id))
+            .unwrap_or(&self.metadata.property_name)
+            .clone()
+    }
+
+    /// Scans a limited-use code has left; None when it has no limit
+    pub fn remaining_uses(&self) -> Option<i64> {
+        self.max_scans.map(|max_scans| (max_scans - self.limited_scans).max(0))
+    }
+
+    /// Whether a limited-use code has used up its scans; unlimited codes never do
+    pub fn is_used_up(&self) -> bool {
+        self.max_scans.is_some() && (!self.is_active || self.remaining_uses() == Some(0))
+    }
+
+    /// Whether `now` falls in the code's scheduled window; codes without one are always open
+    pub fn window(&self, now: DateTime<Utc>) -> QrCodeWindow {
+        match (self.active_from, self.active_until) {
+            (Some(from), _) if now < from => QrCodeWindow::NotStarted(from),
+            (_, Some(until)) if now >= until => QrCodeWindow::Ended,
+            _ => QrCodeWindow::Open,
+        }
+    }
+
+    /// Where scans go instead of the property page, unless no redirect is set or it has lapsed
+    pub fn active_redirect_url(&self, now: DateTime<Utc>) -> Option<&str> {
+        match self.custom_redirect_until {
+            Some(until) if until <= now => None,
+            _ => self.custom_redirect_url.as_deref(),
+        }
     }
 
     /// Deactivate the QR code
@@ -254,9 +661,84 @@ impl QrCodeMetadata {
         Utc::now() > expiry_date
     }
 
+    /// Scan URL encoded in the QR pattern, if it can be decoded. URL-only codes encode nothing
+    /// else; their property ID, version and generation time live on this record.
+    pub fn encoded_scan_url(&self) -> Option<String> {
+        match self.payload {
+            QrPayload::Scan => match QrPayloadMode::of(&self.qr_pattern) {
+                QrPayloadMode::Url => Some(self.qr_pattern.clone()),
+                QrPayloadMode::Json => QrCodeData::from_json_string(&self.qr_pattern)
+                    .ok()
+                    .map(|data| data.scan_url),
+            },
+            QrPayload::Vcard(_) => QrPayload::vcard_scan_url(&self.qr_pattern),
+            QrPayload::Geo | QrPayload::Wifi(_) => None,
+        }
+    }
+
+    /// Whether the encoded scan URL no longer matches the configured base URL
+    pub fn is_stale(&self, base_url: &str) -> bool {
+        // Geo and WiFi codes don't point at this service at all
+        if !self.payload.tracks_scans() {
+            return false;
+        }
+        let expected = format!("{}/scan/{}", base_url, self.property_id);
+        let encoded = self.encoded_scan_url();
+        // The version query only says which print this is, not where it points
+        encoded.as_deref().and_then(|url| url.split('?').next()) != Some(expected.as_str())
+    }
+
     /// Get S3 key for the QR image
     pub fn get_s3_key(&self) -> String {
-        format!("qr-images/{}.png", self.property_id)
+        self.image_key.clone()
+            .unwrap_or_else(|| Self::image_key_for(&self.property_id, 1))
+    }
+
+    /// Key a version of a property's image is stored at. Regenerated images get a new key, so
+    /// CDN caches holding the old image for up to a year never serve it in place of the new one.
+    pub fn image_key_for(property_id: &str, qr_version: i32) -> String {
+        Self::image_key_with_extension(property_id, qr_version, "png")
+    }
+
+    /// Key for an uploaded image, which may be an SVG
+    pub fn image_key_with_extension(property_id: &str, qr_version: i32, extension: &str) -> String {
+        match qr_version {
+            ..=1 => format!("qr-images/{}.{}", property_id, extension),
+            version => format!("qr-images/{}-v{}.{}", property_id, version, extension),
+        }
+    }
+
+    /// Key a rendition of the image at `image_key` is stored at, e.g.
+    /// qr-renditions/{property_id}-v2/512.webp
+    pub fn rendition_key(image_key: &str, size: u32, format: QrRenditionFormat) -> String {
+        let stem = image_key.strip_prefix("qr-images/")
+            .and_then(|key| key.strip_suffix(".png"))
+            .unwrap_or(image_key);
+        format!("qr-renditions/{}/{}.{}", stem, size, format.extension())
+    }
+
+    /// Key of the image a rendition was drawn from
+    pub fn image_key_of_rendition(key: &str) -> Option<String> {
+        let (stem, _) = key.strip_prefix("qr-renditions/")?.rsplit_once('/')?;
+        Some(format!("qr-images/{}.png", stem))
+    }
+
+    /// Keys of every stored rendition of this code's image
+    pub fn rendition_keys(&self) -> Vec<String> {
+        let image_key = self.get_s3_key();
+        self.rendition_sizes.iter()
+            .flat_map(|&size| QrRenditionFormat::ALL.map(|format| Self::rendition_key(&image_key, size, format)))
+            .collect()
+    }
+
+    /// Property a stored QR image belongs to, from its key
+    pub fn property_id_from_s3_key(key: &str) -> Option<&str> {
+        let file = key.strip_prefix("qr-images/")?;
+        let name = file.strip_suffix(".png").or_else(|| file.strip_suffix(".svg"))?;
+        match name.rsplit_once("-v") {
+            Some((property_id, version)) if !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()) => Some(property_id),
+            _ => Some(name),
+        }
     }
 
     /// Get S3 key for metadata
@@ -265,13 +747,40 @@ impl QrCodeMetadata {
     }
 }
 
+impl QrRegenerationJob {
+    /// Start tracking a regeneration run
+    pub fn new(base_url: String, batch_size: usize, total: usize) -> Self {
+        Self {
+            id: ObjectId::new(),
+            status: RegenerationJobStatus::Running,
+            base_url,
+            batch_size,
+            total,
+            processed: 0,
+            succeeded: 0,
+            failed: Vec::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    /// Percentage of QR codes processed so far
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            (self.processed as f64 / self.total as f64) * 100.0
+        }
+    }
+}
+
 impl QrCodeData {
-    /// Create new QR code data
-    pub fn new(property_id: String, scan_base_url: &str) -> Self {
+    /// Create new QR code data; the scan URL carries the QR version so scans of old prints can be spotted
+    pub fn new(property_id: String, scan_base_url: &str, qr_version: i32) -> Self {
         Self {
             qr_type: "daobitat_property".to_string(),
             property_id: property_id.clone(),
-            scan_url: format!("{}/scan/{}", scan_base_url, property_id),
+            scan_url: format!("{}/scan/{}?v={}", scan_base_url, property_id, qr_version),
             version: "1.0".to_string(),
             timestamp: Utc::now().timestamp(),
         }
@@ -282,6 +791,14 @@ impl QrCodeData {
         serde_json::to_string(self)
     }
 
+    /// What the QR image encodes: just the scan URL, or the whole structure as JSON
+    pub fn to_payload(&self, mode: QrPayloadMode) -> Result<String, serde_json::Error> {
+        match mode {
+            QrPayloadMode::Url => Ok(self.scan_url.clone()),
+            QrPayloadMode::Json => self.to_json_string(),
+        }
+    }
+
     /// Create from JSON string
     pub fn from_json_string(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
@@ -296,6 +813,93 @@ impl QrCodeData {
     }
 }
 
+impl OwnerQrListing {
+    /// Join the owner's property IDs with their codes and each code's recent scans
+    pub fn new(
+        owner_id: String,
+        days: i64,
+        property_ids: &[String],
+        qr_codes: Vec<QrCodeMetadata>,
+        scans: Vec<OrgPropertyScans>,
+    ) -> Self {
+        let mut scans: HashMap<String, OrgPropertyScans> = scans.into_iter()
+            .map(|property| (property.property_id.clone(), property))
+            .collect();
+        let properties_without_qr = property_ids.iter()
+            .filter(|property_id| !qr_codes.iter().any(|qr| &qr.property_id == *property_id))
+            .cloned()
+            .collect();
+
+        let items: Vec<OwnerQrCode> = qr_codes.into_iter()
+            .map(|qr_code| {
+                let scans = scans.remove(&qr_code.property_id);
+                OwnerQrCode {
+                    recent_scans: scans.as_ref().map_or(0, |scans| scans.scans),
+                    unique_visitors: scans.as_ref().map_or(0, |scans| scans.unique_visitors),
+                    qr_code,
+                }
+            })
+            .collect();
+
+        Self {
+            owner_id,
+            days,
+            total_scans: items.iter().map(|item| item.qr_code.scan_count).sum(),
+            recent_scans: items.iter().map(|item| item.recent_scans).sum(),
+            items,
+            properties_without_qr,
+        }
+    }
+}
+
+impl QrCodePage {
+    pub fn new(items: Vec<QrCodeMetadata>, total_count: u64, limit: i64, skip: u64) -> Self {
+        let page_size = limit.max(1) as u64;
+        let next_cursor = Some(skip + page_size).filter(|next| *next < total_count);
+        let prev_cursor = (skip > 0).then(|| skip.saturating_sub(page_size));
+
+        Self { items, total_count, limit, skip, next_cursor, prev_cursor }
+    }
+}
+
+impl Validate for GenerateQrRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut builder = ValidationBuilder::new();
+        builder.validate(|| validate_object_id(&self.property_id, "propertyId"));
+        if self.max_scans.is_some_and(|max_scans| max_scans < 1) {
+            builder.add_error(ValidationError::new("maxScans", "maxScans must be at least 1", "MAX_SCANS_TOO_LOW"));
+        }
+        match (self.active_from, self.active_until) {
+            (Some(from), Some(until)) if until <= from => {
+                builder.add_error(ValidationError::new("activeUntil", "activeUntil must be after activeFrom", "INVALID_WINDOW"));
+            }
+            (_, Some(until)) if until <= Utc::now() => {
+                builder.add_error(ValidationError::new("activeUntil", "activeUntil must be in the future", "WINDOW_ENDED"));
+            }
+            _ => {}
+        }
+        builder.build_all_errors()
+    }
+}
+
+impl Validate for BatchGenerateQrRequest {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut builder = ValidationBuilder::new();
+        if self.property_ids.is_empty() {
+            builder.add_error(ValidationError::new("propertyIds", "Property IDs list cannot be empty", "EMPTY_LIST"));
+        }
+        if self.property_ids.len() > MAX_BATCH_PROPERTIES {
+            builder.add_error(ValidationError::new(
+                "propertyIds",
+                &format!("Cannot process more than {} properties at once", MAX_BATCH_PROPERTIES),
+                "TOO_MANY_ITEMS",
+            ));
+        }
+        validate_property_ids(&self.property_ids, "propertyIds", &mut builder);
+        builder.build_all_errors()
+    }
+}
+
 impl Default for QrGenerationSettings {
     fn default() -> Self {
         Self {
@@ -306,6 +910,9 @@ impl Default for QrGenerationSettings {
             background_color: "#FFFFFF".to_string(),
             foreground_color: "#000000".to_string(),
             format: QrImageFormat::Png,
+            rounded_modules: false,
+            quiet_zone: crate::models::DEFAULT_QUIET_ZONE,
+            payload_mode: QrPayloadMode::Url,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+// src/models/qr_payload.rs
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::Coordinates;
+
+// What a QR code hands the phone. Scan codes open the tracked scan URL; the others are
+// read by the phone itself, so only a vCard's link ever reaches the scan pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QrPayload {
+    #[default]
+    Scan,
+    Vcard(AgentContact), // Listing agent's contact card, linking to the scan URL
+    Geo,                 // geo: URI for the property's coordinates
+    Wifi(WifiNetwork),   // Join a network, e.g. the guest WiFi at an open house
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AgentContact {
+    pub name: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub organization: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WifiNetwork {
+    pub ssid: String,
+    pub password: Option<String>, // Required unless the network is open
+    #[serde(default)]
+    pub security: WifiSecurity,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum WifiSecurity {
+    #[default]
+    #[serde(rename = "WPA")]
+    Wpa,
+    #[serde(rename = "WEP")]
+    Wep,
+    #[serde(rename = "nopass")]
+    Open,
+}
+
+// vCard line carrying the scan URL
+const VCARD_URL_PREFIX: &str = "URL:";
+
+impl QrPayload {
+    /// Check the details are enough to encode
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            QrPayload::Scan | QrPayload::Geo => Ok(()),
+            QrPayload::Vcard(contact) => {
+                if contact.name.trim().is_empty() {
+                    return Err("vcard name is required".to_string());
+                }
+                let reachable = [&contact.phone, &contact.email]
+                    .iter()
+                    .any(|field| field.as_deref().is_some_and(|value| !value.trim().is_empty()));
+                if !reachable {
+                    return Err("vcard needs a phone or an email".to_string());
+                }
+                Ok(())
+            }
+            QrPayload::Wifi(network) => {
+                if network.ssid.is_empty() || network.ssid.len() > 32 {
+                    return Err("wifi ssid must be 1-32 bytes".to_string());
+                }
+                if network.security != WifiSecurity::Open && network.password.as_deref().is_none_or(str::is_empty) {
+                    return Err("wifi password is required unless security is nopass".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Whether scans of this code go through the scan URL and show up in analytics
+    pub fn tracks_scans(&self) -> bool {
+        matches!(self, QrPayload::Scan | QrPayload::Vcard(_))
+    }
+
+    /// vCard 3.0 for the agent, noting the listing and linking to its scan URL
+    pub fn vcard(contact: &AgentContact, listing_name: &str, scan_url: &str) -> String {
+        let mut lines = vec![
+            "BEGIN:VCARD".to_string(),
+            "VERSION:3.0".to_string(),
+            format!("FN:{}", escape_vcard(&contact.name)),
+        ];
+        if let Some(organization) = &contact.organization {
+            lines.push(format!("ORG:{}", escape_vcard(organization)));
+        }
+        if let Some(phone) = &contact.phone {
+            lines.push(format!("TEL;TYPE=CELL:{}", escape_vcard(phone)));
+        }
+        if let Some(email) = &contact.email {
+            lines.push(format!("EMAIL:{}", escape_vcard(email)));
+        }
+        lines.push(format!("NOTE:{}", escape_vcard(listing_name)));
+        lines.push(format!("{}{}", VCARD_URL_PREFIX, scan_url));
+        lines.push("END:VCARD".to_string());
+        lines.join("\r\n")
+    }
+
+    /// RFC 5870 geo: URI, or None when the listing has no usable coordinates
+    pub fn geo_uri(coordinates: &Coordinates) -> Option<String> {
+        let valid = (-90.0..=90.0).contains(&coordinates.lat)
+            && (-180.0..=180.0).contains(&coordinates.lng)
+            // Listings created without a pin default to 0,0
+            && !(coordinates.lat == 0.0 && coordinates.lng == 0.0);
+
+        valid.then(|| format!("geo:{},{}", coordinates.lat, coordinates.lng))
+    }
+
+    /// WIFI: network string most phone cameras can join from
+    pub fn wifi(network: &WifiNetwork) -> String {
+        let security = match network.security {
+            WifiSecurity::Wpa => "WPA",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::Open => "nopass",
+        };
+        let mut payload = format!("WIFI:T:{};S:{};", security, escape_wifi(&network.ssid));
+        if network.security != WifiSecurity::Open {
+            payload.push_str(&format!("P:{};", escape_wifi(network.password.as_deref().unwrap_or_default())));
+        }
+        if network.hidden {
+            payload.push_str("H:true;");
+        }
+        payload.push(';');
+        payload
+    }
+
+    /// Scan URL a vCard payload links to
+    pub fn vcard_scan_url(payload: &str) -> Option<String> {
+        payload.split("\r\n")
+            .find_map(|line| line.strip_prefix(VCARD_URL_PREFIX))
+            .map(str::to_string)
+    }
+}
+
+fn escape_vcard(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+fn escape_wifi(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact() -> AgentContact {
+        AgentContact {
+            name: "Wanjiku Kamau".to_string(),
+            phone: Some("+254712345678".to_string()),
+            email: None,
+            organization: Some("Acme Realty, Ltd".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_vcard_links_to_scan_url() {
+        let scan_url = "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011?v=2";
+        let vcard = QrPayload::vcard(&contact(), "Garden Villa; Karen", scan_url);
+
+        assert!(vcard.starts_with("BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Wanjiku Kamau\r\n"));
+        assert!(vcard.contains("ORG:Acme Realty\\, Ltd\r\n"));
+        assert!(vcard.contains("NOTE:Garden Villa\\; Karen\r\n"));
+        assert!(!vcard.contains("EMAIL:"));
+        assert_eq!(QrPayload::vcard_scan_url(&vcard).as_deref(), Some(scan_url));
+    }
+
+    #[test]
+    fn test_geo_uri() {
+        assert_eq!(
+            QrPayload::geo_uri(&Coordinates { lat: -1.2921, lng: 36.8219 }).as_deref(),
+            Some("geo:-1.2921,36.8219")
+        );
+        assert_eq!(QrPayload::geo_uri(&Coordinates { lat: 0.0, lng: 0.0 }), None);
+        assert_eq!(QrPayload::geo_uri(&Coordinates { lat: 91.0, lng: 0.0 }), None);
+    }
+
+    #[test]
+    fn test_wifi() {
+        let network = WifiNetwork {
+            ssid: "Open House".to_string(),
+            password: Some("pa;ss:word".to_string()),
+            security: WifiSecurity::Wpa,
+            hidden: true,
+        };
+        assert_eq!(QrPayload::wifi(&network), "WIFI:T:WPA;S:Open House;P:pa\\;ss\\:word;H:true;;");
+
+        let open = WifiNetwork { security: WifiSecurity::Open, password: None, hidden: false, ..network };
+        assert_eq!(QrPayload::wifi(&open), "WIFI:T:nopass;S:Open House;;");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(QrPayload::Vcard(contact()).validate().is_ok());
+        assert!(QrPayload::Vcard(AgentContact { phone: None, ..contact() }).validate().is_err());
+        assert!(QrPayload::Vcard(AgentContact { name: " ".to_string(), ..contact() }).validate().is_err());
+
+        let network = WifiNetwork { ssid: "Guest".to_string(), password: None, security: WifiSecurity::Wpa, hidden: false };
+        assert!(QrPayload::Wifi(network.clone()).validate().is_err());
+        assert!(QrPayload::Wifi(WifiNetwork { security: WifiSecurity::Open, ..network }).validate().is_ok());
+    }
+
+    #[test]
+    fn test_payload_json_shape() {
+        let payload: QrPayload = serde_json::from_value(serde_json::json!({
+            "type": "wifi", "ssid": "Guest", "password": "secret"
+        })).unwrap();
+        assert_eq!(payload, QrPayload::Wifi(WifiNetwork {
+            ssid: "Guest".to_string(),
+            password: Some("secret".to_string()),
+            security: WifiSecurity::Wpa,
+            hidden: false,
+        }));
+        assert_eq!(serde_json::to_value(QrPayload::Geo).unwrap(), serde_json::json!({ "type": "geo" }));
+    }
+}
@@ -0,0 +1,163 @@
+// src/models/qr_style.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::QrGenerationSettings;
+
+// Quiet zone the QR spec asks for, in modules
+pub const DEFAULT_QUIET_ZONE: u32 = 4;
+
+// Named look for QR codes, e.g. one per partner brand. Size, error correction and format
+// stay the deployment's; a style only changes how the code looks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QrStyle {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String, // Slug used as the `style` in generation requests
+    #[serde(rename = "foregroundColor")]
+    pub foreground_color: String,
+    #[serde(rename = "backgroundColor")]
+    pub background_color: String,
+    #[serde(rename = "roundedModules")]
+    pub rounded_modules: bool,
+    #[serde(rename = "quietZone")]
+    pub quiet_zone: u32, // Modules of margin around the code
+    #[serde(rename = "logoUrl")]
+    pub logo_url: Option<String>, // Replaces the deployment's logo when set
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertQrStyleRequest {
+    #[serde(rename = "foregroundColor")]
+    pub foreground_color: String, // e.g. #1A2B3C
+    #[serde(rename = "backgroundColor")]
+    pub background_color: String,
+    #[serde(rename = "roundedModules", default)]
+    pub rounded_modules: bool,
+    #[serde(rename = "quietZone")]
+    pub quiet_zone: Option<u32>, // 0-16 modules (default 4)
+    #[serde(rename = "logoUrl")]
+    pub logo_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrStyleResponse {
+    pub name: String,
+    #[serde(rename = "foregroundColor")]
+    pub foreground_color: String,
+    #[serde(rename = "backgroundColor")]
+    pub background_color: String,
+    #[serde(rename = "roundedModules")]
+    pub rounded_modules: bool,
+    #[serde(rename = "quietZone")]
+    pub quiet_zone: u32,
+    #[serde(rename = "logoUrl")]
+    pub logo_url: Option<String>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QrStyle {
+    /// Create a named style
+    pub fn new(name: String, request: UpsertQrStyleRequest) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            name,
+            foreground_color: request.foreground_color,
+            background_color: request.background_color,
+            rounded_modules: request.rounded_modules,
+            quiet_zone: request.quiet_zone.unwrap_or(DEFAULT_QUIET_ZONE),
+            logo_url: request.logo_url,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Replace the look, keeping the name and creation date
+    pub fn apply(mut self, request: UpsertQrStyleRequest) -> Self {
+        self.foreground_color = request.foreground_color;
+        self.background_color = request.background_color;
+        self.rounded_modules = request.rounded_modules;
+        self.quiet_zone = request.quiet_zone.unwrap_or(DEFAULT_QUIET_ZONE);
+        self.logo_url = request.logo_url;
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// The deployment's generation settings with this style's look on top
+    pub fn settings(&self, base: &QrGenerationSettings) -> QrGenerationSettings {
+        QrGenerationSettings {
+            foreground_color: self.foreground_color.clone(),
+            background_color: self.background_color.clone(),
+            rounded_modules: self.rounded_modules,
+            quiet_zone: self.quiet_zone,
+            include_logo: base.include_logo || self.logo_url.is_some(),
+            logo_url: self.logo_url.clone().or_else(|| base.logo_url.clone()),
+            ..base.clone()
+        }
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> QrStyleResponse {
+        QrStyleResponse {
+            name: self.name.clone(),
+            foreground_color: self.foreground_color.clone(),
+            background_color: self.background_color.clone(),
+            rounded_modules: self.rounded_modules,
+            quiet_zone: self.quiet_zone,
+            logo_url: self.logo_url.clone(),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(logo_url: Option<&str>) -> UpsertQrStyleRequest {
+        UpsertQrStyleRequest {
+            foreground_color: "#0B3D91".to_string(),
+            background_color: "#F5F5F5".to_string(),
+            rounded_modules: true,
+            quiet_zone: None,
+            logo_url: logo_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_style_settings_keep_deployment_size() {
+        let base = QrGenerationSettings::default();
+        let settings = QrStyle::new("acme".to_string(), request(None)).settings(&base);
+
+        assert_eq!(settings.foreground_color, "#0B3D91");
+        assert_eq!(settings.background_color, "#F5F5F5");
+        assert!(settings.rounded_modules);
+        assert_eq!(settings.quiet_zone, DEFAULT_QUIET_ZONE);
+        assert_eq!(settings.size, base.size);
+        assert_eq!(settings.logo_url, base.logo_url);
+
+        let branded = QrStyle::new("acme".to_string(), request(Some("https://acme.example/logo.png"))).settings(&base);
+        assert_eq!(branded.logo_url.as_deref(), Some("https://acme.example/logo.png"));
+    }
+
+    #[test]
+    fn test_apply_keeps_identity() {
+        let style = QrStyle::new("acme".to_string(), request(None));
+        let updated = style.clone().apply(UpsertQrStyleRequest { quiet_zone: Some(2), ..request(None) });
+
+        assert_eq!(updated.id, style.id);
+        assert_eq!(updated.name, "acme");
+        assert_eq!(updated.created_at, style.created_at);
+        assert_eq!(updated.quiet_zone, 2);
+    }
+}
@@ -1,18 +1,32 @@
  // src/models/scan_analytics.rs
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use async_graphql::SimpleObject;
+use utoipa::ToSchema;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::models::{LandingVariant, ScanExperiment};
+
+// Version written with every new scan event; bump it and add a step to
+// `ScanEventDocument::upgrade` whenever stored events need new defaults
+pub const SCAN_EVENT_SCHEMA_VERSION: i32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(from = "ScanEventDocument")]
 pub struct ScanEvent {
     #[serde(rename = "_id")]
+    #[schema(value_type = String)]
     pub id: ObjectId,
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: i32,
     #[serde(rename = "propertyId")]
     pub property_id: String,
     #[serde(rename = "qrVersion")]
-    pub qr_version: i32,
+    pub qr_version: i32, // Version printed in the scanned code
+    #[serde(rename = "outdatedQr")]
+    pub outdated_qr: bool, // The code had been regenerated since this one was printed
     #[serde(rename = "scannedAt")]
     pub scanned_at: DateTime<Utc>,
     #[serde(rename = "scanSource")]
@@ -27,6 +41,10 @@ pub struct ScanEvent {
     pub device_info: Option<DeviceInfo>,
     #[serde(rename = "sessionId")]
     pub session_id: Option<String>,
+    #[serde(rename = "visitorId")]
+    pub visitor_id: Option<String>, // Session cookie value or IP+UA hash
+    #[serde(rename = "isBot")]
+    pub is_bot: bool, // Crawler or link-preview fetch, excluded from aggregates
     #[serde(rename = "referrer")]
     pub referrer: Option<String>,
     #[serde(rename = "redirectSuccess")]
@@ -35,10 +53,173 @@ pub struct ScanEvent {
     pub redirect_type: RedirectType,
     #[serde(rename = "responseTime")]
     pub response_time: Option<u64>, // Response time in milliseconds
+    pub utm: Option<UtmParameters>, // Campaign tags from the scanned URL
+    #[serde(default)]
+    pub experiment: Option<ScanExperiment>, // Landing page variant the scan was shown
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// A scan event as stored, at any schema version. Fields added after v1 are
+// optional here and filled in by `upgrade`, so ScanEvent itself stays strict.
+//   v1: no schemaVersion, isBot, visitorId or metadata
+//   v2: adds schemaVersion; isBot and visitorId derived from the request data
+// utm and experiment are optional and outdatedQr defaults to false at every version,
+// so adding them needed no upgrade step.
+#[derive(Deserialize)]
+struct ScanEventDocument {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    #[serde(rename = "schemaVersion", default = "legacy_schema_version")]
+    schema_version: i32,
+    #[serde(rename = "propertyId")]
+    property_id: String,
+    #[serde(rename = "qrVersion", default = "legacy_qr_version")]
+    qr_version: i32,
+    #[serde(rename = "outdatedQr", default)]
+    outdated_qr: bool,
+    #[serde(rename = "scannedAt")]
+    scanned_at: DateTime<Utc>,
+    #[serde(rename = "scanSource", default = "legacy_scan_source")]
+    scan_source: ScanSource,
+    #[serde(rename = "userAgent")]
+    user_agent: Option<String>,
+    #[serde(rename = "ipAddress")]
+    ip_address: Option<String>,
+    geolocation: Option<GeoLocation>,
+    #[serde(rename = "deviceInfo")]
+    device_info: Option<DeviceInfo>,
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+    #[serde(rename = "visitorId")]
+    visitor_id: Option<String>,
+    #[serde(rename = "isBot")]
+    is_bot: Option<bool>,
+    referrer: Option<String>,
+    #[serde(rename = "redirectSuccess", default = "legacy_redirect_success")]
+    redirect_success: bool,
+    #[serde(rename = "redirectType")]
+    redirect_type: RedirectType,
+    #[serde(rename = "responseTime")]
+    response_time: Option<u64>,
+    utm: Option<UtmParameters>,
+    #[serde(default)]
+    experiment: Option<ScanExperiment>,
+    #[serde(default)]
+    metadata: HashMap<String, serde_json::Value>,
+}
+
+// Documents written before schemaVersion existed
+fn legacy_schema_version() -> i32 {
+    1
+}
+
+fn legacy_qr_version() -> i32 {
+    1
+}
+
+fn legacy_scan_source() -> ScanSource {
+    ScanSource::Unknown
+}
+
+fn legacy_redirect_success() -> bool {
+    true
+}
+
+impl ScanEventDocument {
+    /// Apply each upgrade step from the stored version to the current one
+    fn upgrade(mut self) -> Self {
+        if self.schema_version < 2 {
+            // v1 events were never classified and had no visitor identifier
+            if self.is_bot.is_none() {
+                self.is_bot = Some(self.user_agent.as_deref().is_some_and(ScanEvent::is_bot_user_agent));
+            }
+            if self.visitor_id.is_none() {
+                self.visitor_id = Some(ScanEvent::visitor_hash(self.ip_address.as_deref(), self.user_agent.as_deref()));
+            }
+            self.schema_version = 2;
+        }
+        self
+    }
+}
+
+impl From<ScanEventDocument> for ScanEvent {
+    fn from(document: ScanEventDocument) -> Self {
+        let document = document.upgrade();
+        Self {
+            id: document.id,
+            schema_version: document.schema_version,
+            property_id: document.property_id,
+            qr_version: document.qr_version,
+            outdated_qr: document.outdated_qr,
+            scanned_at: document.scanned_at,
+            scan_source: document.scan_source,
+            user_agent: document.user_agent,
+            ip_address: document.ip_address,
+            geolocation: document.geolocation,
+            device_info: document.device_info,
+            session_id: document.session_id,
+            visitor_id: document.visitor_id,
+            is_bot: document.is_bot.unwrap_or(false),
+            referrer: document.referrer,
+            redirect_success: document.redirect_success,
+            redirect_type: document.redirect_type,
+            response_time: document.response_time,
+            utm: document.utm,
+            experiment: document.experiment,
+            metadata: document.metadata,
+        }
+    }
+}
+
+// User agent fragments that identify crawlers and link-preview fetchers
+// User agent marker of the post-deploy smoke test; its scans are kept out of analytics like any bot's
+pub const SMOKE_TEST_USER_AGENT_MARKER: &str = "property-qr-smoke-test";
+
+// A bare "bot" substring would also catch phones like Cubot's, so crawlers are named here and
+// other bots are caught by how they sign themselves (see `has_bot_product`)
+const BOT_USER_AGENT_MARKERS: &[&str] = &[
+    SMOKE_TEST_USER_AGENT_MARKER,
+    "googlebot",
+    "bingbot",
+    "yandexbot",
+    "duckduckbot",
+    "applebot",
+    "twitterbot",
+    "slackbot",
+    "discordbot",
+    "linkedinbot",
+    "ahrefsbot",
+    "semrushbot",
+    "petalbot",
+    "crawler",
+    "spider",
+    "slurp",
+    "facebookexternalhit",
+    "whatsapp",
+    "telegram",
+    "skypeuripreview",
+    "embedly",
+    "pinterest",
+    "headlesschrome",
+    "lighthouse",
+    "curl/",
+    "wget/",
+    "python-requests",
+    "go-http-client",
+];
+
+// A product token like "ExampleBot/1.2", or a standalone "bot" word. Phone models that end in
+// "bot" appear without a version, e.g. "CUBOT_X30" or "Cubot Note 20".
+fn has_bot_product(user_agent_lower: &str) -> bool {
+    user_agent_lower
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '/'))
+        .any(|word| match word.split_once('/') {
+            Some((product, _)) => product.ends_with("bot"),
+            None => word == "bot",
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ScanSource {
     QrCode,         // Direct QR code scan
@@ -46,21 +227,26 @@ pub enum ScanSource {
     ShareLink,      // Shared link
     SearchEngine,   // From search engine
     SocialMedia,    // From social media
+    EmailSignature, // Link in an agent's email signature
     Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RedirectType {
     DualRedirect,   // Both DAO-Bitat and blockchain explorer
     DaobitarOnly,   // Only DAO-Bitat property page
     BlockchainOnly, // Only blockchain explorer
+    CustomRedirect, // Owner's custom redirect URL
+    DocumentVerification, // A property document's verification page
+    ShareOffer,     // Share-purchase flow for a co-owned property's offer
     Failed,         // Redirect failed
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GeoLocation {
-    pub country: Option<String>,
+    pub country: Option<String>, // ISO 3166-1 alpha-2 code
+
     pub region: Option<String>,
     pub city: Option<String>,
     pub latitude: Option<f64>,
@@ -68,7 +254,7 @@ pub struct GeoLocation {
     pub timezone: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DeviceInfo {
     #[serde(rename = "deviceType")]
     pub device_type: DeviceType,
@@ -82,7 +268,7 @@ pub struct DeviceInfo {
     pub is_mobile: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DeviceType {
     Mobile,
@@ -92,9 +278,11 @@ pub enum DeviceType {
 }
 
 // Aggregated analytics data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct PropertyScanAnalytics {
     #[serde(rename = "_id")]
+    #[schema(value_type = String)]
+    #[graphql(skip)]
     pub id: ObjectId,
     #[serde(rename = "propertyId")]
     pub property_id: String,
@@ -102,6 +290,10 @@ pub struct PropertyScanAnalytics {
     pub total_scans: i64,
     #[serde(rename = "uniqueScans")]
     pub unique_scans: i64, // Based on IP/session
+    #[serde(rename = "newVisitorSessions", default)]
+    pub new_visitor_sessions: i64, // Visits from first-time visitors
+    #[serde(rename = "returningVisitorSessions", default)]
+    pub returning_visitor_sessions: i64, // Visits from visitors seen in an earlier session
     #[serde(rename = "lastScanned")]
     pub last_scanned: Option<DateTime<Utc>>,
     #[serde(rename = "firstScanned")]
@@ -122,18 +314,154 @@ pub struct PropertyScanAnalytics {
     pub average_response_time: Option<f64>,
     #[serde(rename = "successRate")]
     pub success_rate: f64, // Percentage of successful redirects
+    #[serde(rename = "clickThrough", default)]
+    pub click_through: ClickThroughRates, // Recent dual page button clicks, refreshed when analytics are read
     #[serde(rename = "lastUpdated")]
     pub last_updated: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// UTM tags a scan arrived with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UtmParameters {
+    pub source: Option<String>,
+    pub medium: Option<String>,
+    pub campaign: Option<String>,
+}
+
+// Scans for one campaign/source/medium combination
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignStats {
+    pub campaign: Option<String>, // All three are None for untagged scans
+    pub source: Option<String>,
+    pub medium: Option<String>,
+    pub scans: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+    pub percentage: f64,
+}
+
+// Scans of one printed QR version
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QrVersionStats {
+    #[serde(rename = "qrVersion")]
+    pub qr_version: i32,
+    pub scans: i64,
+    #[serde(rename = "outdatedScans")]
+    pub outdated_scans: i64, // Scanned after the code was regenerated; that signage needs reprinting
+    #[serde(rename = "lastScannedAt")]
+    pub last_scanned_at: Option<DateTime<Utc>>,
+}
+
+// Human scans whose origin falls in one grid cell of a property's heatmap
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HeatmapCell {
+    pub lat: f64, // Cell coordinates, rounded to the heatmap's precision
+    pub lng: f64,
+    pub scans: i64,
+}
+
+// Where a property's scans came from, bucketed coarsely enough that no one scanner is located
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScanHeatmap {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub days: i64,
+    pub precision: u32, // Decimal places the coordinates were rounded to
+    #[serde(rename = "maxScans")]
+    pub max_scans: i64, // Busiest cell, for scaling intensities
+    pub cells: Vec<HeatmapCell>,
+}
+
+// When scans happen: counts by hour of day and day of week, in the requested timezone
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HourlyScanDistribution {
+    #[serde(rename = "propertyId")]
+    pub property_id: Option<String>, // None for the system-wide distribution
+    pub days: i64,
+    pub timezone: String,
+    #[serde(rename = "byHour")]
+    pub by_hour: Vec<i64>, // 24 entries, midnight first
+    #[serde(rename = "byDayOfWeek")]
+    pub by_day_of_week: Vec<i64>, // 7 entries, Monday first
+    pub grid: Vec<Vec<i64>>, // by_day_of_week x by_hour, for a punch-card chart
+    #[serde(rename = "peakHour")]
+    pub peak_hour: Option<u32>, // None when there were no scans
+}
+
+// Side-by-side totals for one tag, matched against a scan's UTM campaign, source or medium
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagComparison {
+    pub tag: String,
+    pub scans: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+    #[serde(rename = "convertedVisitors")]
+    pub converted_visitors: i64,
+    #[serde(rename = "conversionRate")]
+    pub conversion_rate: f64, // Percentage of unique visitors who converted
+}
+
+// Side-by-side totals for one property, for benchmarking listings against each other
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PropertyComparison {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub scans: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+    #[serde(rename = "deviceBreakdown")]
+    pub device_breakdown: DeviceBreakdown,
+    #[serde(rename = "dailyScans")]
+    pub daily_scans: Vec<DailyScanCount>, // Every day of the window, oldest first
+}
+
+// A comparison holds either tags or properties, depending on what was asked for
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnalyticsComparison {
+    pub from: NaiveDate,
+    pub to: NaiveDate, // Inclusive
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<TagComparison>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<PropertyComparison>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct CountryStats {
     pub country: String,
     pub count: i64,
     pub percentage: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Human scans from one neighborhood, as far as geolocation resolves it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AreaStats {
+    pub country: String, // ISO 3166-1 alpha-2 code
+    pub region: Option<String>,
+    pub city: String,
+    pub scans: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+}
+
+// Scans per neighborhood across all properties, for the public stats page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicAreaStats {
+    pub days: i64,
+    pub areas: Vec<AreaStats>, // Most scanned first
+    pub privacy: Option<PrivacyNotice>, // Set when counts are noised; they're then approximate
+}
+
+// How shared counts were protected: Laplace noise at `epsilon` per count, and buckets
+// with fewer than `minBucketSize` (noisy) unique visitors left out
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+pub struct PrivacyNotice {
+    pub epsilon: f64,
+    #[serde(rename = "minBucketSize")]
+    pub min_bucket_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct DeviceBreakdown {
     pub mobile: i64,
     pub desktop: i64,
@@ -141,16 +469,18 @@ pub struct DeviceBreakdown {
     pub unknown: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct DailyScanCount {
     pub date: String, // YYYY-MM-DD format
     pub count: i64,
 }
 
 // System-wide analytics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct SystemAnalytics {
     #[serde(rename = "_id")]
+    #[schema(value_type = String)]
+    #[graphql(skip)]
     pub id: ObjectId,
     #[serde(rename = "totalProperties")]
     pub total_properties: i64,
@@ -174,12 +504,260 @@ pub struct SystemAnalytics {
     pub last_updated: DateTime<Utc>,
 }
 
+// Visitor action on the landing page that signals buying/renting intent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "conversionType")]
+    pub conversion_type: ConversionType,
+    #[serde(rename = "visitorId")]
+    pub visitor_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionType {
+    SmsShare, // "Text me this listing"
+}
+
+// Step a visitor reached on the dual landing page, reported by the page's beacon;
+// stored once per scan and stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunnelEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "scanId")]
+    pub scan_id: ObjectId,
+    pub stage: FunnelStage,
+    #[serde(rename = "visitorId")]
+    pub visitor_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FunnelStage {
+    PageView,
+    AutoRedirect, // The countdown ran out and sent the visitor on
+    ManualClickProperty,
+    ManualClickBlockchain,
+    ManualClickPayment, // "Pay with crypto" on a crypto-accepted listing
+}
+
+impl FunnelStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FunnelStage::PageView => "page_view",
+            FunnelStage::AutoRedirect => "auto_redirect",
+            FunnelStage::ManualClickProperty => "manual_click_property",
+            FunnelStage::ManualClickBlockchain => "manual_click_blockchain",
+            FunnelStage::ManualClickPayment => "manual_click_payment",
+        }
+    }
+}
+
+// Button a dual page visitor followed, recorded by the tracked /scan/{property_id}/go/{outcome} redirects
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanOutcome {
+    Property,
+    Blockchain,
+    CryptoPayment, // DAO-Bitat's payment flow, for listings that accept crypto
+}
+
+impl ScanOutcome {
+    pub fn funnel_stage(&self) -> FunnelStage {
+        match self {
+            ScanOutcome::Property => FunnelStage::ManualClickProperty,
+            ScanOutcome::Blockchain => FunnelStage::ManualClickBlockchain,
+            ScanOutcome::CryptoPayment => FunnelStage::ManualClickPayment,
+        }
+    }
+}
+
+// Dual page button clicks as a share of page views
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, SimpleObject)]
+pub struct ClickThroughRates {
+    #[serde(rename = "propertyClicks")]
+    pub property_clicks: i64,
+    #[serde(rename = "blockchainClicks")]
+    pub blockchain_clicks: i64,
+    #[serde(rename = "propertyRate")]
+    pub property_rate: f64, // Percentage of page views
+    #[serde(rename = "blockchainRate")]
+    pub blockchain_rate: f64,
+    #[serde(rename = "paymentClicks", default)]
+    pub payment_clicks: i64,
+    #[serde(rename = "paymentRate", default)]
+    pub payment_rate: f64,
+}
+
+impl From<&FunnelStats> for ClickThroughRates {
+    fn from(stats: &FunnelStats) -> Self {
+        // Clicks are recorded server-side, page views by a beacon that script blockers can stop,
+        // so a rate can't be allowed past 100
+        let rate = |clicks: i64| {
+            if stats.page_views == 0 {
+                0.0
+            } else {
+                (clicks as f64 / stats.page_views as f64 * 100.0).min(100.0)
+            }
+        };
+
+        Self {
+            property_clicks: stats.manual_click_property,
+            blockchain_clicks: stats.manual_click_blockchain,
+            property_rate: rate(stats.manual_click_property),
+            blockchain_rate: rate(stats.manual_click_blockchain),
+            payment_clicks: stats.manual_click_payment,
+            payment_rate: rate(stats.manual_click_payment),
+        }
+    }
+}
+
+// How a property's landing page visitors left it: by the countdown or by which button
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct FunnelStats {
+    #[serde(rename = "pageViews")]
+    pub page_views: i64,
+    #[serde(rename = "autoRedirects")]
+    pub auto_redirects: i64,
+    #[serde(rename = "manualClickProperty")]
+    pub manual_click_property: i64,
+    #[serde(rename = "manualClickBlockchain")]
+    pub manual_click_blockchain: i64,
+    #[serde(rename = "manualClickPayment", default)]
+    pub manual_click_payment: i64,
+}
+
+// Outcome of a retention pass; counts are what would be removed when dry_run is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionReport {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    #[serde(rename = "rawEventCutoff")]
+    pub raw_event_cutoff: DateTime<Utc>,
+    #[serde(rename = "aggregateCutoff")]
+    pub aggregate_cutoff: DateTime<Utc>,
+    #[serde(rename = "scanEvents")]
+    pub scan_events: u64,
+    pub conversions: u64,
+    #[serde(rename = "funnelEvents")]
+    pub funnel_events: u64,
+    #[serde(rename = "dailyCounters")]
+    pub daily_counters: u64,
+    #[serde(rename = "propertyAnalytics")]
+    pub property_analytics: u64,
+    #[serde(rename = "propertySnapshots")]
+    pub property_snapshots: u64,
+    #[serde(rename = "propertyDailyStats")]
+    pub property_daily_stats: u64, // Monthly rollups are small enough to keep
+}
+
+// Per-day scan counter, incremented atomically as scans arrive
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyScanCounter {
+    #[serde(rename = "_id")]
+    pub date: String, // YYYY-MM-DD format
+    #[serde(rename = "totalScans")]
+    pub total_scans: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// One property's human scans over one day (property_daily_stats) or month (property_monthly_stats),
+// kept by the analytics worker so long-range queries don't aggregate raw events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyRollup {
+    #[serde(rename = "_id")]
+    pub id: String, // "{propertyId}:{period}"
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub period: String, // YYYY-MM-DD for daily rollups, YYYY-MM for monthly ones
+    pub scans: i64,
+    #[serde(rename = "successfulScans")]
+    pub successful_scans: i64,
+    #[serde(rename = "uniqueVisitors", default)]
+    pub unique_visitors: i64, // Filled in by reconciliation; the worker can't tell repeat visitors apart
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PropertyRollup {
+    pub fn id_for(property_id: &str, period: &str) -> String {
+        format!("{}:{}", property_id, period)
+    }
+}
+
+// Human scans of one property in one calendar month
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MonthlyScanCount {
+    pub month: String, // YYYY-MM
+    pub count: i64,
+    #[serde(rename = "uniqueVisitors")]
+    pub unique_visitors: i64,
+}
+
+// Scans whose aggregate update failed, kept for the retry job instead of being lost.
+// One per property, so a retry never re-applies another property's scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedAnalyticsUpdate {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "scanEvents")]
+    pub scan_events: Vec<ScanEvent>,
+    pub persisted: bool, // False for deferred scans whose raw event write failed too
+    pub error: String,   // From the latest attempt
+    pub attempts: i32,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "nextAttemptAt")]
+    pub next_attempt_at: Option<DateTime<Utc>>, // None once retries are exhausted
+}
+
+// A property's cumulative analytics as they stood at the end of a day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PropertyAnalyticsSnapshot {
+    #[serde(rename = "_id")]
+    pub id: String, // "<propertyId>:<date>", so re-snapshotting a day overwrites it
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub analytics: PropertyScanAnalytics,
+    #[serde(rename = "capturedAt")]
+    pub captured_at: DateTime<Utc>,
+}
+
+impl PropertyAnalyticsSnapshot {
+    pub fn new(analytics: PropertyScanAnalytics, date: NaiveDate) -> Self {
+        let date = date.format("%Y-%m-%d").to_string();
+        Self {
+            id: format!("{}:{}", analytics.property_id, date),
+            property_id: analytics.property_id.clone(),
+            date,
+            analytics,
+            captured_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct PropertyPerformance {
     #[serde(rename = "propertyId")]
     pub property_id: String,
     #[serde(rename = "propertyName")]
     pub property_name: String,
+    pub location: Option<String>,
     #[serde(rename = "totalScans")]
     pub total_scans: i64,
     #[serde(rename = "uniqueScans")]
@@ -188,7 +766,7 @@ pub struct PropertyPerformance {
     pub success_rate: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct QrGenerationStats {
     #[serde(rename = "totalGenerated")]
     pub total_generated: i64,
@@ -205,23 +783,25 @@ pub struct QrGenerationStats {
 }
 
 // API Response DTOs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ScanAnalyticsResponse {
     #[serde(rename = "propertyId")]
     pub property_id: String,
     pub analytics: PropertyScanAnalytics,
+    #[serde(rename = "returningVisitorRate")]
+    pub returning_visitor_rate: f64, // Percentage of visits from returning visitors
     #[serde(rename = "recentScans")]
     pub recent_scans: Vec<ScanEvent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct SystemAnalyticsResponse {
     pub system: SystemAnalytics,
     #[serde(rename = "periodComparison")]
     pub period_comparison: Option<PeriodComparison>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct PeriodComparison {
     #[serde(rename = "currentPeriod")]
     pub current_period: PeriodStats,
@@ -231,7 +811,7 @@ pub struct PeriodComparison {
     pub percentage_change: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct PeriodStats {
     #[serde(rename = "totalScans")]
     pub total_scans: i64,
@@ -261,10 +841,57 @@ pub struct ScanRedirectData {
     pub is_verified: bool,
     #[serde(rename = "cryptoAccepted")]
     pub crypto_accepted: bool,
+    #[serde(rename = "baseName", default)]
+    pub base_name: Option<String>, // Confirmed Base name, resolved for display
+    #[serde(rename = "onchainVerifiedAt", default)]
+    pub onchain_verified_at: Option<DateTime<Utc>>, // When the chain last confirmed the recorded owner
+    #[serde(skip)]
+    pub variant: Option<LandingVariant>, // Experiment text the page shows in place of the defaults
     #[serde(rename = "scanId")]
     pub scan_id: ObjectId, // For tracking this specific scan
 }
 
+impl TagComparison {
+    /// Totals for a tag, with the conversion rate worked out from them
+    pub fn new(tag: String, scans: i64, unique_visitors: i64, converted_visitors: i64) -> Self {
+        let conversion_rate = if unique_visitors > 0 {
+            converted_visitors as f64 / unique_visitors as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            tag,
+            scans,
+            unique_visitors,
+            converted_visitors,
+            conversion_rate,
+        }
+    }
+}
+
+impl UtmParameters {
+    /// Longest tag value kept; anything longer is cut
+    pub const MAX_VALUE_LENGTH: usize = 100;
+
+    /// Build from raw query values, or None when the URL carried no tags
+    pub fn from_query(source: Option<&str>, medium: Option<&str>, campaign: Option<&str>) -> Option<Self> {
+        let clean = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.chars().take(Self::MAX_VALUE_LENGTH).collect::<String>())
+        };
+
+        let utm = Self {
+            source: clean(source),
+            medium: clean(medium),
+            campaign: clean(campaign),
+        };
+        (utm.source.is_some() || utm.medium.is_some() || utm.campaign.is_some()).then_some(utm)
+    }
+}
+
 impl ScanEvent {
     /// Create a new scan event
     pub fn new(
@@ -275,8 +902,10 @@ impl ScanEvent {
     ) -> Self {
         Self {
             id: ObjectId::new(),
+            schema_version: SCAN_EVENT_SCHEMA_VERSION,
             property_id,
             qr_version,
+            outdated_qr: false,
             scanned_at: Utc::now(),
             scan_source,
             user_agent: None,
@@ -284,10 +913,14 @@ impl ScanEvent {
             geolocation: None,
             device_info: None,
             session_id: None,
+            visitor_id: None,
+            is_bot: false,
             referrer: None,
             redirect_success: true,
             redirect_type,
             response_time: None,
+            utm: None,
+            experiment: None,
             metadata: HashMap::new(),
         }
     }
@@ -316,9 +949,57 @@ impl ScanEvent {
         self.ip_address = ip_address;
         self.session_id = session_id;
         self.referrer = referrer;
+        self.is_bot = self.user_agent
+            .as_deref()
+            .map(Self::is_bot_user_agent)
+            .unwrap_or(false);
         self
     }
 
+    /// Check whether a user agent belongs to a crawler or link-preview bot
+    pub fn is_bot_user_agent(user_agent: &str) -> bool {
+        let user_agent_lower = user_agent.to_lowercase();
+        BOT_USER_AGENT_MARKERS
+            .iter()
+            .any(|marker| user_agent_lower.contains(marker))
+            || has_bot_product(&user_agent_lower)
+    }
+
+    /// Flag the scan when the code has been regenerated since the scanned one was printed
+    pub fn with_current_qr_version(mut self, current_qr_version: i32) -> Self {
+        self.outdated_qr = self.qr_version < current_qr_version;
+        self
+    }
+
+    /// Set the UTM campaign tags
+    pub fn with_utm(mut self, utm: UtmParameters) -> Self {
+        self.utm = Some(utm);
+        self
+    }
+
+    /// Set the landing page variant the scan was shown
+    pub fn with_experiment(mut self, experiment: ScanExperiment) -> Self {
+        self.experiment = Some(experiment);
+        self
+    }
+
+    /// Set the unique visitor identifier
+    pub fn with_visitor_id(mut self, visitor_id: String) -> Self {
+        self.visitor_id = Some(visitor_id);
+        self
+    }
+
+    /// Build an anonymous visitor identifier from IP address and user agent
+    pub fn visitor_hash(ip_address: Option<&str>, user_agent: Option<&str>) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(ip_address.unwrap_or(""));
+        hasher.update("|");
+        hasher.update(user_agent.unwrap_or(""));
+        format!("{:x}", hasher.finalize())[..32].to_string()
+    }
+
     /// Set response time
     pub fn with_response_time(mut self, response_time: u64) -> Self {
         self.response_time = Some(response_time);
@@ -342,62 +1023,55 @@ impl ScanEvent {
 impl DeviceInfo {
     /// Parse device info from user agent string
     pub fn from_user_agent(user_agent: &str) -> Self {
-        // Simple user agent parsing - in production, use a proper library
-        let user_agent_lower = user_agent.to_lowercase();
-        
-        let is_mobile = user_agent_lower.contains("mobile") 
-            || user_agent_lower.contains("android")
-            || user_agent_lower.contains("iphone");
-            
-        let is_tablet = user_agent_lower.contains("tablet") 
-            || user_agent_lower.contains("ipad");
-
-        let device_type = if is_tablet {
-            DeviceType::Tablet
-        } else if is_mobile {
-            DeviceType::Mobile
-        } else {
-            DeviceType::Desktop
+        let parsed = woothee::parser::Parser::new().parse(user_agent);
+        let known = |value: &str| (!value.is_empty() && value != woothee::woothee::VALUE_UNKNOWN)
+            .then(|| value.to_string());
+
+        let (category, os, browser, browser_version) = match &parsed {
+            Some(result) => (result.category, result.os, known(result.name), known(result.version)),
+            None => (woothee::woothee::VALUE_UNKNOWN, woothee::woothee::VALUE_UNKNOWN, None, None),
         };
 
-        let platform = if user_agent_lower.contains("windows") {
-            Some("Windows".to_string())
-        } else if user_agent_lower.contains("mac") {
-            Some("macOS".to_string())
-        } else if user_agent_lower.contains("linux") {
-            Some("Linux".to_string())
-        } else if user_agent_lower.contains("android") {
-            Some("Android".to_string())
-        } else if user_agent_lower.contains("ios") || user_agent_lower.contains("iphone") {
-            Some("iOS".to_string())
-        } else {
-            None
+        // Woothee files tablets under smartphone, so split them out by OS and UA
+        let is_tablet = os == "iPad"
+            || (os == "Android" && !user_agent.contains("Mobile"))
+            || user_agent.contains("Tablet");
+
+        let device_type = match category {
+            _ if is_tablet => DeviceType::Tablet,
+            "smartphone" | "mobilephone" => DeviceType::Mobile,
+            "pc" => DeviceType::Desktop,
+            _ => DeviceType::Unknown,
         };
 
-        let browser = if user_agent_lower.contains("chrome") {
-            Some("Chrome".to_string())
-        } else if user_agent_lower.contains("firefox") {
-            Some("Firefox".to_string())
-        } else if user_agent_lower.contains("safari") && !user_agent_lower.contains("chrome") {
-            Some("Safari".to_string())
-        } else if user_agent_lower.contains("edge") {
-            Some("Edge".to_string())
-        } else {
-            None
+        let platform = match os {
+            "iPhone" | "iPad" | "iPod" => Some("iOS".to_string()),
+            "Mac OSX" => Some("macOS".to_string()),
+            os if os.starts_with("Windows") => Some("Windows".to_string()),
+            os => known(os),
         };
 
         Self {
+            is_mobile: matches!(device_type, DeviceType::Mobile | DeviceType::Tablet),
             device_type,
             platform,
             browser,
-            browser_version: None, // Would need more sophisticated parsing
+            browser_version,
             screen_size: None,
-            is_mobile,
         }
     }
 }
 
 impl PropertyScanAnalytics {
+    /// Share of visits made by returning visitors, as a percentage
+    pub fn returning_visitor_rate(&self) -> f64 {
+        let sessions = self.new_visitor_sessions + self.returning_visitor_sessions;
+        if sessions == 0 {
+            return 0.0;
+        }
+        self.returning_visitor_sessions as f64 / sessions as f64 * 100.0
+    }
+
     /// Create new analytics entry for a property
     pub fn new(property_id: String) -> Self {
         Self {
@@ -405,6 +1079,8 @@ impl PropertyScanAnalytics {
             property_id,
             total_scans: 0,
             unique_scans: 0,
+            new_visitor_sessions: 0,
+            returning_visitor_sessions: 0,
             last_scanned: None,
             first_scanned: None,
             scans_today: 0,
@@ -420,13 +1096,20 @@ impl PropertyScanAnalytics {
             scan_trends: Vec::new(),
             average_response_time: None,
             success_rate: 100.0,
+            click_through: ClickThroughRates::default(),
             last_updated: Utc::now(),
         }
     }
 
-    /// Update analytics with new scan event
-    pub fn update_with_scan(&mut self, scan_event: &ScanEvent) {
+    /// Update analytics with new scan event; `starts_session` is false for repeat scans within a visit
+    pub fn update_with_scan(&mut self, scan_event: &ScanEvent, is_new_visitor: bool, starts_session: bool) {
         self.total_scans += 1;
+        if is_new_visitor {
+            self.unique_scans += 1;
+            self.new_visitor_sessions += 1;
+        } else if starts_session {
+            self.returning_visitor_sessions += 1;
+        }
         self.last_scanned = Some(scan_event.scanned_at);
         
         if self.first_scanned.is_none() {
@@ -0,0 +1,105 @@
+// src/models/scan_cap.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Daily scan limit an owner sets on a property, e.g. for a limited-release listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCap {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "maxScansPerDay")]
+    pub max_scans_per_day: i64,
+    pub enabled: bool,
+    #[serde(rename = "pausedAt")]
+    pub paused_at: Option<DateTime<Utc>>, // When the cap was last hit; the pause lasts until midnight UTC
+    #[serde(rename = "resumedAt")]
+    pub resumed_at: Option<DateTime<Utc>>, // Owner lifted the pause by hand; holds for the rest of that day
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertScanCapRequest {
+    #[serde(rename = "maxScansPerDay")]
+    pub max_scans_per_day: i64,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScanCapResponse {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "maxScansPerDay")]
+    pub max_scans_per_day: i64,
+    pub enabled: bool,
+    pub paused: bool, // Scans currently get the waitlist page
+    #[serde(rename = "scansToday")]
+    pub scans_today: i64,
+    #[serde(rename = "pausedAt")]
+    pub paused_at: Option<DateTime<Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ScanCap {
+    /// Create an enabled cap for a property
+    pub fn new(property_id: String, max_scans_per_day: i64) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            property_id,
+            max_scans_per_day,
+            enabled: true,
+            paused_at: None,
+            resumed_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Apply an upsert request on top of the current values
+    pub fn apply(mut self, request: UpsertScanCapRequest) -> Self {
+        self.max_scans_per_day = request.max_scans_per_day;
+        if let Some(enabled) = request.enabled {
+            self.enabled = enabled;
+        }
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Whether scans are being turned away right now
+    pub fn is_paused(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.paused_at.is_some_and(|paused_at| same_day(paused_at, now))
+    }
+
+    /// Whether the owner lifted today's pause, so the cap shouldn't trip again until tomorrow
+    pub fn is_resumed(&self, now: DateTime<Utc>) -> bool {
+        self.resumed_at.is_some_and(|resumed_at| same_day(resumed_at, now))
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self, scans_today: i64) -> ScanCapResponse {
+        ScanCapResponse {
+            property_id: self.property_id.clone(),
+            max_scans_per_day: self.max_scans_per_day,
+            enabled: self.enabled,
+            paused: self.is_paused(Utc::now()),
+            scans_today,
+            paused_at: self.paused_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+// Caps count scans per UTC day, like the scansToday counter
+fn same_day(a: DateTime<Utc>, b: DateTime<Utc>) -> bool {
+    a.date_naive() == b.date_naive()
+}
@@ -0,0 +1,177 @@
+// src/models/share_offer.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Longest label an offer can be given, e.g. "Diaspora investors, March"
+pub const MAX_SHARE_OFFER_LABEL_CHARS: usize = 80;
+
+// QR code for one offer of a co-owned property's shares, opening DAO-Bitat's share-purchase
+// flow. A property can run several offers at once, each with its own scan counts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareOfferQrCode {
+    #[serde(rename = "_id")]
+    pub id: ObjectId, // The offer ID
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub shares: i32, // Offered; scans are offered no more than the property still has available
+    pub label: Option<String>,
+    #[serde(rename = "scanUrl")]
+    pub scan_url: String,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "imageKey")]
+    pub image_key: String,
+    pub scans: i64,
+    #[serde(rename = "lastScannedAt")]
+    pub last_scanned_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    #[serde(rename = "generatedBy")]
+    pub generated_by: Option<ObjectId>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateShareOfferQrRequest {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub shares: Option<i32>, // Defaults to every available share
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShareOfferQrResponse {
+    #[serde(rename = "offerId")]
+    pub offer_id: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub shares: i32,
+    pub label: Option<String>,
+    #[serde(rename = "qrCodeUrl")]
+    pub qr_code_url: String,
+    #[serde(rename = "scanUrl")]
+    pub scan_url: String,
+    #[serde(rename = "purchaseUrl")]
+    pub purchase_url: String,
+    pub active: bool,
+    pub scans: i64,
+    #[serde(rename = "lastScannedAt")]
+    pub last_scanned_at: Option<DateTime<Utc>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShareOfferQrCode {
+    /// Create an offer whose code has been uploaded
+    pub fn new(
+        id: ObjectId,
+        property_id: String,
+        shares: i32,
+        label: Option<String>,
+        base_url: &str,
+        qr_code_url: String,
+        generated_by: Option<ObjectId>,
+    ) -> Self {
+        Self {
+            scan_url: Self::scan_url_for(base_url, &property_id, &id),
+            image_key: Self::image_key_for(&property_id, &id),
+            id,
+            property_id,
+            shares,
+            label,
+            qr_code_url,
+            scans: 0,
+            last_scanned_at: None,
+            active: true,
+            generated_by,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Page an offer's code resolves to
+    pub fn scan_url_for(base_url: &str, property_id: &str, offer_id: &ObjectId) -> String {
+        format!(
+            "{}/scan/{}/shares/{}",
+            base_url.trim_end_matches('/'),
+            urlencoding::encode(property_id),
+            offer_id.to_hex()
+        )
+    }
+
+    /// Key an offer's image is stored at; kept apart from listing codes' images, which
+    /// storage reconciliation sweeps for orphans
+    pub fn image_key_for(property_id: &str, offer_id: &ObjectId) -> String {
+        format!("share-offer-qr/{}/{}.png", property_id, offer_id.to_hex())
+    }
+
+    /// Shares a scan is offered, given how many the property has left; 0 once they're gone
+    pub fn shares_on_offer(&self, available_shares: i32) -> i32 {
+        self.shares.min(available_shares).max(0)
+    }
+
+    /// DAO-Bitat's share-purchase flow for this offer
+    pub fn purchase_url(&self, daobitar_base_url: &str, shares: i32) -> String {
+        format!(
+            "{}/property/{}/shares?offer={}&shares={}",
+            daobitar_base_url.trim_end_matches('/'),
+            urlencoding::encode(&self.property_id),
+            self.id.to_hex(),
+            shares
+        )
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self, daobitar_base_url: &str) -> ShareOfferQrResponse {
+        ShareOfferQrResponse {
+            offer_id: self.id.to_hex(),
+            property_id: self.property_id.clone(),
+            shares: self.shares,
+            label: self.label.clone(),
+            qr_code_url: self.qr_code_url.clone(),
+            scan_url: self.scan_url.clone(),
+            purchase_url: self.purchase_url(daobitar_base_url, self.shares),
+            active: self.active,
+            scans: self.scans,
+            last_scanned_at: self.last_scanned_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_offer_urls() {
+        let offer_id = ObjectId::parse_str("65f0c0ffee0000000000abcd").unwrap();
+        let offer = ShareOfferQrCode::new(
+            offer_id,
+            "507f1f77bcf86cd799439011".to_string(),
+            25,
+            None,
+            "https://qr-service.daobitat.xyz/",
+            "https://cdn.daobitat.xyz/share-offer-qr/offer.png".to_string(),
+            None,
+        );
+
+        assert_eq!(
+            offer.scan_url,
+            "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011/shares/65f0c0ffee0000000000abcd"
+        );
+        assert_eq!(offer.image_key, "share-offer-qr/507f1f77bcf86cd799439011/65f0c0ffee0000000000abcd.png");
+        assert_eq!(
+            offer.purchase_url("https://daobitat.xyz", 10),
+            "https://daobitat.xyz/property/507f1f77bcf86cd799439011/shares?offer=65f0c0ffee0000000000abcd&shares=10"
+        );
+
+        // Never more than the property has left
+        assert_eq!(offer.shares_on_offer(100), 25);
+        assert_eq!(offer.shares_on_offer(10), 10);
+        assert_eq!(offer.shares_on_offer(0), 0);
+    }
+}
@@ -0,0 +1,123 @@
+// src/models/short_link.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Short marketing link that resolves to a property page (email, SMS campaigns)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortLink {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub code: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub campaign: Option<String>, // Sent as utm_campaign
+    pub channel: Option<String>,  // "email", "sms", ... sent as utm_source
+    #[serde(rename = "targetUrl")]
+    pub target_url: Option<String>, // Overrides the property page
+    pub clicks: i64,
+    pub active: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateShortLinkRequest {
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub code: Option<String>, // Custom vanity code, generated when absent
+    pub campaign: Option<String>,
+    pub channel: Option<String>,
+    #[serde(rename = "targetUrl")]
+    pub target_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateShortLinkRequest {
+    pub campaign: Option<String>,
+    pub channel: Option<String>,
+    #[serde(rename = "targetUrl")]
+    pub target_url: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShortLinkResponse {
+    pub id: String,
+    pub code: String,
+    #[serde(rename = "shortUrl")]
+    pub short_url: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub campaign: Option<String>,
+    pub channel: Option<String>,
+    #[serde(rename = "targetUrl")]
+    pub target_url: Option<String>,
+    pub clicks: i64,
+    pub active: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShortLink {
+    /// Create a new short link
+    pub fn new(code: String, request: CreateShortLinkRequest) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            code,
+            property_id: request.property_id,
+            campaign: request.campaign,
+            channel: request.channel,
+            target_url: request.target_url,
+            clicks: 0,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Resolve the destination, tagging it with UTM parameters
+    pub fn destination_url(&self, daobitat_base_url: &str) -> String {
+        let base = self.target_url.clone()
+            .unwrap_or_else(|| format!("{}/property/{}", daobitat_base_url, self.property_id));
+
+        let mut params = vec![("utm_medium", "short_link")];
+        if let Some(channel) = &self.channel {
+            params.push(("utm_source", channel));
+        }
+        if let Some(campaign) = &self.campaign {
+            params.push(("utm_campaign", campaign));
+        }
+
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        let separator = if base.contains('?') { '&' } else { '?' };
+
+        format!("{}{}{}", base, separator, query)
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self, base_url: &str) -> ShortLinkResponse {
+        ShortLinkResponse {
+            id: self.id.to_hex(),
+            code: self.code.clone(),
+            short_url: format!("{}/l/{}", base_url, self.code),
+            property_id: self.property_id.clone(),
+            campaign: self.campaign.clone(),
+            channel: self.channel.clone(),
+            target_url: self.target_url.clone(),
+            clicks: self.clicks,
+            active: self.active,
+            created_at: self.created_at,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+// src/models/tracking.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Per-tenant (property owner) forwarding of scans to marketing tools
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackingConfig {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    pub ga4: Option<Ga4Config>,
+    #[serde(rename = "metaPixel")]
+    pub meta_pixel: Option<MetaPixelConfig>,
+    #[serde(rename = "requireConsent")]
+    pub require_consent: bool, // Only forward scans with an explicit consent signal
+    pub enabled: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+// GA4 Measurement Protocol credentials
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Ga4Config {
+    #[serde(rename = "measurementId")]
+    pub measurement_id: String, // "G-XXXXXXX"
+    #[serde(rename = "apiSecret")]
+    pub api_secret: String,
+}
+
+// Meta Conversions API credentials
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MetaPixelConfig {
+    #[serde(rename = "pixelId")]
+    pub pixel_id: String,
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "testEventCode")]
+    pub test_event_code: Option<String>, // Routes events to the Events Manager test tab
+}
+
+// Visitor's tracking consent as signalled on the scan request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingConsent {
+    Granted, // Consent cookie or query flag present
+    Unknown, // No signal either way
+    Denied,  // Global Privacy Control / Do Not Track
+}
+
+// Scan details handed to the forwarder
+#[derive(Debug, Clone)]
+pub struct ForwardedScan {
+    pub scan_id: String,
+    pub property_id: String,
+    pub visitor_id: String,
+    pub scan_source: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub scanned_at: DateTime<Utc>,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpsertTrackingConfigRequest {
+    pub ga4: Option<Ga4Config>,
+    #[serde(rename = "metaPixel")]
+    pub meta_pixel: Option<MetaPixelConfig>,
+    #[serde(rename = "requireConsent")]
+    pub require_consent: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TrackingConfigResponse {
+    #[serde(rename = "ownerId")]
+    pub owner_id: String,
+    #[serde(rename = "ga4MeasurementId")]
+    pub ga4_measurement_id: Option<String>,
+    #[serde(rename = "metaPixelId")]
+    pub meta_pixel_id: Option<String>,
+    #[serde(rename = "requireConsent")]
+    pub require_consent: bool,
+    pub enabled: bool,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TrackingConfig {
+    /// Create a tracking config for an owner
+    pub fn new(owner_id: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: ObjectId::new(),
+            owner_id,
+            ga4: None,
+            meta_pixel: None,
+            require_consent: true,
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Apply an upsert request on top of the current values
+    pub fn apply(mut self, request: UpsertTrackingConfigRequest) -> Self {
+        self.ga4 = request.ga4;
+        self.meta_pixel = request.meta_pixel;
+        if let Some(require_consent) = request.require_consent {
+            self.require_consent = require_consent;
+        }
+        if let Some(enabled) = request.enabled {
+            self.enabled = enabled;
+        }
+        self.updated_at = Utc::now();
+        self
+    }
+
+    /// Check whether a scan with the given consent may be forwarded
+    pub fn allows(&self, consent: TrackingConsent) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        match consent {
+            TrackingConsent::Granted => true,
+            TrackingConsent::Unknown => !self.require_consent,
+            TrackingConsent::Denied => false,
+        }
+    }
+
+    /// Convert to API response, leaving secrets out
+    pub fn to_response(&self) -> TrackingConfigResponse {
+        TrackingConfigResponse {
+            owner_id: self.owner_id.clone(),
+            ga4_measurement_id: self.ga4.as_ref().map(|g| g.measurement_id.clone()),
+            meta_pixel_id: self.meta_pixel.as_ref().map(|m| m.pixel_id.clone()),
+            require_consent: self.require_consent,
+            enabled: self.enabled,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// src/models/waitlist.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Prospect who asked to hear back about a listing they couldn't view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitlistEntry {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub reason: WaitlistReason,
+    #[serde(rename = "visitorId")]
+    pub visitor_id: Option<String>,
+
+    // Listing as it was when they joined, for finding similar ones later
+    pub location: String,
+    pub action: String,
+    pub price: i64,
+
+    #[serde(rename = "notifiedAt")]
+    pub notified_at: Option<DateTime<Utc>>, // Set once they've been told about a listing
+    #[serde(rename = "notifiedPropertyId")]
+    pub notified_property_id: Option<String>, // The listing they were told about: this one or a similar one
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitlistReason {
+    Capped, // Property reached its daily scan cap
+    Sold,
+}
+
+impl WaitlistReason {
+    /// Stored value, matching the serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WaitlistReason::Capped => "capped",
+            WaitlistReason::Sold => "sold",
+        }
+    }
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JoinWaitlistRequest {
+    pub name: Option<String>,
+    pub email: Option<String>, // At least one of email and phone
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WaitlistEntryResponse {
+    pub id: String,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub reason: WaitlistReason,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "notifiedAt")]
+    pub notified_at: Option<DateTime<Utc>>,
+    #[serde(rename = "notifiedPropertyId")]
+    pub notified_property_id: Option<String>,
+}
+
+impl WaitlistEntry {
+    /// Create a waitlist entry, snapshotting the listing it was made for
+    pub fn new(
+        property: &crate::models::PropertyQrInfo,
+        reason: WaitlistReason,
+        request: JoinWaitlistRequest,
+        visitor_id: Option<String>,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            property_id: property.id.to_hex(),
+            name: request.name,
+            email: request.email,
+            phone: request.phone,
+            reason,
+            visitor_id,
+            location: property.location.clone(),
+            action: property.action.clone(),
+            price: property.price,
+            notified_at: None,
+            notified_property_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> WaitlistEntryResponse {
+        WaitlistEntryResponse {
+            id: self.id.to_hex(),
+            property_id: self.property_id.clone(),
+            name: self.name.clone(),
+            email: self.email.clone(),
+            phone: self.phone.clone(),
+            reason: self.reason,
+            created_at: self.created_at,
+            notified_at: self.notified_at,
+            notified_property_id: self.notified_property_id.clone(),
+        }
+    }
+}
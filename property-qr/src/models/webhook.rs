@@ -0,0 +1,149 @@
+// src/models/webhook.rs
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::{ScanEvent, ScanSource, RedirectType, DeviceType, WaitlistEntry, WaitlistReason};
+
+// REST Hook subscription registered by a no-code platform (Zapier, Make, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookSubscription {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "targetUrl")]
+    pub target_url: String, // URL the platform wants events POSTed to
+    pub event: HookEvent,
+    #[serde(rename = "propertyId")]
+    pub property_id: Option<String>, // Restrict to a single property
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "lastDeliveredAt")]
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    #[serde(rename = "failureCount")]
+    pub failure_count: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum HookEvent {
+    #[serde(rename = "scan.created")]
+    ScanCreated,
+    #[serde(rename = "lead.created")]
+    LeadCreated,
+    #[serde(rename = "scan_cap.reached")]
+    ScanCapReached,
+}
+
+// Request/Response DTOs for API
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubscribeHookRequest {
+    #[serde(rename = "targetUrl")]
+    pub target_url: String,
+    pub event: HookEvent,
+    #[serde(rename = "propertyId")]
+    pub property_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HookSubscriptionResponse {
+    pub id: String,
+    #[serde(rename = "targetUrl")]
+    pub target_url: String,
+    pub event: HookEvent,
+    #[serde(rename = "propertyId")]
+    pub property_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+// Flat payload delivered for scan.created, also used for polling samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanHookPayload {
+    pub id: String,
+    pub event: HookEvent,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    #[serde(rename = "scannedAt")]
+    pub scanned_at: DateTime<Utc>,
+    #[serde(rename = "scanSource")]
+    pub scan_source: ScanSource,
+    #[serde(rename = "redirectType")]
+    pub redirect_type: RedirectType,
+    #[serde(rename = "deviceType")]
+    pub device_type: Option<DeviceType>,
+    pub country: Option<String>,
+}
+
+// Flat payload delivered for lead.created, also used for polling samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadHookPayload {
+    pub id: String,
+    pub event: HookEvent,
+    #[serde(rename = "propertyId")]
+    pub property_id: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub source: String,
+    pub reason: WaitlistReason,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl HookSubscription {
+    /// Create a new subscription
+    pub fn new(target_url: String, event: HookEvent, property_id: Option<String>) -> Self {
+        Self {
+            id: ObjectId::new(),
+            target_url,
+            event,
+            property_id,
+            created_at: Utc::now(),
+            last_delivered_at: None,
+            failure_count: 0,
+        }
+    }
+
+    /// Convert to API response
+    pub fn to_response(&self) -> HookSubscriptionResponse {
+        HookSubscriptionResponse {
+            id: self.id.to_hex(),
+            target_url: self.target_url.clone(),
+            event: self.event,
+            property_id: self.property_id.clone(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+impl From<&ScanEvent> for ScanHookPayload {
+    fn from(scan_event: &ScanEvent) -> Self {
+        Self {
+            id: scan_event.id.to_hex(),
+            event: HookEvent::ScanCreated,
+            property_id: scan_event.property_id.clone(),
+            scanned_at: scan_event.scanned_at,
+            scan_source: scan_event.scan_source.clone(),
+            redirect_type: scan_event.redirect_type.clone(),
+            device_type: scan_event.device_info.as_ref().map(|d| d.device_type.clone()),
+            country: scan_event.geolocation.as_ref().and_then(|g| g.country.clone()),
+        }
+    }
+}
+
+impl From<&WaitlistEntry> for LeadHookPayload {
+    fn from(entry: &WaitlistEntry) -> Self {
+        Self {
+            id: entry.id.to_hex(),
+            event: HookEvent::LeadCreated,
+            property_id: entry.property_id.clone(),
+            name: entry.name.clone(),
+            email: entry.email.clone(),
+            phone: entry.phone.clone(),
+            source: "waitlist".to_string(),
+            reason: entry.reason,
+            created_at: entry.created_at,
+        }
+    }
+}
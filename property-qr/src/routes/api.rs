@@ -1,6 +1,8 @@
  // src/routes/api.rs
 
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post, put, delete, patch},
     Router,
 };
@@ -14,12 +16,41 @@ use crate::handlers::{
     regenerate_qr_code,
     delete_qr_code,
     deactivate_qr_code,
+    update_qr_redirect,
+    get_qr_signature,
+    refresh_qr_metadata,
+    verify_qr_asset,
+    decode_qr_code,
+    MAX_DECODE_IMAGE_BYTES,
+    upload_qr_image,
+    MAX_UPLOADED_QR_IMAGE_BYTES,
+    get_qr_poster,
+    property_changed,
     list_qr_codes,
+    search_qr_codes,
+    export_qr_codes,
+    create_sticker_sheet,
     generate_missing_qr_codes,
+    get_stale_qr_codes,
+    regenerate_stale_qr_codes,
+    get_regeneration_job,
+    generate_document_qr_code,
+    generate_share_offer_qr_code,
+    list_share_offer_qr_codes,
+    create_experiment,
+    list_experiments,
+    end_experiment,
+    generate_booking_qr_code,
     
     // Scan handlers
     scan_qr_code,
+    scan_document_qr,
+    scan_share_offer,
+    check_in_booking,
     get_scan_data,
+    send_listing_sms,
+    record_funnel_event,
+    follow_scan_outcome,
     scan_health,
     
     // Health handlers
@@ -28,32 +59,329 @@ use crate::handlers::{
     liveness,
     readiness,
     
+    // REST hook handlers
+    subscribe_hook,
+    unsubscribe_hook,
+    list_hooks,
+    get_hook_samples,
+    
+    // Short link handlers
+    create_link,
+    get_link,
+    list_links,
+    update_link,
+    delete_link,
+    follow_link,
+    
+    // Tracking config handlers
+    get_tracking_config,
+    upsert_tracking_config,
+    delete_tracking_config,
+    
+    // Admin dashboard handlers
+    admin_dashboard,
+    admin_login_page,
+    admin_login,
+    admin_logout,
+    admin_regenerate_qr,
+    admin_deactivate_qr,
+    require_admin_key,
+    
+    // Support impersonation handlers
+    issue_impersonation_token,
+    revoke_impersonation_token,
+    get_impersonation_audit,
+    audit_impersonation,
+    
+    // Analytics handlers
+    get_property_analytics_history,
+    get_campaign_breakdown,
+    get_property_campaign_breakdown,
+    get_qr_version_breakdown,
+    get_scan_heatmap,
+    get_property_scan_trends,
+    get_property_monthly_trends,
+    get_top_properties,
+    get_hourly_distribution,
+    get_property_hourly_distribution,
+    get_funnel_stats,
+    get_experiment_reports,
+    compare_analytics,
+    get_public_area_stats,
+    
+    // Geo-blocking handlers
+    get_geo_block_policy,
+    upsert_geo_block_policy,
+    delete_geo_block_policy,
+    
+    // Scan cap handlers
+    get_scan_cap,
+    upsert_scan_cap,
+    delete_scan_cap,
+    resume_scan_cap,
+    join_waitlist,
+    
+    // QR style preset handlers
+    list_qr_styles,
+    get_qr_style,
+    upsert_qr_style,
+    delete_qr_style,
+    
+    // Auto-redirect config handlers
+    get_auto_redirect_config,
+    upsert_auto_redirect_config,
+    delete_auto_redirect_config,
+    get_global_auto_redirect_config,
+    upsert_global_auto_redirect_config,
+    delete_global_auto_redirect_config,
+    
+    // Waitlist handlers
+    get_waitlist,
+    export_waitlist,
+    
+    // Organization handlers
+    create_organization,
+    add_organization_member,
+    remove_organization_member,
+    require_org_key,
+    get_organization,
+    list_org_qr_codes,
+    regenerate_org_qr,
+    deactivate_org_qr,
+    get_org_analytics,
+    update_org_branding,
+    export_org_branding_kit,
+    
+    // GraphQL handler
+    graphql_query,
+    
+    // Role checks for the QR management API
+    authenticate,
+    require_admin,
+    require_staff,
+    require_property_access,
+    require_body_property_access,
+    require_booking_access,
+    require_compare_access,
+    require_owner_access,
+    require_scope_access,
+    
+    // Owner handlers
+    list_owner_qr_codes,
+    get_digest_preference,
+    update_digest_preference,
+    
+    // Audit log handlers
+    list_audit_log,
+    
+    // Prometheus metrics
+    metrics,
+    
+    // Local storage
+    get_stored_file,
+    
     // State types
+    AdminAppState,
+    AnalyticsAppState,
+    AuditAppState,
+    AuthAppState,
+    AutoRedirectAppState,
+    QrStyleAppState,
+    GeoBlockAppState,
+    GraphQlAppState,
     AppState,
+    HealthAppState,
+    ImpersonationAppState,
+    OrgAppState,
+    OwnerAppState,
     ScanAppState,
+    ScanCapAppState,
+    HookAppState,
+    TrackingAppState,
+    WaitlistAppState,
+    LinkAppState,
+    MetricsAppState,
+    StorageAppState,
+    PropertyAppState,
+    get_eligibility_report,
+    get_property_stats,
+    get_recent_properties,
+    search_properties,
 };
 
 /// QR code management routes
 /// Mounted at /api/v1
-pub fn qr_routes(state: Arc<AppState>) -> Router {
-    Router::new()
+/// With a JWT secret configured, agents reach only their own properties, read-only staff
+/// can't change anything and batch and delete operations are admin only
+pub fn qr_routes(state: Arc<AppState>, auth: Arc<AuthAppState>) -> Router {
+    // Routes on a single property
+    let property_routes = Router::new()
         // QR Generation Routes
-        .route("/qr/generate/:property_id", post(generate_qr_code))
-        .route("/qr/generate/batch", post(batch_generate_qr_codes))
-        .route("/qr/generate/missing", post(generate_missing_qr_codes))
+        .route("/qr/generate/{property_id}", post(generate_qr_code))
         
         // QR Management Routes
-        .route("/qr/:property_id", get(get_qr_code))
-        .route("/qr/:property_id", delete(delete_qr_code))
-        .route("/qr/regenerate/:property_id", put(regenerate_qr_code))
-        .route("/qr/deactivate/:property_id", patch(deactivate_qr_code))
-        
-        // QR Listing Routes
-        .route("/qr", get(list_qr_codes))
+        .route("/qr/{property_id}", get(get_qr_code))
+        .route("/qr/regenerate/{property_id}", put(regenerate_qr_code))
+        .route("/qr/deactivate/{property_id}", patch(deactivate_qr_code))
+        .route("/qr/{property_id}/redirect", patch(update_qr_redirect))
+        .route("/qr/{property_id}/refresh-metadata", post(refresh_qr_metadata))
+        .route("/qr/{property_id}/verify-asset", get(verify_qr_asset))
+        .route("/qr/{property_id}/image", put(upload_qr_image).layer(DefaultBodyLimit::max(MAX_UPLOADED_QR_IMAGE_BYTES)))
+        .route("/qr/{property_id}/signature.html", get(get_qr_signature))
+        .route("/qr/{property_id}/poster", get(get_qr_poster))
+        .route("/qr/{property_id}/share-offers", get(list_share_offer_qr_codes))
+        .route("/qr/{property_id}/experiments", get(list_experiments).post(create_experiment))
+        .route("/qr/{property_id}/experiments/{experiment_id}/end", post(end_experiment))
         
-        // Analytics Routes (using scan data endpoint)
-        .route("/scan/:property_id", get(get_scan_data))
+        // Called by the listing platform after a property is edited
+        .route("/properties/{property_id}/changed", post(property_changed))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_property_access));
+    
+    // Codes for a property named in the request body
+    let document_routes = Router::new()
+        .route("/qr/document", post(generate_document_qr_code))
+        .route("/qr/share-offer", post(generate_share_offer_qr_code))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_body_property_access));
+    
+    // Check-in codes, for the booked property
+    let booking_routes = Router::new()
+        .route("/qr/booking/{booking_id}", post(generate_booking_qr_code))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_booking_access));
+    
+    // Batch, bulk and delete operations
+    let admin_routes = Router::new()
+        .route("/qr/generate/batch", post(batch_generate_qr_codes))
+        .route("/qr/generate/missing", post(generate_missing_qr_codes))
+        .route("/qr/{property_id}", delete(delete_qr_code))
         
+        // Bulk regeneration after a base URL change
+        .route("/qr/regenerate/stale", get(get_stale_qr_codes).post(regenerate_stale_qr_codes))
+        .route("/qr/regenerate/jobs/{job_id}", get(get_regeneration_job))
+        .route_layer(middleware::from_fn(require_admin));
+    
+    // QR Listing Routes, across every property
+    let listing_routes = Router::new()
+        .route("/qr", get(list_qr_codes))
+        .route("/qr/search", get(search_qr_codes))
+        .route("/qr/export", post(export_qr_codes))
+        .route("/qr/stickers", post(create_sticker_sheet))
+        .route("/qr/decode", post(decode_qr_code).layer(DefaultBodyLimit::max(MAX_DECODE_IMAGE_BYTES)))
+        .route_layer(middleware::from_fn(require_staff));
+    
+    Router::new()
+        .merge(property_routes)
+        .merge(document_routes)
+        .merge(booking_routes)
+        .merge(admin_routes)
+        .merge(listing_routes)
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// An owner's QR codes for the app's "My listings" page, their weekly digest settings and
+/// their GA4 / Meta Pixel forwarding config; agents reach only their own, and support
+/// can act as them with an impersonation token
+/// Mounted at /api/v1
+pub fn owner_routes(
+    state: Arc<OwnerAppState>,
+    tracking: Arc<TrackingAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    let tracking_routes = Router::new()
+        .route(
+            "/tracking/{owner_id}",
+            get(get_tracking_config).put(upsert_tracking_config).delete(delete_tracking_config),
+        )
+        .with_state(tracking);
+    
+    Router::new()
+        .route("/owners/{owner_id}/qr", get(list_owner_qr_codes))
+        .route("/owners/{owner_id}/digest", get(get_digest_preference).put(update_digest_preference))
+        .with_state(state)
+        .merge(tracking_routes)
+        .route_layer(middleware::from_fn(require_owner_access))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+}
+
+/// Listing search and reports for the QR admin UI, read from the listing platform's properties;
+/// staff only, as they span every owner
+/// Mounted at /api/v1
+pub fn property_routes(state: Arc<PropertyAppState>, auth: Arc<AuthAppState>) -> Router {
+    Router::new()
+        .route("/properties", get(search_properties))
+        .route("/properties/stats", get(get_property_stats))
+        .route("/properties/recent", get(get_recent_properties))
+        .route("/properties/eligibility", get(get_eligibility_report))
+        .route_layer(middleware::from_fn(require_staff))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// GraphQL endpoint for dashboards querying QR codes and analytics together; staff only
+/// Mounted at /
+pub fn graphql_routes(state: Arc<GraphQlAppState>, auth: Arc<AuthAppState>) -> Router {
+    Router::new()
+        .route("/api/graphql", post(graphql_query))
+        .route_layer(middleware::from_fn(require_staff))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// Who generated, regenerated, deleted or deactivated each QR code; admin only
+/// Mounted at /api/v1
+pub fn audit_routes(state: Arc<AuditAppState>, auth: Arc<AuthAppState>) -> Router {
+    Router::new()
+        .route("/audit", get(list_audit_log))
+        .route_layer(middleware::from_fn(require_admin))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// Named QR style presets codes can be generated with; anyone can read them, and only
+/// admins can change them
+/// Mounted at /api/v1
+pub fn qr_style_routes(state: Arc<QrStyleAppState>, auth: Arc<AuthAppState>) -> Router {
+    let write_routes = Router::new()
+        .route("/qr/styles/{name}", put(upsert_qr_style).delete(delete_qr_style))
+        .route_layer(middleware::from_fn(require_admin))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate));
+    
+    Router::new()
+        .route("/qr/styles", get(list_qr_styles))
+        .route("/qr/styles/{name}", get(get_qr_style))
+        .merge(write_routes)
+        .with_state(state)
+}
+
+/// Server-rendered admin dashboard, behind the admin API key
+/// Mounted at /
+pub fn admin_routes(state: Arc<AdminAppState>) -> Router {
+    let protected = Router::new()
+        .route("/admin", get(admin_dashboard))
+        .route("/admin/logout", post(admin_logout))
+        .route("/admin/qr/{property_id}/regenerate", post(admin_regenerate_qr))
+        .route("/admin/qr/{property_id}/deactivate", post(admin_deactivate_qr))
+        .route("/admin/impersonation", post(issue_impersonation_token))
+        .route("/admin/impersonation/audit", get(get_impersonation_audit))
+        .route("/admin/impersonation/{token_id}", delete(revoke_impersonation_token))
+        .route("/admin/orgs", post(create_organization))
+        .route(
+            "/admin/orgs/{org_id}/members/{owner_id}",
+            put(add_organization_member).delete(remove_organization_member),
+        )
+        .route(
+            "/admin/auto-redirect",
+            get(get_global_auto_redirect_config)
+                .put(upsert_global_auto_redirect_config)
+                .delete(delete_global_auto_redirect_config),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_admin_key));
+
+    Router::new()
+        .route("/admin/login", get(admin_login_page).post(admin_login))
+        .merge(protected)
         .with_state(state)
 }
 
@@ -62,10 +390,31 @@ pub fn qr_routes(state: Arc<AppState>) -> Router {
 pub fn scan_routes(state: Arc<ScanAppState>) -> Router {
     Router::new()
         // Main scan endpoint - handles QR code scans
-        .route("/scan/:property_id", get(scan_qr_code))
+        .route("/scan/{property_id}", get(scan_qr_code))
+
+        // Verification pages for property documents' QR codes
+        .route("/scan/{property_id}/documents/{document_id}", get(scan_document_qr))
+
+        // Co-ownership share offers, each with its own code
+        .route("/scan/{property_id}/shares/{offer_id}", get(scan_share_offer))
+
+        // One-time check-in codes for short-term rental bookings
+        .route("/checkin/{token}", get(check_in_booking))
         
         // API endpoint for scan data
-        .route("/api/scan/:property_id", get(get_scan_data))
+        .route("/api/scan/{property_id}", get(get_scan_data))
+        
+        // "Text me this listing" from the landing page
+        .route("/api/scan/{property_id}/sms", post(send_listing_sms))
+        
+        // Funnel beacons from the dual page: page view, auto-redirect, button clicks
+        .route("/api/scan/{property_id}/funnel", post(record_funnel_event))
+
+        // Tracked dual page buttons: record the click, then redirect
+        .route("/scan/{property_id}/go/{outcome}", get(follow_scan_outcome))
+        
+        // Waitlist sign-up for sold properties and ones that reached their daily scan cap
+        .route("/api/scan/{property_id}/waitlist", post(join_waitlist))
         
         // Scan service health
         .route("/scan/health", get(scan_health))
@@ -73,9 +422,188 @@ pub fn scan_routes(state: Arc<ScanAppState>) -> Router {
         .with_state(state)
 }
 
+/// REST hook routes for Zapier/Make integrations; admin only, since hooks receive every
+/// matching event across all properties
+/// Mounted at /api/v1
+pub fn hook_routes(state: Arc<HookAppState>, auth: Arc<AuthAppState>) -> Router {
+    Router::new()
+        .route("/hooks", get(list_hooks).post(subscribe_hook))
+        .route("/hooks/{hook_id}", delete(unsubscribe_hook))
+        .route("/hooks/samples/{event}", get(get_hook_samples))
+        .route_layer(middleware::from_fn(require_admin))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// Property analytics routes for the property's owner, open to support impersonation;
+/// analytics across properties are staff only, though agents may compare their own
+/// Mounted at /api/v1
+pub fn analytics_routes(
+    state: Arc<AnalyticsAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    let property_routes = Router::new()
+        .route("/analytics/properties/{property_id}/history", get(get_property_analytics_history))
+        .route("/analytics/properties/{property_id}/campaigns", get(get_property_campaign_breakdown))
+        .route("/analytics/properties/{property_id}/qr-versions", get(get_qr_version_breakdown))
+        .route("/analytics/properties/{property_id}/heatmap", get(get_scan_heatmap))
+        .route("/analytics/properties/{property_id}/hourly", get(get_property_hourly_distribution))
+        .route("/analytics/properties/{property_id}/trends", get(get_property_scan_trends))
+        .route("/analytics/properties/{property_id}/trends/monthly", get(get_property_monthly_trends))
+        .route("/analytics/properties/{property_id}/funnel", get(get_funnel_stats))
+        .route("/analytics/properties/{property_id}/experiments", get(get_experiment_reports))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_property_access));
+    
+    let staff_routes = Router::new()
+        .route("/analytics/campaigns", get(get_campaign_breakdown))
+        .route("/analytics/hourly", get(get_hourly_distribution))
+        .route("/analytics/top-properties", get(get_top_properties))
+        .route_layer(middleware::from_fn(require_staff));
+    
+    // Agents may compare properties they own
+    let compare_routes = Router::new()
+        .route("/analytics/compare", get(compare_analytics))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_compare_access));
+    
+    Router::new()
+        .merge(property_routes)
+        .merge(staff_routes)
+        .merge(compare_routes)
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+        .with_state(state)
+}
+
+/// Public aggregate stats, noised per the deployment's privacy settings
+/// Mounted at /api/v1
+pub fn public_stats_routes(state: Arc<AnalyticsAppState>) -> Router {
+    Router::new()
+        .route("/stats/areas", get(get_public_area_stats))
+        
+        .with_state(state)
+}
+
+/// Geo-blocking policy routes for the owner or property's owner, open to support impersonation
+/// Mounted at /api/v1
+pub fn geo_block_routes(
+    state: Arc<GeoBlockAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    Router::new()
+        .route(
+            "/geo-blocks/{scope}/{scope_id}",
+            get(get_geo_block_policy).put(upsert_geo_block_policy).delete(delete_geo_block_policy),
+        )
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_scope_access))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+        .with_state(state)
+}
+
+/// Dual page auto-redirect config routes for the property's owner, open to support impersonation
+/// Mounted at /api/v1
+pub fn auto_redirect_routes(
+    state: Arc<AutoRedirectAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    Router::new()
+        .route(
+            "/auto-redirects/{property_id}",
+            get(get_auto_redirect_config).put(upsert_auto_redirect_config).delete(delete_auto_redirect_config),
+        )
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_property_access))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+        .with_state(state)
+}
+
+/// Daily scan cap routes for the property's owner, open to support impersonation
+/// Mounted at /api/v1
+pub fn scan_cap_routes(
+    state: Arc<ScanCapAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    Router::new()
+        .route(
+            "/scan-caps/{property_id}",
+            get(get_scan_cap).put(upsert_scan_cap).delete(delete_scan_cap),
+        )
+        .route("/scan-caps/{property_id}/resume", post(resume_scan_cap))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_property_access))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+        .with_state(state)
+}
+
+/// Waitlist routes for the property's owner, open to support impersonation
+/// Mounted at /api/v1
+pub fn waitlist_routes(
+    state: Arc<WaitlistAppState>,
+    auth: Arc<AuthAppState>,
+    impersonation: Arc<ImpersonationAppState>,
+) -> Router {
+    Router::new()
+        .route("/waitlist/{property_id}", get(get_waitlist))
+        .route("/waitlist/{property_id}/export", get(export_waitlist))
+        .route_layer(middleware::from_fn_with_state(auth.clone(), require_property_access))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .route_layer(middleware::from_fn_with_state(impersonation, audit_impersonation))
+        
+        .with_state(state)
+}
+
+/// Agency routes across every member owner's properties, behind the organization's API key
+/// Mounted at /api/v1
+pub fn organization_routes(state: Arc<OrgAppState>) -> Router {
+    Router::new()
+        .route("/orgs/{org_id}", get(get_organization))
+        .route("/orgs/{org_id}/qr", get(list_org_qr_codes))
+        .route("/orgs/{org_id}/qr/{property_id}/regenerate", post(regenerate_org_qr))
+        .route("/orgs/{org_id}/qr/{property_id}/deactivate", post(deactivate_org_qr))
+        .route("/orgs/{org_id}/analytics", get(get_org_analytics))
+        .route("/orgs/{org_id}/branding", put(update_org_branding))
+        .route("/orgs/{org_id}/kit", get(export_org_branding_kit))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_org_key))
+        
+        .with_state(state)
+}
+
+/// Short link management routes; staff can look, and only admins can point links anywhere
+/// Mounted at /api/v1
+pub fn link_routes(state: Arc<LinkAppState>, auth: Arc<AuthAppState>) -> Router {
+    let read_routes = Router::new()
+        .route("/links", get(list_links))
+        .route("/links/{link_id}", get(get_link))
+        .route_layer(middleware::from_fn(require_staff));
+    
+    let write_routes = Router::new()
+        .route("/links", post(create_link))
+        .route("/links/{link_id}", patch(update_link).delete(delete_link))
+        .route_layer(middleware::from_fn(require_admin));
+    
+    Router::new()
+        .merge(read_routes)
+        .merge(write_routes)
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .with_state(state)
+}
+
+/// Public short link redirects
+/// Mounted at /
+pub fn short_link_routes(state: Arc<LinkAppState>) -> Router {
+    Router::new()
+        .route("/l/{code}", get(follow_link))
+        
+        .with_state(state)
+}
+
 /// Health check routes
 /// Mounted at /health
-pub fn health_routes() -> Router {
+pub fn health_routes(state: Arc<HealthAppState>) -> Router {
     Router::new()
         // Basic health check
         .route("/", get(health))
@@ -86,20 +614,39 @@ pub fn health_routes() -> Router {
         // Kubernetes-style probes
         .route("/live", get(liveness))
         .route("/ready", get(readiness))
+        
+        .with_state(state)
+}
+
+/// Prometheus scrape endpoint
+/// Mounted at the root
+pub fn metrics_routes(state: Arc<MetricsAppState>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+/// Files kept by the local storage backend
+/// Mounted at the root, only when that backend is configured
+pub fn storage_routes(state: Arc<StorageAppState>) -> Router {
+    Router::new()
+        .route("/storage/{*key}", get(get_stored_file))
+        .with_state(state)
 }
 
 /// Complete API routes structure
 /// This function combines all routes if you want a single router
 pub fn create_app_router(
     qr_state: Arc<AppState>,
+    auth_state: Arc<AuthAppState>,
     scan_state: Arc<ScanAppState>,
 ) -> Router {
     Router::new()
-        // Health routes (no state needed)
-        .nest("/health", health_routes())
+        // Health routes (no startup self-test)
+        .nest("/health", health_routes(Arc::new(HealthAppState::default())))
         
         // QR management API routes
-        .nest("/api/v1", qr_routes(qr_state))
+        .nest("/api/v1", qr_routes(qr_state, auth_state))
         
         // Scan routes (public-facing)
         .merge(scan_routes(scan_state))
@@ -124,7 +671,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_health_routes() {
-        let app = health_routes();
+        let app = health_routes(Arc::new(HealthAppState::default()));
         
         let response = app
             .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
@@ -135,6 +682,208 @@ mod tests {
         assert!(response.status().is_success() || response.status().is_server_error());
     }
 
+    // The client connects lazily, so requests turned away by the auth layers need no database
+    async fn test_database() -> mongodb::Database {
+        mongodb::Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .unwrap()
+            .database("test_qr_routes")
+    }
+
+    fn test_auth_state(db: &mongodb::Database) -> Arc<AuthAppState> {
+        Arc::new(AuthAppState {
+            verifier: Some(crate::utils::JwtVerifier::new(None).with_hmac_secret("test-secret")),
+            admin_api_key: Some("test-admin-key".to_string()),
+            property_service: crate::services::PropertyService::new(db),
+        })
+    }
+
+    fn test_impersonation_state(db: &mongodb::Database) -> Arc<ImpersonationAppState> {
+        Arc::new(ImpersonationAppState {
+            impersonation_service: crate::services::ImpersonationService::new(db),
+            property_service: crate::services::PropertyService::new(db),
+        })
+    }
+
+    async fn anonymous_status(app: Router, method: &str, uri: &str) -> StatusCode {
+        let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_waitlist_routes_require_authentication() {
+        let db = test_database().await;
+        let waitlist_state = Arc::new(WaitlistAppState {
+            waitlist_service: crate::services::WaitlistService::new(
+                &db,
+                crate::services::PropertyService::new(&db),
+                "https://www.daobitat.xyz".to_string(),
+            ),
+        });
+        let app = waitlist_routes(waitlist_state, test_auth_state(&db), test_impersonation_state(&db));
+
+        for uri in ["/waitlist/507f1f77bcf86cd799439011", "/waitlist/507f1f77bcf86cd799439011/export"] {
+            assert_eq!(anonymous_status(app.clone(), "GET", uri).await, StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_routes_require_admin() {
+        let db = test_database().await;
+        let audit_state = Arc::new(AuditAppState {
+            audit_service: crate::services::AuditService::new(&db),
+            admin_api_key: Some("test-admin-key".to_string()),
+        });
+        let app = audit_routes(audit_state, test_auth_state(&db));
+
+        assert_eq!(anonymous_status(app, "GET", "/audit").await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_qr_style_writes_require_admin() {
+        let db = test_database().await;
+        let qr_style_state = Arc::new(QrStyleAppState {
+            qr_style_service: crate::services::QrStyleService::new(&db),
+        });
+        let app = qr_style_routes(qr_style_state, test_auth_state(&db));
+
+        for method in ["PUT", "DELETE"] {
+            assert_eq!(anonymous_status(app.clone(), method, "/qr/styles/partner").await, StatusCode::UNAUTHORIZED, "{}", method);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_management_routes_require_authentication() {
+        use crate::services::{
+            AnalyticsService, AutoRedirectService, ExperimentService, GeoBlockService, HookService,
+            LinkService, PropertyService, QrGeneratorService, S3Storage, ScanCapService, StorageService,
+        };
+
+        let db = test_database().await;
+        let auth = test_auth_state(&db);
+        let impersonation = test_impersonation_state(&db);
+        let analytics_service = AnalyticsService::new(&db);
+        let storage = StorageService::new(Arc::new(
+            S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).unwrap(),
+        ));
+        let qr_generator = QrGeneratorService::new(
+            &db,
+            PropertyService::new(&db),
+            storage,
+            "https://qr-service.daobitat.xyz".to_string(),
+        );
+
+        let app = Router::new()
+            .merge(property_routes(Arc::new(PropertyAppState { property_service: PropertyService::new(&db) }), auth.clone()))
+            .merge(graphql_routes(
+                Arc::new(GraphQlAppState {
+                    schema: crate::graphql::build_schema(qr_generator, PropertyService::new(&db), analytics_service.clone()),
+                }),
+                auth.clone(),
+            ))
+            .merge(analytics_routes(
+                Arc::new(AnalyticsAppState {
+                    analytics_service: analytics_service.clone(),
+                    experiment_service: ExperimentService::new(&db),
+                    public_privacy: None,
+                }),
+                auth.clone(),
+                impersonation.clone(),
+            ))
+            .merge(geo_block_routes(
+                Arc::new(GeoBlockAppState { geo_block_service: GeoBlockService::new(&db) }),
+                auth.clone(),
+                impersonation.clone(),
+            ))
+            .merge(auto_redirect_routes(
+                Arc::new(AutoRedirectAppState { auto_redirect_service: AutoRedirectService::new(&db) }),
+                auth.clone(),
+                impersonation.clone(),
+            ))
+            .merge(scan_cap_routes(
+                Arc::new(ScanCapAppState { scan_cap_service: ScanCapService::new(&db) }),
+                auth.clone(),
+                impersonation,
+            ))
+            .merge(hook_routes(Arc::new(HookAppState { hook_service: HookService::new(&db, Vec::new()) }), auth.clone()))
+            .merge(link_routes(
+                Arc::new(LinkAppState {
+                    link_service: LinkService::new(&db, "https://qr-service.daobitat.xyz".to_string(), Vec::new()),
+                    analytics_service,
+                    daobitar_base_url: "https://www.daobitat.xyz".to_string(),
+                }),
+                auth,
+            ));
+
+        let property = "507f1f77bcf86cd799439011";
+        let routes = [
+            ("GET", "/properties".to_string()),
+            ("GET", "/properties/eligibility".to_string()),
+            ("POST", "/api/graphql".to_string()),
+            ("GET", format!("/analytics/properties/{property}/history")),
+            ("GET", "/analytics/top-properties".to_string()),
+            ("PUT", format!("/geo-blocks/property/{property}")),
+            ("DELETE", format!("/geo-blocks/owner/{property}")),
+            ("PUT", format!("/auto-redirects/{property}")),
+            ("DELETE", format!("/auto-redirects/{property}")),
+            ("PUT", format!("/scan-caps/{property}")),
+            ("DELETE", format!("/scan-caps/{property}")),
+            ("POST", format!("/scan-caps/{property}/resume")),
+            ("POST", "/hooks".to_string()),
+            ("DELETE", format!("/hooks/{property}")),
+            ("POST", "/links".to_string()),
+            ("PATCH", format!("/links/{property}")),
+            ("DELETE", format!("/links/{property}")),
+        ];
+        for (method, uri) in routes {
+            assert_eq!(anonymous_status(app.clone(), method, &uri).await, StatusCode::UNAUTHORIZED, "{} {}", method, uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agents_compare_their_own_properties() {
+        use crate::models::Property;
+        use crate::services::{AnalyticsService, ExperimentService};
+        use mongodb::bson::oid::ObjectId;
+
+        let db = test_database().await;
+        let agent = ObjectId::new();
+        let listing = |owner| Property { id: ObjectId::new(), owner, ..Default::default() };
+        let (villa, flat, foreign) = (listing(agent), listing(agent), listing(ObjectId::new()));
+        db.collection::<Property>("properties")
+            .insert_many([&villa, &flat, &foreign])
+            .await
+            .expect("Failed to insert properties");
+
+        let app = analytics_routes(
+            Arc::new(AnalyticsAppState {
+                analytics_service: AnalyticsService::new(&db),
+                experiment_service: ExperimentService::new(&db),
+                public_privacy: None,
+            }),
+            test_auth_state(&db),
+            test_impersonation_state(&db),
+        );
+        let claims = serde_json::json!({ "sub": agent.to_hex(), "role": "agent", "exp": 4_000_000_000i64 });
+        let token = crate::utils::jwt::tests::token("HS256", claims, "test-secret");
+        let status = |uri: String| {
+            let request = Request::builder()
+                .uri(uri)
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        let own = format!("/analytics/compare?property_a={}&property_b={}", villa.id.to_hex(), flat.id.to_hex());
+        assert_eq!(status(own).await, StatusCode::OK);
+        let mixed = format!("/analytics/compare?property_a={}&property_b={}", villa.id.to_hex(), foreign.id.to_hex());
+        assert_eq!(status(mixed).await, StatusCode::FORBIDDEN);
+        // Tags span every owner's properties
+        assert_eq!(status("/analytics/compare?tags=billboard".to_string()).await, StatusCode::FORBIDDEN);
+    }
+
     #[tokio::test]
     async fn test_app_router_creation() {
         // This test just ensures the router can be created without panicking
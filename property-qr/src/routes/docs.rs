@@ -0,0 +1,246 @@
+// src/routes/docs.rs
+
+use axum::Router;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::handlers::{
+    self, DetailedHealthResponse, ErrorResponse, FieldError, HealthResponse, PropertySummary, RedirectUrls, ScanResponse,
+    FunnelBeaconRequest, SendListingSmsRequest,
+};
+use crate::services::property_service::PropertyStats;
+use crate::models::{
+    AnalyticsComparison, AreaStats, PrivacyNotice, PublicAreaStats, BatchGenerateQrRequest, BatchQrCodeResponse, CampaignStats, CreateShortLinkRequest, FunnelStage, FunnelStats, ScanOutcome, ClickThroughRates, GenerateQrRequest,
+    GeoBlockPolicyResponse, GeoBlockScope, JoinWaitlistRequest, UpsertGeoBlockPolicyRequest,
+    HookEvent, HookSubscriptionResponse, BrandingProfile, OrgAnalyticsSummary, OrgPropertyScans, OrganizationResponse, OwnerQrCode, OwnerQrListing, DigestPreferenceResponse, UpdateDigestPreferenceRequest, QrCodeMetadata, QrCodePage, QrCodeResponse, QrExportRequest, QrGenerationReason, QrStyleResponse, UpsertQrStyleRequest, QrPayload, AgentContact, WifiNetwork, WifiSecurity, PosterSize, StickerSheetRequest,
+    QrCodeDetails, QrRegenerationJobResponse, QrSortField, QrStatus, QrStorageState, AssetStatus, AssetVerification, QrDecodeReport, QrVersionStats, ScanHeatmap, HeatmapCell, HourlyScanDistribution, DailyScanCount, MonthlyScanCount, PropertyPerformance, PropertyAnalyticsSnapshot, ScanAnalyticsResponse, ScanCapResponse, ShortLinkResponse, StaleQrReport,
+    SortOrder, SubscribeHookRequest, SystemAnalyticsResponse, TagComparison, PropertyComparison, TrackingConfigResponse, UpdateQrRedirectRequest, UpdateShortLinkRequest,
+    UpsertScanCapRequest, UpsertTrackingConfigRequest, UpsertAutoRedirectConfigRequest, AutoRedirectConfigResponse,
+    AutoRedirectDestination, DestinationWeight, WaitlistEntryResponse, WaitlistReason,
+    EligibilityReport, IneligibilityGroup, IneligibilityReason, IneligibleProperty, PropertyListItem,
+    ActorKind, AuditAction, AuditActor, AuditEntryResponse, AuditLogPage,
+    CreateDocumentQrRequest, DocumentQrResponse, CreateShareOfferQrRequest, ShareOfferQrResponse,
+    BookingQrResponse, CreateExperimentRequest, LandingVariantRequest, LandingVariant, ExperimentResponse,
+    ExperimentReport, VariantStats,
+};
+
+// OpenAPI document for every public and management endpoint
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "DAO-Bitat QR Service",
+        description = "QR code generation, scan redirects, analytics and marketing integrations for DAO-Bitat properties"
+    ),
+    paths(
+        handlers::generate_qr_code,
+        handlers::batch_generate_qr_codes,
+        handlers::generate_missing_qr_codes,
+        handlers::get_qr_code,
+        handlers::list_qr_codes,
+        handlers::search_qr_codes,
+        handlers::export_qr_codes,
+        handlers::create_sticker_sheet,
+        handlers::regenerate_qr_code,
+        handlers::deactivate_qr_code,
+        handlers::update_qr_redirect,
+        handlers::get_qr_signature,
+        handlers::refresh_qr_metadata,
+        handlers::verify_qr_asset,
+        handlers::decode_qr_code,
+        handlers::upload_qr_image,
+        handlers::get_qr_poster,
+        handlers::property_changed,
+        handlers::delete_qr_code,
+        handlers::get_stale_qr_codes,
+        handlers::regenerate_stale_qr_codes,
+        handlers::get_regeneration_job,
+        handlers::generate_document_qr_code,
+        handlers::generate_share_offer_qr_code,
+        handlers::list_share_offer_qr_codes,
+        handlers::create_experiment,
+        handlers::list_experiments,
+        handlers::end_experiment,
+        handlers::generate_booking_qr_code,
+        handlers::scan_qr_code,
+        handlers::scan_document_qr,
+        handlers::scan_share_offer,
+        handlers::check_in_booking,
+        handlers::get_scan_data,
+        handlers::send_listing_sms,
+        handlers::record_funnel_event,
+        handlers::follow_scan_outcome,
+        handlers::join_waitlist,
+        handlers::scan_health,
+        handlers::subscribe_hook,
+        handlers::list_hooks,
+        handlers::unsubscribe_hook,
+        handlers::get_hook_samples,
+        handlers::create_link,
+        handlers::list_links,
+        handlers::get_link,
+        handlers::update_link,
+        handlers::delete_link,
+        handlers::follow_link,
+        handlers::get_property_analytics_history,
+        handlers::get_property_campaign_breakdown,
+        handlers::get_campaign_breakdown,
+        handlers::get_qr_version_breakdown,
+        handlers::get_scan_heatmap,
+        handlers::get_property_scan_trends,
+        handlers::get_property_monthly_trends,
+        handlers::get_top_properties,
+        handlers::get_hourly_distribution,
+        handlers::get_property_hourly_distribution,
+        handlers::get_funnel_stats,
+        handlers::get_experiment_reports,
+        handlers::compare_analytics,
+        handlers::get_public_area_stats,
+        handlers::get_geo_block_policy,
+        handlers::upsert_geo_block_policy,
+        handlers::delete_geo_block_policy,
+        handlers::get_scan_cap,
+        handlers::upsert_scan_cap,
+        handlers::delete_scan_cap,
+        handlers::resume_scan_cap,
+        handlers::list_qr_styles,
+        handlers::get_qr_style,
+        handlers::upsert_qr_style,
+        handlers::delete_qr_style,
+        handlers::get_auto_redirect_config,
+        handlers::upsert_auto_redirect_config,
+        handlers::delete_auto_redirect_config,
+        handlers::get_waitlist,
+        handlers::export_waitlist,
+        handlers::search_properties,
+        handlers::get_property_stats,
+        handlers::get_recent_properties,
+        handlers::get_eligibility_report,
+        handlers::get_organization,
+        handlers::list_org_qr_codes,
+        handlers::regenerate_org_qr,
+        handlers::deactivate_org_qr,
+        handlers::get_org_analytics,
+        handlers::update_org_branding,
+        handlers::export_org_branding_kit,
+        handlers::get_tracking_config,
+        handlers::upsert_tracking_config,
+        handlers::delete_tracking_config,
+        handlers::list_owner_qr_codes,
+        handlers::get_digest_preference,
+        handlers::update_digest_preference,
+        handlers::list_audit_log,
+        handlers::health,
+        handlers::health_detailed,
+        handlers::liveness,
+        handlers::readiness,
+        handlers::metrics,
+        handlers::get_stored_file,
+    ),
+    components(schemas(
+        GenerateQrRequest, BatchGenerateQrRequest, QrExportRequest, StickerSheetRequest, QrCodeResponse, BatchQrCodeResponse,
+        QrCodeMetadata, QrCodeDetails, QrCodePage, QrSortField, SortOrder, PosterSize, QrGenerationReason, QrStatus, QrStorageState, AssetStatus, AssetVerification, QrDecodeReport, StaleQrReport, QrRegenerationJobResponse, UpdateQrRedirectRequest,
+        CreateDocumentQrRequest, DocumentQrResponse, CreateShareOfferQrRequest, ShareOfferQrResponse, BookingQrResponse,
+        CreateExperimentRequest, LandingVariantRequest, LandingVariant, ExperimentResponse, ExperimentReport, VariantStats,
+        ScanResponse, RedirectUrls, PropertySummary, SendListingSmsRequest, FunnelBeaconRequest, JoinWaitlistRequest,
+        ScanAnalyticsResponse, SystemAnalyticsResponse, PropertyAnalyticsSnapshot, CampaignStats,
+        QrVersionStats, ScanHeatmap, HeatmapCell, HourlyScanDistribution, DailyScanCount, MonthlyScanCount, PropertyPerformance, FunnelStats, FunnelStage, ScanOutcome, ClickThroughRates,
+        AnalyticsComparison, TagComparison, PropertyComparison, PublicAreaStats, AreaStats, PrivacyNotice,
+        UpsertGeoBlockPolicyRequest, GeoBlockPolicyResponse, GeoBlockScope,
+        UpsertScanCapRequest, ScanCapResponse, WaitlistEntryResponse, WaitlistReason,
+        EligibilityReport, IneligibilityGroup, IneligibilityReason, IneligibleProperty, PropertyListItem, PropertyStats,
+        UpsertQrStyleRequest, QrStyleResponse, QrPayload, AgentContact, WifiNetwork, WifiSecurity,
+        UpsertAutoRedirectConfigRequest, AutoRedirectConfigResponse, AutoRedirectDestination, DestinationWeight,
+        SubscribeHookRequest, HookSubscriptionResponse, HookEvent,
+        CreateShortLinkRequest, UpdateShortLinkRequest, ShortLinkResponse,
+        UpsertTrackingConfigRequest, TrackingConfigResponse,
+        OrganizationResponse, OrgAnalyticsSummary, OrgPropertyScans, BrandingProfile,
+        OwnerQrListing, OwnerQrCode, DigestPreferenceResponse, UpdateDigestPreferenceRequest,
+        AuditLogPage, AuditEntryResponse, AuditAction, AuditActor, ActorKind,
+        HealthResponse, DetailedHealthResponse, ErrorResponse, FieldError,
+    )),
+    tags(
+        (name = "qr", description = "QR code generation and management"),
+        (name = "scan", description = "Public scan redirects and landing page actions"),
+        (name = "analytics", description = "Property analytics and historical snapshots"),
+        (name = "geo-blocking", description = "Per-owner and per-property country blocking for scans"),
+        (name = "auto-redirect", description = "Where the dual landing page sends visitors on its own, with weighted experiments"),
+        (name = "scan-caps", description = "Daily scan caps and auto-pause for limited-release listings"),
+        (name = "waitlist", description = "Prospects waiting on sold or capped listings, with CSV export"),
+        (name = "hooks", description = "REST hook subscriptions for no-code integrations"),
+        (name = "organizations", description = "Agency-wide QR management and analytics across member owners"),
+        (name = "links", description = "Short marketing links"),
+        (name = "tracking", description = "GA4 / Meta Pixel forwarding config"),
+        (name = "owners", description = "An owner's QR codes and scan totals for the app's \"My listings\" page, and their weekly digest email"),
+        (name = "audit", description = "Who generated, regenerated, deleted or deactivated each QR code"),
+        (name = "health", description = "Health checks and probes"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Swagger UI and the raw OpenAPI spec
+/// Served at /api/v1/docs and /api/v1/openapi.json
+pub fn docs_routes() -> Router {
+    SwaggerUi::new("/api/v1/docs")
+        .url("/api/v1/openapi.json", ApiDoc::openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_spec_covers_routes() {
+        let spec = ApiDoc::openapi();
+
+        for path in [
+            "/api/v1/qr/generate/{property_id}",
+            "/api/v1/qr/{property_id}/refresh-metadata",
+            "/api/v1/qr/search",
+            "/api/v1/qr/export",
+            "/api/v1/qr/stickers",
+            "/api/v1/qr/document",
+            "/api/v1/qr/share-offer",
+            "/api/v1/qr/{property_id}/share-offers",
+            "/api/v1/qr/{property_id}/experiments",
+            "/api/v1/qr/{property_id}/experiments/{experiment_id}/end",
+            "/api/v1/qr/booking/{booking_id}",
+            "/api/v1/qr/styles/{name}",
+            "/api/scan/{property_id}",
+            "/scan/{property_id}/go/{outcome}",
+            "/scan/{property_id}/documents/{document_id}",
+            "/scan/{property_id}/shares/{offer_id}",
+            "/checkin/{token}",
+            "/api/v1/links/{link_id}",
+            "/api/v1/analytics/properties/{property_id}/history",
+            "/api/v1/analytics/properties/{property_id}/experiments",
+            "/api/v1/analytics/properties/{property_id}/heatmap",
+            "/api/v1/analytics/properties/{property_id}/hourly",
+            "/api/v1/analytics/hourly",
+            "/api/v1/analytics/properties/{property_id}/trends",
+            "/api/v1/analytics/properties/{property_id}/trends/monthly",
+            "/api/v1/analytics/top-properties",
+            "/api/v1/analytics/campaigns",
+            "/api/v1/analytics/compare",
+            "/api/v1/stats/areas",
+            "/api/v1/properties",
+            "/api/v1/properties/stats",
+            "/api/v1/properties/eligibility",
+            "/api/v1/geo-blocks/{scope}/{scope_id}",
+            "/api/v1/orgs/{org_id}/analytics",
+            "/api/v1/owners/{owner_id}/qr",
+            "/api/v1/owners/{owner_id}/digest",
+            "/api/v1/audit",
+            "/metrics",
+            "/storage/{key}",
+            "/api/v1/qr/{property_id}/verify-asset",
+            "/api/v1/qr/decode",
+            "/api/v1/qr/{property_id}/image",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+        }
+        let schemas = spec.components.expect("components").schemas;
+        assert!(schemas.contains_key("QrCodeResponse"));
+        assert!(schemas.contains_key("ScanAnalyticsResponse"));
+    }
+}
@@ -1,6 +1,8 @@
  // src/routes/mod.rs
 
 pub mod api;
+pub mod docs;
 
 // Re-export route functions
-pub use api::{qr_routes, scan_routes, health_routes};
+pub use api::{admin_routes, analytics_routes, audit_routes, auto_redirect_routes, public_stats_routes, geo_block_routes, graphql_routes, qr_routes, property_routes, qr_style_routes, scan_cap_routes, scan_routes, waitlist_routes, organization_routes, owner_routes, health_routes, hook_routes, metrics_routes, storage_routes, link_routes, short_link_routes};
+pub use docs::{docs_routes, ApiDoc};
@@ -2,25 +2,88 @@
 
 use crate::models::{
     ScanEvent, PropertyScanAnalytics, SystemAnalytics, ScanSource, RedirectType, 
-    DeviceInfo, GeoLocation, CountryStats, DailyScanCount, PropertyPerformance,
-    QrGenerationStats, PeriodStats, PeriodComparison,
-    ScanAnalyticsResponse, SystemAnalyticsResponse
+    DeviceInfo, DeviceBreakdown, GeoLocation, CountryStats, AreaStats, DailyScanCount, PropertyPerformance,
+    QrGenerationStats, PeriodStats, PeriodComparison, DailyScanCounter,
+    ClickThroughRates, ConversionEvent, ScanExperiment, ConversionType, FailedAnalyticsUpdate, FunnelEvent, FunnelStage, FunnelStats, RetentionReport, PropertyAnalyticsSnapshot,
+    GeoBlockPolicy, MonthlyScanCount, PropertyRollup, UtmParameters, CampaignStats, QrVersionStats, HeatmapCell, ScanHeatmap, HourlyScanDistribution, OrgPropertyScans, PropertyScanActivity, PropertyWeekScans, PropertyComparison, TagComparison, SCAN_EVENT_SCHEMA_VERSION,
+    ScanAnalyticsResponse, SystemAnalyticsResponse, HookEvent, ScanHookPayload
 };
+use crate::config::RetentionConfig;
+use crate::services::{AnalyticsQueue, EventPublisher, GeolocationService, HookService, LoadShedder, PropertyService};
+use crate::services::analytics_worker::AnalyticsWorkerMetrics;
+use crate::services::geolocation_service::GeolocationMetrics;
+use crate::services::load_shedder::LoadShedderMetrics;
 use futures_util::stream::TryStreamExt;
-use chrono::{DateTime, Utc, Duration, Datelike};
+use chrono::{DateTime, NaiveDate, Utc, Duration, Datelike, Months};
 use mongodb::{
-    bson::{doc, oid::ObjectId, DateTime as BsonDateTime},
-    Collection, Database, options::{ReplaceOptions, FindOptions},
+    bson::{doc, oid::ObjectId, to_bson, DateTime as BsonDateTime, Document},
+    Collection, Database, IndexModel,
+    options::{IndexOptions, ReplaceOptions, FindOptions, UpdateOptions},
 };
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tracing::{info, warn, error};
 
+// Days of daily counters the periodic reconciliation rebuilds from raw events
+const RECONCILE_WINDOW_DAYS: i64 = 2;
+
+// Ranges longer than this read the per-property rollups instead of raw scan events
+const ROLLUP_THRESHOLD_DAYS: i64 = 30;
+
+// Window of dual page clicks behind a property's click-through rates
+const CLICK_THROUGH_WINDOW_DAYS: i64 = 30;
+
+// Failed aggregate updates are retried with backoff, up to this many times
+const DEAD_LETTER_MAX_ATTEMPTS: i32 = 10;
+const DEAD_LETTER_MAX_BACKOFF_MINUTES: i64 = 6 * 60;
+
 #[derive(Clone)]
 pub struct AnalyticsService {
     scan_events: Collection<ScanEvent>,
     property_analytics: Collection<PropertyScanAnalytics>,
+    snapshots: Collection<PropertyAnalyticsSnapshot>,
     system_analytics: Collection<SystemAnalytics>,
+    daily_counters: Collection<DailyScanCounter>,
+    daily_rollups: Collection<PropertyRollup>,
+    monthly_rollups: Collection<PropertyRollup>,
+    conversions: Collection<ConversionEvent>,
+    funnel_events: Collection<FunnelEvent>,
+    failed_updates: Collection<FailedAnalyticsUpdate>, // Dead letters for the retry job
+    dead_letters: Arc<DeadLetterCounters>,
+    hooks: Option<HookService>,
+    properties: Option<PropertyService>,
+    // Bounded queue for per-scan aggregate updates; applied inline when absent
+    queue: Option<AnalyticsQueue>,
+    load_shedder: Option<LoadShedder>,
+    geolocation: Option<GeolocationService>,
+    event_publisher: Option<EventPublisher>,
+}
+
+#[derive(Default)]
+struct DeadLetterCounters {
+    queued: AtomicU64,         // Scans whose aggregate update failed and were queued for retry
+    recovered: AtomicU64,      // ... and were applied by a later retry
+    retry_failures: AtomicU64, // Retries that failed again
+    lost: AtomicU64,           // Scans that couldn't even be queued
+}
+
+/// Dead-letter counters since startup
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetterMetrics {
+    pub queued: u64,
+    pub recovered: u64,
+    #[serde(rename = "retryFailures")]
+    pub retry_failures: u64,
+    pub lost: u64,
+}
+
+// Wait before the next retry of a dead letter: doubling from a minute, capped
+fn dead_letter_backoff(attempts: i32) -> Duration {
+    let minutes = 1i64.checked_shl(attempts.clamp(0, 30) as u32).unwrap_or(i64::MAX);
+    Duration::minutes(minutes.min(DEAD_LETTER_MAX_BACKOFF_MINUTES))
 }
 
 // Helper function to convert chrono DateTime to BSON DateTime
@@ -28,7 +91,278 @@ fn utc_to_bson(dt: chrono::DateTime<Utc>) -> BsonDateTime {
     BsonDateTime::from_millis(dt.timestamp_millis())
 }
 
+// Start of the UTC day `days` before `now`, so whole days are kept or removed together
+fn retention_cutoff(now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+    (now - Duration::days(days))
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
 // Helper function to convert BSON DateTime to chrono DateTime
+/// Turn `$group` rows keyed by UTM tags into campaign stats with each row's share of scans
+fn campaign_stats(rows: &[Document]) -> Vec<CampaignStats> {
+    let total: i64 = rows.iter().map(|row| row.get_i64("scans").unwrap_or(0)).sum();
+
+    rows.iter()
+        .map(|row| {
+            let key = row.get_document("_id").ok();
+            let tag = |name: &str| key.and_then(|key| key.get_str(name).ok()).map(|value| value.to_string());
+            let scans = row.get_i64("scans").unwrap_or(0);
+
+            CampaignStats {
+                campaign: tag("campaign"),
+                source: tag("source"),
+                medium: tag("medium"),
+                scans,
+                unique_visitors: row.get_i32("uniqueVisitors").map(i64::from).unwrap_or(0),
+                percentage: if total > 0 { scans as f64 / total as f64 * 100.0 } else { 0.0 },
+            }
+        })
+        .collect()
+}
+
+// Scans a worker batch adds to one property's day or month
+struct RollupIncrement {
+    property_id: String,
+    period: String,
+    scans: i64,
+    successful_scans: i64,
+}
+
+impl RollupIncrement {
+    fn add(increments: &mut HashMap<String, RollupIncrement>, scan_event: &ScanEvent, period: String) {
+        let increment = increments
+            .entry(PropertyRollup::id_for(&scan_event.property_id, &period))
+            .or_insert_with(|| RollupIncrement {
+                property_id: scan_event.property_id.clone(),
+                period,
+                scans: 0,
+                successful_scans: 0,
+            });
+        increment.scans += 1;
+        if scan_event.redirect_success {
+            increment.successful_scans += 1;
+        }
+    }
+}
+
+/// Turn a `$group` row keyed by property and period into a rollup; visitors without an ID aren't counted
+fn property_rollup(row: &Document, now: DateTime<Utc>) -> Option<PropertyRollup> {
+    let key = row.get_document("_id").ok()?;
+    let property_id = key.get_str("propertyId").ok()?.to_string();
+    let period = key.get_str("period").ok()?.to_string();
+    let unique_visitors = row.get_array("visitors")
+        .map(|visitors| visitors.iter().filter(|visitor| visitor.as_str().is_some()).count() as i64)
+        .unwrap_or(0);
+
+    Some(PropertyRollup {
+        id: PropertyRollup::id_for(&property_id, &period),
+        property_id,
+        period,
+        scans: row.get_i64("scans").unwrap_or(0),
+        successful_scans: row.get_i64("successfulScans").unwrap_or(0),
+        unique_visitors,
+        updated_at: now,
+    })
+}
+
+/// Merge a rollup rebuilt from raw events into the stored one without lowering any count, since
+/// retention may already have purged the raw events behind part of the period
+fn rollup_rebuild_update(rollup: &PropertyRollup) -> Document {
+    doc! {
+        "$max": {
+            "scans": rollup.scans,
+            "successfulScans": rollup.successful_scans,
+            "uniqueVisitors": rollup.unique_visitors
+        },
+        "$set": { "updatedAt": utc_to_bson(rollup.updated_at) },
+        "$setOnInsert": { "propertyId": &rollup.property_id, "period": &rollup.period }
+    }
+}
+
+/// Turn top-performer rows into performances, named after their IDs until details are resolved
+fn property_performances(rows: &[Document]) -> Vec<PropertyPerformance> {
+    // `$sum` and `$size` give 32-bit ints for small counts
+    let count = |row: &Document, key: &str| {
+        row.get_i64(key).or_else(|_| row.get_i32(key).map(i64::from)).unwrap_or(0)
+    };
+
+    rows.iter()
+        .map(|row| {
+            let property_id = row.get_str("propertyId").unwrap_or("").to_string();
+            PropertyPerformance {
+                property_name: format!("Property {}", property_id),
+                property_id,
+                location: None,
+                total_scans: count(row, "totalScans"),
+                unique_scans: count(row, "uniqueScans"),
+                success_rate: row.get_f64("successRate").unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+/// Turn `$group` rows keyed by QR version into per-version stats
+fn qr_version_stats(rows: &[Document]) -> Vec<QrVersionStats> {
+    rows.iter()
+        .map(|row| QrVersionStats {
+            qr_version: row.get_i32("_id").unwrap_or(1),
+            scans: row.get_i64("scans").unwrap_or(0),
+            outdated_scans: row.get_i64("outdatedScans").unwrap_or(0),
+            last_scanned_at: row.get_datetime("lastScannedAt").ok().map(|dt| bson_to_utc(*dt)),
+        })
+        .collect()
+}
+
+/// Turn `$group` rows keyed by property into per-property scan totals
+fn org_property_scans(rows: &[Document]) -> Vec<OrgPropertyScans> {
+    rows.iter()
+        .map(|row| OrgPropertyScans {
+            property_id: row.get_str("_id").unwrap_or_default().to_string(),
+            scans: row.get_i64("scans").unwrap_or(0),
+            unique_visitors: row.get_i32("uniqueVisitors").map(i64::from).unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Turn `$group` rows keyed by country, region and city into per-neighborhood counts
+fn area_stats(rows: &[Document]) -> Vec<AreaStats> {
+    rows.iter()
+        .filter_map(|row| {
+            let area = row.get_document("_id").ok()?;
+            Some(AreaStats {
+                country: area.get_str("country").ok()?.to_string(),
+                region: area.get_str("region").ok().map(str::to_string),
+                city: area.get_str("city").ok()?.to_string(),
+                scans: row.get_i64("scans").unwrap_or(0),
+                unique_visitors: row.get_i32("uniqueVisitors").map(i64::from).unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// Turn `$group` rows keyed by rounded coordinates into heatmap cells, busiest first
+fn scan_heatmap(property_id: &str, days: i64, precision: u32, rows: &[Document]) -> ScanHeatmap {
+    let mut cells: Vec<HeatmapCell> = rows
+        .iter()
+        .filter_map(|row| {
+            let cell = row.get_document("_id").ok()?;
+            Some(HeatmapCell {
+                lat: cell.get_f64("lat").ok()?,
+                lng: cell.get_f64("lng").ok()?,
+                scans: row.get_i64("scans").unwrap_or(0),
+            })
+        })
+        .collect();
+    cells.sort_by_key(|cell| std::cmp::Reverse(cell.scans));
+
+    ScanHeatmap {
+        property_id: property_id.to_string(),
+        days,
+        precision,
+        max_scans: cells.first().map(|cell| cell.scans).unwrap_or(0),
+        cells,
+    }
+}
+
+/// Turn `$group` rows keyed by ISO day of week and hour into a week-by-hour grid
+fn hourly_distribution(
+    property_id: Option<&str>,
+    days: i64,
+    timezone: &str,
+    rows: &[Document],
+) -> HourlyScanDistribution {
+    let mut grid = vec![vec![0i64; 24]; 7];
+    for row in rows {
+        let Ok(slot) = row.get_document("_id") else { continue };
+        let (Ok(day), Ok(hour)) = (slot.get_i32("day"), slot.get_i32("hour")) else { continue };
+        if (1..=7).contains(&day) && (0..24).contains(&hour) {
+            grid[(day - 1) as usize][hour as usize] += row.get_i64("scans").unwrap_or(0);
+        }
+    }
+
+    let by_hour: Vec<i64> = (0..24).map(|hour| grid.iter().map(|day| day[hour]).sum()).collect();
+    let by_day_of_week = grid.iter().map(|day| day.iter().sum()).collect();
+    let peak_hour = by_hour.iter()
+        .enumerate()
+        .filter(|(_, scans)| **scans > 0)
+        .max_by_key(|(hour, scans)| (**scans, std::cmp::Reverse(*hour)))
+        .map(|(hour, _)| hour as u32);
+
+    HourlyScanDistribution {
+        property_id: property_id.map(str::to_string),
+        days,
+        timezone: timezone.to_string(),
+        by_hour,
+        by_day_of_week,
+        grid,
+        peak_hour,
+    }
+}
+
+/// Turn one property's `$facet` of totals, device counts and per-day counts into a comparison
+/// row, filling in days without scans
+fn property_comparison(property_id: &str, from: NaiveDate, to: NaiveDate, facets: &Document) -> PropertyComparison {
+    let facet = |name: &str| -> Vec<Document> {
+        facets.get_array(name)
+            .map(|rows| rows.iter().filter_map(|row| row.as_document().cloned()).collect())
+            .unwrap_or_default()
+    };
+
+    let totals = facet("totals").into_iter().next().unwrap_or_default();
+
+    let mut device_breakdown = DeviceBreakdown { mobile: 0, desktop: 0, tablet: 0, unknown: 0 };
+    for row in facet("devices") {
+        let count = row.get_i64("count").unwrap_or(0);
+        match row.get_str("_id").unwrap_or("") {
+            "mobile" => device_breakdown.mobile += count,
+            "desktop" => device_breakdown.desktop += count,
+            "tablet" => device_breakdown.tablet += count,
+            _ => device_breakdown.unknown += count,
+        }
+    }
+
+    let counts: HashMap<String, i64> = facet("days")
+        .iter()
+        .filter_map(|row| Some((row.get_str("_id").ok()?.to_string(), row.get_i64("count").unwrap_or(0))))
+        .collect();
+    let daily_scans = from.iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let count = counts.get(&date).copied().unwrap_or(0);
+            DailyScanCount { date, count }
+        })
+        .collect();
+
+    PropertyComparison {
+        property_id: property_id.to_string(),
+        scans: totals.get_i64("scans").unwrap_or(0),
+        unique_visitors: totals.get_i32("uniqueVisitors").map(i64::from).unwrap_or(0),
+        device_breakdown,
+        daily_scans,
+    }
+}
+
+/// Turn `$group` rows keyed by funnel stage into per-stage counts
+fn funnel_stats(rows: &[Document]) -> FunnelStats {
+    let mut stats = FunnelStats::default();
+    for row in rows {
+        let count = row.get_i64("count").unwrap_or(0);
+        match row.get_str("_id").unwrap_or("") {
+            "page_view" => stats.page_views = count,
+            "auto_redirect" => stats.auto_redirects = count,
+            "manual_click_property" => stats.manual_click_property = count,
+            "manual_click_blockchain" => stats.manual_click_blockchain = count,
+            "manual_click_payment" => stats.manual_click_payment = count,
+            _ => {}
+        }
+    }
+    stats
+}
+
 fn bson_to_utc(dt: BsonDateTime) -> chrono::DateTime<Utc> {
     chrono::DateTime::from_timestamp_millis(dt.timestamp_millis())
         .unwrap_or_else(|| Utc::now())
@@ -40,30 +374,96 @@ impl AnalyticsService {
         Self {
             scan_events: db.collection("scan_events"),
             property_analytics: db.collection("property_analytics"),
+            snapshots: db.collection("property_analytics_snapshots"),
             system_analytics: db.collection("system_analytics"),
+            daily_counters: db.collection("daily_scan_counters"),
+            daily_rollups: db.collection("property_daily_stats"),
+            monthly_rollups: db.collection("property_monthly_stats"),
+            conversions: db.collection("conversions"),
+            funnel_events: db.collection("funnel_events"),
+            failed_updates: db.collection("failed_analytics_updates"),
+            dead_letters: Arc::new(DeadLetterCounters::default()),
+            hooks: None,
+            properties: None,
+            queue: None,
+            load_shedder: None,
+            geolocation: None,
+            event_publisher: None,
         }
     }
 
+    /// Forward recorded scans to REST hook subscribers
+    pub fn with_hooks(mut self, hooks: HookService) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Resolve property names and locations in analytics via the property service
+    pub fn with_property_service(mut self, properties: PropertyService) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Skip enrichment and defer scan writes when the load shedder says the runtime is saturated
+    pub fn with_load_shedder(mut self, load_shedder: LoadShedder) -> Self {
+        self.load_shedder = Some(load_shedder);
+        self
+    }
+
+    /// Resolve scan IPs to a country and city through the configured provider
+    pub fn with_geolocation(mut self, geolocation: GeolocationService) -> Self {
+        self.geolocation = Some(geolocation);
+        self
+    }
+
+    /// Stream every stored scan event, bots and failures included, to Kafka or NATS
+    pub fn with_event_publisher(mut self, event_publisher: EventPublisher) -> Self {
+        self.event_publisher = Some(event_publisher);
+        self
+    }
+
+    /// Apply per-scan aggregate updates on a background worker in batches.
+    /// Call last: the worker runs on a copy of the service as configured so far.
+    pub fn with_worker(mut self, capacity: usize, batch_size: usize) -> Self {
+        self.queue = Some(AnalyticsQueue::spawn(self.clone(), capacity, batch_size));
+        self
+    }
+
     /// Record a new scan event
     pub async fn record_scan(
         &self,
         property_id: String,
         qr_version: i32,
+        current_qr_version: Option<i32>, // None when the scan didn't come from a printed code
         scan_source: ScanSource,
         redirect_type: RedirectType,
         user_agent: Option<String>,
         ip_address: Option<String>,
         session_id: Option<String>,
+        visitor_id: Option<String>,
         referrer: Option<String>,
+        utm: Option<UtmParameters>,
+        experiment: Option<ScanExperiment>, // Landing page variant the scan is shown
     ) -> Result<ObjectId, mongodb::error::Error> {
         let start_time = std::time::Instant::now();
 
+        // Enrichment is the first thing dropped when the runtime falls behind
+        let enrich = !self.load_shedder.as_ref().is_some_and(|shedder| shedder.shed_enrichment());
+
         // Parse device info from user agent
         let device_info = user_agent.as_ref()
+            .filter(|_| enrich)
             .map(|ua| DeviceInfo::from_user_agent(ua));
 
-        // TODO: Get geolocation from IP address (would use external service)
-        let geolocation = self.get_geolocation_from_ip(ip_address.as_deref()).await;
+        let geolocation = match enrich {
+            true => self.get_geolocation_from_ip(ip_address.as_deref()).await,
+            false => None,
+        };
+
+        // Identify the visitor by cookie, falling back to an IP+UA hash
+        let visitor_id = visitor_id.unwrap_or_else(|| {
+            ScanEvent::visitor_hash(ip_address.as_deref(), user_agent.as_deref())
+        });
 
         // Create scan event
         let mut scan_event = ScanEvent::new(
@@ -72,7 +472,12 @@ impl AnalyticsService {
             scan_source,
             redirect_type,
         )
-        .with_request_data(user_agent, ip_address, session_id, referrer);
+        .with_request_data(user_agent, ip_address, session_id, referrer)
+        .with_visitor_id(visitor_id);
+
+        if let Some(current_qr_version) = current_qr_version {
+            scan_event = scan_event.with_current_qr_version(current_qr_version);
+        }
 
         if let Some(device_info) = device_info {
             scan_event = scan_event.with_device_info(device_info);
@@ -82,31 +487,50 @@ impl AnalyticsService {
             scan_event = scan_event.with_geolocation(geolocation);
         }
 
+        if let Some(utm) = utm {
+            scan_event = scan_event.with_utm(utm);
+        }
+
+        if let Some(experiment) = experiment {
+            scan_event = scan_event.with_experiment(experiment);
+        }
+
         let response_time = start_time.elapsed().as_millis() as u64;
         scan_event = scan_event.with_response_time(response_time);
 
+        // Under heavier load the worker writes the event along with its aggregates;
+        // if it has stopped, fall through and write inline
+        if let (Some(shedder), Some(queue)) = (&self.load_shedder, &self.queue) {
+            if shedder.shed_persistence() && queue.enqueue_deferred(scan_event.clone()).await {
+                self.dispatch_scan_hook(&scan_event);
+                return Ok(scan_event.id);
+            }
+        }
+
         // Insert scan event
         let result = self.scan_events.insert_one(&scan_event).await?;
         let scan_id = result.inserted_id.as_object_id().unwrap();
+        self.publish_scan_event(&scan_event);
 
-        // Update property analytics asynchronously
-        let analytics_service = self.clone();
-        let property_id_clone = property_id.clone();
-        let scan_event_clone = scan_event.clone();
-        
-        tokio::spawn(async move {
-            if let Err(e) = analytics_service.update_property_analytics(&property_id_clone, &scan_event_clone).await {
-                error!("Failed to update property analytics: {}", e);
-            }
-        });
+        // Bot hits are kept as raw events but never reach the aggregates
+        if scan_event.is_bot {
+            info!("Recorded bot scan for property {} with ID {}", property_id, scan_id);
+            return Ok(scan_id);
+        }
 
-        // Update system analytics asynchronously
-        let analytics_service = self.clone();
-        tokio::spawn(async move {
-            if let Err(e) = analytics_service.update_system_analytics().await {
-                error!("Failed to update system analytics: {}", e);
+        // Notify REST hook subscribers
+        self.dispatch_scan_hook(&scan_event);
+
+        // Update aggregates on the worker, or inline if it isn't running
+        let queued = match &self.queue {
+            Some(queue) => queue.enqueue(scan_event.clone()).await,
+            None => false,
+        };
+        if !queued {
+            if let Err(e) = self.apply_scan_batch(std::slice::from_ref(&scan_event)).await {
+                error!("Failed to update scan aggregates: {}", e);
             }
-        });
+        }
 
         info!("Recorded scan for property {} with ID {}", property_id, scan_id);
         Ok(scan_id)
@@ -123,6 +547,7 @@ impl AnalyticsService {
     ) -> Result<ObjectId, mongodb::error::Error> {
         let device_info = user_agent.as_ref()
             .map(|ua| DeviceInfo::from_user_agent(ua));
+        let visitor_id = ScanEvent::visitor_hash(ip_address.as_deref(), user_agent.as_deref());
 
         let mut scan_event = ScanEvent::new(
             property_id.clone(),
@@ -131,6 +556,7 @@ impl AnalyticsService {
             RedirectType::Failed,
         )
         .with_request_data(user_agent, ip_address, None, None)
+        .with_visitor_id(visitor_id)
         .mark_failed()
         .add_metadata("error_reason".to_string(), Value::String(error_reason));
 
@@ -140,11 +566,125 @@ impl AnalyticsService {
 
         let result = self.scan_events.insert_one(&scan_event).await?;
         let scan_id = result.inserted_id.as_object_id().unwrap();
+        self.publish_scan_event(&scan_event);
 
         warn!("Recorded failed scan for property {} with ID {}", property_id, scan_id);
         Ok(scan_id)
     }
 
+    /// Record a scan refused by a geo-blocking policy
+    pub async fn record_blocked_scan(
+        &self,
+        property_id: String,
+        policy: &GeoBlockPolicy,
+        geolocation: Option<GeoLocation>,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<ObjectId, mongodb::error::Error> {
+        let visitor_id = ScanEvent::visitor_hash(ip_address.as_deref(), user_agent.as_deref());
+        let country = geolocation.as_ref()
+            .and_then(|location| location.country.clone())
+            .map(Value::String)
+            .unwrap_or(Value::Null);
+
+        let mut scan_event = ScanEvent::new(
+            property_id.clone(),
+            1,
+            ScanSource::QrCode,
+            RedirectType::Failed,
+        )
+        .with_request_data(user_agent, ip_address, None, None)
+        .with_visitor_id(visitor_id)
+        .mark_failed()
+        .add_metadata("error_reason".to_string(), Value::String("geo_blocked".to_string()))
+        .add_metadata("blocked_by".to_string(), Value::String(policy.scope.as_str().to_string()))
+        .add_metadata("blocked_country".to_string(), country);
+
+        if let Some(geolocation) = geolocation {
+            scan_event = scan_event.with_geolocation(geolocation);
+        }
+
+        let result = self.scan_events.insert_one(&scan_event).await?;
+        let scan_id = result.inserted_id.as_object_id().unwrap();
+        self.publish_scan_event(&scan_event);
+
+        info!("Recorded geo-blocked scan for property {} with ID {}", property_id, scan_id);
+        Ok(scan_id)
+    }
+
+    /// Locate a visitor for policy checks; unlike enrichment this is never shed
+    pub async fn locate(&self, ip_address: &str) -> Option<GeoLocation> {
+        self.get_geolocation_from_ip(Some(ip_address)).await
+    }
+
+    /// Record a landing-page conversion for a property
+    pub async fn record_conversion(
+        &self,
+        property_id: String,
+        conversion_type: ConversionType,
+        visitor_id: Option<String>,
+    ) -> Result<ObjectId, mongodb::error::Error> {
+        let conversion = ConversionEvent {
+            id: ObjectId::new(),
+            property_id,
+            conversion_type,
+            visitor_id,
+            created_at: Utc::now(),
+        };
+
+        self.conversions.insert_one(&conversion).await?;
+
+        info!("Recorded {:?} conversion for property {}", conversion.conversion_type, conversion.property_id);
+        Ok(conversion.id)
+    }
+
+    /// Record a landing-page funnel stage for a scan; a stage reported again for the
+    /// same scan (e.g. after a reload) is ignored. Returns whether it was new.
+    pub async fn record_funnel_event(
+        &self,
+        property_id: &str,
+        scan_id: ObjectId,
+        stage: FunnelStage,
+        visitor_id: Option<String>,
+    ) -> Result<bool, mongodb::error::Error> {
+        let result = self.funnel_events
+            .update_one(
+                doc! { "scanId": scan_id, "stage": stage.as_str() },
+                doc! {
+                    "$setOnInsert": {
+                        "propertyId": property_id,
+                        "visitorId": visitor_id,
+                        "createdAt": to_bson(&Utc::now())?
+                    }
+                },
+            )
+            .upsert(true)
+            .await?;
+
+        Ok(result.upserted_id.is_some())
+    }
+
+    /// How a property's landing page visitors moved on over the last `days` days
+    pub async fn get_funnel_stats(&self, property_id: &str, days: i64) -> Result<FunnelStats, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": property_id,
+                    "createdAt": { "$gte": to_bson(&(Utc::now() - Duration::days(days)))? }
+                }
+            },
+            doc! { "$group": { "_id": "$stage", "count": { "$sum": 1i64 } } },
+        ];
+
+        let mut cursor = self.funnel_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(funnel_stats(&rows))
+    }
+
     /// Get analytics for a specific property
     pub async fn get_property_analytics(
         &self,
@@ -152,7 +692,7 @@ impl AnalyticsService {
         include_recent_scans: bool,
     ) -> Result<ScanAnalyticsResponse, mongodb::error::Error> {
         // Get or create property analytics
-        let analytics = match self.property_analytics
+        let mut analytics = match self.property_analytics
             .find_one(doc! { "propertyId": property_id })
             .await?
         {
@@ -165,6 +705,9 @@ impl AnalyticsService {
             }
         };
 
+        let funnel = self.get_funnel_stats(property_id, CLICK_THROUGH_WINDOW_DAYS).await?;
+        analytics.click_through = ClickThroughRates::from(&funnel);
+
         // Get recent scans if requested
         let recent_scans = if include_recent_scans {
             self.scan_events
@@ -183,17 +726,80 @@ impl AnalyticsService {
 
         Ok(ScanAnalyticsResponse {
             property_id: property_id.to_string(),
+            returning_visitor_rate: analytics.returning_visitor_rate(),
             analytics,
             recent_scans,
         })
     }
 
+    /// A property's latest human scans, newest first
+    pub async fn recent_scans(&self, property_id: &str, limit: i64) -> Result<Vec<ScanEvent>, mongodb::error::Error> {
+        let options = FindOptions::builder()
+            .sort(doc! { "scannedAt": -1 })
+            .limit(limit)
+            .build();
+
+        self.scan_events
+            .find(doc! { "propertyId": property_id, "isBot": { "$ne": true } })
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Get a property's analytics as they stood at the end of `date`, using the latest
+    /// snapshot on or before it; today's snapshot reflects the last reconciliation
+    pub async fn get_property_analytics_history(
+        &self,
+        property_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<PropertyAnalyticsSnapshot>, mongodb::error::Error> {
+        // Snapshot dates are YYYY-MM-DD, so string order is date order
+        let options = FindOptions::builder()
+            .sort(doc! { "date": -1 })
+            .limit(1)
+            .build();
+
+        let mut snapshots: Vec<PropertyAnalyticsSnapshot> = self.snapshots
+            .find(doc! {
+                "propertyId": property_id,
+                "date": { "$lte": date.format("%Y-%m-%d").to_string() }
+            })
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(snapshots.pop())
+    }
+
+    /// Copy every property's cumulative analytics into the snapshot for `date`.
+    /// Re-running on the same day overwrites it, so the last run of the day wins.
+    pub async fn snapshot_property_analytics(&self, date: NaiveDate) -> Result<u64, mongodb::error::Error> {
+        let mut cursor = self.property_analytics.find(doc! {}).await?;
+        let options = ReplaceOptions::builder().upsert(true).build();
+        let mut snapshotted = 0;
+
+        while let Some(analytics) = cursor.try_next().await? {
+            let snapshot = PropertyAnalyticsSnapshot::new(analytics, date);
+            self.snapshots
+                .replace_one(doc! { "_id": &snapshot.id }, &snapshot)
+                .with_options(options.clone())
+                .await?;
+            snapshotted += 1;
+        }
+
+        info!("Snapshotted analytics for {} properties on {}", snapshotted, date);
+        Ok(snapshotted)
+    }
+
     /// Get system-wide analytics
     pub async fn get_system_analytics(
         &self,
         include_comparison: bool,
     ) -> Result<SystemAnalyticsResponse, mongodb::error::Error> {
-        let system_analytics = self.get_or_create_system_analytics().await?;
+        let mut system_analytics = self.get_or_create_system_analytics().await?;
+        self.apply_counter_totals(&mut system_analytics).await?;
 
         let period_comparison = if include_comparison {
             Some(self.calculate_period_comparison().await?)
@@ -207,12 +813,16 @@ impl AnalyticsService {
         })
     }
 
-    /// Get top performing properties
+    /// Get top performing properties; ranges past a month are read from the daily rollups
     pub async fn get_top_performing_properties(
         &self,
         limit: i64,
         days: i64,
     ) -> Result<Vec<PropertyPerformance>, mongodb::error::Error> {
+        if days > ROLLUP_THRESHOLD_DAYS {
+            return self.top_performers_from_rollups(limit, days).await;
+        }
+
         let since_date = utc_to_bson(Utc::now() - Duration::days(days));
 
         // Aggregate top properties by scan count
@@ -220,14 +830,15 @@ impl AnalyticsService {
             doc! {
                 "$match": {
                     "scannedAt": { "$gte": since_date },
-                    "redirectSuccess": true
+                    "redirectSuccess": true,
+                    "isBot": { "$ne": true }
                 }
             },
             doc! {
                 "$group": {
                     "_id": "$propertyId",
                     "totalScans": { "$sum": 1 },
-                    "uniqueScans": { "$addToSet": "$ipAddress" },
+                    "uniqueScans": { "$addToSet": "$visitorId" },
                     "successfulScans": {
                         "$sum": { "$cond": ["$redirectSuccess", 1, 0] }
                     }
@@ -251,36 +862,124 @@ impl AnalyticsService {
         ];
 
         let mut cursor = self.scan_events.aggregate(pipeline).await?;
-        let mut performances = Vec::new();
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
 
-        while cursor.advance().await? {
-            let doc = cursor.current();
-            let performance = PropertyPerformance {
-                property_id: doc.get_str("propertyId").unwrap_or("").to_string(),
-                property_name: format!("Property {}", doc.get_str("propertyId").unwrap_or("")), // TODO: Get from property service
-                total_scans: doc.get_i64("totalScans").unwrap_or(0),
-                unique_scans: doc.get_i64("uniqueScans").unwrap_or(0),
-                success_rate: doc.get_f64("successRate").unwrap_or(0.0),
-            };
-            performances.push(performance);
+        let mut performances = property_performances(&rows);
+        self.resolve_property_details(&mut performances).await;
+
+        Ok(performances)
+    }
+
+    /// Top performers summed from the daily rollups. Unique scans add up each day's
+    /// unique visitors, so a visitor seen on several days counts once per day.
+    async fn top_performers_from_rollups(
+        &self,
+        limit: i64,
+        days: i64,
+    ) -> Result<Vec<PropertyPerformance>, mongodb::error::Error> {
+        let since = (Utc::now().date_naive() - Duration::days(days)).format("%Y-%m-%d").to_string();
+
+        let pipeline = vec![
+            doc! { "$match": { "period": { "$gte": since } } },
+            doc! {
+                "$group": {
+                    "_id": "$propertyId",
+                    "scans": { "$sum": "$scans" },
+                    "totalScans": { "$sum": "$successfulScans" },
+                    "uniqueScans": { "$sum": "$uniqueVisitors" }
+                }
+            },
+            doc! {
+                "$project": {
+                    "propertyId": "$_id",
+                    "totalScans": 1,
+                    "uniqueScans": 1,
+                    "successRate": {
+                        "$cond": [
+                            { "$gt": ["$scans", 0] },
+                            { "$multiply": [{ "$divide": ["$totalScans", "$scans"] }, 100] },
+                            0.0
+                        ]
+                    }
+                }
+            },
+            doc! { "$sort": { "totalScans": -1 } },
+            doc! { "$limit": limit }
+        ];
+
+        let mut cursor = self.daily_rollups.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
         }
 
+        let mut performances = property_performances(&rows);
+        self.resolve_property_details(&mut performances).await;
+
         Ok(performances)
     }
 
-    /// Get scan trends for a property
+    /// Fill in property names and locations with one batched lookup
+    async fn resolve_property_details(&self, performances: &mut [PropertyPerformance]) {
+        let Some(properties) = &self.properties else {
+            return;
+        };
+
+        // Skip IDs that are not ObjectIds so one bad event doesn't fail the batch
+        let property_ids: Vec<String> = performances
+            .iter()
+            .filter(|p| ObjectId::parse_str(&p.property_id).is_ok())
+            .map(|p| p.property_id.clone())
+            .collect();
+
+        if property_ids.is_empty() {
+            return;
+        }
+
+        let found = match properties.get_properties_by_ids(property_ids).await {
+            Ok(found) => found,
+            Err(e) => {
+                warn!("Failed to resolve property names for top performers: {}", e);
+                return;
+            }
+        };
+
+        let details: HashMap<String, (String, String)> = found
+            .into_iter()
+            .map(|p| (p.id.to_hex(), (p.property_name, p.location)))
+            .collect();
+
+        for performance in performances.iter_mut() {
+            if let Some((name, location)) = details.get(&performance.property_id) {
+                performance.property_name = name.clone();
+                performance.location = Some(location.clone());
+            }
+        }
+    }
+
+    /// Get scan trends for a property; ranges past a month are read from the daily rollups
     pub async fn get_property_scan_trends(
         &self,
         property_id: &str,
         days: i64,
     ) -> Result<Vec<DailyScanCount>, mongodb::error::Error> {
+        if days > ROLLUP_THRESHOLD_DAYS {
+            let since = (Utc::now().date_naive() - Duration::days(days)).format("%Y-%m-%d").to_string();
+            let rollups = self.property_rollups(&self.daily_rollups, property_id, since).await?;
+            return Ok(rollups.into_iter().map(|rollup| DailyScanCount { date: rollup.period, count: rollup.scans }).collect());
+        }
+
         let since_date = utc_to_bson(Utc::now() - Duration::days(days));
 
         let pipeline = vec![
             doc! {
                 "$match": {
                     "propertyId": property_id,
-                    "scannedAt": { "$gte": since_date }
+                    "scannedAt": { "$gte": since_date },
+                    "isBot": { "$ne": true }
                 }
             },
             doc! {
@@ -291,7 +990,7 @@ impl AnalyticsService {
                             "date": "$scannedAt"
                         }
                     },
-                    "count": { "$sum": 1 }
+                    "count": { "$sum": 1i64 }
                 }
             },
             doc! { "$sort": { "_id": 1 } }
@@ -312,6 +1011,45 @@ impl AnalyticsService {
         Ok(trends)
     }
 
+    /// Human scans of a property per calendar month, for the last `months` months including this one
+    pub async fn get_property_monthly_trends(
+        &self,
+        property_id: &str,
+        months: u32,
+    ) -> Result<Vec<MonthlyScanCount>, mongodb::error::Error> {
+        let today = Utc::now().date_naive();
+        let since = today
+            .checked_sub_months(Months::new(months.saturating_sub(1)))
+            .unwrap_or(today)
+            .format("%Y-%m")
+            .to_string();
+
+        let rollups = self.property_rollups(&self.monthly_rollups, property_id, since).await?;
+        Ok(rollups
+            .into_iter()
+            .map(|rollup| MonthlyScanCount {
+                month: rollup.period,
+                count: rollup.scans,
+                unique_visitors: rollup.unique_visitors,
+            })
+            .collect())
+    }
+
+    // A property's rollups from `since` on, oldest first; periods sort as strings
+    async fn property_rollups(
+        &self,
+        collection: &Collection<PropertyRollup>,
+        property_id: &str,
+        since: String,
+    ) -> Result<Vec<PropertyRollup>, mongodb::error::Error> {
+        collection
+            .find(doc! { "propertyId": property_id, "period": { "$gte": since } })
+            .sort(doc! { "period": 1 })
+            .await?
+            .try_collect()
+            .await
+    }
+
     /// Get geographic distribution of scans
     pub async fn get_geographic_distribution(
         &self,
@@ -375,109 +1113,1164 @@ impl AnalyticsService {
         Ok(countries)
     }
 
-    /// Delete old scan events (data retention)
-    pub async fn cleanup_old_events(&self, retention_days: i64) -> Result<u64, mongodb::error::Error> {
-        let cutoff_date = utc_to_bson(Utc::now() - Duration::days(retention_days));
-        
-        let result = self.scan_events
-            .delete_many(doc! { "scannedAt": { "$lt": cutoff_date } })
-            .await?;
-
-        info!("Cleaned up {} old scan events", result.deleted_count);
-        Ok(result.deleted_count)
-    }
+    /// Human scans and unique visitors per neighborhood (country, region and city) across all properties
+    pub async fn get_area_distribution(&self, days: i64) -> Result<Vec<AreaStats>, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
 
-    /// Update property analytics with new scan event
-    async fn update_property_analytics(
-        &self,
-        property_id: &str,
-        scan_event: &ScanEvent,
-    ) -> Result<(), mongodb::error::Error> {
-        // Try to find existing analytics
-        let mut analytics = match self.property_analytics
-            .find_one(doc! { "propertyId": property_id })
-            .await?
-        {
-            Some(analytics) => analytics,
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "scannedAt": { "$gte": since_date },
+                    "isBot": { "$ne": true },
+                    "geolocation.country": { "$exists": true, "$ne": null },
+                    "geolocation.city": { "$exists": true, "$ne": null }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "country": "$geolocation.country",
+                        "region": "$geolocation.region",
+                        "city": "$geolocation.city"
+                    },
+                    "scans": { "$sum": 1i64 },
+                    "visitors": { "$addToSet": "$visitorId" }
+                }
+            },
+            doc! { "$addFields": { "uniqueVisitors": { "$size": "$visitors" } } },
+            doc! { "$project": { "visitors": 0 } },
+            doc! { "$sort": { "scans": -1 } }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(area_stats(&rows))
+    }
+
+    /// Break human scans down by UTM campaign, source and medium; untagged scans form one row
+    pub async fn get_campaign_breakdown(
+        &self,
+        property_id: Option<&str>,
+        days: i64,
+    ) -> Result<Vec<CampaignStats>, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
+
+        let mut match_doc = doc! {
+            "scannedAt": { "$gte": since_date },
+            "isBot": { "$ne": true },
+            "redirectSuccess": true
+        };
+
+        if let Some(property_id) = property_id {
+            match_doc.insert("propertyId", property_id);
+        }
+
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "campaign": "$utm.campaign",
+                        "source": "$utm.source",
+                        "medium": "$utm.medium"
+                    },
+                    "scans": { "$sum": 1i64 },
+                    "visitors": { "$addToSet": "$visitorId" }
+                }
+            },
+            doc! { "$addFields": { "uniqueVisitors": { "$size": "$visitors" } } },
+            doc! { "$project": { "visitors": 0 } },
+            doc! { "$sort": { "scans": -1 } },
+            doc! { "$limit": 50 }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(campaign_stats(&rows))
+    }
+
+    /// Break a property's human scans down by the QR version printed in the scanned code, newest first
+    pub async fn get_qr_version_breakdown(
+        &self,
+        property_id: &str,
+        days: i64,
+    ) -> Result<Vec<QrVersionStats>, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": property_id,
+                    "scannedAt": { "$gte": since_date },
+                    "isBot": { "$ne": true }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$qrVersion",
+                    "scans": { "$sum": 1i64 },
+                    "outdatedScans": { "$sum": { "$cond": ["$outdatedQr", 1i64, 0i64] } },
+                    "lastScannedAt": { "$max": "$scannedAt" }
+                }
+            },
+            doc! { "$sort": { "_id": -1 } }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(qr_version_stats(&rows))
+    }
+
+    /// Human scans of one property bucketed by where they came from, rounding coordinates to `precision` decimal places
+    pub async fn get_scan_heatmap(
+        &self,
+        property_id: &str,
+        days: i64,
+        precision: u32,
+    ) -> Result<ScanHeatmap, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
+        let places = precision as i32;
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": property_id,
+                    "scannedAt": { "$gte": since_date },
+                    "isBot": { "$ne": true },
+                    "geolocation.latitude": { "$type": "number" },
+                    "geolocation.longitude": { "$type": "number" }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "lat": { "$round": ["$geolocation.latitude", places] },
+                        "lng": { "$round": ["$geolocation.longitude", places] }
+                    },
+                    "scans": { "$sum": 1i64 }
+                }
+            }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(scan_heatmap(property_id, days, precision, &rows))
+    }
+
+    /// Human scans by hour of day and day of week in `timezone` (an IANA name or UTC offset), for one property or, with None, all of them
+    pub async fn get_hourly_distribution(
+        &self,
+        property_id: Option<&str>,
+        days: i64,
+        timezone: &str,
+    ) -> Result<HourlyScanDistribution, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
+
+        let mut match_doc = doc! {
+            "scannedAt": { "$gte": since_date },
+            "isBot": { "$ne": true }
+        };
+
+        if let Some(property_id) = property_id {
+            match_doc.insert("propertyId", property_id);
+        }
+
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "day": { "$isoDayOfWeek": { "date": "$scannedAt", "timezone": timezone } },
+                        "hour": { "$hour": { "date": "$scannedAt", "timezone": timezone } }
+                    },
+                    "scans": { "$sum": 1i64 }
+                }
+            }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(hourly_distribution(property_id, days, timezone, &rows))
+    }
+
+    /// Human scans of each of a set of properties, e.g. an organization's, most scanned first
+    pub async fn get_property_scan_totals(
+        &self,
+        property_ids: &[String],
+        days: i64,
+    ) -> Result<Vec<OrgPropertyScans>, mongodb::error::Error> {
+        let since_date = utc_to_bson(Utc::now() - Duration::days(days));
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": { "$in": property_ids },
+                    "scannedAt": { "$gte": since_date },
+                    "isBot": { "$ne": true }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$propertyId",
+                    "scans": { "$sum": 1i64 },
+                    "visitors": { "$addToSet": "$visitorId" }
+                }
+            },
+            doc! { "$addFields": { "uniqueVisitors": { "$size": "$visitors" } } },
+            doc! { "$project": { "visitors": 0 } },
+            doc! { "$sort": { "scans": -1 } }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(row);
+        }
+
+        Ok(org_property_scans(&rows))
+    }
+
+    /// Scans of every property scanned since the start of the baseline: within `spike_window`
+    /// and `silence_window` of `now`, and over `baseline` before the silence window. Bot scans
+    /// count, since a spike may be an attack the bot filter missed.
+    pub async fn get_scan_activity(
+        &self,
+        now: DateTime<Utc>,
+        spike_window: Duration,
+        silence_window: Duration,
+        baseline: Duration,
+    ) -> Result<Vec<PropertyScanActivity>, mongodb::error::Error> {
+        let spike_start = utc_to_bson(now - spike_window);
+        let silence_start = utc_to_bson(now - silence_window);
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "scannedAt": { "$gte": utc_to_bson(now - silence_window - baseline), "$lt": utc_to_bson(now) }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$propertyId",
+                    "spikeWindowScans": { "$sum": { "$cond": [{ "$gte": ["$scannedAt", spike_start] }, 1i64, 0i64] } },
+                    "silenceWindowScans": { "$sum": { "$cond": [{ "$gte": ["$scannedAt", silence_start] }, 1i64, 0i64] } },
+                    "baselineScans": { "$sum": { "$cond": [{ "$lt": ["$scannedAt", silence_start] }, 1i64, 0i64] } }
+                }
+            },
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(mongodb::bson::from_document(row)?);
+        }
+
+        Ok(rows)
+    }
+
+    /// Human scans of every scanned property in the seven days from `week_start`, with
+    /// the seven days before for comparison
+    pub async fn get_weekly_property_scans(
+        &self,
+        week_start: DateTime<Utc>,
+    ) -> Result<Vec<PropertyWeekScans>, mongodb::error::Error> {
+        let week_start_date = utc_to_bson(week_start);
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "scannedAt": {
+                        "$gte": utc_to_bson(week_start - Duration::days(7)),
+                        "$lt": utc_to_bson(week_start + Duration::days(7))
+                    },
+                    "isBot": { "$ne": true }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$propertyId",
+                    "scans": { "$sum": { "$cond": [{ "$gte": ["$scannedAt", week_start_date] }, 1i64, 0i64] } },
+                    "previousScans": { "$sum": { "$cond": [{ "$lt": ["$scannedAt", week_start_date] }, 1i64, 0i64] } }
+                }
+            },
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let mut rows = Vec::new();
+        while let Some(row) = cursor.try_next().await? {
+            rows.push(mongodb::bson::from_document(row)?);
+        }
+
+        Ok(rows)
+    }
+
+    /// Compare tags side by side over whole UTC days `from` through `to`; a scan counts
+    /// toward a tag when its UTM campaign, source or medium equals it
+    pub async fn compare_tags(
+        &self,
+        tags: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<TagComparison>, mongodb::error::Error> {
+        let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let end = to.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc() + Duration::days(1);
+
+        futures::future::try_join_all(tags.iter().map(|tag| self.tag_totals(tag, start, end))).await
+    }
+
+    async fn tag_totals(
+        &self,
+        tag: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<TagComparison, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "scannedAt": { "$gte": utc_to_bson(start), "$lt": utc_to_bson(end) },
+                    "isBot": { "$ne": true },
+                    "$or": [
+                        { "utm.campaign": tag },
+                        { "utm.source": tag },
+                        { "utm.medium": tag }
+                    ]
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": null,
+                    "scans": { "$sum": 1i64 },
+                    "visitors": { "$addToSet": "$visitorId" }
+                }
+            },
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let Some(totals) = cursor.try_next().await? else {
+            return Ok(TagComparison::new(tag.to_string(), 0, 0, 0));
+        };
+
+        let visitors = totals.get_array("visitors").cloned().unwrap_or_default();
+        let converted = if visitors.is_empty() {
+            0
+        } else {
+            self.conversions
+                .distinct("visitorId", doc! {
+                    "visitorId": { "$in": &visitors },
+                    "createdAt": { "$gte": utc_to_bson(start), "$lt": utc_to_bson(end) }
+                })
+                .await?
+                .len()
+        };
+
+        Ok(TagComparison::new(
+            tag.to_string(),
+            totals.get_i64("scans").unwrap_or(0),
+            visitors.len() as i64,
+            converted as i64,
+        ))
+    }
+
+    /// Scans, visitors, devices and daily trend of each property over the same window, in the order given
+    pub async fn compare_properties(
+        &self,
+        property_ids: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PropertyComparison>, mongodb::error::Error> {
+        futures::future::try_join_all(property_ids.iter().map(|property_id| self.property_totals(property_id, from, to))).await
+    }
+
+    async fn property_totals(
+        &self,
+        property_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<PropertyComparison, mongodb::error::Error> {
+        let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let end = to.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc() + Duration::days(1);
+
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": property_id,
+                    "scannedAt": { "$gte": utc_to_bson(start), "$lt": utc_to_bson(end) },
+                    "isBot": { "$ne": true }
+                }
+            },
+            doc! {
+                "$facet": {
+                    "totals": [
+                        { "$group": { "_id": null, "scans": { "$sum": 1i64 }, "visitors": { "$addToSet": "$visitorId" } } },
+                        { "$project": { "scans": 1, "uniqueVisitors": { "$size": { "$setDifference": ["$visitors", [null]] } } } }
+                    ],
+                    "devices": [
+                        { "$group": { "_id": "$deviceInfo.deviceType", "count": { "$sum": 1i64 } } }
+                    ],
+                    "days": [
+                        {
+                            "$group": {
+                                "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$scannedAt" } },
+                                "count": { "$sum": 1i64 }
+                            }
+                        }
+                    ]
+                }
+            },
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let facets = cursor.try_next().await?.unwrap_or_default();
+        Ok(property_comparison(property_id, from, to, &facets))
+    }
+
+    /// Remove raw events and aggregates older than the configured retention periods
+    pub async fn apply_retention(
+        &self,
+        config: &RetentionConfig,
+    ) -> Result<RetentionReport, mongodb::error::Error> {
+        let now = Utc::now();
+        let raw_event_cutoff = retention_cutoff(now, config.raw_event_days);
+        let aggregate_cutoff = retention_cutoff(now, config.aggregate_days);
+
+        let scan_filter = doc! { "scannedAt": { "$lt": utc_to_bson(raw_event_cutoff) } };
+        let conversion_filter = doc! { "createdAt": { "$lt": utc_to_bson(raw_event_cutoff) } };
+        let funnel_filter = doc! { "createdAt": { "$lt": to_bson(&raw_event_cutoff)? } };
+        // Counter IDs are YYYY-MM-DD, so string order is date order
+        let counter_filter = doc! { "_id": { "$lt": aggregate_cutoff.format("%Y-%m-%d").to_string() } };
+        // Properties with no scans inside the aggregate window
+        let property_filter = doc! { "lastUpdated": { "$lt": utc_to_bson(aggregate_cutoff) } };
+        let snapshot_filter = doc! { "date": { "$lt": aggregate_cutoff.format("%Y-%m-%d").to_string() } };
+        let rollup_filter = doc! { "period": { "$lt": aggregate_cutoff.format("%Y-%m-%d").to_string() } };
+
+        let report = RetentionReport {
+            dry_run: config.dry_run,
+            raw_event_cutoff,
+            aggregate_cutoff,
+            scan_events: self.purge(&self.scan_events, scan_filter, config.dry_run).await?,
+            conversions: self.purge(&self.conversions, conversion_filter, config.dry_run).await?,
+            funnel_events: self.purge(&self.funnel_events, funnel_filter, config.dry_run).await?,
+            daily_counters: self.purge(&self.daily_counters, counter_filter, config.dry_run).await?,
+            property_analytics: self.purge(&self.property_analytics, property_filter, config.dry_run).await?,
+            property_snapshots: self.purge(&self.snapshots, snapshot_filter, config.dry_run).await?,
+            property_daily_stats: self.purge(&self.daily_rollups, rollup_filter, config.dry_run).await?,
+        };
+
+        info!(
+            "Retention{}: {} scan events, {} conversions, {} funnel events, {} daily counters, {} property analytics, {} snapshots, {} daily rollups",
+            if report.dry_run { " (dry run, nothing removed)" } else { "" },
+            report.scan_events,
+            report.conversions,
+            report.funnel_events,
+            report.daily_counters,
+            report.property_analytics,
+            report.property_snapshots,
+            report.property_daily_stats
+        );
+
+        Ok(report)
+    }
+
+    /// Periodically apply the retention policy in the background
+    pub fn spawn_retention(&self, config: RetentionConfig) {
+        let analytics_service = self.clone();
+        let interval = std::time::Duration::from_secs(config.interval_hours.max(1) * 60 * 60);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = analytics_service.apply_retention(&config).await {
+                    error!("Analytics retention failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Count or delete the documents matching a retention filter
+    async fn purge<T: Send + Sync>(
+        &self,
+        collection: &Collection<T>,
+        filter: Document,
+        dry_run: bool,
+    ) -> Result<u64, mongodb::error::Error> {
+        if dry_run {
+            collection.count_documents(filter).await
+        } else {
+            Ok(collection.delete_many(filter).await?.deleted_count)
+        }
+    }
+
+    /// Notify REST hook subscribers of a human scan
+    fn dispatch_scan_hook(&self, scan_event: &ScanEvent) {
+        if scan_event.is_bot {
+            return;
+        }
+        if let Some(hooks) = &self.hooks {
+            if let Ok(payload) = serde_json::to_value(ScanHookPayload::from(scan_event)) {
+                hooks.dispatch(HookEvent::ScanCreated, &scan_event.property_id, payload);
+            }
+        }
+    }
+
+    /// Stream a stored scan event, if event streaming is on
+    fn publish_scan_event(&self, scan_event: &ScanEvent) {
+        if let Some(event_publisher) = &self.event_publisher {
+            event_publisher.publish(scan_event);
+        }
+    }
+
+    /// Write scan events whose persistence was deferred under load
+    pub(crate) async fn persist_scan_events(&self, scan_events: &[ScanEvent]) -> Result<(), mongodb::error::Error> {
+        self.scan_events.insert_many(scan_events).await?;
+        for scan_event in scan_events {
+            self.publish_scan_event(scan_event);
+        }
+        Ok(())
+    }
+
+    /// Apply a batch of recorded scans to per-property analytics and daily counters
+    pub(crate) async fn apply_scan_batch(&self, scan_events: &[ScanEvent]) -> Result<(), mongodb::error::Error> {
+        let mut by_property: HashMap<&str, Vec<&ScanEvent>> = HashMap::new();
+        let mut by_day: HashMap<String, i64> = HashMap::new();
+        let mut daily_rollups: HashMap<String, RollupIncrement> = HashMap::new();
+        let mut monthly_rollups: HashMap<String, RollupIncrement> = HashMap::new();
+        for scan_event in scan_events {
+            by_property.entry(scan_event.property_id.as_str()).or_default().push(scan_event);
+            *by_day.entry(scan_event.scanned_at.format("%Y-%m-%d").to_string()).or_default() += 1;
+
+            if !scan_event.is_bot {
+                let day = scan_event.scanned_at.format("%Y-%m-%d").to_string();
+                let month = scan_event.scanned_at.format("%Y-%m").to_string();
+                RollupIncrement::add(&mut daily_rollups, scan_event, day);
+                RollupIncrement::add(&mut monthly_rollups, scan_event, month);
+            }
+        }
+
+        // A property whose update fails is queued for retry rather than losing its scans
+        let mut result = Ok(());
+        for (property_id, property_events) in by_property {
+            if let Err(e) = self.update_property_analytics(property_id, &property_events).await {
+                let scan_events = property_events.into_iter().cloned().collect();
+                self.dead_letter(property_id, scan_events, true, &e).await;
+                result = Err(e);
+            }
+        }
+        for (date, count) in by_day {
+            self.increment_daily_counter(&date, count).await?;
+        }
+        for increment in daily_rollups.values() {
+            self.increment_rollup(&self.daily_rollups, increment).await?;
+        }
+        for increment in monthly_rollups.values() {
+            self.increment_rollup(&self.monthly_rollups, increment).await?;
+        }
+
+        result
+    }
+
+    /// Queue scans whose raw events couldn't be written, split by property, for the retry job.
+    /// Their daily counters are rebuilt by reconciliation once they're written.
+    pub(crate) async fn dead_letter_unpersisted(&self, scan_events: Vec<ScanEvent>, error: &mongodb::error::Error) {
+        let mut by_property: HashMap<String, Vec<ScanEvent>> = HashMap::new();
+        for scan_event in scan_events {
+            by_property.entry(scan_event.property_id.clone()).or_default().push(scan_event);
+        }
+        for (property_id, property_events) in by_property {
+            self.dead_letter(&property_id, property_events, false, error).await;
+        }
+    }
+
+    async fn dead_letter(&self, property_id: &str, scan_events: Vec<ScanEvent>, persisted: bool, error: &mongodb::error::Error) {
+        let count = scan_events.len() as u64;
+        let now = Utc::now();
+        let failed_update = FailedAnalyticsUpdate {
+            id: ObjectId::new(),
+            property_id: property_id.to_string(),
+            scan_events,
+            persisted,
+            error: error.to_string(),
+            attempts: 0,
+            created_at: now,
+            next_attempt_at: Some(now + dead_letter_backoff(0)),
+        };
+
+        match self.failed_updates.insert_one(&failed_update).await {
+            Ok(_) => {
+                self.dead_letters.queued.fetch_add(count, Ordering::Relaxed);
+                warn!("Queued {} scans of property {} for an analytics retry: {}", count, property_id, error);
+            }
+            Err(e) => {
+                self.dead_letters.lost.fetch_add(count, Ordering::Relaxed);
+                error!("Lost analytics for {} scans of property {}: {} (dead-lettering failed: {})", count, property_id, error, e);
+            }
+        }
+    }
+
+    /// Retry up to `limit` dead letters that are due; returns how many were applied
+    pub async fn retry_failed_updates(&self, limit: i64) -> Result<u64, mongodb::error::Error> {
+        let now = Utc::now();
+        let options = FindOptions::builder()
+            .sort(doc! { "nextAttemptAt": 1 })
+            .limit(limit)
+            .build();
+        let due: Vec<FailedAnalyticsUpdate> = self.failed_updates
+            .find(doc! { "nextAttemptAt": { "$ne": null, "$lte": to_bson(&now)? } })
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut recovered = 0;
+        for failed_update in due {
+            match self.retry_failed_update(&failed_update).await {
+                Ok(()) => {
+                    self.failed_updates.delete_one(doc! { "_id": failed_update.id }).await?;
+                    let count = failed_update.scan_events.len() as u64;
+                    self.dead_letters.recovered.fetch_add(count, Ordering::Relaxed);
+                    recovered += count;
+                }
+                Err(e) => {
+                    self.dead_letters.retry_failures.fetch_add(1, Ordering::Relaxed);
+                    let attempts = failed_update.attempts + 1;
+                    let next_attempt_at = (attempts < DEAD_LETTER_MAX_ATTEMPTS)
+                        .then(|| now + dead_letter_backoff(attempts));
+                    if next_attempt_at.is_none() {
+                        error!("Giving up on analytics retry {} for property {}: {}", failed_update.id, failed_update.property_id, e);
+                    }
+                    self.failed_updates
+                        .update_one(
+                            doc! { "_id": failed_update.id },
+                            doc! { "$set": {
+                                "attempts": attempts,
+                                "error": e.to_string(),
+                                "nextAttemptAt": to_bson(&next_attempt_at)?
+                            } },
+                        )
+                        .await?;
+                }
+            }
+        }
+
+        Ok(recovered)
+    }
+
+    async fn retry_failed_update(&self, failed_update: &FailedAnalyticsUpdate) -> Result<(), mongodb::error::Error> {
+        if !failed_update.persisted {
+            // Upserts by ID, so events written before an earlier failure aren't duplicated
+            for scan_event in &failed_update.scan_events {
+                self.scan_events
+                    .replace_one(doc! { "_id": scan_event.id }, scan_event)
+                    .upsert(true)
+                    .await?;
+                self.publish_scan_event(scan_event);
+            }
+            self.failed_updates
+                .update_one(doc! { "_id": failed_update.id }, doc! { "$set": { "persisted": true } })
+                .await?;
+        }
+
+        // Bots are stored but never aggregated
+        let scan_events: Vec<&ScanEvent> = failed_update.scan_events.iter().filter(|scan_event| !scan_event.is_bot).collect();
+        if scan_events.is_empty() {
+            return Ok(());
+        }
+        self.update_property_analytics(&failed_update.property_id, &scan_events).await
+    }
+
+    /// Retry due dead letters in the background
+    pub fn spawn_dead_letter_retry(&self, interval: std::time::Duration, batch_size: i64) {
+        let analytics_service = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match analytics_service.retry_failed_updates(batch_size).await {
+                    Ok(0) => {}
+                    Ok(recovered) => info!("Recovered analytics for {} scans from the dead-letter queue", recovered),
+                    Err(e) => error!("Analytics dead-letter retry failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Dead letters still waiting for a retry, or given up on
+    pub async fn count_failed_updates(&self) -> Result<u64, mongodb::error::Error> {
+        self.failed_updates.count_documents(doc! {}).await
+    }
+
+    /// Dead-letter counters since startup
+    pub fn dead_letter_metrics(&self) -> DeadLetterMetrics {
+        DeadLetterMetrics {
+            queued: self.dead_letters.queued.load(Ordering::Relaxed),
+            recovered: self.dead_letters.recovered.load(Ordering::Relaxed),
+            retry_failures: self.dead_letters.retry_failures.load(Ordering::Relaxed),
+            lost: self.dead_letters.lost.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Update property analytics with new scan events, writing the document once
+    async fn update_property_analytics(
+        &self,
+        property_id: &str,
+        scan_events: &[&ScanEvent],
+    ) -> Result<(), mongodb::error::Error> {
+        // Try to find existing analytics
+        let mut analytics = match self.property_analytics
+            .find_one(doc! { "propertyId": property_id })
+            .await?
+        {
+            Some(analytics) => analytics,
             None => PropertyScanAnalytics::new(property_id.to_string()),
         };
 
-        // Update analytics with new scan
-        analytics.update_with_scan(scan_event);
+        // Time-based counter boundaries
+        let now = Utc::now();
+        let today_start = now.date_naive().and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_local_timezone(Utc)
+            .unwrap();
+        let week_start = today_start - Duration::days(now.weekday().num_days_from_monday() as i64);
+        let month_start = now.date_naive()
+            .with_day(1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_local_timezone(Utc)
+            .unwrap();
+
+        for scan_event in scan_events {
+            // A visitor is unique if no earlier event for this property carries their ID
+            let is_new_visitor = match &scan_event.visitor_id {
+                Some(visitor_id) => {
+                    self.scan_events
+                        .count_documents(doc! {
+                            "propertyId": property_id,
+                            "visitorId": visitor_id,
+                            "isBot": { "$ne": true },
+                            "_id": { "$lt": scan_event.id }
+                        })
+                        .await? == 0
+                }
+                None => true,
+            };
+
+            // A scan opens a visit unless an earlier scan of this property shares its session
+            let starts_session = match &scan_event.session_id {
+                Some(session_id) if !is_new_visitor => {
+                    self.scan_events
+                        .count_documents(doc! {
+                            "propertyId": property_id,
+                            "sessionId": session_id,
+                            "isBot": { "$ne": true },
+                            "_id": { "$lt": scan_event.id }
+                        })
+                        .await? == 0
+                }
+                _ => true,
+            };
+
+            // Update analytics with new scan
+            analytics.update_with_scan(scan_event, is_new_visitor, starts_session);
+
+            if scan_event.scanned_at >= today_start {
+                analytics.scans_today += 1;
+            }
+            if scan_event.scanned_at >= week_start {
+                analytics.scans_this_week += 1;
+            }
+            if scan_event.scanned_at >= month_start {
+                analytics.scans_this_month += 1;
+            }
+        }
+
+        // Upsert the analytics
+        let filter = doc! { "propertyId": property_id };
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.property_analytics
+            .replace_one(filter, &analytics)
+            .with_options(options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Queue depth and throughput of the analytics worker, if running
+    pub fn worker_metrics(&self) -> Option<AnalyticsWorkerMetrics> {
+        self.queue.as_ref().map(|queue| queue.metrics())
+    }
+
+    /// Provider, cache and failure counters for geolocation lookups, if configured
+    pub fn geolocation_metrics(&self) -> Option<GeolocationMetrics> {
+        self.geolocation.as_ref().map(|geolocation| geolocation.metrics())
+    }
+
+    /// Current load shedding level and counters, if enabled
+    pub fn load_shedding_metrics(&self) -> Option<LoadShedderMetrics> {
+        self.load_shedder.as_ref().map(|shedder| shedder.metrics())
+    }
+
+    /// Wait for queued per-scan writes so a shutdown doesn't lose them
+    pub async fn drain(&self, timeout: std::time::Duration) {
+        let Some(queue) = &self.queue else { return };
+
+        if queue.flush(timeout).await {
+            info!("Analytics writes drained");
+        } else {
+            warn!("Shutdown with {} analytics writes still queued", queue.metrics().queue_depth);
+        }
+    }
+
+    /// Create the indexes analytics queries rely on
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.scan_events
+            .create_indexes(vec![
+                IndexModel::builder().keys(doc! { "scannedAt": -1 }).build(),
+                IndexModel::builder().keys(doc! { "propertyId": 1, "scannedAt": -1 }).build(),
+                IndexModel::builder().keys(doc! { "propertyId": 1, "visitorId": 1 }).build(),
+                IndexModel::builder().keys(doc! { "schemaVersion": 1 }).build(),
+            ])
+            .await?;
+
+        self.property_analytics
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "propertyId": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+
+        self.snapshots
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "date": -1 }).build())
+            .await?;
 
-        // Update time-based counters
-        let now = Utc::now();
-        let today_start = now.date_naive().and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Utc)
-            .unwrap();
-        let week_start = today_start - Duration::days(now.weekday().num_days_from_monday() as i64);
-        let month_start = now.date_naive()
-            .with_day(1)
-            .unwrap()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Utc)
-            .unwrap();
+        // Rollups are read by property and period, and the daily ones purged by period
+        for rollups in [&self.daily_rollups, &self.monthly_rollups] {
+            rollups
+                .create_indexes(vec![
+                    IndexModel::builder().keys(doc! { "propertyId": 1, "period": 1 }).build(),
+                    IndexModel::builder().keys(doc! { "period": 1 }).build(),
+                ])
+                .await?;
+        }
+
+        // Due retries
+        self.failed_updates
+            .create_index(IndexModel::builder().keys(doc! { "nextAttemptAt": 1 }).build())
+            .await?;
+
+        // One event per scan and stage
+        self.funnel_events
+            .create_indexes(vec![
+                IndexModel::builder()
+                    .keys(doc! { "scanId": 1, "stage": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+                IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": -1 }).build(),
+            ])
+            .await?;
+
+        info!("Analytics indexes ensured");
+        Ok(())
+    }
+
+    /// Periodically rebuild recent counters and system analytics in the background,
+    /// refreshing today's property snapshots after each pass
+    pub fn spawn_reconciliation(&self, interval: std::time::Duration) {
+        let analytics_service = self.clone();
 
-        if scan_event.scanned_at >= today_start {
-            analytics.scans_today += 1;
+        tokio::spawn(async move {
+            // Full rebuild once at startup, then only the recent window
+            if let Err(e) = analytics_service.reconcile_system_analytics(None).await {
+                error!("Initial analytics reconciliation failed: {}", e);
+            }
+            analytics_service.refresh_snapshots().await;
+
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let since = Utc::now() - Duration::days(RECONCILE_WINDOW_DAYS);
+                if let Err(e) = analytics_service.reconcile_system_analytics(Some(since)).await {
+                    error!("Analytics reconciliation failed: {}", e);
+                }
+                analytics_service.refresh_snapshots().await;
+            }
+        });
+    }
+
+    async fn refresh_snapshots(&self) {
+        let today = Utc::now().date_naive();
+        if let Err(e) = self.snapshot_property_analytics(today).await {
+            error!("Analytics snapshot for {} failed: {}", today, e);
         }
-        if scan_event.scanned_at >= week_start {
-            analytics.scans_this_week += 1;
+    }
+
+    /// Rewrite up to `batch_size` scan events stored at an older schema version.
+    /// Reads already upgrade in memory; this brings the stored documents, and so
+    /// aggregation pipelines that match on raw fields, up to date.
+    pub async fn upgrade_scan_events(&self, batch_size: i64) -> Result<u64, mongodb::error::Error> {
+        let filter = doc! {
+            "$or": [
+                { "schemaVersion": { "$exists": false } },
+                { "schemaVersion": { "$lt": SCAN_EVENT_SCHEMA_VERSION } },
+            ]
+        };
+        let options = FindOptions::builder().limit(batch_size).build();
+
+        let mut cursor = self.scan_events.find(filter).with_options(options).await?;
+        let mut upgraded = 0;
+
+        while let Some(scan_event) = cursor.try_next().await? {
+            self.scan_events
+                .replace_one(doc! { "_id": scan_event.id }, &scan_event)
+                .await?;
+            upgraded += 1;
         }
-        if scan_event.scanned_at >= month_start {
-            analytics.scans_this_month += 1;
+
+        Ok(upgraded)
+    }
+
+    /// Upgrade stored scan events in the background, a batch at a time, until none are left
+    pub fn spawn_scan_event_upgrade(&self, batch_size: i64, pause: std::time::Duration) {
+        let analytics_service = self.clone();
+
+        tokio::spawn(async move {
+            let mut total = 0;
+            loop {
+                match analytics_service.upgrade_scan_events(batch_size).await {
+                    Ok(0) => break,
+                    Ok(upgraded) => total += upgraded,
+                    Err(e) => {
+                        error!("Scan event upgrade stopped after {} events: {}", total, e);
+                        return;
+                    }
+                }
+                // Leave room for live traffic between batches
+                tokio::time::sleep(pause).await;
+            }
+
+            if total > 0 {
+                info!("Upgraded {} scan events to schema version {}", total, SCAN_EVENT_SCHEMA_VERSION);
+            }
+        });
+    }
+
+    /// Rebuild daily counters from raw events and refresh the system analytics document
+    pub async fn reconcile_system_analytics(
+        &self,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<(), mongodb::error::Error> {
+        let mut match_doc = doc! { "isBot": { "$ne": true } };
+        if let Some(since) = since {
+            let day_start = since.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+            match_doc.insert("scannedAt", doc! { "$gte": utc_to_bson(day_start) });
         }
 
-        // Upsert the analytics
-        let filter = doc! { "propertyId": property_id };
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "$dateToString": {
+                            "format": "%Y-%m-%d",
+                            "date": "$scannedAt"
+                        }
+                    },
+                    "count": { "$sum": 1 }
+                }
+            },
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        let options = UpdateOptions::builder().upsert(true).build();
+        let mut days = 0;
+
+        while cursor.advance().await? {
+            let doc = cursor.current();
+            let date = doc.get_str("_id").unwrap_or("").to_string();
+            let count = doc.get_i32("count").map(i64::from).unwrap_or(0);
+
+            self.daily_counters
+                .update_one(
+                    doc! { "_id": &date },
+                    doc! { "$set": { "totalScans": count, "updatedAt": utc_to_bson(Utc::now()) } },
+                )
+                .with_options(options.clone())
+                .await?;
+            days += 1;
+        }
+
+        self.reconcile_rollups(since).await?;
+
+        let mut system_analytics = self.get_or_create_system_analytics().await?;
+        self.apply_counter_totals(&mut system_analytics).await?;
+        system_analytics.top_performing_properties = self.get_top_performing_properties(10, 30).await?;
+        system_analytics.last_updated = Utc::now();
+
         let options = ReplaceOptions::builder().upsert(true).build();
-        self.property_analytics
-            .replace_one(filter, &analytics)
+        self.system_analytics
+            .replace_one(doc! {}, &system_analytics)
             .with_options(options)
             .await?;
 
+        info!("Reconciled system analytics across {} day(s) of scans", days);
         Ok(())
     }
 
-    /// Update system-wide analytics
-    async fn update_system_analytics(&self) -> Result<(), mongodb::error::Error> {
-        let mut system_analytics = self.get_or_create_system_analytics().await?;
+    /// Atomically count a batch's scans against a property's day or month
+    async fn increment_rollup(
+        &self,
+        collection: &Collection<PropertyRollup>,
+        increment: &RollupIncrement,
+    ) -> Result<(), mongodb::error::Error> {
+        collection
+            .update_one(
+                doc! { "_id": PropertyRollup::id_for(&increment.property_id, &increment.period) },
+                doc! {
+                    "$inc": { "scans": increment.scans, "successfulScans": increment.successful_scans },
+                    "$set": { "updatedAt": utc_to_bson(Utc::now()) },
+                    "$setOnInsert": {
+                        "propertyId": &increment.property_id,
+                        "period": &increment.period,
+                        "uniqueVisitors": 0i64
+                    }
+                },
+            )
+            .upsert(true)
+            .await?;
 
-        // Update counters (this is a simplified version - in production you'd want more efficient aggregations)
-        let total_scans = self.scan_events
-            .count_documents(doc! {})
-            .await? as i64;
+        Ok(())
+    }
 
-        let today_start = utc_to_bson(Utc::now().date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Utc)
-            .unwrap());
+    /// Rebuild per-property daily and monthly rollups from raw events, from the start of the
+    /// day and month `since` falls in, or from the beginning. Counts are only ever raised, so
+    /// periods whose raw events were partly purged keep what the worker counted live
+    async fn reconcile_rollups(&self, since: Option<DateTime<Utc>>) -> Result<(), mongodb::error::Error> {
+        let day_start = since.map(|since| since.date_naive());
+        let month_start = day_start.and_then(|day| day.with_day(1));
 
-        let scans_today = self.scan_events
-            .count_documents(doc! { "scannedAt": { "$gte": today_start } })
-            .await? as i64;
+        self.rebuild_rollups(&self.daily_rollups, "%Y-%m-%d", day_start).await?;
+        self.rebuild_rollups(&self.monthly_rollups, "%Y-%m", month_start).await
+    }
 
-        system_analytics.total_scans_all_time = total_scans;
-        system_analytics.total_scans_today = scans_today;
-        system_analytics.last_updated = Utc::now();
+    async fn rebuild_rollups(
+        &self,
+        collection: &Collection<PropertyRollup>,
+        period_format: &str,
+        since: Option<NaiveDate>,
+    ) -> Result<(), mongodb::error::Error> {
+        let mut match_doc = doc! { "isBot": { "$ne": true } };
+        if let Some(since) = since {
+            let start = since.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+            match_doc.insert("scannedAt", doc! { "$gte": utc_to_bson(start) });
+        }
 
-        // Update top performing properties
-        system_analytics.top_performing_properties = self.get_top_performing_properties(10, 30).await?;
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "propertyId": "$propertyId",
+                        "period": { "$dateToString": { "format": period_format, "date": "$scannedAt" } }
+                    },
+                    "scans": { "$sum": 1i64 },
+                    "successfulScans": { "$sum": { "$cond": ["$redirectSuccess", 1i64, 0i64] } },
+                    "visitors": { "$addToSet": "$visitorId" }
+                }
+            },
+        ];
 
-        // Upsert system analytics
-        let filter = doc! {};
-        let options = ReplaceOptions::builder().upsert(true).build();
-        self.system_analytics
-            .replace_one(filter, &system_analytics)
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        while let Some(row) = cursor.try_next().await? {
+            let Some(rollup) = property_rollup(&row, Utc::now()) else { continue };
+            collection
+                .update_one(doc! { "_id": &rollup.id }, rollup_rebuild_update(&rollup))
+                .upsert(true)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Atomically count scans against their day
+    async fn increment_daily_counter(&self, date: &str, count: i64) -> Result<(), mongodb::error::Error> {
+        let options = UpdateOptions::builder().upsert(true).build();
+
+        self.daily_counters
+            .update_one(
+                doc! { "_id": date },
+                doc! {
+                    "$inc": { "totalScans": count },
+                    "$set": { "updatedAt": utc_to_bson(Utc::now()) }
+                },
+            )
             .with_options(options)
             .await?;
 
         Ok(())
     }
 
+    /// Fill scan totals from the daily counters
+    async fn apply_counter_totals(&self, system_analytics: &mut SystemAnalytics) -> Result<(), mongodb::error::Error> {
+        let now = Utc::now();
+        let today = now.date_naive();
+        let week_start = today - Duration::days(now.weekday().num_days_from_monday() as i64);
+        let month_start = today.with_day(1).unwrap();
+
+        system_analytics.total_scans_all_time = self.sum_daily_counters(None).await?;
+        system_analytics.total_scans_today = self.sum_daily_counters(Some(today.to_string())).await?;
+        system_analytics.total_scans_this_week = self.sum_daily_counters(Some(week_start.to_string())).await?;
+        system_analytics.total_scans_this_month = self.sum_daily_counters(Some(month_start.to_string())).await?;
+
+        Ok(())
+    }
+
+    /// Sum daily counters from a YYYY-MM-DD date onwards
+    async fn sum_daily_counters(&self, from_date: Option<String>) -> Result<i64, mongodb::error::Error> {
+        let match_doc = match from_date {
+            Some(from_date) => doc! { "_id": { "$gte": from_date } },
+            None => doc! {},
+        };
+
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! { "$group": { "_id": null, "total": { "$sum": "$totalScans" } } },
+        ];
+
+        let mut cursor = self.daily_counters.aggregate(pipeline).await?;
+        if cursor.advance().await? {
+            let doc = cursor.current();
+            return Ok(doc.get_i64("total")
+                .or_else(|_| doc.get_i32("total").map(i64::from))
+                .unwrap_or(0));
+        }
+
+        Ok(0)
+    }
+
     /// Get or create system analytics
     async fn get_or_create_system_analytics(&self) -> Result<SystemAnalytics, mongodb::error::Error> {
         match self.system_analytics.find_one(doc! {}).await? {
@@ -531,6 +2324,13 @@ impl AnalyticsService {
             })
             .await? as i64;
 
+        let current_unique = self
+            .count_unique_visitors(thirty_days_ago, now)
+            .await?;
+        let previous_unique = self
+            .count_unique_visitors(sixty_days_ago, thirty_days_ago)
+            .await?;
+
         let percentage_change = if previous_scans > 0 {
             ((current_scans - previous_scans) as f64 / previous_scans as f64) * 100.0
         } else {
@@ -540,29 +2340,61 @@ impl AnalyticsService {
         Ok(PeriodComparison {
             current_period: PeriodStats {
                 total_scans: current_scans,
-                unique_scans: current_scans, // Simplified
+                unique_scans: current_unique,
                 average_scans_per_day: current_scans as f64 / 30.0,
             },
             previous_period: PeriodStats {
                 total_scans: previous_scans,
-                unique_scans: previous_scans, // Simplified
+                unique_scans: previous_unique,
                 average_scans_per_day: previous_scans as f64 / 30.0,
             },
             percentage_change,
         })
     }
 
-    /// Get geolocation from IP address (placeholder - would use external service)
-    async fn get_geolocation_from_ip(&self, _ip_address: Option<&str>) -> Option<GeoLocation> {
-        // TODO: Implement with external geolocation service like MaxMind or ipapi
-        // For now, return None
-        None
+    /// Count distinct visitors with scans in the given time range
+    async fn count_unique_visitors(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<i64, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "scannedAt": {
+                        "$gte": utc_to_bson(from),
+                        "$lt": utc_to_bson(to)
+                    },
+                    "visitorId": { "$exists": true, "$ne": null },
+                    "isBot": { "$ne": true }
+                }
+            },
+            doc! { "$group": { "_id": "$visitorId" } },
+            doc! { "$count": "uniqueVisitors" }
+        ];
+
+        let mut cursor = self.scan_events.aggregate(pipeline).await?;
+        if cursor.advance().await? {
+            let doc = cursor.current();
+            return Ok(doc.get_i32("uniqueVisitors").map(i64::from).unwrap_or(0));
+        }
+
+        Ok(0)
+    }
+
+    /// Get geolocation from IP address via the configured provider
+    async fn get_geolocation_from_ip(&self, ip_address: Option<&str>) -> Option<GeoLocation> {
+        match (&self.geolocation, ip_address) {
+            (Some(geolocation), Some(ip_address)) => geolocation.locate(ip_address).await,
+            _ => None,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::DeviceType;
     use mongodb::Client;
 
     async fn get_test_service() -> AnalyticsService {
@@ -580,11 +2412,15 @@ mod tests {
         let scan_id = service.record_scan(
             "test_property_123".to_string(),
             1,
+            Some(2),
             ScanSource::QrCode,
             RedirectType::DualRedirect,
             Some("Mozilla/5.0 (iPhone; CPU iPhone OS 14_0 like Mac OS X)".to_string()),
             Some("192.168.1.1".to_string()),
             Some("session_123".to_string()),
+            Some("visitor_123".to_string()),
+            None,
+            UtmParameters::from_query(Some("flyer"), Some("print"), Some("spring-open-house")),
             None,
         ).await.expect("Failed to record scan");
 
@@ -599,12 +2435,16 @@ mod tests {
         service.record_scan(
             "test_property_456".to_string(),
             1,
+            None,
             ScanSource::QrCode,
             RedirectType::DualRedirect,
             None,
             None,
             None,
             None,
+            None,
+            None,
+            None,
         ).await.expect("Failed to record scan");
 
         // Get analytics
@@ -614,4 +2454,399 @@ mod tests {
 
         assert_eq!(analytics.property_id, "test_property_456");
     }
+
+    #[test]
+    fn test_returning_visitor_sessions() {
+        let scan = ScanEvent::new("p1".to_string(), 1, ScanSource::QrCode, RedirectType::DaobitarOnly);
+        let mut analytics = PropertyScanAnalytics::new("p1".to_string());
+
+        analytics.update_with_scan(&scan, true, true);   // First visit
+        analytics.update_with_scan(&scan, false, false); // Rescan in the same session
+        analytics.update_with_scan(&scan, false, true);  // Back in a later session
+
+        assert_eq!(analytics.total_scans, 3);
+        assert_eq!(analytics.unique_scans, 1);
+        assert_eq!(analytics.new_visitor_sessions, 1);
+        assert_eq!(analytics.returning_visitor_sessions, 1);
+        assert_eq!(analytics.returning_visitor_rate(), 50.0);
+        assert_eq!(PropertyScanAnalytics::new("p2".to_string()).returning_visitor_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_click_through_rates() {
+        let stats = FunnelStats {
+            page_views: 40,
+            auto_redirects: 10,
+            manual_click_property: 10,
+            manual_click_blockchain: 2,
+            manual_click_payment: 4,
+        };
+
+        let rates = ClickThroughRates::from(&stats);
+        assert_eq!(rates.property_clicks, 10);
+        assert_eq!(rates.property_rate, 25.0);
+        assert_eq!(rates.blockchain_rate, 5.0);
+        assert_eq!((rates.payment_clicks, rates.payment_rate), (4, 10.0));
+
+        // Clicks from visitors whose page view beacon never arrived
+        let blocked = FunnelStats { page_views: 1, manual_click_property: 3, ..FunnelStats::default() };
+        assert_eq!(ClickThroughRates::from(&blocked).property_rate, 100.0);
+        assert_eq!(ClickThroughRates::from(&FunnelStats::default()).property_rate, 0.0);
+    }
+
+    #[test]
+    fn test_property_comparison() {
+        let facets = doc! {
+            "totals": [{ "_id": null, "scans": 7i64, "uniqueVisitors": 5 }],
+            "devices": [
+                { "_id": "mobile", "count": 5i64 },
+                { "_id": "desktop", "count": 1i64 },
+                { "_id": null, "count": 1i64 }
+            ],
+            "days": [
+                { "_id": "2025-07-01", "count": 3i64 },
+                { "_id": "2025-07-03", "count": 4i64 }
+            ]
+        };
+        let from = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 7, 3).unwrap();
+
+        let comparison = property_comparison("p1", from, to, &facets);
+        assert_eq!((comparison.scans, comparison.unique_visitors), (7, 5));
+        assert_eq!(comparison.device_breakdown.mobile, 5);
+        assert_eq!(comparison.device_breakdown.unknown, 1);
+        let daily: Vec<i64> = comparison.daily_scans.iter().map(|day| day.count).collect();
+        assert_eq!(daily, vec![3, 0, 4]);
+        assert_eq!(comparison.daily_scans[1].date, "2025-07-02");
+
+        // A property with no scans at all still gets a zero-filled trend
+        let empty = property_comparison("p2", from, to, &Document::new());
+        assert_eq!(empty.scans, 0);
+        assert_eq!(empty.daily_scans.len(), 3);
+    }
+
+    #[test]
+    fn test_property_rollup() {
+        let row = doc! {
+            "_id": { "propertyId": "p1", "period": "2025-07-01" },
+            "scans": 5i64,
+            "successfulScans": 4i64,
+            "visitors": ["v1", "v2", null]
+        };
+
+        let rollup = property_rollup(&row, Utc::now()).unwrap();
+        assert_eq!(rollup.id, "p1:2025-07-01");
+        assert_eq!((rollup.scans, rollup.successful_scans, rollup.unique_visitors), (5, 4, 2));
+
+        assert!(property_rollup(&doc! { "_id": { "period": "2025-07" }, "scans": 1i64 }, Utc::now()).is_none());
+    }
+
+    #[test]
+    fn test_rollup_rebuild_update_never_lowers_counts() {
+        let rollup = property_rollup(
+            &doc! { "_id": { "propertyId": "p1", "period": "2025-07" }, "scans": 3i64, "successfulScans": 2i64, "visitors": ["v1"] },
+            Utc::now(),
+        ).unwrap();
+
+        let update = rollup_rebuild_update(&rollup);
+        let max = update.get_document("$max").unwrap();
+        assert_eq!(max.get_i64("scans").unwrap(), 3);
+        assert_eq!(max.get_i64("successfulScans").unwrap(), 2);
+        assert_eq!(max.get_i64("uniqueVisitors").unwrap(), 1);
+        assert!(update.get_document("$set").unwrap().get_i64("scans").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_rollups_keeps_counts_for_purged_events() {
+        let service = get_test_service().await;
+        let property_id = ObjectId::new().to_hex();
+        let now = Utc::now();
+        let month = now.format("%Y-%m").to_string();
+
+        // The month was counted live, then retention purged most of its raw events
+        let counted = PropertyRollup {
+            id: PropertyRollup::id_for(&property_id, &month),
+            property_id: property_id.clone(),
+            period: month.clone(),
+            scans: 40,
+            successful_scans: 38,
+            unique_visitors: 25,
+            updated_at: now,
+        };
+        service.monthly_rollups.insert_one(&counted).await.expect("Failed to insert rollup");
+        service.record_scan(
+            property_id.clone(),
+            1,
+            None,
+            ScanSource::QrCode,
+            RedirectType::DualRedirect,
+            None,
+            None,
+            None,
+            Some("visitor_123".to_string()),
+            None,
+            None,
+            None,
+        ).await.expect("Failed to record scan");
+
+        service.reconcile_rollups(Some(now)).await.expect("Failed to reconcile rollups");
+
+        let rollup = service.monthly_rollups
+            .find_one(doc! { "_id": &counted.id })
+            .await
+            .expect("Failed to read rollup")
+            .expect("Rollup missing");
+        assert_eq!((rollup.scans, rollup.successful_scans, rollup.unique_visitors), (40, 38, 25));
+    }
+
+    #[test]
+    fn test_rollup_increments() {
+        let mut scan = ScanEvent::new("p1".to_string(), 1, ScanSource::QrCode, RedirectType::DaobitarOnly);
+        let mut increments = HashMap::new();
+
+        scan.redirect_success = true;
+        RollupIncrement::add(&mut increments, &scan, "2025-07-01".to_string());
+        scan.redirect_success = false;
+        RollupIncrement::add(&mut increments, &scan, "2025-07-01".to_string());
+        RollupIncrement::add(&mut increments, &scan, "2025-07-02".to_string());
+
+        let day = &increments["p1:2025-07-01"];
+        assert_eq!((day.scans, day.successful_scans), (2, 1));
+        assert_eq!(increments.len(), 2);
+    }
+
+    #[test]
+    fn test_property_performances() {
+        let rows = vec![
+            doc! { "propertyId": "p1", "totalScans": 12, "uniqueScans": 9, "successRate": 100.0 },
+            doc! { "propertyId": "p2", "totalScans": 30i64, "uniqueScans": 21i64, "successRate": 75.0 },
+        ];
+
+        let performances = property_performances(&rows);
+        assert_eq!((performances[0].total_scans, performances[0].unique_scans), (12, 9));
+        assert_eq!(performances[0].property_name, "Property p1");
+        assert_eq!((performances[1].total_scans, performances[1].success_rate), (30, 75.0));
+    }
+
+    #[test]
+    fn test_campaign_stats() {
+        let rows = vec![
+            doc! {
+                "_id": { "campaign": "spring-open-house", "source": "flyer", "medium": "print" },
+                "scans": 3i64,
+                "uniqueVisitors": 2,
+            },
+            doc! { "_id": {}, "scans": 1i64, "uniqueVisitors": 1 },
+        ];
+
+        let stats = campaign_stats(&rows);
+        assert_eq!(stats[0].campaign.as_deref(), Some("spring-open-house"));
+        assert_eq!(stats[0].medium.as_deref(), Some("print"));
+        assert_eq!(stats[0].unique_visitors, 2);
+        assert_eq!(stats[0].percentage, 75.0);
+        assert!(stats[1].campaign.is_none() && stats[1].source.is_none());
+        assert_eq!(stats[1].scans, 1);
+    }
+
+    #[test]
+    fn test_qr_version_stats() {
+        let last_scanned_at = BsonDateTime::from_millis(1_710_000_000_000);
+        let rows = vec![
+            doc! { "_id": 2, "scans": 5i64, "outdatedScans": 0i64, "lastScannedAt": last_scanned_at },
+            doc! { "_id": 1, "scans": 3i64, "outdatedScans": 2i64, "lastScannedAt": last_scanned_at },
+        ];
+
+        let stats = qr_version_stats(&rows);
+        assert_eq!(stats[0].qr_version, 2);
+        assert_eq!(stats[0].outdated_scans, 0);
+        assert_eq!(stats[1].outdated_scans, 2);
+        assert_eq!(stats[1].last_scanned_at, Some(bson_to_utc(last_scanned_at)));
+    }
+
+    #[test]
+    fn test_scan_heatmap() {
+        let rows = vec![
+            doc! { "_id": { "lat": -1.3, "lng": 36.8 }, "scans": 4i64 },
+            doc! { "_id": { "lat": -4.0, "lng": 39.7 }, "scans": 9i64 },
+            doc! { "_id": { "lat": null, "lng": 36.8 }, "scans": 2i64 },
+        ];
+
+        let heatmap = scan_heatmap("p1", 30, 1, &rows);
+        assert_eq!(heatmap.cells.len(), 2);
+        assert_eq!((heatmap.cells[0].lat, heatmap.cells[0].lng), (-4.0, 39.7));
+        assert_eq!(heatmap.max_scans, 9);
+
+        let empty = scan_heatmap("p1", 30, 1, &[]);
+        assert!(empty.cells.is_empty());
+        assert_eq!(empty.max_scans, 0);
+    }
+
+    #[test]
+    fn test_hourly_distribution() {
+        let rows = vec![
+            doc! { "_id": { "day": 1, "hour": 9 }, "scans": 3i64 },
+            doc! { "_id": { "day": 6, "hour": 9 }, "scans": 4i64 },
+            doc! { "_id": { "day": 6, "hour": 14 }, "scans": 5i64 },
+            doc! { "_id": { "day": 8, "hour": 14 }, "scans": 50i64 },
+        ];
+
+        let distribution = hourly_distribution(Some("p1"), 30, "Africa/Nairobi", &rows);
+        assert_eq!(distribution.by_hour.len(), 24);
+        assert_eq!(distribution.by_hour[9], 7);
+        assert_eq!(distribution.by_hour[14], 5);
+        assert_eq!(distribution.by_day_of_week, vec![3, 0, 0, 0, 0, 9, 0]);
+        assert_eq!(distribution.grid[5][14], 5);
+        assert_eq!(distribution.peak_hour, Some(9));
+
+        let empty = hourly_distribution(None, 30, "UTC", &[]);
+        assert_eq!(empty.by_hour.iter().sum::<i64>(), 0);
+        assert_eq!(empty.peak_hour, None);
+    }
+
+    #[test]
+    fn test_org_property_scans() {
+        let rows = vec![
+            doc! { "_id": "p1", "scans": 12i64, "uniqueVisitors": 9 },
+            doc! { "_id": "p2", "scans": 3i64, "uniqueVisitors": 3 },
+        ];
+
+        let properties = org_property_scans(&rows);
+        assert_eq!(properties[0].property_id, "p1");
+        assert_eq!((properties[0].scans, properties[0].unique_visitors), (12, 9));
+
+        let summary = crate::models::OrgAnalyticsSummary::new("org1".to_string(), 30, 5, properties);
+        assert_eq!(summary.total_scans, 15);
+        assert_eq!(summary.property_count, 5);
+    }
+
+    #[test]
+    fn test_funnel_stats() {
+        let rows = vec![
+            doc! { "_id": "page_view", "count": 10i64 },
+            doc! { "_id": "auto_redirect", "count": 6i64 },
+            doc! { "_id": "manual_click_blockchain", "count": 1i64 },
+        ];
+
+        let stats = funnel_stats(&rows);
+        assert_eq!(stats.page_views, 10);
+        assert_eq!(stats.auto_redirects, 6);
+        assert_eq!(stats.manual_click_property, 0);
+        assert_eq!(stats.manual_click_blockchain, 1);
+    }
+
+    #[test]
+    fn test_outdated_qr_scans_are_flagged() {
+        let scan = |version| ScanEvent::new("p1".to_string(), version, ScanSource::QrCode, RedirectType::DaobitarOnly);
+
+        assert!(scan(1).with_current_qr_version(2).outdated_qr);
+        assert!(!scan(2).with_current_qr_version(2).outdated_qr);
+        assert!(!scan(1).outdated_qr);
+    }
+
+    #[test]
+    fn test_retention_cutoff_starts_at_midnight() {
+        let now = DateTime::parse_from_rfc3339("2024-03-10T15:42:00Z").unwrap().with_timezone(&Utc);
+        let cutoff = retention_cutoff(now, 30);
+
+        assert_eq!(cutoff.to_rfc3339(), "2024-02-09T00:00:00+00:00");
+        assert!(cutoff < now - Duration::days(30));
+    }
+
+    #[test]
+    fn test_dead_letter_backoff() {
+        assert_eq!(dead_letter_backoff(0), Duration::minutes(1));
+        assert_eq!(dead_letter_backoff(3), Duration::minutes(8));
+        assert_eq!(dead_letter_backoff(DEAD_LETTER_MAX_ATTEMPTS), Duration::hours(6));
+        assert_eq!(dead_letter_backoff(i32::MAX), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_legacy_scan_event_upgrades_on_read() {
+        // Shape of events written before schema versioning
+        let legacy = doc! {
+            "_id": ObjectId::new(),
+            "propertyId": "507f1f77bcf86cd799439011",
+            "qrVersion": 1,
+            "scannedAt": "2024-03-10T15:42:00Z",
+            "scanSource": "qr_code",
+            "userAgent": "facebookexternalhit/1.1",
+            "ipAddress": "203.0.113.7",
+            "redirectSuccess": true,
+            "redirectType": "dual_redirect",
+        };
+
+        let scan_event: ScanEvent = mongodb::bson::from_document(legacy).expect("legacy event should load");
+        assert_eq!(scan_event.schema_version, SCAN_EVENT_SCHEMA_VERSION);
+        assert!(scan_event.is_bot);
+        assert_eq!(
+            scan_event.visitor_id.as_deref(),
+            Some(ScanEvent::visitor_hash(Some("203.0.113.7"), Some("facebookexternalhit/1.1")).as_str())
+        );
+        assert!(scan_event.metadata.is_empty());
+
+        // Current events round-trip unchanged
+        let current = ScanEvent::new("p1".to_string(), 2, ScanSource::ShareLink, RedirectType::DaobitarOnly)
+            .with_visitor_id("cookie-visitor".to_string());
+        let stored = mongodb::bson::to_document(&current).unwrap();
+        let reloaded: ScanEvent = mongodb::bson::from_document(stored).unwrap();
+        assert_eq!(reloaded.visitor_id.as_deref(), Some("cookie-visitor"));
+        assert_eq!(reloaded.qr_version, 2);
+    }
+
+    #[test]
+    fn test_device_info_from_user_agent() {
+        let iphone = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1",
+        );
+        assert!(matches!(iphone.device_type, DeviceType::Mobile));
+        assert_eq!(iphone.platform.as_deref(), Some("iOS"));
+        assert_eq!(iphone.browser.as_deref(), Some("Safari"));
+        assert_eq!(iphone.browser_version.as_deref(), Some("17.1"));
+
+        let ipad = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (iPad; CPU OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1",
+        );
+        assert!(matches!(ipad.device_type, DeviceType::Tablet));
+
+        let desktop = DeviceInfo::from_user_agent(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        );
+        assert!(matches!(desktop.device_type, DeviceType::Desktop));
+        assert_eq!(desktop.platform.as_deref(), Some("Windows"));
+        assert_eq!(desktop.browser.as_deref(), Some("Chrome"));
+        assert_eq!(desktop.browser_version.as_deref(), Some("120.0.0.0"));
+        assert!(!desktop.is_mobile);
+    }
+
+    #[test]
+    fn test_is_bot_user_agent() {
+        let browsers = [
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1",
+            "Mozilla/5.0 (Linux; Android 14; SM-A546E) AppleWebKit/537.36 (KHTML, like Gecko) SamsungBrowser/23.0 Chrome/115.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (Linux; Android 10; CUBOT_X30) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36",
+            "Mozilla/5.0 (Linux; Android 11; Cubot Note 20) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (Linux; Android 12; KINGKONG 9 Build/SP1A.210812.016) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/118.0.0.0 Mobile Safari/537.36",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0",
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91",
+        ];
+        for user_agent in browsers {
+            assert!(!ScanEvent::is_bot_user_agent(user_agent), "{}", user_agent);
+        }
+
+        let crawlers = [
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)",
+            "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)",
+            "Mozilla/5.0 (compatible; AhrefsBot/7.0; +http://ahrefs.com/robot/)",
+            "Slackbot-LinkExpanding 1.0 (+https://api.slack.com/robots)",
+            "Twitterbot/1.0",
+            "facebookexternalhit/1.1 (+http://www.facebook.com/externalhit_uatext.php)",
+            "WhatsApp/2.23.20.0 A",
+            "Mozilla/5.0 (compatible; Discordbot/2.0; +https://discordapp.com)",
+            "Mozilla/5.0 (compatible; ExampleBot/0.3; +https://example.com/bot)",
+            "curl/8.4.0",
+        ];
+        for user_agent in crawlers {
+            assert!(ScanEvent::is_bot_user_agent(user_agent), "{}", user_agent);
+        }
+    }
 }
\ No newline at end of file
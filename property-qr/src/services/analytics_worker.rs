@@ -0,0 +1,194 @@
+// src/services/analytics_worker.rs
+
+use crate::models::ScanEvent;
+use crate::services::AnalyticsService;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error, warn};
+
+enum WorkerMessage {
+    Scan(Box<ScanEvent>),
+    Deferred(Box<ScanEvent>), // Not yet written to scan_events; shed under load
+    Flush(oneshot::Sender<()>),
+}
+
+#[derive(Default)]
+struct WorkerCounters {
+    enqueued: AtomicU64,
+    processed: AtomicU64,
+    persisted: AtomicU64,
+    failed: AtomicU64,
+    batches: AtomicU64,
+    backpressure_waits: AtomicU64, // Sends that found the queue full and had to wait
+}
+
+/// Point-in-time view of the analytics worker queue
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsWorkerMetrics {
+    #[serde(rename = "queueDepth")]
+    pub queue_depth: usize,
+    #[serde(rename = "queueCapacity")]
+    pub queue_capacity: usize,
+    pub enqueued: u64,
+    pub processed: u64,
+    pub persisted: u64, // Deferred scan events written by the worker
+    pub failed: u64,
+    pub batches: u64,
+    #[serde(rename = "backpressureWaits")]
+    pub backpressure_waits: u64,
+}
+
+/// Sending half of the analytics worker; cheap to clone
+#[derive(Clone)]
+pub struct AnalyticsQueue {
+    sender: mpsc::Sender<WorkerMessage>,
+    counters: Arc<WorkerCounters>,
+}
+
+impl AnalyticsQueue {
+    /// Start a worker that applies scan aggregates in batches of up to `batch_size`
+    pub fn spawn(service: AnalyticsService, capacity: usize, batch_size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let counters = Arc::new(WorkerCounters::default());
+
+        tokio::spawn(run_worker(service, receiver, counters.clone(), batch_size.max(1)));
+
+        Self { sender, counters }
+    }
+
+    /// Queue a scan for aggregation, waiting for room when the queue is full.
+    /// Returns false if the worker has stopped so the caller can apply it inline.
+    pub async fn enqueue(&self, scan_event: ScanEvent) -> bool {
+        self.send(WorkerMessage::Scan(Box::new(scan_event))).await
+    }
+
+    /// Queue a scan that hasn't been written yet; the worker inserts it before aggregating.
+    /// Returns false if the worker has stopped so the caller can write it inline.
+    pub async fn enqueue_deferred(&self, scan_event: ScanEvent) -> bool {
+        self.send(WorkerMessage::Deferred(Box::new(scan_event))).await
+    }
+
+    async fn send(&self, message: WorkerMessage) -> bool {
+        let message = match self.sender.try_send(message) {
+            Ok(()) => {
+                self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+                return true;
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => return false,
+            Err(mpsc::error::TrySendError::Full(message)) => message,
+        };
+
+        self.counters.backpressure_waits.fetch_add(1, Ordering::Relaxed);
+        warn!("Analytics queue full ({} pending), applying backpressure", self.sender.max_capacity());
+
+        if self.sender.send(message).await.is_err() {
+            return false;
+        }
+        self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Wait until everything queued so far has been applied
+    pub async fn flush(&self, timeout: Duration) -> bool {
+        let (ack, done) = oneshot::channel();
+        let flushed = async {
+            self.sender.send(WorkerMessage::Flush(ack)).await.is_ok() && done.await.is_ok()
+        };
+
+        tokio::time::timeout(timeout, flushed).await.unwrap_or(false)
+    }
+
+    pub fn metrics(&self) -> AnalyticsWorkerMetrics {
+        AnalyticsWorkerMetrics {
+            queue_depth: self.sender.max_capacity() - self.sender.capacity(),
+            queue_capacity: self.sender.max_capacity(),
+            enqueued: self.counters.enqueued.load(Ordering::Relaxed),
+            processed: self.counters.processed.load(Ordering::Relaxed),
+            persisted: self.counters.persisted.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            batches: self.counters.batches.load(Ordering::Relaxed),
+            backpressure_waits: self.counters.backpressure_waits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+async fn run_worker(
+    service: AnalyticsService,
+    mut receiver: mpsc::Receiver<WorkerMessage>,
+    counters: Arc<WorkerCounters>,
+    batch_size: usize,
+) {
+    let mut messages = Vec::with_capacity(batch_size);
+
+    // Ends once every AnalyticsService holding the queue has been dropped
+    while receiver.recv_many(&mut messages, batch_size).await > 0 {
+        let mut batch = Vec::with_capacity(messages.len());
+        let mut deferred = Vec::new();
+        let mut flushes = Vec::new();
+        for message in messages.drain(..) {
+            match message {
+                WorkerMessage::Scan(scan_event) => batch.push(*scan_event),
+                WorkerMessage::Deferred(scan_event) => deferred.push(*scan_event),
+                WorkerMessage::Flush(ack) => flushes.push(ack),
+            }
+        }
+
+        if !deferred.is_empty() {
+            let count = deferred.len() as u64;
+            match service.persist_scan_events(&deferred).await {
+                Ok(()) => {
+                    counters.persisted.fetch_add(count, Ordering::Relaxed);
+                    // Bots are stored but never aggregated, as on the inline path
+                    batch.extend(deferred.into_iter().filter(|scan_event| !scan_event.is_bot));
+                }
+                Err(e) => {
+                    counters.failed.fetch_add(count, Ordering::Relaxed);
+                    error!("Failed to write {} deferred scan events: {}", count, e);
+                    service.dead_letter_unpersisted(deferred, &e).await;
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            let count = batch.len() as u64;
+            match service.apply_scan_batch(&batch).await {
+                Ok(()) => {
+                    counters.processed.fetch_add(count, Ordering::Relaxed);
+                    debug!("Applied analytics batch of {} scans", count);
+                }
+                Err(e) => {
+                    counters.failed.fetch_add(count, Ordering::Relaxed);
+                    error!("Failed to apply analytics batch of {} scans: {}", count, e);
+                }
+            }
+            counters.batches.fetch_add(1, Ordering::Relaxed);
+        }
+
+        for ack in flushes {
+            let _ = ack.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::Client;
+
+    #[tokio::test]
+    async fn test_flush_empty_queue() {
+        // The client connects lazily, so no database is needed for an empty queue
+        let client = Client::with_uri_str("mongodb://localhost:27017").await.unwrap();
+        let queue = AnalyticsQueue::spawn(AnalyticsService::new(&client.database("test_qr_analytics")), 8, 4);
+
+        assert!(queue.flush(Duration::from_secs(1)).await);
+
+        let metrics = queue.metrics();
+        assert_eq!(metrics.queue_capacity, 8);
+        assert_eq!(metrics.queue_depth, 0);
+        assert_eq!(metrics.enqueued, 0);
+    }
+}
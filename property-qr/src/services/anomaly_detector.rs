@@ -0,0 +1,209 @@
+// src/services/anomaly_detector.rs
+
+use crate::config::{AlertWebhookKind, AnomalyConfig};
+use crate::models::{AnomalyKind, PropertyScanActivity, ScanAnomaly};
+use crate::services::AnalyticsService;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+
+// A property's usual scan rate is taken over this long before the silence window
+const BASELINE_DAYS: i64 = 7;
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+// When each property was last alerted on, per kind
+type AlertTimes = HashMap<(String, AnomalyKind), DateTime<Utc>>;
+
+/// Watches scan volumes for spikes and for active codes going quiet, and posts alerts
+/// to a Slack or Discord webhook
+#[derive(Clone)]
+pub struct AnomalyDetector {
+    analytics_service: AnalyticsService,
+    config: AnomalyConfig,
+    http_client: reqwest::Client,
+    last_alerted: Arc<Mutex<AlertTimes>>, // For the cooldown
+}
+
+impl AnomalyDetector {
+    pub fn new(analytics_service: AnalyticsService, config: AnomalyConfig) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            analytics_service,
+            config,
+            http_client,
+            last_alerted: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Look for anomalies as of `now` and alert on those outside their cooldown; returns them
+    pub async fn check(&self, now: DateTime<Utc>) -> Result<Vec<ScanAnomaly>, mongodb::error::Error> {
+        let activity = self.analytics_service
+            .get_scan_activity(
+                now,
+                Duration::minutes(self.config.spike_window_minutes),
+                Duration::hours(self.config.silence_hours),
+                Duration::days(BASELINE_DAYS),
+            )
+            .await?;
+
+        let anomalies = self.outside_cooldown(detect_anomalies(&activity, &self.config), now);
+        for anomaly in &anomalies {
+            warn!("Scan anomaly: {}", anomaly.message());
+            if let Err(e) = self.send_alert(anomaly).await {
+                error!("Failed to post scan anomaly alert for property {}: {}", anomaly.property_id, e);
+            }
+        }
+
+        Ok(anomalies)
+    }
+
+    /// Check for anomalies in the background every configured interval
+    pub fn spawn(&self) {
+        let detector = self.clone();
+        let interval = std::time::Duration::from_secs(self.config.check_interval_minutes.max(1) * 60);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match detector.check(Utc::now()).await {
+                    Ok(anomalies) if !anomalies.is_empty() => info!("Alerted on {} scan anomalies", anomalies.len()),
+                    Ok(_) => {}
+                    Err(e) => error!("Scan anomaly check failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Drop anomalies alerted on within the cooldown, and start the cooldown for the rest
+    fn outside_cooldown(&self, anomalies: Vec<ScanAnomaly>, now: DateTime<Utc>) -> Vec<ScanAnomaly> {
+        let cooldown = Duration::hours(self.config.cooldown_hours);
+        let mut last_alerted = self.last_alerted.lock().unwrap_or_else(|e| e.into_inner());
+        last_alerted.retain(|_, alerted_at| now - *alerted_at < cooldown);
+
+        anomalies.into_iter()
+            .filter(|anomaly| {
+                let key = (anomaly.property_id.clone(), anomaly.kind);
+                if last_alerted.contains_key(&key) {
+                    return false;
+                }
+                last_alerted.insert(key, now);
+                true
+            })
+            .collect()
+    }
+
+    async fn send_alert(&self, anomaly: &ScanAnomaly) -> Result<(), reqwest::Error> {
+        let Some(webhook_url) = &self.config.webhook_url else {
+            return Ok(());
+        };
+
+        self.http_client
+            .post(webhook_url)
+            .json(&alert_payload(self.config.webhook_kind, &anomaly.message()))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Spikes: at least the minimum scans in the spike window, and `spike_multiplier` times the
+/// baseline rate. Silences: no scans in the silence window after an active baseline week.
+fn detect_anomalies(activity: &[PropertyScanActivity], config: &AnomalyConfig) -> Vec<ScanAnomaly> {
+    let baseline_minutes = (BASELINE_DAYS * 24 * 60) as f64;
+
+    activity.iter()
+        .filter_map(|property| {
+            let rate_per_minute = property.baseline_scans as f64 / baseline_minutes;
+
+            let expected_spike_scans = rate_per_minute * config.spike_window_minutes as f64;
+            if property.spike_window_scans >= config.spike_min_scans
+                && property.spike_window_scans as f64 >= config.spike_multiplier * expected_spike_scans.max(1.0)
+            {
+                return Some(ScanAnomaly {
+                    property_id: property.property_id.clone(),
+                    kind: AnomalyKind::Spike,
+                    recent_scans: property.spike_window_scans,
+                    expected_scans: expected_spike_scans,
+                    window_minutes: config.spike_window_minutes,
+                });
+            }
+
+            (property.silence_window_scans == 0 && property.baseline_scans >= config.active_min_scans)
+                .then(|| ScanAnomaly {
+                    property_id: property.property_id.clone(),
+                    kind: AnomalyKind::Silence,
+                    recent_scans: 0,
+                    expected_scans: rate_per_minute * (config.silence_hours * 60) as f64,
+                    window_minutes: config.silence_hours * 60,
+                })
+        })
+        .collect()
+}
+
+/// Incoming webhook body: Slack reads `text`, Discord `content`
+fn alert_payload(kind: AlertWebhookKind, message: &str) -> serde_json::Value {
+    match kind {
+        AlertWebhookKind::Slack => serde_json::json!({ "text": message }),
+        AlertWebhookKind::Discord => serde_json::json!({ "content": message }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AnomalyConfig {
+        AnomalyConfig {
+            enabled: true,
+            webhook_url: Some("https://hooks.slack.com/services/T000/B000/XXXX".to_string()),
+            webhook_kind: AlertWebhookKind::Slack,
+            check_interval_minutes: 15,
+            spike_window_minutes: 60,
+            spike_multiplier: 10.0,
+            spike_min_scans: 50,
+            silence_hours: 48,
+            active_min_scans: 20,
+            cooldown_hours: 24,
+        }
+    }
+
+    fn activity(property_id: &str, spike_window_scans: i64, silence_window_scans: i64, baseline_scans: i64) -> PropertyScanActivity {
+        PropertyScanActivity {
+            property_id: property_id.to_string(),
+            spike_window_scans,
+            silence_window_scans,
+            baseline_scans,
+        }
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let anomalies = detect_anomalies(
+            &[
+                activity("bot-target", 400, 420, 168), // ~1 an hour usually
+                activity("busy", 60, 900, 16_800),     // 100 an hour usually
+                activity("quiet", 3, 3, 0),            // Too few scans to page on
+                activity("taken-down", 0, 0, 140),
+                activity("never-active", 0, 0, 5),
+            ],
+            &config(),
+        );
+
+        let kinds: Vec<(&str, AnomalyKind)> = anomalies.iter().map(|a| (a.property_id.as_str(), a.kind)).collect();
+        assert_eq!(kinds, vec![("bot-target", AnomalyKind::Spike), ("taken-down", AnomalyKind::Silence)]);
+        assert_eq!(anomalies[1].expected_scans, 40.0);
+    }
+
+    #[test]
+    fn test_alert_payload() {
+        assert_eq!(alert_payload(AlertWebhookKind::Slack, "hi"), serde_json::json!({ "text": "hi" }));
+        assert_eq!(alert_payload(AlertWebhookKind::Discord, "hi"), serde_json::json!({ "content": "hi" }));
+    }
+}
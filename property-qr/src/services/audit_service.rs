@@ -0,0 +1,133 @@
+// src/services/audit_service.rs
+
+use crate::models::{AuditAction, AuditActor, AuditEntry, AuditFilter, AuditLogPage, QrCodeMetadata};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, to_bson, Document},
+    options::FindOptions,
+    Collection, Database, IndexModel,
+};
+use tracing::error;
+
+// Mongo filter for the audit log; every given condition applies
+fn audit_filter(filter: &AuditFilter) -> Result<Document, mongodb::error::Error> {
+    let mut query = doc! {};
+    if let Some(property_id) = &filter.property_id {
+        query.insert("propertyId", property_id);
+    }
+    if let Some(action) = filter.action {
+        query.insert("action", to_bson(&action)?);
+    }
+    if let Some(kind) = filter.actor_kind {
+        query.insert("actor.kind", to_bson(&kind)?);
+    }
+    if let Some(actor_id) = &filter.actor_id {
+        query.insert("actor.id", actor_id);
+    }
+
+    let mut created_at = doc! {};
+    if let Some(from) = filter.from {
+        created_at.insert("$gte", to_bson(&from)?);
+    }
+    if let Some(to) = filter.to {
+        created_at.insert("$lt", to_bson(&to)?);
+    }
+    if !created_at.is_empty() {
+        query.insert("createdAt", created_at);
+    }
+    Ok(query)
+}
+
+/// Append-only record of who generated, regenerated, deleted or deactivated each QR code
+#[derive(Clone)]
+pub struct AuditService {
+    entries: Collection<AuditEntry>,
+}
+
+impl AuditService {
+    pub fn new(db: &Database) -> Self {
+        Self {
+            entries: db.collection("audit_log"),
+        }
+    }
+
+    /// Indexes for a property's history and for time-ranged queries across all codes
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.entries
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": -1 }).build())
+            .await?;
+        self.entries
+            .create_index(IndexModel::builder().keys(doc! { "actor.kind": 1, "actor.id": 1, "createdAt": -1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Record an action; failures are logged rather than undoing the action itself
+    pub async fn record(
+        &self,
+        action: AuditAction,
+        property_id: &str,
+        actor: AuditActor,
+        before: Option<QrCodeMetadata>,
+        after: Option<QrCodeMetadata>,
+    ) {
+        let entry = AuditEntry::new(action, property_id.to_string(), actor, before, after);
+        if let Err(e) = self.entries.insert_one(&entry).await {
+            error!("Failed to write audit entry for {:?} on property {}: {}", action, property_id, e);
+        }
+    }
+
+    /// Matching entries, newest first
+    pub async fn list(&self, filter: &AuditFilter, limit: i64, skip: u64) -> Result<AuditLogPage, mongodb::error::Error> {
+        let query = audit_filter(filter)?;
+        let total_count = self.entries.count_documents(query.clone()).await?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1, "_id": -1 })
+            .limit(limit)
+            .skip(skip)
+            .build();
+        let entries: Vec<AuditEntry> = self.entries.find(query).with_options(options).await?.try_collect().await?;
+
+        Ok(AuditLogPage {
+            items: entries.iter().map(AuditEntry::to_response).collect(),
+            total_count,
+            limit,
+            skip,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ActorKind;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        assert!(audit_filter(&AuditFilter::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_filter_by_actor_action_and_range() {
+        let from = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let filter = AuditFilter {
+            property_id: Some("p1".into()),
+            action: Some(AuditAction::Deactivate),
+            actor_kind: Some(ActorKind::Organization),
+            actor_id: Some("org-7".into()),
+            from: Some(from),
+            to: None,
+        };
+
+        let query = audit_filter(&filter).unwrap();
+        assert_eq!(query.get_str("propertyId").unwrap(), "p1");
+        assert_eq!(query.get_str("action").unwrap(), "deactivate");
+        assert_eq!(query.get_str("actor.kind").unwrap(), "organization");
+        assert_eq!(query.get_str("actor.id").unwrap(), "org-7");
+        let created_at = query.get_document("createdAt").unwrap();
+        assert!(created_at.contains_key("$gte"));
+        assert!(!created_at.contains_key("$lt"));
+    }
+}
@@ -0,0 +1,207 @@
+// src/services/auto_redirect_service.rs
+
+use crate::models::{
+    AutoRedirectConfig, AutoRedirectDestination, UpsertAutoRedirectConfigRequest, GLOBAL_AUTO_REDIRECT_SCOPE,
+};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::doc,
+    options::{IndexOptions, ReplaceOptions},
+    Collection, Database, IndexModel,
+};
+use tracing::info;
+
+#[derive(Clone)]
+pub struct AutoRedirectService {
+    configs: Collection<AutoRedirectConfig>,
+}
+
+#[derive(Debug)]
+pub enum AutoRedirectError {
+    NotFound,
+    InvalidConfig(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for AutoRedirectError {
+    fn from(err: mongodb::error::Error) -> Self {
+        AutoRedirectError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for AutoRedirectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AutoRedirectError::NotFound => write!(f, "Auto-redirect config not found"),
+            AutoRedirectError::InvalidConfig(reason) => write!(f, "Invalid auto-redirect config: {}", reason),
+            AutoRedirectError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AutoRedirectError {}
+
+impl AutoRedirectService {
+    /// Create a new auto-redirect config service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            configs: db.collection("auto_redirect_configs"),
+        }
+    }
+
+    /// One config per property, plus the global one
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.configs
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "scopeId": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Get the config for a property, or the global one
+    pub async fn get_config(&self, scope_id: &str) -> Result<AutoRedirectConfig, AutoRedirectError> {
+        self.configs
+            .find_one(doc! { "scopeId": scope_id })
+            .await?
+            .ok_or(AutoRedirectError::NotFound)
+    }
+
+    /// Create or replace the config for a property, or the global one
+    pub async fn upsert_config(
+        &self,
+        scope_id: &str,
+        request: UpsertAutoRedirectConfigRequest,
+    ) -> Result<AutoRedirectConfig, AutoRedirectError> {
+        validate_request(&request)?;
+
+        let filter = doc! { "scopeId": scope_id };
+        let config = match self.configs.find_one(filter.clone()).await? {
+            Some(existing) => existing.apply(request),
+            None => AutoRedirectConfig::new(scope_id.to_string(), request),
+        };
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.configs
+            .replace_one(filter, &config)
+            .with_options(options)
+            .await?;
+
+        info!(
+            "Updated auto-redirect config for {}: {} ({} weighted destinations)",
+            scope_id, config.destination.as_str(), config.weights.len()
+        );
+        Ok(config)
+    }
+
+    /// Remove the config for a property, or the global one
+    pub async fn delete_config(&self, scope_id: &str) -> Result<(), AutoRedirectError> {
+        let result = self.configs.delete_one(doc! { "scopeId": scope_id }).await?;
+
+        if result.deleted_count == 0 {
+            return Err(AutoRedirectError::NotFound);
+        }
+
+        info!("Removed auto-redirect config for {}", scope_id);
+        Ok(())
+    }
+
+    /// Configs that may apply to a property: its own and the global one
+    pub async fn configs_for(&self, property_id: &str) -> Result<Vec<AutoRedirectConfig>, AutoRedirectError> {
+        let configs = self.configs
+            .find(doc! { "scopeId": { "$in": [property_id, GLOBAL_AUTO_REDIRECT_SCOPE] } })
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(configs)
+    }
+}
+
+/// Where a visitor's dual page goes on its own: the property's config, else the global one,
+/// else the deployment's default target
+pub fn resolve_destination(
+    configs: &[AutoRedirectConfig],
+    property_id: &str,
+    visitor_id: &str,
+    default: AutoRedirectDestination,
+) -> AutoRedirectDestination {
+    configs.iter()
+        .find(|config| config.scope_id == property_id)
+        .or_else(|| configs.iter().find(|config| config.scope_id == GLOBAL_AUTO_REDIRECT_SCOPE))
+        .map_or(default, |config| config.destination_for(property_id, visitor_id))
+}
+
+/// Weights must not repeat a destination and must leave every visitor somewhere to go
+fn validate_request(request: &UpsertAutoRedirectConfigRequest) -> Result<(), AutoRedirectError> {
+    for (i, arm) in request.weights.iter().enumerate() {
+        if request.weights[..i].iter().any(|earlier| earlier.destination == arm.destination) {
+            return Err(AutoRedirectError::InvalidConfig(format!(
+                "'{}' is weighted more than once",
+                arm.destination.as_str()
+            )));
+        }
+    }
+
+    if !request.weights.is_empty() && request.weights.iter().all(|arm| arm.weight == 0) {
+        return Err(AutoRedirectError::InvalidConfig("at least one weight must be above zero".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DestinationWeight;
+
+    fn request(destination: AutoRedirectDestination, weights: &[(AutoRedirectDestination, u32)]) -> UpsertAutoRedirectConfigRequest {
+        UpsertAutoRedirectConfigRequest {
+            destination,
+            weights: weights.iter().map(|&(destination, weight)| DestinationWeight { destination, weight }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        assert!(validate_request(&request(AutoRedirectDestination::None, &[])).is_ok());
+        assert!(validate_request(&request(
+            AutoRedirectDestination::Property,
+            &[(AutoRedirectDestination::Property, 80), (AutoRedirectDestination::Blockchain, 20)],
+        )).is_ok());
+        assert!(validate_request(&request(
+            AutoRedirectDestination::Property,
+            &[(AutoRedirectDestination::Blockchain, 20), (AutoRedirectDestination::Blockchain, 80)],
+        )).is_err());
+        assert!(validate_request(&request(
+            AutoRedirectDestination::Property,
+            &[(AutoRedirectDestination::Blockchain, 0)],
+        )).is_err());
+    }
+
+    #[test]
+    fn test_property_config_overrides_global() {
+        const PROPERTY: &str = "507f1f77bcf86cd799439011";
+        let global = AutoRedirectConfig::new(
+            GLOBAL_AUTO_REDIRECT_SCOPE.to_string(),
+            request(AutoRedirectDestination::Blockchain, &[]),
+        );
+        let own = AutoRedirectConfig::new(PROPERTY.to_string(), request(AutoRedirectDestination::None, &[]));
+
+        assert_eq!(
+            resolve_destination(&[], PROPERTY, "visitor", AutoRedirectDestination::Property),
+            AutoRedirectDestination::Property
+        );
+        assert_eq!(
+            resolve_destination(std::slice::from_ref(&global), PROPERTY, "visitor", AutoRedirectDestination::Property),
+            AutoRedirectDestination::Blockchain
+        );
+        assert_eq!(
+            resolve_destination(&[global, own], PROPERTY, "visitor", AutoRedirectDestination::Property),
+            AutoRedirectDestination::None
+        );
+    }
+}
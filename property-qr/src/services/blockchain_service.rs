@@ -0,0 +1,325 @@
+// src/services/blockchain_service.rs
+
+use crate::config::BlockchainConfig;
+use crate::models::PropertyQrInfo;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+const RPC_TIMEOUT_SECS: u64 = 3;
+
+// ownerOf(uint256), shared by ERC-721 tokens and the SBTs built on it
+const OWNER_OF_SELECTOR: &str = "6352211e";
+
+#[derive(Debug)]
+pub enum BlockchainError {
+    InvalidTokenId(String),
+    Http(String),
+    Rpc(String),
+}
+
+impl std::fmt::Display for BlockchainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockchainError::InvalidTokenId(token_id) => write!(f, "Invalid token ID: {}", token_id),
+            BlockchainError::Http(reason) => write!(f, "RPC request failed: {}", reason),
+            BlockchainError::Rpc(reason) => write!(f, "RPC error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BlockchainError {}
+
+/// Outcome of the last ownership check for a listing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnchainVerification {
+    pub verified: bool, // Token exists and is held by the recorded wallet
+    pub checked_at: DateTime<Utc>,
+}
+
+// Subset of a JSON-RPC 2.0 response
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<RpcErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcErrorBody {
+    message: String,
+}
+
+impl RpcResponse {
+    /// The token's owner; None when the token doesn't exist (the call reverts or returns nothing)
+    fn into_owner(self) -> Result<Option<String>, BlockchainError> {
+        if let Some(error) = self.error {
+            return if error.message.to_lowercase().contains("revert") {
+                Ok(None)
+            } else {
+                Err(BlockchainError::Rpc(error.message))
+            };
+        }
+        Ok(self.result.as_deref().and_then(decode_address))
+    }
+
+    /// The block number answered to eth_blockNumber
+    fn into_block_number(self) -> Result<u64, BlockchainError> {
+        if let Some(error) = self.error {
+            return Err(BlockchainError::Rpc(error.message));
+        }
+        self.result.as_deref()
+            .and_then(|result| result.strip_prefix("0x"))
+            .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| BlockchainError::Rpc(format!("unexpected block number {:?}", self.result)))
+    }
+}
+
+/// Confirms listings' ownership tokens against the chain, caching each answer for a TTL; cheap to clone
+#[derive(Clone)]
+pub struct BlockchainService {
+    http_client: reqwest::Client,
+    rpc_urls: BTreeMap<u64, String>,
+    default_rpc_url: Option<String>,
+    registry_contract: String,
+    cache: Arc<Mutex<HashMap<String, OnchainVerification>>>, // By property ID
+    refreshing: Arc<Mutex<HashSet<String>>>,
+    cache_ttl: Duration,
+}
+
+impl BlockchainService {
+    /// Create the service from config; None when there's no registry contract or RPC endpoint to ask
+    pub fn from_config(config: &BlockchainConfig) -> Option<Self> {
+        let registry_contract = config.registry_contract.clone()?;
+        if config.default_rpc_url.is_none() && config.rpc_urls.is_empty() {
+            return None;
+        }
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(RPC_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        info!("On-chain verification against registry {}", registry_contract);
+        Some(Self {
+            http_client,
+            rpc_urls: config.rpc_urls.clone(),
+            default_rpc_url: config.default_rpc_url.clone(),
+            registry_contract,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            cache_ttl: Duration::from_secs(config.cache_ttl_secs),
+        })
+    }
+
+    /// Last known verification for a listing, without waiting on the chain. Missing or stale
+    /// answers are refreshed in the background, so the first scan after that goes without a badge.
+    pub fn verification(&self, listing: &PropertyQrInfo) -> Option<OnchainVerification> {
+        listing.onchain_id.as_ref()?;
+        listing.owner_wallet_address.as_ref()?;
+
+        let property_id = listing.id.to_hex();
+        let cached = self.cache.lock().unwrap_or_else(|e| e.into_inner()).get(&property_id).copied();
+        let fresh = cached.is_some_and(|entry| {
+            Utc::now().signed_duration_since(entry.checked_at).to_std().unwrap_or_default() < self.cache_ttl
+        });
+        if !fresh {
+            self.refresh(property_id, listing.clone());
+        }
+        cached
+    }
+
+    /// Ask the chain whether the listing's token exists and is held by its recorded wallet
+    pub async fn check(&self, listing: &PropertyQrInfo) -> Result<bool, BlockchainError> {
+        let (Some(token_id), Some(wallet)) = (&listing.onchain_id, &listing.owner_wallet_address) else {
+            return Ok(false);
+        };
+        let Some(rpc_url) = listing.chain_id
+            .and_then(|chain_id| self.rpc_urls.get(&chain_id))
+            .or(self.default_rpc_url.as_ref())
+        else {
+            return Err(BlockchainError::Rpc(format!("no RPC endpoint for chain {:?}", listing.chain_id)));
+        };
+
+        let owner = self.owner_of(rpc_url, token_id).await?;
+        Ok(owner.is_some_and(|owner| owner.eq_ignore_ascii_case(wallet.trim())))
+    }
+
+    /// The latest block at each configured RPC endpoint, by chain ID (None for the default
+    /// endpoint); a cheap call for health checks
+    pub async fn block_numbers(&self) -> Vec<(Option<u64>, Result<u64, BlockchainError>)> {
+        let endpoints = self.default_rpc_url.iter()
+            .map(|rpc_url| (None, rpc_url))
+            .chain(self.rpc_urls.iter().map(|(chain_id, rpc_url)| (Some(*chain_id), rpc_url)));
+
+        futures::future::join_all(endpoints.map(|(chain_id, rpc_url)| async move {
+            (chain_id, self.block_number(rpc_url).await)
+        }))
+        .await
+    }
+
+    async fn block_number(&self, rpc_url: &str) -> Result<u64, BlockchainError> {
+        self.rpc_call(rpc_url, "eth_blockNumber", serde_json::json!([])).await?.into_block_number()
+    }
+
+    async fn owner_of(&self, rpc_url: &str, token_id: &str) -> Result<Option<String>, BlockchainError> {
+        let params = serde_json::json!([
+            { "to": self.registry_contract, "data": owner_of_call_data(token_id)? },
+            "latest",
+        ]);
+        self.rpc_call(rpc_url, "eth_call", params).await?.into_owner()
+    }
+
+    async fn rpc_call(&self, rpc_url: &str, method: &str, params: serde_json::Value) -> Result<RpcResponse, BlockchainError> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self.http_client.post(rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| BlockchainError::Http(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(BlockchainError::Http(format!("status {}", response.status())));
+        }
+
+        response
+            .json::<RpcResponse>()
+            .await
+            .map_err(|e| BlockchainError::Http(e.to_string()))
+    }
+
+    // One check per listing at a time; failures keep whatever answer was cached before
+    fn refresh(&self, property_id: String, listing: PropertyQrInfo) {
+        if !self.refreshing.lock().unwrap_or_else(|e| e.into_inner()).insert(property_id.clone()) {
+            return;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            match service.check(&listing).await {
+                Ok(verified) => {
+                    let verification = OnchainVerification { verified, checked_at: Utc::now() };
+                    service.cache.lock().unwrap_or_else(|e| e.into_inner()).insert(property_id.clone(), verification);
+                }
+                Err(e) => warn!("On-chain verification failed for {}: {}", property_id, e),
+            }
+            service.refreshing.lock().unwrap_or_else(|e| e.into_inner()).remove(&property_id);
+        });
+    }
+}
+
+/// Calldata for ownerOf(token_id); the ID may be decimal or 0x-prefixed hex
+pub fn owner_of_call_data(token_id: &str) -> Result<String, BlockchainError> {
+    let invalid = || BlockchainError::InvalidTokenId(token_id.to_string());
+    let token_id = token_id.trim();
+
+    let mut word = [0u8; 32];
+    if let Some(hex) = token_id.strip_prefix("0x").or_else(|| token_id.strip_prefix("0X")) {
+        if hex.is_empty() || hex.len() > 64 {
+            return Err(invalid());
+        }
+        for (i, digit) in hex.chars().rev().enumerate() {
+            let nibble = digit.to_digit(16).ok_or_else(invalid)? as u8;
+            word[31 - i / 2] |= nibble << (4 * (i % 2));
+        }
+    } else {
+        if token_id.is_empty() {
+            return Err(invalid());
+        }
+        // Big-endian multiply-and-add, one decimal digit at a time
+        for digit in token_id.chars() {
+            let mut carry = digit.to_digit(10).ok_or_else(invalid)?;
+            for byte in word.iter_mut().rev() {
+                let value = *byte as u32 * 10 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return Err(invalid());
+            }
+        }
+    }
+
+    let argument: String = word.iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok(format!("0x{}{}", OWNER_OF_SELECTOR, argument))
+}
+
+// The address in an ABI-encoded return word; the zero address means nobody holds the token
+fn decode_address(result: &str) -> Option<String> {
+    let hex = result.strip_prefix("0x")?;
+    if hex.len() < 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let address = &hex[hex.len() - 40..];
+    if address.chars().all(|c| c == '0') {
+        return None;
+    }
+    Some(format!("0x{}", address.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_of_call_data() {
+        let expected = format!("0x6352211e{:0>64}", "2a");
+        assert_eq!(owner_of_call_data("42").unwrap(), expected);
+        assert_eq!(owner_of_call_data("0x2A").unwrap(), expected);
+
+        // Wider than a u64
+        assert_eq!(
+            owner_of_call_data("18446744073709551616").unwrap(),
+            format!("0x6352211e{:0>64}", "10000000000000000"),
+        );
+
+        for token_id in ["", "0x", "12a", "-1", &"f".repeat(66)] {
+            assert!(owner_of_call_data(token_id).is_err(), "{:?}", token_id);
+        }
+        // 2^256 overflows the word
+        assert!(owner_of_call_data(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+        ).is_err());
+    }
+
+    #[test]
+    fn test_rpc_response_owner() {
+        let owned: RpcResponse = serde_json::from_str(&format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":"0x{:0>64}"}}"#,
+            "AbC0000000000000000000000000000000000001",
+        )).unwrap();
+        assert_eq!(owned.into_owner().unwrap().as_deref(), Some("0xabc0000000000000000000000000000000000001"));
+
+        let burned: RpcResponse = serde_json::from_str(&format!(
+            r#"{{"jsonrpc":"2.0","id":1,"result":"0x{:0>64}"}}"#, "0",
+        )).unwrap();
+        assert_eq!(burned.into_owner().unwrap(), None);
+
+        let missing: RpcResponse = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted: ERC721: invalid token ID"}}"#,
+        ).unwrap();
+        assert_eq!(missing.into_owner().unwrap(), None);
+
+        let limited: RpcResponse = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"rate limit exceeded"}}"#,
+        ).unwrap();
+        assert!(limited.into_owner().is_err());
+    }
+
+    #[test]
+    fn test_rpc_response_block_number() {
+        let response = |json: &str| serde_json::from_str::<RpcResponse>(json).unwrap();
+        assert_eq!(response(r#"{"jsonrpc":"2.0","id":1,"result":"0x1b4"}"#).into_block_number().unwrap(), 436);
+        assert!(response(r#"{"jsonrpc":"2.0","id":1,"result":"436"}"#).into_block_number().is_err());
+        assert!(response(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"rate limited"}}"#)
+            .into_block_number()
+            .is_err());
+    }
+}
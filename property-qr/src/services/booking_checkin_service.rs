@@ -0,0 +1,217 @@
+// src/services/booking_checkin_service.rs
+
+use crate::models::{BookingCheckinCode, BookingStatus, CheckinWindow, QrStatus};
+use crate::services::{
+    impersonation_service::{generate_secret, hash_secret},
+    property_service::PropertyError,
+    PropertyService, QrGeneratorService,
+};
+use chrono::{DateTime, Utc};
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson},
+    options::{IndexOptions, ReturnDocument},
+    Collection, Database, IndexModel,
+};
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct BookingCheckinService {
+    codes: Collection<BookingCheckinCode>,
+    property_service: PropertyService,
+    qr_generator: QrGeneratorService,
+    base_url: String,
+}
+
+#[derive(Debug)]
+pub enum BookingCheckinError {
+    BookingNotFound,
+    InvalidBookingId,
+    NotConfirmed, // Pending, cancelled or completed bookings get no code
+    BookingEnded,
+    CodeNotFound, // Unknown or replaced token
+    AlreadyUsed(DateTime<Utc>),
+    TooEarly(DateTime<Utc>),
+    Expired,
+    GenerationFailed(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for BookingCheckinError {
+    fn from(err: mongodb::error::Error) -> Self {
+        BookingCheckinError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for BookingCheckinError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        BookingCheckinError::DatabaseError(err.into())
+    }
+}
+
+impl From<PropertyError> for BookingCheckinError {
+    fn from(err: PropertyError) -> Self {
+        match err {
+            PropertyError::DatabaseError(e) => BookingCheckinError::DatabaseError(e),
+            _ => BookingCheckinError::BookingNotFound,
+        }
+    }
+}
+
+impl std::fmt::Display for BookingCheckinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookingCheckinError::BookingNotFound => write!(f, "Booking not found"),
+            BookingCheckinError::InvalidBookingId => write!(f, "Invalid booking ID"),
+            BookingCheckinError::NotConfirmed => write!(f, "Booking is not confirmed"),
+            BookingCheckinError::BookingEnded => write!(f, "Booking has already ended"),
+            BookingCheckinError::CodeNotFound => write!(f, "Check-in code not found"),
+            BookingCheckinError::AlreadyUsed(at) => write!(f, "Check-in code already used at {}", at.to_rfc3339()),
+            BookingCheckinError::TooEarly(opens) => write!(f, "Check-in opens at {}", opens.to_rfc3339()),
+            BookingCheckinError::Expired => write!(f, "Check-in code has expired"),
+            BookingCheckinError::GenerationFailed(reason) => write!(f, "Check-in QR generation failed: {}", reason),
+            BookingCheckinError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BookingCheckinError {}
+
+impl BookingCheckinService {
+    /// Create a new booking check-in service
+    pub fn new(
+        db: &Database,
+        property_service: PropertyService,
+        qr_generator: QrGeneratorService,
+        base_url: String,
+    ) -> Self {
+        Self {
+            codes: db.collection("booking_checkin_codes"),
+            property_service,
+            qr_generator,
+            base_url,
+        }
+    }
+
+    /// One live code per booking, found by its token's hash
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        for keys in [doc! { "bookingId": 1 }, doc! { "tokenHash": 1 }] {
+            self.codes
+                .create_index(
+                    IndexModel::builder()
+                        .keys(keys)
+                        .options(IndexOptions::builder().unique(true).build())
+                        .build(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Generate the check-in code for a confirmed booking. An unused code is returned as it is
+    /// unless `force_regenerate` is set; a new code always replaces the old one, whose token stops
+    /// working. The scan URL is returned only with a new code.
+    pub async fn generate(
+        &self,
+        booking_id: &str,
+        force_regenerate: bool,
+        generated_by: Option<ObjectId>,
+    ) -> Result<(BookingCheckinCode, Option<String>, QrStatus), BookingCheckinError> {
+        let booking_id = ObjectId::parse_str(booking_id).map_err(|_| BookingCheckinError::InvalidBookingId)?;
+        let property = self.property_service.get_property_by_booking(&booking_id).await?;
+        let booking = property.booking(&booking_id).ok_or(BookingCheckinError::BookingNotFound)?;
+        if !matches!(booking.status, BookingStatus::Confirmed) {
+            return Err(BookingCheckinError::NotConfirmed);
+        }
+        if booking.check_out < Utc::now() {
+            return Err(BookingCheckinError::BookingEnded);
+        }
+
+        let existing = self.codes.find_one(doc! { "bookingId": booking_id }).await?;
+        if let Some(existing) = existing.as_ref().filter(|code| code.checked_in_at.is_none() && !force_regenerate) {
+            return Ok((existing.clone(), None, QrStatus::Exists));
+        }
+
+        let property_id = property.id.to_hex();
+        let code_id = existing.as_ref().map_or_else(ObjectId::new, |code| code.id);
+        let token = generate_secret();
+        let scan_url = BookingCheckinCode::scan_url_for(&self.base_url, &token);
+        let image_key = BookingCheckinCode::image_key_for(&property_id, &booking_id, &ObjectId::new());
+        let qr_code_url = self.qr_generator
+            .upload_standalone_qr(&image_key, &scan_url)
+            .await
+            .map_err(|e| BookingCheckinError::GenerationFailed(e.to_string()))?;
+
+        let code = BookingCheckinCode {
+            id: code_id,
+            booking_id,
+            property_id,
+            renter_id: booking.renter_id,
+            token_hash: hash_secret(&token),
+            check_in: booking.check_in,
+            check_out: booking.check_out,
+            qr_code_url,
+            image_key,
+            checked_in_at: None,
+            generated_by,
+            created_at: Utc::now(),
+        };
+        self.codes
+            .replace_one(doc! { "bookingId": booking_id }, &code)
+            .upsert(true)
+            .await?;
+
+        let status = match existing {
+            Some(existing) => {
+                if let Err(e) = self.qr_generator.delete_standalone_qr(&existing.image_key).await {
+                    warn!("Failed to delete replaced check-in QR image {}: {}", existing.image_key, e);
+                }
+                QrStatus::Regenerated
+            }
+            None => QrStatus::Generated,
+        };
+
+        info!("Generated check-in QR code for booking {} of property {}", booking_id, code.property_id);
+        Ok((code, Some(scan_url), status))
+    }
+
+    /// Check a renter in with a scanned token: the booking must still be confirmed and `now`
+    /// within its window, and the code unused. The code is spent by whichever scan marks it first.
+    pub async fn check_in(&self, token: &str, now: DateTime<Utc>) -> Result<(BookingCheckinCode, String), BookingCheckinError> {
+        let code = self.codes
+            .find_one(doc! { "tokenHash": hash_secret(token) })
+            .await?
+            .ok_or(BookingCheckinError::CodeNotFound)?;
+        if let Some(checked_in_at) = code.checked_in_at {
+            return Err(BookingCheckinError::AlreadyUsed(checked_in_at));
+        }
+
+        // The platform may have cancelled or moved the booking since the code was generated
+        let property = self.property_service.get_property_by_booking(&code.booking_id).await?;
+        let booking = property.booking(&code.booking_id).ok_or(BookingCheckinError::BookingNotFound)?;
+        if !matches!(booking.status, BookingStatus::Confirmed) {
+            return Err(BookingCheckinError::NotConfirmed);
+        }
+        let code = BookingCheckinCode { check_in: booking.check_in, check_out: booking.check_out, ..code };
+        match code.window(now) {
+            CheckinWindow::Open => {}
+            CheckinWindow::TooEarly(opens) => return Err(BookingCheckinError::TooEarly(opens)),
+            CheckinWindow::Expired => return Err(BookingCheckinError::Expired),
+        }
+
+        let checked_in = self.codes
+            .find_one_and_update(
+                doc! { "_id": code.id, "tokenHash": &code.token_hash, "checkedInAt": null },
+                doc! { "$set": { "checkedInAt": to_bson(&now)? } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?;
+        match checked_in {
+            Some(checked_in) => {
+                info!("Booking {} checked in at property {}", checked_in.booking_id, checked_in.property_id);
+                Ok((checked_in, property.property_name))
+            }
+            // Another scan got there first
+            None => Err(BookingCheckinError::AlreadyUsed(now)),
+        }
+    }
+}
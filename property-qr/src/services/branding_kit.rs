@@ -0,0 +1,150 @@
+// src/services/branding_kit.rs
+
+use std::io::{Cursor, Write};
+
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::models::{BrandingProfile, QrCodeMetadata};
+use crate::services::waitlist_service::csv_field;
+use crate::utils::escape_html;
+
+// Frame size in SVG user units; prints at A6 when scaled to 105mm wide
+const FRAME_WIDTH: u32 = 420;
+const FRAME_HEIGHT: u32 = 560;
+const FRAME_QR_SIZE: u32 = 300;
+const DEFAULT_TAGLINE: &str = "Scan to view this property";
+
+/// Zip an agency's branded frames, one SVG per QR code, with a CSV manifest
+/// mapping each file to its property and scan URL
+pub fn build_branding_kit(
+    branding: &BrandingProfile,
+    entries: &[(QrCodeMetadata, String)], // QR code and current listing name
+) -> Result<Vec<u8>, ZipError> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut manifest = String::from("propertyId,propertyName,scanUrl,qrCodeUrl,file\r\n");
+
+    for (qr_code, property_name) in entries {
+        let file = format!("frames/{}.svg", qr_code.property_id);
+        zip.start_file(file.as_str(), options)?;
+        zip.write_all(branded_frame_svg(branding, qr_code, property_name).as_bytes())?;
+
+        manifest.push_str(&[
+            csv_field(&qr_code.property_id),
+            csv_field(property_name),
+            csv_field(&qr_code.encoded_scan_url().unwrap_or_default()),
+            csv_field(&qr_code.qr_code_url),
+            csv_field(&file),
+        ].join(","));
+        manifest.push_str("\r\n");
+    }
+
+    zip.start_file("manifest.csv", options)?;
+    zip.write_all(manifest.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Printable frame around one QR code: agency logo, listing name, the code and a tagline
+pub fn branded_frame_svg(branding: &BrandingProfile, qr_code: &QrCodeMetadata, property_name: &str) -> String {
+    let logo = branding.logo_url.as_ref()
+        .map(|logo_url| format!(
+            r#"<image href="{}" x="{}" y="24" width="160" height="48" preserveAspectRatio="xMidYMid meet"/>"#,
+            escape_html(logo_url),
+            (FRAME_WIDTH - 160) / 2
+        ))
+        .unwrap_or_default();
+    let qr_x = (FRAME_WIDTH - FRAME_QR_SIZE) / 2;
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">
+  <rect x="4" y="4" width="{inner_width}" height="{inner_height}" rx="24" fill="#FFFFFF" stroke="{primary}" stroke-width="8"/>
+  {logo}
+  <text x="{center}" y="110" text-anchor="middle" font-family="Helvetica, Arial, sans-serif" font-size="22" font-weight="bold" fill="{primary}">{name}</text>
+  <image href="{qr_url}" x="{qr_x}" y="130" width="{qr_size}" height="{qr_size}"/>
+  <rect x="40" y="{cta_y}" width="{cta_width}" height="56" rx="28" fill="{accent}"/>
+  <text x="{center}" y="{cta_text_y}" text-anchor="middle" font-family="Helvetica, Arial, sans-serif" font-size="20" fill="#FFFFFF">{tagline}</text>
+</svg>
+"##,
+        width = FRAME_WIDTH,
+        height = FRAME_HEIGHT,
+        inner_width = FRAME_WIDTH - 8,
+        inner_height = FRAME_HEIGHT - 8,
+        primary = escape_html(&branding.primary_color),
+        accent = escape_html(&branding.accent_color),
+        logo = logo,
+        center = FRAME_WIDTH / 2,
+        name = escape_html(property_name),
+        qr_url = escape_html(&qr_code.qr_code_url),
+        qr_x = qr_x,
+        qr_size = FRAME_QR_SIZE,
+        cta_y = FRAME_HEIGHT - 104,
+        cta_width = FRAME_WIDTH - 80,
+        cta_text_y = FRAME_HEIGHT - 69,
+        tagline = escape_html(branding.tagline.as_deref().unwrap_or(DEFAULT_TAGLINE)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QrGenerationReason, QrMetadata};
+    use std::io::Read;
+
+    fn qr_code(property_id: &str) -> QrCodeMetadata {
+        QrCodeMetadata::new(
+            property_id.to_string(),
+            "{}".to_string(),
+            format!("https://cdn.daobitat.xyz/qr-images/{}.png", property_id),
+            QrMetadata {
+                property_name: "Garden Villa".to_string(),
+                location: "Kilimani".to_string(),
+                action: "for sale".to_string(),
+                price: 10_000_000,
+                onchain_id: None,
+                crypto_accepted: false,
+                primary_image: None,
+                is_verified: true,
+                generated_by: None,
+                generation_reason: QrGenerationReason::NewProperty,
+            },
+        )
+    }
+
+    fn branding() -> BrandingProfile {
+        BrandingProfile {
+            logo_url: Some("https://cdn.kilimanihomes.co.ke/logo.png".to_string()),
+            primary_color: "#0B3D2E".to_string(),
+            accent_color: "#F5A623".to_string(),
+            tagline: None,
+        }
+    }
+
+    #[test]
+    fn test_branded_frame_escapes_listing_name() {
+        let svg = branded_frame_svg(&branding(), &qr_code("507f1f77bcf86cd799439011"), "Villa <2BR> & Pool");
+
+        assert!(svg.contains("Villa &lt;2BR&gt; &amp; Pool"));
+        assert!(svg.contains(r##"stroke="#0B3D2E""##));
+        assert!(svg.contains("https://cdn.kilimanihomes.co.ke/logo.png"));
+        assert!(svg.contains(DEFAULT_TAGLINE));
+    }
+
+    #[test]
+    fn test_kit_has_a_frame_per_code_and_a_manifest() {
+        let entries = vec![
+            (qr_code("507f1f77bcf86cd799439011"), "Garden Villa".to_string()),
+            (qr_code("507f1f77bcf86cd799439012"), "Studio, Kilimani".to_string()),
+        ];
+        let bytes = build_branding_kit(&branding(), &entries).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 3);
+        assert!(archive.by_name("frames/507f1f77bcf86cd799439012.svg").is_ok());
+
+        let mut manifest = String::new();
+        archive.by_name("manifest.csv").unwrap().read_to_string(&mut manifest).unwrap();
+        assert_eq!(manifest.lines().count(), 3);
+        assert!(manifest.contains("\"Studio, Kilimani\""));
+    }
+}
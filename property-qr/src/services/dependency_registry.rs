@@ -0,0 +1,162 @@
+// src/services/dependency_registry.rs
+
+use futures::future::{join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyStatus {
+    Healthy,
+    Degraded, // Working, but with failures worth a look
+    Unhealthy,
+}
+
+impl DependencyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyStatus::Healthy => "healthy",
+            DependencyStatus::Degraded => "degraded",
+            DependencyStatus::Unhealthy => "unhealthy",
+        }
+    }
+}
+
+/// What a probe found
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub status: DependencyStatus,
+    pub message: Option<String>,
+}
+
+impl ProbeResult {
+    pub fn healthy(message: impl Into<String>) -> Self {
+        Self { status: DependencyStatus::Healthy, message: Some(message.into()) }
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self { status: DependencyStatus::Degraded, message: Some(message.into()) }
+    }
+
+    pub fn unhealthy(message: impl Into<String>) -> Self {
+        Self { status: DependencyStatus::Unhealthy, message: Some(message.into()) }
+    }
+}
+
+/// One probe's outcome, timed
+#[derive(Debug, Clone)]
+pub struct DependencyCheck {
+    pub name: &'static str,
+    pub critical: bool,
+    pub status: DependencyStatus,
+    pub message: Option<String>,
+    pub response_time_ms: u64,
+}
+
+type Probe = Arc<dyn Fn() -> BoxFuture<'static, ProbeResult> + Send + Sync>;
+
+#[derive(Clone)]
+struct RegisteredProbe {
+    name: &'static str,
+    critical: bool,
+    probe: Probe,
+}
+
+/// External systems the service talks to, each with a health probe; cheap to clone
+#[derive(Clone, Default)]
+pub struct DependencyRegistry {
+    probes: Vec<RegisteredProbe>,
+}
+
+impl DependencyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a probe; a failing critical dependency makes the whole service unhealthy
+    /// rather than degraded. Registering a name again replaces the earlier probe.
+    pub fn register<F, Fut>(&mut self, name: &'static str, critical: bool, probe: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ProbeResult> + Send + 'static,
+    {
+        let probe: Probe = Arc::new(move || Box::pin(probe()));
+        self.probes.retain(|registered| registered.name != name);
+        self.probes.push(RegisteredProbe { name, critical, probe });
+    }
+
+    /// Registered dependency names, in registration order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.probes.iter().map(|registered| registered.name).collect()
+    }
+
+    /// Run every probe concurrently; one that outlasts the timeout counts as unhealthy
+    pub async fn check_all(&self, timeout: Duration) -> Vec<DependencyCheck> {
+        join_all(self.probes.iter().map(|registered| async move {
+            let start = Instant::now();
+            let result = tokio::time::timeout(timeout, (registered.probe)())
+                .await
+                .unwrap_or_else(|_| ProbeResult::unhealthy(format!("No answer within {}ms", timeout.as_millis())));
+
+            DependencyCheck {
+                name: registered.name,
+                critical: registered.critical,
+                status: result.status,
+                message: result.message,
+                response_time_ms: start.elapsed().as_millis() as u64,
+            }
+        }))
+        .await
+    }
+}
+
+/// Overall status: any critical failure is unhealthy, anything else short of healthy is degraded
+pub fn overall_status(checks: &[DependencyCheck]) -> DependencyStatus {
+    if checks.iter().any(|check| check.critical && check.status == DependencyStatus::Unhealthy) {
+        DependencyStatus::Unhealthy
+    } else if checks.iter().any(|check| check.status != DependencyStatus::Healthy) {
+        DependencyStatus::Degraded
+    } else {
+        DependencyStatus::Healthy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_all_runs_registered_probes() {
+        let mut registry = DependencyRegistry::new();
+        registry.register("mongodb", true, || async { ProbeResult::healthy("ok") });
+        registry.register("webhooks", false, || async { ProbeResult::unhealthy("down") });
+        registry.register("slow", false, || async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            ProbeResult::healthy("late")
+        });
+
+        let checks = registry.check_all(Duration::from_millis(50)).await;
+        assert_eq!(registry.names(), vec!["mongodb", "webhooks", "slow"]);
+        assert_eq!(checks[0].status, DependencyStatus::Healthy);
+        assert_eq!(checks[1].status, DependencyStatus::Unhealthy);
+        assert_eq!(checks[2].status, DependencyStatus::Unhealthy);
+
+        // Non-critical failures only degrade the service
+        assert_eq!(overall_status(&checks), DependencyStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_critical_failure_is_unhealthy() {
+        let mut registry = DependencyRegistry::new();
+        registry.register("mongodb", true, || async { ProbeResult::healthy("ok") });
+        registry.register("mongodb", true, || async { ProbeResult::unhealthy("ping failed") });
+
+        let checks = registry.check_all(Duration::from_secs(1)).await;
+        assert_eq!(checks.len(), 1);
+        assert_eq!(overall_status(&checks), DependencyStatus::Unhealthy);
+        assert_eq!(overall_status(&[]), DependencyStatus::Healthy);
+    }
+}
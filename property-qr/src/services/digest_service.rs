@@ -0,0 +1,330 @@
+// src/services/digest_service.rs
+
+use crate::config::DigestConfig;
+use crate::models::{DigestPreference, DigestTopProperty, OwnerDigest, Property, PropertyWeekScans};
+use crate::services::{AnalyticsService, EmailService, PropertyService, property_service::PropertyError};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+    Collection, Database,
+};
+use std::collections::HashMap;
+use tracing::{error, info, warn};
+
+// How often the scheduler checks whether the digest is due
+const DIGEST_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+pub struct DigestService {
+    preferences: Collection<DigestPreference>,
+    property_service: PropertyService,
+    analytics_service: AnalyticsService,
+    email: EmailService,
+}
+
+#[derive(Debug)]
+pub enum DigestError {
+    InvalidOwnerId,
+    PropertyError(PropertyError),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for DigestError {
+    fn from(err: mongodb::error::Error) -> Self {
+        DigestError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for DigestError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        DigestError::DatabaseError(err.into())
+    }
+}
+
+impl From<PropertyError> for DigestError {
+    fn from(err: PropertyError) -> Self {
+        DigestError::PropertyError(err)
+    }
+}
+
+impl std::fmt::Display for DigestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigestError::InvalidOwnerId => write!(f, "Invalid owner ID format"),
+            DigestError::PropertyError(e) => write!(f, "{}", e),
+            DigestError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DigestError {}
+
+// What one weekly run did
+#[derive(Debug, Default, PartialEq)]
+pub struct DigestReport {
+    pub week: String,
+    pub owners: usize, // Owners whose properties were scanned in the two weeks
+    pub sent: usize,
+    pub opted_out: usize,
+    pub already_sent: usize,
+    pub no_email: usize,
+    pub failed: usize,
+}
+
+impl DigestService {
+    /// Create a new digest service
+    pub fn new(
+        db: &Database,
+        property_service: PropertyService,
+        analytics_service: AnalyticsService,
+        email: EmailService,
+    ) -> Self {
+        Self {
+            preferences: db.collection("digest_preferences"),
+            property_service,
+            analytics_service,
+            email,
+        }
+    }
+
+    /// Whether digests can be delivered
+    pub fn email_enabled(&self) -> bool {
+        self.email.is_enabled()
+    }
+
+    /// An owner's digest settings; owners who never changed them get the digest
+    pub async fn get_preference(&self, owner_id: &str) -> Result<DigestPreference, DigestError> {
+        ObjectId::parse_str(owner_id).map_err(|_| DigestError::InvalidOwnerId)?;
+
+        let preference = self.preferences.find_one(doc! { "_id": owner_id }).await?;
+        Ok(preference.unwrap_or_else(|| DigestPreference {
+            owner_id: owner_id.to_string(),
+            opted_out: false,
+            last_sent_week: None,
+            updated_at: Utc::now(),
+        }))
+    }
+
+    /// Turn an owner's weekly digest off or back on
+    pub async fn set_opt_out(&self, owner_id: &str, opted_out: bool) -> Result<DigestPreference, DigestError> {
+        ObjectId::parse_str(owner_id).map_err(|_| DigestError::InvalidOwnerId)?;
+
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .build();
+        let preference = self.preferences
+            .find_one_and_update(
+                doc! { "_id": owner_id },
+                doc! {
+                    "$set": { "optedOut": opted_out, "updatedAt": to_bson(&Utc::now())? },
+                    "$setOnInsert": { "lastSentWeek": null }
+                },
+            )
+            .with_options(options)
+            .await?
+            .ok_or(DigestError::InvalidOwnerId)?;
+
+        info!("Owner {} {} the weekly digest", owner_id, if opted_out { "opted out of" } else { "opted into" });
+        Ok(preference)
+    }
+
+    /// Email every owner whose properties were scanned their summary of the seven days from
+    /// `week_start`. Owners already sent this week's digest are skipped, so a run can be repeated.
+    pub async fn send_weekly(&self, week_start: NaiveDate) -> Result<DigestReport, DigestError> {
+        let week = week_start.format("%G-W%V").to_string();
+        let mut report = DigestReport { week: week.clone(), ..Default::default() };
+
+        let start = week_start.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let scans = self.analytics_service.get_weekly_property_scans(start).await?;
+        // Scans can name properties that were never valid IDs, e.g. mistyped short URLs
+        let property_ids = scans.iter()
+            .map(|property| property.property_id.clone())
+            .filter(|property_id| ObjectId::parse_str(property_id).is_ok())
+            .collect();
+        let properties = self.property_service.get_properties_by_ids(property_ids).await?;
+
+        let digests = compile_digests(week_start, &properties, &scans);
+        report.owners = digests.len();
+        if digests.is_empty() {
+            return Ok(report);
+        }
+
+        let owner_ids: Vec<&str> = digests.iter().map(|digest| digest.owner_id.as_str()).collect();
+        let preferences: HashMap<String, DigestPreference> = self.preferences
+            .find(doc! { "_id": { "$in": &owner_ids } })
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .map(|preference| (preference.owner_id.clone(), preference))
+            .collect();
+        let owner_object_ids: Vec<ObjectId> = owner_ids.iter().filter_map(|owner_id| ObjectId::parse_str(owner_id).ok()).collect();
+        let emails = self.property_service.get_owner_emails(&owner_object_ids).await?;
+
+        for digest in digests {
+            let preference = preferences.get(&digest.owner_id);
+            if preference.is_some_and(|preference| preference.opted_out) {
+                report.opted_out += 1;
+                continue;
+            }
+            if preference.is_some_and(|preference| preference.last_sent_week.as_deref() == Some(week.as_str())) {
+                report.already_sent += 1;
+                continue;
+            }
+            let Some(email) = emails.get(&digest.owner_id) else {
+                report.no_email += 1;
+                continue;
+            };
+
+            let (subject, body) = digest.render();
+            match self.email.send(email, &subject, &body).await {
+                Ok(()) => {
+                    self.preferences
+                        .update_one(
+                            doc! { "_id": &digest.owner_id },
+                            doc! {
+                                "$set": { "lastSentWeek": &week },
+                                "$setOnInsert": { "optedOut": false, "updatedAt": to_bson(&Utc::now())? }
+                            },
+                        )
+                        .upsert(true)
+                        .await?;
+                    report.sent += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to send weekly digest to owner {}: {}", digest.owner_id, e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Weekly digest {}: {} sent, {} opted out, {} already sent, {} without an email, {} failed",
+            report.week, report.sent, report.opted_out, report.already_sent, report.no_email, report.failed
+        );
+        Ok(report)
+    }
+
+    /// Send the digest in the background once it's due each week
+    pub fn spawn_scheduler(&self, config: DigestConfig) {
+        let digest_service = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+            let mut last_week_start = None;
+            loop {
+                ticker.tick().await;
+                let Some(week_start) = due_week_start(Utc::now(), &config) else {
+                    continue;
+                };
+                if last_week_start == Some(week_start) {
+                    continue;
+                }
+
+                match digest_service.send_weekly(week_start).await {
+                    Ok(_) => last_week_start = Some(week_start),
+                    Err(e) => error!("Weekly digest failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+/// The first day of the week to report on, when `now` is on or after the configured send time
+fn due_week_start(now: DateTime<Utc>, config: &DigestConfig) -> Option<NaiveDate> {
+    (now.weekday() == config.weekday && now.hour() >= config.hour_utc)
+        .then(|| now.date_naive() - Duration::days(7))
+}
+
+/// Roll property scans up to their owners, most scanned property first; properties
+/// that are no longer listed are left out
+fn compile_digests(week_start: NaiveDate, properties: &[Property], scans: &[PropertyWeekScans]) -> Vec<OwnerDigest> {
+    let properties: HashMap<String, &Property> = properties.iter().map(|property| (property.id.to_hex(), property)).collect();
+
+    let mut digests: HashMap<String, OwnerDigest> = HashMap::new();
+    for property_scans in scans {
+        let Some(property) = properties.get(&property_scans.property_id) else {
+            continue;
+        };
+        let owner_id = property.owner.to_hex();
+        let digest = digests.entry(owner_id.clone()).or_insert_with(|| OwnerDigest {
+            owner_id,
+            week_start,
+            total_scans: 0,
+            previous_scans: 0,
+            top_property: None,
+        });
+
+        digest.total_scans += property_scans.scans;
+        digest.previous_scans += property_scans.previous_scans;
+        let is_top = property_scans.scans > 0
+            && digest.top_property.as_ref().is_none_or(|top| {
+                (property_scans.scans, &top.property_id) > (top.scans, &property_scans.property_id)
+            });
+        if is_top {
+            digest.top_property = Some(DigestTopProperty {
+                property_id: property_scans.property_id.clone(),
+                property_name: property.property_name.clone(),
+                scans: property_scans.scans,
+            });
+        }
+    }
+
+    let mut digests: Vec<OwnerDigest> = digests.into_values().collect();
+    digests.sort_by(|a, b| a.owner_id.cmp(&b.owner_id));
+    digests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Weekday};
+
+    fn property(owner: ObjectId, name: &str) -> Property {
+        Property {
+            id: ObjectId::new(),
+            owner,
+            property_name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn week_scans(property: &Property, scans: i64, previous_scans: i64) -> PropertyWeekScans {
+        PropertyWeekScans { property_id: property.id.to_hex(), scans, previous_scans }
+    }
+
+    #[test]
+    fn test_compile_digests() {
+        let week_start = NaiveDate::from_ymd_opt(2026, 10, 5).unwrap();
+        let (owner, other_owner) = (ObjectId::new(), ObjectId::new());
+        let villa = property(owner, "Garden Villa");
+        let flat = property(owner, "City Flat");
+        let bungalow = property(other_owner, "Beach Bungalow");
+        let delisted = PropertyWeekScans { property_id: ObjectId::new().to_hex(), scans: 99, previous_scans: 0 };
+
+        let scans = vec![week_scans(&villa, 30, 10), week_scans(&flat, 12, 20), week_scans(&bungalow, 0, 4), delisted];
+        let digests = compile_digests(week_start, &[villa.clone(), flat, bungalow], &scans);
+
+        assert_eq!(digests.len(), 2);
+        let digest = digests.iter().find(|digest| digest.owner_id == owner.to_hex()).unwrap();
+        assert_eq!((digest.total_scans, digest.previous_scans), (42, 30));
+        assert_eq!(digest.top_property.as_ref().map(|top| top.property_name.as_str()), Some("Garden Villa"));
+
+        // An owner whose codes went quiet still hears about it, with no top property
+        let quiet = digests.iter().find(|digest| digest.owner_id == other_owner.to_hex()).unwrap();
+        assert_eq!((quiet.total_scans, quiet.previous_scans, quiet.top_property.clone()), (0, 4, None));
+    }
+
+    #[test]
+    fn test_due_week_start() {
+        let config = DigestConfig { enabled: true, weekday: Weekday::Mon, hour_utc: 6 };
+        let monday = Utc.with_ymd_and_hms(2026, 10, 12, 6, 30, 0).unwrap();
+
+        assert_eq!(due_week_start(monday, &config), NaiveDate::from_ymd_opt(2026, 10, 5));
+        assert_eq!(due_week_start(monday - Duration::hours(1), &config), None);
+        assert_eq!(due_week_start(monday + Duration::days(1), &config), None);
+    }
+}
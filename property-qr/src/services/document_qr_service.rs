@@ -0,0 +1,226 @@
+// src/services/document_qr_service.rs
+
+use crate::models::{CreateDocumentQrRequest, DocumentQrCode, DocumentVerification, QrStatus, ScanEvent};
+use crate::services::{property_service::PropertyError, PropertyService, QrGeneratorService};
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::IndexOptions,
+    Collection, Database, IndexModel,
+};
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct DocumentQrService {
+    codes: Collection<DocumentQrCode>,
+    property_service: PropertyService,
+    qr_generator: QrGeneratorService,
+    base_url: String,
+}
+
+#[derive(Debug)]
+pub enum DocumentQrError {
+    NotFound, // No active code for the document
+    PropertyNotFound,
+    DocumentNotFound,
+    InvalidPropertyId,
+    GenerationFailed(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for DocumentQrError {
+    fn from(err: mongodb::error::Error) -> Self {
+        DocumentQrError::DatabaseError(err)
+    }
+}
+
+impl From<PropertyError> for DocumentQrError {
+    fn from(err: PropertyError) -> Self {
+        match err {
+            PropertyError::NotFound | PropertyError::NotEligibleForQr(_) => DocumentQrError::PropertyNotFound,
+            PropertyError::InvalidId => DocumentQrError::InvalidPropertyId,
+            PropertyError::DatabaseError(e) => DocumentQrError::DatabaseError(e),
+        }
+    }
+}
+
+impl std::fmt::Display for DocumentQrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentQrError::NotFound => write!(f, "Document QR code not found"),
+            DocumentQrError::PropertyNotFound => write!(f, "Property not found"),
+            DocumentQrError::DocumentNotFound => write!(f, "Property has no such document"),
+            DocumentQrError::InvalidPropertyId => write!(f, "Invalid property ID"),
+            DocumentQrError::GenerationFailed(reason) => write!(f, "Document QR generation failed: {}", reason),
+            DocumentQrError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DocumentQrError {}
+
+impl DocumentQrService {
+    /// Create a new document QR service
+    pub fn new(
+        db: &Database,
+        property_service: PropertyService,
+        qr_generator: QrGeneratorService,
+        base_url: String,
+    ) -> Self {
+        Self {
+            codes: db.collection("document_qr_codes"),
+            property_service,
+            qr_generator,
+            base_url,
+        }
+    }
+
+    /// One code per document
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.codes
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "propertyId": 1, "documentId": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Generate the code for one of a property's documents; an active code is returned as it
+    /// is unless `force_regenerate` is set
+    pub async fn generate(
+        &self,
+        request: CreateDocumentQrRequest,
+        generated_by: Option<ObjectId>,
+    ) -> Result<(DocumentQrCode, QrStatus), DocumentQrError> {
+        let CreateDocumentQrRequest { property_id, document_id, force_regenerate } = request;
+
+        let property = self.property_service.get_property_by_id(&property_id).await?;
+        if property.document(&document_id).is_none() {
+            return Err(DocumentQrError::DocumentNotFound);
+        }
+
+        let existing = self.codes
+            .find_one(doc! { "propertyId": &property_id, "documentId": &document_id })
+            .await?;
+        if let Some(existing) = existing.as_ref().filter(|code| code.active && !force_regenerate.unwrap_or(false)) {
+            return Ok((existing.clone(), QrStatus::Exists));
+        }
+
+        // A regenerated image gets a new key, as CDN caches would keep serving the old one
+        let qr_version = existing.as_ref().map_or(1, |code| code.qr_version + 1);
+        let scan_url = DocumentQrCode::scan_url_for(&self.base_url, &property_id, &document_id);
+        let image_key = DocumentQrCode::image_key_for(&property_id, &document_id, qr_version);
+        let qr_code_url = self.qr_generator
+            .upload_standalone_qr(&image_key, &scan_url)
+            .await
+            .map_err(|e| DocumentQrError::GenerationFailed(e.to_string()))?;
+
+        let (code, status) = match existing {
+            Some(existing) => {
+                let code = DocumentQrCode {
+                    qr_version,
+                    scan_url,
+                    qr_code_url,
+                    image_key,
+                    active: true,
+                    generated_by: generated_by.or(existing.generated_by),
+                    updated_at: Utc::now(),
+                    ..existing.clone()
+                };
+                if let Err(e) = self.qr_generator.delete_standalone_qr(&existing.image_key).await {
+                    warn!("Failed to delete replaced document QR image {}: {}", existing.image_key, e);
+                }
+                (code, QrStatus::Regenerated)
+            }
+            None => (
+                DocumentQrCode::new(property_id, document_id, &self.base_url, qr_code_url, generated_by),
+                QrStatus::Generated,
+            ),
+        };
+
+        self.codes
+            .replace_one(doc! { "_id": code.id }, &code)
+            .upsert(true)
+            .await?;
+
+        info!("Generated QR code for document {} of property {}", code.document_id, code.property_id);
+        Ok((code, status))
+    }
+
+    /// What the page of an active document code shows
+    pub async fn verification(&self, property_id: &str, document_id: &str) -> Result<DocumentVerification, DocumentQrError> {
+        self.codes
+            .find_one(doc! { "propertyId": property_id, "documentId": document_id, "active": true })
+            .await?
+            .ok_or(DocumentQrError::NotFound)?;
+
+        let property = self.property_service.get_property_by_id(property_id).await?;
+        let document = property.document(document_id).ok_or(DocumentQrError::DocumentNotFound)?;
+        Ok(DocumentVerification::new(&property, document, Utc::now()))
+    }
+
+    /// Count a scan of a document's code; crawlers aren't counted. Document scans are kept
+    /// apart from the listing's scan analytics.
+    pub async fn record_scan(
+        &self,
+        property_id: &str,
+        document_id: &str,
+        user_agent: Option<&str>,
+    ) -> Result<(), DocumentQrError> {
+        if user_agent.is_some_and(ScanEvent::is_bot_user_agent) {
+            return Ok(());
+        }
+
+        self.codes
+            .update_one(
+                doc! { "propertyId": property_id, "documentId": document_id },
+                doc! { "$inc": { "scans": 1i64 } },
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{AnalyticsService, S3Storage, StorageService};
+    use mongodb::Client;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_document_scan_leaves_listing_scans_alone() {
+        let client = Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .expect("Failed to connect to MongoDB");
+        let db = client.database("test_document_qr");
+        let property_service = PropertyService::new(&db);
+        let storage = StorageService::new(Arc::new(
+            S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).expect("Failed to create S3 storage"),
+        ));
+        let base_url = "https://qr-service.daobitat.xyz".to_string();
+        let qr_generator = QrGeneratorService::new(&db, property_service.clone(), storage, base_url.clone());
+        let service = DocumentQrService::new(&db, property_service, qr_generator, base_url.clone());
+        let analytics_service = AnalyticsService::new(&db);
+
+        let property_id = ObjectId::new().to_hex();
+        let code = DocumentQrCode::new(property_id.clone(), "title-deed".to_string(), &base_url, String::new(), None);
+        service.codes.insert_one(&code).await.expect("Failed to store document code");
+
+        let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1";
+        service.record_scan(&property_id, "title-deed", Some(iphone)).await.expect("Failed to record scan");
+        service.record_scan(&property_id, "title-deed", Some("Twitterbot/1.0")).await.expect("Failed to record scan");
+
+        let code = service.codes.find_one(doc! { "_id": code.id }).await.unwrap().expect("Document code missing");
+        assert_eq!(code.scans, 1);
+
+        let analytics = analytics_service.get_property_analytics(&property_id, true)
+            .await
+            .expect("Failed to get analytics");
+        assert_eq!(analytics.analytics.total_scans, 0);
+        assert!(analytics.recent_scans.is_empty());
+    }
+}
@@ -0,0 +1,450 @@
+// src/services/email_service.rs
+
+use crate::config::{EmailConfig, EmailProviderKind, settings::AwsConfig};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tracing::{info, warn};
+
+const SENDGRID_API_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+const SES_SEND_PATH: &str = "/v2/email/outbound-emails";
+const SEND_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug)]
+pub enum EmailError {
+    Disabled,
+    InvalidAddress(String),
+    ProviderError(String),
+}
+
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailError::Disabled => write!(f, "Email sending is not configured"),
+            EmailError::InvalidAddress(reason) => write!(f, "Invalid email address: {}", reason),
+            EmailError::ProviderError(reason) => write!(f, "Email provider error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+// A plain-text email, ready for a transport
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub from_address: String,
+    pub from_name: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// A provider emails are sent through
+pub trait EmailTransport: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> BoxFuture<'a, Result<(), EmailError>>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(SEND_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_default()
+}
+
+async fn check_response(response: Result<reqwest::Response, reqwest::Error>) -> Result<(), EmailError> {
+    let response = response.map_err(|e| EmailError::ProviderError(e.to_string()))?;
+    if !response.status().is_success() {
+        warn!("Email provider rejected message with status {}", response.status());
+        return Err(EmailError::ProviderError(format!("status {}", response.status())));
+    }
+    Ok(())
+}
+
+/// SendGrid's v3 mail API
+pub struct SendGridTransport {
+    http_client: reqwest::Client,
+    api_key: String,
+}
+
+impl SendGridTransport {
+    pub fn new(api_key: String) -> Self {
+        Self { http_client: http_client(), api_key }
+    }
+}
+
+impl EmailTransport for SendGridTransport {
+    fn name(&self) -> &'static str {
+        "sendgrid"
+    }
+
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> BoxFuture<'a, Result<(), EmailError>> {
+        Box::pin(async move {
+            let response = self.http_client
+                .post(SENDGRID_API_URL)
+                .bearer_auth(&self.api_key)
+                .json(&serde_json::json!({
+                    "personalizations": [{ "to": [{ "email": message.to }] }],
+                    "from": { "email": message.from_address, "name": message.from_name },
+                    "subject": message.subject,
+                    "content": [{ "type": "text/plain", "value": message.body }],
+                }))
+                .send()
+                .await;
+            check_response(response).await
+        })
+    }
+}
+
+/// Amazon SES's v2 SendEmail API, signed with the service's AWS credentials
+pub struct SesTransport {
+    http_client: reqwest::Client,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl SesTransport {
+    pub fn new(region: String, access_key_id: String, secret_access_key: String, session_token: Option<String>) -> Self {
+        Self {
+            http_client: http_client(),
+            region,
+            access_key_id,
+            secret_access_key,
+            session_token,
+        }
+    }
+
+    fn host(&self) -> String {
+        format!("email.{}.amazonaws.com", self.region)
+    }
+
+    /// The Authorization header for a POST of `payload` to the send path, per AWS Signature Version 4
+    fn authorization(&self, payload: &[u8], now: DateTime<Utc>) -> String {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{}/{}/ses/aws4_request", date, self.region);
+
+        let mut headers = vec![
+            ("content-type", "application/json".to_string()),
+            ("host", self.host()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(session_token) = &self.session_token {
+            headers.push(("x-amz-security-token", session_token.clone()));
+        }
+        let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+
+        let canonical_request = format!(
+            "POST\n{}\n\n{}\n{}\n{}",
+            SES_SEND_PATH,
+            canonical_headers,
+            signed_headers,
+            hex(&Sha256::digest(payload)),
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&Sha256::digest(canonical_request.as_bytes())),
+        );
+        let signing_key = sigv4_signing_key(&self.secret_access_key, &date, &self.region, "ses");
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id,
+            scope,
+            signed_headers,
+            hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes())),
+        )
+    }
+}
+
+impl EmailTransport for SesTransport {
+    fn name(&self) -> &'static str {
+        "ses"
+    }
+
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> BoxFuture<'a, Result<(), EmailError>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&serde_json::json!({
+                "FromEmailAddress": format!("\"{}\" <{}>", message.from_name.replace('"', ""), message.from_address),
+                "Destination": { "ToAddresses": [message.to] },
+                "Content": {
+                    "Simple": {
+                        "Subject": { "Data": message.subject, "Charset": "UTF-8" },
+                        "Body": { "Text": { "Data": message.body, "Charset": "UTF-8" } },
+                    }
+                },
+            }))
+            .map_err(|e| EmailError::ProviderError(e.to_string()))?;
+
+            let now = Utc::now();
+            let mut request = self.http_client
+                .post(format!("https://{}{}", self.host(), SES_SEND_PATH))
+                .header("content-type", "application/json")
+                .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+                .header("authorization", self.authorization(&payload, now));
+            if let Some(session_token) = &self.session_token {
+                request = request.header("x-amz-security-token", session_token);
+            }
+
+            check_response(request.body(payload).send().await).await
+        })
+    }
+}
+
+/// A plain SMTP relay, e.g. a local MTA or mail sidecar; no TLS or authentication,
+/// so keep it on a trusted network
+pub struct SmtpTransport {
+    host: String,
+    port: u16,
+}
+
+impl SmtpTransport {
+    pub fn new(host: String, port: u16) -> Self {
+        Self { host, port }
+    }
+
+    async fn deliver(&self, message: &EmailMessage) -> Result<(), EmailError> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(|e| EmailError::ProviderError(format!("SMTP connect failed: {}", e)))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        smtp_reply(&mut reader, 220).await?;
+        let commands = [
+            ("EHLO daobitat-qr".to_string(), 250),
+            (format!("MAIL FROM:<{}>", message.from_address), 250),
+            (format!("RCPT TO:<{}>", message.to), 250),
+            ("DATA".to_string(), 354),
+        ];
+        for (command, expected) in commands {
+            smtp_command(&mut writer, &command).await?;
+            smtp_reply(&mut reader, expected).await?;
+        }
+
+        writer.write_all(smtp_data(message, Utc::now()).as_bytes())
+            .await
+            .map_err(|e| EmailError::ProviderError(e.to_string()))?;
+        smtp_reply(&mut reader, 250).await?;
+
+        // The message is accepted; a failed goodbye doesn't matter
+        let _ = smtp_command(&mut writer, "QUIT").await;
+        Ok(())
+    }
+}
+
+impl EmailTransport for SmtpTransport {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn send<'a>(&'a self, message: &'a EmailMessage) -> BoxFuture<'a, Result<(), EmailError>> {
+        Box::pin(async move {
+            tokio::time::timeout(Duration::from_secs(SEND_TIMEOUT_SECS), self.deliver(message))
+                .await
+                .map_err(|_| EmailError::ProviderError("SMTP relay timed out".to_string()))?
+        })
+    }
+}
+
+async fn smtp_command(writer: &mut tokio::net::tcp::OwnedWriteHalf, command: &str) -> Result<(), EmailError> {
+    writer.write_all(format!("{}\r\n", command).as_bytes())
+        .await
+        .map_err(|e| EmailError::ProviderError(e.to_string()))
+}
+
+/// Read a (possibly multi-line) reply and check its code
+async fn smtp_reply(reader: &mut BufReader<tokio::net::tcp::OwnedReadHalf>, expected: u16) -> Result<(), EmailError> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.map_err(|e| EmailError::ProviderError(e.to_string()))? == 0 {
+            return Err(EmailError::ProviderError("SMTP relay closed the connection".to_string()));
+        }
+        // "250-..." continues the reply, "250 ..." ends it
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        return match line.get(..3).and_then(|code| code.parse::<u16>().ok()) {
+            Some(code) if code == expected => Ok(()),
+            _ => Err(EmailError::ProviderError(format!("SMTP relay replied {}", line.trim_end()))),
+        };
+    }
+}
+
+/// Headers and dot-stuffed body of a message, ending with the DATA terminator
+fn smtp_data(message: &EmailMessage, now: DateTime<Utc>) -> String {
+    let header = |value: &str| value.replace(['\r', '\n'], " ");
+    let mut data = format!(
+        "From: \"{}\" <{}>\r\nTo: <{}>\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+        header(&message.from_name).replace('"', ""),
+        message.from_address,
+        message.to,
+        header(&message.subject),
+        now.to_rfc2822(),
+    );
+    for line in message.body.lines() {
+        if line.starts_with('.') {
+            data.push('.');
+        }
+        data.push_str(line);
+        data.push_str("\r\n");
+    }
+    data.push_str(".\r\n");
+    data
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sigv4_signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let date_key = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, service.as_bytes());
+    hmac_sha256(&service_key, b"aws4_request")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Sends plain-text email through whichever provider is configured
+#[derive(Clone)]
+pub struct EmailService {
+    transport: Option<Arc<dyn EmailTransport>>,
+    from_address: String,
+    from_name: String,
+}
+
+impl EmailService {
+    /// Pick the transport for the configured provider; SES signs with the service's AWS credentials
+    pub fn from_config(config: &EmailConfig, aws: &AwsConfig) -> Result<Self, EmailError> {
+        let from_address = config.from_address.clone().unwrap_or_default();
+        let missing = |setting: &str| EmailError::ProviderError(format!("{:?} needs {}", config.provider, setting));
+
+        let transport: Option<Arc<dyn EmailTransport>> = match config.provider {
+            EmailProviderKind::SendGrid => {
+                let api_key = config.api_key.clone().ok_or_else(|| missing("EMAIL_API_KEY"))?;
+                Some(Arc::new(SendGridTransport::new(api_key)))
+            }
+            EmailProviderKind::Ses => {
+                let (Some(access_key_id), Some(secret_access_key)) = (&aws.access_key_id, &aws.secret_access_key) else {
+                    return Err(missing("AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY"));
+                };
+                Some(Arc::new(SesTransport::new(
+                    aws.region.clone(),
+                    access_key_id.clone(),
+                    secret_access_key.clone(),
+                    aws.session_token.clone(),
+                )))
+            }
+            EmailProviderKind::Smtp => {
+                let host = config.smtp_host.clone().ok_or_else(|| missing("EMAIL_SMTP_HOST"))?;
+                Some(Arc::new(SmtpTransport::new(host, config.smtp_port)))
+            }
+            EmailProviderKind::Disabled => None,
+        };
+
+        Ok(Self::with_transport(transport, from_address, config.from_name.clone()))
+    }
+
+    /// An email service sending through `transport`, or a disabled one when None
+    pub fn with_transport(transport: Option<Arc<dyn EmailTransport>>, from_address: String, from_name: String) -> Self {
+        let transport = transport.filter(|_| !from_address.is_empty());
+        Self { transport, from_address, from_name }
+    }
+
+    /// Whether an email provider is configured
+    pub fn is_enabled(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// Send a plain-text email
+    pub async fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), EmailError> {
+        crate::utils::validate_email(to).map_err(|e| EmailError::InvalidAddress(e.message))?;
+
+        let Some(transport) = &self.transport else {
+            return Err(EmailError::Disabled);
+        };
+
+        let message = EmailMessage {
+            from_address: self.from_address.clone(),
+            from_name: self.from_name.clone(),
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        };
+        transport.send(&message).await?;
+
+        info!("Sent email via {}", transport.name());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn message(subject: &str, body: &str) -> EmailMessage {
+        EmailMessage {
+            from_address: "listings@daobitat.xyz".to_string(),
+            from_name: "DAO-Bitat".to_string(),
+            to: "owner@example.com".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sigv4_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = sigv4_signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[test]
+    fn test_ses_authorization_header() {
+        let ses = SesTransport::new("eu-west-1".into(), "AKIDEXAMPLE".into(), "secret".into(), Some("token".into()));
+        let now = Utc.with_ymd_and_hms(2026, 10, 12, 6, 0, 0).unwrap();
+        let authorization = ses.authorization(b"{}", now);
+
+        assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261012/eu-west-1/ses/aws4_request, "));
+        assert!(authorization.contains("SignedHeaders=content-type;host;x-amz-date;x-amz-security-token, "));
+        // Signing is deterministic for a given request and time
+        assert_eq!(authorization, ses.authorization(b"{}", now));
+        assert_ne!(authorization, ses.authorization(b"{ }", now));
+    }
+
+    #[test]
+    fn test_smtp_data() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 12, 6, 0, 0).unwrap();
+        let data = smtp_data(&message("Weekly\r\nBcc: x@example.com", "Hello\n.hidden\nBye"), now);
+
+        assert!(data.contains("Subject: Weekly  Bcc: x@example.com\r\n"));
+        assert!(data.ends_with("\r\n\r\nHello\r\n..hidden\r\nBye\r\n.\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_send_when_disabled() {
+        let service = EmailService::with_transport(None, String::new(), "DAO-Bitat".to_string());
+
+        assert!(!service.is_enabled());
+        assert!(matches!(service.send("owner@example.com", "Hi", "hi").await, Err(EmailError::Disabled)));
+        assert!(matches!(service.send("not-an-email", "Hi", "hi").await, Err(EmailError::InvalidAddress(_))));
+    }
+}
@@ -0,0 +1,297 @@
+// src/services/event_publisher.rs
+
+use crate::config::{EventStreamConfig, EventStreamKind};
+use crate::models::ScanEvent;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug)]
+pub enum EventStreamError {
+    Connect(String),
+    Publish(String),
+}
+
+impl std::fmt::Display for EventStreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventStreamError::Connect(reason) => write!(f, "Event stream connection failed: {}", reason),
+            EventStreamError::Publish(reason) => write!(f, "Event publish failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EventStreamError {}
+
+/// A broker scan events are published to; `key` keeps one property's events in order
+pub trait EventSink: Send + Sync {
+    /// Short name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    fn publish(&self, key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), EventStreamError>>;
+}
+
+/// Kafka producer for one topic, spreading properties across its partitions
+pub struct KafkaEventSink {
+    partitions: Vec<rskafka::client::partition::PartitionClient>,
+}
+
+impl KafkaEventSink {
+    /// Connect to the bootstrap brokers and look up the topic's partitions; the topic must exist
+    pub async fn connect(brokers: &str, topic: &str) -> Result<Self, EventStreamError> {
+        let brokers = brokers.split(',').map(|broker| broker.trim().to_string()).collect();
+        let client = rskafka::client::ClientBuilder::new(brokers)
+            .build()
+            .await
+            .map_err(|e| EventStreamError::Connect(e.to_string()))?;
+
+        let partition_ids = client.list_topics()
+            .await
+            .map_err(|e| EventStreamError::Connect(e.to_string()))?
+            .into_iter()
+            .find(|t| t.name == topic)
+            .map(|t| t.partitions)
+            .ok_or_else(|| EventStreamError::Connect(format!("topic '{}' not found", topic)))?;
+
+        let mut partitions = Vec::with_capacity(partition_ids.len());
+        for partition in partition_ids {
+            let partition_client = client
+                .partition_client(topic, partition, rskafka::client::partition::UnknownTopicHandling::Error)
+                .await
+                .map_err(|e| EventStreamError::Connect(e.to_string()))?;
+            partitions.push(partition_client);
+        }
+        if partitions.is_empty() {
+            return Err(EventStreamError::Connect(format!("topic '{}' has no partitions", topic)));
+        }
+
+        Ok(Self { partitions })
+    }
+}
+
+impl EventSink for KafkaEventSink {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn publish(&self, key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), EventStreamError>> {
+        Box::pin(async move {
+            let partition = &self.partitions[partition_for(&key, self.partitions.len())];
+            let record = rskafka::record::Record {
+                key: Some(key.into_bytes()),
+                value: Some(payload),
+                headers: Default::default(),
+                timestamp: chrono::Utc::now(),
+            };
+
+            partition
+                .produce(vec![record], rskafka::client::partition::Compression::NoCompression)
+                .await
+                .map(|_| ())
+                .map_err(|e| EventStreamError::Publish(e.to_string()))
+        })
+    }
+}
+
+/// NATS publisher for one subject
+pub struct NatsEventSink {
+    client: async_nats::Client,
+    subject: String,
+}
+
+impl NatsEventSink {
+    pub async fn connect(url: &str, subject: &str) -> Result<Self, EventStreamError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| EventStreamError::Connect(e.to_string()))?;
+
+        Ok(Self { client, subject: subject.to_string() })
+    }
+}
+
+impl EventSink for NatsEventSink {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    fn publish(&self, _key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), EventStreamError>> {
+        Box::pin(async move {
+            self.client
+                .publish(self.subject.clone(), payload.into())
+                .await
+                .map_err(|e| EventStreamError::Publish(e.to_string()))
+        })
+    }
+}
+
+/// Stable partition for a key (FNV-1a), so a property's events always land on the same one
+fn partition_for(key: &str, partitions: usize) -> usize {
+    let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    (hash % partitions as u64) as usize
+}
+
+/// Point-in-time view of scan event streaming
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPublisherMetrics {
+    pub sink: &'static str,
+    pub published: u64,
+    pub dropped: u64, // Queue full; the event is still in Mongo
+    pub failures: u64,
+    #[serde(rename = "queueDepth")]
+    pub queue_depth: usize,
+}
+
+#[derive(Default)]
+struct PublisherCounters {
+    published: AtomicU64,
+    dropped: AtomicU64,
+    failures: AtomicU64,
+}
+
+/// Streams recorded scan events, serialized as JSON, to the configured sink from a
+/// background task so scans never wait on the broker; cheap to clone
+#[derive(Clone)]
+pub struct EventPublisher {
+    sender: mpsc::Sender<ScanEvent>,
+    sink_name: &'static str,
+    counters: Arc<PublisherCounters>,
+}
+
+impl EventPublisher {
+    /// Start publishing to any sink, buffering up to `capacity` events
+    pub fn spawn(sink: Arc<dyn EventSink>, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let counters = Arc::new(PublisherCounters::default());
+        let sink_name = sink.name();
+
+        tokio::spawn(run_publisher(sink, receiver, counters.clone()));
+
+        Self { sender, sink_name, counters }
+    }
+
+    /// Connect to the sink selected in config; None when streaming is disabled
+    pub async fn from_config(config: &EventStreamConfig) -> Result<Option<Self>, EventStreamError> {
+        let connect = async {
+            let sink: Arc<dyn EventSink> = match config.kind {
+                EventStreamKind::Kafka => Arc::new(KafkaEventSink::connect(&config.url, &config.topic).await?),
+                EventStreamKind::Nats => Arc::new(NatsEventSink::connect(&config.url, &config.topic).await?),
+                EventStreamKind::Disabled => return Ok(None),
+            };
+            Ok(Some(sink))
+        };
+
+        let sink = tokio::time::timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS), connect)
+            .await
+            .map_err(|_| EventStreamError::Connect(format!("timed out connecting to {}", config.url)))??;
+
+        Ok(sink.map(|sink| {
+            info!("Streaming scan events to {} '{}'", sink.name(), config.topic);
+            Self::spawn(sink, config.queue_capacity)
+        }))
+    }
+
+    /// Queue a scan event for publishing; dropped with a warning if the queue is full
+    pub fn publish(&self, scan_event: &ScanEvent) {
+        if self.sender.try_send(scan_event.clone()).is_err() {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+            warn!("Event stream queue full, dropping scan event {}", scan_event.id);
+        }
+    }
+
+    pub fn metrics(&self) -> EventPublisherMetrics {
+        EventPublisherMetrics {
+            sink: self.sink_name,
+            published: self.counters.published.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            failures: self.counters.failures.load(Ordering::Relaxed),
+            queue_depth: self.sender.max_capacity() - self.sender.capacity(),
+        }
+    }
+}
+
+async fn run_publisher(
+    sink: Arc<dyn EventSink>,
+    mut receiver: mpsc::Receiver<ScanEvent>,
+    counters: Arc<PublisherCounters>,
+) {
+    while let Some(scan_event) = receiver.recv().await {
+        let payload = match serde_json::to_vec(&scan_event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                counters.failures.fetch_add(1, Ordering::Relaxed);
+                warn!("Failed to serialize scan event {}: {}", scan_event.id, e);
+                continue;
+            }
+        };
+
+        match sink.publish(scan_event.property_id.clone(), payload).await {
+            Ok(()) => {
+                counters.published.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                counters.failures.fetch_add(1, Ordering::Relaxed);
+                warn!("Failed to publish scan event {} to {}: {}", scan_event.id, sink.name(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{RedirectType, ScanSource};
+    use std::sync::Mutex;
+
+    struct RecordingSink {
+        published: Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn publish(&self, key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), EventStreamError>> {
+            let value = serde_json::from_slice(&payload).unwrap();
+            self.published.lock().unwrap().push((key, value));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_partition_for_is_stable() {
+        assert_eq!(partition_for("507f1f77bcf86cd799439011", 6), partition_for("507f1f77bcf86cd799439011", 6));
+        assert!(partition_for("507f1f77bcf86cd799439011", 6) < 6);
+        assert_eq!(partition_for("anything", 1), 0);
+    }
+
+    #[tokio::test]
+    async fn test_publishes_serialized_scan_events() {
+        let sink = Arc::new(RecordingSink { published: Mutex::new(Vec::new()) });
+        let publisher = EventPublisher::spawn(sink.clone(), 8);
+
+        let scan_event = ScanEvent::new("p1".to_string(), 2, ScanSource::QrCode, RedirectType::DualRedirect);
+        publisher.publish(&scan_event);
+
+        for _ in 0..50 {
+            if publisher.metrics().published == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let published = sink.published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].0, "p1");
+        assert_eq!(published[0].1["propertyId"], "p1");
+        assert_eq!(published[0].1["qrVersion"], 2);
+    }
+}
@@ -0,0 +1,305 @@
+// src/services/experiment_service.rs
+
+use crate::models::{
+    CreateExperimentRequest, ExperimentReport, FunnelStage, LandingExperiment, VariantStats,
+};
+use crate::services::TtlCache;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson, Document},
+    error::{ErrorKind, WriteFailure},
+    options::IndexOptions,
+    Collection, Database, IndexModel,
+};
+use std::time::Duration;
+use tracing::info;
+
+// Clicks on the dual page that count as a variant's scan converting
+const CONVERSION_STAGES: [FunnelStage; 3] = [
+    FunnelStage::ManualClickProperty,
+    FunnelStage::ManualClickBlockchain,
+    FunnelStage::ManualClickPayment,
+];
+
+#[derive(Clone)]
+pub struct ExperimentService {
+    experiments: Collection<LandingExperiment>,
+    scan_events: Collection<Document>,
+    // Each property's running experiment (or none), read on every dual-page scan
+    active_cache: Option<TtlCache<String, Option<LandingExperiment>>>,
+}
+
+#[derive(Debug)]
+pub enum ExperimentError {
+    NotFound,
+    AlreadyRunning, // The property has an active experiment; end it first
+    InvalidExperiment(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for ExperimentError {
+    fn from(err: mongodb::error::Error) -> Self {
+        ExperimentError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for ExperimentError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        ExperimentError::DatabaseError(err.into())
+    }
+}
+
+impl std::fmt::Display for ExperimentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExperimentError::NotFound => write!(f, "Experiment not found"),
+            ExperimentError::AlreadyRunning => write!(f, "Property already has a running experiment"),
+            ExperimentError::InvalidExperiment(reason) => write!(f, "Invalid experiment: {}", reason),
+            ExperimentError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExperimentError {}
+
+/// Per-variant totals from the report aggregation, in the experiment's variant order
+fn variant_stats(experiment: &LandingExperiment, rows: &[Document]) -> Vec<VariantStats> {
+    experiment.variants
+        .iter()
+        .map(|variant| {
+            let row = rows.iter().find(|row| row.get_str("_id") == Ok(variant.key.as_str()));
+            let count = |field: &str| row.and_then(|row| row.get_i64(field).ok()).unwrap_or(0);
+            VariantStats::new(variant.key.clone(), count("scans"), count("conversions"))
+        })
+        .collect()
+}
+
+impl ExperimentService {
+    /// Create a new experiment service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            experiments: db.collection("landing_experiments"),
+            scan_events: db.collection("scan_events"),
+            active_cache: None,
+        }
+    }
+
+    /// Serve `active_for` from memory for up to `ttl`, holding at most `capacity` properties.
+    /// Starting or ending an experiment here clears its property's entry straight away.
+    pub fn with_active_cache(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.active_cache = Some(TtlCache::new(ttl, capacity));
+        self
+    }
+
+    /// One running experiment per property, and scan events looked up by experiment
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.experiments
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "propertyId": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("one_active_experiment".to_string())
+                            .unique(true)
+                            .partial_filter_expression(doc! { "active": true })
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await?;
+        self.experiments
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": -1 }).build())
+            .await?;
+        self.scan_events
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "experiment.experimentId": 1 })
+                    .options(IndexOptions::builder().sparse(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Start an experiment on a property's landing page
+    pub async fn create(
+        &self,
+        property_id: &str,
+        request: CreateExperimentRequest,
+        created_by: Option<ObjectId>,
+    ) -> Result<LandingExperiment, ExperimentError> {
+        let experiment = LandingExperiment::new(property_id.to_string(), request, created_by)
+            .map_err(ExperimentError::InvalidExperiment)?;
+
+        match self.experiments.insert_one(&experiment).await {
+            Ok(_) => {}
+            Err(e) if is_duplicate_key(&e) => return Err(ExperimentError::AlreadyRunning),
+            Err(e) => return Err(e.into()),
+        }
+
+        self.invalidate_active(property_id);
+        info!("Started experiment {} on property {}", experiment.id, property_id);
+        Ok(experiment)
+    }
+
+    /// The property's running experiment, if any
+    pub async fn active_for(&self, property_id: &str) -> Result<Option<LandingExperiment>, ExperimentError> {
+        if let Some(experiment) = self.active_cache.as_ref().and_then(|cache| cache.get(property_id)) {
+            return Ok(experiment);
+        }
+
+        let experiment = self.experiments.find_one(doc! { "propertyId": property_id, "active": true }).await?;
+        if let Some(cache) = &self.active_cache {
+            cache.insert(property_id.to_string(), experiment.clone());
+        }
+        Ok(experiment)
+    }
+
+    fn invalidate_active(&self, property_id: &str) {
+        if let Some(cache) = &self.active_cache {
+            cache.remove(property_id);
+        }
+    }
+
+    /// A property's experiments, newest first
+    pub async fn list_for_property(&self, property_id: &str) -> Result<Vec<LandingExperiment>, ExperimentError> {
+        let experiments = self.experiments
+            .find(doc! { "propertyId": property_id })
+            .sort(doc! { "createdAt": -1 })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(experiments)
+    }
+
+    /// Stop splitting scans; the experiment's results are kept
+    pub async fn end(&self, property_id: &str, experiment_id: &str) -> Result<LandingExperiment, ExperimentError> {
+        let experiment_id = ObjectId::parse_str(experiment_id).map_err(|_| ExperimentError::NotFound)?;
+        let experiment = self.experiments
+            .find_one_and_update(
+                doc! { "_id": experiment_id, "propertyId": property_id, "active": true },
+                doc! { "$set": { "active": false, "endedAt": to_bson(&Utc::now())? } },
+            )
+            .return_document(mongodb::options::ReturnDocument::After)
+            .await?
+            .ok_or(ExperimentError::NotFound)?;
+
+        self.invalidate_active(property_id);
+        info!("Ended experiment {} on property {}", experiment.id, property_id);
+        Ok(experiment)
+    }
+
+    /// Scans and conversions per variant for each of a property's experiments, newest first.
+    /// Bot scans are left out.
+    pub async fn reports(&self, property_id: &str) -> Result<Vec<ExperimentReport>, ExperimentError> {
+        let mut reports = Vec::new();
+        for experiment in self.list_for_property(property_id).await? {
+            let rows = self.variant_rows(&experiment).await?;
+            reports.push(ExperimentReport {
+                variant_stats: variant_stats(&experiment, &rows),
+                experiment: experiment.to_response(),
+            });
+        }
+        Ok(reports)
+    }
+
+    async fn variant_rows(&self, experiment: &LandingExperiment) -> Result<Vec<Document>, ExperimentError> {
+        let stages: Vec<&str> = CONVERSION_STAGES.iter().map(FunnelStage::as_str).collect();
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "propertyId": &experiment.property_id,
+                    "experiment.experimentId": experiment.id.to_hex(),
+                    "isBot": false
+                }
+            },
+            doc! {
+                "$lookup": {
+                    "from": "funnel_events",
+                    "let": { "scanId": "$_id" },
+                    "pipeline": [
+                        { "$match": { "$expr": { "$eq": ["$scanId", "$$scanId"] }, "stage": { "$in": stages } } },
+                        { "$limit": 1 }
+                    ],
+                    "as": "clicks"
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$experiment.variant",
+                    "scans": { "$sum": 1i64 },
+                    "conversions": { "$sum": { "$cond": [{ "$gt": [{ "$size": "$clicks" }, 0] }, 1i64, 0i64] } }
+                }
+            },
+        ];
+
+        let rows = self.scan_events.aggregate(pipeline).await?.try_collect().await?;
+        Ok(rows)
+    }
+}
+
+fn is_duplicate_key(e: &mongodb::error::Error) -> bool {
+    matches!(e.kind.as_ref(), ErrorKind::Write(WriteFailure::WriteError(write_error)) if write_error.code == 11000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LandingVariantRequest;
+    use mongodb::Client;
+
+    #[test]
+    fn test_variant_stats() {
+        let experiment = LandingExperiment::new(
+            "p1".to_string(),
+            CreateExperimentRequest {
+                name: "Button copy".to_string(),
+                variants: vec![
+                    LandingVariantRequest::default(),
+                    LandingVariantRequest { property_button: Some("Book a viewing".to_string()), ..Default::default() },
+                ],
+            },
+            None,
+        )
+        .unwrap();
+
+        // Variant a had no scans yet; rows for unknown variants are ignored
+        let rows = vec![
+            doc! { "_id": "b", "scans": 40i64, "conversions": 10i64 },
+            doc! { "_id": "c", "scans": 5i64, "conversions": 5i64 },
+        ];
+        assert_eq!(variant_stats(&experiment, &rows), vec![
+            VariantStats::new("a".to_string(), 0, 0),
+            VariantStats::new("b".to_string(), 40, 10),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_active_experiment_is_served_from_cache() {
+        // Never connects: a cache hit doesn't touch the database
+        let client = Client::with_uri_str("mongodb://localhost:27017").await.unwrap();
+        let service = ExperimentService::new(&client.database("test_experiments"))
+            .with_active_cache(Duration::from_secs(30), 10);
+        let experiment = LandingExperiment::new(
+            "p1".to_string(),
+            CreateExperimentRequest {
+                name: "Button copy".to_string(),
+                variants: vec![
+                    LandingVariantRequest::default(),
+                    LandingVariantRequest { property_button: Some("Book a viewing".to_string()), ..Default::default() },
+                ],
+            },
+            None,
+        )
+        .unwrap();
+
+        service.active_cache.as_ref().unwrap().insert("p1".to_string(), Some(experiment.clone()));
+        service.active_cache.as_ref().unwrap().insert("p2".to_string(), None);
+        assert_eq!(service.active_for("p1").await.unwrap().map(|e| e.id), Some(experiment.id));
+        assert!(service.active_for("p2").await.unwrap().is_none());
+
+        service.invalidate_active("p1");
+        assert!(service.active_cache.as_ref().unwrap().get("p1").is_none());
+    }
+}
@@ -0,0 +1,317 @@
+// src/services/gcs_storage.rs
+
+use crate::services::object_storage::{FileMetadata, ObjectStorage, StorageError, StoredObject, UploadResult};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::info;
+
+const API_BASE_URL: &str = "https://storage.googleapis.com";
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+// Refresh metadata-server tokens this long before they expire
+const TOKEN_EXPIRY_MARGIN_SECS: u64 = 60;
+
+/// Google Cloud Storage through its JSON API
+#[derive(Clone)]
+pub struct GcsStorage {
+    http_client: reqwest::Client,
+    bucket_name: String,
+    public_base_url: String,
+    static_token: Option<String>,
+    cached_token: Arc<Mutex<Option<(String, Instant)>>>, // From the metadata server, with its expiry
+}
+
+#[derive(Debug, Deserialize)]
+struct GcsObject {
+    name: String,
+    #[serde(default)]
+    size: String, // The API sends int64s as strings
+    #[serde(rename = "contentType")]
+    content_type: Option<String>,
+    updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    etag: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GcsObjectList {
+    #[serde(default)]
+    items: Vec<GcsObject>,
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl GcsObject {
+    fn size(&self) -> i64 {
+        self.size.parse().unwrap_or(0)
+    }
+
+    fn into_metadata(self) -> FileMetadata {
+        FileMetadata {
+            size: self.size(),
+            content_type: self.content_type.unwrap_or_default(),
+            last_modified: self.updated.unwrap_or_else(Utc::now),
+            etag: self.etag,
+            key: self.name,
+        }
+    }
+
+    fn into_stored_object(self) -> StoredObject {
+        StoredObject {
+            size: self.size(),
+            last_modified: self.updated,
+            etag: self.etag,
+            key: self.name,
+        }
+    }
+}
+
+impl GcsStorage {
+    /// Without an access token, tokens are fetched from the GCE metadata server as needed
+    pub fn new(bucket_name: String, access_token: Option<String>, public_base_url: Option<String>) -> Result<Self, StorageError> {
+        if bucket_name.is_empty() {
+            return Err(StorageError::ConfigurationError("Bucket name cannot be empty".to_string()));
+        }
+
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| StorageError::ConfigurationError(e.to_string()))?;
+
+        let public_base_url = public_base_url
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("{}/{}", API_BASE_URL, bucket_name));
+
+        Ok(Self {
+            http_client,
+            bucket_name,
+            public_base_url,
+            static_token: access_token,
+            cached_token: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    async fn access_token(&self) -> Result<String, StorageError> {
+        if let Some(token) = &self.static_token {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.cached_token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let token: MetadataToken = self.http_client
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| StorageError::ConfigurationError(format!("Failed to get a GCS access token: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| StorageError::ConfigurationError(format!("Failed to get a GCS access token: {}", e)))?;
+
+        let lifetime = token.expires_in.saturating_sub(TOKEN_EXPIRY_MARGIN_SECS);
+        *cached = Some((token.access_token.clone(), Instant::now() + Duration::from_secs(lifetime)));
+        Ok(token.access_token)
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/storage/v1/b/{}/o/{}", API_BASE_URL, self.bucket_name, urlencoding::encode(key))
+    }
+
+    // GET an object's metadata or media; None on 404
+    async fn fetch(&self, key: &str, media: bool) -> Result<Option<reqwest::Response>, StorageError> {
+        let mut request = self.http_client
+            .get(self.object_url(key))
+            .bearer_auth(self.access_token().await?);
+        if media {
+            request = request.query(&[("alt", "media")]);
+        }
+
+        let response = request.send().await
+            .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        response.error_for_status()
+            .map(Some)
+            .map_err(|e| StorageError::NetworkError(e.to_string()))
+    }
+}
+
+impl ObjectStorage for GcsStorage {
+    fn name(&self) -> String {
+        format!("GCS bucket {}", self.bucket_name)
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_base_url, key)
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> BoxFuture<'a, Result<UploadResult, StorageError>> {
+        Box::pin(async move {
+            let size = data.len();
+            let object: GcsObject = self.http_client
+                .post(format!("{}/upload/storage/v1/b/{}/o", API_BASE_URL, self.bucket_name))
+                .query(&[("uploadType", "media"), ("name", key)])
+                .bearer_auth(self.access_token().await?)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(data)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| StorageError::UploadError(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| StorageError::UploadError(e.to_string()))?;
+
+            info!("Uploaded to GCS: {} ({} bytes)", key, size);
+
+            Ok(UploadResult {
+                url: self.public_url(key),
+                key: key.to_string(),
+                size,
+                content_type: content_type.to_string(),
+                etag: object.etag,
+            })
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>> {
+        Box::pin(async move {
+            let response = self.fetch(key, true).await
+                .map_err(|e| StorageError::DownloadError(e.to_string()))?
+                .ok_or_else(|| StorageError::DownloadError(format!("No such object: {}", key)))?;
+
+            response.bytes().await
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| StorageError::DownloadError(e.to_string()))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, StorageError>> {
+        Box::pin(async move {
+            let response = self.http_client
+                .delete(self.object_url(key))
+                .bearer_auth(self.access_token().await?)
+                .send()
+                .await
+                .map_err(|e| StorageError::DeleteError(e.to_string()))?;
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(false);
+            }
+            response.error_for_status()
+                .map_err(|e| StorageError::DeleteError(e.to_string()))?;
+
+            info!("Deleted from GCS: {}", key);
+            Ok(true)
+        })
+    }
+
+    fn head<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<FileMetadata>, StorageError>> {
+        Box::pin(async move {
+            let Some(response) = self.fetch(key, false).await? else {
+                return Ok(None);
+            };
+
+            let object: GcsObject = response.json().await
+                .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+            Ok(Some(object.into_metadata()))
+        })
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str, max_keys: Option<i32>) -> BoxFuture<'a, Result<Vec<StoredObject>, StorageError>> {
+        Box::pin(async move {
+            let limit = max_keys.map(|max| max.max(0) as usize);
+            let mut objects = Vec::new();
+            let mut page_token: Option<String> = None;
+
+            // Follow pages until the listing, or the requested number of keys, runs out
+            loop {
+                let mut request = self.http_client
+                    .get(format!("{}/storage/v1/b/{}/o", API_BASE_URL, self.bucket_name))
+                    .query(&[("prefix", prefix)])
+                    .bearer_auth(self.access_token().await?);
+                if let Some(limit) = limit {
+                    request = request.query(&[("maxResults", limit - objects.len())]);
+                }
+                if let Some(token) = &page_token {
+                    request = request.query(&[("pageToken", token)]);
+                }
+
+                let page: GcsObjectList = request.send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| StorageError::NetworkError(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| StorageError::NetworkError(e.to_string()))?;
+
+                objects.extend(page.items.into_iter().map(GcsObject::into_stored_object));
+                page_token = page.next_page_token;
+
+                if page_token.is_none() || limit.is_some_and(|limit| objects.len() >= limit) {
+                    break;
+                }
+            }
+
+            Ok(objects)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_listing() {
+        let page: GcsObjectList = serde_json::from_str(r#"{
+            "kind": "storage#objects",
+            "nextPageToken": "CgtxcjEucG5n",
+            "items": [{
+                "name": "qr-images/507f1f77bcf86cd799439011.png",
+                "size": "2048",
+                "contentType": "image/png",
+                "updated": "2026-10-01T12:00:00.000Z",
+                "etag": "CKih16GjycICEAE="
+            }]
+        }"#).unwrap();
+
+        assert_eq!(page.next_page_token.as_deref(), Some("CgtxcjEucG5n"));
+        let object = page.items.into_iter().next().unwrap().into_stored_object();
+        assert_eq!(object.key, "qr-images/507f1f77bcf86cd799439011.png");
+        assert_eq!(object.size, 2048);
+        assert_eq!(object.etag, "CKih16GjycICEAE=");
+        assert!(object.last_modified.is_some());
+
+        // An empty bucket has no items at all
+        let empty: GcsObjectList = serde_json::from_str(r#"{"kind": "storage#objects"}"#).unwrap();
+        assert!(empty.items.is_empty());
+    }
+
+    #[test]
+    fn test_public_url() {
+        let storage = GcsStorage::new("qr-codes".to_string(), Some("token".to_string()), None).unwrap();
+        assert_eq!(storage.public_url("qr-images/a.png"), "https://storage.googleapis.com/qr-codes/qr-images/a.png");
+
+        let storage = GcsStorage::new("qr-codes".to_string(), None, Some("https://cdn.daobitat.xyz/".to_string())).unwrap();
+        assert_eq!(storage.public_url("qr-images/a.png"), "https://cdn.daobitat.xyz/qr-images/a.png");
+    }
+}
@@ -0,0 +1,216 @@
+// src/services/geo_block_service.rs
+
+use crate::models::{GeoBlockPolicy, GeoBlockScope, GeoLocation, UpsertGeoBlockPolicyRequest};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    options::{IndexOptions, ReplaceOptions},
+    Collection, Database, IndexModel,
+};
+use tracing::info;
+
+#[derive(Clone)]
+pub struct GeoBlockService {
+    policies: Collection<GeoBlockPolicy>,
+}
+
+#[derive(Debug)]
+pub enum GeoBlockError {
+    NotFound,
+    InvalidPolicy(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for GeoBlockError {
+    fn from(err: mongodb::error::Error) -> Self {
+        GeoBlockError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for GeoBlockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeoBlockError::NotFound => write!(f, "Geo-blocking policy not found"),
+            GeoBlockError::InvalidPolicy(reason) => write!(f, "Invalid geo-blocking policy: {}", reason),
+            GeoBlockError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GeoBlockError {}
+
+impl GeoBlockService {
+    /// Create a new geo-blocking service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            policies: db.collection("geo_block_policies"),
+        }
+    }
+
+    /// One policy per owner or property
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.policies
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "scope": 1, "scopeId": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Get the policy for an owner or property
+    pub async fn get_policy(&self, scope: GeoBlockScope, scope_id: &str) -> Result<GeoBlockPolicy, GeoBlockError> {
+        self.policies
+            .find_one(scope_filter(scope, scope_id))
+            .await?
+            .ok_or(GeoBlockError::NotFound)
+    }
+
+    /// Create or replace the policy for an owner or property
+    pub async fn upsert_policy(
+        &self,
+        scope: GeoBlockScope,
+        scope_id: &str,
+        request: UpsertGeoBlockPolicyRequest,
+    ) -> Result<GeoBlockPolicy, GeoBlockError> {
+        validate_request(&request)?;
+
+        let filter = scope_filter(scope, scope_id);
+        let existing = self.policies.find_one(filter.clone()).await?;
+        let policy = existing
+            .unwrap_or_else(|| GeoBlockPolicy::new(scope, scope_id.to_string()))
+            .apply(request);
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.policies
+            .replace_one(filter, &policy)
+            .with_options(options)
+            .await?;
+
+        info!("Updated geo-blocking policy for {:?} {}: {:?}", scope, scope_id, policy.blocked_countries);
+        Ok(policy)
+    }
+
+    /// Remove the policy for an owner or property
+    pub async fn delete_policy(&self, scope: GeoBlockScope, scope_id: &str) -> Result<(), GeoBlockError> {
+        let result = self.policies.delete_one(scope_filter(scope, scope_id)).await?;
+
+        if result.deleted_count == 0 {
+            return Err(GeoBlockError::NotFound);
+        }
+
+        info!("Removed geo-blocking policy for {:?} {}", scope, scope_id);
+        Ok(())
+    }
+
+    /// Enabled policies that apply to a property: its own and its owner's
+    pub async fn policies_for(&self, owner_id: &str, property_id: &str) -> Result<Vec<GeoBlockPolicy>, GeoBlockError> {
+        let policies = self.policies
+            .find(doc! {
+                "enabled": true,
+                "$or": [
+                    { "scope": GeoBlockScope::Owner.as_str(), "scopeId": owner_id },
+                    { "scope": GeoBlockScope::Property.as_str(), "scopeId": property_id },
+                ]
+            })
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(policies)
+    }
+}
+
+/// The first policy that refuses a scan from this location; owner and property policies both apply
+pub fn blocking_policy<'a>(
+    policies: &'a [GeoBlockPolicy],
+    geolocation: Option<&GeoLocation>,
+) -> Option<&'a GeoBlockPolicy> {
+    policies.iter().find(|policy| policy.blocks(geolocation))
+}
+
+fn scope_filter(scope: GeoBlockScope, scope_id: &str) -> Document {
+    doc! { "scope": scope.as_str(), "scopeId": scope_id }
+}
+
+/// Check that every blocked country is an ISO 3166-1 alpha-2 code
+fn validate_request(request: &UpsertGeoBlockPolicyRequest) -> Result<(), GeoBlockError> {
+    if let Some(country) = request.blocked_countries
+        .iter()
+        .find(|country| country.trim().len() != 2 || !country.trim().chars().all(|c| c.is_ascii_alphabetic()))
+    {
+        return Err(GeoBlockError::InvalidPolicy(format!(
+            "'{}' is not a two-letter ISO country code",
+            country
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(country: Option<&str>) -> GeoLocation {
+        GeoLocation {
+            country: country.map(|c| c.to_string()),
+            region: None,
+            city: None,
+            latitude: None,
+            longitude: None,
+            timezone: None,
+        }
+    }
+
+    fn policy(scope: GeoBlockScope, countries: &[&str], block_unknown: bool) -> GeoBlockPolicy {
+        GeoBlockPolicy::new(scope, "507f1f77bcf86cd799439011".to_string()).apply(UpsertGeoBlockPolicyRequest {
+            blocked_countries: countries.iter().map(|c| c.to_string()).collect(),
+            block_unknown: Some(block_unknown),
+            enabled: None,
+        })
+    }
+
+    #[test]
+    fn test_blocking_policy() {
+        let policies = vec![
+            policy(GeoBlockScope::Owner, &["ru", " KP "], false),
+            policy(GeoBlockScope::Property, &["US"], false),
+        ];
+
+        assert!(blocking_policy(&policies, Some(&location(Some("KE")))).is_none());
+        assert_eq!(
+            blocking_policy(&policies, Some(&location(Some("KP")))).map(|p| p.scope),
+            Some(GeoBlockScope::Owner)
+        );
+        assert_eq!(
+            blocking_policy(&policies, Some(&location(Some("US")))).map(|p| p.scope),
+            Some(GeoBlockScope::Property)
+        );
+
+        // Unknown locations pass unless a policy opts into blocking them
+        assert!(blocking_policy(&policies, None).is_none());
+        let strict = vec![policy(GeoBlockScope::Owner, &["RU"], true)];
+        assert!(blocking_policy(&strict, Some(&location(None))).is_some());
+
+        let mut disabled = policy(GeoBlockScope::Owner, &["KE"], true);
+        disabled.enabled = false;
+        assert!(!disabled.blocks(Some(&location(Some("KE")))));
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let request = |countries: &[&str]| UpsertGeoBlockPolicyRequest {
+            blocked_countries: countries.iter().map(|c| c.to_string()).collect(),
+            block_unknown: None,
+            enabled: None,
+        };
+
+        assert!(validate_request(&request(&["RU", "kp"])).is_ok());
+        assert!(validate_request(&request(&[])).is_ok());
+        assert!(matches!(validate_request(&request(&["Russia"])), Err(GeoBlockError::InvalidPolicy(_))));
+        assert!(validate_request(&request(&["K1"])).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+// src/services/geolocation_service.rs
+
+use crate::config::{GeoProviderKind, GeolocationConfig};
+use crate::models::GeoLocation;
+use crate::services::TtlCache;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const LOOKUP_TIMEOUT_SECS: u64 = 3;
+
+#[derive(Debug)]
+pub enum GeoError {
+    Database(String),
+    Http(String),
+    Provider(String),
+}
+
+impl std::fmt::Display for GeoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeoError::Database(reason) => write!(f, "Geolocation database error: {}", reason),
+            GeoError::Http(reason) => write!(f, "Geolocation request failed: {}", reason),
+            GeoError::Provider(reason) => write!(f, "Geolocation provider error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GeoError {}
+
+/// A source of IP geolocation; `Ok(None)` means the address isn't in its data
+pub trait GeoProvider: Send + Sync {
+    /// Short name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<GeoLocation>, GeoError>>;
+}
+
+/// Provider used when geolocation is disabled
+pub struct NullGeoProvider;
+
+impl GeoProvider for NullGeoProvider {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn lookup(&self, _ip: IpAddr) -> BoxFuture<'_, Result<Option<GeoLocation>, GeoError>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Lookups against a local GeoLite2/GeoIP2 City database, held in memory
+pub struct MaxMindGeoProvider {
+    reader: maxminddb::Reader<Vec<u8>>,
+}
+
+impl MaxMindGeoProvider {
+    pub fn open(path: &str) -> Result<Self, GeoError> {
+        let reader = maxminddb::Reader::open_readfile(path)
+            .map_err(|e| GeoError::Database(format!("{}: {}", path, e)))?;
+        Ok(Self { reader })
+    }
+
+    fn lookup_city(&self, ip: IpAddr) -> Result<Option<GeoLocation>, GeoError> {
+        let result = self.reader.lookup(ip).map_err(|e| GeoError::Database(e.to_string()))?;
+        let Some(city) = result
+            .decode::<maxminddb::geoip2::City>()
+            .map_err(|e| GeoError::Database(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(GeoLocation {
+            country: city.country.iso_code.map(str::to_string),
+            region: city.subdivisions.first().and_then(|s| s.names.english).map(str::to_string),
+            city: city.city.names.english.map(str::to_string),
+            latitude: city.location.latitude,
+            longitude: city.location.longitude,
+            timezone: city.location.time_zone.map(str::to_string),
+        }))
+    }
+}
+
+impl GeoProvider for MaxMindGeoProvider {
+    fn name(&self) -> &'static str {
+        "maxmind"
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<GeoLocation>, GeoError>> {
+        // In-memory tree walk; cheap enough to run inline
+        Box::pin(async move { self.lookup_city(ip) })
+    }
+}
+
+/// Lookups against the ipapi.co HTTP API
+pub struct IpApiGeoProvider {
+    http_client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+// Subset of the ipapi.co JSON response
+#[derive(Debug, Deserialize)]
+struct IpApiResponse {
+    #[serde(default)]
+    error: bool,
+    reason: Option<String>,
+    country_code: Option<String>,
+    region: Option<String>,
+    city: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    timezone: Option<String>,
+}
+
+impl IpApiResponse {
+    fn into_geolocation(self) -> Result<Option<GeoLocation>, GeoError> {
+        if self.error {
+            // Reserved ranges are answered with an error rather than an empty record
+            return match self.reason.as_deref() {
+                Some("Reserved IP Address") => Ok(None),
+                reason => Err(GeoError::Provider(reason.unwrap_or("unknown error").to_string())),
+            };
+        }
+        if self.country_code.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(GeoLocation {
+            country: self.country_code,
+            region: self.region,
+            city: self.city,
+            latitude: self.latitude,
+            longitude: self.longitude,
+            timezone: self.timezone,
+        }))
+    }
+}
+
+impl IpApiGeoProvider {
+    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(LOOKUP_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+}
+
+impl GeoProvider for IpApiGeoProvider {
+    fn name(&self) -> &'static str {
+        "ipapi"
+    }
+
+    fn lookup(&self, ip: IpAddr) -> BoxFuture<'_, Result<Option<GeoLocation>, GeoError>> {
+        Box::pin(async move {
+            let mut request = self.http_client.get(format!("{}/{}/json/", self.base_url, ip));
+            if let Some(api_key) = &self.api_key {
+                request = request.query(&[("key", api_key)]);
+            }
+
+            let response = request.send().await.map_err(|e| GeoError::Http(e.to_string()))?;
+            if !response.status().is_success() {
+                return Err(GeoError::Http(format!("status {}", response.status())));
+            }
+
+            response
+                .json::<IpApiResponse>()
+                .await
+                .map_err(|e| GeoError::Http(e.to_string()))?
+                .into_geolocation()
+        })
+    }
+}
+
+/// Point-in-time view of geolocation lookups
+#[derive(Debug, Clone, Serialize)]
+pub struct GeolocationMetrics {
+    pub provider: &'static str,
+    pub lookups: u64,
+    #[serde(rename = "cacheHits")]
+    pub cache_hits: u64,
+    pub failures: u64,
+    #[serde(rename = "cachedAddresses")]
+    pub cached_addresses: usize,
+}
+
+#[derive(Default)]
+struct GeoCounters {
+    lookups: AtomicU64,
+    cache_hits: AtomicU64,
+    failures: AtomicU64,
+}
+
+/// Resolves scan IPs through the configured provider, caching answers for a TTL; cheap to clone
+#[derive(Clone)]
+pub struct GeolocationService {
+    provider: Arc<dyn GeoProvider>,
+    cache: TtlCache<IpAddr, Option<GeoLocation>>,
+    counters: Arc<GeoCounters>,
+}
+
+impl GeolocationService {
+    /// Create a geolocation service around any provider
+    pub fn new(provider: Arc<dyn GeoProvider>, cache_ttl: Duration, cache_capacity: usize) -> Self {
+        Self {
+            provider,
+            cache: TtlCache::new(cache_ttl, cache_capacity),
+            counters: Arc::new(GeoCounters::default()),
+        }
+    }
+
+    /// Create the provider selected in config; fails if the MaxMind database can't be opened
+    pub fn from_config(config: &GeolocationConfig) -> Result<Self, GeoError> {
+        let provider: Arc<dyn GeoProvider> = match config.provider {
+            GeoProviderKind::MaxMind => {
+                let path = config.maxmind_db_path.as_deref()
+                    .ok_or_else(|| GeoError::Database("no database path configured".to_string()))?;
+                Arc::new(MaxMindGeoProvider::open(path)?)
+            }
+            GeoProviderKind::IpApi => Arc::new(IpApiGeoProvider::new(
+                config.ipapi_base_url.clone(),
+                config.ipapi_key.clone(),
+            )),
+            GeoProviderKind::Disabled => Arc::new(NullGeoProvider),
+        };
+
+        info!("Geolocation provider: {}", provider.name());
+        Ok(Self::new(
+            provider,
+            Duration::from_secs(config.cache_ttl_secs),
+            config.cache_capacity,
+        ))
+    }
+
+    /// Locate a client IP; private and unparseable addresses, and provider failures, yield None
+    pub async fn locate(&self, ip_address: &str) -> Option<GeoLocation> {
+        let ip: IpAddr = ip_address.parse().ok()?;
+        if !is_public(ip) {
+            return None;
+        }
+
+        if let Some(cached) = self.cache.get(&ip) {
+            self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return cached;
+        }
+
+        self.counters.lookups.fetch_add(1, Ordering::Relaxed);
+        match self.provider.lookup(ip).await {
+            Ok(geolocation) => {
+                self.cache.insert(ip, geolocation.clone());
+                geolocation
+            }
+            Err(e) => {
+                // Not cached, so the next scan from this address retries
+                self.counters.failures.fetch_add(1, Ordering::Relaxed);
+                warn!("{} geolocation lookup failed: {}", self.provider.name(), e);
+                None
+            }
+        }
+    }
+
+    pub fn metrics(&self) -> GeolocationMetrics {
+        GeolocationMetrics {
+            provider: self.provider.name(),
+            lookups: self.counters.lookups.load(Ordering::Relaxed),
+            cache_hits: self.counters.cache_hits.load(Ordering::Relaxed),
+            failures: self.counters.failures.load(Ordering::Relaxed),
+            cached_addresses: self.cache.len(),
+        }
+    }
+}
+
+// Loopback, private and link-local addresses never resolve to a location
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => !(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified()),
+        IpAddr::V6(ip) => {
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Answers with a fixed Nairobi location and counts how often it's asked
+    struct StubProvider {
+        calls: AtomicU64,
+        fail: bool,
+    }
+
+    impl GeoProvider for StubProvider {
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+
+        fn lookup(&self, _ip: IpAddr) -> BoxFuture<'_, Result<Option<GeoLocation>, GeoError>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let fail = self.fail;
+            Box::pin(async move {
+                if fail {
+                    return Err(GeoError::Http("timed out".to_string()));
+                }
+                Ok(Some(GeoLocation {
+                    country: Some("KE".to_string()),
+                    region: Some("Nairobi County".to_string()),
+                    city: Some("Nairobi".to_string()),
+                    latitude: Some(-1.2841),
+                    longitude: Some(36.8155),
+                    timezone: Some("Africa/Nairobi".to_string()),
+                }))
+            })
+        }
+    }
+
+    fn stub_service(fail: bool, ttl: Duration) -> (GeolocationService, Arc<StubProvider>) {
+        let provider = Arc::new(StubProvider { calls: AtomicU64::new(0), fail });
+        (GeolocationService::new(provider.clone(), ttl, 2), provider)
+    }
+
+    #[tokio::test]
+    async fn test_locate_caches_within_ttl() {
+        let (service, provider) = stub_service(false, Duration::from_secs(60));
+
+        let first = service.locate("41.90.64.10").await.expect("located");
+        assert_eq!(first.country.as_deref(), Some("KE"));
+        assert!(service.locate("41.90.64.10").await.is_some());
+        assert_eq!(provider.calls.load(Ordering::Relaxed), 1);
+
+        // Private and malformed addresses never reach the provider
+        assert!(service.locate("192.168.1.1").await.is_none());
+        assert!(service.locate("not-an-ip").await.is_none());
+        assert_eq!(provider.calls.load(Ordering::Relaxed), 1);
+
+        let metrics = service.metrics();
+        assert_eq!((metrics.lookups, metrics.cache_hits, metrics.failures), (1, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_locate_expires_and_evicts() {
+        let (service, provider) = stub_service(false, Duration::ZERO);
+        service.locate("41.90.64.10").await;
+        service.locate("41.90.64.10").await;
+        assert_eq!(provider.calls.load(Ordering::Relaxed), 2);
+
+        // Capacity is 2, so the oldest live entry makes way
+        let (service, _) = stub_service(false, Duration::from_secs(60));
+        for ip in ["41.90.64.10", "41.90.64.11", "41.90.64.12"] {
+            service.locate(ip).await;
+        }
+        assert_eq!(service.metrics().cached_addresses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_counted_and_not_cached() {
+        let (service, provider) = stub_service(true, Duration::from_secs(60));
+
+        assert!(service.locate("41.90.64.10").await.is_none());
+        assert!(service.locate("41.90.64.10").await.is_none());
+
+        assert_eq!(provider.calls.load(Ordering::Relaxed), 2);
+        let metrics = service.metrics();
+        assert_eq!(metrics.provider, "stub");
+        assert_eq!(metrics.failures, 2);
+    }
+
+    #[test]
+    fn test_ipapi_response_mapping() {
+        let located: IpApiResponse = serde_json::from_str(
+            r#"{"ip":"41.90.64.10","city":"Nairobi","region":"Nairobi County","country_code":"KE","latitude":-1.28,"longitude":36.81,"timezone":"Africa/Nairobi"}"#,
+        ).unwrap();
+        let geolocation = located.into_geolocation().unwrap().unwrap();
+        assert_eq!(geolocation.country.as_deref(), Some("KE"));
+        assert_eq!(geolocation.city.as_deref(), Some("Nairobi"));
+
+        let reserved: IpApiResponse = serde_json::from_str(r#"{"error":true,"reason":"Reserved IP Address"}"#).unwrap();
+        assert!(reserved.into_geolocation().unwrap().is_none());
+
+        let limited: IpApiResponse = serde_json::from_str(r#"{"error":true,"reason":"RateLimited"}"#).unwrap();
+        assert!(matches!(limited.into_geolocation(), Err(GeoError::Provider(_))));
+    }
+}
@@ -0,0 +1,279 @@
+// src/services/hook_service.rs
+
+use crate::models::{HookEvent, HookSubscription, LeadHookPayload, ScanEvent, ScanHookPayload, WaitlistEntry};
+use chrono::Utc;
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson, DateTime as BsonDateTime, Document},
+    options::FindOptions,
+    Collection, Database,
+};
+use serde_json::Value;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{info, warn};
+
+// Subscriptions are dropped after this many consecutive failed deliveries
+const MAX_DELIVERY_FAILURES: i32 = 10;
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+const SAMPLE_LIMIT: i64 = 3;
+
+#[derive(Clone)]
+pub struct HookService {
+    subscriptions: Collection<HookSubscription>,
+    scan_events: Collection<ScanEvent>,
+    waitlist: Collection<WaitlistEntry>,
+    http_client: reqwest::Client,
+    allowed_domains: Vec<String>, // Hosts events may be delivered to
+}
+
+#[derive(Debug)]
+pub enum HookError {
+    NotFound,
+    InvalidId,
+    InvalidTargetUrl(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for HookError {
+    fn from(err: mongodb::error::Error) -> Self {
+        HookError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for HookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookError::NotFound => write!(f, "Hook subscription not found"),
+            HookError::InvalidId => write!(f, "Invalid hook subscription ID"),
+            HookError::InvalidTargetUrl(reason) => write!(f, "Invalid target URL: {}", reason),
+            HookError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HookError {}
+
+// Helper function to convert chrono DateTime to BSON DateTime
+fn utc_to_bson(dt: chrono::DateTime<Utc>) -> BsonDateTime {
+    BsonDateTime::from_millis(dt.timestamp_millis())
+}
+
+impl HookService {
+    /// Create a new hook service
+    pub fn new(db: &Database, allowed_domains: Vec<String>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            subscriptions: db.collection("hook_subscriptions"),
+            scan_events: db.collection("scan_events"),
+            waitlist: db.collection("waitlist"),
+            http_client,
+            allowed_domains,
+        }
+    }
+
+    /// Register a new REST hook subscription
+    pub async fn subscribe(
+        &self,
+        target_url: String,
+        event: HookEvent,
+        property_id: Option<String>,
+    ) -> Result<HookSubscription, HookError> {
+        crate::utils::validate_allowed_url(&target_url, "targetUrl", &self.allowed_domains)
+            .map_err(|e| HookError::InvalidTargetUrl(e.message))?;
+
+        let subscription = HookSubscription::new(target_url, event, property_id);
+        self.subscriptions.insert_one(&subscription).await?;
+
+        info!("Registered {:?} hook {} -> {}", event, subscription.id, subscription.target_url);
+        Ok(subscription)
+    }
+
+    /// Remove a REST hook subscription
+    pub async fn unsubscribe(&self, subscription_id: &str) -> Result<(), HookError> {
+        let object_id = ObjectId::from_str(subscription_id)
+            .map_err(|_| HookError::InvalidId)?;
+
+        let result = self.subscriptions
+            .delete_one(doc! { "_id": object_id })
+            .await?;
+
+        if result.deleted_count == 0 {
+            return Err(HookError::NotFound);
+        }
+
+        info!("Removed hook subscription {}", subscription_id);
+        Ok(())
+    }
+
+    /// List all active subscriptions
+    pub async fn list_subscriptions(&self) -> Result<Vec<HookSubscription>, HookError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .build();
+
+        let mut cursor = self.subscriptions.find(doc! {}).with_options(options).await?;
+        let mut subscriptions = Vec::new();
+
+        while cursor.advance().await? {
+            subscriptions.push(cursor.deserialize_current()?);
+        }
+
+        Ok(subscriptions)
+    }
+
+    /// Get recent sample payloads for an event, used by platforms when setting up a trigger
+    pub async fn sample_events(&self, event: HookEvent) -> Result<Vec<Value>, HookError> {
+        match event {
+            HookEvent::ScanCreated => {
+                let options = FindOptions::builder()
+                    .sort(doc! { "scannedAt": -1 })
+                    .limit(SAMPLE_LIMIT)
+                    .build();
+
+                let mut cursor = self.scan_events
+                    .find(doc! { "isBot": { "$ne": true }, "redirectSuccess": true })
+                    .with_options(options)
+                    .await?;
+                let mut samples = Vec::new();
+
+                while cursor.advance().await? {
+                    let scan_event: ScanEvent = cursor.deserialize_current()?;
+                    samples.push(serde_json::to_value(ScanHookPayload::from(&scan_event)).unwrap_or(Value::Null));
+                }
+
+                Ok(samples)
+            }
+            HookEvent::LeadCreated => {
+                let options = FindOptions::builder()
+                    .sort(doc! { "createdAt": -1 })
+                    .limit(SAMPLE_LIMIT)
+                    .build();
+
+                let entries: Vec<WaitlistEntry> = self.waitlist
+                    .find(doc! {})
+                    .with_options(options)
+                    .await?
+                    .try_collect()
+                    .await?;
+
+                Ok(entries.iter()
+                    .map(|entry| serde_json::to_value(LeadHookPayload::from(entry)).unwrap_or(Value::Null))
+                    .collect())
+            }
+            HookEvent::ScanCapReached => Ok(vec![serde_json::json!({
+                "id": "000000000000000000000000",
+                "event": "scan_cap.reached",
+                "propertyId": "000000000000000000000000",
+                "maxScansPerDay": 50,
+                "scansToday": 50,
+                "pausedAt": Utc::now().to_rfc3339(),
+            })]),
+        }
+    }
+
+    /// Subscriptions for an event on a property: ones for that property and ones for all
+    async fn subscriptions_for(&self, event: HookEvent, property_id: &str) -> Result<Vec<HookSubscription>, HookError> {
+        let filter = subscription_filter(event, property_id)
+            .map_err(|e| HookError::DatabaseError(e.into()))?;
+
+        Ok(self.subscriptions.find(filter).await?.try_collect().await?)
+    }
+
+    /// Deliver an event payload to every matching subscription in the background
+    pub fn dispatch(&self, event: HookEvent, property_id: &str, payload: Value) {
+        let service = self.clone();
+        let property_id = property_id.to_string();
+
+        tokio::spawn(async move {
+            let subscriptions = match service.subscriptions_for(event, &property_id).await {
+                Ok(subscriptions) => subscriptions,
+                Err(e) => {
+                    warn!("Failed to load hook subscriptions: {}", e);
+                    return;
+                }
+            };
+
+            for subscription in &subscriptions {
+                service.deliver(subscription, &payload).await;
+            }
+        });
+    }
+
+    /// POST a payload to one subscription, following REST Hooks semantics
+    async fn deliver(&self, subscription: &HookSubscription, payload: &Value) {
+        // Subscriptions made before the host allow-list, or since taken off it, get nothing
+        if crate::utils::validate_allowed_url(&subscription.target_url, "targetUrl", &self.allowed_domains).is_err() {
+            warn!("Skipping hook {} to disallowed URL {}", subscription.id, subscription.target_url);
+            return;
+        }
+
+        let result = self.http_client
+            .post(&subscription.target_url)
+            .json(payload)
+            .send()
+            .await;
+
+        let update = match result {
+            // 410 Gone means the platform has dropped the hook on its side
+            Ok(response) if response.status() == reqwest::StatusCode::GONE => {
+                info!("Hook {} returned 410, unsubscribing", subscription.id);
+                let _ = self.subscriptions.delete_one(doc! { "_id": subscription.id }).await;
+                return;
+            }
+            Ok(response) if response.status().is_success() => doc! {
+                "$set": { "lastDeliveredAt": utc_to_bson(Utc::now()), "failureCount": 0 }
+            },
+            Ok(response) => {
+                warn!("Hook {} delivery failed with status {}", subscription.id, response.status());
+                doc! { "$inc": { "failureCount": 1 } }
+            }
+            Err(e) => {
+                warn!("Hook {} delivery failed: {}", subscription.id, e);
+                doc! { "$inc": { "failureCount": 1 } }
+            }
+        };
+
+        if let Err(e) = self.subscriptions
+            .update_one(doc! { "_id": subscription.id }, update)
+            .await
+        {
+            warn!("Failed to update hook {} delivery state: {}", subscription.id, e);
+        }
+
+        if subscription.failure_count + 1 >= MAX_DELIVERY_FAILURES {
+            warn!("Hook {} exceeded {} failures, unsubscribing", subscription.id, MAX_DELIVERY_FAILURES);
+            let _ = self.subscriptions
+                .delete_one(doc! { "_id": subscription.id, "failureCount": { "$gte": MAX_DELIVERY_FAILURES } })
+                .await;
+        }
+    }
+}
+
+fn subscription_filter(event: HookEvent, property_id: &str) -> Result<Document, mongodb::bson::ser::Error> {
+    // A null propertyId also matches subscriptions stored without one
+    Ok(doc! {
+        "event": to_bson(&event)?,
+        "propertyId": { "$in": [property_id, null] },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_filter() {
+        assert_eq!(
+            subscription_filter(HookEvent::ScanCreated, "507f1f77bcf86cd799439011").unwrap(),
+            doc! {
+                "event": "scan.created",
+                "propertyId": { "$in": ["507f1f77bcf86cd799439011", null] },
+            }
+        );
+    }
+}
@@ -0,0 +1,240 @@
+// src/services/impersonation_service.rs
+
+use crate::models::{CreateImpersonationTokenRequest, ImpersonationAuditEntry, ImpersonationToken};
+use chrono::{Duration, Utc};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::{FindOptions, IndexOptions},
+    Collection, Database, IndexModel,
+};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+const DEFAULT_TTL_MINUTES: i64 = 60;
+const MAX_TTL_MINUTES: i64 = 8 * 60; // One support shift
+
+#[derive(Clone)]
+pub struct ImpersonationService {
+    tokens: Collection<ImpersonationToken>,
+    audit: Collection<ImpersonationAuditEntry>,
+}
+
+#[derive(Debug)]
+pub enum ImpersonationError {
+    NotFound,
+    InvalidToken,
+    InvalidRequest(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for ImpersonationError {
+    fn from(err: mongodb::error::Error) -> Self {
+        ImpersonationError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for ImpersonationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImpersonationError::NotFound => write!(f, "Impersonation token not found"),
+            ImpersonationError::InvalidToken => write!(f, "Impersonation token is invalid, expired or revoked"),
+            ImpersonationError::InvalidRequest(reason) => write!(f, "Invalid impersonation request: {}", reason),
+            ImpersonationError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImpersonationError {}
+
+impl ImpersonationService {
+    /// Create a new impersonation service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            tokens: db.collection("impersonation_tokens"),
+            audit: db.collection("impersonation_audit"),
+        }
+    }
+
+    /// Token lookups by hash, and the audit trail by owner and time
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.tokens
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "tokenHash": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        self.audit
+            .create_index(IndexModel::builder().keys(doc! { "ownerId": 1, "requestedAt": -1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Issue a token for an admin to act as an owner; returns the token and its secret,
+    /// which is not stored and can't be shown again
+    pub async fn issue(
+        &self,
+        request: CreateImpersonationTokenRequest,
+    ) -> Result<(ImpersonationToken, String), ImpersonationError> {
+        let ttl = validate_request(&request)?;
+
+        let secret = generate_secret();
+        let token = ImpersonationToken::new(request, hash_secret(&secret), ttl);
+        self.tokens.insert_one(&token).await?;
+
+        warn!(
+            "Admin {} issued impersonation token {} for owner {} until {}: {}",
+            token.admin_id, token.id, token.owner_id, token.expires_at, token.reason
+        );
+        Ok((token, secret))
+    }
+
+    /// Revoke a token before it expires
+    pub async fn revoke(&self, token_id: &str) -> Result<ImpersonationToken, ImpersonationError> {
+        let id = ObjectId::parse_str(token_id).map_err(|_| ImpersonationError::NotFound)?;
+        let mut token = self.tokens
+            .find_one(doc! { "_id": id })
+            .await?
+            .ok_or(ImpersonationError::NotFound)?;
+
+        if token.revoked_at.is_none() {
+            token.revoked_at = Some(Utc::now());
+            self.tokens.replace_one(doc! { "_id": id }, &token).await?;
+            info!("Revoked impersonation token {} for owner {}", token.id, token.owner_id);
+        }
+
+        Ok(token)
+    }
+
+    /// Resolve a presented secret to a live token
+    pub async fn authenticate(&self, secret: &str) -> Result<ImpersonationToken, ImpersonationError> {
+        self.tokens
+            .find_one(doc! { "tokenHash": hash_secret(secret) })
+            .await?
+            .filter(|token| token.is_active(Utc::now()))
+            .ok_or(ImpersonationError::InvalidToken)
+    }
+
+    /// Append a request made with a token to the audit trail
+    pub async fn record(
+        &self,
+        token: &ImpersonationToken,
+        method: String,
+        path: String,
+        status: u16,
+    ) -> Result<(), mongodb::error::Error> {
+        self.audit
+            .insert_one(ImpersonationAuditEntry::new(token, method, path, status))
+            .await?;
+        Ok(())
+    }
+
+    /// Most recent audited requests, optionally for one owner
+    pub async fn audit_log(
+        &self,
+        owner_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ImpersonationAuditEntry>, mongodb::error::Error> {
+        let filter = match owner_id {
+            Some(owner_id) => doc! { "ownerId": owner_id },
+            None => doc! {},
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "requestedAt": -1 })
+            .limit(limit)
+            .build();
+
+        self.audit.find(filter).with_options(options).await?.try_collect().await
+    }
+}
+
+/// 256 random bits, hex encoded
+pub(crate) fn generate_secret() -> String {
+    rand::random::<[u8; 32]>()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub(crate) fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.trim().as_bytes()))
+}
+
+/// Check the request and work out how long the token lives
+fn validate_request(request: &CreateImpersonationTokenRequest) -> Result<Duration, ImpersonationError> {
+    if request.admin_id.trim().is_empty() {
+        return Err(ImpersonationError::InvalidRequest("adminId is required".to_string()));
+    }
+
+    if request.reason.trim().is_empty() {
+        return Err(ImpersonationError::InvalidRequest("a reason is required for the audit trail".to_string()));
+    }
+
+    if ObjectId::parse_str(&request.owner_id).is_err() {
+        return Err(ImpersonationError::InvalidRequest(format!("'{}' is not a valid owner ID", request.owner_id)));
+    }
+
+    let ttl_minutes = request.ttl_minutes.unwrap_or(DEFAULT_TTL_MINUTES);
+    if !(1..=MAX_TTL_MINUTES).contains(&ttl_minutes) {
+        return Err(ImpersonationError::InvalidRequest(format!(
+            "ttlMinutes must be between 1 and {}",
+            MAX_TTL_MINUTES
+        )));
+    }
+
+    Ok(Duration::minutes(ttl_minutes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(ttl_minutes: Option<i64>) -> CreateImpersonationTokenRequest {
+        CreateImpersonationTokenRequest {
+            admin_id: "support@daobitat.xyz".to_string(),
+            owner_id: "507f1f77bcf86cd799439011".to_string(),
+            reason: "Ticket #4821".to_string(),
+            ttl_minutes,
+            allow_writes: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        assert_eq!(validate_request(&request(None)).unwrap(), Duration::minutes(60));
+        assert_eq!(validate_request(&request(Some(480))).unwrap(), Duration::minutes(480));
+        assert!(validate_request(&request(Some(0))).is_err());
+        assert!(validate_request(&request(Some(481))).is_err());
+
+        let mut missing_reason = request(None);
+        missing_reason.reason = "  ".to_string();
+        assert!(matches!(validate_request(&missing_reason), Err(ImpersonationError::InvalidRequest(_))));
+
+        let mut bad_owner = request(None);
+        bad_owner.owner_id = "owner-1".to_string();
+        assert!(validate_request(&bad_owner).is_err());
+    }
+
+    #[test]
+    fn test_secrets_are_random_and_hashed() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 64);
+        assert_ne!(secret, generate_secret());
+        assert_eq!(hash_secret(&secret), hash_secret(&format!(" {} ", secret)));
+        assert_ne!(hash_secret(&secret), secret);
+    }
+
+    #[test]
+    fn test_token_is_read_only_and_expires() {
+        let token = ImpersonationToken::new(request(None), hash_secret("s"), Duration::minutes(60));
+        assert!(!token.allow_writes);
+        assert!(token.is_active(Utc::now()));
+        assert!(!token.is_active(token.expires_at));
+
+        let mut revoked = token.clone();
+        revoked.revoked_at = Some(Utc::now());
+        assert!(!revoked.is_active(Utc::now()));
+    }
+}
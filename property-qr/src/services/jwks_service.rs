@@ -0,0 +1,81 @@
+// src/services/jwks_service.rs
+
+use crate::utils::jwt::{JwkSet, JwtVerifier};
+use std::time::Duration;
+use tracing::{info, error};
+
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug)]
+pub enum JwksError {
+    Fetch(String),
+    NoUsableKeys,
+}
+
+impl std::fmt::Display for JwksError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwksError::Fetch(reason) => write!(f, "JWKS fetch failed: {}", reason),
+            JwksError::NoUsableKeys => write!(f, "JWKS has no RSA signing keys"),
+        }
+    }
+}
+
+impl std::error::Error for JwksError {}
+
+/// Keeps a verifier's RS256 keys in step with the auth service's JWKS, so key rotations
+/// are picked up without a restart
+#[derive(Clone)]
+pub struct JwksService {
+    http_client: reqwest::Client,
+    url: String,
+    verifier: JwtVerifier,
+}
+
+impl JwksService {
+    pub fn new(url: String, verifier: JwtVerifier) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self { http_client, url, verifier }
+    }
+
+    /// Fetch the key set and hand it to the verifier; returns how many keys were loaded.
+    /// On failure the verifier keeps the keys it had.
+    pub async fn refresh(&self) -> Result<usize, JwksError> {
+        let jwks: JwkSet = self.http_client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| JwksError::Fetch(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| JwksError::Fetch(e.to_string()))?;
+
+        match self.verifier.set_jwks(&jwks) {
+            0 => Err(JwksError::NoUsableKeys),
+            count => Ok(count),
+        }
+    }
+
+    /// Refresh the keys in the background every `interval`
+    pub fn spawn_refresher(&self, interval: Duration) {
+        let jwks_service = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick fires immediately; startup has just fetched the keys
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match jwks_service.refresh().await {
+                    Ok(count) => info!("Refreshed {} JWKS signing keys", count),
+                    Err(e) => error!("Failed to refresh JWKS from {}: {}", jwks_service.url, e),
+                }
+            }
+        });
+    }
+}
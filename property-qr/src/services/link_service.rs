@@ -0,0 +1,297 @@
+// src/services/link_service.rs
+
+use crate::models::{CreateShortLinkRequest, ShortLink, UpdateShortLinkRequest};
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, oid::ObjectId, Document},
+    options::FindOptions,
+    Collection, Database,
+};
+use std::str::FromStr;
+use tracing::info;
+
+const GENERATED_CODE_LENGTH: usize = 7;
+const MAX_CODE_ATTEMPTS: usize = 5;
+const CODE_ALPHABET: &[u8] = b"0123456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+#[derive(Clone)]
+pub struct LinkService {
+    links: Collection<ShortLink>,
+    base_url: String,
+    allowed_domains: Vec<String>, // Hosts a custom target URL may be on
+}
+
+#[derive(Debug)]
+pub enum LinkError {
+    NotFound,
+    InvalidId,
+    InvalidCode(String),
+    CodeTaken,
+    InvalidTargetUrl(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for LinkError {
+    fn from(err: mongodb::error::Error) -> Self {
+        LinkError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for LinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkError::NotFound => write!(f, "Short link not found"),
+            LinkError::InvalidId => write!(f, "Invalid short link ID"),
+            LinkError::InvalidCode(reason) => write!(f, "Invalid short code: {}", reason),
+            LinkError::CodeTaken => write!(f, "Short code is already in use"),
+            LinkError::InvalidTargetUrl(reason) => write!(f, "Invalid target URL: {}", reason),
+            LinkError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+impl LinkService {
+    /// Create a new link service
+    pub fn new(db: &Database, base_url: String, allowed_domains: Vec<String>) -> Self {
+        Self {
+            links: db.collection("short_links"),
+            base_url,
+            allowed_domains,
+        }
+    }
+
+    /// Public base URL short links are served from
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Create a short link, generating a code unless a custom one is given
+    pub async fn create_link(&self, request: CreateShortLinkRequest) -> Result<ShortLink, LinkError> {
+        crate::utils::validate_property_id(&request.property_id)
+            .map_err(|_| LinkError::InvalidId)?;
+        if let Some(target_url) = &request.target_url {
+            crate::utils::validate_allowed_url(target_url, "targetUrl", &self.allowed_domains)
+                .map_err(|e| LinkError::InvalidTargetUrl(e.message))?;
+        }
+
+        let code = match request.code.clone() {
+            Some(code) => {
+                validate_code(&code)?;
+                if self.code_exists(&code).await? {
+                    return Err(LinkError::CodeTaken);
+                }
+                code
+            }
+            None => self.generate_unique_code().await?,
+        };
+
+        let link = ShortLink::new(code, request);
+        self.links.insert_one(&link).await?;
+
+        info!("Created short link {} for property {}", link.code, link.property_id);
+        Ok(link)
+    }
+
+    /// Reuse the generated link for a property and channel, creating it on first use
+    pub async fn get_or_create_channel_link(
+        &self,
+        property_id: &str,
+        channel: &str,
+    ) -> Result<ShortLink, LinkError> {
+        let existing = self.links
+            .find_one(doc! {
+                "propertyId": property_id,
+                "channel": channel,
+                "campaign": null,
+                "targetUrl": null,
+                "active": true
+            })
+            .await?;
+
+        match existing {
+            Some(link) => Ok(link),
+            None => self.create_link(CreateShortLinkRequest {
+                property_id: property_id.to_string(),
+                code: None,
+                campaign: None,
+                channel: Some(channel.to_string()),
+                target_url: None,
+            }).await,
+        }
+    }
+
+    /// Get a short link by ID
+    pub async fn get_link(&self, link_id: &str) -> Result<ShortLink, LinkError> {
+        let object_id = ObjectId::from_str(link_id).map_err(|_| LinkError::InvalidId)?;
+
+        self.links
+            .find_one(doc! { "_id": object_id })
+            .await?
+            .ok_or(LinkError::NotFound)
+    }
+
+    /// Resolve an active short link by its code
+    pub async fn resolve_code(&self, code: &str) -> Result<ShortLink, LinkError> {
+        self.links
+            .find_one(doc! { "code": code, "active": true })
+            .await?
+            .ok_or(LinkError::NotFound)
+    }
+
+    /// List short links, optionally for a single property
+    pub async fn list_links(
+        &self,
+        property_id: Option<String>,
+        limit: Option<i64>,
+        skip: Option<u64>,
+    ) -> Result<Vec<ShortLink>, LinkError> {
+        let mut filter = Document::new();
+        if let Some(property_id) = property_id {
+            filter.insert("propertyId", property_id);
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .limit(limit.unwrap_or(50))
+            .skip(skip.unwrap_or(0))
+            .build();
+
+        let mut cursor = self.links.find(filter).with_options(options).await?;
+        let mut links = Vec::new();
+
+        while cursor.advance().await? {
+            links.push(cursor.deserialize_current()?);
+        }
+
+        Ok(links)
+    }
+
+    /// Update a short link's campaign details or status
+    pub async fn update_link(
+        &self,
+        link_id: &str,
+        request: UpdateShortLinkRequest,
+    ) -> Result<ShortLink, LinkError> {
+        let mut link = self.get_link(link_id).await?;
+
+        if let Some(target_url) = &request.target_url {
+            crate::utils::validate_allowed_url(target_url, "targetUrl", &self.allowed_domains)
+                .map_err(|e| LinkError::InvalidTargetUrl(e.message))?;
+            link.target_url = Some(target_url.clone());
+        }
+        if let Some(campaign) = request.campaign {
+            link.campaign = Some(campaign);
+        }
+        if let Some(channel) = request.channel {
+            link.channel = Some(channel);
+        }
+        if let Some(active) = request.active {
+            link.active = active;
+        }
+        link.updated_at = Utc::now();
+
+        self.links.replace_one(doc! { "_id": link.id }, &link).await?;
+
+        info!("Updated short link {}", link.code);
+        Ok(link)
+    }
+
+    /// Delete a short link
+    pub async fn delete_link(&self, link_id: &str) -> Result<(), LinkError> {
+        let object_id = ObjectId::from_str(link_id).map_err(|_| LinkError::InvalidId)?;
+
+        let result = self.links.delete_one(doc! { "_id": object_id }).await?;
+        if result.deleted_count == 0 {
+            return Err(LinkError::NotFound);
+        }
+
+        info!("Deleted short link {}", link_id);
+        Ok(())
+    }
+
+    /// Count a click on a short link
+    pub async fn record_click(&self, link: &ShortLink) -> Result<(), LinkError> {
+        self.links
+            .update_one(doc! { "_id": link.id }, doc! { "$inc": { "clicks": 1 } })
+            .await?;
+        Ok(())
+    }
+
+    async fn code_exists(&self, code: &str) -> Result<bool, LinkError> {
+        Ok(self.links.count_documents(doc! { "code": code }).await? > 0)
+    }
+
+    async fn generate_unique_code(&self) -> Result<String, LinkError> {
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            let code = generate_code(&ObjectId::new());
+            if !self.code_exists(&code).await? {
+                return Ok(code);
+            }
+        }
+
+        Err(LinkError::CodeTaken)
+    }
+}
+
+/// Derive a short, unambiguous code from a fresh ObjectId
+fn generate_code(seed: &ObjectId) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(seed.bytes())
+        .iter()
+        .take(GENERATED_CODE_LENGTH)
+        .map(|byte| CODE_ALPHABET[*byte as usize % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Check a custom short code
+fn validate_code(code: &str) -> Result<(), LinkError> {
+    if code.len() < 3 || code.len() > 32 {
+        return Err(LinkError::InvalidCode("must be 3-32 characters".to_string()));
+    }
+
+    if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(LinkError::InvalidCode("only letters, digits, '-' and '_' are allowed".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_code() {
+        let code = generate_code(&ObjectId::new());
+        assert_eq!(code.len(), GENERATED_CODE_LENGTH);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(code, generate_code(&ObjectId::new()));
+    }
+
+    #[test]
+    fn test_validate_code() {
+        assert!(validate_code("spring-open-house").is_ok());
+        assert!(validate_code("ab").is_err());
+        assert!(validate_code("has space").is_err());
+        assert!(validate_code("../admin").is_err());
+    }
+
+    #[test]
+    fn test_destination_url_tags_campaign() {
+        let link = ShortLink::new("abc1234".to_string(), CreateShortLinkRequest {
+            property_id: "507f1f77bcf86cd799439011".to_string(),
+            code: None,
+            campaign: Some("spring open house".to_string()),
+            channel: Some("sms".to_string()),
+            target_url: None,
+        });
+
+        assert_eq!(
+            link.destination_url("https://www.daobitat.xyz"),
+            "https://www.daobitat.xyz/property/507f1f77bcf86cd799439011?utm_medium=short_link&utm_source=sms&utm_campaign=spring%20open%20house"
+        );
+    }
+}
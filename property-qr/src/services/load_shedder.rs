@@ -0,0 +1,233 @@
+// src/services/load_shedder.rs
+
+use crate::config::LoadSheddingConfig;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+// Weight of the newest lag sample in the moving average
+const LAG_SMOOTHING: f64 = 0.3;
+// Step back down only once lag falls below this share of the current level's threshold
+const RECOVERY_RATIO: f64 = 0.8;
+
+/// How much work is being shed; each level includes everything below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShedLevel {
+    Normal,
+    SkipEnrichment,   // Record scans without geo/device parsing
+    DeferPersistence, // Hand scan events to the analytics worker instead of writing inline
+    RejectNonScan,    // 503 for everything except scan redirects, short links and health
+}
+
+impl ShedLevel {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ShedLevel::Normal,
+            1 => ShedLevel::SkipEnrichment,
+            2 => ShedLevel::DeferPersistence,
+            _ => ShedLevel::RejectNonScan,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ShedCounters {
+    enrichment_skipped: AtomicU64,
+    persistence_deferred: AtomicU64,
+    requests_rejected: AtomicU64,
+    level_changes: AtomicU64,
+}
+
+/// Point-in-time view of load shedding
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadShedderMetrics {
+    pub enabled: bool,
+    pub level: ShedLevel,
+    #[serde(rename = "runtimeLagMs")]
+    pub runtime_lag_ms: f64,
+    #[serde(rename = "enrichmentSkipped")]
+    pub enrichment_skipped: u64,
+    #[serde(rename = "persistenceDeferred")]
+    pub persistence_deferred: u64,
+    #[serde(rename = "requestsRejected")]
+    pub requests_rejected: u64,
+    #[serde(rename = "levelChanges")]
+    pub level_changes: u64,
+}
+
+struct ShedderState {
+    config: LoadSheddingConfig,
+    level: AtomicU8,
+    lag_micros: AtomicU64, // Smoothed runtime scheduling lag
+    counters: ShedCounters,
+}
+
+/// Tracks how far behind the runtime is and decides what work to shed; cheap to clone
+#[derive(Clone)]
+pub struct LoadShedder {
+    state: Arc<ShedderState>,
+}
+
+impl LoadShedder {
+    /// Create a load shedder; it stays at `Normal` until the probe is started
+    pub fn new(config: LoadSheddingConfig) -> Self {
+        Self {
+            state: Arc::new(ShedderState {
+                config,
+                level: AtomicU8::new(ShedLevel::Normal as u8),
+                lag_micros: AtomicU64::new(0),
+                counters: ShedCounters::default(),
+            }),
+        }
+    }
+
+    /// Start measuring runtime lag: how late a timer fires past its deadline
+    /// grows with the number of tasks queued ahead of it
+    pub fn spawn_probe(&self) {
+        if !self.state.config.enabled {
+            return;
+        }
+
+        let shedder = self.clone();
+        let interval = Duration::from_millis(self.state.config.probe_interval_ms);
+        tokio::spawn(async move {
+            loop {
+                let started = Instant::now();
+                tokio::time::sleep(interval).await;
+                shedder.observe(started.elapsed().saturating_sub(interval));
+            }
+        });
+    }
+
+    pub fn level(&self) -> ShedLevel {
+        ShedLevel::from_u8(self.state.level.load(Ordering::Relaxed))
+    }
+
+    /// Whether to skip geo/device enrichment for a scan; counts each skip
+    pub fn shed_enrichment(&self) -> bool {
+        self.shed_at(ShedLevel::SkipEnrichment, &self.state.counters.enrichment_skipped)
+    }
+
+    /// Whether to defer writing a scan event to the analytics worker; counts each deferral
+    pub fn shed_persistence(&self) -> bool {
+        self.shed_at(ShedLevel::DeferPersistence, &self.state.counters.persistence_deferred)
+    }
+
+    /// Whether to reject a non-scan request; counts each rejection
+    pub fn shed_request(&self) -> bool {
+        self.shed_at(ShedLevel::RejectNonScan, &self.state.counters.requests_rejected)
+    }
+
+    pub fn metrics(&self) -> LoadShedderMetrics {
+        let counters = &self.state.counters;
+        LoadShedderMetrics {
+            enabled: self.state.config.enabled,
+            level: self.level(),
+            runtime_lag_ms: self.state.lag_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+            enrichment_skipped: counters.enrichment_skipped.load(Ordering::Relaxed),
+            persistence_deferred: counters.persistence_deferred.load(Ordering::Relaxed),
+            requests_rejected: counters.requests_rejected.load(Ordering::Relaxed),
+            level_changes: counters.level_changes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn shed_at(&self, level: ShedLevel, counter: &AtomicU64) -> bool {
+        let shed = self.level() >= level;
+        if shed {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        shed
+    }
+
+    /// Fold a lag sample into the moving average and move to the matching level
+    fn observe(&self, lag: Duration) {
+        let previous = self.state.lag_micros.load(Ordering::Relaxed) as f64;
+        let smoothed = previous + LAG_SMOOTHING * (lag.as_micros() as f64 - previous);
+        self.state.lag_micros.store(smoothed as u64, Ordering::Relaxed);
+
+        let current = self.level();
+        let next = next_level(&self.state.config, current, smoothed / 1000.0);
+        if next != current {
+            self.state.level.store(next as u8, Ordering::Relaxed);
+            self.state.counters.level_changes.fetch_add(1, Ordering::Relaxed);
+            if next > current {
+                warn!("Runtime lag {:.1}ms, load shedding raised to {:?}", smoothed / 1000.0, next);
+            } else {
+                info!("Runtime lag {:.1}ms, load shedding lowered to {:?}", smoothed / 1000.0, next);
+            }
+        }
+    }
+}
+
+/// Level for the current lag; rising is immediate, falling waits for some headroom
+fn next_level(config: &LoadSheddingConfig, current: ShedLevel, lag_ms: f64) -> ShedLevel {
+    let threshold = |level: ShedLevel| match level {
+        ShedLevel::Normal => 0.0,
+        ShedLevel::SkipEnrichment => config.skip_enrichment_ms as f64,
+        ShedLevel::DeferPersistence => config.defer_persistence_ms as f64,
+        ShedLevel::RejectNonScan => config.reject_non_scan_ms as f64,
+    };
+
+    let target = [ShedLevel::RejectNonScan, ShedLevel::DeferPersistence, ShedLevel::SkipEnrichment]
+        .into_iter()
+        .find(|level| lag_ms >= threshold(*level))
+        .unwrap_or(ShedLevel::Normal);
+
+    if target >= current || lag_ms < threshold(current) * RECOVERY_RATIO {
+        target
+    } else {
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LoadSheddingConfig {
+        LoadSheddingConfig {
+            enabled: true,
+            probe_interval_ms: 100,
+            skip_enrichment_ms: 25,
+            defer_persistence_ms: 75,
+            reject_non_scan_ms: 200,
+        }
+    }
+
+    #[test]
+    fn test_next_level_escalates_and_recovers_with_hysteresis() {
+        let config = config();
+
+        assert_eq!(next_level(&config, ShedLevel::Normal, 5.0), ShedLevel::Normal);
+        assert_eq!(next_level(&config, ShedLevel::Normal, 30.0), ShedLevel::SkipEnrichment);
+        assert_eq!(next_level(&config, ShedLevel::Normal, 250.0), ShedLevel::RejectNonScan);
+
+        // Just under the threshold isn't enough to step down
+        assert_eq!(next_level(&config, ShedLevel::RejectNonScan, 180.0), ShedLevel::RejectNonScan);
+        assert_eq!(next_level(&config, ShedLevel::RejectNonScan, 150.0), ShedLevel::DeferPersistence);
+        assert_eq!(next_level(&config, ShedLevel::SkipEnrichment, 1.0), ShedLevel::Normal);
+    }
+
+    #[test]
+    fn test_shedding_counts_and_levels() {
+        let shedder = LoadShedder::new(config());
+        assert!(!shedder.shed_enrichment());
+
+        // Sustained lag pushes the average past every threshold
+        for _ in 0..20 {
+            shedder.observe(Duration::from_millis(400));
+        }
+        assert_eq!(shedder.level(), ShedLevel::RejectNonScan);
+        assert!(shedder.shed_enrichment());
+        assert!(shedder.shed_persistence());
+        assert!(shedder.shed_request());
+
+        let metrics = shedder.metrics();
+        assert_eq!(metrics.enrichment_skipped, 1);
+        assert_eq!(metrics.requests_rejected, 1);
+        assert!(metrics.level_changes >= 1);
+    }
+}
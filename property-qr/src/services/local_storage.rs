@@ -0,0 +1,228 @@
+// src/services/local_storage.rs
+
+use crate::services::object_storage::{FileMetadata, ObjectStorage, StorageError, StoredObject, UploadResult};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use tracing::info;
+
+/// Files under a directory on this machine, served by the /storage route; for development
+/// and tests without S3 or LocalStack
+#[derive(Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+    public_base_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>, public_base_url: String) -> Result<Self, StorageError> {
+        let root = root.into();
+        if root.as_os_str().is_empty() {
+            return Err(StorageError::ConfigurationError("Storage root cannot be empty".to_string()));
+        }
+
+        Ok(Self {
+            root,
+            public_base_url: public_base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Where a key lives on disk; keys that would leave the root are refused
+    pub fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        let is_plain = !key.is_empty()
+            && relative.components().all(|component| matches!(component, Component::Normal(_)));
+        if !is_plain {
+            return Err(StorageError::InvalidKey(format!("Key escapes the storage root: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    async fn metadata_for(&self, key: &str, path: &Path) -> Result<Option<FileMetadata>, StorageError> {
+        let data = match tokio::fs::read(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(StorageError::NetworkError(e.to_string())),
+        };
+        let modified = tokio::fs::metadata(path).await
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        Ok(Some(FileMetadata {
+            key: key.to_string(),
+            size: data.len() as i64,
+            content_type: content_type_for(key).to_string(),
+            last_modified: modified,
+            etag: etag(&data),
+        }))
+    }
+}
+
+/// Content type from the key's extension, for serving local files
+pub fn content_type_for(key: &str) -> &'static str {
+    match Path::new(key).extension().and_then(|extension| extension.to_str()) {
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("json") => "application/json",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+// Content hash standing in for the ETag a bucket would report
+fn etag(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+impl ObjectStorage for LocalStorage {
+    fn name(&self) -> String {
+        format!("local directory {}", self.root.display())
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_base_url, key)
+    }
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> BoxFuture<'a, Result<UploadResult, StorageError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await
+                    .map_err(|e| StorageError::UploadError(e.to_string()))?;
+            }
+            tokio::fs::write(&path, &data).await
+                .map_err(|e| StorageError::UploadError(e.to_string()))?;
+
+            info!("Stored {} locally ({} bytes)", key, data.len());
+
+            Ok(UploadResult {
+                url: self.public_url(key),
+                key: key.to_string(),
+                size: data.len(),
+                content_type: content_type.to_string(),
+                etag: etag(&data),
+            })
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            tokio::fs::read(&path).await
+                .map_err(|e| StorageError::DownloadError(format!("{}: {}", key, e)))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, StorageError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => Ok(true),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+                Err(e) => Err(StorageError::DeleteError(e.to_string())),
+            }
+        })
+    }
+
+    fn head<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<FileMetadata>, StorageError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            self.metadata_for(key, &path).await
+        })
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str, max_keys: Option<i32>) -> BoxFuture<'a, Result<Vec<StoredObject>, StorageError>> {
+        Box::pin(async move {
+            let limit = max_keys.map(|max| max.max(0) as usize).unwrap_or(usize::MAX);
+            let mut objects = Vec::new();
+            let mut directories = vec![self.root.clone()];
+
+            while let Some(directory) = directories.pop() {
+                let mut entries = match tokio::fs::read_dir(&directory).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                    Err(e) => return Err(StorageError::NetworkError(e.to_string())),
+                };
+
+                while let Some(entry) = entries.next_entry().await
+                    .map_err(|e| StorageError::NetworkError(e.to_string()))?
+                {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        directories.push(path);
+                        continue;
+                    }
+
+                    // Keys use forward slashes whatever the platform
+                    let Ok(relative) = path.strip_prefix(&self.root) else { continue };
+                    let key = relative.components()
+                        .filter_map(|component| component.as_os_str().to_str())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if !key.starts_with(prefix) {
+                        continue;
+                    }
+
+                    if let Some(metadata) = self.metadata_for(&key, &path).await? {
+                        objects.push(StoredObject {
+                            key: metadata.key,
+                            size: metadata.size,
+                            last_modified: Some(metadata.last_modified),
+                            etag: metadata.etag,
+                        });
+                    }
+                }
+            }
+
+            // Key order, as a bucket listing would give
+            objects.sort_by(|a, b| a.key.cmp(&b.key));
+            objects.truncate(limit);
+            Ok(objects)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let root = std::env::temp_dir().join(format!("property-qr-storage-{}", std::process::id()));
+        let storage = LocalStorage::new(&root, "http://localhost:3000/storage/".to_string()).unwrap();
+
+        let upload = storage.put("qr-images/a.png", b"png bytes".to_vec(), "image/png").await.unwrap();
+        assert_eq!(upload.url, "http://localhost:3000/storage/qr-images/a.png");
+        storage.put("metadata/a.json", b"{}".to_vec(), "application/json").await.unwrap();
+
+        assert_eq!(storage.get("qr-images/a.png").await.unwrap(), b"png bytes");
+        let metadata = storage.head("qr-images/a.png").await.unwrap().unwrap();
+        assert_eq!(metadata.size, 9);
+        assert_eq!(metadata.etag, upload.etag);
+        assert_eq!(metadata.content_type, "image/png");
+
+        let listed = storage.list("qr-images/", None).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].key, "qr-images/a.png");
+
+        assert!(storage.delete("qr-images/a.png").await.unwrap());
+        assert!(!storage.delete("qr-images/a.png").await.unwrap());
+        assert!(storage.head("qr-images/a.png").await.unwrap().is_none());
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+
+    #[test]
+    fn test_keys_stay_under_root() {
+        let storage = LocalStorage::new("/var/qr", "http://localhost:3000/storage".to_string()).unwrap();
+
+        assert_eq!(storage.path_for("qr-images/a.png").unwrap(), Path::new("/var/qr/qr-images/a.png"));
+        assert!(storage.path_for("../etc/passwd").is_err());
+        assert!(storage.path_for("qr-images/../../etc/passwd").is_err());
+        assert!(storage.path_for("/etc/passwd").is_err());
+        assert!(storage.path_for("").is_err());
+    }
+}
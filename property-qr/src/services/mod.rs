@@ -1,12 +1,84 @@
  // src/services/mod.rs
 
 pub mod analytics_service;
+pub mod analytics_worker;
+pub mod anomaly_detector;
+pub mod audit_service;
+pub mod auto_redirect_service;
+pub mod blockchain_service;
+pub mod branding_kit;
+pub mod dependency_registry;
+pub mod booking_checkin_service;
+pub mod digest_service;
+pub mod document_qr_service;
+pub mod experiment_service;
+pub mod email_service;
+pub mod event_publisher;
+pub mod gcs_storage;
+pub mod geo_block_service;
+pub mod geolocation_service;
+pub mod hook_service;
+pub mod impersonation_service;
+pub mod jwks_service;
+pub mod link_service;
+pub mod load_shedder;
+pub mod local_storage;
+pub mod notification_service;
+pub mod object_storage;
+pub mod organization_service;
+pub mod page_cache;
+pub mod poster_service;
+pub mod privacy;
 pub mod property_service;
+pub mod property_watcher;
+pub mod qr_export;
 pub mod qr_generator;
+pub mod qr_style_service;
 pub mod s3_service;
+pub mod scan_cap_service;
+pub mod share_offer_service;
+pub mod sms_service;
+pub mod tracking_service;
+pub mod ttl_cache;
+pub mod waitlist_service;
 
 // Re-export services for convenience
 pub use analytics_service::AnalyticsService;
+pub use analytics_worker::AnalyticsQueue;
+pub use anomaly_detector::AnomalyDetector;
+pub use audit_service::AuditService;
+pub use auto_redirect_service::AutoRedirectService;
+pub use blockchain_service::BlockchainService;
+pub use dependency_registry::DependencyRegistry;
+pub use booking_checkin_service::BookingCheckinService;
+pub use digest_service::DigestService;
+pub use document_qr_service::DocumentQrService;
+pub use experiment_service::ExperimentService;
+pub use email_service::EmailService;
+pub use event_publisher::EventPublisher;
+pub use gcs_storage::GcsStorage;
+pub use geo_block_service::GeoBlockService;
+pub use geolocation_service::GeolocationService;
+pub use hook_service::HookService;
+pub use impersonation_service::ImpersonationService;
+pub use jwks_service::JwksService;
+pub use link_service::LinkService;
+pub use load_shedder::LoadShedder;
+pub use local_storage::LocalStorage;
+pub use notification_service::NotificationService;
+pub use object_storage::{ObjectStorage, StorageService};
+pub use organization_service::OrganizationService;
+pub use page_cache::PageCache;
+pub use poster_service::PosterService;
+pub use privacy::PrivacyPolicy;
 pub use property_service::PropertyService;
-pub use qr_generator::QrGeneratorService;
-pub use s3_service::S3Service;
+pub use property_watcher::PropertyWatcher;
+pub use qr_generator::{QrGenerationOptions, QrGeneratorService};
+pub use qr_style_service::QrStyleService;
+pub use s3_service::S3Storage;
+pub use scan_cap_service::ScanCapService;
+pub use share_offer_service::ShareOfferService;
+pub use sms_service::SmsService;
+pub use tracking_service::TrackingService;
+pub use ttl_cache::TtlCache;
+pub use waitlist_service::WaitlistService;
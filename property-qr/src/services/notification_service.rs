@@ -0,0 +1,134 @@
+// src/services/notification_service.rs
+
+use crate::services::{EmailService, SmsService, email_service::EmailError, sms_service::SmsError};
+
+/// Delivers messages to prospects: email when we have an address, SMS otherwise
+#[derive(Clone)]
+pub struct NotificationService {
+    email: EmailService,
+    sms: Option<SmsService>,
+}
+
+#[derive(Debug)]
+pub enum NotificationError {
+    Disabled,
+    NoContact, // No channel configured for any contact detail we have
+    InvalidEmail(String),
+    ProviderError(String),
+    Sms(SmsError),
+}
+
+impl From<EmailError> for NotificationError {
+    fn from(err: EmailError) -> Self {
+        match err {
+            EmailError::Disabled => NotificationError::Disabled,
+            EmailError::InvalidAddress(reason) => NotificationError::InvalidEmail(reason),
+            EmailError::ProviderError(reason) => NotificationError::ProviderError(reason),
+        }
+    }
+}
+
+impl From<SmsError> for NotificationError {
+    fn from(err: SmsError) -> Self {
+        NotificationError::Sms(err)
+    }
+}
+
+impl std::fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationError::Disabled => write!(f, "Email sending is not configured"),
+            NotificationError::NoContact => write!(f, "No configured channel reaches this contact"),
+            NotificationError::InvalidEmail(reason) => write!(f, "Invalid email address: {}", reason),
+            NotificationError::ProviderError(reason) => write!(f, "Email provider error: {}", reason),
+            NotificationError::Sms(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for NotificationError {}
+
+impl NotificationService {
+    /// Create a new notification service
+    pub fn new(email: EmailService) -> Self {
+        Self {
+            email,
+            sms: None,
+        }
+    }
+
+    /// Fall back to text messages for contacts without an email address
+    pub fn with_sms(mut self, sms: SmsService) -> Self {
+        self.sms = Some(sms);
+        self
+    }
+
+    /// Whether an email provider is configured
+    pub fn email_enabled(&self) -> bool {
+        self.email.is_enabled()
+    }
+
+    /// Whether any channel can deliver messages
+    pub fn is_enabled(&self) -> bool {
+        self.email_enabled() || self.sms.as_ref().is_some_and(|sms| sms.is_enabled())
+    }
+
+    /// Reach a contact on the best channel we have for them
+    pub async fn notify(
+        &self,
+        email: Option<&str>,
+        phone: Option<&str>,
+        subject: &str,
+        message: &str,
+    ) -> Result<(), NotificationError> {
+        if let Some(email) = email.filter(|_| self.email_enabled()) {
+            return self.send_email(email, subject, message).await;
+        }
+
+        match (phone, &self.sms) {
+            (Some(phone), Some(sms)) if sms.is_enabled() => Ok(sms.send(phone, message).await?),
+            _ => Err(NotificationError::NoContact),
+        }
+    }
+
+    /// Send a plain-text email through the configured provider
+    pub async fn send_email(&self, to: &str, subject: &str, body: &str) -> Result<(), NotificationError> {
+        Ok(self.email.send(to, subject, body).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{SmsConfig, SmsProviderKind};
+
+    fn disabled_email() -> EmailService {
+        EmailService::with_transport(None, String::new(), "DAO-Bitat".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_notify_without_channels() {
+        let sms = SmsService::new(SmsConfig {
+            provider: SmsProviderKind::Disabled,
+            account_id: None,
+            api_key: None,
+            sender_id: None,
+            max_per_ip_per_hour: 3,
+        });
+        let service = NotificationService::new(disabled_email()).with_sms(sms);
+
+        assert!(!service.is_enabled());
+        assert!(matches!(
+            service.notify(Some("buyer@example.com"), Some("+254712345678"), "Hi", "hi").await,
+            Err(NotificationError::NoContact)
+        ));
+        assert!(matches!(
+            service.send_email("buyer@example.com", "Hi", "hi").await,
+            Err(NotificationError::Disabled)
+        ));
+        assert!(matches!(
+            service.send_email("not-an-email", "Hi", "hi").await,
+            Err(NotificationError::InvalidEmail(_))
+        ));
+    }
+}
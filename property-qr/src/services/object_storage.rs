@@ -0,0 +1,414 @@
+// src/services/object_storage.rs
+
+use crate::config::{settings::AwsConfig, StorageBackendKind, StorageConfig};
+use crate::services::{GcsStorage, LocalStorage, S3Storage};
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn, error};
+
+// Key HEADed to tell whether the primary backend is reachable
+const HEALTH_PROBE_KEY: &str = "health/probe";
+
+const PNG_CONTENT_TYPE: &str = "image/png";
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+#[derive(Debug)]
+pub enum StorageError {
+    ConfigurationError(String),
+    UploadError(String),
+    DownloadError(String),
+    DeleteError(String),
+    InvalidKey(String),
+    NetworkError(String),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::ConfigurationError(msg) => write!(f, "Storage configuration error: {}", msg),
+            StorageError::UploadError(msg) => write!(f, "Storage upload error: {}", msg),
+            StorageError::DownloadError(msg) => write!(f, "Storage download error: {}", msg),
+            StorageError::DeleteError(msg) => write!(f, "Storage delete error: {}", msg),
+            StorageError::InvalidKey(msg) => write!(f, "Invalid storage key: {}", msg),
+            StorageError::NetworkError(msg) => write!(f, "Storage network error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+#[derive(Debug, Clone)]
+pub struct UploadResult {
+    pub url: String,
+    pub key: String,
+    pub size: usize,
+    pub content_type: String,
+    pub etag: String, // As the backend reports it, for later integrity checks
+}
+
+#[derive(Debug, Clone)]
+pub struct FileMetadata {
+    pub key: String,
+    pub size: i64,
+    pub content_type: String,
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+    pub etag: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub size: i64,
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+    pub etag: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct BucketStats {
+    pub total_qr_images: i64,
+    pub total_metadata_files: i64,
+    pub total_qr_size: i64,
+    pub total_metadata_size: i64,
+    pub total_size: i64,
+}
+
+/// A place QR images and metadata are kept. Keys are validated before they get here.
+pub trait ObjectStorage: Send + Sync {
+    /// Short name used in logs, e.g. the bucket
+    fn name(&self) -> String;
+
+    /// URL an object is publicly served from
+    fn public_url(&self, key: &str) -> String;
+
+    fn put<'a>(&'a self, key: &'a str, data: Vec<u8>, content_type: &'a str) -> BoxFuture<'a, Result<UploadResult, StorageError>>;
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Vec<u8>, StorageError>>;
+
+    /// Whether there was an object to delete
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, StorageError>>;
+
+    /// None if there is no such object
+    fn head<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<FileMetadata>, StorageError>>;
+
+    fn list<'a>(&'a self, prefix: &'a str, max_keys: Option<i32>) -> BoxFuture<'a, Result<Vec<StoredObject>, StorageError>>;
+}
+
+/// QR image storage on the configured backend, with an optional replica that uploads are
+/// copied to and that is served from while the primary is down
+#[derive(Clone)]
+pub struct StorageService {
+    primary: Arc<dyn ObjectStorage>,
+    secondary: Option<Arc<dyn ObjectStorage>>,
+    primary_healthy: Arc<AtomicBool>,
+}
+
+impl StorageService {
+    pub fn new(primary: Arc<dyn ObjectStorage>) -> Self {
+        Self {
+            primary,
+            secondary: None,
+            primary_healthy: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// The backend chosen in the settings; S3 gets the secondary bucket too, if one is set.
+    /// Local files are served under `{base_url}/storage` unless a public URL is configured.
+    pub fn from_config(storage: &StorageConfig, aws: &AwsConfig, base_url: &str) -> Result<Self, StorageError> {
+        let service = match storage.backend {
+            StorageBackendKind::S3 => {
+                let mut service = Self::new(Arc::new(S3Storage::new(aws.s3_bucket.clone(), aws.region.clone())?));
+                if let (Some(bucket), Some(region)) = (&aws.secondary_s3_bucket, &aws.secondary_s3_bucket_region) {
+                    service = service.with_secondary(Arc::new(S3Storage::new(bucket.clone(), region.clone())?));
+                }
+                service
+            }
+            StorageBackendKind::Gcs => {
+                let bucket = storage.gcs_bucket.clone()
+                    .ok_or_else(|| StorageError::ConfigurationError("GCS bucket is not set".to_string()))?;
+                Self::new(Arc::new(GcsStorage::new(bucket, storage.gcs_access_token.clone(), storage.public_url.clone())?))
+            }
+            StorageBackendKind::Local => {
+                let public_url = storage.public_url.clone()
+                    .unwrap_or_else(|| format!("{}/storage", base_url.trim_end_matches('/')));
+                Self::new(Arc::new(LocalStorage::new(storage.local_root.clone(), public_url)?))
+            }
+        };
+
+        info!("Storing QR images in {}", service.primary.name());
+        Ok(service)
+    }
+
+    /// Replicate uploads to a second backend, and serve from it while the primary is unreachable
+    pub fn with_secondary(mut self, secondary: Arc<dyn ObjectStorage>) -> Self {
+        self.secondary = Some(secondary);
+        self
+    }
+
+    /// Whether the primary answered its last request or probe
+    pub fn is_primary_healthy(&self) -> bool {
+        self.primary_healthy.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_primary_health(&self, healthy: bool) {
+        if self.secondary.is_none() {
+            return;
+        }
+        let was_healthy = self.primary_healthy.swap(healthy, Ordering::Relaxed);
+        match (was_healthy, healthy) {
+            (true, false) => error!("{} is unreachable, serving from the secondary", self.primary.name()),
+            (false, true) => info!("{} is reachable again", self.primary.name()),
+            _ => {}
+        }
+    }
+
+    // The replica while the primary is down, otherwise the primary
+    fn serving(&self) -> &Arc<dyn ObjectStorage> {
+        match &self.secondary {
+            Some(secondary) if !self.is_primary_healthy() => secondary,
+            _ => &self.primary,
+        }
+    }
+
+    /// Upload a QR image, and to the secondary if there is one. Succeeds if either upload
+    /// does, with the result from the backend being served from.
+    pub async fn upload_qr_image(&self, key: &str, image_data: Vec<u8>) -> Result<UploadResult, StorageError> {
+        self.put_replicated(key, image_data, PNG_CONTENT_TYPE).await
+    }
+
+    /// Upload a QR image in another format, e.g. a rendition or an uploaded design,
+    /// replicated like drawn images
+    pub async fn upload_qr_asset(&self, key: &str, image_data: Vec<u8>, content_type: &str) -> Result<UploadResult, StorageError> {
+        self.put_replicated(key, image_data, content_type).await
+    }
+
+    async fn put_replicated(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<UploadResult, StorageError> {
+        validate_key(key)?;
+        let Some(secondary) = &self.secondary else {
+            return self.primary.put(key, data, content_type).await;
+        };
+
+        let (primary, replica) = tokio::join!(
+            self.primary.put(key, data.clone(), content_type),
+            secondary.put(key, data, content_type),
+        );
+        self.record_primary_health(primary.is_ok());
+
+        match (primary, replica) {
+            (Ok(primary), Ok(replica)) => Ok(if self.is_primary_healthy() { primary } else { replica }),
+            (Ok(primary), Err(e)) => {
+                warn!("Failed to replicate {} to {}: {}", key, secondary.name(), e);
+                Ok(primary)
+            }
+            (Err(e), Ok(replica)) => {
+                warn!("Uploaded {} to the secondary only: {}", key, e);
+                Ok(replica)
+            }
+            (Err(e), Err(_)) => Err(e),
+        }
+    }
+
+    /// Upload QR metadata JSON
+    pub async fn upload_qr_metadata(&self, key: &str, metadata_json: String) -> Result<String, StorageError> {
+        validate_key(key)?;
+        let upload = self.primary.put(key, metadata_json.into_bytes(), JSON_CONTENT_TYPE).await?;
+        Ok(upload.url)
+    }
+
+    /// Download a file, falling back to the secondary
+    pub async fn download_file(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        validate_key(key)?;
+        let Some(secondary) = &self.secondary else {
+            return self.primary.get(key).await;
+        };
+
+        if self.is_primary_healthy() {
+            match self.primary.get(key).await {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) => {
+                    warn!("Failed to download {} from {}, trying the secondary: {}", key, self.primary.name(), e);
+                    self.record_primary_health(false);
+                }
+            }
+        }
+        secondary.get(key).await
+    }
+
+    /// Delete a QR image here and on the secondary; a replica that can't be deleted is only
+    /// logged, as the primary decides what exists
+    pub async fn delete_qr_image(&self, key: &str) -> Result<bool, StorageError> {
+        validate_key(key)?;
+        if let Some(secondary) = &self.secondary {
+            if let Err(e) = secondary.delete(key).await {
+                warn!("Failed to delete {} from {}: {}", key, secondary.name(), e);
+            }
+        }
+        self.primary.delete(key).await
+    }
+
+    /// Delete several files, skipping any that fail
+    pub async fn delete_multiple_files(&self, keys: Vec<String>) -> Result<Vec<String>, StorageError> {
+        let mut deleted_keys = Vec::new();
+
+        for key in keys {
+            match self.delete_qr_image(&key).await {
+                Ok(_) => deleted_keys.push(key),
+                Err(e) => {
+                    warn!("Failed to delete key {}: {}", key, e);
+                    // Continue with other deletions
+                }
+            }
+        }
+
+        Ok(deleted_keys)
+    }
+
+    /// Check if a file exists on the primary
+    pub async fn file_exists(&self, key: &str) -> Result<bool, StorageError> {
+        Ok(self.get_file_metadata(key).await?.is_some())
+    }
+
+    /// Get file metadata from the primary; None if there is no such object
+    pub async fn get_file_metadata(&self, key: &str) -> Result<Option<FileMetadata>, StorageError> {
+        validate_key(key)?;
+        self.primary.head(key).await
+    }
+
+    /// List files on the primary with a prefix
+    pub async fn list_files_with_prefix(&self, prefix: &str, max_keys: Option<i32>) -> Result<Vec<StoredObject>, StorageError> {
+        self.primary.list(prefix, max_keys).await
+    }
+
+    /// Clean up old QR codes
+    pub async fn cleanup_old_qr_codes(&self, older_than_days: i64) -> Result<Vec<String>, StorageError> {
+        let cutoff_date = chrono::Utc::now() - chrono::Duration::days(older_than_days);
+        let qr_objects = self.list_files_with_prefix("qr-images/", None).await?;
+
+        let mut deleted_keys = Vec::new();
+
+        for obj in qr_objects {
+            if let Some(last_modified) = obj.last_modified {
+                if last_modified < cutoff_date {
+                    match self.delete_qr_image(&obj.key).await {
+                        Ok(_) => deleted_keys.push(obj.key),
+                        Err(e) => warn!("Failed to delete old QR code {}: {}", obj.key, e),
+                    }
+                }
+            }
+        }
+
+        info!("Cleaned up {} old QR codes from {}", deleted_keys.len(), self.primary.name());
+        Ok(deleted_keys)
+    }
+
+    /// Get bucket statistics
+    pub async fn get_bucket_stats(&self) -> Result<BucketStats, StorageError> {
+        let qr_images = self.list_files_with_prefix("qr-images/", None).await?;
+        let metadata_files = self.list_files_with_prefix("metadata/", None).await?;
+
+        let total_qr_size: i64 = qr_images.iter().map(|obj| obj.size).sum();
+        let total_metadata_size: i64 = metadata_files.iter().map(|obj| obj.size).sum();
+
+        Ok(BucketStats {
+            total_qr_images: qr_images.len() as i64,
+            total_metadata_files: metadata_files.len() as i64,
+            total_qr_size,
+            total_metadata_size,
+            total_size: total_qr_size + total_metadata_size,
+        })
+    }
+
+    /// URL an object is served from: the secondary's while the primary is down
+    pub fn get_public_url(&self, key: &str) -> String {
+        self.serving().public_url(key)
+    }
+
+    /// A stored image URL, pointed at the secondary while the primary is down
+    pub fn serving_url(&self, url: &str) -> String {
+        let serving = self.serving();
+        if Arc::ptr_eq(serving, &self.primary) {
+            return url.to_string();
+        }
+        match url.strip_prefix(&self.primary.public_url("")) {
+            Some(key) => serving.public_url(key),
+            None => url.to_string(), // Not one of the primary's, e.g. already on the secondary
+        }
+    }
+
+    /// Check the primary in the background, so serving moves back once it recovers
+    pub fn spawn_health_probe(&self, interval: Duration) {
+        if self.secondary.is_none() {
+            return;
+        }
+        let storage = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let reachable = storage.file_exists(HEALTH_PROBE_KEY).await.is_ok();
+                storage.record_primary_health(reachable);
+            }
+        });
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    if key.is_empty() {
+        return Err(StorageError::InvalidKey("Key cannot be empty".to_string()));
+    }
+
+    if key.len() > 1024 {
+        return Err(StorageError::InvalidKey("Key too long (max 1024 characters)".to_string()));
+    }
+
+    // Check for invalid characters; `..` would let the local backend escape its root
+    if key.contains("//") || key.starts_with('/') || key.split('/').any(|segment| segment == "..") {
+        return Err(StorageError::InvalidKey("Invalid key format".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("valid/key.png").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("/invalid").is_err());
+        assert!(validate_key("invalid//key").is_err());
+        assert!(validate_key("qr-images/../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_secondary_failover() {
+        let storage = StorageService::new(Arc::new(S3Storage::new("qr-primary".to_string(), "us-east-1".to_string()).unwrap()))
+            .with_secondary(Arc::new(S3Storage::new("qr-replica".to_string(), "eu-west-1".to_string()).unwrap()));
+        let stored_url = "https://qr-primary.s3.us-east-1.amazonaws.com/qr-images/507f1f77bcf86cd799439011.png";
+
+        assert_eq!(storage.serving_url(stored_url), stored_url);
+
+        storage.record_primary_health(false);
+        assert_eq!(
+            storage.serving_url(stored_url),
+            "https://qr-replica.s3.eu-west-1.amazonaws.com/qr-images/507f1f77bcf86cd799439011.png"
+        );
+        assert_eq!(storage.get_public_url("qr-images/a.png"), "https://qr-replica.s3.eu-west-1.amazonaws.com/qr-images/a.png");
+        // URLs elsewhere, e.g. already on the replica, are left alone
+        assert_eq!(storage.serving_url("https://cdn.daobitat.xyz/qr.png"), "https://cdn.daobitat.xyz/qr.png");
+
+        storage.record_primary_health(true);
+        assert_eq!(storage.serving_url(stored_url), stored_url);
+    }
+
+    #[tokio::test]
+    async fn test_file_exists_placeholder() {
+        let storage = StorageService::new(Arc::new(S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).unwrap()));
+        let exists = storage.file_exists("test/key.png").await.unwrap();
+        assert!(!exists); // Placeholder S3 implementation finds nothing
+    }
+}
@@ -0,0 +1,254 @@
+// src/services/organization_service.rs
+
+use crate::models::{BrandingProfile, CreateOrganizationRequest, Organization};
+use crate::services::impersonation_service::{generate_secret, hash_secret};
+use crate::utils::validate_url;
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson},
+    options::{IndexOptions, ReturnDocument},
+    Collection, Database, IndexModel,
+};
+use tracing::info;
+
+#[derive(Clone)]
+pub struct OrganizationService {
+    organizations: Collection<Organization>,
+}
+
+#[derive(Debug)]
+pub enum OrganizationError {
+    NotFound,
+    InvalidKey,
+    InvalidRequest(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for OrganizationError {
+    fn from(err: mongodb::error::Error) -> Self {
+        OrganizationError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for OrganizationError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        OrganizationError::DatabaseError(err.into())
+    }
+}
+
+impl std::fmt::Display for OrganizationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrganizationError::NotFound => write!(f, "Organization not found"),
+            OrganizationError::InvalidKey => write!(f, "Organization API key is invalid"),
+            OrganizationError::InvalidRequest(reason) => write!(f, "Invalid organization request: {}", reason),
+            OrganizationError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for OrganizationError {}
+
+impl OrganizationService {
+    /// Create a new organization service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            organizations: db.collection("organizations"),
+        }
+    }
+
+    /// Key lookups by hash, and organizations by member owner
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.organizations
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "apiKeyHash": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        self.organizations
+            .create_index(IndexModel::builder().keys(doc! { "ownerIds": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Create an organization; returns it with its API key, which is not stored and can't be shown again
+    pub async fn create(
+        &self,
+        request: CreateOrganizationRequest,
+    ) -> Result<(Organization, String), OrganizationError> {
+        validate_request(&request)?;
+
+        let api_key = generate_secret();
+        let organization = Organization::new(request, hash_secret(&api_key));
+        self.organizations.insert_one(&organization).await?;
+
+        info!(
+            "Created organization {} ({}) with {} members",
+            organization.id, organization.name, organization.owner_ids.len()
+        );
+        Ok((organization, api_key))
+    }
+
+    pub async fn get(&self, organization_id: &str) -> Result<Organization, OrganizationError> {
+        let id = ObjectId::parse_str(organization_id).map_err(|_| OrganizationError::NotFound)?;
+        self.organizations
+            .find_one(doc! { "_id": id })
+            .await?
+            .ok_or(OrganizationError::NotFound)
+    }
+
+    /// Add an owner account to an organization; adding an existing member changes nothing
+    pub async fn add_member(&self, organization_id: &str, owner_id: &str) -> Result<Organization, OrganizationError> {
+        validate_owner_id(owner_id)?;
+        self.update_members(organization_id, doc! { "$addToSet": { "ownerIds": owner_id } }).await
+    }
+
+    pub async fn remove_member(&self, organization_id: &str, owner_id: &str) -> Result<Organization, OrganizationError> {
+        self.update_members(organization_id, doc! { "$pull": { "ownerIds": owner_id } }).await
+    }
+
+    async fn update_members(
+        &self,
+        organization_id: &str,
+        mut update: mongodb::bson::Document,
+    ) -> Result<Organization, OrganizationError> {
+        let id = ObjectId::parse_str(organization_id).map_err(|_| OrganizationError::NotFound)?;
+        update.insert("$set", doc! { "updatedAt": to_bson(&Utc::now())? });
+
+        let organization = self.organizations
+            .find_one_and_update(doc! { "_id": id }, update)
+            .return_document(ReturnDocument::After)
+            .await?
+            .ok_or(OrganizationError::NotFound)?;
+
+        info!("Organization {} now has {} members", organization.id, organization.owner_ids.len());
+        Ok(organization)
+    }
+
+    /// Set the look of the organization's branding kit
+    pub async fn set_branding(
+        &self,
+        organization_id: &str,
+        branding: BrandingProfile,
+    ) -> Result<Organization, OrganizationError> {
+        validate_branding(&branding)?;
+        let id = ObjectId::parse_str(organization_id).map_err(|_| OrganizationError::NotFound)?;
+
+        self.organizations
+            .find_one_and_update(
+                doc! { "_id": id },
+                doc! { "$set": { "branding": to_bson(&branding)?, "updatedAt": to_bson(&Utc::now())? } },
+            )
+            .return_document(ReturnDocument::After)
+            .await?
+            .ok_or(OrganizationError::NotFound)
+    }
+
+    /// Resolve a presented API key to the organization it was issued for
+    pub async fn authenticate(&self, organization_id: &str, api_key: &str) -> Result<Organization, OrganizationError> {
+        let organization = self.organizations
+            .find_one(doc! { "apiKeyHash": hash_secret(api_key) })
+            .await?
+            .ok_or(OrganizationError::InvalidKey)?;
+
+        // A key for one organization never opens another
+        if organization.id.to_hex() != organization_id {
+            return Err(OrganizationError::InvalidKey);
+        }
+        Ok(organization)
+    }
+}
+
+fn validate_owner_id(owner_id: &str) -> Result<(), OrganizationError> {
+    if ObjectId::parse_str(owner_id).is_err() {
+        return Err(OrganizationError::InvalidRequest(format!("'{}' is not a valid owner ID", owner_id)));
+    }
+    Ok(())
+}
+
+pub(crate) fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn validate_branding(branding: &BrandingProfile) -> Result<(), OrganizationError> {
+    for (field, color) in [("primaryColor", &branding.primary_color), ("accentColor", &branding.accent_color)] {
+        if !is_hex_color(color) {
+            return Err(OrganizationError::InvalidRequest(format!("{} must look like #1A2B3C", field)));
+        }
+    }
+    if let Some(logo_url) = &branding.logo_url {
+        validate_url(logo_url, "logoUrl").map_err(|e| OrganizationError::InvalidRequest(e.to_string()))?;
+    }
+    if branding.tagline.as_ref().is_some_and(|tagline| tagline.chars().count() > 80) {
+        return Err(OrganizationError::InvalidRequest("tagline must be at most 80 characters".to_string()));
+    }
+    Ok(())
+}
+
+fn validate_request(request: &CreateOrganizationRequest) -> Result<(), OrganizationError> {
+    if request.name.trim().is_empty() {
+        return Err(OrganizationError::InvalidRequest("name is required".to_string()));
+    }
+    request.owner_ids.iter().try_for_each(|owner_id| validate_owner_id(owner_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(owner_ids: &[&str]) -> CreateOrganizationRequest {
+        CreateOrganizationRequest {
+            name: " Kilimani Homes Agency ".to_string(),
+            owner_ids: owner_ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        assert!(validate_request(&request(&[])).is_ok());
+        assert!(validate_request(&request(&["507f1f77bcf86cd799439011"])).is_ok());
+        assert!(matches!(
+            validate_request(&request(&["owner-1"])),
+            Err(OrganizationError::InvalidRequest(_))
+        ));
+
+        let mut unnamed = request(&[]);
+        unnamed.name = "  ".to_string();
+        assert!(validate_request(&unnamed).is_err());
+    }
+
+    #[test]
+    fn test_validate_branding() {
+        let branding = BrandingProfile {
+            logo_url: Some("https://cdn.kilimanihomes.co.ke/logo.png".to_string()),
+            primary_color: "#0B3D2E".to_string(),
+            accent_color: "#f5a623".to_string(),
+            tagline: Some("Scan to view this home".to_string()),
+        };
+        assert!(validate_branding(&branding).is_ok());
+
+        let mut bad_color = branding.clone();
+        bad_color.primary_color = "green".to_string();
+        assert!(validate_branding(&bad_color).is_err());
+
+        let mut bad_logo = branding.clone();
+        bad_logo.logo_url = Some("javascript:alert(1)".to_string());
+        assert!(validate_branding(&bad_logo).is_err());
+    }
+
+    #[test]
+    fn test_new_organization_dedups_members() {
+        let organization = Organization::new(
+            request(&["507f1f77bcf86cd799439012", "507f1f77bcf86cd799439011", "507f1f77bcf86cd799439012"]),
+            hash_secret("key"),
+        );
+
+        assert_eq!(organization.name, "Kilimani Homes Agency");
+        assert_eq!(organization.owner_ids, vec!["507f1f77bcf86cd799439011", "507f1f77bcf86cd799439012"]);
+        assert!(organization.has_member("507f1f77bcf86cd799439011"));
+        assert!(!organization.has_member("507f1f77bcf86cd799439013"));
+        assert!(organization.to_response(None).api_key.is_none());
+    }
+}
@@ -0,0 +1,113 @@
+// src/services/page_cache.rs
+
+use crate::services::TtlCache;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Rendered landing pages, keyed by property and variant (page type, language, countdown).
+/// Entries expire after a TTL and are dropped as soon as their property changes; cheap to clone.
+#[derive(Clone)]
+pub struct PageCache {
+    // Every variant of one property's pages, expiring together
+    properties: TtlCache<String, HashMap<String, String>>,
+    counters: Arc<PageCacheCounters>,
+}
+
+#[derive(Default)]
+struct PageCacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PageCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    #[serde(rename = "cachedProperties")]
+    pub cached_properties: usize,
+}
+
+impl PageCache {
+    /// `capacity` counts properties, each with all of its variants
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            properties: TtlCache::new(ttl, capacity),
+            counters: Arc::new(PageCacheCounters::default()),
+        }
+    }
+
+    /// A cached page, if one was rendered for this variant within the TTL
+    pub fn get(&self, property_id: &str, variant: &str) -> Option<String> {
+        let page = self.properties.read(property_id, |variants| variants.get(variant).cloned()).flatten();
+
+        let counter = if page.is_some() { &self.counters.hits } else { &self.counters.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        page
+    }
+
+    /// Add a variant to the property's pages; an expired set starts over rather than mixing
+    /// old and new variants
+    pub fn store(&self, property_id: &str, variant: &str, html: String) {
+        self.properties.update(property_id.to_string(), |variants| {
+            variants.insert(variant.to_string(), html);
+        });
+    }
+
+    /// Drop every cached page for a property, e.g. after it was edited or its QR regenerated
+    pub fn invalidate(&self, property_id: &str) {
+        if self.properties.remove(property_id) {
+            self.counters.invalidations.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn metrics(&self) -> PageCacheMetrics {
+        PageCacheMetrics {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            invalidations: self.counters.invalidations.load(Ordering::Relaxed),
+            cached_properties: self.properties.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_and_invalidation() {
+        let cache = PageCache::new(Duration::from_secs(30), 10);
+        cache.store("p1", "dual:en:5", "<html>en</html>".to_string());
+        cache.store("p1", "dual:sw:5", "<html>sw</html>".to_string());
+        cache.store("p2", "dual:en:5", "<html>p2</html>".to_string());
+
+        assert_eq!(cache.get("p1", "dual:sw:5").as_deref(), Some("<html>sw</html>"));
+        assert!(cache.get("p1", "dual:en:0").is_none());
+
+        cache.invalidate("p1");
+        assert!(cache.get("p1", "dual:en:5").is_none());
+        assert!(cache.get("p2", "dual:en:5").is_some());
+
+        let metrics = cache.metrics();
+        assert_eq!((metrics.hits, metrics.misses, metrics.invalidations), (2, 2, 1));
+        assert_eq!(metrics.cached_properties, 1);
+    }
+
+    #[test]
+    fn test_expiry_and_eviction() {
+        let expired = PageCache::new(Duration::ZERO, 10);
+        expired.store("p1", "dual:en:5", "<html></html>".to_string());
+        assert!(expired.get("p1", "dual:en:5").is_none());
+
+        let cache = PageCache::new(Duration::from_secs(30), 1);
+        cache.store("p1", "dual:en:5", "<html>p1</html>".to_string());
+        cache.store("p2", "dual:en:5", "<html>p2</html>".to_string());
+        assert!(cache.get("p1", "dual:en:5").is_none());
+        assert!(cache.get("p2", "dual:en:5").is_some());
+    }
+}
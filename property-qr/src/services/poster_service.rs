@@ -0,0 +1,532 @@
+// src/services/poster_service.rs
+
+use std::time::Duration;
+use tracing::warn;
+
+use crate::models::PosterSize;
+use crate::utils::UrlValidator;
+
+const PHOTO_FETCH_TIMEOUT_SECS: u64 = 5;
+const MAX_PHOTO_BYTES: usize = 5 * 1024 * 1024;
+
+// Layout is drawn for A4 in points (1/72 inch) and scaled down for smaller sheets
+const A4_WIDTH: f32 = 595.28;
+const MARGIN: f32 = 40.0;
+const PHOTO_HEIGHT: f32 = 300.0;
+const QR_SIZE: f32 = 220.0;
+const FOOTER_HEIGHT: f32 = 44.0;
+
+// Unprinted border around a sticker sheet's grid, about 10mm
+const SHEET_MARGIN: f32 = 28.0;
+
+// DAO-Bitat purple, as on the landing pages
+const BRAND_RGB: (f32, f32, f32) = (0.463, 0.294, 0.635);
+
+/// Composes print-ready posters. Pages are written directly as PDF with the standard
+/// Helvetica fonts; photos (JPEG) and QR codes (PNG) are embedded without re-encoding.
+#[derive(Clone)]
+pub struct PosterService {
+    http_client: reqwest::Client,
+    image_domains: Vec<String>, // Hosts listing photos may be fetched from
+}
+
+// One code on a sticker sheet, labelled underneath
+#[derive(Debug, Clone)]
+pub struct Sticker {
+    pub label: String,
+    pub qr_png: Option<Vec<u8>>,
+}
+
+// Everything printed on one poster
+#[derive(Debug, Clone)]
+pub struct PosterContent {
+    pub property_name: String,
+    pub location: String,
+    pub action: String, // "for sale" | "for rent"
+    pub price: i64,
+    pub scan_url: String,
+    pub qr_png: Option<Vec<u8>>,
+    pub photo_jpeg: Option<Vec<u8>>,
+}
+
+impl PosterService {
+    pub fn new(image_domains: Vec<String>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(PHOTO_FETCH_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self { http_client, image_domains }
+    }
+
+    /// Download a listing photo from an allowed host; a poster without its photo beats no poster
+    pub async fn fetch_photo(&self, url: &str) -> Option<Vec<u8>> {
+        let domains: Vec<&str> = self.image_domains.iter().map(String::as_str).collect();
+        if !UrlValidator::is_secure_url(url) || !UrlValidator::is_allowed_domain(url, &domains) {
+            warn!("Skipping poster photo from disallowed URL: {}", url);
+            return None;
+        }
+
+        let response = match self.http_client.get(url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Failed to fetch poster photo {}: {}", url, e);
+                return None;
+            }
+        };
+        if response.content_length().is_some_and(|length| length as usize > MAX_PHOTO_BYTES) {
+            warn!("Poster photo {} is too large", url);
+            return None;
+        }
+
+        match response.bytes().await {
+            Ok(bytes) if bytes.len() <= MAX_PHOTO_BYTES => Some(bytes.to_vec()),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Failed to read poster photo {}: {}", url, e);
+                None
+            }
+        }
+    }
+}
+
+/// Lay out a one-page poster: photo, name, price, location, QR code with its link, and a footer
+pub fn compose_poster(content: &PosterContent, size: PosterSize) -> Vec<u8> {
+    let (page_width, page_height) = size.dimensions();
+    let scale = page_width / A4_WIDTH;
+    let margin = MARGIN * scale;
+    let inner_width = page_width - 2.0 * margin;
+
+    let photo = content.photo_jpeg.as_deref().and_then(jpeg_image);
+    let qr = content.qr_png.as_deref().and_then(png_image);
+
+    let mut ops = String::new();
+    let mut y = page_height - margin;
+
+    // Listing photo, fitted into the top band; a tinted panel stands in when there is none
+    let band_height = PHOTO_HEIGHT * scale;
+    y -= band_height;
+    match &photo {
+        Some(image) => {
+            let fit = (inner_width / image.width as f32).min(band_height / image.height as f32);
+            let (width, height) = (image.width as f32 * fit, image.height as f32 * fit);
+            let x = margin + (inner_width - width) / 2.0;
+            ops.push_str(&format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Photo Do Q\n", width, height, x, y + (band_height - height) / 2.0));
+        }
+        None => {
+            ops.push_str(&format!(
+                "q 0.94 0.93 0.97 rg {:.2} {:.2} {:.2} {:.2} re f Q\n",
+                margin, y, inner_width, band_height
+            ));
+        }
+    }
+
+    let name_size = 28.0 * scale;
+    y -= 22.0 * scale + name_size;
+    let name = fit_text(&content.property_name, inner_width, name_size);
+    ops.push_str(&text_op("F2", name_size, (0.13, 0.13, 0.13), margin, y, &name));
+
+    let price_size = 20.0 * scale;
+    y -= 10.0 * scale + price_size;
+    let price = format!("KES {} - {}", group_thousands(content.price), content.action);
+    ops.push_str(&text_op("F2", price_size, BRAND_RGB, margin, y, &fit_text(&price, inner_width, price_size)));
+
+    let location_size = 14.0 * scale;
+    y -= 8.0 * scale + location_size;
+    ops.push_str(&text_op("F1", location_size, (0.4, 0.4, 0.4), margin, y, &fit_text(&content.location, inner_width, location_size)));
+
+    // QR code, centred, with a call to action and the link for anyone who can't scan
+    let qr_size = QR_SIZE * scale;
+    let qr_x = (page_width - qr_size) / 2.0;
+    y -= 24.0 * scale + qr_size;
+    match &qr {
+        Some(_) => ops.push_str(&format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Qr Do Q\n", qr_size, qr_size, qr_x, y)),
+        None => ops.push_str(&format!(
+            "q 0 0 0 RG {:.2} w {:.2} {:.2} {:.2} {:.2} re S Q\n",
+            2.0 * scale, qr_x, y, qr_size, qr_size
+        )),
+    }
+
+    let cta_size = 16.0 * scale;
+    y -= 12.0 * scale + cta_size;
+    ops.push_str(&centered_text_op("F2", cta_size, (0.13, 0.13, 0.13), 0.0, page_width, y, "Scan to view this property"));
+
+    let url_size = 10.0 * scale;
+    y -= 6.0 * scale + url_size;
+    ops.push_str(&centered_text_op("F1", url_size, (0.4, 0.4, 0.4), 0.0, page_width, y, &fit_text(&content.scan_url, inner_width, url_size)));
+
+    // Footer band
+    let footer_height = FOOTER_HEIGHT * scale;
+    ops.push_str(&format!(
+        "q {:.3} {:.3} {:.3} rg 0 0 {:.2} {:.2} re f Q\n",
+        BRAND_RGB.0, BRAND_RGB.1, BRAND_RGB.2, page_width, footer_height
+    ));
+    let footer_size = 12.0 * scale;
+    ops.push_str(&centered_text_op(
+        "F2", footer_size, (1.0, 1.0, 1.0), 0.0, page_width, (footer_height - footer_size) / 2.0 + 2.0 * scale,
+        "Listed on DAO-Bitat - verified property listings",
+    ));
+
+    let images: Vec<(&str, PdfImage)> = [("Photo", photo), ("Qr", qr)]
+        .into_iter()
+        .filter_map(|(name, image)| image.map(|image| (name, image)))
+        .collect();
+    write_pdf(page_width, page_height, &[ops], &images)
+}
+
+/// Lay QR codes out in a grid of `rows` x `columns` per page, for pre-cut sticker sheets.
+/// Codes run left to right, top to bottom, onto as many pages as they need.
+pub fn compose_sticker_sheet(stickers: &[Sticker], size: PosterSize, rows: u32, columns: u32) -> Vec<u8> {
+    let (page_width, page_height) = size.dimensions();
+    let cell_width = (page_width - 2.0 * SHEET_MARGIN) / columns as f32;
+    let cell_height = (page_height - 2.0 * SHEET_MARGIN) / rows as f32;
+    let label_size = (cell_height * 0.09).clamp(6.0, 11.0);
+    let label_band = label_size * 2.0;
+    let qr_size = cell_width.min(cell_height - label_band) * 0.85;
+
+    let names: Vec<String> = (0..stickers.len()).map(|n| format!("Qr{}", n)).collect();
+    let images: Vec<(&str, PdfImage)> = stickers.iter()
+        .zip(&names)
+        .filter_map(|(sticker, name)| sticker.qr_png.as_deref().and_then(png_image).map(|image| (name.as_str(), image)))
+        .collect();
+
+    let per_page = (rows * columns) as usize;
+    let pages: Vec<String> = stickers.chunks(per_page.max(1))
+        .enumerate()
+        .map(|(page, chunk)| {
+            let mut ops = String::new();
+            for (slot, sticker) in chunk.iter().enumerate() {
+                let index = page * per_page + slot;
+                let (row, column) = ((slot as u32) / columns, (slot as u32) % columns);
+                let cell_x = SHEET_MARGIN + column as f32 * cell_width;
+                let cell_y = page_height - SHEET_MARGIN - (row + 1) as f32 * cell_height;
+
+                let qr_x = cell_x + (cell_width - qr_size) / 2.0;
+                let qr_y = cell_y + label_band + (cell_height - label_band - qr_size) / 2.0;
+                if images.iter().any(|(name, _)| *name == names[index]) {
+                    ops.push_str(&format!("q {:.2} 0 0 {:.2} {:.2} {:.2} cm /{} Do Q\n", qr_size, qr_size, qr_x, qr_y, names[index]));
+                } else {
+                    ops.push_str(&format!("q 0 0 0 RG 1 w {:.2} {:.2} {:.2} {:.2} re S Q\n", qr_x, qr_y, qr_size, qr_size));
+                }
+
+                let label = fit_text(&sticker.label, cell_width * 0.95, label_size);
+                ops.push_str(&centered_text_op("F1", label_size, (0.13, 0.13, 0.13), cell_x, cell_width, cell_y + label_size * 0.8, &label));
+            }
+            ops
+        })
+        .collect();
+
+    write_pdf(page_width, page_height, &pages, &images)
+}
+
+/// An image XObject, with its data already in a PDF filter's format
+struct PdfImage {
+    width: u32,
+    height: u32,
+    color_space: String,
+    bits_per_component: u8,
+    filter: &'static str,
+    decode_parms: Option<String>,
+    data: Vec<u8>,
+}
+
+/// Baseline or progressive JPEG, embedded as-is with DCTDecode
+fn jpeg_image(bytes: &[u8]) -> Option<PdfImage> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut i = 2;
+    while i + 9 < bytes.len() {
+        if bytes[i] != 0xFF {
+            return None;
+        }
+        let marker = bytes[i + 1];
+        let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        if (0xC0..=0xC2).contains(&marker) {
+            let height = u16::from_be_bytes([bytes[i + 5], bytes[i + 6]]) as u32;
+            let width = u16::from_be_bytes([bytes[i + 7], bytes[i + 8]]) as u32;
+            let color_space = match bytes[i + 9] {
+                1 => "/DeviceGray",
+                3 => "/DeviceRGB",
+                _ => return None, // CMYK JPEGs come out inverted without more work
+            };
+            return Some(PdfImage {
+                width,
+                height,
+                color_space: color_space.to_string(),
+                bits_per_component: 8,
+                filter: "/DCTDecode",
+                decode_parms: None,
+                data: bytes.to_vec(),
+            });
+        }
+        i += 2 + length;
+    }
+    None
+}
+
+/// Non-interlaced PNG without alpha: its IDAT stream is already FlateDecode data with PNG predictors
+fn png_image(bytes: &[u8]) -> Option<PdfImage> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    if !bytes.starts_with(&SIGNATURE) {
+        return None;
+    }
+
+    let mut header = None;
+    let mut palette = None;
+    let mut data = Vec::new();
+    let mut i = SIGNATURE.len();
+    while i + 8 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[i..i + 4].try_into().ok()?) as usize;
+        let kind = &bytes[i + 4..i + 8];
+        let chunk = bytes.get(i + 8..i + 8 + length)?;
+        match kind {
+            b"IHDR" if length >= 13 => header = Some(chunk.to_vec()),
+            b"PLTE" => palette = Some(chunk.to_vec()),
+            b"IDAT" => data.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        i += 12 + length;
+    }
+
+    let header = header?;
+    let width = u32::from_be_bytes(header[0..4].try_into().ok()?);
+    let height = u32::from_be_bytes(header[4..8].try_into().ok()?);
+    let (bit_depth, color_type, interlace) = (header[8], header[9], header[12]);
+    if interlace != 0 || data.is_empty() {
+        return None;
+    }
+
+    let (color_space, colors) = match (color_type, palette) {
+        (0, _) => ("/DeviceGray".to_string(), 1),
+        (2, _) if bit_depth == 8 => ("/DeviceRGB".to_string(), 3),
+        (3, Some(palette)) if palette.len().is_multiple_of(3) => {
+            let hex: String = palette.iter().map(|byte| format!("{:02X}", byte)).collect();
+            (format!("[/Indexed /DeviceRGB {} <{}>]", palette.len() / 3 - 1, hex), 1)
+        }
+        _ => return None, // Alpha channels would need splitting into a soft mask
+    };
+
+    Some(PdfImage {
+        width,
+        height,
+        color_space,
+        bits_per_component: bit_depth,
+        filter: "/FlateDecode",
+        decode_parms: Some(format!(
+            "<< /Predictor 15 /Colors {} /BitsPerComponent {} /Columns {} >>",
+            colors, bit_depth, width
+        )),
+        data,
+    })
+}
+
+/// One PDF with a page per content stream; every page shares the fonts and images
+fn write_pdf(width: f32, height: f32, pages: &[String], images: &[(&str, PdfImage)]) -> Vec<u8> {
+    // 1 catalog, 2 page tree, 3-4 fonts, then the images, then each page and its content
+    let first_page = 5 + images.len();
+    let xobjects: String = images.iter()
+        .enumerate()
+        .map(|(n, (name, _))| format!("/{} {} 0 R ", name, 5 + n))
+        .collect();
+    let kids: Vec<String> = (0..pages.len()).map(|n| format!("{} 0 R", first_page + 2 * n)).collect();
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (_, image) in images {
+        let mut dictionary = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent {} /Filter {} /Length {}",
+            image.width, image.height, image.color_space, image.bits_per_component, image.filter, image.data.len()
+        );
+        if let Some(decode_parms) = &image.decode_parms {
+            dictionary.push_str(&format!(" /DecodeParms {}", decode_parms));
+        }
+        dictionary.push_str(" >>");
+        objects.push(stream_object(&dictionary, &image.data));
+    }
+    for (n, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> /XObject << {}>> >> /Contents {} 0 R >>",
+            width, height, xobjects, first_page + 2 * n + 1
+        ).into_bytes());
+        objects.push(stream_object(&format!("<< /Length {} >>", content.len()), content.as_bytes()));
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (n, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", n + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ).as_bytes());
+    pdf
+}
+
+fn stream_object(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("{}\nstream\n", dictionary).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+fn text_op(font: &str, size: f32, rgb: (f32, f32, f32), x: f32, y: f32, text: &str) -> String {
+    format!(
+        "BT /{} {:.2} Tf {:.3} {:.3} {:.3} rg {:.2} {:.2} Td ({}) Tj ET\n",
+        font, size, rgb.0, rgb.1, rgb.2, x, y, pdf_string(text)
+    )
+}
+
+/// Text centred within a column starting at `left`
+fn centered_text_op(font: &str, size: f32, rgb: (f32, f32, f32), left: f32, width: f32, y: f32, text: &str) -> String {
+    let x = left + ((width - approximate_width(text, size)) / 2.0).max(0.0);
+    text_op(font, size, rgb, x, y, text)
+}
+
+// Helvetica averages a little over half an em per character; close enough to centre and truncate
+fn approximate_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.56
+}
+
+/// Cut text that would run off the page, ending it with "..."
+fn fit_text(text: &str, max_width: f32, size: f32) -> String {
+    if approximate_width(text, size) <= max_width {
+        return text.to_string();
+    }
+    let keep = ((max_width / (size * 0.56)) as usize).saturating_sub(3);
+    format!("{}...", text.chars().take(keep).collect::<String>().trim_end())
+}
+
+/// Literal string for the WinAnsi-encoded standard fonts; characters they can't show become '?'
+fn pdf_string(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            '\u{a0}'..='\u{ff}' => format!("\\{:03o}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+fn group_thousands(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if value < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content() -> PosterContent {
+        PosterContent {
+            property_name: "Garden Villa (2BR)".to_string(),
+            location: "Kilimani, Nairobi".to_string(),
+            action: "for sale".to_string(),
+            price: 12_500_000,
+            scan_url: "https://qr.daobitat.xyz/scan/507f1f77bcf86cd799439011".to_string(),
+            qr_png: None,
+            photo_jpeg: None,
+        }
+    }
+
+    #[test]
+    fn test_poster_is_a_well_formed_pdf() {
+        let pdf = compose_poster(&content(), PosterSize::A5);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/MediaBox [0 0 419.53 595.28]"));
+        assert!(text.contains("(Garden Villa \\(2BR\\)) Tj"));
+        assert!(text.contains("(KES 12,500,000 - for sale) Tj"));
+
+        // Each xref entry points at its object
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let first_entry = text[xref..].lines().nth(3).unwrap();
+        let offset: usize = first_entry[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(b"1 0 obj"));
+    }
+
+    #[test]
+    fn test_images_are_embedded() {
+        // 2x1 grey PNG: IHDR, one IDAT, IEND (CRCs aren't checked)
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        for (kind, data) in [
+            (&b"IHDR"[..], vec![0, 0, 0, 2, 0, 0, 0, 1, 8, 0, 0, 0, 0]),
+            (&b"IDAT"[..], vec![0x78, 0x9C, 0x63, 0x60, 0x00, 0x00]),
+            (&b"IEND"[..], vec![]),
+        ] {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            png.extend_from_slice(kind);
+            png.extend_from_slice(&data);
+            png.extend_from_slice(&[0, 0, 0, 0]);
+        }
+        // Minimal JPEG header up to a 3-component SOF0 of 640x480
+        let jpeg = vec![0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, 0x03, 0, 0, 0, 0, 0, 0];
+
+        let qr = png_image(&png).unwrap();
+        assert_eq!((qr.width, qr.height, qr.color_space.as_str()), (2, 1, "/DeviceGray"));
+        let photo = jpeg_image(&jpeg).unwrap();
+        assert_eq!((photo.width, photo.height, photo.color_space.as_str()), (640, 480, "/DeviceRGB"));
+
+        let pdf = compose_poster(&PosterContent { qr_png: Some(png), photo_jpeg: Some(jpeg), ..content() }, PosterSize::A4);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/XObject << /Photo 5 0 R /Qr 6 0 R >>"));
+        assert!(text.contains("/Filter /DCTDecode"));
+        assert!(text.contains("/DecodeParms << /Predictor 15 /Colors 1 /BitsPerComponent 8 /Columns 2 >>"));
+
+        assert!(png_image(b"not a png").is_none());
+    }
+
+    #[test]
+    fn test_sticker_sheet_pages() {
+        let stickers: Vec<Sticker> = (1..=5)
+            .map(|n| Sticker { label: format!("Unit {}", n), qr_png: None })
+            .collect();
+        let pdf = compose_sticker_sheet(&stickers, PosterSize::A4, 2, 2);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Unit 1) Tj"));
+        assert!(text.contains("(Unit 5) Tj"));
+        // Without images, every code is an outlined placeholder
+        assert_eq!(text.matches(" re S Q").count(), 5);
+    }
+
+    #[test]
+    fn test_text_helpers() {
+        assert_eq!(group_thousands(12_500_000), "12,500,000");
+        assert_eq!(group_thousands(950), "950");
+        assert_eq!(pdf_string("Caf\u{e9} (new) \u{4e2d}"), "Caf\\351 \\(new\\) ?");
+        assert!(fit_text(&"x".repeat(200), 300.0, 20.0).ends_with("..."));
+        assert_eq!(fit_text("Short", 300.0, 20.0), "Short");
+    }
+}
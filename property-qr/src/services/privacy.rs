@@ -0,0 +1,135 @@
+// src/services/privacy.rs
+
+use rand::Rng;
+
+use crate::config::PrivacyConfig;
+use crate::models::{AreaStats, OrgPropertyScans, PrivacyNotice};
+
+// Aggregate row whose counts can be noised before it leaves the owner's account
+pub trait NoisyBucket {
+    /// Counts to perturb; the first is the one held against the suppression threshold
+    fn counts_mut(&mut self) -> Vec<&mut i64>;
+}
+
+impl NoisyBucket for AreaStats {
+    fn counts_mut(&mut self) -> Vec<&mut i64> {
+        vec![&mut self.unique_visitors, &mut self.scans]
+    }
+}
+
+impl NoisyBucket for OrgPropertyScans {
+    fn counts_mut(&mut self) -> Vec<&mut i64> {
+        vec![&mut self.unique_visitors, &mut self.scans]
+    }
+}
+
+// Differential privacy for shared aggregates: Laplace noise on every count, then buckets
+// whose noisy crowd is still small are dropped so no one scanner stands out
+#[derive(Debug, Clone, Copy)]
+pub struct PrivacyPolicy {
+    epsilon: f64,         // Per count; lower is noisier
+    min_bucket_size: i64, // Buckets below this after noise are left out
+}
+
+impl From<&PrivacyConfig> for PrivacyPolicy {
+    fn from(config: &PrivacyConfig) -> Self {
+        Self::new(config.epsilon, config.min_bucket_size)
+    }
+}
+
+impl PrivacyPolicy {
+    pub fn new(epsilon: f64, min_bucket_size: i64) -> Self {
+        Self { epsilon, min_bucket_size }
+    }
+
+    /// Noise every count and drop small buckets. Noise is calibrated to one scan or one visitor
+    /// changing a count by 1; the threshold is checked on the noisy value, never the true one.
+    pub fn apply<T: NoisyBucket>(&self, buckets: Vec<T>, rng: &mut impl Rng) -> Vec<T> {
+        buckets.into_iter()
+            .filter_map(|mut bucket| {
+                let mut counts = bucket.counts_mut();
+                for count in counts.iter_mut() {
+                    **count = self.noisy_count(**count, rng);
+                }
+                let crowd = counts.first().map_or(0, |count| **count);
+                (crowd >= self.min_bucket_size).then_some(bucket)
+            })
+            .collect()
+    }
+
+    /// A count plus Laplace(1/epsilon) noise, rounded and kept non-negative
+    pub fn noisy_count(&self, count: i64, rng: &mut impl Rng) -> i64 {
+        let noise = laplace(1.0 / self.epsilon, rng.random_range(-0.5..0.5));
+        (count as f64 + noise).round().max(0.0) as i64
+    }
+
+    /// What readers are told about how the figures were protected
+    pub fn notice(&self) -> PrivacyNotice {
+        PrivacyNotice {
+            epsilon: self.epsilon,
+            min_bucket_size: self.min_bucket_size,
+        }
+    }
+}
+
+/// Inverse CDF of the Laplace distribution for a uniform draw in [-0.5, 0.5)
+fn laplace(scale: f64, uniform: f64) -> f64 {
+    -scale * uniform.signum() * (1.0 - 2.0 * uniform.abs()).max(f64::MIN_POSITIVE).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    struct Bucket {
+        visitors: i64,
+        scans: i64,
+    }
+
+    impl NoisyBucket for Bucket {
+        fn counts_mut(&mut self) -> Vec<&mut i64> {
+            vec![&mut self.visitors, &mut self.scans]
+        }
+    }
+
+    #[test]
+    fn test_laplace() {
+        assert_eq!(laplace(1.0, 0.0), 0.0);
+        assert!(laplace(1.0, 0.25) > 0.0);
+        assert!(laplace(1.0, -0.25) < 0.0);
+        assert!((laplace(2.0, 0.25) - 2.0 * 2f64.ln()).abs() < 1e-9);
+        assert!(laplace(1.0, -0.5).is_finite());
+    }
+
+    #[test]
+    fn test_noise_is_centered_and_scaled() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let policy = PrivacyPolicy::new(0.5, 0);
+
+        let draws: Vec<i64> = (0..5000).map(|_| policy.noisy_count(1000, &mut rng) - 1000).collect();
+        let mean = draws.iter().sum::<i64>() as f64 / draws.len() as f64;
+        let mean_abs = draws.iter().map(|d| d.abs()).sum::<i64>() as f64 / draws.len() as f64;
+        assert!(mean.abs() < 0.3, "mean noise {}", mean);
+        // E|Laplace(b)| = b = 2
+        assert!((1.7..2.3).contains(&mean_abs), "mean absolute noise {}", mean_abs);
+        assert!(draws.iter().any(|d| *d != 0));
+
+        assert!((0..100).all(|_| policy.noisy_count(0, &mut rng) >= 0));
+    }
+
+    #[test]
+    fn test_small_buckets_are_suppressed() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let policy = PrivacyPolicy::new(1.0, 10);
+        let buckets = vec![
+            Bucket { visitors: 500, scans: 800 },
+            Bucket { visitors: 1, scans: 40 },
+        ];
+
+        let shared = policy.apply(buckets, &mut rng);
+        assert_eq!(shared.len(), 1);
+        assert!((480..=520).contains(&shared[0].visitors));
+        assert!((780..=820).contains(&shared[0].scans));
+    }
+}
@@ -1,16 +1,24 @@
 // src/services/property_service.rs
 
-use crate::models::{Property, PropertyQrInfo};
+use crate::models::{EligibilityReport, IneligibilityGroup, IneligibilityReason, Property, PropertyQrInfo};
+use crate::services::TtlCache;
 use mongodb::{
-    bson::{doc, oid::ObjectId, DateTime as BsonDateTime},
+    bson::{doc, oid::ObjectId, DateTime as BsonDateTime, Document},
     Collection, Database, options::FindOptions,
 };
+use serde::Serialize;
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
+use utoipa::ToSchema;
 
 #[derive(Clone)]
 pub struct PropertyService {
     properties: Collection<Property>,
+    users: Collection<Document>, // The listing platform's accounts, for owner contact details
+    // Recently loaded listings, so popular codes don't read the database on every render
+    listing_cache: Option<TtlCache<String, PropertyQrInfo>>,
 }
 
 #[derive(Debug)]
@@ -40,6 +48,16 @@ impl std::fmt::Display for PropertyError {
 
 impl std::error::Error for PropertyError {}
 
+// Listings failing one eligibility rule; mirrors Property::ineligibility_reason, except that
+// a live listing without images and a price matches both of those rules
+fn ineligibility_filter(reason: IneligibilityReason) -> Document {
+    match reason {
+        IneligibilityReason::Removed => doc! { "removed": true },
+        IneligibilityReason::NoImages => doc! { "removed": { "$ne": true }, "images.0": { "$exists": false } },
+        IneligibilityReason::ZeroPrice => doc! { "removed": { "$ne": true }, "price": { "$lte": 0 } },
+    }
+}
+
 // Helper function to convert chrono DateTime to BSON DateTime
 fn utc_to_bson(dt: chrono::DateTime<chrono::Utc>) -> BsonDateTime {
     BsonDateTime::from_millis(dt.timestamp_millis())
@@ -50,9 +68,17 @@ impl PropertyService {
     pub fn new(db: &Database) -> Self {
         Self {
             properties: db.collection("properties"),
+            users: db.collection("users"),
+            listing_cache: None,
         }
     }
 
+    /// Serve `get_live_listing` from memory for up to `ttl`, holding at most `capacity` listings
+    pub fn with_listing_cache(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.listing_cache = Some(TtlCache::new(ttl, capacity));
+        self
+    }
+
     /// Get a property by its MongoDB ID
     pub async fn get_property_by_id(&self, property_id: &str) -> Result<Property, PropertyError> {
         let object_id = ObjectId::from_str(property_id)
@@ -66,6 +92,14 @@ impl PropertyService {
         Ok(property)
     }
 
+    /// Property a booking was made on
+    pub async fn get_property_by_booking(&self, booking_id: &ObjectId) -> Result<Property, PropertyError> {
+        self.properties
+            .find_one(doc! { "bookings._id": booking_id })
+            .await?
+            .ok_or(PropertyError::NotFound)
+    }
+
     /// Get property info suitable for QR generation
     pub async fn get_property_qr_info(&self, property_id: &str) -> Result<PropertyQrInfo, PropertyError> {
         let property = self.get_property_by_id(property_id).await?;
@@ -79,6 +113,27 @@ impl PropertyService {
         Ok(property.to_qr_info())
     }
 
+    /// Current listing details for rendering pages, at most one cache TTL old.
+    /// Use this rather than a QR code's metadata, which is a generation-time snapshot.
+    pub async fn get_live_listing(&self, property_id: &str) -> Result<PropertyQrInfo, PropertyError> {
+        if let Some(listing) = self.listing_cache.as_ref().and_then(|cache| cache.get(property_id)) {
+            return Ok(listing);
+        }
+
+        let listing = self.get_property_qr_info(property_id).await?;
+        if let Some(cache) = &self.listing_cache {
+            cache.insert(property_id.to_string(), listing.clone());
+        }
+        Ok(listing)
+    }
+
+    /// Forget a cached listing so the next render reads the property again
+    pub fn invalidate_listing(&self, property_id: &str) {
+        if let Some(cache) = &self.listing_cache {
+            cache.remove(property_id);
+        }
+    }
+
     /// Get multiple properties by their IDs
     pub async fn get_properties_by_ids(&self, property_ids: Vec<String>) -> Result<Vec<Property>, PropertyError> {
         let mut object_ids = Vec::new();
@@ -101,6 +156,37 @@ impl PropertyService {
         Ok(properties)
     }
 
+    /// Current names of some listings, for labelling their QR codes. Listings that can't be
+    /// read are left out, and callers fall back to the name each code was generated with.
+    pub async fn listing_names(&self, property_ids: &[ObjectId]) -> HashMap<ObjectId, String> {
+        match self.find_listing_names(property_ids).await {
+            Ok(names) => names,
+            Err(e) => {
+                warn!("Failed to load names of {} listing(s): {}", property_ids.len(), e);
+                HashMap::new()
+            }
+        }
+    }
+
+    async fn find_listing_names(&self, property_ids: &[ObjectId]) -> Result<HashMap<ObjectId, String>, PropertyError> {
+        let filter = doc! { "_id": { "$in": property_ids } };
+        let options = FindOptions::builder()
+            .projection(doc! { "propertyName": 1 })
+            .build();
+
+        let mut cursor = self.properties.clone_with_type::<Document>().find(filter).with_options(options).await?;
+        let mut names = HashMap::new();
+
+        while cursor.advance().await? {
+            let property = cursor.deserialize_current()?;
+            if let (Ok(id), Ok(name)) = (property.get_object_id("_id"), property.get_str("propertyName")) {
+                names.insert(id, name.to_string());
+            }
+        }
+
+        Ok(names)
+    }
+
     /// Get properties eligible for QR generation
     pub async fn get_qr_eligible_properties(&self, limit: Option<i64>) -> Result<Vec<PropertyQrInfo>, PropertyError> {
         let filter = doc! {
@@ -209,13 +295,63 @@ impl PropertyService {
         })
     }
 
+    /// Listings blocked from QR generation for each of `reasons`, with one page of each
+    /// group, most recently updated first
+    pub async fn eligibility_report(
+        &self,
+        reasons: &[IneligibilityReason],
+        limit: i64,
+        skip: u64,
+    ) -> Result<EligibilityReport, PropertyError> {
+        let total_properties = self.properties.count_documents(doc! {}).await?;
+        let eligible_properties = self.properties
+            .count_documents(doc! {
+                "removed": { "$ne": true },
+                "images.0": { "$exists": true },
+                "price": { "$gt": 0 }
+            })
+            .await?;
+
+        let mut groups = Vec::with_capacity(reasons.len());
+        for reason in reasons {
+            let filter = ineligibility_filter(*reason);
+            let count = self.properties.count_documents(filter.clone()).await?;
+
+            let options = FindOptions::builder()
+                .sort(doc! { "updatedAt": -1, "_id": -1 })
+                .skip(skip)
+                .limit(limit)
+                .build();
+            let mut cursor = self.properties.find(filter).with_options(options).await?;
+            let mut properties = Vec::new();
+            while cursor.advance().await? {
+                let property: Property = cursor.deserialize_current()?;
+                properties.push(property.to_ineligible_property());
+            }
+
+            groups.push(IneligibilityGroup {
+                reason: *reason,
+                count,
+                properties,
+                next_cursor: Some(skip + limit.max(1) as u64).filter(|next| *next < count),
+            });
+        }
+
+        Ok(EligibilityReport {
+            total_properties,
+            eligible_properties,
+            ineligible_properties: total_properties.saturating_sub(eligible_properties),
+            groups,
+        })
+    }
+
     /// Search properties by criteria
     pub async fn search_properties(&self, criteria: PropertySearchCriteria) -> Result<Vec<Property>, PropertyError> {
         let mut filter = doc! { "removed": { "$ne": true } };
 
         // Add location filter
         if let Some(location) = criteria.location {
-            filter.insert("location", doc! { "$regex": location, "$options": "i" });
+            filter.insert("location", doc! { "$regex": escape_regex(&location), "$options": "i" });
         }
 
         // Add price range filter
@@ -310,6 +446,48 @@ if !price_conditions.is_empty() {
         Ok(properties)
     }
 
+    /// IDs of every listed property owned by any of these owners
+    pub async fn get_property_ids_by_owners(&self, owner_ids: &[String]) -> Result<Vec<String>, PropertyError> {
+        let owner_object_ids = owner_ids.iter()
+            .map(|owner_id| ObjectId::from_str(owner_id).map_err(|_| PropertyError::InvalidId))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let filter = doc! {
+            "owner": { "$in": owner_object_ids },
+            "removed": { "$ne": true }
+        };
+
+        let mut cursor = self.properties.find(filter).await?;
+        let mut property_ids = Vec::new();
+
+        while cursor.advance().await? {
+            let property = cursor.deserialize_current()?;
+            property_ids.push(property.id.to_hex());
+        }
+
+        Ok(property_ids)
+    }
+
+    /// Email addresses of these owners, by owner ID; owners without one are left out
+    pub async fn get_owner_emails(&self, owner_ids: &[ObjectId]) -> Result<HashMap<String, String>, PropertyError> {
+        let filter = doc! { "_id": { "$in": owner_ids }, "email": { "$type": "string" } };
+        let options = FindOptions::builder()
+            .projection(doc! { "email": 1 })
+            .build();
+
+        let mut cursor = self.users.find(filter).with_options(options).await?;
+        let mut emails = HashMap::new();
+
+        while cursor.advance().await? {
+            let user = cursor.deserialize_current()?;
+            if let (Ok(owner_id), Ok(email)) = (user.get_object_id("_id"), user.get_str("email")) {
+                emails.insert(owner_id.to_hex(), email.to_string());
+            }
+        }
+
+        Ok(emails)
+    }
+
     /// Get recently added properties
     pub async fn get_recent_properties(&self, limit: i64) -> Result<Vec<PropertyQrInfo>, PropertyError> {
         let filter = doc! { "removed": { "$ne": true } };
@@ -331,30 +509,78 @@ if !price_conditions.is_empty() {
         Ok(recent_properties)
     }
 
+    /// Unsold listings other than `exclude_id` in the same location with the same action,
+    /// priced within `price_range` and added after `since`, newest first
+    pub async fn find_similar_listings(
+        &self,
+        exclude_id: &str,
+        location: &str,
+        action: &str,
+        price_range: std::ops::RangeInclusive<i64>,
+        since: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<PropertyQrInfo>, PropertyError> {
+        let exclude_id = ObjectId::from_str(exclude_id)
+            .map_err(|_| PropertyError::InvalidId)?;
+
+        let filter = doc! {
+            "_id": { "$ne": exclude_id },
+            "removed": { "$ne": true },
+            "status.sold": { "$ne": true },
+            "location": { "$regex": format!("^{}$", escape_regex(location)), "$options": "i" },
+            "action": action,
+            "price": { "$gte": price_range.start(), "$lte": price_range.end() },
+            "createdAt": { "$gt": utc_to_bson(since) }
+        };
+        let options = FindOptions::builder()
+            .limit(limit)
+            .sort(doc! { "createdAt": -1 })
+            .build();
+
+        let mut cursor = self.properties.find(filter).with_options(options).await?;
+        let mut similar_properties = Vec::new();
+
+        while cursor.advance().await? {
+            let property: Property = cursor.deserialize_current()?;
+            if property.is_qr_eligible() {
+                similar_properties.push(property.to_qr_info());
+            }
+        }
+
+        Ok(similar_properties)
+    }
+
     /// Helper method to determine why a property is not eligible for QR generation
     fn get_ineligibility_reason(&self, property: &Property) -> String {
-        if property.removed.unwrap_or(false) {
-            return "Property has been removed".to_string();
-        }
-        
-        if property.images.is_empty() {
-            return "Property has no images".to_string();
-        }
-        
-        if property.price <= 0 {
-            return "Property has invalid price".to_string();
+        property.ineligibility_reason()
+            .map_or("Unknown reason", |reason| reason.description())
+            .to_string()
+    }
+}
+
+// Match user-entered text literally inside a $regex
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
         }
-        
-        "Unknown reason".to_string()
+        escaped.push(c);
     }
+    escaped
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct PropertyStats {
+    #[serde(rename = "totalProperties")]
     pub total_properties: i64,
-    pub active_properties: i64,
+    #[serde(rename = "activeProperties")]
+    pub active_properties: i64, // Not removed
+    #[serde(rename = "verifiedProperties")]
     pub verified_properties: i64,
+    #[serde(rename = "propertiesWithImages")]
     pub properties_with_images: i64,
+    #[serde(rename = "qrEligibleProperties")]
     pub qr_eligible_properties: i64,
 }
 
@@ -388,6 +614,24 @@ mod tests {
     use super::*;
     use mongodb::Client;
 
+    #[test]
+    fn test_ineligibility_matches_the_report_filters() {
+        let live = Property {
+            images: vec!["https://cdn.daobitat.xyz/villa.jpg".to_string()],
+            price: 10_000_000,
+            ..Property::default()
+        };
+        assert_eq!(live.ineligibility_reason(), None);
+        assert_eq!(Property { removed: Some(true), ..live.clone() }.ineligibility_reason(), Some(IneligibilityReason::Removed));
+        assert_eq!(Property { images: Vec::new(), price: 0, ..live.clone() }.ineligibility_reason(), Some(IneligibilityReason::NoImages));
+        assert_eq!(Property { price: 0, ..live }.ineligibility_reason(), Some(IneligibilityReason::ZeroPrice));
+
+        // Removed listings are only reported as removed
+        for reason in [IneligibilityReason::NoImages, IneligibilityReason::ZeroPrice] {
+            assert_eq!(ineligibility_filter(reason).get_document("removed").unwrap(), &doc! { "$ne": true });
+        }
+    }
+
     async fn get_test_service() -> PropertyService {
         let client = Client::with_uri_str("mongodb://localhost:27017")
             .await
@@ -426,4 +670,10 @@ mod tests {
         // Should not fail, may return empty vec
         assert!(properties.len() >= 0);
     }
+
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("Kilimani"), "Kilimani");
+        assert_eq!(escape_regex("Westlands (Phase 2).*"), "Westlands \\(Phase 2\\)\\.\\*");
+    }
 }
\ No newline at end of file
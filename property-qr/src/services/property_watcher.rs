@@ -0,0 +1,179 @@
+// src/services/property_watcher.rs
+
+use crate::models::{AuditActor, QrGenerationReason};
+use crate::services::{QrGenerationOptions, QrGeneratorService};
+use futures_util::StreamExt;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken},
+    Collection, Database,
+};
+use std::time::Duration;
+use tracing::{info, warn, error};
+
+// Wait before reopening the change stream after it errors
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+// Listing fields printed on or encoded in QR codes; edits to any of them regenerate the code
+const QR_FIELDS: [&str; 3] = ["propertyName", "price", "images"];
+
+fn watcher_actor() -> AuditActor {
+    AuditActor::system("property-watcher")
+}
+
+/// What a property edit means for its QR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyChangeAction {
+    Regenerate,
+    Deactivate,
+}
+
+impl PropertyChangeAction {
+    /// Action for an update, from the change stream's updated fields
+    pub fn for_update(updated_fields: &Document) -> Option<Self> {
+        if updated_fields.get("removed") == Some(&Bson::Boolean(true)) {
+            return Some(PropertyChangeAction::Deactivate);
+        }
+
+        // Array edits arrive as dotted paths, e.g. "images.2"
+        let touches_qr = updated_fields.keys().any(|key| {
+            let field = key.split('.').next().unwrap_or(key);
+            QR_FIELDS.contains(&field)
+        });
+        touches_qr.then_some(PropertyChangeAction::Regenerate)
+    }
+
+    /// Action for a whole-document replace, which doesn't say what changed
+    pub fn for_replace(document: &Document) -> Self {
+        match document.get_bool("removed") {
+            Ok(true) => PropertyChangeAction::Deactivate,
+            _ => PropertyChangeAction::Regenerate,
+        }
+    }
+}
+
+/// Watches the listing platform's properties collection and keeps QR codes in step with
+/// listing edits, instead of waiting for the property webhook. Needs a replica set.
+#[derive(Clone)]
+pub struct PropertyWatcher {
+    properties: Collection<Document>,
+    qr_generator: QrGeneratorService,
+}
+
+impl PropertyWatcher {
+    pub fn new(db: &Database, qr_generator: QrGeneratorService) -> Self {
+        Self {
+            properties: db.collection("properties"),
+            qr_generator,
+        }
+    }
+
+    /// Watch for changes in the background, reopening the stream where it left off after errors
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let mut resume_token = None;
+            loop {
+                if let Err(e) = self.watch(&mut resume_token).await {
+                    error!("Property change stream failed, reopening in {:?}: {}", RECONNECT_DELAY, e);
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    async fn watch(&self, resume_token: &mut Option<ResumeToken>) -> Result<(), mongodb::error::Error> {
+        let pipeline = [doc! { "$match": { "operationType": { "$in": ["update", "replace"] } } }];
+        let mut watch = self.properties.watch().pipeline(pipeline);
+        if let Some(resume_token) = resume_token.clone() {
+            watch = watch.start_after(resume_token);
+        }
+        let mut stream = watch.await?;
+        info!("Watching properties for QR-relevant changes");
+
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            self.handle(&event).await;
+            *resume_token = stream.resume_token();
+        }
+        Ok(())
+    }
+
+    async fn handle(&self, event: &ChangeStreamEvent<Document>) {
+        let Some(property_id) = event.document_key.as_ref().and_then(|key| key.get_object_id("_id").ok()) else {
+            return;
+        };
+        let property_id = property_id.to_hex();
+
+        let action = match event.operation_type {
+            OperationType::Update => event.update_description.as_ref()
+                .and_then(|update| PropertyChangeAction::for_update(&update.updated_fields)),
+            OperationType::Replace => event.full_document.as_ref().map(PropertyChangeAction::for_replace),
+            _ => None,
+        };
+
+        // Any edit can change the rendered listing
+        self.qr_generator.invalidate_property(&property_id);
+
+        match action {
+            Some(PropertyChangeAction::Deactivate) => self.deactivate(&property_id).await,
+            Some(PropertyChangeAction::Regenerate) => self.regenerate(&property_id).await,
+            None => {}
+        }
+    }
+
+    async fn deactivate(&self, property_id: &str) {
+        match self.qr_generator.deactivate_qr_code(property_id, Some(watcher_actor())).await {
+            Ok(true) => info!("Property {} removed, deactivated its QR code", property_id),
+            Ok(false) => {}
+            Err(e) => error!("Failed to deactivate QR code for removed property {}: {}", property_id, e),
+        }
+    }
+
+    /// Regenerate the property's code, if it has an active one
+    async fn regenerate(&self, property_id: &str) {
+        match self.qr_generator.get_qr_code(property_id).await {
+            Ok(qr_code) if qr_code.is_active => {}
+            _ => return,
+        }
+
+        match self.qr_generator
+            .generate_qr_code_with(property_id.to_string(), true, QrGenerationReason::PropertyUpdated, QrGenerationOptions {
+                actor: Some(watcher_actor()),
+                ..QrGenerationOptions::default()
+            })
+            .await
+        {
+            Ok(_) => info!("Property {} changed, regenerated its QR code", property_id),
+            Err(e) => warn!("Failed to regenerate QR code for changed property {}: {}", property_id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_actions() {
+        assert_eq!(
+            PropertyChangeAction::for_update(&doc! { "price": 12_000_000i64 }),
+            Some(PropertyChangeAction::Regenerate)
+        );
+        assert_eq!(
+            PropertyChangeAction::for_update(&doc! { "images.2": "https://cdn.example/3.jpg" }),
+            Some(PropertyChangeAction::Regenerate)
+        );
+        assert_eq!(
+            PropertyChangeAction::for_update(&doc! { "removed": true, "price": 1i64 }),
+            Some(PropertyChangeAction::Deactivate)
+        );
+        assert_eq!(PropertyChangeAction::for_update(&doc! { "removed": false }), None);
+        assert_eq!(PropertyChangeAction::for_update(&doc! { "clicks": 41, "popularityScore": 2.5 }), None);
+    }
+
+    #[test]
+    fn test_replace_actions() {
+        assert_eq!(PropertyChangeAction::for_replace(&doc! { "removed": true }), PropertyChangeAction::Deactivate);
+        assert_eq!(PropertyChangeAction::for_replace(&doc! { "price": 1i64 }), PropertyChangeAction::Regenerate);
+    }
+}
@@ -0,0 +1,107 @@
+// src/services/qr_export.rs
+
+use std::io::{Cursor, Write};
+
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::services::waitlist_service::csv_field;
+
+// One QR code in a print export; `image` is None when the stored PNG couldn't be fetched
+#[derive(Debug, Clone)]
+pub struct ExportedQrImage {
+    pub property_id: String,
+    pub property_name: String,
+    pub image: Option<Vec<u8>>,
+}
+
+/// File name for a code inside the archive, readable for whoever prints it:
+/// "garden-villa-kilimani-507f1f77bcf86cd799439011.png"
+pub fn export_file_name(property_name: &str, property_id: &str) -> String {
+    let slug = property_name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let property_id: String = property_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+
+    if slug.is_empty() {
+        format!("{}.png", property_id)
+    } else {
+        format!("{}-{}.png", slug, property_id)
+    }
+}
+
+/// Zip the PNGs with a manifest listing every requested code; codes without an image
+/// keep their manifest row with an empty file column so nothing goes missing unnoticed
+pub fn build_qr_archive(images: &[ExportedQrImage]) -> Result<Vec<u8>, ZipError> {
+    // PNGs are already compressed
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut manifest = String::from("propertyId,propertyName,file\r\n");
+
+    for exported in images {
+        let file = match &exported.image {
+            Some(image) => {
+                let file = export_file_name(&exported.property_name, &exported.property_id);
+                zip.start_file(file.as_str(), options)?;
+                zip.write_all(image)?;
+                file
+            }
+            None => String::new(),
+        };
+
+        manifest.push_str(&[
+            csv_field(&exported.property_id),
+            csv_field(&exported.property_name),
+            csv_field(&file),
+        ].join(","));
+        manifest.push_str("\r\n");
+    }
+
+    zip.start_file("manifest.csv", options.compression_method(CompressionMethod::Deflated))?;
+    zip.write_all(manifest.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(
+            export_file_name("Garden Villa, Kilimani (2BR)", "507f1f77bcf86cd799439011"),
+            "garden-villa-kilimani-2br-507f1f77bcf86cd799439011.png"
+        );
+        assert_eq!(export_file_name("  ", "507f1f77bcf86cd799439011"), "507f1f77bcf86cd799439011.png");
+        assert_eq!(export_file_name("Villa", "../etc"), "villa-etc.png");
+    }
+
+    #[test]
+    fn test_archive_lists_codes_without_images() {
+        let images = vec![
+            ExportedQrImage {
+                property_id: "507f1f77bcf86cd799439011".to_string(),
+                property_name: "Garden Villa".to_string(),
+                image: Some(vec![0x89, 0x50, 0x4E, 0x47]),
+            },
+            ExportedQrImage {
+                property_id: "507f1f77bcf86cd799439012".to_string(),
+                property_name: "Studio".to_string(),
+                image: None,
+            },
+        ];
+        let bytes = build_qr_archive(&images).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert!(archive.by_name("garden-villa-507f1f77bcf86cd799439011.png").is_ok());
+
+        let mut manifest = String::new();
+        archive.by_name("manifest.csv").unwrap().read_to_string(&mut manifest).unwrap();
+        assert!(manifest.contains("507f1f77bcf86cd799439012,Studio,\r\n"));
+    }
+}
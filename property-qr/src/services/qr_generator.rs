@@ -2,25 +2,115 @@
 // src/services/qr_generator.rs
 
 use crate::models::{
-    QrCodeMetadata, QrCodeData, QrMetadata, QrGenerationSettings, QrGenerationReason,
-    QrStatus, QrCodeResponse, BatchQrCodeResponse, QrGenerationError, PropertyQrInfo
+    QrCodeMetadata, QrCodeData, QrCodeWindow, QrMetadata, QrGenerationSettings, QrGenerationReason,
+    QrStatus, QrStorageState, QrDecodeReport, QrRenditionFormat, QR_RENDITION_SIZES, AssetStatus, AssetVerification, QrCodeResponse, BatchQrCodeResponse, QrGenerationError, PropertyQrInfo, QrPayload,
+    QrRegenerationJob, RegenerationJobStatus, StaleQrReport, SelfTestReport, SelfTestStep, EligibilityOverride,
+    UpdateQrRedirectRequest, QrCodePage, QrSortField, SortOrder, AuditAction, AuditActor,
+};
+use crate::config::QrPayloadMode;
+use crate::services::{
+    AuditService, PageCache, PropertyService, QrStyleService, StorageService,
+    property_service::PropertyError, qr_style_service::QrStyleError, object_storage::{FileMetadata, StorageError, StoredObject, UploadResult},
 };
-use crate::services::{PropertyService, S3Service};
 use mongodb::{
-    bson::{doc, oid::ObjectId}, 
-options::FindOptions, Collection, Database};
+    bson::{doc, oid::ObjectId, to_bson, Document}, 
+options::{FindOptions, IndexOptions}, Collection, Database, IndexModel};
 
-use chrono::Utc;
-use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Instant;
 use tracing::{info, warn, error};
 
+// Synthetic property the startup self-test generates a QR for; never stored in qr_metadata
+const SELF_TEST_PROPERTY_ID: &str = "5e1f7e570000000000000000";
+const PNG_SIGNATURE: [u8; 4] = [0x89, 0x50, 0x4E, 0x47];
+
+// Mongo filter for the QR code list; both conditions apply when given
+fn qr_list_filter(property_id: Option<&str>, active_only: bool) -> Document {
+    let mut filter = doc! {};
+    if let Some(property_id) = property_id {
+        filter.insert("propertyId", property_id);
+    }
+    if active_only {
+        filter.insert("isActive", true);
+    }
+    filter
+}
+
+// Sort for the QR code list, with _id as a tie-breaker so pages never overlap
+fn qr_list_sort(field: QrSortField, order: SortOrder) -> Document {
+    let direction = match order {
+        SortOrder::Asc => 1,
+        SortOrder::Desc => -1,
+    };
+    let field = match field {
+        QrSortField::GeneratedAt => "generatedAt",
+        QrSortField::ScanCount => "scanCount",
+    };
+    doc! { field: direction, "_id": direction }
+}
+
+/// Formats a designed QR image can be uploaded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadedImageFormat {
+    Png,
+    Svg,
+}
+
+impl UploadedImageFormat {
+    /// Sniff the format from the file's contents, whatever it was named
+    pub fn detect(image: &[u8]) -> Option<Self> {
+        if image.starts_with(&PNG_SIGNATURE) {
+            return Some(UploadedImageFormat::Png);
+        }
+        let head = String::from_utf8_lossy(&image[..image.len().min(1024)]);
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        if (head.starts_with("<?xml") || head.starts_with("<svg")) && head.contains("<svg") {
+            return Some(UploadedImageFormat::Svg);
+        }
+        None
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            UploadedImageFormat::Png => "png",
+            UploadedImageFormat::Svg => "svg",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            UploadedImageFormat::Png => "image/png",
+            UploadedImageFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+// Per-request choices for one generation; left unset, a regenerated code keeps what it has
+#[derive(Debug, Clone, Default)]
+pub struct QrGenerationOptions {
+    pub style: Option<String>,
+    pub payload: Option<QrPayload>,
+    pub eligibility_override: Option<String>, // Admin note; generates for ineligible listings and is audited
+    pub actor: Option<AuditActor>,            // Who asked, for the audit log; None for background jobs
+    pub generated_by: Option<ObjectId>,       // Authenticated DAO-Bitat user, recorded on the code
+    pub max_scans: Option<i64>,               // Makes the code limited-use, starting a fresh count
+    pub active_from: Option<DateTime<Utc>>,   // Setting either end replaces the scheduled window
+    pub active_until: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct QrGeneratorService {
     qr_metadata: Collection<QrCodeMetadata>,
+    regeneration_jobs: Collection<QrRegenerationJob>,
+    eligibility_overrides: Collection<EligibilityOverride>,
     property_service: PropertyService,
-    s3_service: S3Service,
+    storage: StorageService,
     settings: QrGenerationSettings,
     base_url: String,
+    page_cache: Option<PageCache>,
+    styles: Option<QrStyleService>,
+    audit: Option<AuditService>,
 }
 
 #[derive(Debug)]
@@ -29,8 +119,14 @@ pub enum QrGeneratorError {
     PropertyNotEligible(String),
     QrGenerationFailed(String),
     S3UploadFailed(String),
+    StorageError(String), // Any other S3 request
     DatabaseError(mongodb::error::Error),
     InvalidPropertyId,
+    InvalidRedirect(String),
+    JobNotFound,
+    StyleNotFound(String),
+    InvalidPayload(String),
+    DecodeFailed(String),
 }
 
 // Helper function to convert chrono DateTime to BSON DateTime
@@ -51,28 +147,64 @@ impl std::fmt::Display for QrGeneratorError {
             QrGeneratorError::PropertyNotEligible(reason) => write!(f, "Property not eligible: {}", reason),
             QrGeneratorError::QrGenerationFailed(reason) => write!(f, "QR generation failed: {}", reason),
             QrGeneratorError::S3UploadFailed(reason) => write!(f, "S3 upload failed: {}", reason),
+            QrGeneratorError::StorageError(reason) => write!(f, "S3 error: {}", reason),
             QrGeneratorError::DatabaseError(e) => write!(f, "Database error: {}", e),
             QrGeneratorError::InvalidPropertyId => write!(f, "Invalid property ID"),
+            QrGeneratorError::InvalidRedirect(reason) => write!(f, "Invalid custom redirect: {}", reason),
+            QrGeneratorError::JobNotFound => write!(f, "Regeneration job not found"),
+            QrGeneratorError::StyleNotFound(name) => write!(f, "QR style '{}' not found", name),
+            QrGeneratorError::InvalidPayload(reason) => write!(f, "Invalid QR payload: {}", reason),
+            QrGeneratorError::DecodeFailed(reason) => write!(f, "QR decoding failed: {}", reason),
         }
     }
 }
 
 impl std::error::Error for QrGeneratorError {}
 
+// What one asset verification pass found
+#[derive(Debug, Default, PartialEq)]
+pub struct AssetVerificationReport {
+    pub checked: usize,
+    pub intact: usize,
+    pub unrecorded: usize,  // Present, with their size and ETag recorded for next time
+    pub regenerated: usize, // Missing or corrupted
+    pub failed: usize,
+}
+
+// What one storage reconciliation pass repaired
+#[derive(Debug, Default, PartialEq)]
+pub struct StorageReconcileReport {
+    pub reuploaded: usize,      // Pending codes whose image was uploaded and that were activated
+    pub orphans_deleted: usize, // Images no code refers to
+    pub failed: usize,
+}
+
+// What one pass over scheduled windows changed
+#[derive(Debug, Default, PartialEq)]
+pub struct ScheduleReport {
+    pub activated: u64,
+    pub deactivated: u64,
+}
+
 impl QrGeneratorService {
     /// Create a new QR generator service
     pub fn new(
         db: &Database,
         property_service: PropertyService,
-        s3_service: S3Service,
+        storage: StorageService,
         base_url: String,
     ) -> Self {
         Self {
             qr_metadata: db.collection("qr_metadata"),
+            regeneration_jobs: db.collection("qr_regeneration_jobs"),
+            eligibility_overrides: db.collection("eligibility_overrides"),
             property_service,
-            s3_service,
+            storage,
             settings: QrGenerationSettings::default(),
             base_url,
+            page_cache: None,
+            styles: None,
+            audit: None,
         }
     }
 
@@ -80,16 +212,86 @@ impl QrGeneratorService {
     pub fn with_settings(
         db: &Database,
         property_service: PropertyService,
-        s3_service: S3Service,
+        storage: StorageService,
         base_url: String,
         settings: QrGenerationSettings,
     ) -> Self {
         Self {
             qr_metadata: db.collection("qr_metadata"),
+            regeneration_jobs: db.collection("qr_regeneration_jobs"),
+            eligibility_overrides: db.collection("eligibility_overrides"),
             property_service,
-            s3_service,
+            storage,
             settings,
             base_url,
+            page_cache: None,
+            styles: None,
+            audit: None,
+        }
+    }
+
+    /// Drop a property's rendered landing pages whenever its QR code changes
+    pub fn with_page_cache(mut self, page_cache: PageCache) -> Self {
+        self.page_cache = Some(page_cache);
+        self
+    }
+
+    /// Choose what new codes encode; existing codes keep their payload until regenerated
+    pub fn with_payload_mode(mut self, payload_mode: QrPayloadMode) -> Self {
+        self.settings.payload_mode = payload_mode;
+        self
+    }
+
+    /// Look up named QR styles when generating
+    pub fn with_styles(mut self, styles: QrStyleService) -> Self {
+        self.styles = Some(styles);
+        self
+    }
+
+    /// Record generations, regenerations, deletions and deactivations in the audit log
+    pub fn with_audit_log(mut self, audit: AuditService) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Image and color settings QR codes are generated with
+    pub fn settings(&self) -> &QrGenerationSettings {
+        &self.settings
+    }
+
+    /// Text index behind QR code search, over the listing name and location, and the
+    /// eligibility override audit trail by property
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.qr_metadata
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "metadata.propertyName": "text", "metadata.location": "text" })
+                    .options(IndexOptions::builder().name("qr_metadata_text".to_string()).build())
+                    .build(),
+            )
+            .await?;
+        self.eligibility_overrides
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": -1 }).build())
+            .await?;
+        // Storage reconciliation looks up codes left pending
+        self.qr_metadata
+            .create_index(IndexModel::builder().keys(doc! { "storageState": 1, "lastUpdated": 1 }).build())
+            .await?;
+        // The schedule job looks up codes whose window has opened or ended
+        self.qr_metadata
+            .create_index(IndexModel::builder().keys(doc! { "awaitingActivation": 1, "activeFrom": 1 }).build())
+            .await?;
+        self.qr_metadata
+            .create_index(IndexModel::builder().keys(doc! { "isActive": 1, "activeUntil": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Forget everything cached about a property, so the next scan renders from fresh data
+    pub fn invalidate_property(&self, property_id: &str) {
+        self.property_service.invalidate_listing(property_id);
+        if let Some(page_cache) = &self.page_cache {
+            page_cache.invalidate(property_id);
         }
     }
 
@@ -99,18 +301,38 @@ impl QrGeneratorService {
         property_id: String,
         force_regenerate: bool,
         reason: QrGenerationReason,
+    ) -> Result<QrCodeResponse, QrGeneratorError> {
+        self.generate_qr_code_with(property_id, force_regenerate, reason, QrGenerationOptions::default()).await
+    }
+
+    /// Generate QR code for a single property with a named style, an alternative payload or an
+    /// eligibility override; without them, a regenerated code keeps its style and payload
+    pub async fn generate_qr_code_with(
+        &self,
+        property_id: String,
+        force_regenerate: bool,
+        reason: QrGenerationReason,
+        options: QrGenerationOptions,
     ) -> Result<QrCodeResponse, QrGeneratorError> {
         let start_time = std::time::Instant::now();
+        let QrGenerationOptions { style, payload, eligibility_override, actor, generated_by, max_scans, active_from, active_until } = options;
+
+        if let Some(payload) = &payload {
+            payload.validate().map_err(QrGeneratorError::InvalidPayload)?;
+        }
 
         // Check if QR already exists and force_regenerate is false
         if !force_regenerate {
             if let Ok(existing_qr) = self.get_existing_qr(&property_id).await {
-                if existing_qr.is_active {
+                // Codes waiting for their scheduled window count as active
+                if existing_qr.is_active || existing_qr.awaiting_activation {
                     return Ok(QrCodeResponse {
                         property_id: property_id.clone(),
-                        qr_code_url: existing_qr.qr_code_url,
-                        scan_url: format!("{}/scan/{}", self.base_url, property_id),
+                        scan_url: existing_qr.encoded_scan_url()
+                            .unwrap_or_else(|| self.scan_url(&property_id)),
+                        qr_code_url: self.storage.serving_url(&existing_qr.qr_code_url),
                         generated_at: existing_qr.generated_at,
+                        renditions: rendition_urls(&existing_qr, &self.storage),
                         metadata: existing_qr.metadata,
                         status: QrStatus::Exists,
                     });
@@ -118,61 +340,154 @@ impl QrGeneratorService {
             }
         }
 
-        // Get property information
-        let property_info = self.property_service
-            .get_property_qr_info(&property_id)
-            .await
-            .map_err(|e| match e {
-                crate::services::property_service::PropertyError::NotFound => QrGeneratorError::PropertyNotFound,
-                crate::services::property_service::PropertyError::NotEligibleForQr(reason) => QrGeneratorError::PropertyNotEligible(reason),
-                crate::services::property_service::PropertyError::InvalidId => QrGeneratorError::InvalidPropertyId,
-                crate::services::property_service::PropertyError::DatabaseError(db_err) => QrGeneratorError::DatabaseError(db_err),
-            })?;
+        // Get property information; with an override, ineligible listings get a code too
+        let (property_info, ineligible_reason) = match self.property_service.get_property_qr_info(&property_id).await {
+            Err(PropertyError::NotEligibleForQr(ineligible_reason)) if eligibility_override.is_some() => {
+                let property = self.property_service
+                    .get_property_by_id(&property_id)
+                    .await
+                    .map_err(property_error)?;
+                (property.to_qr_info(), Some(ineligible_reason))
+            }
+            result => (result.map_err(property_error)?, None),
+        };
+
+        // Regenerating bumps the version, which is encoded in the new scan URL
+        let existing = match force_regenerate {
+            true => self.get_existing_qr(&property_id).await.ok(),
+            false => None,
+        };
+        let qr_version = existing.as_ref().map_or(1, |qr| qr.qr_version + 1);
+
+        // Without force, an inactive code is replaced; the audit log still wants it as "before"
+        let before = match (&existing, &self.audit) {
+            (Some(existing), _) => Some(existing.clone()),
+            (None, Some(_)) => self.get_existing_qr(&property_id).await.ok(),
+            (None, None) => None,
+        };
+
+        let (style, settings) = match style {
+            Some(name) => (Some(name.clone()), self.style_settings(&name).await?),
+            None => match existing.as_ref().and_then(|qr| qr.style.clone()) {
+                Some(name) => match self.style_settings(&name).await {
+                    Ok(settings) => (Some(name), settings),
+                    Err(QrGeneratorError::StyleNotFound(_)) => {
+                        warn!("QR style '{}' of property {} is gone, regenerating with defaults", name, property_id);
+                        (None, self.settings.clone())
+                    }
+                    Err(e) => return Err(e),
+                },
+                None => (None, self.settings.clone()),
+            },
+        };
+
+        let payload = payload
+            .or_else(|| existing.as_ref().map(|qr| qr.payload.clone()))
+            .unwrap_or_default();
 
         // Create QR code data
-        let qr_data = QrCodeData::new(property_id.clone(), &self.base_url);
-        let qr_json = qr_data.to_json_string()
-            .map_err(|e| QrGeneratorError::QrGenerationFailed(e.to_string()))?;
+        let qr_data = QrCodeData::new(property_id.clone(), &self.base_url, qr_version);
+        let qr_payload = encode_payload(&payload, &qr_data, &property_info, &settings)?;
 
         // Generate QR code image
-        let qr_image_data = self.generate_qr_image(&qr_json).await?;
+        let qr_image_data = self.generate_qr_image(&qr_payload, &settings).await?;
 
-        // Upload to S3
-        let s3_key = format!("qr-images/{}.png", property_id);
-        let qr_code_url = self.s3_service
-            .upload_qr_image(&s3_key, qr_image_data)
-            .await
-            .map_err(|e| QrGeneratorError::S3UploadFailed(e.to_string()))?;
+        // A regenerated image gets a new key, as CDN caches would keep serving the old one
+        let s3_key = QrCodeMetadata::image_key_for(&property_id, qr_version);
+        let previous = existing.as_ref().map(|qr| (qr.get_s3_key(), qr.rendition_keys()));
+        let qr_code_url = self.storage.get_public_url(&s3_key);
 
-        // Create metadata
-        let metadata = QrMetadata {
-            property_name: property_info.property_name,
-            location: property_info.location,
-            action: property_info.action,
-            price: property_info.price,
-            onchain_id: property_info.onchain_id,
-            crypto_accepted: property_info.crypto_accepted,
-            primary_image: property_info.images.first().cloned(),
-            is_verified: property_info.is_verified.unwrap_or(false),
-            generated_by: None, // TODO: Add user context
-            generation_reason: reason.clone(),
-        };
+        // Create metadata; regenerated without a user (e.g. by a background job), a code keeps
+        // the attribution it had
+        let generated_by = generated_by.or_else(|| existing.as_ref().and_then(|qr| qr.metadata.generated_by));
+        let metadata = QrMetadata { generated_by, ..listing_metadata(property_info, reason.clone()) };
 
         // Create QR metadata record
-        let qr_metadata = if force_regenerate {
+        let qr_metadata = match existing {
             // Update existing QR
-            let mut existing = self.get_existing_qr(&property_id).await
-                .unwrap_or_else(|_| QrCodeMetadata::new(property_id.clone(), qr_json.clone(), qr_code_url.clone(), metadata.clone()));
-            existing.regenerate(qr_json, qr_code_url.clone());
-            existing.metadata = metadata.clone();
-            existing
-        } else {
+            Some(mut existing) => {
+                existing.regenerate(qr_payload, qr_code_url.clone());
+                existing.metadata = metadata.clone();
+                existing
+            }
             // Create new QR
-            QrCodeMetadata::new(property_id.clone(), qr_json, qr_code_url.clone(), metadata.clone())
+            None => QrCodeMetadata::new(property_id.clone(), qr_payload, qr_code_url.clone(), metadata.clone()),
+        };
+        // Codes regenerated without a limit keep the one they had, and the scans already used
+        let (max_scans, limited_scans) = match max_scans {
+            Some(max_scans) => (Some(max_scans), 0),
+            None => (qr_metadata.max_scans, qr_metadata.limited_scans),
+        };
+        // Likewise the scheduled window
+        let (active_from, active_until) = match (active_from, active_until) {
+            (None, None) => (qr_metadata.active_from, qr_metadata.active_until),
+            window => window,
+        };
+        let qr_metadata = QrCodeMetadata {
+            style,
+            payload,
+            max_scans,
+            limited_scans,
+            active_from,
+            active_until,
+            storage_state: QrStorageState::Pending,
+            image_key: Some(s3_key.clone()),
+            rendition_sizes: QR_RENDITION_SIZES.to_vec(),
+            custom_image: false,
+            ..qr_metadata
+        };
+        // A code scheduled to open later stays inactive until then, and one whose window has
+        // passed stays inactive
+        let window = qr_metadata.window(Utc::now());
+        let qr_metadata = QrCodeMetadata {
+            is_active: window == QrCodeWindow::Open,
+            awaiting_activation: matches!(window, QrCodeWindow::NotStarted(_)),
+            ..qr_metadata
         };
 
-        // Save to database
+        // Save as pending, upload, then activate; storage reconciliation finishes the upload
+        // if anything in between fails
         self.upsert_qr_metadata(&qr_metadata).await?;
+        self.invalidate_property(&property_id);
+        let upload = self.upload_images(&qr_metadata, qr_image_data, &settings)
+            .await
+            .inspect_err(|e| {
+                warn!("QR image upload for property {} failed, left pending for reconciliation: {}", property_id, e);
+            })?;
+        self.activate_storage(&qr_metadata, &upload).await?;
+        let qr_metadata = QrCodeMetadata {
+            storage_state: QrStorageState::Active,
+            image_size: Some(upload.size as i64),
+            image_etag: Some(upload.etag),
+            ..qr_metadata
+        };
+
+        // The replaced image and its renditions are no longer referenced; reconciliation
+        // removes them if this fails
+        if let Some((previous_key, previous_renditions)) = previous.filter(|(key, _)| *key != s3_key) {
+            if let Err(e) = self.storage.delete_qr_image(&previous_key).await {
+                warn!("Failed to delete replaced QR image {}: {}", previous_key, e);
+            }
+            self.delete_renditions(previous_renditions).await;
+        }
+
+        let action = if force_regenerate { AuditAction::Regenerate } else { AuditAction::Generate };
+        self.audit(action, &property_id, actor, before, Some(qr_metadata.clone())).await;
+
+        if let (Some(ineligible_reason), Some(note)) = (ineligible_reason, eligibility_override) {
+            warn!("Generated QR code for ineligible property {} ({}): {}", property_id, ineligible_reason, note);
+            self.eligibility_overrides
+                .insert_one(EligibilityOverride {
+                    id: ObjectId::new(),
+                    property_id: property_id.clone(),
+                    ineligible_reason,
+                    note,
+                    generation_reason: reason.clone(),
+                    qr_version: qr_metadata.qr_version,
+                    created_at: Utc::now(),
+                })
+                .await?;
+        }
 
         let generation_time = start_time.elapsed();
         info!(
@@ -182,21 +497,363 @@ impl QrGeneratorService {
         );
 
         Ok(QrCodeResponse {
+            renditions: rendition_urls(&qr_metadata, &self.storage),
             property_id,
             qr_code_url,
-            scan_url: format!("{}/scan/{}", self.base_url, qr_metadata.property_id),
+            scan_url: qr_metadata.encoded_scan_url()
+                .unwrap_or_else(|| self.scan_url(&qr_metadata.property_id)),
             generated_at: qr_metadata.generated_at,
             metadata,
             status: if force_regenerate { QrStatus::Regenerated } else { QrStatus::Generated },
         })
     }
 
+    /// Draw a code for something other than a listing with the default settings and upload
+    /// it to `key`; returns the image's public URL
+    pub async fn upload_standalone_qr(&self, key: &str, payload: &str) -> Result<String, QrGeneratorError> {
+        let image = self.generate_qr_image(payload, &self.settings).await?;
+        self.storage
+            .upload_qr_image(key, image)
+            .await
+            .map_err(|e| QrGeneratorError::S3UploadFailed(e.to_string()))?;
+        Ok(self.storage.get_public_url(key))
+    }
+
+    /// Delete an image uploaded with `upload_standalone_qr`
+    pub async fn delete_standalone_qr(&self, key: &str) -> Result<(), QrGeneratorError> {
+        self.storage
+            .delete_qr_image(key)
+            .await
+            .map(|_| ())
+            .map_err(|e| QrGeneratorError::StorageError(e.to_string()))
+    }
+
+    /// Finish uploads for codes left pending longer than `grace`, and delete images no code
+    /// refers to. Images must be older than `grace` too, so in-flight generations are left alone.
+    pub async fn reconcile_storage(&self, grace: Duration) -> Result<StorageReconcileReport, QrGeneratorError> {
+        let cutoff = Utc::now() - grace;
+        let mut report = StorageReconcileReport::default();
+
+        let mut cursor = self.qr_metadata
+            .find(doc! {
+                "storageState": to_bson(&QrStorageState::Pending).map_err(mongodb::error::Error::from)?,
+                "lastUpdated": { "$lt": to_bson(&cutoff).map_err(mongodb::error::Error::from)? }
+            })
+            .await?;
+        while cursor.advance().await? {
+            let qr_code = cursor.deserialize_current()?;
+            match self.reupload(&qr_code).await {
+                Ok(_) => report.reuploaded += 1,
+                Err(e) => {
+                    warn!("Failed to re-upload QR image of property {}: {}", qr_code.property_id, e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        let mut objects = Vec::new();
+        for prefix in ["qr-images/", "qr-renditions/"] {
+            objects.extend(
+                self.storage
+                    .list_files_with_prefix(prefix, None)
+                    .await
+                    .map_err(|e| QrGeneratorError::StorageError(e.to_string()))?,
+            );
+        }
+        let known = self.current_image_keys().await?;
+        let orphans = orphaned_image_keys(&objects, &known, cutoff);
+        if !orphans.is_empty() {
+            let deleted = self.storage
+                .delete_multiple_files(orphans.clone())
+                .await
+                .map_err(|e| QrGeneratorError::StorageError(e.to_string()))?;
+            report.failed += orphans.len() - deleted.len();
+            report.orphans_deleted = deleted.len();
+        }
+
+        if report != StorageReconcileReport::default() {
+            info!(
+                "QR storage reconciliation: {} re-uploaded, {} orphaned images deleted, {} failed",
+                report.reuploaded, report.orphans_deleted, report.failed
+            );
+        }
+        Ok(report)
+    }
+
+    /// Redraw a code's image from its stored pattern and style, upload it and activate it
+    async fn reupload(&self, qr_code: &QrCodeMetadata) -> Result<UploadResult, QrGeneratorError> {
+        if qr_code.custom_image {
+            return Err(QrGeneratorError::StorageError(format!(
+                "{} is an uploaded design and can't be redrawn; upload it again",
+                qr_code.get_s3_key()
+            )));
+        }
+
+        let settings = match &qr_code.style {
+            Some(name) => match self.style_settings(name).await {
+                Ok(settings) => settings,
+                Err(QrGeneratorError::StyleNotFound(_)) => self.settings.clone(),
+                Err(e) => return Err(e),
+            },
+            None => self.settings.clone(),
+        };
+
+        let image = self.generate_qr_image(&qr_code.qr_pattern, &settings).await?;
+        let upload = self.upload_images(qr_code, image, &settings).await?;
+        self.activate_storage(qr_code, &upload).await?;
+        self.invalidate_property(&qr_code.property_id);
+        Ok(upload)
+    }
+
+    /// Upload a code's image along with every rendition of it; the image's upload is returned
+    async fn upload_images(
+        &self,
+        qr_code: &QrCodeMetadata,
+        image: Vec<u8>,
+        settings: &QrGenerationSettings,
+    ) -> Result<UploadResult, QrGeneratorError> {
+        let image_key = qr_code.get_s3_key();
+        let renditions = self.generate_renditions(&qr_code.qr_pattern, settings).await?;
+        let rendition_uploads = renditions.into_iter().map(|(size, format, data)| {
+            let key = QrCodeMetadata::rendition_key(&image_key, size, format);
+            async move { self.storage.upload_qr_asset(&key, data, format.content_type()).await }
+        });
+
+        let (upload, renditions) = tokio::join!(
+            self.storage.upload_qr_image(&image_key, image),
+            futures::future::try_join_all(rendition_uploads),
+        );
+        renditions.map_err(|e| QrGeneratorError::S3UploadFailed(e.to_string()))?;
+        upload.map_err(|e| QrGeneratorError::S3UploadFailed(e.to_string()))
+    }
+
+    /// Delete renditions best-effort; any left behind are orphans reconciliation removes
+    async fn delete_renditions(&self, keys: Vec<String>) {
+        let count = keys.len();
+        match self.storage.delete_multiple_files(keys).await {
+            Ok(deleted) if deleted.len() < count => {
+                warn!("Deleted {} of {} QR renditions", deleted.len(), count);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to delete QR renditions: {}", e),
+        }
+    }
+
+    /// HEAD a code's image and compare it with the upload; a missing or corrupted image is
+    /// redrawn from the stored pattern and uploaded again
+    pub async fn verify_asset(&self, property_id: &str) -> Result<AssetVerification, QrGeneratorError> {
+        let qr_code = self.get_existing_qr(property_id).await?;
+        let s3_key = qr_code.get_s3_key();
+        let head = self.storage
+            .get_file_metadata(&s3_key)
+            .await
+            .map_err(|e| QrGeneratorError::StorageError(e.to_string()))?;
+
+        let status = asset_status(&qr_code, head.as_ref());
+        let (size, etag, regenerated) = match (status, head) {
+            (AssetStatus::Missing | AssetStatus::Corrupted, _) => {
+                warn!("QR image {} is {:?}, regenerating it", s3_key, status);
+                let upload = self.reupload(&qr_code).await?;
+                (Some(upload.size as i64), Some(upload.etag), true)
+            }
+            (AssetStatus::Unrecorded, Some(head)) => {
+                self.qr_metadata
+                    .update_one(
+                        doc! { "propertyId": property_id, "qrCodeHash": &qr_code.qr_code_hash },
+                        doc! { "$set": { "imageSize": head.size, "imageEtag": &head.etag } },
+                    )
+                    .await?;
+                (Some(head.size), Some(head.etag), false)
+            }
+            (_, head) => (head.as_ref().map(|head| head.size), head.map(|head| head.etag), false),
+        };
+
+        Ok(AssetVerification { property_id: property_id.to_string(), s3_key, status, regenerated, size, etag })
+    }
+
+    /// Replace a code's image with one designed elsewhere. The image must decode to the
+    /// property's scan URL; it's stored under the next version's key and its payload becomes
+    /// the code's pattern.
+    pub async fn replace_qr_image(
+        &self,
+        property_id: &str,
+        image: Vec<u8>,
+        format: UploadedImageFormat,
+        actor: Option<AuditActor>,
+    ) -> Result<QrCodeMetadata, QrGeneratorError> {
+        let existing = self.get_existing_qr(property_id).await?;
+
+        let decoded = decode_uploaded_image(&image, format)?;
+        check_uploaded_scan_url(&decoded, &self.scan_url(property_id))?;
+
+        // Uploaded before the record points at it; if saving the record fails, the image is an
+        // orphan reconciliation removes
+        let s3_key = QrCodeMetadata::image_key_with_extension(property_id, existing.qr_version + 1, format.extension());
+        let upload = self.storage
+            .upload_qr_asset(&s3_key, image, format.content_type())
+            .await
+            .map_err(|e| QrGeneratorError::S3UploadFailed(e.to_string()))?;
+
+        // A designed contact card stays one; anything else decoding to the scan URL is a scan code
+        let payload = match &existing.payload {
+            QrPayload::Vcard(_) if QrPayload::vcard_scan_url(&decoded).is_some() => existing.payload.clone(),
+            _ => QrPayload::Scan,
+        };
+        let mut qr_code = existing.clone();
+        qr_code.regenerate(decoded, upload.url.clone());
+        let qr_code = QrCodeMetadata {
+            payload,
+            style: None,
+            storage_state: QrStorageState::Active,
+            image_size: Some(upload.size as i64),
+            image_etag: Some(upload.etag),
+            image_key: Some(s3_key.clone()),
+            rendition_sizes: Vec::new(),
+            custom_image: true,
+            ..qr_code
+        };
+        self.upsert_qr_metadata(&qr_code).await?;
+        self.invalidate_property(property_id);
+
+        let previous_key = existing.get_s3_key();
+        if previous_key != s3_key {
+            if let Err(e) = self.storage.delete_qr_image(&previous_key).await {
+                warn!("Failed to delete replaced QR image {}: {}", previous_key, e);
+            }
+        }
+        self.delete_renditions(existing.rendition_keys()).await;
+
+        info!("Replaced QR image of property {} with an uploaded {} (version {})", property_id, format.extension(), qr_code.qr_version);
+        self.audit(AuditAction::Upload, property_id, actor, Some(existing), Some(qr_code.clone())).await;
+        Ok(self.served(qr_code))
+    }
+
+    /// Decode a photo of a QR code and check what it points at against the code on record
+    pub async fn inspect_qr_image(&self, image: &[u8]) -> Result<QrDecodeReport, QrGeneratorError> {
+        let decoded_text = decode_qr_image(image)?;
+        self.inspect_decoded(decoded_text).await
+    }
+
+    /// Check a decoded payload: the property it points at, the version it was printed as and
+    /// whether the stored image still matches. Nothing is repaired.
+    pub async fn inspect_decoded(&self, decoded_text: String) -> Result<QrDecodeReport, QrGeneratorError> {
+        let Some((scan_url, property_id, encoded_version)) = scan_target(&decoded_text) else {
+            return Ok(QrDecodeReport { decoded_text, ..Default::default() });
+        };
+
+        let known_property = self.property_service.get_property_by_id(&property_id).await.is_ok();
+        let qr_code = match self.get_existing_qr(&property_id).await {
+            Ok(qr_code) => Some(qr_code),
+            Err(QrGeneratorError::PropertyNotFound) => None,
+            Err(e) => return Err(e),
+        };
+
+        let asset_status = match &qr_code {
+            Some(qr_code) => {
+                let head = self.storage
+                    .get_file_metadata(&qr_code.get_s3_key())
+                    .await
+                    .map_err(|e| QrGeneratorError::StorageError(e.to_string()))?;
+                Some(asset_status(qr_code, head.as_ref()))
+            }
+            None => None,
+        };
+
+        Ok(QrDecodeReport {
+            is_current: qr_code.as_ref().is_some_and(|qr_code| qr_code.qr_pattern == decoded_text),
+            decoded_text,
+            scan_url: Some(scan_url),
+            property_id: Some(property_id),
+            known_property,
+            has_qr_code: qr_code.is_some(),
+            encoded_version,
+            current_version: qr_code.as_ref().map(|qr_code| qr_code.qr_version),
+            is_active: qr_code.as_ref().map(|qr_code| qr_code.is_active),
+            asset_status,
+        })
+    }
+
+    /// Verify every code's image, e.g. after a bucket migration
+    pub async fn verify_assets(&self) -> Result<AssetVerificationReport, QrGeneratorError> {
+        let mut report = AssetVerificationReport::default();
+
+        for property_id in self.get_all_qr_property_ids().await? {
+            report.checked += 1;
+            match self.verify_asset(&property_id).await {
+                Ok(verification) if verification.regenerated => report.regenerated += 1,
+                Ok(verification) if verification.status == AssetStatus::Unrecorded => report.unrecorded += 1,
+                Ok(_) => report.intact += 1,
+                // Deleted since the IDs were read
+                Err(QrGeneratorError::PropertyNotFound) => report.checked -= 1,
+                Err(e) => {
+                    warn!("Failed to verify QR image of property {}: {}", property_id, e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "QR asset verification: {} checked, {} intact, {} recorded, {} regenerated, {} failed",
+            report.checked, report.intact, report.unrecorded, report.regenerated, report.failed
+        );
+        Ok(report)
+    }
+
+    /// Verify every code's image in the background
+    pub fn spawn_asset_verification(&self, interval: std::time::Duration) {
+        let qr_generator = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = qr_generator.verify_assets().await {
+                    error!("QR asset verification failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Reconcile QR records with S3 in the background
+    pub fn spawn_storage_reconciliation(&self, interval: std::time::Duration, grace: Duration) {
+        let qr_generator = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = qr_generator.reconcile_storage(grace).await {
+                    error!("QR storage reconciliation failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Generation settings with a named style's look applied
+    async fn style_settings(&self, name: &str) -> Result<QrGenerationSettings, QrGeneratorError> {
+        let Some(styles) = &self.styles else {
+            return Err(QrGeneratorError::StyleNotFound(name.to_string()));
+        };
+
+        match styles.get_style(name).await {
+            Ok(style) => Ok(style.settings(&self.settings)),
+            Err(QrStyleError::NotFound | QrStyleError::InvalidStyle(_)) => Err(QrGeneratorError::StyleNotFound(name.to_string())),
+            Err(QrStyleError::DatabaseError(e)) => Err(QrGeneratorError::DatabaseError(e)),
+        }
+    }
+
+    /// Public scan URL for a property, without the QR version its printed codes carry
+    pub fn scan_url(&self, property_id: &str) -> String {
+        format!("{}/scan/{}", self.base_url, property_id)
+    }
+
     /// Generate QR codes for multiple properties
     pub async fn batch_generate_qr_codes(
         &self,
         property_ids: Vec<String>,
         force_regenerate: bool,
         reason: QrGenerationReason,
+        options: QrGenerationOptions, // Applied to every property
     ) -> Result<BatchQrCodeResponse, QrGeneratorError> {
         let mut successful = Vec::new();
         let mut failed = Vec::new();
@@ -206,7 +863,7 @@ impl QrGeneratorService {
 
         // Process each property
         for property_id in property_ids {
-            match self.generate_qr_code(property_id.clone(), force_regenerate, reason.clone()).await {
+            match self.generate_qr_code_with(property_id.clone(), force_regenerate, reason.clone(), options.clone()).await {
                 Ok(qr_response) => {
                     successful.push(qr_response);
                 }
@@ -216,8 +873,14 @@ impl QrGeneratorService {
                         QrGeneratorError::PropertyNotEligible(_) => "PROPERTY_NOT_ELIGIBLE",
                         QrGeneratorError::QrGenerationFailed(_) => "QR_GENERATION_FAILED",
                         QrGeneratorError::S3UploadFailed(_) => "S3_UPLOAD_FAILED",
+                        QrGeneratorError::StorageError(_) => "STORAGE_ERROR",
                         QrGeneratorError::DatabaseError(_) => "DATABASE_ERROR",
                         QrGeneratorError::InvalidPropertyId => "INVALID_PROPERTY_ID",
+                        QrGeneratorError::InvalidRedirect(_) => "INVALID_REDIRECT",
+                        QrGeneratorError::JobNotFound => "JOB_NOT_FOUND",
+                        QrGeneratorError::InvalidPayload(_) => "INVALID_PAYLOAD",
+                        QrGeneratorError::StyleNotFound(_) => "STYLE_NOT_FOUND",
+                        QrGeneratorError::DecodeFailed(_) => "DECODE_FAILED",
                     };
 
                     failed.push(QrGenerationError {
@@ -249,33 +912,209 @@ impl QrGeneratorService {
 
     /// Get existing QR code for a property
     pub async fn get_qr_code(&self, property_id: &str) -> Result<QrCodeMetadata, QrGeneratorError> {
-        self.get_existing_qr(property_id).await
+        self.get_existing_qr(property_id).await.map(|qr_code| self.served(qr_code))
+    }
+
+    // A code as handed out, with its image URL on whichever bucket is being served from
+    fn served(&self, qr_code: QrCodeMetadata) -> QrCodeMetadata {
+        let qr_code_url = self.storage.serving_url(&qr_code.qr_code_url);
+        QrCodeMetadata { qr_code_url, ..qr_code }
+    }
+
+    /// Live listing behind a QR code, for rendering; None if the property can't be loaded
+    pub async fn live_listing(&self, property_id: &str) -> Option<PropertyQrInfo> {
+        self.property_service.get_live_listing(property_id).await.ok()
     }
 
     /// Delete QR code for a property
-    pub async fn delete_qr_code(&self, property_id: &str) -> Result<bool, QrGeneratorError> {
-        // Get existing QR to get S3 key
-        if let Ok(existing_qr) = self.get_existing_qr(property_id).await {
-            // Delete from S3
+    pub async fn delete_qr_code(&self, property_id: &str, actor: Option<AuditActor>) -> Result<bool, QrGeneratorError> {
+        let existing = self.get_existing_qr(property_id).await.ok();
+
+        // Delete from database first; an image left behind is an orphan reconciliation removes
+        let result = self.qr_metadata
+        .delete_one(doc! { "propertyId": property_id })
+            .await?;
+
+        if let Some(existing_qr) = &existing {
             let s3_key = existing_qr.get_s3_key();
-            if let Err(e) = self.s3_service.delete_qr_image(&s3_key).await {
+            if let Err(e) = self.storage.delete_qr_image(&s3_key).await {
                 warn!("Failed to delete QR image from S3: {}", e);
             }
+            self.delete_renditions(existing_qr.rendition_keys()).await;
+        }
+
+        if result.deleted_count > 0 {
+            self.audit(AuditAction::Delete, property_id, actor, existing, None).await;
+        }
+        Ok(result.deleted_count > 0)
+    }
+
+    /// Point a property's QR code somewhere other than its property page, or clear that redirect
+    pub async fn set_custom_redirect(
+        &self,
+        property_id: &str,
+        request: UpdateQrRedirectRequest,
+    ) -> Result<QrCodeMetadata, QrGeneratorError> {
+        if let Some(url) = &request.custom_redirect_url {
+            crate::utils::validate_url(url, "customRedirectUrl")
+                .map_err(|e| QrGeneratorError::InvalidRedirect(e.message))?;
         }
+        if request.until.is_some_and(|until| until <= Utc::now()) {
+            return Err(QrGeneratorError::InvalidRedirect("until must be in the future".to_string()));
+        }
+
+        let mut qr_code = self.get_existing_qr(property_id).await?;
+        qr_code.custom_redirect_until = request.custom_redirect_url.as_ref().and(request.until);
+        qr_code.custom_redirect_url = request.custom_redirect_url;
+        qr_code.last_updated = Utc::now();
+        self.upsert_qr_metadata(&qr_code).await?;
+        self.invalidate_property(property_id);
+
+        match &qr_code.custom_redirect_url {
+            Some(url) => info!("QR code for property {} now redirects to {}", property_id, url),
+            None => info!("Cleared custom redirect for property {}", property_id),
+        }
+        Ok(qr_code)
+    }
+
+    /// Re-read the listing into a code's metadata snapshot, keeping its image, payload and
+    /// version. For name and price edits, which don't change the scan URL the code encodes.
+    pub async fn refresh_metadata(&self, property_id: &str) -> Result<QrCodeMetadata, QrGeneratorError> {
+        let mut qr_code = self.get_existing_qr(property_id).await?;
+        // Mirrors the listing as it is now, even if it has since become ineligible
+        let property_info = self.property_service
+            .get_property_by_id(property_id)
+            .await
+            .map_err(property_error)?
+            .to_qr_info();
+
+        let metadata = QrMetadata {
+            generated_by: qr_code.metadata.generated_by,
+            ..listing_metadata(property_info, qr_code.metadata.generation_reason.clone())
+        };
+        let now = Utc::now();
+        self.qr_metadata
+            .update_one(
+                doc! { "propertyId": property_id },
+                doc! { "$set": { "metadata": to_bson(&metadata).map_err(mongodb::error::Error::from)?, "lastUpdated": utc_to_bson(now) } },
+            )
+            .await?;
+        self.invalidate_property(property_id);
+
+        info!("Refreshed QR metadata for property {}", property_id);
+        qr_code.metadata = metadata;
+        qr_code.last_updated = now;
+        Ok(qr_code)
+    }
+
+    /// Count a scan on the QR code's own record, atomically so concurrent scans aren't lost
+    pub async fn record_scan(&self, property_id: &str) -> Result<(), mongodb::error::Error> {
+        let now = to_bson(&Utc::now())?;
+        self.qr_metadata
+            .update_one(
+                doc! { "propertyId": property_id },
+                doc! { "$inc": { "scanCount": 1i64 }, "$set": { "lastScanned": now } },
+            )
+            .await?;
+        Ok(())
+    }
 
-        // Delete from database
+    /// Take one of a limited-use code's scans, deactivating the code with its last one; false
+    /// when it had none left. Atomic, so concurrent scans can't overrun the limit.
+    pub async fn use_limited_scan(&self, property_id: &str) -> Result<bool, mongodb::error::Error> {
+        let now = to_bson(&Utc::now())?;
+        let limited_scans = doc! { "$add": ["$limitedScans", 1] };
         let result = self.qr_metadata
-        .delete_one(doc! { "propertyId": property_id })
+            .update_one(
+                doc! {
+                    "propertyId": property_id,
+                    "isActive": true,
+                    "$expr": { "$lt": ["$limitedScans", "$maxScans"] },
+                },
+                vec![doc! {
+                    "$set": {
+                        "scanCount": { "$add": ["$scanCount", 1] },
+                        "limitedScans": limited_scans.clone(),
+                        "lastScanned": now,
+                        "isActive": { "$lt": [limited_scans, "$maxScans"] },
+                    }
+                }],
+            )
             .await?;
+        Ok(result.matched_count == 1)
+    }
 
-        Ok(result.deleted_count > 0)
+    /// Activate codes whose scheduled window has opened and deactivate those whose window has
+    /// ended, so an open house's codes stop working afterwards
+    pub async fn apply_schedules(&self, now: DateTime<Utc>) -> Result<ScheduleReport, QrGeneratorError> {
+        let now_bson = to_bson(&now).map_err(mongodb::error::Error::from)?;
+        let opened = doc! { "awaitingActivation": true, "activeFrom": { "$lte": now_bson.clone() } };
+        let ended = doc! { "isActive": true, "activeUntil": { "$lte": now_bson } };
+
+        let mut report = ScheduleReport::default();
+        for (filter, is_active) in [(opened, true), (ended, false)] {
+            let mut cursor = self.qr_metadata.find(filter.clone()).await?;
+            let mut property_ids = Vec::new();
+            while cursor.advance().await? {
+                property_ids.push(cursor.deserialize_current()?.property_id);
+            }
+            if property_ids.is_empty() {
+                continue;
+            }
+
+            // A code whose whole window passed between runs opens and ends in the same run
+            let result = self.qr_metadata
+                .update_many(
+                    filter,
+                    doc! {
+                        "$set": {
+                            "isActive": is_active,
+                            "awaitingActivation": false,
+                            "lastUpdated": utc_to_bson(now)
+                        }
+                    },
+                )
+                .await?;
+            for property_id in &property_ids {
+                self.invalidate_property(property_id);
+            }
+            match is_active {
+                true => report.activated = result.modified_count,
+                false => report.deactivated = result.modified_count,
+            }
+        }
+
+        if report.activated > 0 || report.deactivated > 0 {
+            info!("QR schedules: {} codes activated, {} deactivated", report.activated, report.deactivated);
+        }
+        Ok(report)
+    }
+
+    /// Apply scheduled windows in the background
+    pub fn spawn_schedule_job(&self, interval: std::time::Duration) {
+        let qr_generator = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = qr_generator.apply_schedules(Utc::now()).await {
+                    error!("Applying QR schedules failed: {}", e);
+                }
+            }
+        });
     }
 
     /// Deactivate QR code (soft delete)
-    pub async fn deactivate_qr_code(&self, property_id: &str) -> Result<bool, QrGeneratorError> {
+    pub async fn deactivate_qr_code(&self, property_id: &str, actor: Option<AuditActor>) -> Result<bool, QrGeneratorError> {
+        let before = match &self.audit {
+            Some(_) => self.get_existing_qr(property_id).await.ok(),
+            None => None,
+        };
         let update = doc! {
             "$set": {
                 "isActive": false,
+                "awaitingActivation": false,
                 "lastUpdated": utc_to_bson(Utc::now())
             }
         };
@@ -283,10 +1122,29 @@ impl QrGeneratorService {
         let result = self.qr_metadata
         .update_one(doc! { "propertyId": property_id }, update)
             .await?;
+        self.invalidate_property(property_id);
 
+        if result.modified_count > 0 {
+            let after = before.clone().map(|qr| QrCodeMetadata { is_active: false, last_updated: Utc::now(), ..qr });
+            self.audit(AuditAction::Deactivate, property_id, actor, before, after).await;
+        }
         Ok(result.modified_count > 0)
     }
 
+    async fn audit(
+        &self,
+        action: AuditAction,
+        property_id: &str,
+        actor: Option<AuditActor>,
+        before: Option<QrCodeMetadata>,
+        after: Option<QrCodeMetadata>,
+    ) {
+        if let Some(audit) = &self.audit {
+            let actor = actor.unwrap_or_else(|| AuditActor::system("qr-generator"));
+            audit.record(action, property_id, actor, before, after).await;
+        }
+    }
+
     /// Get all QR codes with pagination
     pub async fn get_all_qr_codes(
         &self,
@@ -311,18 +1169,95 @@ impl QrGeneratorService {
 
         while cursor.advance().await? {
             let qr_code = cursor.deserialize_current()?;
-            qr_codes.push(qr_code);
+            qr_codes.push(self.served(qr_code));
         }
 
         Ok(qr_codes)
     }
 
-    /// Get QR codes that need regeneration (expired or outdated)
-    pub async fn get_qr_codes_needing_regeneration(&self, expiry_days: i64) -> Result<Vec<String>, QrGeneratorError> {
-        let cutoff_date = Utc::now() - chrono::Duration::days(expiry_days);
-        
-        let filter = doc! {
-            "generatedAt": { "$lt": utc_to_bson(cutoff_date) },
+    /// One page of QR codes, filtered and sorted in the database, with the total match count
+    pub async fn list_qr_codes(
+        &self,
+        property_id: Option<&str>,
+        active_only: bool,
+        sort: QrSortField,
+        order: SortOrder,
+        limit: i64,
+        skip: u64,
+    ) -> Result<QrCodePage, QrGeneratorError> {
+        let filter = qr_list_filter(property_id, active_only);
+        let total_count = self.qr_metadata.count_documents(filter.clone()).await?;
+
+        let options = FindOptions::builder()
+            .limit(limit)
+            .skip(skip)
+            .sort(qr_list_sort(sort, order))
+            .build();
+
+        let mut cursor = self.qr_metadata.find(filter).with_options(options).await?;
+        let mut qr_codes = Vec::new();
+
+        while cursor.advance().await? {
+            qr_codes.push(self.served(cursor.deserialize_current()?));
+        }
+
+        Ok(QrCodePage::new(qr_codes, total_count, limit, skip))
+    }
+
+    /// QR codes of a set of properties, newest first
+    pub async fn get_qr_codes_for_properties(&self, property_ids: &[String]) -> Result<Vec<QrCodeMetadata>, QrGeneratorError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "generatedAt": -1 })
+            .build();
+
+        let mut cursor = self.qr_metadata
+            .find(doc! { "propertyId": { "$in": property_ids } })
+            .with_options(options)
+            .await?;
+        let mut qr_codes = Vec::new();
+
+        while cursor.advance().await? {
+            qr_codes.push(self.served(cursor.deserialize_current()?));
+        }
+
+        Ok(qr_codes)
+    }
+
+    /// QR codes whose listing name or location matches a text search, best match first
+    pub async fn search_qr_codes(&self, query: &str, limit: i64) -> Result<Vec<QrCodeMetadata>, QrGeneratorError> {
+        let options = FindOptions::builder()
+            .limit(limit)
+            .sort(doc! { "score": { "$meta": "textScore" }, "generatedAt": -1 })
+            .build();
+
+        let mut cursor = self.qr_metadata
+            .find(doc! { "$text": { "$search": query } })
+            .with_options(options)
+            .await?;
+        let mut qr_codes = Vec::new();
+
+        while cursor.advance().await? {
+            qr_codes.push(self.served(cursor.deserialize_current()?));
+        }
+
+        Ok(qr_codes)
+    }
+
+    /// The stored PNG of a QR code, as uploaded when it was generated
+    pub async fn qr_image(&self, qr_code: &QrCodeMetadata) -> Result<Vec<u8>, StorageError> {
+        let key = qr_code.get_s3_key();
+        if !key.ends_with(".png") {
+            return Err(StorageError::DownloadError(format!("{} is not a PNG; upload the design as a PNG to use it here", key)));
+        }
+        self.storage.download_file(&key).await
+    }
+
+    /// Get QR codes that need regeneration (expired or outdated)
+    pub async fn get_qr_codes_needing_regeneration(&self, expiry_days: i64) -> Result<Vec<String>, QrGeneratorError> {
+        let cutoff_date = Utc::now() - chrono::Duration::days(expiry_days);
+        
+        let filter = doc! {
+            "generatedAt": { "$lt": utc_to_bson(cutoff_date) },
             "isActive": true
         };
 
@@ -368,10 +1303,175 @@ impl QrGeneratorService {
             });
         }
 
-        self.batch_generate_qr_codes(property_ids, false, QrGenerationReason::BatchGeneration).await
+        self.batch_generate_qr_codes(property_ids, false, QrGenerationReason::BatchGeneration, system_options("missing-qr-generation")).await
+    }
+
+    /// Find active QR codes whose encoded scan URL doesn't match the current base URL
+    pub async fn find_stale_qr_codes(&self) -> Result<StaleQrReport, QrGeneratorError> {
+        let mut cursor = self.qr_metadata.find(doc! { "isActive": true }).await?;
+        let mut property_ids = Vec::new();
+
+        while cursor.advance().await? {
+            let qr_code: QrCodeMetadata = cursor.deserialize_current()?;
+            if qr_code.is_stale(&self.base_url) {
+                property_ids.push(qr_code.property_id);
+            }
+        }
+
+        Ok(StaleQrReport {
+            base_url: self.base_url.clone(),
+            stale_count: property_ids.len(),
+            property_ids,
+        })
+    }
+
+    /// Start regenerating stale QR codes in batches, returning the job to poll for progress
+    pub async fn start_stale_regeneration(&self, batch_size: usize) -> Result<QrRegenerationJob, QrGeneratorError> {
+        // Only one run at a time; hand back the one already in progress
+        if let Some(running) = self.regeneration_jobs
+            .find_one(doc! { "status": "running" })
+            .await?
+        {
+            return Ok(running);
+        }
+
+        let report = self.find_stale_qr_codes().await?;
+        let job = QrRegenerationJob::new(report.base_url, batch_size.max(1), report.stale_count);
+        self.regeneration_jobs.insert_one(&job).await?;
+
+        info!("Starting regeneration job {} for {} stale QR codes", job.id, job.total);
+
+        let service = self.clone();
+        let mut job_state = job.clone();
+        tokio::spawn(async move {
+            for batch in report.property_ids.chunks(job_state.batch_size) {
+                match service.batch_generate_qr_codes(batch.to_vec(), true, QrGenerationReason::BaseUrlChanged, system_options("stale-regeneration")).await {
+                    Ok(result) => {
+                        job_state.succeeded += result.total_successful;
+                        job_state.failed.extend(result.failed);
+                    }
+                    Err(e) => {
+                        error!("Regeneration job {} batch failed: {}", job_state.id, e);
+                        job_state.status = RegenerationJobStatus::Failed;
+                        break;
+                    }
+                }
+                job_state.processed += batch.len();
+
+                if let Err(e) = service.save_regeneration_job(&job_state).await {
+                    warn!("Failed to save progress for regeneration job {}: {}", job_state.id, e);
+                }
+            }
+
+            if job_state.status == RegenerationJobStatus::Running {
+                job_state.status = RegenerationJobStatus::Completed;
+            }
+            job_state.completed_at = Some(Utc::now());
+
+            if let Err(e) = service.save_regeneration_job(&job_state).await {
+                error!("Failed to finalise regeneration job {}: {}", job_state.id, e);
+            }
+            info!(
+                "Regeneration job {} finished: {} succeeded, {} failed",
+                job_state.id, job_state.succeeded, job_state.failed.len()
+            );
+        });
+
+        Ok(job)
+    }
+
+    /// Get a regeneration job by ID
+    pub async fn get_regeneration_job(&self, job_id: &str) -> Result<QrRegenerationJob, QrGeneratorError> {
+        // An unparseable ID can't match any job
+        let object_id = ObjectId::parse_str(job_id)
+            .map_err(|_| QrGeneratorError::JobNotFound)?;
+
+        self.regeneration_jobs
+            .find_one(doc! { "_id": object_id })
+            .await?
+            .ok_or(QrGeneratorError::JobNotFound)
+    }
+
+    /// Most recent regeneration jobs, newest first
+    pub async fn recent_regeneration_jobs(&self, limit: i64) -> Result<Vec<QrRegenerationJob>, QrGeneratorError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .limit(limit)
+            .build();
+
+        let mut cursor = self.regeneration_jobs.find(doc! {}).with_options(options).await?;
+        let mut jobs = Vec::new();
+
+        while cursor.advance().await? {
+            jobs.push(cursor.deserialize_current()?);
+        }
+
+        Ok(jobs)
+    }
+
+    /// Generate a QR for a synthetic property, read its payload back and round-trip a probe
+    /// object through storage, so bad credentials or missing libraries show up at boot
+    pub async fn run_self_test(&self) -> SelfTestReport {
+        let mut steps = Vec::new();
+
+        let outcome = self.run_self_test_steps(&mut steps).await;
+        match &outcome {
+            Ok(()) => info!("Startup self-test passed in {} steps", steps.len()),
+            Err(e) => error!("Startup self-test failed: {}", e),
+        }
+
+        SelfTestReport {
+            passed: outcome.is_ok(),
+            steps,
+            completed_at: Utc::now(),
+        }
     }
 
     /// Private helper methods
+    async fn run_self_test_steps(&self, steps: &mut Vec<SelfTestStep>) -> Result<(), String> {
+        let started = Instant::now();
+        let encoded = async {
+            let payload = QrCodeData::new(SELF_TEST_PROPERTY_ID.to_string(), &self.base_url, 1)
+                .to_payload(self.settings.payload_mode)
+                .map_err(|e| e.to_string())?;
+            let image = self.generate_qr_image(&payload, &self.settings).await.map_err(|e| e.to_string())?;
+            if !image.starts_with(&PNG_SIGNATURE) {
+                return Err("generated image is not a PNG".to_string());
+            }
+            Ok((payload, image))
+        }.await;
+        let (payload, image) = finish_self_test_step(steps, "qr_encode", started, encoded)?;
+
+        let started = Instant::now();
+        let decoded = check_self_test_payload(&payload, &self.base_url);
+        finish_self_test_step(steps, "qr_decode", started, decoded)?;
+
+        // Unique key so pods starting together don't delete each other's probe
+        let probe_key = format!("self-test/{}.png", ObjectId::new().to_hex());
+
+        let started = Instant::now();
+        let uploaded = self.storage.upload_qr_image(&probe_key, image).await
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+        finish_self_test_step(steps, "storage_upload", started, uploaded)?;
+
+        let started = Instant::now();
+        let deleted = match self.storage.delete_qr_image(&probe_key).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("probe object {} was not deleted", probe_key)),
+            Err(e) => Err(e.to_string()),
+        };
+        finish_self_test_step(steps, "storage_delete", started, deleted)
+    }
+
+    async fn save_regeneration_job(&self, job: &QrRegenerationJob) -> Result<(), QrGeneratorError> {
+        self.regeneration_jobs
+            .replace_one(doc! { "_id": job.id }, job)
+            .await?;
+        Ok(())
+    }
+
+
     async fn get_existing_qr(&self, property_id: &str) -> Result<QrCodeMetadata, QrGeneratorError> {
         self.qr_metadata
         .find_one(doc! { "propertyId": property_id })
@@ -379,6 +1479,27 @@ impl QrGeneratorService {
             .ok_or(QrGeneratorError::PropertyNotFound)
     }
 
+    // Only the version that was uploaded is activated, not one a concurrent regeneration wrote since
+    async fn activate_storage(&self, qr_metadata: &QrCodeMetadata, upload: &UploadResult) -> Result<(), QrGeneratorError> {
+        // Drawn images are uploaded with every rendition; uploaded designs have none
+        let rendition_sizes: Vec<i64> = match qr_metadata.custom_image {
+            true => Vec::new(),
+            false => QR_RENDITION_SIZES.map(i64::from).to_vec(),
+        };
+        self.qr_metadata
+            .update_one(
+                doc! { "propertyId": &qr_metadata.property_id, "qrCodeHash": &qr_metadata.qr_code_hash },
+                doc! { "$set": {
+                    "storageState": to_bson(&QrStorageState::Active).map_err(mongodb::error::Error::from)?,
+                    "imageSize": upload.size as i64,
+                    "imageEtag": &upload.etag,
+                    "renditionSizes": rendition_sizes
+                } },
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn upsert_qr_metadata(&self, qr_metadata: &QrCodeMetadata) -> Result<(), QrGeneratorError> {
         let filter = doc! { "propertyId": &qr_metadata.property_id };
         let options = mongodb::options::ReplaceOptions::builder()
@@ -392,7 +1513,25 @@ impl QrGeneratorService {
     Ok(())
 }
 
-async fn generate_qr_image(&self, _qr_data: &str) -> Result<Vec<u8>, QrGeneratorError> {
+// Every rendition size, as PNG and WebP, drawn from the same pattern and style
+async fn generate_renditions(
+    &self,
+    qr_data: &str,
+    settings: &QrGenerationSettings,
+) -> Result<Vec<(u32, QrRenditionFormat, Vec<u8>)>, QrGeneratorError> {
+    let mut renditions = Vec::with_capacity(QR_RENDITION_SIZES.len() * QrRenditionFormat::ALL.len());
+
+    for size in QR_RENDITION_SIZES {
+        let png = self.generate_qr_image(qr_data, &QrGenerationSettings { size, ..settings.clone() }).await?;
+        let webp = encode_webp(&png)?;
+        renditions.push((size, QrRenditionFormat::Png, png));
+        renditions.push((size, QrRenditionFormat::Webp, webp));
+    }
+
+    Ok(renditions)
+}
+
+async fn generate_qr_image(&self, _qr_data: &str, _settings: &QrGenerationSettings) -> Result<Vec<u8>, QrGeneratorError> {
     // TODO: Implement actual QR code generation using qrcode crate
     // For now, return a placeholder
     // 
@@ -404,7 +1543,8 @@ async fn generate_qr_image(&self, _qr_data: &str) -> Result<Vec<u8>, QrGenerator
     //     .map_err(|e| QrGeneratorError::QrGenerationFailed(e.to_string()))?;
     
     // let image = code.render::<Luma<u8>>()
-    //     .min_dimensions(self.settings.size, self.settings.size)
+    //     .quiet_zone(settings.quiet_zone > 0)
+    //     .min_dimensions(settings.size, settings.size)
     //     .build();
     
     // Convert image to PNG bytes
@@ -417,6 +1557,28 @@ async fn generate_qr_image(&self, _qr_data: &str) -> Result<Vec<u8>, QrGenerator
     Ok(vec![0x89, 0x50, 0x4E, 0x47]) // PNG header as placeholder
 }
 
+// Keys of the images current code records point at
+async fn current_image_keys(&self) -> Result<HashSet<String>, QrGeneratorError> {
+    let projection = doc! { "propertyId": 1, "imageKey": 1, "_id": 0 };
+    let options = mongodb::options::FindOptions::builder()
+        .projection(projection)
+        .build();
+
+    let mut cursor = self.qr_metadata.find(doc! {}).with_options(options).await?;
+    let mut keys = HashSet::new();
+
+    while cursor.advance().await? {
+        let current = cursor.current();
+        match (current.get_str("imageKey"), current.get_str("propertyId")) {
+            (Ok(image_key), _) => keys.insert(image_key.to_string()),
+            (Err(_), Ok(property_id)) => keys.insert(QrCodeMetadata::image_key_for(property_id, 1)),
+            _ => false,
+        };
+    }
+
+    Ok(keys)
+}
+
 async fn get_all_qr_property_ids(&self) -> Result<Vec<String>, QrGeneratorError> {
     let projection = doc! { "propertyId": 1, "_id": 0 };
     let options = mongodb::options::FindOptions::builder()
@@ -436,11 +1598,250 @@ async fn get_all_qr_property_ids(&self) -> Result<Vec<String>, QrGeneratorError>
 }
 }
 
+/// Re-encode a PNG rendition as lossless WebP
+fn encode_webp(png: &[u8]) -> Result<Vec<u8>, QrGeneratorError> {
+    let image = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map_err(|e| QrGeneratorError::QrGenerationFailed(e.to_string()))?;
+
+    // The encoder takes 8-bit pixels only
+    let mut webp_data = Vec::new();
+    image::DynamicImage::ImageRgba8(image.to_rgba8())
+        .write_with_encoder(image::codecs::webp::WebPEncoder::new_lossless(&mut webp_data))
+        .map_err(|e| QrGeneratorError::QrGenerationFailed(e.to_string()))?;
+    Ok(webp_data)
+}
+
+/// A code's stored renditions by format, then srcset width descriptor, at the URLs they're
+/// being served from
+fn rendition_urls(qr_code: &QrCodeMetadata, storage: &StorageService) -> BTreeMap<String, BTreeMap<String, String>> {
+    let image_key = qr_code.get_s3_key();
+    QrRenditionFormat::ALL.into_iter()
+        .filter(|_| !qr_code.rendition_sizes.is_empty())
+        .map(|format| {
+            let urls = qr_code.rendition_sizes.iter()
+                .map(|&size| {
+                    let key = QrCodeMetadata::rendition_key(&image_key, size, format);
+                    (format!("{}w", size), storage.get_public_url(&key))
+                })
+                .collect();
+            (format.extension().to_string(), urls)
+        })
+        .collect()
+}
+
+/// Compare an image's HEAD with what was recorded at upload
+fn asset_status(qr_code: &QrCodeMetadata, head: Option<&FileMetadata>) -> AssetStatus {
+    let Some(head) = head else {
+        return AssetStatus::Missing;
+    };
+    match (qr_code.image_size, &qr_code.image_etag) {
+        (Some(size), Some(etag)) if size == head.size && *etag == head.etag => AssetStatus::Intact,
+        (Some(_), Some(_)) => AssetStatus::Corrupted,
+        _ => AssetStatus::Unrecorded,
+    }
+}
+
+/// QR images and renditions older than `cutoff` that no code record points at, e.g. of
+/// deleted codes or versions replaced by a regeneration; objects without a modification time
+/// are kept, as their age is unknown
+fn orphaned_image_keys(objects: &[StoredObject], known: &HashSet<String>, cutoff: DateTime<Utc>) -> Vec<String> {
+    objects.iter()
+        .filter(|object| object.last_modified.is_some_and(|last_modified| last_modified < cutoff))
+        .filter(|object| {
+            let image_key = match QrCodeMetadata::image_key_of_rendition(&object.key) {
+                Some(image_key) => image_key,
+                None => object.key.clone(),
+            };
+            QrCodeMetadata::property_id_from_s3_key(&image_key).is_some() && !known.contains(&image_key)
+        })
+        .map(|object| object.key.clone())
+        .collect()
+}
+
+/// Snapshot of the listing stored alongside a code
+fn listing_metadata(property_info: PropertyQrInfo, reason: QrGenerationReason) -> QrMetadata {
+    QrMetadata {
+        property_name: property_info.property_name,
+        location: property_info.location,
+        action: property_info.action,
+        price: property_info.price,
+        onchain_id: property_info.onchain_id,
+        crypto_accepted: property_info.crypto_accepted,
+        primary_image: property_info.images.first().cloned(),
+        is_verified: property_info.is_verified.unwrap_or(false),
+        generated_by: None, // Set by the caller, who knows the user
+        generation_reason: reason,
+    }
+}
+
+// Options for generations started by a background job rather than a user
+fn system_options(job: &str) -> QrGenerationOptions {
+    QrGenerationOptions {
+        actor: Some(AuditActor::system(job)),
+        ..QrGenerationOptions::default()
+    }
+}
+
+fn property_error(e: PropertyError) -> QrGeneratorError {
+    match e {
+        PropertyError::NotFound => QrGeneratorError::PropertyNotFound,
+        PropertyError::NotEligibleForQr(reason) => QrGeneratorError::PropertyNotEligible(reason),
+        PropertyError::InvalidId => QrGeneratorError::InvalidPropertyId,
+        PropertyError::DatabaseError(db_err) => QrGeneratorError::DatabaseError(db_err),
+    }
+}
+
+/// What a code for this listing encodes, with the generation's payload mode used for scan codes
+fn encode_payload(
+    payload: &QrPayload,
+    qr_data: &QrCodeData,
+    listing: &PropertyQrInfo,
+    settings: &QrGenerationSettings,
+) -> Result<String, QrGeneratorError> {
+    match payload {
+        QrPayload::Scan => qr_data.to_payload(settings.payload_mode)
+            .map_err(|e| QrGeneratorError::QrGenerationFailed(e.to_string())),
+        QrPayload::Vcard(contact) => Ok(QrPayload::vcard(contact, &listing.property_name, &qr_data.scan_url)),
+        QrPayload::Geo => listing.coordinates.as_ref()
+            .and_then(QrPayload::geo_uri)
+            .ok_or_else(|| QrGeneratorError::PropertyNotEligible("listing has no map coordinates".to_string())),
+        QrPayload::Wifi(network) => Ok(QrPayload::wifi(network)),
+    }
+}
+
+/// Record a self-test step's outcome, passing its result through
+fn finish_self_test_step<T>(
+    steps: &mut Vec<SelfTestStep>,
+    name: &str,
+    started: Instant,
+    result: Result<T, String>,
+) -> Result<T, String> {
+    steps.push(SelfTestStep {
+        name: name.to_string(),
+        passed: result.is_ok(),
+        error: result.as_ref().err().cloned(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    });
+    result.map_err(|e| format!("{}: {}", name, e))
+}
+
+/// Read the payload of the QR code in a PNG or JPEG photo
+fn decode_qr_image(image: &[u8]) -> Result<String, QrGeneratorError> {
+    if image.is_empty() {
+        return Err(QrGeneratorError::DecodeFailed("image is empty".to_string()));
+    }
+
+    let luma = image::load_from_memory(image)
+        .map_err(|e| QrGeneratorError::DecodeFailed(e.to_string()))?
+        .to_luma8();
+    decode_qr_luma(&luma)
+}
+
+/// Read the payload of an uploaded code, rasterizing SVGs first
+fn decode_uploaded_image(image: &[u8], format: UploadedImageFormat) -> Result<String, QrGeneratorError> {
+    match format {
+        UploadedImageFormat::Png => decode_qr_image(image),
+        UploadedImageFormat::Svg => decode_qr_luma(&rasterize_svg(image)?),
+    }
+}
+
+/// Render an SVG on white at a size the detector reads reliably, whatever its own size
+fn rasterize_svg(svg: &[u8]) -> Result<image::GrayImage, QrGeneratorError> {
+    const RASTER_SIZE: f32 = 600.0;
+
+    let tree = resvg::usvg::Tree::from_data(svg, &resvg::usvg::Options::default())
+        .map_err(|e| QrGeneratorError::DecodeFailed(e.to_string()))?;
+    let size = tree.size();
+    let scale = RASTER_SIZE / size.width().max(size.height());
+    let (width, height) = ((size.width() * scale).ceil() as u32, (size.height() * scale).ceil() as u32);
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| QrGeneratorError::DecodeFailed("SVG has no size".to_string()))?;
+    pixmap.fill(resvg::tiny_skia::Color::WHITE);
+    resvg::render(&tree, resvg::tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    Ok(image::GrayImage::from_fn(width, height, |x, y| {
+        let pixel = pixmap.pixel(x, y).unwrap_or_else(|| resvg::tiny_skia::ColorU8::from_rgba(255, 255, 255, 255).premultiply());
+        let luma = (u32::from(pixel.red()) * 299 + u32::from(pixel.green()) * 587 + u32::from(pixel.blue()) * 114) / 1000;
+        image::Luma([luma as u8])
+    }))
+}
+
+/// An uploaded image has to point at the property's own scan page, at any version
+fn check_uploaded_scan_url(decoded: &str, expected: &str) -> Result<(), QrGeneratorError> {
+    match scan_target(decoded) {
+        Some((scan_url, _, _)) if scan_url.split('?').next() == Some(expected) => Ok(()),
+        Some((scan_url, _, _)) => {
+            Err(QrGeneratorError::InvalidPayload(format!("image encodes {}, expected {}", scan_url, expected)))
+        }
+        None => {
+            Err(QrGeneratorError::InvalidPayload(format!("image doesn't encode a scan URL, expected {}", expected)))
+        }
+    }
+}
+
+fn decode_qr_luma(luma: &image::GrayImage) -> Result<String, QrGeneratorError> {
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        luma.width() as usize,
+        luma.height() as usize,
+        |x, y| luma.get_pixel(x as u32, y as u32).0[0],
+    );
+
+    // A photo may catch more than one code; the first that reads is the one
+    prepared.detect_grids()
+        .into_iter()
+        .find_map(|grid| grid.decode().ok())
+        .map(|(_, content)| content)
+        .ok_or_else(|| QrGeneratorError::DecodeFailed("no readable QR code found".to_string()))
+}
+
+/// The scan URL a decoded payload points at, with the property and printed version in it.
+/// Scan URLs on any host count, as old prints may use a previous domain.
+fn scan_target(decoded: &str) -> Option<(String, String, Option<i32>)> {
+    let scan_url = match QrPayloadMode::of(decoded) {
+        QrPayloadMode::Url => decoded.to_string(),
+        QrPayloadMode::Json => QrCodeData::from_json_string(decoded)
+            .map(|data| data.scan_url)
+            .ok()
+            .or_else(|| QrPayload::vcard_scan_url(decoded))?,
+    };
+
+    let (path, query) = scan_url.split_once('?').unwrap_or((scan_url.as_str(), ""));
+    let (_, property_id) = path.rsplit_once("/scan/")?;
+    ObjectId::parse_str(property_id).ok()?;
+    let version = query.split('&')
+        .find_map(|pair| pair.strip_prefix("v="))
+        .and_then(|version| version.parse().ok());
+
+    Some((scan_url.clone(), property_id.to_string(), version))
+}
+
+/// Check an encoded payload decodes back to the synthetic property and its scan URL.
+/// Image generation is still a placeholder, so this reads the payload rather than the PNG.
+fn check_self_test_payload(payload: &str, base_url: &str) -> Result<(), String> {
+    let scan_url = match QrPayloadMode::of(payload) {
+        QrPayloadMode::Url => payload.to_string(),
+        QrPayloadMode::Json => {
+            let decoded = QrCodeData::from_json_string(payload).map_err(|e| e.to_string())?;
+            if !decoded.is_valid() || decoded.property_id != SELF_TEST_PROPERTY_ID {
+                return Err("decoded payload does not match the synthetic property".to_string());
+            }
+            decoded.scan_url
+        }
+    };
+
+    if scan_url != format!("{}/scan/{}?v=1", base_url, SELF_TEST_PROPERTY_ID) {
+        return Err(format!("decoded scan URL {} does not match the base URL", scan_url));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 use super::*;
 use mongodb::Client;
-use crate::services::{PropertyService, S3Service};
+use crate::services::{PropertyService, S3Storage};
+use std::sync::Arc;
 
 async fn get_test_service() -> QrGeneratorService {
     let client = Client::with_uri_str("mongodb://localhost:27017")
@@ -449,23 +1850,125 @@ async fn get_test_service() -> QrGeneratorService {
     let db = client.database("test_qr_generator");
     
     let property_service = PropertyService::new(&db);
-    let s3_service = S3Service::new("test-bucket".to_string(), "us-east-1".to_string())
-        .expect("Failed to create S3 service");
+    let storage = StorageService::new(Arc::new(
+        S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).expect("Failed to create S3 storage"),
+    ));
     
     QrGeneratorService::new(
         &db,
         property_service,
-        s3_service,
+        storage,
         "https://qr-service.daobitat.xyz".to_string(),
     )
 }
 
+#[test]
+fn test_qr_list_query_documents() {
+    assert_eq!(qr_list_filter(None, false), doc! {});
+    assert_eq!(
+        qr_list_filter(Some("p1"), true),
+        doc! { "propertyId": "p1", "isActive": true }
+    );
+    assert_eq!(
+        qr_list_sort(QrSortField::ScanCount, SortOrder::Asc),
+        doc! { "scanCount": 1, "_id": 1 }
+    );
+    assert_eq!(
+        qr_list_sort(QrSortField::default(), SortOrder::default()),
+        doc! { "generatedAt": -1, "_id": -1 }
+    );
+}
+
+#[test]
+fn test_qr_code_page_cursors() {
+    let first = QrCodePage::new(Vec::new(), 120, 50, 0);
+    assert_eq!((first.prev_cursor, first.next_cursor), (None, Some(50)));
+
+    let last = QrCodePage::new(Vec::new(), 120, 50, 100);
+    assert_eq!((last.prev_cursor, last.next_cursor), (Some(50), None));
+
+    // An offset that isn't a multiple of the page size still steps back to the start
+    let odd = QrCodePage::new(Vec::new(), 120, 50, 30);
+    assert_eq!((odd.prev_cursor, odd.next_cursor), (Some(0), Some(80)));
+}
+
 #[tokio::test]
 async fn test_qr_generator_creation() {
     let service = get_test_service().await;
     assert_eq!(service.base_url, "https://qr-service.daobitat.xyz");
 }
 
+#[test]
+fn test_qr_code_is_stale() {
+    let qr_data = QrCodeData::new("507f1f77bcf86cd799439011".to_string(), "https://old.daobitat.xyz", 3);
+    let metadata = QrMetadata {
+        property_name: "Test".to_string(),
+        location: "Nairobi".to_string(),
+        action: "for sale".to_string(),
+        price: 1,
+        onchain_id: None,
+        crypto_accepted: false,
+        primary_image: None,
+        is_verified: false,
+        generated_by: None,
+        generation_reason: QrGenerationReason::NewProperty,
+    };
+    let qr_code = QrCodeMetadata::new(
+        "507f1f77bcf86cd799439011".to_string(),
+        qr_data.to_json_string().unwrap(),
+        "https://cdn.daobitat.xyz/qr.png".to_string(),
+        metadata,
+    );
+
+    assert!(!qr_code.is_stale("https://old.daobitat.xyz"));
+    assert!(qr_code.is_stale("https://qr-service.daobitat.xyz"));
+}
+
+#[test]
+fn test_listing_metadata_snapshot() {
+    let property = crate::test_support::PropertyFixture::new()
+        .with_name("Garden Villa")
+        .with_price(12_500_000)
+        .build();
+
+    let metadata = listing_metadata(property.to_qr_info(), QrGenerationReason::PropertyUpdated);
+    assert_eq!(metadata.property_name, "Garden Villa");
+    assert_eq!(metadata.price, 12_500_000);
+    assert_eq!(metadata.primary_image, property.images.first().cloned());
+    assert!(matches!(metadata.generation_reason, QrGenerationReason::PropertyUpdated));
+}
+
+#[test]
+fn test_custom_redirect_lapses() {
+    let metadata = QrMetadata {
+        property_name: "Test".to_string(),
+        location: "Nairobi".to_string(),
+        action: "for sale".to_string(),
+        price: 1,
+        onchain_id: None,
+        crypto_accepted: false,
+        primary_image: None,
+        is_verified: false,
+        generated_by: None,
+        generation_reason: QrGenerationReason::NewProperty,
+    };
+    let mut qr_code = QrCodeMetadata::new(
+        "507f1f77bcf86cd799439011".to_string(),
+        "{}".to_string(),
+        "https://cdn.daobitat.xyz/qr.png".to_string(),
+        metadata,
+    );
+    let now = Utc::now();
+    assert_eq!(qr_code.active_redirect_url(now), None);
+
+    qr_code.custom_redirect_url = Some("https://daobitat.xyz/open-house".to_string());
+    assert_eq!(qr_code.active_redirect_url(now), Some("https://daobitat.xyz/open-house"));
+
+    qr_code.custom_redirect_until = Some(now + chrono::Duration::hours(1));
+    assert_eq!(qr_code.active_redirect_url(now), Some("https://daobitat.xyz/open-house"));
+    assert_eq!(qr_code.active_redirect_url(now + chrono::Duration::hours(2)), None);
+}
+
 #[tokio::test]
 async fn test_get_all_qr_codes_empty() {
     let service = get_test_service().await;
@@ -476,17 +1979,413 @@ async fn test_get_all_qr_codes_empty() {
     assert!(qr_codes.len() >= 0);
 }
 
+#[tokio::test]
+async fn test_record_scan_updates_qr_metadata() {
+    let service = get_test_service().await;
+    let property_id = ObjectId::new().to_hex();
+    let qr_data = QrCodeData::new(property_id.clone(), &service.base_url, 1);
+    let metadata = QrMetadata {
+        property_name: "Test".to_string(),
+        location: "Nairobi".to_string(),
+        action: "for sale".to_string(),
+        price: 1,
+        onchain_id: None,
+        crypto_accepted: false,
+        primary_image: None,
+        is_verified: false,
+        generated_by: None,
+        generation_reason: QrGenerationReason::NewProperty,
+    };
+    let qr_code = QrCodeMetadata::new(
+        property_id.clone(),
+        qr_data.to_json_string().unwrap(),
+        "https://cdn.daobitat.xyz/qr.png".to_string(),
+        metadata,
+    );
+    service.upsert_qr_metadata(&qr_code).await.expect("Failed to store QR code");
+
+    service.record_scan(&property_id).await.expect("Failed to record scan");
+    service.record_scan(&property_id).await.expect("Failed to record scan");
+
+    let reloaded = service.get_qr_code(&property_id).await.expect("Failed to reload QR code");
+    assert_eq!(reloaded.scan_count, 2);
+    assert!(reloaded.last_scanned.is_some());
+}
+
 #[tokio::test]
 async fn test_batch_generate_empty_list() {
     let service = get_test_service().await;
     let result = service.batch_generate_qr_codes(
         vec![], 
         false, 
-        QrGenerationReason::BatchGeneration
+        QrGenerationReason::BatchGeneration,
+        QrGenerationOptions::default(),
     ).await.expect("Failed batch generation");
     
     assert_eq!(result.total_requested, 0);
     assert_eq!(result.total_successful, 0);
     assert_eq!(result.total_failed, 0);
 }
-}
\ No newline at end of file
+
+#[tokio::test]
+async fn test_run_self_test() {
+    let service = get_test_service().await;
+    let report = service.run_self_test().await;
+
+    assert!(report.passed, "{:?}", report.steps);
+    let names: Vec<&str> = report.steps.iter().map(|step| step.name.as_str()).collect();
+    assert_eq!(names, ["qr_encode", "qr_decode", "storage_upload", "storage_delete"]);
+}
+
+#[test]
+fn test_orphaned_image_keys() {
+    let now = Utc::now();
+    let object = |key: &str, age_minutes: Option<i64>| StoredObject {
+        key: key.to_string(),
+        size: 4,
+        last_modified: age_minutes.map(|minutes| now - Duration::minutes(minutes)),
+        etag: String::new(),
+    };
+    let known: HashSet<String> = [
+        "qr-images/507f1f77bcf86cd799439011.png".to_string(),
+        "qr-images/507f1f77bcf86cd799439015-v3.png".to_string(),
+    ].into();
+
+    let orphans = orphaned_image_keys(
+        &[
+            object("qr-images/507f1f77bcf86cd799439011.png", Some(120)), // Still has a code
+            object("qr-images/507f1f77bcf86cd799439012.png", Some(120)),
+            object("qr-images/507f1f77bcf86cd799439015-v2.png", Some(120)), // Replaced by v3
+            object("qr-images/507f1f77bcf86cd799439015-v3.png", Some(120)),
+            object("qr-renditions/507f1f77bcf86cd799439015-v2/512.webp", Some(120)),
+            object("qr-renditions/507f1f77bcf86cd799439015-v3/512.webp", Some(120)),
+            object("qr-images/507f1f77bcf86cd799439013.png", Some(5)),   // May be mid-generation
+            object("qr-images/507f1f77bcf86cd799439014.png", None),
+            object("qr-images/readme.txt", Some(120)),
+        ],
+        &known,
+        now - Duration::minutes(30),
+    );
+
+    assert_eq!(orphans, [
+        "qr-images/507f1f77bcf86cd799439012.png",
+        "qr-images/507f1f77bcf86cd799439015-v2.png",
+        "qr-renditions/507f1f77bcf86cd799439015-v2/512.webp",
+    ]);
+}
+
+#[test]
+fn test_scan_target() {
+    let property_id = "507f1f77bcf86cd799439011";
+    let url = format!("https://qr-service.daobitat.xyz/scan/{}?v=3", property_id);
+    assert_eq!(scan_target(&url), Some((url.clone(), property_id.to_string(), Some(3))));
+
+    // Legacy JSON payloads, codes printed before versions and old domains
+    let json = QrCodeData::new(property_id.to_string(), "https://qr.daobitat.com", 2).to_json_string().unwrap();
+    assert_eq!(scan_target(&json).map(|(_, id, version)| (id, version)), Some((property_id.to_string(), Some(2))));
+    let unversioned = format!("https://qr.daobitat.com/scan/{}", property_id);
+    assert_eq!(scan_target(&unversioned).map(|(_, _, version)| version), Some(None));
+
+    // Contact cards carry the scan URL on their URL line
+    let vcard = format!("BEGIN:VCARD\r\nVERSION:3.0\r\nURL:{}\r\nEND:VCARD", url);
+    assert_eq!(scan_target(&vcard).map(|(scan_url, _, _)| scan_url), Some(url));
+
+    assert_eq!(scan_target("WIFI:T:WPA;S:Villa;P:secret;;"), None);
+    assert_eq!(scan_target("https://example.com/scan/not-an-id"), None);
+}
+
+#[test]
+fn test_detect_uploaded_image_format() {
+    let png = [&PNG_SIGNATURE[..], b"\r\n\x1a\n"].concat();
+    assert_eq!(UploadedImageFormat::detect(&png), Some(UploadedImageFormat::Png));
+    assert_eq!(
+        UploadedImageFormat::detect(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>"),
+        Some(UploadedImageFormat::Svg)
+    );
+    assert_eq!(UploadedImageFormat::detect(b"  <svg viewBox=\"0 0 29 29\"></svg>"), Some(UploadedImageFormat::Svg));
+    assert_eq!(UploadedImageFormat::detect(b"<?xml version=\"1.0\"?><html></html>"), None);
+    assert_eq!(UploadedImageFormat::detect(b"\xff\xd8\xff\xe0 JFIF"), None);
+
+    // Uploaded SVGs keep their extension and are still recognised as QR images
+    let key = QrCodeMetadata::image_key_with_extension("507f1f77bcf86cd799439011", 3, "svg");
+    assert_eq!(key, "qr-images/507f1f77bcf86cd799439011-v3.svg");
+    assert_eq!(QrCodeMetadata::property_id_from_s3_key(&key), Some("507f1f77bcf86cd799439011"));
+}
+
+#[test]
+fn test_rendition_urls() {
+    let storage = StorageService::new(Arc::new(S3Storage::new("qr-codes".to_string(), "us-east-1".to_string()).unwrap()));
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let legacy = QrCodeMetadata::new("507f1f77bcf86cd799439011".to_string(), "{}".to_string(), String::new(), metadata);
+
+    // Codes drawn before renditions have none to offer
+    assert!(rendition_urls(&legacy, &storage).is_empty());
+    assert!(legacy.rendition_keys().is_empty());
+
+    let qr_code = QrCodeMetadata {
+        image_key: Some(QrCodeMetadata::image_key_for(&legacy.property_id, 2)),
+        rendition_sizes: QR_RENDITION_SIZES.to_vec(),
+        ..legacy
+    };
+    let renditions = rendition_urls(&qr_code, &storage);
+    assert_eq!(renditions.keys().collect::<Vec<_>>(), ["png", "webp"]);
+    assert_eq!(renditions["webp"].keys().collect::<Vec<_>>(), ["1024w", "256w", "512w"]);
+    assert_eq!(
+        renditions["webp"]["512w"],
+        "https://qr-codes.s3.us-east-1.amazonaws.com/qr-renditions/507f1f77bcf86cd799439011-v2/512.webp"
+    );
+    assert_eq!(qr_code.rendition_keys().len(), 6);
+    assert_eq!(
+        QrCodeMetadata::image_key_of_rendition("qr-renditions/507f1f77bcf86cd799439011-v2/512.webp").as_deref(),
+        Some("qr-images/507f1f77bcf86cd799439011-v2.png")
+    );
+}
+
+#[test]
+fn test_versioned_image_keys() {
+    let property_id = "507f1f77bcf86cd799439011";
+    assert_eq!(QrCodeMetadata::image_key_for(property_id, 1), "qr-images/507f1f77bcf86cd799439011.png");
+    assert_eq!(QrCodeMetadata::image_key_for(property_id, 4), "qr-images/507f1f77bcf86cd799439011-v4.png");
+
+    for version in [1, 4] {
+        let key = QrCodeMetadata::image_key_for(property_id, version);
+        assert_eq!(QrCodeMetadata::property_id_from_s3_key(&key), Some(property_id));
+    }
+    assert_eq!(QrCodeMetadata::property_id_from_s3_key("qr-images/probe-vx.png"), Some("probe-vx"));
+    assert_eq!(QrCodeMetadata::property_id_from_s3_key("qr-images/readme.txt"), None);
+
+    // Records from before keys were stored use the original key
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let legacy = QrCodeMetadata::new(property_id.to_string(), "{}".to_string(), String::new(), metadata);
+    assert_eq!(legacy.get_s3_key(), "qr-images/507f1f77bcf86cd799439011.png");
+    let regenerated = QrCodeMetadata { image_key: Some(QrCodeMetadata::image_key_for(property_id, 2)), ..legacy };
+    assert_eq!(regenerated.get_s3_key(), "qr-images/507f1f77bcf86cd799439011-v2.png");
+}
+
+#[test]
+fn test_asset_status() {
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let legacy = QrCodeMetadata::new("507f1f77bcf86cd799439011".to_string(), "{}".to_string(), String::new(), metadata);
+    let qr_code = QrCodeMetadata { image_size: Some(2048), image_etag: Some("\"9b2cf535f27731c974343645a3985328\"".to_string()), ..legacy.clone() };
+    let head = |size: i64, etag: &str| FileMetadata {
+        key: qr_code.get_s3_key(),
+        size,
+        content_type: "image/png".to_string(),
+        last_modified: Utc::now(),
+        etag: etag.to_string(),
+    };
+
+    assert_eq!(asset_status(&qr_code, Some(&head(2048, "\"9b2cf535f27731c974343645a3985328\""))), AssetStatus::Intact);
+    assert_eq!(asset_status(&qr_code, Some(&head(2048, "\"d41d8cd98f00b204e9800998ecf8427e\""))), AssetStatus::Corrupted);
+    assert_eq!(asset_status(&qr_code, Some(&head(0, "\"9b2cf535f27731c974343645a3985328\""))), AssetStatus::Corrupted);
+    assert_eq!(asset_status(&qr_code, None), AssetStatus::Missing);
+    assert_eq!(asset_status(&legacy, Some(&head(2048, "\"9b2cf535f27731c974343645a3985328\""))), AssetStatus::Unrecorded);
+}
+
+#[test]
+fn test_legacy_qr_code_storage_is_active() {
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let qr_code = QrCodeMetadata::new("507f1f77bcf86cd799439011".to_string(), "{}".to_string(), String::new(), metadata);
+    let mut document = mongodb::bson::to_document(&qr_code).unwrap();
+    document.remove("storageState");
+
+    let legacy: QrCodeMetadata = mongodb::bson::from_document(document).unwrap();
+    assert_eq!(legacy.storage_state, QrStorageState::Active);
+}
+
+#[test]
+fn test_check_self_test_payload() {
+    let payload = QrCodeData::new(SELF_TEST_PROPERTY_ID.to_string(), "https://qr-service.daobitat.xyz", 1)
+        .to_json_string()
+        .unwrap();
+
+    assert!(check_self_test_payload(&payload, "https://qr-service.daobitat.xyz").is_ok());
+    assert!(check_self_test_payload(&payload, "https://old.daobitat.xyz").is_err());
+    assert!(check_self_test_payload("not json", "https://qr-service.daobitat.xyz").is_err());
+
+    let url_payload = format!("https://qr-service.daobitat.xyz/scan/{}?v=1", SELF_TEST_PROPERTY_ID);
+    assert!(check_self_test_payload(&url_payload, "https://qr-service.daobitat.xyz").is_ok());
+    assert!(check_self_test_payload(&url_payload, "https://old.daobitat.xyz").is_err());
+}
+
+#[test]
+fn test_url_payload_holds_only_the_scan_url() {
+    let qr_data = QrCodeData::new("507f1f77bcf86cd799439011".to_string(), "https://qr-service.daobitat.xyz", 2);
+    let payload = qr_data.to_payload(QrPayloadMode::Url).unwrap();
+    assert_eq!(payload, "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011?v=2");
+    assert_eq!(QrPayloadMode::of(&payload), QrPayloadMode::Url);
+    assert_eq!(QrPayloadMode::of(&qr_data.to_payload(QrPayloadMode::Json).unwrap()), QrPayloadMode::Json);
+
+    let metadata = QrMetadata {
+        property_name: "Test".to_string(),
+        location: "Nairobi".to_string(),
+        action: "for sale".to_string(),
+        price: 1,
+        onchain_id: None,
+        crypto_accepted: false,
+        primary_image: None,
+        is_verified: false,
+        generated_by: None,
+        generation_reason: QrGenerationReason::NewProperty,
+    };
+    let qr_code = QrCodeMetadata::new(
+        "507f1f77bcf86cd799439011".to_string(),
+        payload.clone(),
+        "https://cdn.daobitat.xyz/qr.png".to_string(),
+        metadata,
+    );
+    assert_eq!(qr_code.encoded_scan_url(), Some(payload));
+    assert!(!qr_code.is_stale("https://qr-service.daobitat.xyz"));
+    assert!(qr_code.is_stale("https://new.daobitat.xyz"));
+}
+
+#[test]
+fn test_encode_payload() {
+    let qr_data = QrCodeData::new("507f1f77bcf86cd799439011".to_string(), "https://qr-service.daobitat.xyz", 1);
+    let settings = QrGenerationSettings::default();
+    let mut listing = crate::models::Property {
+        property_name: "Garden Villa".to_string(),
+        coordinates: crate::models::Coordinates { lat: -1.2921, lng: 36.8219 },
+        ..Default::default()
+    }
+    .to_qr_info();
+
+    assert_eq!(encode_payload(&QrPayload::Geo, &qr_data, &listing, &settings).unwrap(), "geo:-1.2921,36.8219");
+    listing.coordinates = None;
+    assert!(matches!(
+        encode_payload(&QrPayload::Geo, &qr_data, &listing, &settings),
+        Err(QrGeneratorError::PropertyNotEligible(_))
+    ));
+
+    let contact = crate::models::AgentContact {
+        name: "Wanjiku Kamau".to_string(),
+        phone: Some("+254712345678".to_string()),
+        email: None,
+        organization: None,
+    };
+    let vcard = encode_payload(&QrPayload::Vcard(contact.clone()), &qr_data, &listing, &settings).unwrap();
+    let metadata = QrMetadata {
+        property_name: "Garden Villa".to_string(),
+        location: "Nairobi".to_string(),
+        action: "for sale".to_string(),
+        price: 1,
+        onchain_id: None,
+        crypto_accepted: false,
+        primary_image: None,
+        is_verified: false,
+        generated_by: None,
+        generation_reason: QrGenerationReason::NewProperty,
+    };
+    let qr_code = QrCodeMetadata {
+        payload: QrPayload::Vcard(contact),
+        ..QrCodeMetadata::new(qr_data.property_id.clone(), vcard, "https://cdn.daobitat.xyz/qr.png".to_string(), metadata)
+    };
+    // The card's link is still a tracked scan URL, so base URL moves still regenerate it
+    assert_eq!(qr_code.encoded_scan_url(), Some(qr_data.scan_url.clone()));
+    assert!(!qr_code.is_stale("https://qr-service.daobitat.xyz"));
+    assert!(qr_code.is_stale("https://new.daobitat.xyz"));
+
+    let geo_code = QrCodeMetadata { payload: QrPayload::Geo, qr_pattern: "geo:-1.2921,36.8219".to_string(), ..qr_code };
+    assert!(!geo_code.is_stale("https://new.daobitat.xyz"));
+}
+
+#[test]
+fn test_limited_use_codes() {
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let unlimited = QrCodeMetadata::new("507f1f77bcf86cd799439011".to_string(), "{}".to_string(), String::new(), metadata);
+    assert_eq!(unlimited.remaining_uses(), None);
+    assert!(!unlimited.is_used_up());
+
+    let limited = QrCodeMetadata { max_scans: Some(3), limited_scans: 1, ..unlimited.clone() };
+    assert_eq!(limited.remaining_uses(), Some(2));
+    assert!(!limited.is_used_up());
+
+    // Deactivated once the last use is spent, and never reported below zero
+    let spent = QrCodeMetadata { limited_scans: 4, is_active: false, ..limited.clone() };
+    assert_eq!(spent.remaining_uses(), Some(0));
+    assert!(spent.is_used_up());
+    assert!(QrCodeMetadata { is_active: false, ..limited }.is_used_up());
+}
+
+#[test]
+fn test_scheduled_window() {
+    let metadata = listing_metadata(crate::models::Property::default().to_qr_info(), QrGenerationReason::NewProperty);
+    let unscheduled = QrCodeMetadata::new("507f1f77bcf86cd799439011".to_string(), "{}".to_string(), String::new(), metadata);
+    let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+    assert_eq!(unscheduled.window(at("2026-07-10T08:00:00Z")), QrCodeWindow::Open);
+
+    // An open house over a weekend
+    let open_house = QrCodeMetadata {
+        active_from: Some(at("2026-07-11T09:00:00Z")),
+        active_until: Some(at("2026-07-12T17:00:00Z")),
+        ..unscheduled.clone()
+    };
+    assert_eq!(open_house.window(at("2026-07-11T08:59:59Z")), QrCodeWindow::NotStarted(at("2026-07-11T09:00:00Z")));
+    assert_eq!(open_house.window(at("2026-07-11T09:00:00Z")), QrCodeWindow::Open);
+    assert_eq!(open_house.window(at("2026-07-12T17:00:00Z")), QrCodeWindow::Ended);
+
+    let until_only = QrCodeMetadata { active_until: Some(at("2026-07-12T17:00:00Z")), ..unscheduled };
+    assert_eq!(until_only.window(at("2026-01-01T00:00:00Z")), QrCodeWindow::Open);
+}
+
+/// A real PNG of a QR code, since generation is still a placeholder
+fn qr_png(content: &str) -> Vec<u8> {
+    let code = qrcode::QrCode::new(content.as_bytes()).unwrap();
+    let image = code.render::<image::Luma<u8>>().min_dimensions(200, 200).build();
+    let mut png = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+    png
+}
+
+#[test]
+fn test_decode_qr_image() {
+    let url = "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011?v=2";
+    assert_eq!(decode_qr_image(&qr_png(url)).unwrap(), url);
+
+    // A photo with no code in it, and files that aren't images
+    let blank = image::GrayImage::from_pixel(200, 200, image::Luma([255]));
+    let mut png = Vec::new();
+    blank.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+    for image in [png.as_slice(), b"", b"not an image"] {
+        assert!(matches!(decode_qr_image(image), Err(QrGeneratorError::DecodeFailed(_))));
+    }
+}
+
+#[test]
+fn test_encode_webp() {
+    let url = "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011";
+    let png = qr_png(url);
+    let webp = encode_webp(&png).unwrap();
+
+    let source = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap();
+    let decoded = image::load_from_memory_with_format(&webp, image::ImageFormat::WebP).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (source.width(), source.height()));
+    // Lossless, so the code still scans
+    assert_eq!(decode_qr_luma(&decoded.to_luma8()).unwrap(), url);
+
+    assert!(encode_webp(b"RIFF\0\0\0\0WEBP").is_err());
+}
+
+#[test]
+fn test_uploaded_image_must_encode_the_scan_url() {
+    let expected = "https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439011";
+    let png = qr_png(&format!("{}?v=3", expected));
+    let decoded = decode_uploaded_image(&png, UploadedImageFormat::Png).unwrap();
+    assert!(check_uploaded_scan_url(&decoded, expected).is_ok());
+
+    // Designed in a vector tool and exported as SVG
+    let svg = qrcode::QrCode::new(expected.as_bytes())
+        .unwrap()
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(120, 120)
+        .build();
+    let decoded = decode_uploaded_image(svg.as_bytes(), UploadedImageFormat::Svg).unwrap();
+    assert!(check_uploaded_scan_url(&decoded, expected).is_ok());
+
+    // Another property's code, or one that isn't a scan code at all
+    let other = decode_qr_image(&qr_png("https://qr-service.daobitat.xyz/scan/507f1f77bcf86cd799439012")).unwrap();
+    assert!(matches!(check_uploaded_scan_url(&other, expected), Err(QrGeneratorError::InvalidPayload(_))));
+    assert!(matches!(check_uploaded_scan_url("WIFI:T:WPA;S:Villa;;", expected), Err(QrGeneratorError::InvalidPayload(_))));
+    assert!(decode_uploaded_image(b"<svg xmlns=\"http://www.w3.org/2000/svg\"", UploadedImageFormat::Svg).is_err());
+}
+}
@@ -0,0 +1,191 @@
+// src/services/qr_style_service.rs
+
+use crate::models::{QrStyle, UpsertQrStyleRequest};
+use crate::services::organization_service::is_hex_color;
+use crate::utils::validation::validate_url;
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::doc,
+    options::{FindOptions, IndexOptions, ReplaceOptions},
+    Collection, Database, IndexModel,
+};
+use tracing::info;
+
+// Widest quiet zone a style may ask for, in modules
+const MAX_QUIET_ZONE: u32 = 16;
+
+#[derive(Clone)]
+pub struct QrStyleService {
+    styles: Collection<QrStyle>,
+}
+
+#[derive(Debug)]
+pub enum QrStyleError {
+    NotFound,
+    InvalidStyle(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for QrStyleError {
+    fn from(err: mongodb::error::Error) -> Self {
+        QrStyleError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for QrStyleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QrStyleError::NotFound => write!(f, "QR style not found"),
+            QrStyleError::InvalidStyle(reason) => write!(f, "Invalid QR style: {}", reason),
+            QrStyleError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for QrStyleError {}
+
+impl QrStyleService {
+    /// Create a new QR style preset service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            styles: db.collection("qr_styles"),
+        }
+    }
+
+    /// Style names are unique
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.styles
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "name": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// All styles, by name
+    pub async fn list_styles(&self) -> Result<Vec<QrStyle>, QrStyleError> {
+        let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+        let styles = self.styles
+            .find(doc! {})
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(styles)
+    }
+
+    /// Get a style by name
+    pub async fn get_style(&self, name: &str) -> Result<QrStyle, QrStyleError> {
+        self.styles
+            .find_one(doc! { "name": name })
+            .await?
+            .ok_or(QrStyleError::NotFound)
+    }
+
+    /// Create or replace a named style; codes already generated keep their image until regenerated
+    pub async fn upsert_style(&self, name: &str, request: UpsertQrStyleRequest) -> Result<QrStyle, QrStyleError> {
+        validate_name(name)?;
+        validate_request(&request)?;
+
+        let filter = doc! { "name": name };
+        let style = match self.styles.find_one(filter.clone()).await? {
+            Some(existing) => existing.apply(request),
+            None => QrStyle::new(name.to_string(), request),
+        };
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.styles
+            .replace_one(filter, &style)
+            .with_options(options)
+            .await?;
+
+        info!("Saved QR style '{}'", name);
+        Ok(style)
+    }
+
+    /// Remove a style; codes generated with it fall back to the defaults when regenerated
+    pub async fn delete_style(&self, name: &str) -> Result<(), QrStyleError> {
+        let result = self.styles.delete_one(doc! { "name": name }).await?;
+
+        if result.deleted_count == 0 {
+            return Err(QrStyleError::NotFound);
+        }
+
+        info!("Removed QR style '{}'", name);
+        Ok(())
+    }
+}
+
+/// Names go in request bodies and URLs: lowercase letters, digits and dashes
+fn validate_name(name: &str) -> Result<(), QrStyleError> {
+    let valid = (1..=40).contains(&name.len())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-');
+
+    if !valid {
+        return Err(QrStyleError::InvalidStyle(
+            "name must be 1-40 lowercase letters, digits or dashes, like 'acme-realty'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Colors must be hex and tell apart, or the code won't scan
+fn validate_request(request: &UpsertQrStyleRequest) -> Result<(), QrStyleError> {
+    for (field, color) in [("foregroundColor", &request.foreground_color), ("backgroundColor", &request.background_color)] {
+        if !is_hex_color(color) {
+            return Err(QrStyleError::InvalidStyle(format!("{} must look like #1A2B3C", field)));
+        }
+    }
+    if request.foreground_color.eq_ignore_ascii_case(&request.background_color) {
+        return Err(QrStyleError::InvalidStyle("foreground and background colors must differ".to_string()));
+    }
+    if request.quiet_zone.is_some_and(|quiet_zone| quiet_zone > MAX_QUIET_ZONE) {
+        return Err(QrStyleError::InvalidStyle(format!("quietZone must be at most {} modules", MAX_QUIET_ZONE)));
+    }
+    if let Some(logo_url) = &request.logo_url {
+        validate_url(logo_url, "logoUrl").map_err(|e| QrStyleError::InvalidStyle(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(foreground: &str, background: &str, quiet_zone: Option<u32>) -> UpsertQrStyleRequest {
+        UpsertQrStyleRequest {
+            foreground_color: foreground.to_string(),
+            background_color: background.to_string(),
+            rounded_modules: false,
+            quiet_zone,
+            logo_url: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("acme-realty").is_ok());
+        assert!(validate_name("brand2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("-acme").is_err());
+        assert!(validate_name("Acme").is_err());
+        assert!(validate_name("acme/realty").is_err());
+        assert!(validate_name(&"a".repeat(41)).is_err());
+    }
+
+    #[test]
+    fn test_validate_request() {
+        assert!(validate_request(&request("#0B3D91", "#FFFFFF", Some(2))).is_ok());
+        assert!(validate_request(&request("blue", "#FFFFFF", None)).is_err());
+        assert!(validate_request(&request("#ffffff", "#FFFFFF", None)).is_err());
+        assert!(validate_request(&request("#0B3D91", "#FFFFFF", Some(17))).is_err());
+
+        let bad_logo = UpsertQrStyleRequest { logo_url: Some("ftp://logo".to_string()), ..request("#000000", "#FFFFFF", None) };
+        assert!(validate_request(&bad_logo).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+// src/services/scan_cap_service.rs
+
+use crate::models::{HookEvent, ScanCap, UpsertScanCapRequest};
+use crate::services::HookService;
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, to_bson, DateTime as BsonDateTime, Document},
+    options::{IndexOptions, ReplaceOptions},
+    Collection, Database, IndexModel,
+};
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct ScanCapService {
+    caps: Collection<ScanCap>,
+    scan_events: Collection<Document>,
+    hooks: Option<HookService>,
+}
+
+#[derive(Debug)]
+pub enum ScanCapError {
+    NotFound,
+    InvalidCap(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for ScanCapError {
+    fn from(err: mongodb::error::Error) -> Self {
+        ScanCapError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for ScanCapError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        ScanCapError::DatabaseError(err.into())
+    }
+}
+
+impl std::fmt::Display for ScanCapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanCapError::NotFound => write!(f, "Scan cap not found"),
+            ScanCapError::InvalidCap(reason) => write!(f, "Invalid scan cap: {}", reason),
+            ScanCapError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScanCapError {}
+
+impl ScanCapService {
+    /// Create a new scan cap service
+    pub fn new(db: &Database) -> Self {
+        Self {
+            caps: db.collection("scan_caps"),
+            scan_events: db.collection("scan_events"),
+            hooks: None,
+        }
+    }
+
+    /// Notify REST hook subscribers when a cap trips
+    pub fn with_hooks(mut self, hooks: HookService) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// One cap per property
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.caps
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "propertyId": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Get the cap for a property
+    pub async fn get_cap(&self, property_id: &str) -> Result<ScanCap, ScanCapError> {
+        self.caps
+            .find_one(doc! { "propertyId": property_id })
+            .await?
+            .ok_or(ScanCapError::NotFound)
+    }
+
+    /// Create or replace the cap for a property
+    pub async fn upsert_cap(&self, property_id: &str, request: UpsertScanCapRequest) -> Result<ScanCap, ScanCapError> {
+        if request.max_scans_per_day < 1 {
+            return Err(ScanCapError::InvalidCap("maxScansPerDay must be at least 1".to_string()));
+        }
+
+        let filter = doc! { "propertyId": property_id };
+        let existing = self.caps.find_one(filter.clone()).await?;
+        let cap = existing
+            .unwrap_or_else(|| ScanCap::new(property_id.to_string(), request.max_scans_per_day))
+            .apply(request);
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.caps.replace_one(filter, &cap).with_options(options).await?;
+
+        info!("Set scan cap for property {} to {} per day", property_id, cap.max_scans_per_day);
+        Ok(cap)
+    }
+
+    /// Remove the cap for a property
+    pub async fn delete_cap(&self, property_id: &str) -> Result<(), ScanCapError> {
+        let result = self.caps.delete_one(doc! { "propertyId": property_id }).await?;
+
+        if result.deleted_count == 0 {
+            return Err(ScanCapError::NotFound);
+        }
+
+        info!("Removed scan cap for property {}", property_id);
+        Ok(())
+    }
+
+    /// Lift today's pause; the cap applies again from tomorrow
+    pub async fn resume(&self, property_id: &str) -> Result<ScanCap, ScanCapError> {
+        let mut cap = self.get_cap(property_id).await?;
+
+        let now = Utc::now();
+        cap.paused_at = None;
+        cap.resumed_at = Some(now);
+        cap.updated_at = now;
+        self.caps.replace_one(doc! { "_id": cap.id }, &cap).await?;
+
+        info!("Resumed scans for capped property {}", property_id);
+        Ok(cap)
+    }
+
+    /// Human scans of a property since midnight UTC
+    pub async fn scans_today(&self, property_id: &str) -> Result<i64, mongodb::error::Error> {
+        let today_start = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let count = self.scan_events
+            .count_documents(doc! {
+                "propertyId": property_id,
+                "isBot": { "$ne": true },
+                "scannedAt": { "$gte": BsonDateTime::from_millis(today_start.timestamp_millis()) }
+            })
+            .await?;
+        Ok(count as i64)
+    }
+
+    /// Whether a scan should get the waitlist page, pausing the property when today's cap is reached
+    pub async fn check(&self, property_id: &str) -> Result<bool, ScanCapError> {
+        let Some(cap) = self.caps.find_one(doc! { "propertyId": property_id, "enabled": true }).await? else {
+            return Ok(false);
+        };
+
+        let now = Utc::now();
+        if cap.is_paused(now) {
+            return Ok(true);
+        }
+        if cap.is_resumed(now) {
+            return Ok(false);
+        }
+
+        let scans_today = self.scans_today(property_id).await?;
+        if scans_today < cap.max_scans_per_day {
+            return Ok(false);
+        }
+
+        // Only the scan that flips pausedAt notifies; concurrent ones see it already changed
+        let result = self.caps
+            .update_one(
+                doc! { "_id": cap.id, "pausedAt": to_bson(&cap.paused_at)? },
+                doc! { "$set": { "pausedAt": to_bson(&now)? } },
+            )
+            .await?;
+        if result.modified_count == 1 {
+            warn!("Property {} reached its cap of {} scans today; pausing", property_id, cap.max_scans_per_day);
+            self.dispatch(HookEvent::ScanCapReached, property_id, serde_json::json!({
+                "id": cap.id.to_hex(),
+                "event": HookEvent::ScanCapReached,
+                "propertyId": property_id,
+                "maxScansPerDay": cap.max_scans_per_day,
+                "scansToday": scans_today,
+                "pausedAt": now.to_rfc3339(),
+            }));
+        }
+
+        Ok(true)
+    }
+
+    fn dispatch(&self, event: HookEvent, property_id: &str, payload: serde_json::Value) {
+        if let Some(hooks) = &self.hooks {
+            hooks.dispatch(event, property_id, payload);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration};
+
+    fn at(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_pause_lasts_until_midnight() {
+        let mut cap = ScanCap::new("507f1f77bcf86cd799439011".to_string(), 50);
+        let now = at("2026-03-14T15:00:00Z");
+        assert!(!cap.is_paused(now));
+
+        cap.paused_at = Some(at("2026-03-14T09:30:00Z"));
+        assert!(cap.is_paused(now));
+        assert!(!cap.is_paused(at("2026-03-15T00:00:00Z")));
+
+        cap.enabled = false;
+        assert!(!cap.is_paused(now));
+    }
+
+    #[test]
+    fn test_resume_holds_for_the_day() {
+        let mut cap = ScanCap::new("507f1f77bcf86cd799439011".to_string(), 50);
+        let now = at("2026-03-14T15:00:00Z");
+        cap.resumed_at = Some(now - Duration::hours(1));
+
+        assert!(cap.is_resumed(now));
+        assert!(!cap.is_resumed(now + Duration::days(1)));
+    }
+}
@@ -0,0 +1,246 @@
+// src/services/share_offer_service.rs
+
+use crate::models::{CreateShareOfferQrRequest, ScanEvent, ShareOfferQrCode, MAX_SHARE_OFFER_LABEL_CHARS};
+use crate::services::{property_service::PropertyError, PropertyService, QrGeneratorService};
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson},
+    Collection, Database, IndexModel,
+};
+use tracing::info;
+
+#[derive(Clone)]
+pub struct ShareOfferService {
+    offers: Collection<ShareOfferQrCode>,
+    property_service: PropertyService,
+    qr_generator: QrGeneratorService,
+    base_url: String,
+    daobitar_base_url: String,
+}
+
+#[derive(Debug)]
+pub enum ShareOfferError {
+    NotFound, // No active offer with that ID on the property
+    PropertyNotFound,
+    InvalidPropertyId,
+    NotCoOwned,
+    SoldOut, // The property has no shares left to offer
+    Validation(String),
+    GenerationFailed(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for ShareOfferError {
+    fn from(err: mongodb::error::Error) -> Self {
+        ShareOfferError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for ShareOfferError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        ShareOfferError::DatabaseError(err.into())
+    }
+}
+
+impl From<PropertyError> for ShareOfferError {
+    fn from(err: PropertyError) -> Self {
+        match err {
+            PropertyError::NotFound | PropertyError::NotEligibleForQr(_) => ShareOfferError::PropertyNotFound,
+            PropertyError::InvalidId => ShareOfferError::InvalidPropertyId,
+            PropertyError::DatabaseError(e) => ShareOfferError::DatabaseError(e),
+        }
+    }
+}
+
+impl std::fmt::Display for ShareOfferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareOfferError::NotFound => write!(f, "Share offer not found"),
+            ShareOfferError::PropertyNotFound => write!(f, "Property not found"),
+            ShareOfferError::InvalidPropertyId => write!(f, "Invalid property ID"),
+            ShareOfferError::NotCoOwned => write!(f, "Property is not co-owned"),
+            ShareOfferError::SoldOut => write!(f, "Property has no shares available"),
+            ShareOfferError::Validation(reason) => write!(f, "{}", reason),
+            ShareOfferError::GenerationFailed(reason) => write!(f, "Share offer QR generation failed: {}", reason),
+            ShareOfferError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ShareOfferError {}
+
+impl ShareOfferService {
+    /// Create a new share offer service
+    pub fn new(
+        db: &Database,
+        property_service: PropertyService,
+        qr_generator: QrGeneratorService,
+        base_url: String,
+        daobitar_base_url: String,
+    ) -> Self {
+        Self {
+            offers: db.collection("share_offer_qr_codes"),
+            property_service,
+            qr_generator,
+            base_url,
+            daobitar_base_url,
+        }
+    }
+
+    /// DAO-Bitat base URL the offers' purchase links point at
+    pub fn daobitar_base_url(&self) -> &str {
+        &self.daobitar_base_url
+    }
+
+    /// Index for listing a property's offers, newest first
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.offers
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": -1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Generate the code for a new offer of a co-owned property's available shares
+    pub async fn create(
+        &self,
+        request: CreateShareOfferQrRequest,
+        generated_by: Option<ObjectId>,
+    ) -> Result<ShareOfferQrCode, ShareOfferError> {
+        let property = self.property_service.get_property_by_id(&request.property_id).await?;
+        if !property.co_owned {
+            return Err(ShareOfferError::NotCoOwned);
+        }
+        if property.available_shares <= 0 {
+            return Err(ShareOfferError::SoldOut);
+        }
+
+        let shares = request.shares.unwrap_or(property.available_shares);
+        if shares < 1 || shares > property.available_shares {
+            return Err(ShareOfferError::Validation(format!(
+                "shares must be between 1 and the {} available",
+                property.available_shares
+            )));
+        }
+
+        let label = request.label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty());
+        if label.as_ref().is_some_and(|label| label.chars().count() > MAX_SHARE_OFFER_LABEL_CHARS) {
+            return Err(ShareOfferError::Validation(format!(
+                "label must be at most {} characters",
+                MAX_SHARE_OFFER_LABEL_CHARS
+            )));
+        }
+
+        let offer_id = ObjectId::new();
+        let scan_url = ShareOfferQrCode::scan_url_for(&self.base_url, &request.property_id, &offer_id);
+        let qr_code_url = self.qr_generator
+            .upload_standalone_qr(&ShareOfferQrCode::image_key_for(&request.property_id, &offer_id), &scan_url)
+            .await
+            .map_err(|e| ShareOfferError::GenerationFailed(e.to_string()))?;
+
+        let offer = ShareOfferQrCode::new(
+            offer_id,
+            request.property_id,
+            shares,
+            label,
+            &self.base_url,
+            qr_code_url,
+            generated_by,
+        );
+        self.offers.insert_one(&offer).await?;
+
+        info!("Created share offer {} for {} shares of property {}", offer.id, shares, offer.property_id);
+        Ok(offer)
+    }
+
+    /// A property's offers, newest first, with their scan counts
+    pub async fn list_for_property(&self, property_id: &str) -> Result<Vec<ShareOfferQrCode>, ShareOfferError> {
+        let offers = self.offers
+            .find(doc! { "propertyId": property_id })
+            .sort(doc! { "createdAt": -1 })
+            .await?
+            .try_collect()
+            .await?;
+        Ok(offers)
+    }
+
+    /// Where a scan of an active offer goes: the purchase flow for the shares still on offer
+    pub async fn purchase_url(&self, property_id: &str, offer_id: &str) -> Result<String, ShareOfferError> {
+        let offer_id = ObjectId::parse_str(offer_id).map_err(|_| ShareOfferError::NotFound)?;
+        let offer = self.offers
+            .find_one(doc! { "_id": offer_id, "propertyId": property_id, "active": true })
+            .await?
+            .ok_or(ShareOfferError::NotFound)?;
+
+        let property = self.property_service.get_property_by_id(property_id).await?;
+        let shares = offer.shares_on_offer(property.available_shares);
+        if !property.co_owned || shares == 0 {
+            return Err(ShareOfferError::SoldOut);
+        }
+        Ok(offer.purchase_url(&self.daobitar_base_url, shares))
+    }
+
+    /// Count a scan of an offer's code, unless a bot made it
+    pub async fn record_scan(&self, offer_id: &str, user_agent: Option<&str>) -> Result<(), ShareOfferError> {
+        if user_agent.is_some_and(ScanEvent::is_bot_user_agent) {
+            return Ok(());
+        }
+
+        let offer_id = ObjectId::parse_str(offer_id).map_err(|_| ShareOfferError::NotFound)?;
+        self.offers
+            .update_one(
+                doc! { "_id": offer_id },
+                doc! {
+                    "$inc": { "scans": 1i64 },
+                    "$set": { "lastScannedAt": to_bson(&Utc::now())? },
+                },
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{AnalyticsService, S3Storage, StorageService};
+    use mongodb::Client;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_share_offer_scan_leaves_listing_scans_alone() {
+        let client = Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .expect("Failed to connect to MongoDB");
+        let db = client.database("test_share_offers");
+        let property_service = PropertyService::new(&db);
+        let storage = StorageService::new(Arc::new(
+            S3Storage::new("test-bucket".to_string(), "us-east-1".to_string()).expect("Failed to create S3 storage"),
+        ));
+        let base_url = "https://qr-service.daobitat.xyz".to_string();
+        let qr_generator = QrGeneratorService::new(&db, property_service.clone(), storage, base_url.clone());
+        let service = ShareOfferService::new(&db, property_service, qr_generator, base_url.clone(), "https://www.daobitat.xyz".to_string());
+        let analytics_service = AnalyticsService::new(&db);
+
+        let property_id = ObjectId::new().to_hex();
+        let offer = ShareOfferQrCode::new(ObjectId::new(), property_id.clone(), 10, None, &base_url, String::new(), None);
+        service.offers.insert_one(&offer).await.expect("Failed to store share offer");
+
+        let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Mobile/15E148 Safari/604.1";
+        let offer_id = offer.id.to_hex();
+        service.record_scan(&offer_id, Some(iphone)).await.expect("Failed to record scan");
+        service.record_scan(&offer_id, Some("Twitterbot/1.0")).await.expect("Failed to record scan");
+
+        let offer = service.offers.find_one(doc! { "_id": offer.id }).await.unwrap().expect("Share offer missing");
+        assert_eq!(offer.scans, 1);
+        assert!(offer.last_scanned_at.is_some());
+
+        let analytics = analytics_service.get_property_analytics(&property_id, true)
+            .await
+            .expect("Failed to get analytics");
+        assert_eq!(analytics.analytics.total_scans, 0);
+        assert!(analytics.recent_scans.is_empty());
+    }
+}
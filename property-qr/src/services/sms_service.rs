@@ -0,0 +1,186 @@
+// src/services/sms_service.rs
+
+use crate::config::{SmsConfig, SmsProviderKind};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const TWILIO_API_URL: &str = "https://api.twilio.com/2010-04-01/Accounts";
+const AFRICASTALKING_API_URL: &str = "https://api.africastalking.com/version1/messaging";
+const SEND_TIMEOUT_SECS: u64 = 10;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+pub struct SmsService {
+    config: SmsConfig,
+    http_client: reqwest::Client,
+    // Send timestamps per client IP inside the current window
+    recent_sends: Arc<Mutex<HashMap<String, Vec<Instant>>>>,
+}
+
+#[derive(Debug)]
+pub enum SmsError {
+    Disabled,
+    RateLimited,
+    InvalidPhone(String),
+    ProviderError(String),
+}
+
+impl std::fmt::Display for SmsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmsError::Disabled => write!(f, "SMS sending is not configured"),
+            SmsError::RateLimited => write!(f, "Too many SMS requests, please try again later"),
+            SmsError::InvalidPhone(reason) => write!(f, "Invalid phone number: {}", reason),
+            SmsError::ProviderError(reason) => write!(f, "SMS provider error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SmsError {}
+
+impl SmsService {
+    /// Create a new SMS service
+    pub fn new(config: SmsConfig) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(SEND_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            config,
+            http_client,
+            recent_sends: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Whether an SMS provider is configured
+    pub fn is_enabled(&self) -> bool {
+        self.config.provider != SmsProviderKind::Disabled
+    }
+
+    /// Reserve a send slot for a client IP, failing once the hourly limit is reached
+    pub fn check_rate_limit(&self, client_ip: &str) -> Result<(), SmsError> {
+        let now = Instant::now();
+        let mut recent_sends = self.recent_sends.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Drop expired entries so the map doesn't grow without bound
+        recent_sends.retain(|_, sends| {
+            sends.retain(|sent_at| now.duration_since(*sent_at) < RATE_LIMIT_WINDOW);
+            !sends.is_empty()
+        });
+
+        let sends = recent_sends.entry(client_ip.to_string()).or_default();
+        if sends.len() >= self.config.max_per_ip_per_hour as usize {
+            return Err(SmsError::RateLimited);
+        }
+
+        sends.push(now);
+        Ok(())
+    }
+
+    /// Send a text message through the configured provider
+    pub async fn send(&self, to: &str, message: &str) -> Result<(), SmsError> {
+        crate::utils::validate_phone_number(to)
+            .map_err(|e| SmsError::InvalidPhone(e.message))?;
+        let to = normalize_phone_number(to);
+
+        let (account_id, api_key) = match (&self.config.account_id, &self.config.api_key) {
+            (Some(account_id), Some(api_key)) if self.is_enabled() => (account_id, api_key),
+            _ => return Err(SmsError::Disabled),
+        };
+
+        let request = match self.config.provider {
+            SmsProviderKind::Twilio => {
+                let mut form = vec![("To", to.as_str()), ("Body", message)];
+                if let Some(sender_id) = &self.config.sender_id {
+                    form.push(("From", sender_id));
+                }
+
+                self.http_client
+                    .post(format!("{}/{}/Messages.json", TWILIO_API_URL, account_id))
+                    .basic_auth(account_id, Some(api_key))
+                    .form(&form)
+            }
+            SmsProviderKind::AfricasTalking => {
+                let mut form = vec![("username", account_id.as_str()), ("to", to.as_str()), ("message", message)];
+                if let Some(sender_id) = &self.config.sender_id {
+                    form.push(("from", sender_id));
+                }
+
+                self.http_client
+                    .post(AFRICASTALKING_API_URL)
+                    .header("apiKey", api_key)
+                    .header("Accept", "application/json")
+                    .form(&form)
+            }
+            SmsProviderKind::Disabled => return Err(SmsError::Disabled),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SmsError::ProviderError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            warn!("SMS provider rejected message with status {}", response.status());
+            return Err(SmsError::ProviderError(format!("status {}", response.status())));
+        }
+
+        info!("Sent SMS via {:?}", self.config.provider);
+        Ok(())
+    }
+}
+
+/// Strip separators, keeping a leading + for international numbers
+fn normalize_phone_number(phone: &str) -> String {
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    if phone.trim_start().starts_with('+') {
+        format!("+{}", digits)
+    } else {
+        digits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_service(max_per_ip_per_hour: u32) -> SmsService {
+        SmsService::new(SmsConfig {
+            provider: SmsProviderKind::Disabled,
+            account_id: None,
+            api_key: None,
+            sender_id: None,
+            max_per_ip_per_hour,
+        })
+    }
+
+    #[test]
+    fn test_rate_limit_per_ip() {
+        let service = test_service(2);
+
+        assert!(service.check_rate_limit("203.0.113.7").is_ok());
+        assert!(service.check_rate_limit("203.0.113.7").is_ok());
+        assert!(matches!(service.check_rate_limit("203.0.113.7"), Err(SmsError::RateLimited)));
+
+        // Other clients are unaffected
+        assert!(service.check_rate_limit("203.0.113.8").is_ok());
+    }
+
+    #[test]
+    fn test_normalize_phone_number() {
+        assert_eq!(normalize_phone_number("+254 712-345 678"), "+254712345678");
+        assert_eq!(normalize_phone_number("(0712) 345678"), "0712345678");
+    }
+
+    #[tokio::test]
+    async fn test_send_when_disabled() {
+        let service = test_service(3);
+
+        assert!(!service.is_enabled());
+        assert!(matches!(service.send("+254712345678", "hi").await, Err(SmsError::Disabled)));
+        assert!(matches!(service.send("abc", "hi").await, Err(SmsError::InvalidPhone(_))));
+    }
+}
@@ -0,0 +1,325 @@
+// src/services/tracking_service.rs
+
+use crate::models::{
+    ForwardedScan, Ga4Config, MetaPixelConfig, ScanEvent, TrackingConfig, TrackingConsent,
+    UpsertTrackingConfigRequest,
+};
+use mongodb::{bson::doc, options::ReplaceOptions, Collection, Database};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{info, warn};
+
+const GA4_COLLECT_URL: &str = "https://www.google-analytics.com/mp/collect";
+const META_GRAPH_URL: &str = "https://graph.facebook.com/v19.0";
+const GA4_EVENT_NAME: &str = "qr_scan";
+const META_EVENT_NAME: &str = "QRScan";
+const FORWARD_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Clone)]
+pub struct TrackingService {
+    configs: Collection<TrackingConfig>,
+    http_client: reqwest::Client,
+}
+
+#[derive(Debug)]
+pub enum TrackingError {
+    NotFound,
+    InvalidConfig(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for TrackingError {
+    fn from(err: mongodb::error::Error) -> Self {
+        TrackingError::DatabaseError(err)
+    }
+}
+
+impl std::fmt::Display for TrackingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackingError::NotFound => write!(f, "Tracking config not found"),
+            TrackingError::InvalidConfig(reason) => write!(f, "Invalid tracking config: {}", reason),
+            TrackingError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TrackingError {}
+
+impl TrackingService {
+    /// Create a new tracking service
+    pub fn new(db: &Database) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FORWARD_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            configs: db.collection("tracking_configs"),
+            http_client,
+        }
+    }
+
+    /// Get the tracking config for an owner
+    pub async fn get_config(&self, owner_id: &str) -> Result<TrackingConfig, TrackingError> {
+        self.configs
+            .find_one(doc! { "ownerId": owner_id })
+            .await?
+            .ok_or(TrackingError::NotFound)
+    }
+
+    /// Create or replace the tracking config for an owner
+    pub async fn upsert_config(
+        &self,
+        owner_id: &str,
+        request: UpsertTrackingConfigRequest,
+    ) -> Result<TrackingConfig, TrackingError> {
+        validate_request(&request)?;
+
+        let existing = self.configs.find_one(doc! { "ownerId": owner_id }).await?;
+        let config = existing
+            .unwrap_or_else(|| TrackingConfig::new(owner_id.to_string()))
+            .apply(request);
+
+        let options = ReplaceOptions::builder().upsert(true).build();
+        self.configs
+            .replace_one(doc! { "ownerId": owner_id }, &config)
+            .with_options(options)
+            .await?;
+
+        info!("Updated tracking config for owner {}", owner_id);
+        Ok(config)
+    }
+
+    /// Remove the tracking config for an owner
+    pub async fn delete_config(&self, owner_id: &str) -> Result<(), TrackingError> {
+        let result = self.configs.delete_one(doc! { "ownerId": owner_id }).await?;
+
+        if result.deleted_count == 0 {
+            return Err(TrackingError::NotFound);
+        }
+
+        info!("Removed tracking config for owner {}", owner_id);
+        Ok(())
+    }
+
+    /// Forward a scan to the owner's GA4 and Meta destinations in the background
+    pub fn forward_scan(&self, owner_id: &str, scan: ForwardedScan, consent: TrackingConsent) {
+        if consent == TrackingConsent::Denied {
+            return;
+        }
+        if scan.user_agent.as_deref().is_some_and(ScanEvent::is_bot_user_agent) {
+            return;
+        }
+
+        let service = self.clone();
+        let owner_id = owner_id.to_string();
+
+        tokio::spawn(async move {
+            let config = match service.get_config(&owner_id).await {
+                Ok(config) => config,
+                Err(TrackingError::NotFound) => return,
+                Err(e) => {
+                    warn!("Failed to load tracking config for owner {}: {}", owner_id, e);
+                    return;
+                }
+            };
+
+            if !config.allows(consent) {
+                return;
+            }
+
+            if let Some(ga4) = &config.ga4 {
+                service.send_ga4(ga4, &scan).await;
+            }
+            if let Some(meta_pixel) = &config.meta_pixel {
+                service.send_meta(meta_pixel, &scan).await;
+            }
+        });
+    }
+
+    async fn send_ga4(&self, ga4: &Ga4Config, scan: &ForwardedScan) {
+        let result = self.http_client
+            .post(GA4_COLLECT_URL)
+            .query(&[("measurement_id", &ga4.measurement_id), ("api_secret", &ga4.api_secret)])
+            .json(&ga4_payload(scan))
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => warn!("GA4 forwarding for scan {} failed with status {}", scan.scan_id, response.status()),
+            Err(e) => warn!("GA4 forwarding for scan {} failed: {}", scan.scan_id, e),
+        }
+    }
+
+    async fn send_meta(&self, meta_pixel: &MetaPixelConfig, scan: &ForwardedScan) {
+        let result = self.http_client
+            .post(format!("{}/{}/events", META_GRAPH_URL, meta_pixel.pixel_id))
+            .json(&meta_payload(meta_pixel, scan))
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => warn!("Meta forwarding for scan {} failed with status {}", scan.scan_id, response.status()),
+            Err(e) => warn!("Meta forwarding for scan {} failed: {}", scan.scan_id, e),
+        }
+    }
+}
+
+fn validate_request(request: &UpsertTrackingConfigRequest) -> Result<(), TrackingError> {
+    if let Some(ga4) = &request.ga4 {
+        if !ga4.measurement_id.starts_with("G-") {
+            return Err(TrackingError::InvalidConfig("GA4 measurement ID must start with G-".to_string()));
+        }
+        if ga4.api_secret.trim().is_empty() {
+            return Err(TrackingError::InvalidConfig("GA4 API secret is required".to_string()));
+        }
+    }
+
+    if let Some(meta_pixel) = &request.meta_pixel {
+        if meta_pixel.pixel_id.is_empty() || !meta_pixel.pixel_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(TrackingError::InvalidConfig("Meta pixel ID must be numeric".to_string()));
+        }
+        if meta_pixel.access_token.trim().is_empty() {
+            return Err(TrackingError::InvalidConfig("Meta access token is required".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a GA4 Measurement Protocol body for a scan
+fn ga4_payload(scan: &ForwardedScan) -> Value {
+    json!({
+        "client_id": scan.visitor_id,
+        "timestamp_micros": scan.scanned_at.timestamp_micros(),
+        "events": [{
+            "name": GA4_EVENT_NAME,
+            "params": {
+                "property_id": scan.property_id,
+                "scan_id": scan.scan_id,
+                "scan_source": scan.scan_source,
+                "engagement_time_msec": 1
+            }
+        }]
+    })
+}
+
+/// Build a Meta Conversions API body for a scan
+fn meta_payload(meta_pixel: &MetaPixelConfig, scan: &ForwardedScan) -> Value {
+    use sha2::{Digest, Sha256};
+
+    // Meta requires identifiers to be SHA-256 hashed before sending
+    let external_id = format!("{:x}", Sha256::digest(scan.visitor_id.as_bytes()));
+
+    let mut body = json!({
+        "data": [{
+            "event_name": META_EVENT_NAME,
+            "event_time": scan.scanned_at.timestamp(),
+            "event_id": scan.scan_id,
+            "action_source": "physical_store",
+            "user_data": {
+                "external_id": [external_id],
+                "client_ip_address": scan.ip_address,
+                "client_user_agent": scan.user_agent
+            },
+            "custom_data": {
+                "property_id": scan.property_id,
+                "scan_source": scan.scan_source
+            }
+        }],
+        "access_token": meta_pixel.access_token
+    });
+
+    if let Some(test_event_code) = &meta_pixel.test_event_code {
+        body["test_event_code"] = json!(test_event_code);
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn sample_scan() -> ForwardedScan {
+        ForwardedScan {
+            scan_id: "507f1f77bcf86cd799439011".to_string(),
+            property_id: "507f1f77bcf86cd799439012".to_string(),
+            visitor_id: "abc123".to_string(),
+            scan_source: "qr_code".to_string(),
+            user_agent: Some("Mozilla/5.0 (iPhone)".to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+            scanned_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_ga4_payload() {
+        let payload = ga4_payload(&sample_scan());
+
+        assert_eq!(payload["client_id"], "abc123");
+        assert_eq!(payload["events"][0]["name"], GA4_EVENT_NAME);
+        assert_eq!(payload["events"][0]["params"]["property_id"], "507f1f77bcf86cd799439012");
+    }
+
+    #[test]
+    fn test_meta_payload_hashes_visitor() {
+        let meta_pixel = MetaPixelConfig {
+            pixel_id: "1234567890".to_string(),
+            access_token: "token".to_string(),
+            test_event_code: Some("TEST123".to_string()),
+        };
+        let payload = meta_payload(&meta_pixel, &sample_scan());
+
+        let external_id = payload["data"][0]["user_data"]["external_id"][0].as_str().unwrap();
+        assert_eq!(external_id.len(), 64);
+        assert_ne!(external_id, "abc123");
+        assert_eq!(payload["data"][0]["event_id"], "507f1f77bcf86cd799439011");
+        assert_eq!(payload["test_event_code"], "TEST123");
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let valid = UpsertTrackingConfigRequest {
+            ga4: Some(Ga4Config {
+                measurement_id: "G-ABC123".to_string(),
+                api_secret: "secret".to_string(),
+            }),
+            meta_pixel: None,
+            require_consent: None,
+            enabled: None,
+        };
+        assert!(validate_request(&valid).is_ok());
+
+        let invalid = UpsertTrackingConfigRequest {
+            ga4: None,
+            meta_pixel: Some(MetaPixelConfig {
+                pixel_id: "not-a-pixel".to_string(),
+                access_token: "token".to_string(),
+                test_event_code: None,
+            }),
+            require_consent: None,
+            enabled: None,
+        };
+        assert!(validate_request(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_consent_gating() {
+        let mut config = TrackingConfig::new("owner".to_string());
+        assert!(config.allows(TrackingConsent::Granted));
+        assert!(!config.allows(TrackingConsent::Unknown));
+        assert!(!config.allows(TrackingConsent::Denied));
+
+        config.require_consent = false;
+        assert!(config.allows(TrackingConsent::Unknown));
+        assert!(!config.allows(TrackingConsent::Denied));
+
+        config.enabled = false;
+        assert!(!config.allows(TrackingConsent::Granted));
+    }
+}
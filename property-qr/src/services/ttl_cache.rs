@@ -0,0 +1,166 @@
+// src/services/ttl_cache.rs
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// In-memory map whose entries expire after a TTL. Once `capacity` keys are held, expired
+/// entries are dropped first and then the oldest live one; a capacity of 0 caches nothing.
+/// Cheap to clone, and clones share their entries.
+pub struct TtlCache<K, V> {
+    entries: Arc<Mutex<HashMap<K, CachedEntry<V>>>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+struct CachedEntry<V> {
+    value: V,
+    cached_at: Instant,
+}
+
+// Derived Clone would needlessly require K and V to be Clone
+impl<K, V> Clone for TtlCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: Arc::clone(&self.entries),
+            ttl: self.ttl,
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> TtlCache<K, V> {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// A copy of the value cached under `key` within the TTL
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.read(key, V::clone)
+    }
+
+    /// Look into the value cached under `key` within the TTL without copying all of it
+    pub fn read<Q, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.get(key)
+            .filter(|entry| entry.cached_at.elapsed() < self.ttl)
+            .map(|entry| f(&entry.value))
+    }
+
+    /// Cache a value, restarting its TTL
+    pub fn insert(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.make_room(&mut entries, &key);
+        entries.insert(key, CachedEntry { value, cached_at: Instant::now() });
+    }
+
+    /// Change the value cached under `key` in place, keeping its TTL; an expired or missing
+    /// entry starts over from the default
+    pub fn update(&self, key: K, f: impl FnOnce(&mut V))
+    where
+        V: Default,
+    {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.get(&key).is_some_and(|entry| entry.cached_at.elapsed() >= self.ttl) {
+            entries.remove(&key);
+        }
+        self.make_room(&mut entries, &key);
+        let entry = entries.entry(key)
+            .or_insert_with(|| CachedEntry { value: V::default(), cached_at: Instant::now() });
+        f(&mut entry.value);
+    }
+
+    /// Drop the entry for `key`; true if there was one, live or not
+    pub fn remove<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(key).is_some()
+    }
+
+    /// Entries held, including expired ones not yet dropped
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Free a slot for a new key when full: expired entries first, then the oldest live one
+    fn make_room(&self, entries: &mut HashMap<K, CachedEntry<V>>, key: &K) {
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            entries.retain(|_, entry| entry.cached_at.elapsed() < self.ttl);
+        }
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            if let Some(oldest) = entries.iter().min_by_key(|(_, entry)| entry.cached_at).map(|(key, _)| key.clone()) {
+                entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_and_eviction() {
+        let cache = TtlCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("c".to_string(), 3);
+        assert!(cache.get("a").is_none()); // Oldest evicted once full
+        assert_eq!(cache.get("c"), Some(3));
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.remove("c"));
+        assert!(!cache.remove("c"));
+
+        let expired = TtlCache::new(Duration::ZERO, 2);
+        expired.insert("a".to_string(), 1);
+        assert!(expired.get("a").is_none());
+
+        let disabled = TtlCache::new(Duration::from_secs(60), 0);
+        disabled.insert("a".to_string(), 1);
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_update_in_place() {
+        let cache: TtlCache<String, Vec<u32>> = TtlCache::new(Duration::from_secs(60), 2);
+        cache.update("a".to_string(), |values| values.push(1));
+        cache.update("a".to_string(), |values| values.push(2));
+        assert_eq!(cache.read("a", |values| values.len()), Some(2));
+
+        let expired: TtlCache<String, Vec<u32>> = TtlCache::new(Duration::ZERO, 2);
+        expired.update("a".to_string(), |values| values.push(1));
+        expired.update("a".to_string(), |values| values.push(2));
+        assert_eq!(expired.len(), 1);
+        assert!(expired.read("a", |values| values.clone()).is_none());
+    }
+}
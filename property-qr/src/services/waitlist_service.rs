@@ -0,0 +1,466 @@
+// src/services/waitlist_service.rs
+
+use crate::models::{HookEvent, JoinWaitlistRequest, LeadHookPayload, Property, PropertyQrInfo, WaitlistEntry, WaitlistReason};
+use crate::services::{HookService, NotificationService, PropertyService};
+use chrono::{DateTime, Duration, Utc};
+use futures_util::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, to_bson},
+    options::FindOptions,
+    Collection, Database, IndexModel,
+};
+use std::collections::{hash_map::Entry, HashMap};
+use tracing::{error, info, warn};
+
+// Pending entries handled per notification sweep, oldest first
+const NOTIFY_BATCH_SIZE: i64 = 500;
+
+// Prospects stop hearing from us once their entry is this old
+const WAITLIST_MAX_AGE_DAYS: i64 = 90;
+
+// A listing counts as similar within this much of the original price
+const SIMILAR_PRICE_TOLERANCE_PERCENT: i64 = 20;
+
+#[derive(Clone)]
+pub struct WaitlistService {
+    entries: Collection<WaitlistEntry>,
+    property_service: PropertyService,
+    notifications: Option<NotificationService>,
+    hooks: Option<HookService>,
+    daobitat_base_url: String,
+}
+
+#[derive(Debug)]
+pub enum WaitlistError {
+    InvalidContact(String),
+    DatabaseError(mongodb::error::Error),
+}
+
+impl From<mongodb::error::Error> for WaitlistError {
+    fn from(err: mongodb::error::Error) -> Self {
+        WaitlistError::DatabaseError(err)
+    }
+}
+
+impl From<mongodb::bson::ser::Error> for WaitlistError {
+    fn from(err: mongodb::bson::ser::Error) -> Self {
+        WaitlistError::DatabaseError(err.into())
+    }
+}
+
+impl std::fmt::Display for WaitlistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaitlistError::InvalidContact(reason) => write!(f, "Invalid contact details: {}", reason),
+            WaitlistError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WaitlistError {}
+
+impl WaitlistService {
+    /// Create a new waitlist service
+    pub fn new(db: &Database, property_service: PropertyService, daobitat_base_url: String) -> Self {
+        Self {
+            entries: db.collection("waitlist"),
+            property_service,
+            notifications: None,
+            hooks: None,
+            daobitat_base_url,
+        }
+    }
+
+    /// Notify REST hook subscribers when prospects join a waitlist
+    pub fn with_hooks(mut self, hooks: HookService) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Tell prospects when a listing comes back or a similar one appears
+    pub fn with_notifications(mut self, notifications: NotificationService) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Listing a property's waitlist, and finding entries still waiting to hear back
+    pub async fn ensure_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.entries
+            .create_index(IndexModel::builder().keys(doc! { "propertyId": 1, "createdAt": 1 }).build())
+            .await?;
+        self.entries
+            .create_index(IndexModel::builder().keys(doc! { "notifiedAt": 1, "createdAt": 1 }).build())
+            .await?;
+        Ok(())
+    }
+
+    /// Add a prospect to a listing's waitlist; joining again with the same email or phone keeps the first entry
+    pub async fn join(
+        &self,
+        property: &PropertyQrInfo,
+        reason: WaitlistReason,
+        request: JoinWaitlistRequest,
+        visitor_id: Option<String>,
+    ) -> Result<WaitlistEntry, WaitlistError> {
+        let request = normalize_contact(request)?;
+        let property_id = property.id.to_hex();
+
+        let mut same_contact = Vec::new();
+        if let Some(email) = &request.email {
+            same_contact.push(doc! { "email": email });
+        }
+        if let Some(phone) = &request.phone {
+            same_contact.push(doc! { "phone": phone });
+        }
+        if let Some(existing) = self.entries
+            .find_one(doc! { "propertyId": &property_id, "$or": same_contact })
+            .await?
+        {
+            return Ok(existing);
+        }
+
+        let entry = WaitlistEntry::new(property, reason, request, visitor_id);
+        self.entries.insert_one(&entry).await?;
+
+        info!("Prospect joined the {} waitlist for property {}", reason.as_str(), property_id);
+        if let Some(hooks) = &self.hooks {
+            let payload = serde_json::to_value(LeadHookPayload::from(&entry)).unwrap_or_default();
+            hooks.dispatch(HookEvent::LeadCreated, &property_id, payload);
+        }
+        Ok(entry)
+    }
+
+    /// A property's waitlist, oldest first
+    pub async fn list(&self, property_id: &str) -> Result<Vec<WaitlistEntry>, mongodb::error::Error> {
+        let options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+        self.entries
+            .find(doc! { "propertyId": property_id })
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Tell waiting prospects about listings that came back or similar ones that appeared.
+    /// Returns how many were notified; entries nobody could reach stay pending.
+    pub async fn notify_pending(&self) -> Result<usize, WaitlistError> {
+        let Some(notifications) = self.notifications.as_ref().filter(|n| n.is_enabled()) else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let oldest = now - Duration::days(WAITLIST_MAX_AGE_DAYS);
+        let options = FindOptions::builder()
+            .sort(doc! { "createdAt": 1 })
+            .limit(NOTIFY_BATCH_SIZE)
+            .build();
+        let pending: Vec<WaitlistEntry> = self.entries
+            .find(doc! { "notifiedAt": null, "createdAt": { "$gte": to_bson(&oldest)? } })
+            .with_options(options)
+            .await?
+            .try_collect()
+            .await?;
+
+        // Each waitlisted listing is looked up once per sweep
+        let mut properties: HashMap<String, Option<Property>> = HashMap::new();
+        for entry in &pending {
+            if let Entry::Vacant(slot) = properties.entry(entry.property_id.clone()) {
+                slot.insert(self.property_service.get_property_by_id(&entry.property_id).await.ok());
+            }
+        }
+
+        let mut notified = 0;
+        for entry in pending {
+            let relisted = properties[&entry.property_id]
+                .as_ref()
+                .filter(|property| is_back_on_market(&entry, property, now))
+                .map(|property| property.to_qr_info());
+            let listing = match relisted {
+                Some(property) => Some((property, true)),
+                None => self.similar_listing(&entry).await.map(|property| (property, false)),
+            };
+            let Some((listing, relisted)) = listing else {
+                continue;
+            };
+
+            let url = format!("{}/property/{}", self.daobitat_base_url, listing.id.to_hex());
+            let (subject, message) = waitlist_message(&listing, &url, relisted);
+            if let Err(e) = notifications
+                .notify(entry.email.as_deref(), entry.phone.as_deref(), &subject, &message)
+                .await
+            {
+                warn!("Failed to notify waitlist entry {} for property {}: {}", entry.id, entry.property_id, e);
+                continue;
+            }
+
+            self.entries
+                .update_one(
+                    doc! { "_id": entry.id },
+                    doc! { "$set": {
+                        "notifiedAt": to_bson(&now)?,
+                        "notifiedPropertyId": listing.id.to_hex(),
+                    } },
+                )
+                .await?;
+            notified += 1;
+        }
+
+        if notified > 0 {
+            info!("Notified {} waitlisted prospects", notified);
+        }
+        Ok(notified)
+    }
+
+    /// Run `notify_pending` on a fixed interval in the background
+    pub fn spawn_notifier(&self, interval: std::time::Duration) {
+        let waitlist_service = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = waitlist_service.notify_pending().await {
+                    error!("Waitlist notification sweep failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// The newest listing like the one the prospect waited on, added since they joined
+    async fn similar_listing(&self, entry: &WaitlistEntry) -> Option<PropertyQrInfo> {
+        match self.property_service
+            .find_similar_listings(
+                &entry.property_id,
+                &entry.location,
+                &entry.action,
+                similar_price_range(entry.price),
+                entry.created_at,
+                1,
+            )
+            .await
+        {
+            Ok(mut listings) => listings.pop(),
+            Err(e) => {
+                error!("Failed to find listings similar to property {}: {}", entry.property_id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Trim contact details, requiring a valid email or phone number
+fn normalize_contact(request: JoinWaitlistRequest) -> Result<JoinWaitlistRequest, WaitlistError> {
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let request = JoinWaitlistRequest {
+        name: clean(request.name),
+        email: clean(request.email).map(|email| email.to_lowercase()),
+        phone: clean(request.phone),
+    };
+
+    if request.email.is_none() && request.phone.is_none() {
+        return Err(WaitlistError::InvalidContact("an email address or phone number is required".to_string()));
+    }
+    if let Some(email) = &request.email {
+        crate::utils::validate_email(email).map_err(|e| WaitlistError::InvalidContact(e.message))?;
+    }
+    if let Some(phone) = &request.phone {
+        crate::utils::validate_phone_number(phone).map_err(|e| WaitlistError::InvalidContact(e.message))?;
+    }
+    if request.name.as_ref().is_some_and(|name| name.chars().count() > 100) {
+        return Err(WaitlistError::InvalidContact("name must be at most 100 characters".to_string()));
+    }
+
+    Ok(request)
+}
+
+/// Whether the listing a prospect waited on can be viewed again: sold listings once
+/// they're back for sale, capped ones once the day they hit the cap is over
+fn is_back_on_market(entry: &WaitlistEntry, property: &Property, now: DateTime<Utc>) -> bool {
+    if !property.is_qr_eligible() || property.status.sold {
+        return false;
+    }
+
+    match entry.reason {
+        WaitlistReason::Sold => true,
+        WaitlistReason::Capped => entry.created_at.date_naive() < now.date_naive(),
+    }
+}
+
+fn similar_price_range(price: i64) -> std::ops::RangeInclusive<i64> {
+    let tolerance = price.saturating_mul(SIMILAR_PRICE_TOLERANCE_PERCENT) / 100;
+    price.saturating_sub(tolerance)..=price.saturating_add(tolerance)
+}
+
+/// Subject and plain-text body telling a prospect about a listing
+fn waitlist_message(listing: &PropertyQrInfo, url: &str, relisted: bool) -> (String, String) {
+    if relisted {
+        (
+            format!("{} is available again", listing.property_name),
+            format!(
+                "Good news: {} in {} is available again on DAO-Bitat. View it here: {}\n\nYou're receiving this because you joined its waitlist.",
+                listing.property_name, listing.location, url
+            ),
+        )
+    } else {
+        (
+            format!("A similar property just listed in {}", listing.location),
+            format!(
+                "{} in {} ({}, KES {}) just listed on DAO-Bitat. View it here: {}\n\nYou're receiving this because you joined the waitlist for a similar property.",
+                listing.property_name, listing.location, listing.action, listing.price, url
+            ),
+        )
+    }
+}
+
+/// A property's waitlist as CSV, one row per entry
+pub fn export_csv(entries: &[WaitlistEntry]) -> String {
+    let mut csv = String::from("id,propertyId,name,email,phone,reason,createdAt,notifiedAt,notifiedPropertyId\r\n");
+
+    for entry in entries {
+        let row = [
+            entry.id.to_hex(),
+            entry.property_id.clone(),
+            entry.name.clone().unwrap_or_default(),
+            entry.email.clone().unwrap_or_default(),
+            entry.phone.clone().unwrap_or_default(),
+            entry.reason.as_str().to_string(),
+            entry.created_at.to_rfc3339(),
+            entry.notified_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            entry.notified_property_id.clone().unwrap_or_default(),
+        ];
+        csv.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Quote a CSV field, and keep spreadsheets from running prospect-supplied text as a formula
+pub(crate) fn csv_field(value: &str) -> String {
+    let looks_like_phone = value.chars().all(|c| c.is_ascii_digit() || " +-()".contains(c));
+    let value = if value.starts_with(['=', '@', '\t', '\r'])
+        || (value.starts_with(['+', '-']) && !looks_like_phone)
+    {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing() -> PropertyQrInfo {
+        Property {
+            property_name: "Garden Villa".to_string(),
+            location: "Kilimani".to_string(),
+            action: "for sale".to_string(),
+            price: 10_000_000,
+            images: vec!["https://cdn.daobitat.xyz/villa.jpg".to_string()],
+            ..Property::default()
+        }
+        .to_qr_info()
+    }
+
+    fn entry(reason: WaitlistReason, created_at: DateTime<Utc>) -> WaitlistEntry {
+        let request = JoinWaitlistRequest {
+            name: None,
+            email: Some("buyer@example.com".to_string()),
+            phone: None,
+        };
+        WaitlistEntry {
+            created_at,
+            ..WaitlistEntry::new(&listing(), reason, request, None)
+        }
+    }
+
+    fn at(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_normalize_contact() {
+        let request = normalize_contact(JoinWaitlistRequest {
+            name: Some("  ".to_string()),
+            email: Some(" Buyer@Example.com ".to_string()),
+            phone: None,
+        })
+        .unwrap();
+        assert_eq!(request.name, None);
+        assert_eq!(request.email.as_deref(), Some("buyer@example.com"));
+
+        let missing = JoinWaitlistRequest { name: Some("Amina".to_string()), email: None, phone: Some(" ".to_string()) };
+        assert!(matches!(normalize_contact(missing), Err(WaitlistError::InvalidContact(_))));
+
+        let bad_phone = JoinWaitlistRequest { name: None, email: None, phone: Some("abc".to_string()) };
+        assert!(matches!(normalize_contact(bad_phone), Err(WaitlistError::InvalidContact(_))));
+    }
+
+    #[test]
+    fn test_back_on_market() {
+        let now = at("2026-03-14T15:00:00Z");
+        let mut property = Property {
+            images: vec!["https://cdn.daobitat.xyz/villa.jpg".to_string()],
+            price: 10_000_000,
+            ..Property::default()
+        };
+        property.status.sold = true;
+
+        let sold = entry(WaitlistReason::Sold, at("2026-03-10T09:00:00Z"));
+        assert!(!is_back_on_market(&sold, &property, now));
+        property.status.sold = false;
+        assert!(is_back_on_market(&sold, &property, now));
+
+        // Capped listings come back at midnight UTC
+        assert!(!is_back_on_market(&entry(WaitlistReason::Capped, at("2026-03-14T09:00:00Z")), &property, now));
+        assert!(is_back_on_market(&entry(WaitlistReason::Capped, at("2026-03-13T22:00:00Z")), &property, now));
+
+        property.removed = Some(true);
+        assert!(!is_back_on_market(&sold, &property, now));
+    }
+
+    #[test]
+    fn test_similar_price_range() {
+        assert_eq!(similar_price_range(10_000_000), 8_000_000..=12_000_000);
+        assert_eq!(similar_price_range(0), 0..=0);
+    }
+
+    #[test]
+    fn test_waitlist_message() {
+        let listing = listing();
+        let (subject, body) = waitlist_message(&listing, "https://daobitat.xyz/property/1", true);
+        assert_eq!(subject, "Garden Villa is available again");
+        assert!(body.contains("https://daobitat.xyz/property/1"));
+
+        let (subject, body) = waitlist_message(&listing, "https://daobitat.xyz/property/1", false);
+        assert_eq!(subject, "A similar property just listed in Kilimani");
+        assert!(body.contains("KES 10000000"));
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut entry = entry(WaitlistReason::Sold, at("2026-03-14T09:00:00Z"));
+        entry.name = Some("=HYPERLINK(\"x\")".to_string());
+        entry.phone = Some("+254 712 345 678".to_string());
+
+        let csv = export_csv(&[entry.clone()]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("id,propertyId,name,email,phone,reason,createdAt,notifiedAt,notifiedPropertyId"));
+        assert_eq!(
+            lines.next().unwrap(),
+            format!(
+                "{},{},\"'=HYPERLINK(\"\"x\"\")\",buyer@example.com,+254 712 345 678,sold,2026-03-14T09:00:00+00:00,,",
+                entry.id.to_hex(),
+                entry.property_id
+            )
+        );
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,7 @@
+// src/test_support/mod.rs
+
+pub mod property_fixture;
+pub mod seed;
+
+pub use property_fixture::PropertyFixture;
+pub use seed::*;
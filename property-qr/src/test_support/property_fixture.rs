@@ -0,0 +1,148 @@
+// src/test_support/property_fixture.rs
+
+use crate::models::{BlockchainInfo, Coordinates, Property, PropertyType};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+
+/// Builder for realistic properties in tests and local seed data
+#[derive(Debug, Clone)]
+pub struct PropertyFixture {
+    property: Property,
+}
+
+impl Default for PropertyFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PropertyFixture {
+    /// An unverified, QR-eligible residential rental
+    pub fn new() -> Self {
+        Self {
+            property: Property {
+                property_name: "Garden Apartment".to_string(),
+                location: "Kilimani, Nairobi".to_string(),
+                coordinates: Coordinates { lat: -1.2921, lng: 36.7856 },
+                street_address: "Argwings Kodhek Road".to_string(),
+                property_type: PropertyType::Residential,
+                specific_type: "Apartment".to_string(),
+                action: "rent".to_string(),
+                price: 65000,
+                space: 95,
+                bedrooms: Some(2),
+                bathrooms: Some(2),
+                security: "24/7 guard".to_string(),
+                images: vec!["https://cdn.daobitat.xyz/fixtures/apartment.jpg".to_string()],
+                is_verified: Some(false),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// A property verified by a DAO-Bitat admin
+    pub fn verified() -> Self {
+        Self::new().with_verified(true)
+    }
+
+    /// Register the property on-chain with a deterministic token ID
+    pub fn with_onchain(mut self) -> Self {
+        let now = Utc::now();
+        self.property.onchain_id = Some(format!("0x{}", self.property.id.to_hex()));
+        self.property.blockchain = Some(BlockchainInfo {
+            registered: true,
+            registered_at: Some(now),
+            verified: self.property.is_verified.unwrap_or(false),
+            verified_at: None,
+            verified_by: None,
+            transaction_hash: None,
+            sbt_id: None,
+            ownership_token_id: None,
+            zk_proof: None,
+            last_updated_on_chain: Some(now),
+            owner_wallet_address: None,
+        });
+        self
+    }
+
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.property.is_verified = Some(verified);
+        self.property.verified_at = verified.then(Utc::now);
+        self
+    }
+
+    pub fn with_id(mut self, id: ObjectId) -> Self {
+        self.property.id = id;
+        self
+    }
+
+    pub fn with_owner(mut self, owner: ObjectId) -> Self {
+        self.property.owner = owner;
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.property.property_name = name.to_string();
+        self
+    }
+
+    pub fn with_location(mut self, location: &str) -> Self {
+        self.property.location = location.to_string();
+        self
+    }
+
+    pub fn with_price(mut self, price: i64) -> Self {
+        self.property.price = price;
+        self
+    }
+
+    pub fn for_sale(mut self) -> Self {
+        self.property.action = "sale".to_string();
+        self
+    }
+
+    pub fn with_crypto_accepted(mut self) -> Self {
+        self.property.crypto_accepted = true;
+        self
+    }
+
+    pub fn without_images(mut self) -> Self {
+        self.property.images.clear();
+        self
+    }
+
+    pub fn sold(mut self) -> Self {
+        self.property.status.sold = true;
+        self
+    }
+
+    /// Soft-deleted, as done by the main platform
+    pub fn removed(mut self, reason: &str) -> Self {
+        self.property.removed = Some(true);
+        self.property.removed_at = Some(Utc::now());
+        self.property.removed_reason = Some(reason.to_string());
+        self
+    }
+
+    pub fn build(self) -> Property {
+        self.property
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_builder() {
+        let property = PropertyFixture::verified().with_onchain().with_crypto_accepted().build();
+
+        assert!(property.is_qr_eligible());
+        assert!(property.has_blockchain_info());
+        assert_eq!(property.to_qr_info().is_verified, Some(true));
+        assert_eq!(property.onchain_id, Some(format!("0x{}", property.id.to_hex())));
+
+        assert!(!PropertyFixture::new().without_images().build().is_qr_eligible());
+        assert!(!PropertyFixture::new().removed("duplicate").build().is_qr_eligible());
+    }
+}
@@ -0,0 +1,163 @@
+// src/test_support/seed.rs
+
+use crate::models::{Property, RedirectType, ScanEvent, ScanSource};
+use crate::test_support::PropertyFixture;
+use chrono::{Duration, Utc};
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::ReplaceOptions,
+    Database,
+};
+use tracing::info;
+
+// Fixed IDs so repeated seeding updates the same documents
+const SAMPLE_PROPERTY_IDS: [&str; 5] = [
+    "66a000000000000000000001",
+    "66a000000000000000000002",
+    "66a000000000000000000003",
+    "66a000000000000000000004",
+    "66a000000000000000000005",
+];
+const SAMPLE_OWNER_ID: &str = "66a0000000000000000000ff";
+const SEED_SCANS_PER_PROPERTY: usize = 40;
+const SEED_SCAN_DAYS: i64 = 30;
+
+#[derive(Debug, Default)]
+pub struct SeedSummary {
+    pub properties: usize,
+    pub scan_events: usize,
+}
+
+/// Representative properties covering the landing page variants
+pub fn sample_properties() -> Vec<Property> {
+    let id = |i: usize| ObjectId::parse_str(SAMPLE_PROPERTY_IDS[i]).unwrap();
+    let owner = ObjectId::parse_str(SAMPLE_OWNER_ID).unwrap();
+
+    vec![
+        PropertyFixture::verified()
+            .with_id(id(0))
+            .with_onchain()
+            .with_crypto_accepted()
+            .with_name("Lavington Family Home")
+            .with_location("Lavington, Nairobi")
+            .with_price(42_000_000)
+            .for_sale(),
+        PropertyFixture::new()
+            .with_id(id(1))
+            .with_name("Studio near Yaya Centre")
+            .with_price(35_000),
+        PropertyFixture::verified()
+            .with_id(id(2))
+            .with_name("Nyali Beach Villa 🏖️ — Résidence de Vacances")
+            .with_location("Nyali, Mombasa")
+            .with_price(18_500_000)
+            .for_sale(),
+        PropertyFixture::verified()
+            .with_id(id(3))
+            .with_onchain()
+            .with_name("Karen Townhouse")
+            .with_location("Karen, Nairobi")
+            .sold(),
+        PropertyFixture::new()
+            .with_id(id(4))
+            .with_name("Removed Listing")
+            .removed("Listing withdrawn by owner"),
+    ]
+    .into_iter()
+    .map(|fixture| fixture.with_owner(owner).build())
+    .collect()
+}
+
+/// Upsert properties by ID so seeding can be re-run safely
+pub async fn seed_properties(db: &Database, properties: &[Property]) -> Result<usize, mongodb::error::Error> {
+    let collection = db.collection::<Property>("properties");
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    for property in properties {
+        collection
+            .replace_one(doc! { "_id": property.id }, property)
+            .with_options(options.clone())
+            .await?;
+    }
+
+    Ok(properties.len())
+}
+
+/// Insert synthetic scans spread over the last `days` days, replacing earlier seeded scans
+pub async fn seed_scan_events(
+    db: &Database,
+    property_id: &str,
+    count: usize,
+    days: i64,
+) -> Result<usize, mongodb::error::Error> {
+    let collection = db.collection::<ScanEvent>("scan_events");
+    collection
+        .delete_many(doc! { "propertyId": property_id, "metadata.seeded": true })
+        .await?;
+
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    let sources = [ScanSource::QrCode, ScanSource::QrCode, ScanSource::ShareLink, ScanSource::DirectLink];
+    let events: Vec<ScanEvent> = (0..count)
+        .map(|i| {
+            let mut event = ScanEvent::new(
+                property_id.to_string(),
+                1,
+                sources[i % sources.len()].clone(),
+                RedirectType::DualRedirect,
+            );
+            event.scanned_at = now - Duration::days(i as i64 % days.max(1)) - Duration::hours(i as i64 % 24);
+            event.visitor_id = Some(format!("seed-visitor-{}", i % 7));
+            event.metadata.insert("seeded".to_string(), serde_json::Value::Bool(true));
+            event
+        })
+        .collect();
+
+    collection.insert_many(&events).await?;
+    Ok(events.len())
+}
+
+/// Populate a development database with sample properties and scan history
+pub async fn seed_database(db: &Database) -> Result<SeedSummary, mongodb::error::Error> {
+    let properties = sample_properties();
+    let mut summary = SeedSummary {
+        properties: seed_properties(db, &properties).await?,
+        ..Default::default()
+    };
+
+    for property in properties.iter().filter(|p| p.is_qr_eligible()) {
+        summary.scan_events += seed_scan_events(
+            db,
+            &property.id.to_hex(),
+            SEED_SCANS_PER_PROPERTY,
+            SEED_SCAN_DAYS,
+        ).await?;
+    }
+
+    info!(
+        "Seeded {} properties and {} scan events into {}",
+        summary.properties,
+        summary.scan_events,
+        db.name()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_properties_are_stable() {
+        let properties = sample_properties();
+
+        assert_eq!(properties.len(), SAMPLE_PROPERTY_IDS.len());
+        assert_eq!(properties[0].id.to_hex(), SAMPLE_PROPERTY_IDS[0]);
+        assert!(properties[0].has_blockchain_info());
+        assert!(properties[3].status.sold);
+        assert!(!properties[4].is_qr_eligible());
+    }
+}
@@ -0,0 +1,87 @@
+// src/utils/html.rs
+
+/// Escape text for safe inclusion in HTML element content and attribute values
+pub fn escape_html(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote text as a JavaScript string literal for use inside a `<script>` block
+pub fn js_string_literal(input: &str) -> String {
+    // JSON strings are valid JS; "</" is split so the value can't close the script element
+    serde_json::to_string(input)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace("</", "<\\/")
+}
+
+/// Fill `{{name}}` placeholders in an HTML template.
+/// Values are inserted verbatim so fragments can be nested; escape text before passing it in.
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+
+        match after_open.find("}}") {
+            Some(end) => {
+                let name = after_open[..end].trim();
+                match values.iter().find(|(key, _)| *key == name) {
+                    Some((_, value)) => rendered.push_str(value),
+                    // Leave unknown placeholders visible rather than silently dropping them
+                    None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after_open[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<script>alert("x & y")</script>"#),
+            "&lt;script&gt;alert(&quot;x &amp; y&quot;)&lt;/script&gt;"
+        );
+        assert_eq!(escape_html("Nyali Beach Villa"), "Nyali Beach Villa");
+    }
+
+    #[test]
+    fn test_js_string_literal() {
+        assert_eq!(js_string_literal("https://daobitat.xyz/p/1"), r#""https://daobitat.xyz/p/1""#);
+        assert_eq!(js_string_literal("';alert(1)//"), r#""';alert(1)//""#);
+        assert_eq!(js_string_literal("</script><script>"), r#""<\/script><script>""#);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let rendered = render_template(
+            "<h1>{{ title }}</h1><p>{{body}}</p>{{missing}}",
+            &[("title", "Admin"), ("body", "<b>ok</b>")],
+        );
+        assert_eq!(rendered, "<h1>Admin</h1><p><b>ok</b></p>{{missing}}");
+        assert_eq!(render_template("unclosed {{title", &[("title", "x")]), "unclosed {{title");
+    }
+}
@@ -0,0 +1,135 @@
+// src/utils/i18n.rs
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::warn;
+
+// Per-locale strings, compiled in so the binary needs no resource directory at runtime
+const EN_STRINGS: &str = include_str!("../../locales/en.json");
+const SW_STRINGS: &str = include_str!("../../locales/sw.json");
+
+/// Languages the scan landing pages are available in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Sw,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Sw];
+
+    /// BCP 47 code, as used in `<html lang>` and Content-Language
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Sw => "sw",
+        }
+    }
+
+    /// Match a language tag on its primary subtag, so "sw-KE" is Swahili
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+        Locale::ALL.into_iter().find(|locale| locale.code().eq_ignore_ascii_case(primary))
+    }
+
+    /// Pick a locale from a `?lang=` override, else Accept-Language, else English
+    pub fn negotiate(lang_override: Option<&str>, accept_language: Option<&str>) -> Locale {
+        lang_override
+            .and_then(Locale::from_tag)
+            .or_else(|| accept_language.and_then(preferred_locale))
+            .unwrap_or_default()
+    }
+
+    fn strings(&self) -> &'static str {
+        match self {
+            Locale::En => EN_STRINGS,
+            Locale::Sw => SW_STRINGS,
+        }
+    }
+}
+
+// Highest-weighted supported language in an Accept-Language header; q=0 means "not this one"
+fn preferred_locale(accept_language: &str) -> Option<Locale> {
+    let mut candidates: Vec<(f32, Locale)> = accept_language
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let locale = Locale::from_tag(parts.next()?)?;
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (quality > 0.0).then_some((quality, locale))
+        })
+        .collect();
+
+    // Stable sort keeps header order between equal weights
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates.first().map(|(_, locale)| *locale)
+}
+
+fn catalog() -> &'static HashMap<Locale, HashMap<String, String>> {
+    static CATALOG: OnceLock<HashMap<Locale, HashMap<String, String>>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        Locale::ALL
+            .into_iter()
+            .map(|locale| {
+                let strings = serde_json::from_str(locale.strings())
+                    .unwrap_or_else(|e| panic!("Invalid {} locale file: {}", locale.code(), e));
+                (locale, strings)
+            })
+            .collect()
+    })
+}
+
+/// Look up a UI string, falling back to English; unknown keys render as empty text
+pub fn translate(locale: Locale, key: &str) -> &'static str {
+    let catalog = catalog();
+    [locale, Locale::En]
+        .iter()
+        .find_map(|locale| catalog.get(locale).and_then(|strings| strings.get(key)))
+        .map(String::as_str)
+        .unwrap_or_else(|| {
+            warn!("Missing translation for {}", key);
+            ""
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_locale() {
+        assert_eq!(Locale::negotiate(None, None), Locale::En);
+        assert_eq!(Locale::negotiate(None, Some("sw-KE,sw;q=0.9,en;q=0.8")), Locale::Sw);
+        assert_eq!(Locale::negotiate(None, Some("fr-FR, en;q=0.5, sw;q=0.7")), Locale::Sw);
+        assert_eq!(Locale::negotiate(None, Some("sw;q=0, en")), Locale::En);
+        assert_eq!(Locale::negotiate(None, Some("fr, de")), Locale::En);
+
+        // ?lang= wins over the browser, unless it names an unsupported language
+        assert_eq!(Locale::negotiate(Some("sw"), Some("en-US")), Locale::Sw);
+        assert_eq!(Locale::negotiate(Some("EN"), Some("sw")), Locale::En);
+        assert_eq!(Locale::negotiate(Some("xx"), Some("sw")), Locale::Sw);
+    }
+
+    #[test]
+    fn test_locale_files_have_the_same_keys() {
+        let catalog = catalog();
+        let mut english: Vec<_> = catalog[&Locale::En].keys().collect();
+        english.sort();
+
+        for locale in Locale::ALL {
+            let mut keys: Vec<_> = catalog[&locale].keys().collect();
+            keys.sort();
+            assert_eq!(keys, english, "{} locale keys differ from English", locale.code());
+        }
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(Locale::En, "error.property_not_found"), "Property not found");
+        assert_eq!(translate(Locale::Sw, "error.property_not_found"), "Mali haikupatikana");
+        assert_eq!(translate(Locale::Sw, "no.such.key"), "");
+    }
+}
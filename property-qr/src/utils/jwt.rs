@@ -0,0 +1,299 @@
+// src/utils/jwt.rs
+
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::models::AuthClaims;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, PartialEq)]
+pub enum JwtError {
+    Malformed,
+    UnsupportedAlgorithm(String),
+    UnknownKey(Option<String>),
+    InvalidSignature,
+    Expired,
+    WrongIssuer,
+    MissingUserId,
+}
+
+impl std::fmt::Display for JwtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwtError::Malformed => write!(f, "Malformed token"),
+            JwtError::UnsupportedAlgorithm(alg) => write!(f, "Unsupported token algorithm: {}", alg),
+            JwtError::UnknownKey(Some(kid)) => write!(f, "Token signed with unknown key '{}'", kid),
+            JwtError::UnknownKey(None) => write!(f, "Token doesn't name its signing key"),
+            JwtError::InvalidSignature => write!(f, "Invalid token signature"),
+            JwtError::Expired => write!(f, "Token expired"),
+            JwtError::WrongIssuer => write!(f, "Token issued by another service"),
+            JwtError::MissingUserId => write!(f, "Token doesn't identify a user"),
+        }
+    }
+}
+
+impl std::error::Error for JwtError {}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// A JSON Web Key Set, as served at the auth service's JWKS URL
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: Option<String>,
+    #[serde(rename = "use")]
+    pub key_use: Option<String>, // "sig" for signing keys
+    pub n: Option<String>,       // RSA modulus, base64url
+    pub e: Option<String>,       // RSA exponent, base64url
+}
+
+// RSA public key components, big-endian
+struct RsaKey {
+    n: Vec<u8>,
+    e: Vec<u8>,
+}
+
+/// Verifies tokens from the main DAO-Bitat auth service: RS256 against the keys of its
+/// JWKS, and HS256 against a shared secret. Cheap to clone; clones share the key set.
+#[derive(Clone)]
+pub struct JwtVerifier {
+    hmac_key: Option<Vec<u8>>,
+    rsa_keys: Arc<RwLock<HashMap<String, RsaKey>>>, // By key ID, replaced on each JWKS refresh
+    issuer: Option<String>,                         // Checked against `iss` when set
+}
+
+impl JwtVerifier {
+    pub fn new(issuer: Option<String>) -> Self {
+        Self {
+            hmac_key: None,
+            rsa_keys: Arc::new(RwLock::new(HashMap::new())),
+            issuer,
+        }
+    }
+
+    /// Also accept HS256 tokens signed with a shared secret
+    pub fn with_hmac_secret(mut self, secret: &str) -> Self {
+        self.hmac_key = Some(secret.as_bytes().to_vec());
+        self
+    }
+
+    /// Replace the RS256 keys with a freshly fetched key set; returns how many were usable.
+    /// A set with none leaves the current keys in place.
+    pub fn set_jwks(&self, jwks: &JwkSet) -> usize {
+        let keys: HashMap<String, RsaKey> = jwks.keys.iter()
+            .filter(|jwk| jwk.kty == "RSA" && jwk.key_use.as_deref().is_none_or(|key_use| key_use == "sig"))
+            .filter_map(|jwk| {
+                let n = base64url_decode(jwk.n.as_deref()?)?;
+                let e = base64url_decode(jwk.e.as_deref()?)?;
+                Some((jwk.kid.clone().unwrap_or_default(), RsaKey { n, e }))
+            })
+            .collect();
+
+        let count = keys.len();
+        if count > 0 {
+            *self.rsa_keys.write().expect("JWKS lock poisoned") = keys;
+        }
+        count
+    }
+
+    /// Claims from a token whose signature, expiry and issuer check out
+    pub fn verify(&self, token: &str, now: i64) -> Result<AuthClaims, JwtError> {
+        let (signing_input, signature) = token.rsplit_once('.').ok_or(JwtError::Malformed)?;
+        let (header, payload) = signing_input.split_once('.').ok_or(JwtError::Malformed)?;
+
+        let header: JwtHeader = serde_json::from_slice(&base64url_decode(header).ok_or(JwtError::Malformed)?)
+            .map_err(|_| JwtError::Malformed)?;
+        let signature = base64url_decode(signature).ok_or(JwtError::Malformed)?;
+
+        // Only algorithms we hold a key for; never "none"
+        match (header.alg.as_str(), &self.hmac_key) {
+            ("RS256", _) => self.verify_rs256(header.kid, signing_input, &signature)?,
+            ("HS256", Some(hmac_key)) => {
+                let mut mac = HmacSha256::new_from_slice(hmac_key).expect("HMAC accepts keys of any length");
+                mac.update(signing_input.as_bytes());
+                mac.verify_slice(&signature).map_err(|_| JwtError::InvalidSignature)?;
+            }
+            _ => return Err(JwtError::UnsupportedAlgorithm(header.alg)),
+        }
+
+        let claims: AuthClaims = serde_json::from_slice(&base64url_decode(payload).ok_or(JwtError::Malformed)?)
+            .map_err(|_| JwtError::Malformed)?;
+        if claims.exp <= now {
+            return Err(JwtError::Expired);
+        }
+        if self.issuer.as_ref().is_some_and(|issuer| claims.iss.as_ref() != Some(issuer)) {
+            return Err(JwtError::WrongIssuer);
+        }
+        if claims.user_id().is_none() {
+            return Err(JwtError::MissingUserId);
+        }
+        Ok(claims)
+    }
+
+    fn verify_rs256(&self, kid: Option<String>, signing_input: &str, signature: &[u8]) -> Result<(), JwtError> {
+        let rsa_keys = self.rsa_keys.read().expect("JWKS lock poisoned");
+        // A key set with a single unnamed key is used for tokens without a kid
+        let Some(key) = rsa_keys.get(kid.as_deref().unwrap_or_default()) else {
+            return Err(JwtError::UnknownKey(kid));
+        };
+
+        ring::signature::RsaPublicKeyComponents { n: &key.n, e: &key.e }
+            .verify(&ring::signature::RSA_PKCS1_2048_8192_SHA256, signing_input.as_bytes(), signature)
+            .map_err(|_| JwtError::InvalidSignature)
+    }
+}
+
+/// Unpadded base64url, as JWTs use
+fn base64url_decode(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(value.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in value.bytes() {
+        let sextet = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'-' => 62,
+            b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | sextet as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::models::Role;
+
+    const SECRET: &str = "a-long-enough-jwt-secret-for-tests";
+
+    fn base64url_encode(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            }
+        }
+        out
+    }
+
+    /// A token signed with `secret`, for tests of routes behind the role checks
+    pub(crate) fn token(alg: &str, claims: serde_json::Value, secret: &str) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            base64url_encode(serde_json::json!({ "alg": alg, "typ": "JWT" }).to_string().as_bytes()),
+            base64url_encode(claims.to_string().as_bytes()),
+        );
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(signing_input.as_bytes());
+        format!("{}.{}", signing_input, base64url_encode(&mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_base64url_decode() {
+        assert_eq!(base64url_decode("aGk").unwrap(), b"hi");
+        assert_eq!(base64url_decode("-_8").unwrap(), vec![0xfb, 0xff]);
+        assert!(base64url_decode("aGk=").is_none());
+    }
+
+    #[test]
+    fn test_verify_token() {
+        let verifier = JwtVerifier::new(Some("daobitat-auth".into())).with_hmac_secret(SECRET);
+        let claims = serde_json::json!({ "sub": "u1", "role": "agent", "exp": 2_000, "iss": "daobitat-auth" });
+
+        let verified = verifier.verify(&token("HS256", claims.clone(), SECRET), 1_000).unwrap();
+        assert_eq!(verified.user_id(), Some("u1"));
+        assert_eq!(verified.role, Some(Role::Agent));
+
+        assert_eq!(verifier.verify(&token("HS256", claims.clone(), SECRET), 2_000).unwrap_err(), JwtError::Expired);
+        assert_eq!(verifier.verify(&token("HS256", claims.clone(), "another-secret"), 1_000).unwrap_err(), JwtError::InvalidSignature);
+        assert_eq!(
+            verifier.verify(&token("none", claims, SECRET), 1_000).unwrap_err(),
+            JwtError::UnsupportedAlgorithm("none".into())
+        );
+
+        let other_issuer = serde_json::json!({ "sub": "u1", "role": "admin", "exp": 2_000, "iss": "elsewhere" });
+        assert_eq!(verifier.verify(&token("HS256", other_issuer, SECRET), 1_000).unwrap_err(), JwtError::WrongIssuer);
+        assert_eq!(verifier.verify("not-a-token", 1_000).unwrap_err(), JwtError::Malformed);
+
+        let anonymous = serde_json::json!({ "exp": 2_000, "iss": "daobitat-auth" });
+        assert_eq!(verifier.verify(&token("HS256", anonymous, SECRET), 1_000).unwrap_err(), JwtError::MissingUserId);
+
+        // Without a shared secret, HS256 is refused outright
+        let claims = serde_json::json!({ "sub": "u1", "exp": 2_000, "iss": "daobitat-auth" });
+        assert_eq!(
+            JwtVerifier::new(None).verify(&token("HS256", claims, SECRET), 1_000).unwrap_err(),
+            JwtError::UnsupportedAlgorithm("HS256".into())
+        );
+    }
+
+    fn rs256_token(key_pair: &ring::rsa::KeyPair, kid: &str, claims: serde_json::Value) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            base64url_encode(serde_json::json!({ "alg": "RS256", "typ": "JWT", "kid": kid }).to_string().as_bytes()),
+            base64url_encode(claims.to_string().as_bytes()),
+        );
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(&ring::signature::RSA_PKCS1_SHA256, &ring::rand::SystemRandom::new(), signing_input.as_bytes(), &mut signature)
+            .unwrap();
+        format!("{}.{}", signing_input, base64url_encode(&signature))
+    }
+
+    #[test]
+    fn test_verify_rs256_against_jwks() {
+        let key_pair = ring::rsa::KeyPair::from_der(include_bytes!("testdata/jwt_test_rsa_key.der")).unwrap();
+        let public_key: ring::rsa::PublicKeyComponents<Vec<u8>> = key_pair.public().into();
+        let jwks: JwkSet = serde_json::from_value(serde_json::json!({ "keys": [
+            {
+                "kty": "RSA", "use": "sig", "kid": "2026-10",
+                "n": base64url_encode(&public_key.n),
+                "e": base64url_encode(&public_key.e),
+            },
+            { "kty": "EC", "kid": "ec-1", "crv": "P-256", "x": "AA", "y": "AA" },
+        ] })).unwrap();
+
+        let verifier = JwtVerifier::new(None);
+        let claims = serde_json::json!({ "userId": "65f1c0ffee0000000000abcd", "exp": 2_000 });
+        assert_eq!(
+            verifier.verify(&rs256_token(&key_pair, "2026-10", claims.clone()), 1_000).unwrap_err(),
+            JwtError::UnknownKey(Some("2026-10".into()))
+        );
+
+        assert_eq!(verifier.set_jwks(&jwks), 1);
+        let verified = verifier.verify(&rs256_token(&key_pair, "2026-10", claims.clone()), 1_000).unwrap();
+        assert_eq!(verified.user_id(), Some("65f1c0ffee0000000000abcd"));
+        assert_eq!(verified.role, None);
+
+        // Tampered claims no longer match the signature
+        let token = rs256_token(&key_pair, "2026-10", claims);
+        let (header, rest) = token.split_once('.').unwrap();
+        let signature = rest.rsplit_once('.').unwrap().1;
+        let forged_claims = serde_json::json!({ "userId": "someone-else", "role": "admin", "exp": 2_000 });
+        let forged = format!("{}.{}.{}", header, base64url_encode(forged_claims.to_string().as_bytes()), signature);
+        assert_eq!(verifier.verify(&forged, 1_000).unwrap_err(), JwtError::InvalidSignature);
+    }
+}
@@ -3,12 +3,20 @@
 
 pub mod validation;
 pub mod url_builder;
+pub mod html;
+pub mod i18n;
+pub mod session;
+pub mod jwt;
 
 // Re-export commonly used validation functions
 pub use validation::{
-    validate_object_id, validate_property_id, validate_user_id,
-    validate_price, validate_email, validate_url, validate_coordinates,
-    ValidationError, ValidationResult, ValidationBuilder
+    validate_object_id, validate_property_id, validate_property_ids, validate_user_id,
+    validate_price, validate_email, validate_url, validate_allowed_url, validate_coordinates, validate_phone_number,
+    Validate, ValidationError, ValidationResult, ValidationBuilder
 };
 
-pub use url_builder::{UrlBuilder, PropertySearchFilters, UrlValidator};
\ No newline at end of file
+pub use url_builder::{UrlBuilder, PropertySearchFilters, UrlValidator, HostPolicy};
+pub use html::{escape_html, js_string_literal, render_template};
+pub use i18n::{translate, Locale};
+pub use session::SessionSigner;
+pub use jwt::{JwtError, JwtVerifier};
\ No newline at end of file
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::utils::{escape_html, js_string_literal};
+
 /// URL builder utility for constructing application URLs
 #[derive(Debug, Clone)]
 pub struct UrlBuilder {
@@ -46,6 +48,30 @@ impl UrlBuilder {
         format!("{}/address/{}", self.blockchain_explorer_base_url, onchain_id)
     }
 
+    /// Resolve a listing's Base name to the form shown to visitors; a bare label like
+    /// "kilimani-flat" is a base.eth subname. None if it isn't a valid name.
+    pub fn resolve_base_name(base_name: &str) -> Option<String> {
+        let name = base_name.trim().trim_end_matches('.').to_lowercase();
+        let name = if name.contains('.') { name } else { format!("{}.base.eth", name) };
+
+        let valid = name.ends_with(".eth")
+            && name.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+        valid.then_some(name)
+    }
+
+    /// Build the profile URL for a resolved Base or ENS name
+    pub fn build_base_name_url(name: &str) -> String {
+        match name.strip_suffix(".base.eth") {
+            Some(label) => format!("https://www.base.org/name/{}", label),
+            None => format!("https://app.ens.domains/{}", name),
+        }
+    }
+
     /// Build API endpoint URL
     pub fn build_api_url(&self, endpoint: &str) -> String {
         let endpoint = endpoint.trim_start_matches('/');
@@ -232,13 +258,13 @@ impl UrlBuilder {
         </div>
         <div class="links">
             <a href="{}" class="link-button" target="_blank">View Property Details</a>"#,
-            property_name, property_name, daobitat_url
+            escape_html(property_name), escape_html(property_name), escape_html(&daobitat_url)
         );
 
         if let Some(ref blockchain_url) = blockchain_url {
             html.push_str(&format!(
                 r#"<a href="{}" class="link-button" target="_blank">View on Blockchain</a>"#,
-                blockchain_url
+                escape_html(blockchain_url)
             ));
         }
 
@@ -250,7 +276,7 @@ impl UrlBuilder {
     <script>
         // Immediate redirect to main property page
         setTimeout(function() {{
-            window.location.href = '{}';
+            window.location.href = {};
         }}, 3000);
 
         // Open blockchain explorer in new tab if available
@@ -271,11 +297,11 @@ impl UrlBuilder {
     </script>
 </body>
 </html>"#,
-            daobitat_url,
-            if blockchain_url.is_some() {
+            js_string_literal(&daobitat_url),
+            if let Some(blockchain_url) = &blockchain_url {
                 format!(
-                    "setTimeout(function() {{ window.open('{}', '_blank'); }}, 1000);",
-                    blockchain_url.unwrap()
+                    "setTimeout(function() {{ window.open({}, '_blank'); }}, 1000);",
+                    js_string_literal(blockchain_url)
                 )
             } else {
                 String::new()
@@ -357,6 +383,51 @@ impl PropertySearchFilters {
     }
 }
 
+/// Hostnames the public scan routes answer on during a domain migration
+#[derive(Debug, Clone)]
+pub struct HostPolicy {
+    canonical_base_url: String,
+    accepted_hosts: Vec<String>,
+}
+
+impl HostPolicy {
+    /// Accept the base URL's host plus any alias hostnames
+    pub fn new(canonical_base_url: &str, alias_hosts: &[String]) -> Self {
+        let canonical_base_url = canonical_base_url.trim_end_matches('/').to_string();
+        let mut accepted_hosts: Vec<String> = alias_hosts
+            .iter()
+            .map(|host| normalize_host(host))
+            .collect();
+        if let Some(domain) = UrlValidator::extract_domain(&canonical_base_url) {
+            accepted_hosts.push(normalize_host(&domain));
+        }
+
+        Self { canonical_base_url, accepted_hosts }
+    }
+
+    /// Whether a request Host header is one we serve; loopback is always allowed for local use
+    pub fn accepts(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]")
+            || self.accepted_hosts.contains(&host)
+    }
+
+    /// Canonical URL for a path, whichever host the request came in on
+    pub fn canonical_url(&self, path: &str) -> String {
+        format!("{}/{}", self.canonical_base_url, path.trim_start_matches('/'))
+    }
+}
+
+// Lowercase and drop the port so "Old.Example.com:443" matches "old.example.com"
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    if host.starts_with('[') {
+        // IPv6 literal, keep the brackets
+        return host.split_inclusive(']').next().unwrap_or_default().to_string();
+    }
+    host.split(':').next().unwrap_or_default().to_string()
+}
+
 /// URL validation utilities
 pub struct UrlValidator;
 
@@ -375,23 +446,29 @@ impl UrlValidator {
     pub fn extract_domain(url: &str) -> Option<String> {
         if let Some(start) = url.find("://") {
             let after_protocol = &url[start + 3..];
-            if let Some(end) = after_protocol.find('/') {
-                Some(after_protocol[..end].to_string())
-            } else {
-                Some(after_protocol.to_string())
-            }
+            // The authority ends at the path, query or fragment, whichever comes first
+            let end = after_protocol.find(['/', '?', '#']).unwrap_or(after_protocol.len());
+            Some(after_protocol[..end].to_string())
         } else {
             None
         }
     }
 
-    /// Check if URL belongs to allowed domains
+    /// Check if URL belongs to allowed domains: the domain itself or one of its subdomains
     pub fn is_allowed_domain(url: &str, allowed_domains: &[&str]) -> bool {
-        if let Some(domain) = Self::extract_domain(url) {
-            allowed_domains.iter().any(|&allowed| domain.ends_with(allowed))
-        } else {
-            false
+        let Some(domain) = Self::extract_domain(url) else {
+            return false;
+        };
+        // Credentials in the authority ("https://daobitat.xyz@evil.com") hide the real host
+        if domain.contains('@') {
+            return false;
         }
+
+        let host = normalize_host(&domain);
+        allowed_domains.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
     }
 }
 
@@ -433,6 +510,19 @@ mod tests {
         assert_eq!(blockchain_url, "https://basescan.org/address/0x1234567890abcdef");
     }
 
+    #[test]
+    fn test_base_name_resolution() {
+        assert_eq!(UrlBuilder::resolve_base_name("kilimani-flat").as_deref(), Some("kilimani-flat.base.eth"));
+        assert_eq!(UrlBuilder::resolve_base_name(" Kilimani-Flat.base.eth. ").as_deref(), Some("kilimani-flat.base.eth"));
+        assert_eq!(UrlBuilder::resolve_base_name("daobitat.eth").as_deref(), Some("daobitat.eth"));
+        for name in ["", "-flat", "kilimani flat", "flat..base.eth", "kilimani.com", "<b>.base.eth"] {
+            assert_eq!(UrlBuilder::resolve_base_name(name), None, "{:?}", name);
+        }
+
+        assert_eq!(UrlBuilder::build_base_name_url("kilimani-flat.base.eth"), "https://www.base.org/name/kilimani-flat");
+        assert_eq!(UrlBuilder::build_base_name_url("daobitat.eth"), "https://app.ens.domains/daobitat.eth");
+    }
+
     #[test]
     fn test_url_with_params() {
         let builder = UrlBuilder::default_config();
@@ -478,6 +568,27 @@ mod tests {
         let allowed_domains = &["daobitat.xyz", "basescan.org"];
         assert!(UrlValidator::is_allowed_domain("https://qr-service.daobitat.xyz/scan/123", allowed_domains));
         assert!(!UrlValidator::is_allowed_domain("https://malicious.com/scan/123", allowed_domains));
+        assert!(UrlValidator::is_allowed_domain("https://CDN.daobitat.xyz:443/a.jpg", allowed_domains));
+        assert!(!UrlValidator::is_allowed_domain("https://evildaobitat.xyz/a.jpg", allowed_domains));
+        assert!(!UrlValidator::is_allowed_domain("https://evil.com?.daobitat.xyz", allowed_domains));
+        assert!(!UrlValidator::is_allowed_domain("https://daobitat.xyz@evil.com/a.jpg", allowed_domains));
+    }
+
+    #[test]
+    fn test_host_policy() {
+        let policy = HostPolicy::new(
+            "https://qr.daobitat.xyz/",
+            &["QR-Service.daobitat.xyz".to_string()],
+        );
+
+        assert!(policy.accepts("qr.daobitat.xyz"));
+        assert!(policy.accepts("qr-service.daobitat.xyz:443"));
+        assert!(policy.accepts("localhost:3000"));
+        assert!(!policy.accepts("evil.example.com"));
+        assert_eq!(
+            policy.canonical_url("/scan/123"),
+            "https://qr.daobitat.xyz/scan/123"
+        );
     }
 
     #[test]
@@ -494,4 +605,18 @@ mod tests {
         assert!(html.contains("address/0x1234567890abcdef"));
         assert!(html.contains("<!DOCTYPE html>"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn snapshot_dual_redirect_html() {
+        let builder = UrlBuilder::default_config();
+        insta::assert_snapshot!(builder.build_dual_redirect_html(
+            "507f1f77bcf86cd799439011",
+            "Garden Villa",
+            Some("0xabc123")
+        ));
+        insta::assert_snapshot!(
+            "dual_redirect_html_offchain",
+            builder.build_dual_redirect_html("507f1f77bcf86cd799439011", "Studio Apartment", None)
+        );
+    }
+}
@@ -1,13 +1,17 @@
   // src/utils/validation.rs
 
 use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use tracing::warn;
 
-#[derive(Debug, Clone)]
+use crate::utils::UrlValidator;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidationError {
     pub field: String,
     pub message: String,
+    #[serde(rename = "code")]
     pub error_code: String,
 }
 
@@ -256,6 +260,22 @@ pub fn validate_scan_url(url: &str) -> ValidationResult<()> {
     Ok(())
 }
 
+/// URL we'll send visitors or data to: HTTPS, on one of the allowed domains or their subdomains
+pub fn validate_allowed_url(url: &str, field_name: &str, allowed_domains: &[String]) -> ValidationResult<()> {
+    validate_url(url, field_name)?;
+
+    let domains: Vec<&str> = allowed_domains.iter().map(String::as_str).collect();
+    if !UrlValidator::is_secure_url(url) || !UrlValidator::is_allowed_domain(url, &domains) {
+        return Err(ValidationError::new(
+            field_name,
+            "URL must be HTTPS on an allowed domain",
+            "URL_NOT_ALLOWED"
+        ));
+    }
+
+    Ok(())
+}
+
 /// Phone number validation (basic international format)
 pub fn validate_phone_number(phone: &str) -> ValidationResult<()> {
     if phone.is_empty() {
@@ -445,11 +465,17 @@ impl ValidationBuilder {
         self
     }
 
+    /// Record a failed check that doesn't have its own validation function
+    pub fn add_error(&mut self, error: ValidationError) -> &mut Self {
+        self.errors.push(error);
+        self
+    }
+
+    /// The first error only; build_all_errors, which converts into AppError, reports them all
     pub fn build(self) -> ValidationResult<()> {
         if self.errors.is_empty() {
             Ok(())
         } else {
-            // Return the first error, or could be modified to return all errors
             Err(self.errors.into_iter().next().unwrap())
         }
     }
@@ -469,6 +495,24 @@ impl Default for ValidationBuilder {
     }
 }
 
+/// Request bodies checked by the ValidatedJson extractor before they reach a handler
+pub trait Validate {
+    /// Every problem with the value, not just the first
+    fn validate(&self) -> Result<(), Vec<ValidationError>>;
+}
+
+/// Validate each ID in a list, naming failures by position, e.g. "propertyIds[3]"
+pub fn validate_property_ids(property_ids: &[String], field_name: &str, builder: &mut ValidationBuilder) {
+    for (index, property_id) in property_ids.iter().enumerate() {
+        builder.validate(|| {
+            validate_property_id(property_id).map_err(|error| ValidationError {
+                field: format!("{}[{}]", field_name, index),
+                ..error
+            })
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -492,6 +536,22 @@ mod tests {
         assert!(validate_email("test@").is_err());
     }
 
+    #[test]
+    fn test_validate_allowed_url() {
+        let domains = vec!["daobitat.xyz".to_string()];
+        assert!(validate_allowed_url("https://www.daobitat.xyz/property/1", "targetUrl", &domains).is_ok());
+        for url in [
+            "http://www.daobitat.xyz/property/1",
+            "https://evil.example/daobitat.xyz",
+            "https://daobitat.xyz.evil.example/",
+            "https://daobitat.xyz@evil.example/",
+            "javascript:alert(1)",
+        ] {
+            assert_eq!(validate_allowed_url(url, "targetUrl", &domains).unwrap_err().field, "targetUrl", "{}", url);
+        }
+        assert!(validate_allowed_url("https://www.daobitat.xyz/", "targetUrl", &[]).is_err());
+    }
+
     #[test]
     fn test_validate_price() {
         assert!(validate_price(100, "price").is_ok());
@@ -529,22 +589,33 @@ mod tests {
     #[test]
     fn test_validation_builder() {
         let mut builder = ValidationBuilder::new();
-        let result = builder
+        builder
             .validate(|| validate_price(100, "price"))
-            .validate(|| validate_email("test@example.com"))
-            .build();
+            .validate(|| validate_email("test@example.com"));
+        let result = builder.build();
         
         assert!(result.is_ok());
 
         let mut builder = ValidationBuilder::new();
-        let result = builder
+        builder
             .validate(|| validate_price(-100, "price"))
-            .validate(|| validate_email("invalid"))
-            .build_all_errors();
+            .validate(|| validate_email("invalid"));
+        let result = builder.build_all_errors();
         
         assert!(result.is_err());
         let errors = result.unwrap_err();
         assert_eq!(errors.len(), 2);
     }
+
+    #[test]
+    fn test_validate_property_ids() {
+        let ids = ["507f1f77bcf86cd799439011", "nope", "", "507f1f77bcf86cd799439012"].map(str::to_string);
+        let mut builder = ValidationBuilder::new();
+        validate_property_ids(&ids, "propertyIds", &mut builder);
+
+        let errors = builder.build_all_errors().unwrap_err();
+        let fields: Vec<(&str, &str)> = errors.iter().map(|e| (e.field.as_str(), e.error_code.as_str())).collect();
+        assert_eq!(fields, vec![("propertyIds[1]", "INVALID_OBJECT_ID"), ("propertyIds[2]", "EMPTY_ID")]);
+    }
 }
 